@@ -102,6 +102,22 @@ where
     pub async fn into_text(self) -> Result<String, Error> {
         Ok(self.inner.text().await?)
     }
+
+    /// Returns the successive newline delimited JSON values of a streaming response body.
+    /// Returns `None` once the body is exhausted.
+    pub async fn next_json_line(&mut self, buffer: &mut Vec<u8>) -> Result<Option<T>, Error> {
+        loop {
+            if let Some(newline) = buffer.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=newline).collect();
+                return Ok(Some(serde_json::from_slice(&line)?));
+            }
+            match self.inner.chunk().await? {
+                Some(chunk) => buffer.extend_from_slice(&chunk),
+                None => return Ok(None),
+            }
+        }
+    }
+
     pub fn success_or_error(self) -> Result<StatusCode, Error> {
         let http_status_code = self.inner.status();
         let url = self.inner.url().clone();
@@ -12,14 +12,18 @@ use super::AdminClient;
 use super::admin_client::Envelope;
 use http::{Uri, Version};
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 use restate_admin_rest_model::deployments::*;
-use restate_admin_rest_model::invocations::RestartAsNewInvocationResponse;
+use restate_admin_rest_model::invocations::{
+    BulkInvocationProgress, BulkInvocationRequest, RestartAsNewInvocationResponse,
+};
 use restate_admin_rest_model::services::*;
 use restate_admin_rest_model::version::VersionInformation;
 use restate_serde_util::SerdeableHeaderHashMap;
 use restate_types::identifiers::{DeploymentId, LambdaARN};
-use restate_types::schema::deployment::ProtocolType;
+use restate_types::schema::deployment::{HttpConnectionPoolSettings, ProtocolType};
+use restate_types::schema::routing::ServiceRouting;
 use restate_types::schema::service::ServiceMetadata;
 
 pub trait AdminClientInterface {
@@ -32,6 +36,12 @@ pub trait AdminClientInterface {
         name: &str,
         modify_service_request: ModifyServiceRequest,
     ) -> reqwest::Result<Envelope<ServiceMetadata>>;
+    async fn get_service_routing(&self, name: &str) -> reqwest::Result<Envelope<ServiceRouting>>;
+    async fn set_service_routing(
+        &self,
+        name: &str,
+        service_routing: ServiceRouting,
+    ) -> reqwest::Result<Envelope<ServiceRouting>>;
     async fn get_deployments(&self) -> reqwest::Result<Envelope<ListDeploymentsResponse>>;
     async fn get_deployment<D: AsRef<str>>(
         &self,
@@ -59,6 +69,21 @@ pub trait AdminClientInterface {
 
     async fn pause_invocation(&self, id: &str) -> reqwest::Result<Envelope<()>>;
 
+    /// Pin a suspended or paused invocation to the given deployment, or to the deployment of the
+    /// latest service revision if not set.
+    async fn migrate_invocation(
+        &self,
+        id: &str,
+        deployment_id: Option<DeploymentId>,
+    ) -> reqwest::Result<Envelope<()>>;
+
+    /// Apply an operation to all invocations matching a SQL predicate. The returned envelope
+    /// streams the progress events.
+    async fn bulk_invocation_operation(
+        &self,
+        request: &BulkInvocationRequest,
+    ) -> reqwest::Result<Envelope<BulkInvocationProgress>>;
+
     async fn patch_state(
         &self,
         service: &str,
@@ -94,6 +119,21 @@ impl AdminClientInterface for AdminClient {
             .await
     }
 
+    async fn get_service_routing(&self, name: &str) -> reqwest::Result<Envelope<ServiceRouting>> {
+        let url = self.versioned_url(["services", name, "routing"]);
+        self.run(reqwest::Method::GET, url).await
+    }
+
+    async fn set_service_routing(
+        &self,
+        name: &str,
+        service_routing: ServiceRouting,
+    ) -> reqwest::Result<Envelope<ServiceRouting>> {
+        let url = self.versioned_url(["services", name, "routing"]);
+        self.run_with_body(reqwest::Method::PUT, url, service_routing)
+            .await
+    }
+
     async fn get_deployments(&self) -> reqwest::Result<Envelope<ListDeploymentsResponse>> {
         let url = self.versioned_url(["deployments"]);
         self.run(reqwest::Method::GET, url).await
@@ -164,6 +204,27 @@ impl AdminClientInterface for AdminClient {
         self.run(reqwest::Method::PATCH, url).await
     }
 
+    async fn migrate_invocation(
+        &self,
+        id: &str,
+        deployment_id: Option<DeploymentId>,
+    ) -> reqwest::Result<Envelope<()>> {
+        let mut url = self.versioned_url(["invocations", id, "migrate"]);
+        if let Some(deployment_id) = deployment_id {
+            url.set_query(Some(&format!("deployment={deployment_id}")));
+        }
+        self.run(reqwest::Method::PATCH, url).await
+    }
+
+    async fn bulk_invocation_operation(
+        &self,
+        request: &BulkInvocationRequest,
+    ) -> reqwest::Result<Envelope<BulkInvocationProgress>> {
+        let url = self.versioned_url(["invocations", "bulk"]);
+        self.run_with_body(reqwest::Method::POST, url, request)
+            .await
+    }
+
     async fn patch_state(
         &self,
         service: &str,
@@ -186,6 +247,7 @@ pub enum Deployment {
         uri: Uri,
         protocol_type: ProtocolType,
         http_version: Version,
+        connection_pool: HttpConnectionPoolSettings,
         additional_headers: SerdeableHeaderHashMap,
         created_at: humantime::Timestamp,
         min_protocol_version: i32,
@@ -203,6 +265,17 @@ pub enum Deployment {
         metadata: HashMap<String, String>,
         sdk_version: Option<String>,
     },
+    Wasm {
+        module_path: PathBuf,
+        fuel_limit: Option<u64>,
+        memory_limit: Option<u64>,
+        additional_headers: SerdeableHeaderHashMap,
+        created_at: humantime::Timestamp,
+        min_protocol_version: i32,
+        max_protocol_version: i32,
+        metadata: HashMap<String, String>,
+        sdk_version: Option<String>,
+    },
 }
 
 impl Deployment {
@@ -210,6 +283,7 @@ impl Deployment {
         match self {
             Self::Http { created_at, .. } => *created_at,
             Self::Lambda { created_at, .. } => *created_at,
+            Self::Wasm { created_at, .. } => *created_at,
         }
     }
 
@@ -222,6 +296,7 @@ impl Deployment {
                 uri,
                 protocol_type,
                 http_version,
+                connection_pool,
                 additional_headers,
                 created_at,
                 min_protocol_version,
@@ -236,6 +311,7 @@ impl Deployment {
                     uri,
                     protocol_type,
                     http_version,
+                    connection_pool,
                     additional_headers,
                     created_at,
                     min_protocol_version,
@@ -271,6 +347,34 @@ impl Deployment {
                 },
                 services,
             ),
+            DeploymentResponse::Wasm {
+                id,
+                module_path,
+                fuel_limit,
+                memory_limit,
+                additional_headers,
+                created_at,
+                min_protocol_version,
+                max_protocol_version,
+                services,
+                metadata,
+                sdk_version,
+                ..
+            } => (
+                id,
+                Deployment::Wasm {
+                    module_path,
+                    fuel_limit,
+                    memory_limit,
+                    additional_headers,
+                    created_at,
+                    min_protocol_version,
+                    max_protocol_version,
+                    metadata,
+                    sdk_version,
+                },
+                services,
+            ),
         }
     }
 
@@ -283,6 +387,7 @@ impl Deployment {
                 uri,
                 protocol_type,
                 http_version,
+                connection_pool,
                 additional_headers,
                 created_at,
                 min_protocol_version,
@@ -297,6 +402,7 @@ impl Deployment {
                     uri,
                     protocol_type,
                     http_version,
+                    connection_pool,
                     additional_headers,
                     created_at,
                     min_protocol_version,
@@ -332,6 +438,34 @@ impl Deployment {
                 },
                 services,
             ),
+            DetailedDeploymentResponse::Wasm {
+                id,
+                module_path,
+                fuel_limit,
+                memory_limit,
+                additional_headers,
+                created_at,
+                min_protocol_version,
+                max_protocol_version,
+                services,
+                metadata,
+                sdk_version,
+                ..
+            } => (
+                id,
+                Deployment::Wasm {
+                    module_path,
+                    fuel_limit,
+                    memory_limit,
+                    additional_headers,
+                    created_at,
+                    min_protocol_version,
+                    max_protocol_version,
+                    metadata,
+                    sdk_version,
+                },
+                services,
+            ),
         }
     }
 }
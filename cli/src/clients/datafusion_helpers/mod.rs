@@ -403,17 +403,22 @@ impl HandlerInfo {
         self.per_state_totals.get(&state)
     }
 
+    /// Returns the oldest invocation and its state, ignoring suspended invocations.
     pub fn oldest_non_suspended_invocation_state(
         &self,
-    ) -> Option<(InvocationState, &HandlerStateStats)> {
-        let mut oldest: Option<(InvocationState, &HandlerStateStats)> = None;
+    ) -> Option<(InvocationState, chrono::DateTime<Local>, &str)> {
+        let mut oldest: Option<(InvocationState, chrono::DateTime<Local>, &str)> = None;
         for (state, stats) in &self.per_state_totals {
             if state == &InvocationState::Suspended {
                 continue;
             }
-            if oldest.is_none() || oldest.is_some_and(|oldest| stats.oldest_at < oldest.1.oldest_at)
-            {
-                oldest = Some((*state, stats));
+            let (Some(oldest_at), Some(oldest_invocation)) =
+                (stats.oldest_at, stats.oldest_invocation.as_deref())
+            else {
+                continue;
+            };
+            if oldest.is_none_or(|(_, current_oldest_at, _)| oldest_at < current_oldest_at) {
+                oldest = Some((*state, oldest_at, oldest_invocation));
             }
         }
         oldest
@@ -423,6 +428,7 @@ impl HandlerInfo {
 #[derive(Deserialize, Clone)]
 pub struct HandlerStateStats {
     pub num_invocations: i64,
-    pub oldest_at: chrono::DateTime<Local>,
-    pub oldest_invocation: String,
+    /// Unknown if the stats were derived from the invocation counts.
+    pub oldest_at: Option<chrono::DateTime<Local>>,
+    pub oldest_invocation: Option<String>,
 }
@@ -11,6 +11,7 @@
 //! A set of common queries needed by the CLI
 
 use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 use std::fmt::Display;
 
 use anyhow::Result;
@@ -41,32 +42,60 @@ pub async fn find_active_invocations_simple(
     Ok(client.run_json_query::<SimpleInvocation>(query).await?)
 }
 
+/// Servers maintaining the invocation counts answer the deployment usage queries without scanning
+/// all the invocations.
+async fn has_invocation_counts(client: &DataFusionHttpClient) -> Result<bool> {
+    Ok(client
+        .check_columns_exists("sys_invocation_counts", &["pinned_deployment_id", "count"])
+        .await?)
+}
+
 pub async fn count_deployment_active_inv(
     client: &DataFusionHttpClient,
     deployment_id: &DeploymentId,
 ) -> Result<i64> {
-    Ok(client
-        .run_count_agg_query(format!(
+    let query = if has_invocation_counts(client).await? {
+        format!(
+            "SELECT CAST(COALESCE(SUM(count), 0) AS BIGINT) AS inv_count
+            FROM sys_invocation_counts
+            WHERE pinned_deployment_id = '{deployment_id}'"
+        )
+    } else {
+        format!(
             "SELECT COUNT(1) AS inv_count
             FROM sys_invocation_status
             WHERE pinned_deployment_id = '{deployment_id}'"
-        ))
-        .await?)
+        )
+    };
+
+    Ok(client.run_count_agg_query(query).await?)
 }
 
 pub async fn count_deployment_active_inv_by_method(
     client: &DataFusionHttpClient,
     deployment_id: &DeploymentId,
 ) -> Result<Vec<ServiceHandlerUsage>> {
-    let query = format!(
-        "SELECT
+    let query = if has_invocation_counts(client).await? {
+        format!(
+            "SELECT
+            service_name as service,
+            handler_name as handler,
+            CAST(SUM(count) AS BIGINT) AS inv_count
+            FROM sys_invocation_counts
+            WHERE pinned_deployment_id = '{deployment_id}'
+            GROUP BY service_name, handler_name"
+        )
+    } else {
+        format!(
+            "SELECT
             target_service_name as service,
             target_handler_name as handler,
             COUNT(1) AS inv_count
             FROM sys_invocation_status
             WHERE pinned_deployment_id = '{deployment_id}'
             GROUP BY pinned_deployment_id, target_service_name, target_handler_name"
-    );
+        )
+    };
 
     Ok(client.run_json_query::<ServiceHandlerUsage>(query).await?)
 }
@@ -84,8 +113,6 @@ pub async fn get_service_status(
     client: &DataFusionHttpClient,
     services_filter: impl IntoIterator<Item = impl AsRef<str>>,
 ) -> Result<ServiceStatusMap> {
-    let mut status_map = ServiceStatusMap::default();
-
     let query_filter = format!(
         "({})",
         services_filter
@@ -94,6 +121,161 @@ pub async fn get_service_status(
             .collect::<Vec<_>>()
             .join(",")
     );
+
+    if has_invocation_counts(client).await? {
+        get_service_status_from_counts(client, &query_filter).await
+    } else {
+        get_service_status_by_scan(client, &query_filter).await
+    }
+}
+
+/// Above this number of ids, `id IN (...)` predicates are no longer answered with point lookups.
+const INVOCATION_ID_LOOKUP_CHUNK_SIZE: usize = 4096;
+
+/// Counts the invocations without scanning them. Only the invocations held by the invokers are
+/// looked up, to tell the running invocations from the backing-off ones.
+async fn get_service_status_from_counts(
+    client: &DataFusionHttpClient,
+    query_filter: &str,
+) -> Result<ServiceStatusMap> {
+    #[derive(Deserialize)]
+    struct InvocationCountsQueryResult {
+        service_name: String,
+        handler_name: String,
+        status: String,
+        num_invocations: i64,
+    }
+
+    #[derive(Deserialize)]
+    struct InvocationIdRow {
+        id: String,
+    }
+
+    let mut status_map = ServiceStatusMap::default();
+
+    let query = format!(
+        "SELECT
+            service_name,
+            handler_name,
+            status,
+            CAST(SUM(count) AS BIGINT) AS num_invocations
+        FROM sys_invocation_counts
+        WHERE status != 'completed' AND service_name IN {query_filter}
+        GROUP BY service_name, handler_name, status"
+    );
+    let rows = client
+        .run_json_query::<InvocationCountsQueryResult>(query)
+        .await?;
+    let mut num_invoked: HashMap<(String, String), i64> = HashMap::new();
+    for row in rows {
+        let state = match row.status.as_str() {
+            "inboxed" => InvocationState::Pending,
+            "scheduled" => InvocationState::Scheduled,
+            "suspended" => InvocationState::Suspended,
+            "paused" => InvocationState::Paused,
+            "invoked" => {
+                *num_invoked
+                    .entry((row.service_name, row.handler_name))
+                    .or_default() += row.num_invocations;
+                continue;
+            }
+            _ => continue,
+        };
+        status_map.set_handler_stats(
+            &row.service_name,
+            &row.handler_name,
+            state,
+            HandlerStateStats {
+                num_invocations: row.num_invocations,
+                oldest_at: None,
+                oldest_invocation: None,
+            },
+        );
+    }
+
+    if num_invoked.is_empty() {
+        return Ok(status_map);
+    }
+
+    let invoker_rows = client
+        .run_json_query::<InvocationIdRow>("SELECT id FROM sys_invocation_state".to_owned())
+        .await?;
+    let mut active_stats: HashMap<(String, String, InvocationState), HandlerStateStats> =
+        HashMap::new();
+    for chunk in invoker_rows.chunks(INVOCATION_ID_LOOKUP_CHUNK_SIZE) {
+        let id_filter = chunk
+            .iter()
+            .map(|row| format!("'{}'", row.id))
+            .collect::<Vec<_>>()
+            .join(",");
+        let query = format!(
+            "SELECT
+                target_service_name,
+                target_handler_name,
+                status,
+                COUNT(1) as num_invocations,
+                MIN(created_at) as oldest_at,
+                FIRST_VALUE(id ORDER BY created_at ASC) as oldest_invocation
+            FROM sys_invocation
+            WHERE id IN ({id_filter})
+                AND target_service_name IN {query_filter}
+                AND status IN ('ready', 'running', 'backing-off')
+            GROUP BY target_service_name, target_handler_name, status"
+        );
+        let rows = client
+            .run_json_query::<ServiceStatusQueryResult>(query)
+            .await?;
+        for row in rows {
+            match active_stats.entry((row.target_service_name, row.target_handler_name, row.status))
+            {
+                Entry::Vacant(entry) => {
+                    entry.insert(row.stats);
+                }
+                Entry::Occupied(mut entry) => {
+                    let stats = entry.get_mut();
+                    stats.num_invocations += row.stats.num_invocations;
+                    if row.stats.oldest_at < stats.oldest_at {
+                        stats.oldest_at = row.stats.oldest_at;
+                        stats.oldest_invocation = row.stats.oldest_invocation;
+                    }
+                }
+            }
+        }
+    }
+
+    // invoked invocations which the invokers haven't picked up yet are ready
+    for ((service, handler), num_invocations) in num_invoked {
+        let num_active: i64 = [InvocationState::Running, InvocationState::BackingOff]
+            .into_iter()
+            .filter_map(|state| active_stats.get(&(service.clone(), handler.clone(), state)))
+            .map(|stats| stats.num_invocations)
+            .sum();
+        let num_ready = num_invocations - num_active;
+        if num_ready > 0 {
+            active_stats
+                .entry((service, handler, InvocationState::Ready))
+                .or_insert(HandlerStateStats {
+                    num_invocations: 0,
+                    oldest_at: None,
+                    oldest_invocation: None,
+                })
+                .num_invocations = num_ready;
+        }
+    }
+
+    for ((service, handler, state), stats) in active_stats {
+        status_map.set_handler_stats(&service, &handler, state, stats);
+    }
+
+    Ok(status_map)
+}
+
+async fn get_service_status_by_scan(
+    client: &DataFusionHttpClient,
+    query_filter: &str,
+) -> Result<ServiceStatusMap> {
+    let mut status_map = ServiceStatusMap::default();
+
     // Inbox analysis (pending invocations)....
     {
         let query = format!(
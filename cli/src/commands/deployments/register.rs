@@ -10,6 +10,8 @@
 
 use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
 use std::str::FromStr;
 
 use anyhow::{Context, Result, bail};
@@ -27,6 +29,7 @@ use restate_cli_util::ui::console::{Styled, StyledTable, confirm_or_exit};
 use restate_cli_util::ui::stylesheet::Style;
 use restate_cli_util::{c_eprintln, c_error, c_indent_table, c_indentln, c_success, c_warn};
 use restate_types::identifiers::LambdaARN;
+use restate_types::schema::deployment::HttpConnectionPoolSettings;
 use restate_types::schema::service::ServiceMetadata;
 
 use crate::cli_env::CliEnv;
@@ -72,10 +75,22 @@ pub struct Register {
     #[clap(long = "use-http1.1")]
     use_http_11: bool,
 
+    /// Maximum number of HTTP/2 connections Restate server opens to the deployment.
+    /// Defaults to the Restate server configuration.
+    #[clap(long)]
+    max_connections: Option<NonZeroUsize>,
+
+    /// Maximum number of concurrent HTTP/2 streams opened on a connection to the deployment,
+    /// before opening a new connection. Defaults to the Restate server configuration.
+    #[clap(long)]
+    max_streams_per_connection: Option<NonZeroUsize>,
+
     /// The URL or ARN that Restate server needs to fetch service information from.
     ///
     /// The URL must be network-accessible from Restate server. In case of using
-    /// Lambda ARN, the ARN should include the function version.
+    /// Lambda ARN, the ARN should include the function version. WebAssembly modules are
+    /// registered with `wasm:///absolute/path/to/module.wasm`, the path must be readable by
+    /// every Restate server node.
     #[clap(value_parser = parse_deployment)]
     deployment: DeploymentEndpoint,
 }
@@ -96,6 +111,7 @@ struct Metadata {
 enum DeploymentEndpoint {
     Uri(Uri),
     Lambda(LambdaARN),
+    Wasm(PathBuf),
 }
 
 impl DeploymentEndpoint {
@@ -103,6 +119,7 @@ impl DeploymentEndpoint {
         match self {
             DeploymentEndpoint::Uri(uri) => uri.to_string(),
             DeploymentEndpoint::Lambda(arn) => arn.to_string(),
+            DeploymentEndpoint::Wasm(path) => format!("wasm://{}", path.display()),
         }
     }
 }
@@ -112,6 +129,7 @@ impl Display for DeploymentEndpoint {
         match self {
             DeploymentEndpoint::Uri(uri) => write!(f, "URL {uri}"),
             DeploymentEndpoint::Lambda(arn) => write!(f, "AWS Lambda ARN {arn}"),
+            DeploymentEndpoint::Wasm(path) => write!(f, "WebAssembly module {}", path.display()),
         }
     }
 }
@@ -154,6 +172,16 @@ fn parse_deployment(
 ) -> Result<DeploymentEndpoint, Box<dyn std::error::Error + Send + Sync + 'static>> {
     let deployment = if raw.starts_with("arn:") {
         DeploymentEndpoint::Lambda(LambdaARN::from_str(raw)?)
+    } else if let Some(path) = raw.strip_prefix("wasm://") {
+        let path = PathBuf::from(path);
+        if !path.is_absolute() {
+            return Err(format!(
+                "WebAssembly module path must be absolute, got '{}'",
+                path.display()
+            )
+            .into());
+        }
+        DeploymentEndpoint::Wasm(path)
     } else {
         let mut uri = Uri::from_str(raw).map_err(|e| format!("invalid URL({e})"))?;
         let mut parts = uri.into_parts();
@@ -254,6 +282,13 @@ pub async fn run_register(State(env): State<CliEnv>, discover_opts: &Register) -
             additional_headers: headers.clone().map(Into::into),
             metadata: metadata.clone(),
             use_http_11: discover_opts.use_http_11,
+            connection_pool: (discover_opts.max_connections.is_some()
+                || discover_opts.max_streams_per_connection.is_some())
+            .then(|| HttpConnectionPoolSettings {
+                max_connections: discover_opts.max_connections,
+                max_streams_per_connection: discover_opts.max_streams_per_connection,
+                ..Default::default()
+            }),
             breaking,
             force: Some(force),
             dry_run,
@@ -267,6 +302,16 @@ pub async fn run_register(State(env): State<CliEnv>, discover_opts: &Register) -
             force: Some(force),
             dry_run,
         },
+        DeploymentEndpoint::Wasm(module_path) => RegisterDeploymentRequest::Wasm {
+            module_path: module_path.clone(),
+            fuel_limit: None,
+            memory_limit: None,
+            additional_headers: headers.clone().map(Into::into),
+            metadata: metadata.clone(),
+            breaking,
+            force: Some(force),
+            dry_run,
+        },
     };
 
     if client.admin_api_version >= AdminApiVersion::V3 {
@@ -13,6 +13,7 @@ use std::collections::HashMap;
 use anyhow::{Result, bail};
 use cling::prelude::*;
 use comfy_table::Table;
+use http::StatusCode;
 use indoc::indoc;
 
 use restate_admin_rest_model::deployments::ServiceNameRevPair;
@@ -176,13 +177,14 @@ pub async fn run_remove(State(env): State<CliEnv>, opts: &Remove) -> Result<()>
 
     confirm_or_exit("Are you sure you want to remove this deployment?")?;
 
-    let result = client
-        .remove_deployment(
-            &opts.deployment_id,
-            //TODO: Use opts.force when the server implements the false + validation case!
-            true,
-        )
+    // Without --force, the server deletes the deployment only if it's drained
+    let mut result = client
+        .remove_deployment(&opts.deployment_id, opts.force)
         .await?;
+    if result.status_code() == StatusCode::NOT_IMPLEMENTED {
+        // The server can't check whether the deployment is drained, rely on the check above
+        result = client.remove_deployment(&opts.deployment_id, true).await?;
+    }
     let _ = result.success_or_error()?;
 
     c_println!();
@@ -0,0 +1,172 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::num::NonZeroU32;
+
+use anyhow::{Result, bail};
+use cling::prelude::*;
+use comfy_table::{Cell, Color, Table};
+use indicatif::ProgressBar;
+
+use restate_admin_rest_model::invocations::{
+    BulkInvocationOperation, BulkInvocationProgress, BulkInvocationRequest,
+};
+use restate_cli_util::ui::console::{Styled, StyledTable, confirm_or_exit};
+use restate_cli_util::ui::stylesheet::Style;
+use restate_cli_util::{c_indent_table, c_println, c_success, c_warn};
+
+use crate::clients::{AdminClient, AdminClientInterface};
+
+/// Options to select invocations with a SQL predicate, evaluated by the server.
+#[derive(Parser, Collect, Clone, Debug, Default)]
+pub struct BulkOpts {
+    /// SQL predicate on the `sys_invocation` table selecting the invocations, e.g.
+    /// `target_handler_name = 'process' AND status = 'backing-off'`.
+    /// The invocations are selected and processed by the server.
+    #[clap(long = "query", value_name = "SQL_PREDICATE")]
+    pub(super) sql_filter: Option<String>,
+
+    /// Only print the invocations matching the --query predicate, without applying the operation.
+    #[clap(long, requires = "sql_filter")]
+    pub(super) dry_run: bool,
+
+    /// Maximum number of invocations processed per second when using --query.
+    #[clap(long, requires = "sql_filter")]
+    pub(super) rate_limit: Option<NonZeroU32>,
+
+    /// Maximum number of invocations selected by the --query predicate.
+    #[clap(long, requires = "sql_filter")]
+    pub(super) limit: Option<u64>,
+}
+
+/// Runs the operation on the server for all invocations matching `filter`, printing the
+/// progress reported by the server. The operation is applied to exactly the invocations listed
+/// by the dry run and confirmed by the user, the filter is not evaluated a second time.
+pub(super) async fn run_bulk_operation(
+    client: &AdminClient,
+    operation: BulkInvocationOperation,
+    filter: &str,
+    opts: &BulkOpts,
+) -> Result<()> {
+    let dry_run_request = BulkInvocationRequest {
+        operation,
+        filter: Some(filter.to_owned()),
+        invocation_ids: None,
+        dry_run: true,
+        rate_limit: opts.rate_limit,
+        limit: opts.limit,
+    };
+
+    // Always do a dry run first, to show what is going to be affected
+    let mut selected = Vec::new();
+    let mut matched = 0;
+    send_bulk_request(client, &dry_run_request, |event| match event {
+        BulkInvocationProgress::Matched { invocations, .. } => matched = invocations,
+        BulkInvocationProgress::Selected { invocation_id, .. } => selected.push(invocation_id),
+        _ => {}
+    })
+    .await?;
+
+    if matched == 0 {
+        bail!("No invocations found for query {filter}!");
+    }
+
+    let mut invocations_table = Table::new_styled();
+    invocations_table.set_styled_header(vec!["MATCHED INVOCATIONS"]);
+    for id in &selected {
+        invocations_table.add_row(vec![Cell::new(id)]);
+    }
+    c_indent_table!(0, invocations_table);
+    c_println!();
+
+    if opts.dry_run {
+        c_println!("{matched} invocations match the query (dry run, nothing was changed)");
+        return Ok(());
+    }
+
+    let prompt = format!(
+        "Are you sure you want to {} these {matched} invocations?",
+        Styled(Style::Warn, operation),
+    );
+    confirm_or_exit(&prompt)?;
+
+    let request = BulkInvocationRequest {
+        operation,
+        filter: None,
+        invocation_ids: Some(selected),
+        dry_run: false,
+        rate_limit: opts.rate_limit,
+        limit: None,
+    };
+
+    let progress = ProgressBar::new(matched);
+    progress.set_style(
+        indicatif::ProgressStyle::with_template("{bar:40} {pos}/{len} [{elapsed}] {msg}").unwrap(),
+    );
+
+    let mut failures = Vec::new();
+    let mut completed = None;
+    send_bulk_request(client, &request, |event| match event {
+        BulkInvocationProgress::Matched { partitions, .. } => {
+            progress.set_message(format!("Processing {partitions} partitions"))
+        }
+        BulkInvocationProgress::PartitionCompleted {
+            succeeded, failed, ..
+        } => progress.inc(succeeded + failed),
+        BulkInvocationProgress::Failed {
+            invocation_id,
+            reason,
+        } => failures.push((invocation_id, reason)),
+        BulkInvocationProgress::Completed { succeeded, failed } => {
+            completed = Some((succeeded, failed))
+        }
+        BulkInvocationProgress::Selected { .. } => {}
+    })
+    .await?;
+    progress.finish_and_clear();
+
+    let Some((succeeded, _)) = completed else {
+        bail!("The server did not report the completion of the operation");
+    };
+    c_success!("Applied {operation} to {succeeded} invocations");
+
+    if !failures.is_empty() {
+        c_println!();
+        c_warn!("Failed to {operation}:");
+        let mut failures_table = Table::new_styled();
+        failures_table.set_styled_header(vec!["ID", "REASON"]);
+        for (id, reason) in failures {
+            failures_table.add_row(vec![Cell::new(id), Cell::new(reason).fg(Color::DarkRed)]);
+        }
+        c_indent_table!(0, failures_table);
+
+        bail!("Failed to {operation} some invocations");
+    }
+
+    Ok(())
+}
+
+async fn send_bulk_request(
+    client: &AdminClient,
+    request: &BulkInvocationRequest,
+    mut on_event: impl FnMut(BulkInvocationProgress),
+) -> Result<()> {
+    let mut envelope = client.bulk_invocation_operation(request).await?;
+    if !envelope.status_code().is_success() {
+        return Err(envelope.into_api_error().await?.into());
+    }
+
+    let mut buffer = Vec::new();
+    while let Some(event) = envelope.next_json_line(&mut buffer).await? {
+        on_event(event);
+    }
+
+    Ok(())
+}
@@ -11,6 +11,7 @@ use anyhow::{Result, anyhow, bail};
 use cling::prelude::*;
 use comfy_table::{Cell, Color, Table};
 use futures::TryFutureExt;
+use restate_admin_rest_model::invocations::BulkInvocationOperation;
 use restate_cli_util::ui::console::{Styled, StyledTable, confirm_or_exit};
 use restate_cli_util::ui::stylesheet::Style;
 use restate_cli_util::{c_error, c_indent_table, c_println, c_success, c_warn};
@@ -18,6 +19,7 @@ use restate_cli_util::{c_error, c_indent_table, c_println, c_success, c_warn};
 use crate::cli_env::CliEnv;
 use crate::clients::datafusion_helpers::{InvocationState, find_active_invocations_simple};
 use crate::clients::{self, AdminClientInterface, collect_and_split_futures};
+use crate::commands::invocations::bulk::{BulkOpts, run_bulk_operation};
 use crate::commands::invocations::create_query_filter;
 use crate::ui::invocations::render_simple_invocation_list;
 
@@ -34,27 +36,39 @@ pub struct Cancel {
     /// * `workflowName`
     /// * `workflowName/key`
     /// * `workflowName/key/handler`
-    pub(super) query: String,
+    #[clap(required_unless_present = "sql_filter")]
+    pub(super) query: Option<String>,
     /// Ungracefully kill the invocation and its children
     #[clap(long)]
     pub(super) kill: bool,
+    #[clap(flatten)]
+    pub(super) bulk: BulkOpts,
 }
 
 pub async fn run_cancel(State(env): State<CliEnv>, opts: &Cancel) -> Result<()> {
     let client = clients::AdminClient::new(&env).await?;
+    if let Some(sql_filter) = &opts.bulk.sql_filter {
+        let operation = if opts.kill {
+            BulkInvocationOperation::Kill
+        } else {
+            BulkInvocationOperation::Cancel
+        };
+        return run_bulk_operation(&client, operation, sql_filter, &opts.bulk).await;
+    }
+    let query = opts
+        .query
+        .as_deref()
+        .expect("required unless --query is set");
     let sql_client = clients::DataFusionHttpClient::from(client.clone());
 
-    let filter = format!(
-        "{} AND status != 'completed'",
-        create_query_filter(&opts.query)
-    );
+    let filter = format!("{} AND status != 'completed'", create_query_filter(query));
 
     let invocations = find_active_invocations_simple(&sql_client, &filter).await?;
     if invocations.is_empty() {
         bail!(
             "No invocations found for query {}! Note that the cancel command only works on non-completed invocations. \
             If you want to remove a completed invocation, consider using the purge command instead.",
-            opts.query
+            query
         );
     };
 
@@ -11,6 +11,7 @@ use anyhow::Result;
 use cling::prelude::*;
 
 use crate::cli_env::CliEnv;
+use crate::commands::invocations::bulk::BulkOpts;
 use crate::commands::invocations::cancel::{Cancel, run_cancel};
 
 #[derive(Run, Parser, Collect, Clone)]
@@ -26,7 +27,10 @@ pub struct Kill {
     /// * `workflowName`
     /// * `workflowName/key`
     /// * `workflowName/key/handler`
-    query: String,
+    #[clap(required_unless_present = "sql_filter")]
+    query: Option<String>,
+    #[clap(flatten)]
+    bulk: BulkOpts,
 }
 
 pub async fn run_kill(state: State<CliEnv>, opts: &Kill) -> Result<()> {
@@ -35,6 +39,7 @@ pub async fn run_kill(state: State<CliEnv>, opts: &Kill) -> Result<()> {
         &Cancel {
             query: opts.query.clone(),
             kill: true,
+            bulk: opts.bulk.clone(),
         },
     )
     .await
@@ -0,0 +1,125 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::cli_env::CliEnv;
+use crate::clients::datafusion_helpers::find_active_invocations_simple;
+use crate::clients::{self, AdminClientInterface, collect_and_split_futures};
+use crate::ui::invocations::render_simple_invocation_list;
+
+use crate::commands::invocations::bulk::{BulkOpts, run_bulk_operation};
+use crate::commands::invocations::create_query_filter;
+use anyhow::{Result, anyhow, bail};
+use cling::prelude::*;
+use comfy_table::{Cell, Color, Table};
+use futures::TryFutureExt;
+use restate_admin_rest_model::invocations::BulkInvocationOperation;
+use restate_cli_util::ui::console::{StyledTable, confirm_or_exit};
+use restate_cli_util::{c_indent_table, c_println, c_success, c_warn};
+use restate_types::identifiers::DeploymentId;
+
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "run_migrate")]
+pub struct Migrate {
+    /// Either an invocation id, or a target string exact match or prefix, e.g.:
+    /// * `invocationId`
+    /// * `serviceName`
+    /// * `serviceName/handler`
+    /// * `virtualObjectName`
+    /// * `virtualObjectName/key`
+    /// * `virtualObjectName/key/handler`
+    #[clap(required_unless_present = "sql_filter")]
+    query: Option<String>,
+    /// Deployment to pin the invocations to. Defaults to the deployment of the latest service
+    /// revision. The deployment must support the service protocol version of the invocations,
+    /// and contain their handler.
+    #[clap(long, conflicts_with = "sql_filter")]
+    deployment: Option<DeploymentId>,
+    #[clap(flatten)]
+    bulk: BulkOpts,
+}
+
+pub async fn run_migrate(State(env): State<CliEnv>, opts: &Migrate) -> Result<()> {
+    let client = clients::AdminClient::new(&env).await?;
+    if let Some(sql_filter) = &opts.bulk.sql_filter {
+        return run_bulk_operation(
+            &client,
+            BulkInvocationOperation::Migrate,
+            sql_filter,
+            &opts.bulk,
+        )
+        .await;
+    }
+    let query = opts
+        .query
+        .as_deref()
+        .expect("required unless --query is set");
+    let sql_client = clients::DataFusionHttpClient::from(client.clone());
+
+    // Filter only by suspended/paused, this command has no effect on other invocations
+    let filter = format!(
+        "{} AND status IN ('paused', 'suspended')",
+        create_query_filter(query)
+    );
+
+    let invocations = find_active_invocations_simple(&sql_client, &filter).await?;
+    if invocations.is_empty() {
+        bail!(
+            "No invocations found for query {}! Note that the migrate command only works on invocations either 'suspended' or 'paused'.",
+            query
+        );
+    };
+
+    render_simple_invocation_list(&invocations);
+
+    // Get the invocation and confirm
+    confirm_or_exit("Are you sure you want to migrate these invocations?")?;
+
+    // Migrate invocations
+    let (migrated, failed_to_migrate) =
+        collect_and_split_futures(invocations.into_iter().map(|invocation| invocation.id).map(
+            |invocation_id| async {
+                client
+                    .migrate_invocation(&invocation_id, opts.deployment)
+                    .map_err(anyhow::Error::from)
+                    .await
+                    .map(|_| invocation_id.clone())
+                    .map_err(|e| (invocation_id, e))
+            },
+        ))
+        .await;
+
+    c_println!();
+    c_success!("Migrated invocations:");
+
+    let mut invocations_table = Table::new_styled();
+    invocations_table.set_styled_header(vec!["MIGRATED INVOCATIONS"]);
+    for id in migrated {
+        invocations_table.add_row(vec![Cell::new(&id)]);
+    }
+    c_indent_table!(0, invocations_table);
+
+    // Print failed ones, if any
+    if !failed_to_migrate.is_empty() {
+        c_warn!("Failed to migrate:");
+        let mut failed_to_migrate_table = Table::new_styled();
+        failed_to_migrate_table.set_styled_header(vec!["ID", "REASON"]);
+        for (id, reason) in failed_to_migrate {
+            failed_to_migrate_table
+                .add_row(vec![Cell::new(&id), Cell::new(reason).fg(Color::DarkRed)]);
+        }
+        c_indent_table!(0, failed_to_migrate_table);
+
+        return Err(anyhow!("Failed to migrate some invocations"));
+    } else {
+        c_success!("Request was sent successfully");
+    }
+
+    Ok(())
+}
@@ -8,10 +8,12 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+mod bulk;
 mod cancel;
 mod describe;
 mod kill;
 mod list;
+mod migrate;
 mod pause;
 mod purge;
 mod restart_as_new;
@@ -38,6 +40,8 @@ pub enum Invocations {
     Resume(resume::Resume),
     /// Pause an invocation, or a set of invocations.
     Pause(pause::Pause),
+    /// Pin a suspended or paused invocation, or a set of invocations, to a newer deployment, without resuming it.
+    Migrate(migrate::Migrate),
 }
 
 /// See [cancel::Cancel] for more details on query
@@ -13,11 +13,13 @@ use crate::clients::datafusion_helpers::find_active_invocations_simple;
 use crate::clients::{self, AdminClientInterface, collect_and_split_futures};
 use crate::ui::invocations::render_simple_invocation_list;
 
+use crate::commands::invocations::bulk::{BulkOpts, run_bulk_operation};
 use crate::commands::invocations::create_query_filter;
 use anyhow::{Result, anyhow, bail};
 use cling::prelude::*;
 use comfy_table::{Cell, Color, Table};
 use futures::TryFutureExt;
+use restate_admin_rest_model::invocations::BulkInvocationOperation;
 use restate_cli_util::ui::console::{StyledTable, confirm_or_exit};
 use restate_cli_util::{c_indent_table, c_println, c_success, c_warn};
 
@@ -35,24 +37,37 @@ pub struct Purge {
     /// * `workflowName`
     /// * `workflowName/key`
     /// * `workflowName/key/handler`
-    query: String,
+    #[clap(required_unless_present = "sql_filter")]
+    query: Option<String>,
+    #[clap(flatten)]
+    bulk: BulkOpts,
 }
 
 pub async fn run_purge(State(env): State<CliEnv>, opts: &Purge) -> Result<()> {
     let client = clients::AdminClient::new(&env).await?;
+    if let Some(sql_filter) = &opts.bulk.sql_filter {
+        return run_bulk_operation(
+            &client,
+            BulkInvocationOperation::Purge,
+            sql_filter,
+            &opts.bulk,
+        )
+        .await;
+    }
+    let query = opts
+        .query
+        .as_deref()
+        .expect("required unless --query is set");
     let sql_client = clients::DataFusionHttpClient::from(client.clone());
 
-    let filter = format!(
-        "{} AND status = 'completed'",
-        create_query_filter(&opts.query)
-    );
+    let filter = format!("{} AND status = 'completed'", create_query_filter(query));
 
     let invocations = find_active_invocations_simple(&sql_client, &filter).await?;
     if invocations.is_empty() {
         bail!(
             "No invocations found for query {}! Note that the purge command only works on completed invocations. \
             If you need to cancel/kill an invocation, consider using the cancel command instead.",
-            opts.query
+            query
         );
     };
 
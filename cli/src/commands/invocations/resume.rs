@@ -13,11 +13,13 @@ use crate::clients::datafusion_helpers::find_active_invocations_simple;
 use crate::clients::{self, AdminClientInterface, collect_and_split_futures};
 use crate::ui::invocations::render_simple_invocation_list;
 
+use crate::commands::invocations::bulk::{BulkOpts, run_bulk_operation};
 use crate::commands::invocations::create_query_filter;
 use anyhow::{Result, anyhow, bail};
 use cling::prelude::*;
 use comfy_table::{Cell, Color, Table};
 use futures::TryFutureExt;
+use restate_admin_rest_model::invocations::BulkInvocationOperation;
 use restate_cli_util::ui::console::{StyledTable, confirm_or_exit};
 use restate_cli_util::{c_indent_table, c_println, c_success, c_warn};
 
@@ -31,24 +33,40 @@ pub struct Resume {
     /// * `virtualObjectName`
     /// * `virtualObjectName/key`
     /// * `virtualObjectName/key/handler`
-    query: String,
+    #[clap(required_unless_present = "sql_filter")]
+    query: Option<String>,
+    #[clap(flatten)]
+    bulk: BulkOpts,
 }
 
 pub async fn run_resume(State(env): State<CliEnv>, opts: &Resume) -> Result<()> {
     let client = clients::AdminClient::new(&env).await?;
+    if let Some(sql_filter) = &opts.bulk.sql_filter {
+        return run_bulk_operation(
+            &client,
+            BulkInvocationOperation::Resume,
+            sql_filter,
+            &opts.bulk,
+        )
+        .await;
+    }
+    let query = opts
+        .query
+        .as_deref()
+        .expect("required unless --query is set");
     let sql_client = clients::DataFusionHttpClient::from(client.clone());
 
     // Filter only by invoked/suspended/paused, this command has no effect on non-completed invocations
     let filter = format!(
         "{} AND status IN ('paused', 'running', 'backing-off', 'suspended', 'ready')",
-        create_query_filter(&opts.query)
+        create_query_filter(query)
     );
 
     let invocations = find_active_invocations_simple(&sql_client, &filter).await?;
     if invocations.is_empty() {
         bail!(
             "No invocations found for query {}! Note that the resume command only works on invocations either 'running', 'backing-off', 'suspended' or 'paused'.",
-            opts.query
+            query
         );
     };
 
@@ -86,6 +86,7 @@ async fn patch(env: &CliEnv, opts: &Patch) -> Result<()> {
         journal_retention: opts.journal_retention.map(FriendlyDuration::to_std),
         inactivity_timeout: opts.inactivity_timeout.map(FriendlyDuration::to_std),
         abort_timeout: opts.abort_timeout.map(FriendlyDuration::to_std),
+        handler_retry_rules: Default::default(),
     };
 
     apply_service_configuration_patch(&opts.service, admin_client, modify_request).await
@@ -103,6 +104,7 @@ pub(super) async fn apply_service_configuration_patch(
         && modify_request.inactivity_timeout.is_none()
         && modify_request.journal_retention.is_none()
         && modify_request.abort_timeout.is_none()
+        && modify_request.handler_retry_rules.is_empty()
     {
         c_println!("No changes requested");
         return Ok(());
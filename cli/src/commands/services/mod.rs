@@ -11,6 +11,7 @@
 mod config;
 mod describe;
 mod list;
+mod rollout;
 mod status;
 
 use cling::prelude::*;
@@ -24,6 +25,8 @@ pub enum Services {
     Describe(describe::Describe),
     /// Prints activity information about a given service (and method)
     Status(status::Status),
+    /// Gradually shift the new invocations of a service to another deployment
+    Rollout(rollout::Rollout),
     /// Configure a service
     #[clap(name = "config", alias = "conf")]
     #[clap(subcommand)]
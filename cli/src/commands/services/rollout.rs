@@ -0,0 +1,199 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use anyhow::{Context, Result, bail};
+use cling::prelude::*;
+use comfy_table::Table;
+
+use restate_cli_util::ui::console::{StyledTable, confirm_or_exit};
+use restate_cli_util::{c_println, c_success, c_title};
+use restate_types::identifiers::DeploymentId;
+use restate_types::schema::routing::{HeaderRoute, ServiceRouting, WeightedRoute};
+
+use crate::cli_env::CliEnv;
+use crate::clients::{AdminClient, AdminClientInterface};
+
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "run_rollout")]
+pub struct Rollout {
+    /// Service name
+    service: String,
+
+    /// Deployment receiving the rolled out invocations. Defaults to the deployment of the latest
+    /// service revision.
+    #[clap(long)]
+    to: Option<DeploymentId>,
+
+    /// Deployment currently serving the invocations. Defaults to the deployment receiving the
+    /// largest share of the invocations according to the current routing.
+    #[clap(long)]
+    from: Option<DeploymentId>,
+
+    /// Percentage of the new invocations routed to the target deployment
+    #[clap(long, value_parser = clap::value_parser!(u32).range(0..=100), conflicts_with_all = ["promote", "rollback"])]
+    percentage: Option<u32>,
+
+    /// Route the new invocations carrying this header to the target deployment, in the form
+    /// NAME=VALUE. Can be repeated.
+    #[clap(long = "header", value_parser = parse_header, requires = "percentage")]
+    headers: Vec<(String, String)>,
+
+    /// Route all the new invocations to the target deployment
+    #[clap(long, conflicts_with = "rollback")]
+    promote: bool,
+
+    /// Route all the new invocations back to the source deployment
+    #[clap(long)]
+    rollback: bool,
+}
+
+fn parse_header(s: &str) -> Result<(String, String)> {
+    let (name, value) = s
+        .split_once('=')
+        .context("header must be in the form NAME=VALUE")?;
+    Ok((name.trim().to_owned(), value.trim().to_owned()))
+}
+
+pub async fn run_rollout(State(env): State<CliEnv>, opts: &Rollout) -> Result<()> {
+    rollout(&env, opts).await
+}
+
+async fn rollout(env: &CliEnv, opts: &Rollout) -> Result<()> {
+    let client = AdminClient::new(env).await?;
+    let service = client.get_service(&opts.service).await?.into_body().await?;
+    let current_routing = client
+        .get_service_routing(&opts.service)
+        .await?
+        .into_body()
+        .await?;
+
+    if opts.percentage.is_none() && !opts.promote && !opts.rollback {
+        c_title!("🔀", "Routing");
+        if current_routing.is_empty() {
+            c_println!(
+                "All the new invocations are routed to the latest deployment {}",
+                service.deployment_id
+            );
+        } else {
+            c_println!("{}", render_routing(&current_routing));
+        }
+        return Ok(());
+    }
+
+    let to = opts.to.unwrap_or(service.deployment_id);
+    let from = || {
+        opts.from
+            .or_else(|| {
+                current_routing
+                    .weighted_routes
+                    .iter()
+                    .filter(|route| route.deployment_id != to)
+                    .max_by_key(|route| route.weight)
+                    .map(|route| route.deployment_id)
+            })
+            .context(
+                "cannot determine the deployment currently serving the invocations, specify it with --from",
+            )
+    };
+
+    let new_routing = if let Some(percentage) = opts.percentage {
+        let from = from()?;
+        if from == to {
+            bail!("the source and target deployments must be different");
+        }
+        ServiceRouting {
+            header_routes: opts
+                .headers
+                .iter()
+                .map(|(name, value)| HeaderRoute {
+                    name: name.clone(),
+                    value: value.clone(),
+                    deployment_id: to,
+                })
+                .collect(),
+            weighted_routes: [(from, 100 - percentage), (to, percentage)]
+                .into_iter()
+                .filter(|(_, weight)| *weight > 0)
+                .map(|(deployment_id, weight)| WeightedRoute {
+                    deployment_id,
+                    weight,
+                })
+                .collect(),
+        }
+    } else if opts.promote && to == service.deployment_id {
+        // Without rules, invocations are routed to the latest deployment
+        ServiceRouting::default()
+    } else {
+        let deployment_id = if opts.promote { to } else { from()? };
+        ServiceRouting {
+            header_routes: vec![],
+            weighted_routes: vec![WeightedRoute {
+                deployment_id,
+                weight: 100,
+            }],
+        }
+    };
+
+    if new_routing == current_routing {
+        c_println!("No changes requested");
+        return Ok(());
+    }
+
+    c_title!("🔀", "New routing");
+    if new_routing.is_empty() {
+        c_println!(
+            "All the new invocations will be routed to the latest deployment {}",
+            service.deployment_id
+        );
+    } else {
+        c_println!("{}", render_routing(&new_routing));
+    }
+    confirm_or_exit("Are you sure you want to apply this routing?")?;
+
+    let _ = client
+        .set_service_routing(&opts.service, new_routing)
+        .await?
+        .into_body()
+        .await?;
+    c_success!("Routing of service '{}' updated", opts.service);
+
+    Ok(())
+}
+
+fn render_routing(routing: &ServiceRouting) -> Table {
+    let mut table = Table::new_styled();
+    table.set_styled_header(vec!["ROUTE", "DEPLOYMENT", "SHARE"]);
+
+    for route in &routing.header_routes {
+        table.add_row(vec![
+            format!("{}: {}", route.name, route.value),
+            route.deployment_id.to_string(),
+            "-".to_owned(),
+        ]);
+    }
+
+    let total_weight: u64 = routing
+        .weighted_routes
+        .iter()
+        .map(|route| u64::from(route.weight))
+        .sum();
+    for route in &routing.weighted_routes {
+        table.add_row(vec![
+            "weighted".to_owned(),
+            route.deployment_id.to_string(),
+            format!(
+                "{:.1}%",
+                u64::from(route.weight) as f64 * 100.0 / total_weight.max(1) as f64
+            ),
+        ]);
+    }
+
+    table
+}
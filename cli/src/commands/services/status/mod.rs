@@ -171,10 +171,10 @@ async fn render_handlers_status(
         ));
 
         let oldest_cell = if let Some(current_handler) = svc_status.get_handler(&handler.name) {
-            if let Some((oldest_state, oldest_stats)) =
+            if let Some((oldest_state, oldest_at, oldest_invocation)) =
                 current_handler.oldest_non_suspended_invocation_state()
             {
-                let dur = chrono::Local::now().signed_duration_since(oldest_stats.oldest_at);
+                let dur = chrono::Local::now().signed_duration_since(oldest_at);
                 let style = if dur.num_seconds() < 60 {
                     Style::Info
                 } else if dur.num_seconds() < 120 {
@@ -186,7 +186,7 @@ async fn render_handlers_status(
                 let oldest_at_human = duration_to_human_rough(dur, Tense::Past);
                 Cell::new(format!(
                     "{} {} (invoked {})",
-                    oldest_stats.oldest_invocation,
+                    oldest_invocation,
                     invocation_status(oldest_state),
                     Styled(style, oldest_at_human)
                 ))
@@ -36,6 +36,7 @@ pub fn render_deployment_url(deployment: &Deployment) -> String {
     match deployment {
         Deployment::Http { uri, .. } => uri.to_string(),
         Deployment::Lambda { arn, .. } => arn.to_string(),
+        Deployment::Wasm { module_path, .. } => format!("wasm://{}", module_path.display()),
     }
 }
 
@@ -43,6 +44,7 @@ pub fn render_deployment_type(deployment: &Deployment) -> String {
     match deployment {
         Deployment::Http { .. } => "HTTP".to_string(),
         Deployment::Lambda { .. } => "Lambda".to_string(),
+        Deployment::Wasm { .. } => "WebAssembly".to_string(),
     }
 }
 
@@ -52,6 +54,7 @@ pub fn render_transport_protocol(deployment: &Deployment) -> String {
             format!("{http_version:?}")
         }
         Deployment::Lambda { .. } => "AWS Lambda".to_string(),
+        Deployment::Wasm { .. } => "In-process WebAssembly".to_string(),
     }
 }
 
@@ -113,6 +116,7 @@ pub fn add_deployment_to_kv_table(deployment: &Deployment, table: &mut Table) {
             uri,
             protocol_type,
             http_version: _,
+            connection_pool,
             additional_headers,
             created_at,
             min_protocol_version,
@@ -124,6 +128,16 @@ pub fn add_deployment_to_kv_table(deployment: &Deployment, table: &mut Table) {
             table.add_kv_row("Transport:", render_transport_protocol(deployment));
             table.add_kv_row("Protocol Style:", format!("{protocol_type}"));
             table.add_kv_row("Endpoint:", uri);
+            table.add_kv_row_if(
+                || connection_pool.max_connections.is_some(),
+                "Max Connections:",
+                || connection_pool.max_connections.unwrap(),
+            );
+            table.add_kv_row_if(
+                || connection_pool.max_streams_per_connection.is_some(),
+                "Max Streams per Connection:",
+                || connection_pool.max_streams_per_connection.unwrap(),
+            );
             (
                 additional_headers.clone(),
                 metadata.clone(),
@@ -165,6 +179,42 @@ pub fn add_deployment_to_kv_table(deployment: &Deployment, table: &mut Table) {
                 sdk_version,
             )
         }
+        Deployment::Wasm {
+            fuel_limit,
+            memory_limit,
+            additional_headers,
+            created_at,
+            min_protocol_version,
+            max_protocol_version,
+            metadata,
+            sdk_version,
+            ..
+        } => {
+            table.add_kv_row("Transport:", render_transport_protocol(deployment));
+            table.add_kv_row(
+                "Protocol Style:",
+                format!("{}", ProtocolType::RequestResponse),
+            );
+            table.add_kv_row("Endpoint:", render_deployment_url(deployment));
+            table.add_kv_row_if(
+                || fuel_limit.is_some(),
+                "Fuel Limit:",
+                || fuel_limit.unwrap(),
+            );
+            table.add_kv_row_if(
+                || memory_limit.is_some(),
+                "Memory Limit:",
+                || format!("{} bytes", memory_limit.unwrap()),
+            );
+            (
+                additional_headers.clone(),
+                metadata.clone(),
+                created_at,
+                min_protocol_version,
+                max_protocol_version,
+                sdk_version,
+            )
+        }
     };
 
     let additional_headers: HashMap<http::HeaderName, http::HeaderValue> =
@@ -12,12 +12,15 @@ use http::{Uri, Version};
 use restate_serde_util::SerdeableHeaderHashMap;
 use restate_types::identifiers::ServiceRevision;
 use restate_types::identifiers::{DeploymentId, LambdaARN};
-use restate_types::schema::deployment::{EndpointLambdaCompression, ProtocolType};
+use restate_types::schema::deployment::{
+    EndpointLambdaCompression, HttpConnectionPoolSettings, ProtocolType,
+};
 use restate_types::schema::info::Info;
 use restate_types::schema::service::ServiceMetadata;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 // This enum could be a struct with a nested enum to avoid repeating some fields, but serde(flatten) unfortunately breaks the openapi code generation
 #[serde_as]
@@ -63,6 +66,13 @@ pub enum RegisterDeploymentRequest {
         #[serde(default = "restate_serde_util::default::bool::<false>")]
         use_http_11: bool,
 
+        /// # Connection pool
+        ///
+        /// Settings of the HTTP/2 connection pool used to invoke the deployment.
+        /// Unset settings default to the ones configured in the Restate server.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        connection_pool: Option<HttpConnectionPoolSettings>,
+
         /// # Breaking
         ///
         /// If `true`, it allows registering new service revisions with
@@ -146,6 +156,76 @@ pub enum RegisterDeploymentRequest {
         )]
         force: Option<bool>,
 
+        /// # Dry-run mode
+        ///
+        /// If `true`, discovery will run but the deployment will not be registered.
+        /// This is useful to see the impact of a new deployment before registering it.
+        /// `force` and `breaking` will be respected.
+        #[serde(default = "restate_serde_util::default::bool::<false>")]
+        dry_run: bool,
+    },
+    #[cfg_attr(
+        feature = "schema",
+        schemars(
+            title = "RegisterWasmDeploymentRequest",
+            description = "Register WebAssembly deployment request"
+        )
+    )]
+    Wasm {
+        /// # Module path
+        ///
+        /// Path of the WebAssembly module to discover/invoke. The module is executed in-process by
+        /// the workers, hence it must be available at the same path on every node.
+        module_path: PathBuf,
+
+        /// # Fuel limit
+        ///
+        /// Fuel available to each request to the module. Every executed instruction consumes roughly one unit of fuel.
+        /// If unset, the limit configured in the service client options is used.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fuel_limit: Option<u64>,
+
+        /// # Memory limit
+        ///
+        /// Maximum linear memory in bytes the module can allocate when serving a request.
+        /// If unset, the limit configured in the service client options is used.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        memory_limit: Option<u64>,
+
+        /// # Additional headers
+        ///
+        /// Additional headers added to every discover/invoke request to the deployment.
+        additional_headers: Option<SerdeableHeaderHashMap>,
+
+        /// # Metadata
+        ///
+        /// Deployment metadata.
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        metadata: HashMap<String, String>,
+
+        /// # Breaking
+        ///
+        /// If `true`, it allows registering new service revisions with
+        /// schemas incompatible with previous service revisions, such as changing service type, removing a handler, etc.
+        ///
+        /// See the [versioning documentation](https://docs.restate.dev/operate/versioning) for more information.
+        #[serde(default = "restate_serde_util::default::bool::<false>")]
+        breaking: bool,
+
+        /// # Force
+        ///
+        /// If `true`, it overrides, if existing, any deployment using the same `module_path`.
+        /// Beware that this can lead inflight invocations to an unrecoverable error state.
+        ///
+        /// This implies `breaking = true`.
+        ///
+        /// See the [versioning documentation](https://docs.restate.dev/operate/versioning) for more information.
+        #[cfg_attr(
+            feature = "schema",
+            schemars(default = "restate_serde_util::default::bool::<true>")
+        )]
+        force: Option<bool>,
+
         /// # Dry-run mode
         ///
         /// If `true`, discovery will run but the deployment will not be registered.
@@ -235,6 +315,15 @@ pub enum DeploymentResponse {
         #[cfg_attr(feature = "schema", schemars(with = "String"))]
         http_version: Version,
 
+        /// # Connection pool
+        ///
+        /// Settings of the HTTP/2 connection pool configured for this service deployment.
+        #[serde(
+            default,
+            skip_serializing_if = "HttpConnectionPoolSettings::is_default"
+        )]
+        connection_pool: HttpConnectionPoolSettings,
+
         /// # Additional headers
         ///
         /// Additional headers used to invoke this service deployment.
@@ -345,6 +434,77 @@ pub enum DeploymentResponse {
         /// List of services exposed by this deployment.
         services: Vec<ServiceNameRevPair>,
 
+        /// # Info
+        ///
+        /// List of configuration/deprecation information related to this deployment.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        info: Vec<Info>,
+    },
+    #[cfg_attr(
+        feature = "schema",
+        schemars(
+            title = "WasmDeploymentResponse",
+            description = "Deployment response for WebAssembly deployments"
+        )
+    )]
+    Wasm {
+        /// # Deployment ID
+        id: DeploymentId,
+
+        /// # Module path
+        ///
+        /// Path of the WebAssembly module executed to invoke this service deployment.
+        module_path: PathBuf,
+
+        /// # Fuel limit
+        ///
+        /// Fuel available to each request to the module, if different from the configured default.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fuel_limit: Option<u64>,
+
+        /// # Memory limit
+        ///
+        /// Maximum linear memory in bytes of each request to the module, if different from the configured default.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        memory_limit: Option<u64>,
+
+        /// # Additional headers
+        ///
+        /// Additional headers used to invoke this service deployment.
+        #[serde(default, skip_serializing_if = "SerdeableHeaderHashMap::is_empty")]
+        additional_headers: SerdeableHeaderHashMap,
+
+        /// # Metadata
+        ///
+        /// Deployment metadata.
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        metadata: HashMap<String, String>,
+
+        #[serde(with = "serde_with::As::<serde_with::DisplayFromStr>")]
+        #[cfg_attr(feature = "schema", schemars(with = "String"))]
+        created_at: humantime::Timestamp,
+
+        /// # Minimum Service Protocol version
+        ///
+        /// During registration, the SDKs declare a range from minimum (included) to maximum (included) Service Protocol supported version.
+        min_protocol_version: i32,
+
+        /// # Maximum Service Protocol version
+        ///
+        /// During registration, the SDKs declare a range from minimum (included) to maximum (included) Service Protocol supported version.
+        max_protocol_version: i32,
+
+        /// # SDK version
+        ///
+        /// SDK library and version declared during registration.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sdk_version: Option<String>,
+
+        /// # Services
+        ///
+        /// List of services exposed by this deployment.
+        services: Vec<ServiceNameRevPair>,
+
         /// # Info
         ///
         /// List of configuration/deprecation information related to this deployment.
@@ -358,6 +518,7 @@ impl DeploymentResponse {
         match self {
             Self::Http { id, .. } => *id,
             Self::Lambda { id, .. } => *id,
+            Self::Wasm { id, .. } => *id,
         }
     }
 }
@@ -396,6 +557,15 @@ pub enum DetailedDeploymentResponse {
         #[cfg_attr(feature = "schema", schemars(with = "String"))]
         http_version: Version,
 
+        /// # Connection pool
+        ///
+        /// Settings of the HTTP/2 connection pool configured for this service deployment.
+        #[serde(
+            default,
+            skip_serializing_if = "HttpConnectionPoolSettings::is_default"
+        )]
+        connection_pool: HttpConnectionPoolSettings,
+
         /// # Additional headers
         ///
         /// Additional headers used to invoke this service deployment.
@@ -505,6 +675,77 @@ pub enum DetailedDeploymentResponse {
         /// List of services exposed by this deployment.
         services: Vec<ServiceMetadata>,
 
+        /// # Info
+        ///
+        /// List of configuration/deprecation information related to this deployment.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        info: Vec<Info>,
+    },
+    #[cfg_attr(
+        feature = "schema",
+        schemars(
+            title = "WasmDetailedDeploymentResponse",
+            description = "Detailed deployment response for WebAssembly deployments"
+        )
+    )]
+    Wasm {
+        /// # Deployment ID
+        id: DeploymentId,
+
+        /// # Module path
+        ///
+        /// Path of the WebAssembly module executed to invoke this service deployment.
+        module_path: PathBuf,
+
+        /// # Fuel limit
+        ///
+        /// Fuel available to each request to the module, if different from the configured default.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fuel_limit: Option<u64>,
+
+        /// # Memory limit
+        ///
+        /// Maximum linear memory in bytes of each request to the module, if different from the configured default.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        memory_limit: Option<u64>,
+
+        /// # Additional headers
+        ///
+        /// Additional headers used to invoke this service deployment.
+        #[serde(default, skip_serializing_if = "SerdeableHeaderHashMap::is_empty")]
+        additional_headers: SerdeableHeaderHashMap,
+
+        /// # Metadata
+        ///
+        /// Deployment metadata.
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        metadata: HashMap<String, String>,
+
+        #[serde(with = "serde_with::As::<serde_with::DisplayFromStr>")]
+        #[cfg_attr(feature = "schema", schemars(with = "String"))]
+        created_at: humantime::Timestamp,
+
+        /// # Minimum Service Protocol version
+        ///
+        /// During registration, the SDKs declare a range from minimum (included) to maximum (included) Service Protocol supported version.
+        min_protocol_version: i32,
+
+        /// # Maximum Service Protocol version
+        ///
+        /// During registration, the SDKs declare a range from minimum (included) to maximum (included) Service Protocol supported version.
+        max_protocol_version: i32,
+
+        /// # SDK version
+        ///
+        /// SDK library and version declared during registration.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sdk_version: Option<String>,
+
+        /// # Services
+        ///
+        /// List of services exposed by this deployment.
+        services: Vec<ServiceMetadata>,
+
         /// # Info
         ///
         /// List of configuration/deprecation information related to this deployment.
@@ -518,6 +759,7 @@ impl DetailedDeploymentResponse {
         match self {
             Self::Http { id, .. } => *id,
             Self::Lambda { id, .. } => *id,
+            Self::Wasm { id, .. } => *id,
         }
     }
 }
@@ -559,6 +801,13 @@ pub enum UpdateDeploymentRequest {
         /// request-response mode.
         use_http_11: Option<bool>,
 
+        /// # Connection pool
+        ///
+        /// Settings of the HTTP/2 connection pool used to invoke the deployment.
+        /// When provided, this will overwrite the settings previously configured for this deployment.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        connection_pool: Option<HttpConnectionPoolSettings>,
+
         /// # Overwrite
         ///
         /// If `true`, the update will overwrite the schema information, including the exposed service and handlers and service configuration, allowing **breaking changes** too. Use with caution.
@@ -613,3 +862,28 @@ pub enum UpdateDeploymentRequest {
         dry_run: bool,
     },
 }
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeploymentDrainStatusResponse {
+    pub id: DeploymentId,
+
+    /// # Draining
+    ///
+    /// If `true`, the deployment doesn't receive new invocations anymore, because none of its
+    /// services has the latest revision in it, and no routing rule points to it.
+    pub draining: bool,
+
+    /// # Pinned invocations
+    ///
+    /// Number of in-flight invocations pinned to this deployment.
+    /// Not available if the query engine is not available on this node.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pinned_invocations: Option<u64>,
+
+    /// # Drained
+    ///
+    /// If `true`, the deployment is draining and no in-flight invocation is pinned to it anymore,
+    /// hence it can be safely deleted.
+    pub drained: bool,
+}
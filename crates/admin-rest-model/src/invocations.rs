@@ -8,7 +8,9 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use restate_types::identifiers::InvocationId;
+use std::num::NonZeroU32;
+
+use restate_types::identifiers::{InvocationId, PartitionId};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -17,3 +19,94 @@ pub struct RestartAsNewInvocationResponse {
     /// The invocation id of the new invocation.
     pub new_invocation_id: InvocationId,
 }
+
+/// Operation applied to every invocation matched by a [`BulkInvocationRequest`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, strum::Display)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum BulkInvocationOperation {
+    Cancel,
+    Kill,
+    Purge,
+    PurgeJournal,
+    Resume,
+    Pause,
+    Migrate,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct BulkInvocationRequest {
+    /// # Operation
+    ///
+    /// The operation to apply to each matched invocation.
+    pub operation: BulkInvocationOperation,
+
+    /// # Filter
+    ///
+    /// SQL predicate evaluated against the `sys_invocation` table to select the invocations,
+    /// for example `target_handler_name = 'process' AND status = 'backing-off'`.
+    /// The predicate can reference only the columns of `sys_invocation`, and cannot contain
+    /// subqueries or aggregate functions. Exactly one of `filter` and `invocation_ids` must be set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+
+    /// # Invocation ids
+    ///
+    /// The invocations to apply the operation to, for example the ones selected by a previous
+    /// dry run. Exactly one of `filter` and `invocation_ids` must be set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invocation_ids: Option<Vec<InvocationId>>,
+
+    /// # Dry run
+    ///
+    /// If true, only report the matched invocations without applying the operation.
+    #[serde(default)]
+    pub dry_run: bool,
+
+    /// # Rate limit
+    ///
+    /// Maximum number of operations applied per second across all partitions.
+    /// Unlimited if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<u32>"))]
+    pub rate_limit: Option<NonZeroU32>,
+
+    /// # Limit
+    ///
+    /// Maximum number of invocations to select. All matching or listed invocations are selected
+    /// if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+}
+
+/// Progress event of a bulk invocation operation. The response of a bulk operation is a stream
+/// of newline delimited JSON encoded events.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BulkInvocationProgress {
+    /// The filter was evaluated. Emitted once, before any operation is applied.
+    Matched { invocations: u64, partitions: u64 },
+    /// An invocation was selected in dry run mode.
+    Selected {
+        invocation_id: InvocationId,
+        #[cfg_attr(feature = "schema", schemars(with = "u16"))]
+        partition_id: PartitionId,
+    },
+    /// Applying the operation to an invocation failed.
+    Failed {
+        invocation_id: InvocationId,
+        reason: String,
+    },
+    /// All invocations of a partition were processed.
+    PartitionCompleted {
+        #[cfg_attr(feature = "schema", schemars(with = "u16"))]
+        partition_id: PartitionId,
+        succeeded: u64,
+        failed: u64,
+    },
+    /// The bulk operation finished. This is always the last event.
+    Completed { succeeded: u64, failed: u64 },
+}
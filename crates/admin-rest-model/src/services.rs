@@ -15,7 +15,7 @@ use bytes::Bytes;
 use serde::{Deserialize, Serialize};
 
 use restate_time_util::FriendlyDuration;
-use restate_types::schema::service::ServiceMetadata;
+use restate_types::schema::service::{RetryRule, ServiceMetadata};
 
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[derive(Debug, Serialize, Deserialize)]
@@ -95,6 +95,15 @@ pub struct ModifyServiceRequest {
     #[serde(default, with = "serde_with::As::<Option<FriendlyDuration>>")]
     #[cfg_attr(feature = "schema", schemars(with = "Option<String>" /* TODO(slinkydeveloper) https://github.com/restatedev/restate/issues/3766 */))]
     pub abort_timeout: Option<Duration>,
+
+    /// # Handler retry rules
+    ///
+    /// Replace the retry rules of the given handlers, keyed by handler name.
+    /// Retry rules override the retry policy for specific classes of errors, the first matching rule applies.
+    ///
+    /// Set an empty list to remove the retry rules of a handler.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub handler_retry_rules: HashMap<String, Vec<RetryRule>>,
 }
 
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
@@ -8,32 +8,37 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::str::FromStr;
+
 use bytes::{Bytes, BytesMut};
 use datafusion::arrow::ipc::writer::StreamWriter;
 use datafusion::error::DataFusionError;
 use futures::StreamExt;
 use futures::stream::BoxStream;
+use strum::VariantNames;
 use tonic::codec::CompressionEncoding;
 use tonic::{Request, Response, Status, async_trait};
 use tracing::info;
 
 use restate_bifrost::loglet::FindTailOptions;
-use restate_bifrost::{Bifrost, Error as BiforstError};
+use restate_bifrost::{Bifrost, Error as BiforstError, LogEntry};
 use restate_core::protobuf::cluster_ctrl_svc::{
     ClusterStateRequest, ClusterStateResponse, CreatePartitionSnapshotRequest,
     CreatePartitionSnapshotResponse, DescribeLogRequest, DescribeLogResponse, FindTailRequest,
     FindTailResponse, GetClusterConfigurationRequest, GetClusterConfigurationResponse,
-    ListLogsRequest, ListLogsResponse, QueryRequest, QueryResponse, SealAndExtendChainRequest,
-    SealAndExtendChainResponse, SealChainRequest, SealChainResponse, SealedSegment,
-    SetClusterConfigurationRequest, SetClusterConfigurationResponse, TailState, TrimLogRequest,
+    ListLogsRequest, ListLogsResponse, QueryRequest, QueryResponse, ReadLogRequest,
+    ReadLogResponse, SealAndExtendChainRequest, SealAndExtendChainResponse, SealChainRequest,
+    SealChainResponse, SealedSegment, SetClusterConfigurationRequest,
+    SetClusterConfigurationResponse, TailState, TrimLogRequest,
     cluster_ctrl_svc_server::{ClusterCtrlSvc, ClusterCtrlSvcServer},
+    read_log_response,
 };
 use restate_core::{Metadata, MetadataWriter};
 use restate_storage_query_datafusion::context::QueryContext;
 use restate_types::config::NetworkingOptions;
-use restate_types::identifiers::PartitionId;
+use restate_types::identifiers::{InvocationId, PartitionId, WithPartitionKey};
 use restate_types::logs::metadata::SegmentIndex;
-use restate_types::logs::{LogId, Lsn, SequenceNumber};
+use restate_types::logs::{KeyFilter, LogId, Lsn, SequenceNumber};
 use restate_types::metadata_store::keys::NODES_CONFIG_KEY;
 use restate_types::net::partition_processor_manager::Snapshot;
 use restate_types::nodes_config::NodesConfiguration;
@@ -41,6 +46,7 @@ use restate_types::partitions::state::PartitionReplicaSetStates;
 use restate_types::protobuf::cluster::ClusterConfiguration;
 use restate_types::storage::{StorageCodec, StorageEncode};
 use restate_types::{PlainNodeId, Version, Versioned};
+use restate_wal_protocol::{Command, Envelope};
 
 use crate::query_utils::WriteRecordBatchStream;
 
@@ -427,6 +433,159 @@ impl ClusterCtrlSvc for ClusterCtrlSvcHandler {
                 .boxed(),
         ))
     }
+
+    /// Server streaming response type for the ReadLog method.
+    type ReadLogStream = BoxStream<'static, Result<ReadLogResponse, Status>>;
+
+    async fn read_log(
+        &self,
+        request: Request<ReadLogRequest>,
+    ) -> Result<Response<Self::ReadLogStream>, Status> {
+        read_log(&self.bifrost, request.into_inner())
+            .await
+            .map(Response::new)
+    }
+}
+
+async fn read_log(
+    bifrost: &Bifrost,
+    request: ReadLogRequest,
+) -> Result<BoxStream<'static, Result<ReadLogResponse, Status>>, Status> {
+    let log_id = LogId::from(request.log_id);
+    let filter = ReadLogFilter::try_from(&request)?;
+
+    let from_lsn = match request.from_lsn {
+        Some(from_lsn) => Lsn::from(from_lsn),
+        None => bifrost
+            .get_trim_point(log_id)
+            .await
+            .map_err(bifrost_error_to_status)?
+            .next(),
+    };
+    let to_lsn = match (request.to_lsn, request.follow) {
+        (Some(to_lsn), _) => Lsn::from(to_lsn),
+        (None, true) => Lsn::MAX,
+        (None, false) => bifrost
+            .find_tail(log_id, FindTailOptions::default())
+            .await
+            .map_err(bifrost_error_to_status)?
+            .offset()
+            .prev(),
+    };
+
+    if to_lsn < from_lsn {
+        if request.from_lsn.is_some() && request.to_lsn.is_some() {
+            return Err(Status::invalid_argument(format!(
+                "to_lsn {to_lsn} must not be lower than from_lsn {from_lsn}"
+            )));
+        }
+        // The log is empty, or the requested range starts after its tail
+        return Ok(futures::stream::empty().boxed());
+    }
+
+    // Let bifrost skip records of other partition keys if we're looking for a
+    // specific invocation
+    let key_filter = filter
+        .invocation_id
+        .map(|invocation_id| KeyFilter::Include(invocation_id.partition_key()))
+        .unwrap_or(KeyFilter::Any);
+
+    let reader = bifrost
+        .create_reader(log_id, key_filter, from_lsn, to_lsn)
+        .map_err(bifrost_error_to_status)?;
+
+    Ok(reader
+        .filter_map(move |entry| {
+            futures::future::ready(match entry {
+                Ok(entry) => filter.decode(entry).map(Ok),
+                Err(err) => Some(Err(bifrost_error_to_status(err))),
+            })
+        })
+        .boxed())
+}
+
+/// Filters and decodes the log records streamed by [`ClusterCtrlSvc::read_log`].
+struct ReadLogFilter {
+    command_types: Vec<String>,
+    invocation_id: Option<InvocationId>,
+}
+
+impl TryFrom<&ReadLogRequest> for ReadLogFilter {
+    type Error = Status;
+
+    fn try_from(request: &ReadLogRequest) -> Result<Self, Self::Error> {
+        let invocation_id = request
+            .invocation_id
+            .as_deref()
+            .map(InvocationId::from_str)
+            .transpose()
+            .map_err(|err| Status::invalid_argument(format!("Invalid invocation id: {err}")))?;
+
+        for command_type in &request.command_types {
+            if !Command::VARIANTS
+                .iter()
+                .any(|variant| variant.eq_ignore_ascii_case(command_type))
+            {
+                return Err(Status::invalid_argument(format!(
+                    "Unknown command type '{command_type}', supported types are: {}",
+                    Command::VARIANTS.join(", ")
+                )));
+            }
+        }
+
+        Ok(Self {
+            command_types: request.command_types.clone(),
+            invocation_id,
+        })
+    }
+}
+
+impl ReadLogFilter {
+    fn matches(&self, envelope: &Envelope) -> bool {
+        if !self.command_types.is_empty()
+            && !self
+                .command_types
+                .iter()
+                .any(|command_type| command_type.eq_ignore_ascii_case(envelope.command.name()))
+        {
+            return false;
+        }
+
+        self.invocation_id
+            .is_none_or(|invocation_id| envelope.command.invocation_id() == Some(invocation_id))
+    }
+
+    /// Returns `None` if the record is filtered out
+    fn decode(&self, entry: LogEntry<Lsn>) -> Option<ReadLogResponse> {
+        let lsn = entry.sequence_number();
+        if let Some(trim_gap_to) = entry.trim_gap_to_sequence_number() {
+            return Some(ReadLogResponse {
+                lsn: lsn.as_u64(),
+                record: Some(read_log_response::Record::TrimGapToLsn(
+                    trim_gap_to.as_u64(),
+                )),
+            });
+        }
+
+        let record = match entry.try_decode::<Envelope>()? {
+            Ok(envelope) => {
+                if !self.matches(&envelope) {
+                    return None;
+                }
+                match serde_json::to_string(&envelope) {
+                    Ok(json) => read_log_response::Record::Envelope(json),
+                    Err(err) => read_log_response::Record::DecodeError(err.to_string()),
+                }
+            }
+            // Undecodable records can't be filtered, we always return them so they aren't missed
+            Err(err) => read_log_response::Record::DecodeError(err.to_string()),
+        };
+
+        Some(ReadLogResponse {
+            lsn: lsn.as_u64(),
+            record: Some(record),
+        })
+    }
 }
 
 fn serialize_value<T: StorageEncode>(value: &T) -> Bytes {
@@ -435,6 +594,14 @@ fn serialize_value<T: StorageEncode>(value: &T) -> Bytes {
     buf.freeze()
 }
 
+fn bifrost_error_to_status(err: BiforstError) -> Status {
+    match err {
+        BiforstError::UnknownLogId(_) => Status::invalid_argument("Unknown log-id"),
+        BiforstError::Shutdown(_) => Status::aborted("Node is shutting down"),
+        err => Status::internal(err.to_string()),
+    }
+}
+
 fn datafusion_error_to_status(err: DataFusionError) -> Status {
     match err {
         DataFusionError::SQL(..)
@@ -443,3 +610,190 @@ fn datafusion_error_to_status(err: DataFusionError) -> Status {
         _ => Status::internal(err.to_string()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+
+    use futures::TryStreamExt;
+    use googletest::prelude::*;
+    use test_log::test;
+
+    use restate_bifrost::ErrorRecoveryStrategy;
+    use restate_core::TestCoreEnvBuilder;
+    use restate_types::identifiers::{InvocationUuid, PartitionKey};
+    use restate_types::invocation::PurgeInvocationRequest;
+    use restate_types::partition_table::PartitionTable;
+    use restate_wal_protocol::{Destination, Header, Source};
+
+    const LOG_ID: LogId = LogId::new(0);
+
+    fn envelope(command: Command) -> Arc<Envelope> {
+        Arc::new(Envelope::new(
+            Header {
+                source: Source::ControlPlane {},
+                dest: Destination::Processor {
+                    partition_key: PartitionKey::MIN,
+                    dedup: None,
+                },
+            },
+            command,
+        ))
+    }
+
+    fn purge(invocation_id: InvocationId) -> Arc<Envelope> {
+        envelope(Command::PurgeInvocation(PurgeInvocationRequest {
+            invocation_id,
+            response_sink: None,
+        }))
+    }
+
+    fn request() -> ReadLogRequest {
+        ReadLogRequest {
+            log_id: LOG_ID.into(),
+            ..Default::default()
+        }
+    }
+
+    async fn init_bifrost() -> Bifrost {
+        let env = TestCoreEnvBuilder::with_incoming_only_connector()
+            .set_partition_table(PartitionTable::with_equally_sized_partitions(
+                Version::MIN,
+                1,
+            ))
+            .build()
+            .await;
+        Bifrost::init_in_memory(env.metadata_writer).await
+    }
+
+    async fn read_all(bifrost: &Bifrost, request: ReadLogRequest) -> Vec<ReadLogResponse> {
+        read_log(bifrost, request)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap()
+    }
+
+    #[test(restate_core::test)]
+    async fn read_empty_log() -> googletest::Result<()> {
+        let bifrost = init_bifrost().await;
+
+        // The tail of an empty log is before its first readable lsn
+        assert_that!(read_all(&bifrost, request()).await, empty());
+        assert_that!(
+            read_all(
+                &bifrost,
+                ReadLogRequest {
+                    from_lsn: Some(10),
+                    ..request()
+                }
+            )
+            .await,
+            empty()
+        );
+
+        let Err(status) = read_log(
+            &bifrost,
+            ReadLogRequest {
+                from_lsn: Some(5),
+                to_lsn: Some(2),
+                ..request()
+            },
+        )
+        .await
+        else {
+            panic!("inverted range must be rejected");
+        };
+        assert_that!(status.code(), eq(tonic::Code::InvalidArgument));
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_log_filter_validation() {
+        let filter = ReadLogFilter::try_from(&ReadLogRequest {
+            command_types: vec!["purgeinvocation".to_owned()],
+            ..request()
+        });
+        assert!(filter.is_ok());
+
+        let Err(status) = ReadLogFilter::try_from(&ReadLogRequest {
+            command_types: vec!["NoSuchCommand".to_owned()],
+            ..request()
+        }) else {
+            panic!("unknown command types must be rejected");
+        };
+        assert_that!(status.code(), eq(tonic::Code::InvalidArgument));
+
+        let Err(status) = ReadLogFilter::try_from(&ReadLogRequest {
+            invocation_id: Some("not-an-invocation-id".to_owned()),
+            ..request()
+        }) else {
+            panic!("invalid invocation ids must be rejected");
+        };
+        assert_that!(status.code(), eq(tonic::Code::InvalidArgument));
+    }
+
+    #[test(restate_core::test)]
+    async fn read_log_with_filters() -> googletest::Result<()> {
+        let bifrost = init_bifrost().await;
+
+        let invocation_1 =
+            InvocationId::from_parts(PartitionKey::MIN, InvocationUuid::mock_random());
+        let invocation_2 =
+            InvocationId::from_parts(PartitionKey::MIN, InvocationUuid::mock_random());
+
+        let mut appender = bifrost.create_appender(LOG_ID, ErrorRecoveryStrategy::default())?;
+        appender.append(purge(invocation_1)).await?;
+        appender
+            .append(envelope(Command::TruncateOutbox(42)))
+            .await?;
+        let last_lsn = appender.append(purge(invocation_2)).await?;
+
+        let all = read_all(&bifrost, request()).await;
+        assert_that!(all, len(eq(3)));
+        assert_that!(all.last().unwrap().lsn, eq(last_lsn.as_u64()));
+
+        let purges = read_all(
+            &bifrost,
+            ReadLogRequest {
+                command_types: vec!["PurgeInvocation".to_owned()],
+                ..request()
+            },
+        )
+        .await;
+        assert_that!(purges, len(eq(2)));
+
+        let by_invocation = read_all(
+            &bifrost,
+            ReadLogRequest {
+                invocation_id: Some(invocation_2.to_string()),
+                ..request()
+            },
+        )
+        .await;
+        assert_that!(by_invocation, len(eq(1)));
+        let Some(read_log_response::Record::Envelope(json)) = &by_invocation[0].record else {
+            panic!("expected an envelope record");
+        };
+        let envelope: Envelope = serde_json::from_str(json)?;
+        assert_that!(envelope.command.invocation_id(), some(eq(invocation_2)));
+
+        // Bounded reads only return the requested range
+        let bounded = read_all(
+            &bifrost,
+            ReadLogRequest {
+                from_lsn: Some(Lsn::OLDEST.as_u64()),
+                to_lsn: Some(Lsn::OLDEST.as_u64()),
+                ..request()
+            },
+        )
+        .await;
+        assert_that!(bounded, len(eq(1)));
+
+        Ok(())
+    }
+}
@@ -828,6 +828,8 @@ impl SealAndExtendTask {
                             .map(|n| n.len() as u16)
                             .unwrap_or_default()
                             .try_into()?,
+                        // explicit extensions always use full-copy replication
+                        erasure_coding: None,
                     })
                 }
             },
@@ -905,7 +907,7 @@ pub fn build_new_replicated_loglet_configuration(
 
     let mut rng = rng();
 
-    let replication = replicated_loglet_config.replication_property.clone();
+    let replication = replicated_loglet_config.loglet_replication();
 
     let sequencer = preferred_sequencer
         .and_then(|node_id| {
@@ -929,7 +931,7 @@ pub fn build_new_replicated_loglet_configuration(
         }))?;
 
     let opts = NodeSetSelectorOptions::new(u32::from(log_id) as u64)
-        .with_target_size(replicated_loglet_config.target_nodeset_size)
+        .with_target_size(replicated_loglet_config.nodeset_target_size())
         .with_preferred_nodes_opt(preferred_nodes)
         .with_top_priority_node(sequencer.id());
 
@@ -948,6 +950,14 @@ pub fn build_new_replicated_loglet_configuration(
 
     match selection {
         Ok(nodeset) => {
+            let Some(nodeset) = replicated_loglet_config.fit_nodeset(nodeset) else {
+                warn!(
+                    ?log_id,
+                    erasure_coding = ?replicated_loglet_config.erasure_coding,
+                    "Not enough writeable log-servers to store all erasure-coded fragments"
+                );
+                return None;
+            };
             // todo(asoli): here is the right place to do additional validation and reject the nodeset if it
             //  fails to meet some safety margin. For now, we'll accept the nodeset if it fulfills the replication
             //  property.
@@ -974,6 +984,7 @@ pub fn build_new_replicated_loglet_configuration(
                 sequencer,
                 replication,
                 nodeset,
+                erasure_coding: replicated_loglet_config.erasure_coding,
             })
         }
         Err(err) => {
@@ -0,0 +1,213 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use anyhow::Context;
+use datafusion::arrow::array::{Array, AsArray, UInt64Array};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::arrow::record_batch::RecordBatch;
+use futures::TryStreamExt;
+use tokio::time::{Instant, MissedTickBehavior};
+use tracing::{debug, info, warn};
+
+use restate_core::cancellation_watcher;
+use restate_storage_query_datafusion::context::QueryContext;
+use restate_types::config::Configuration;
+use restate_types::identifiers::DeploymentId;
+use restate_types::retries::with_jitter;
+use restate_types::schema::registry::{MetadataService, SchemaRegistry};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// Grace period of deletions requested via the admin API if the automatic cleanup of drained
+/// deployments is not configured.
+const DEFAULT_DELETION_GRACE_PERIOD: Duration = Duration::from_secs(60);
+
+/// The time a deployment must remain drained before it's deleted, see
+/// [`DrainedDeploymentsCleanupTask`].
+pub(crate) fn deletion_grace_period() -> Duration {
+    Configuration::pinned()
+        .admin
+        .cleanup_drained_deployments_after
+        .map(Into::into)
+        .unwrap_or(DEFAULT_DELETION_GRACE_PERIOD)
+}
+
+/// Deletes the draining deployment if it's still drained after the grace period.
+pub(crate) async fn delete_drained_deployment_after<Metadata, Discovery, Telemetry>(
+    schema_registry: SchemaRegistry<Metadata, Discovery, Telemetry>,
+    query_context: QueryContext,
+    deployment_id: DeploymentId,
+    grace_period: Duration,
+) -> anyhow::Result<()>
+where
+    Metadata: MetadataService,
+{
+    tokio::time::sleep(grace_period).await;
+
+    // Invocations which were about to be pinned when the deletion was requested are pinned by now
+    let pinned_invocations = count_pinned_invocations(&query_context, Some(deployment_id)).await?;
+    if let Some(count) = pinned_invocations.get(&deployment_id) {
+        anyhow::bail!("{count} in-flight invocations got pinned to the deployment meanwhile");
+    }
+    // The deletion fails if the deployment received new invocations in the meantime
+    schema_registry
+        .delete_draining_deployment(deployment_id)
+        .await?;
+    info!(%deployment_id, "Deleted drained deployment");
+    Ok(())
+}
+
+/// Counts the in-flight invocations pinned to each deployment. Deployments without pinned
+/// invocations are omitted.
+pub(crate) async fn count_pinned_invocations(
+    query_context: &QueryContext,
+    deployment_id: Option<DeploymentId>,
+) -> anyhow::Result<HashMap<DeploymentId, u64>> {
+    let mut query = "SELECT pinned_deployment_id, SUM(count) AS pinned_invocations \
+        FROM sys_invocation_counts \
+        WHERE status != 'completed' AND pinned_deployment_id IS NOT NULL"
+        .to_owned();
+    if let Some(deployment_id) = deployment_id {
+        query.push_str(&format!(" AND pinned_deployment_id = '{deployment_id}'"));
+    }
+    query.push_str(" GROUP BY pinned_deployment_id");
+
+    let batches: Vec<RecordBatch> = query_context.execute(&query).await?.try_collect().await?;
+
+    let mut pinned_invocations = HashMap::new();
+    for batch in batches {
+        let deployment_ids = cast(
+            batch
+                .column_by_name("pinned_deployment_id")
+                .context("Missing pinned_deployment_id column")?,
+            &DataType::Utf8,
+        )?;
+        let counts_column = batch
+            .column_by_name("pinned_invocations")
+            .context("Missing pinned_invocations column")?;
+        let Some(counts) = counts_column.as_any().downcast_ref::<UInt64Array>() else {
+            anyhow::bail!(
+                "Unexpected pinned_invocations column type: {}",
+                counts_column.data_type()
+            );
+        };
+
+        for (deployment_id, count) in deployment_ids.as_string::<i32>().iter().zip(counts) {
+            if let (Some(deployment_id), Some(count)) = (deployment_id, count)
+                && count > 0
+            {
+                *pinned_invocations
+                    .entry(deployment_id.parse::<DeploymentId>()?)
+                    .or_default() += count;
+            }
+        }
+    }
+
+    Ok(pinned_invocations)
+}
+
+/// Periodically deletes the deployments which have been drained for at least the configured
+/// duration.
+///
+/// The duration must cover the time an invocation takes to be pinned, since an invocation might
+/// be pinned to a deployment which has just been superseded.
+pub struct DrainedDeploymentsCleanupTask<Metadata, Discovery, Telemetry> {
+    schema_registry: SchemaRegistry<Metadata, Discovery, Telemetry>,
+    query_context: QueryContext,
+    cleanup_after: Duration,
+    drained_since: HashMap<DeploymentId, Instant>,
+}
+
+impl<Metadata, Discovery, Telemetry> DrainedDeploymentsCleanupTask<Metadata, Discovery, Telemetry>
+where
+    Metadata: MetadataService,
+{
+    pub fn new(
+        schema_registry: SchemaRegistry<Metadata, Discovery, Telemetry>,
+        query_context: QueryContext,
+        cleanup_after: Duration,
+    ) -> Self {
+        Self {
+            schema_registry,
+            query_context,
+            cleanup_after,
+            drained_since: HashMap::new(),
+        }
+    }
+
+    pub async fn run(mut self) -> anyhow::Result<()> {
+        let effective_interval = with_jitter(CHECK_INTERVAL.min(self.cleanup_after), 0.1);
+        let mut check_interval = tokio::time::interval(effective_interval);
+        check_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        debug!(
+            "Starting drained deployments cleanup task, deleting deployments drained for {:?}",
+            self.cleanup_after
+        );
+        let mut cancel = std::pin::pin!(cancellation_watcher());
+        loop {
+            tokio::select! {
+                _ = check_interval.tick() => {
+                    if let Err(e) = self.cleanup(Instant::now()).await {
+                        info!("Drained deployments cleanup failed: {}", e);
+                    }
+                }
+                _ = &mut cancel => {
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn cleanup(&mut self, now: Instant) -> anyhow::Result<()> {
+        let draining = self.schema_registry.list_draining_deployments();
+        if draining.is_empty() {
+            self.drained_since.clear();
+            return Ok(());
+        }
+
+        let pinned_invocations = count_pinned_invocations(&self.query_context, None).await?;
+        let drained: Vec<_> = draining
+            .into_iter()
+            .filter(|deployment_id| !pinned_invocations.contains_key(deployment_id))
+            .collect();
+        self.drained_since
+            .retain(|deployment_id, _| drained.contains(deployment_id));
+
+        for deployment_id in drained {
+            let drained_since = *self.drained_since.entry(deployment_id).or_insert(now);
+            if now.duration_since(drained_since) < self.cleanup_after {
+                continue;
+            }
+
+            // The deletion fails if the deployment received new invocations in the meantime
+            match self
+                .schema_registry
+                .delete_draining_deployment(deployment_id)
+                .await
+            {
+                Ok(()) => {
+                    info!(%deployment_id, "Deleted drained deployment");
+                    self.drained_since.remove(&deployment_id);
+                }
+                Err(err) => {
+                    warn!(%deployment_id, %err, "Failed deleting drained deployment");
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,242 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use anyhow::Context;
+use datafusion::arrow::array::AsArray;
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::arrow::record_batch::RecordBatch;
+use futures::TryStreamExt;
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, info};
+
+use restate_core::cancellation_watcher;
+use restate_storage_query_datafusion::context::QueryContext;
+use restate_types::identifiers::{DeploymentId, InvocationId, PartitionProcessorRpcRequestId};
+use restate_types::invocation::client::{
+    InvocationClient, MigrateInvocationResponse, PatchDeploymentId,
+};
+use restate_types::retries::with_jitter;
+use restate_types::schema::registry::{MetadataService, SchemaRegistry};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// Maximum number of invocations of a service considered in a single round.
+const MAX_INVOCATIONS_PER_ROUND: usize = 1000;
+
+/// Periodically migrates the suspended and paused invocations of the services having the
+/// `migrate_to_latest` policy to the deployment of the latest service revision.
+///
+/// Only the invocations which can run on the latest deployment, because it supports their
+/// protocol version and contains their handler, are migrated. The others stay pinned to their
+/// deployment. Each round migrates a page of invocations per service, resuming after the last
+/// invocation of the previous round, so that every invocation is eventually considered.
+///
+/// The routing rules of the service take precedence over the policy: the invocations pinned to a
+/// deployment the rules route to are left there.
+pub struct InvocationMigrationTask<Metadata, Discovery, Telemetry, Invocations> {
+    schema_registry: SchemaRegistry<Metadata, Discovery, Telemetry>,
+    query_context: QueryContext,
+    invocation_client: Invocations,
+    /// Last invocation considered by the previous round, by service.
+    cursors: HashMap<String, InvocationId>,
+}
+
+impl<Metadata, Discovery, Telemetry, Invocations>
+    InvocationMigrationTask<Metadata, Discovery, Telemetry, Invocations>
+where
+    Metadata: MetadataService,
+    Invocations: InvocationClient,
+{
+    pub fn new(
+        schema_registry: SchemaRegistry<Metadata, Discovery, Telemetry>,
+        query_context: QueryContext,
+        invocation_client: Invocations,
+    ) -> Self {
+        Self {
+            schema_registry,
+            query_context,
+            invocation_client,
+            cursors: HashMap::new(),
+        }
+    }
+
+    pub async fn run(mut self) -> anyhow::Result<()> {
+        let mut check_interval = tokio::time::interval(with_jitter(CHECK_INTERVAL, 0.1));
+        check_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        debug!("Starting invocation migration task");
+        let mut cancel = std::pin::pin!(cancellation_watcher());
+        loop {
+            tokio::select! {
+                _ = check_interval.tick() => {
+                    if let Err(e) = self.migrate().await {
+                        info!("Invocation migration failed: {}", e);
+                    }
+                }
+                _ = &mut cancel => {
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn migrate(&mut self) -> anyhow::Result<()> {
+        let services = self.schema_registry.list_services_migrating_to_latest();
+        self.cursors
+            .retain(|service_name, _| services.contains(service_name));
+
+        for service_name in services {
+            let Some(service) = self.schema_registry.get_service(&service_name) else {
+                continue;
+            };
+            let latest_deployment_id = service.deployment_id;
+            let Some((latest_deployment, _)) =
+                self.schema_registry.get_deployment(latest_deployment_id)
+            else {
+                continue;
+            };
+            let routed_deployment_ids: Vec<_> = self
+                .schema_registry
+                .get_service_routing(&service_name)
+                .unwrap_or_default()
+                .deployment_ids()
+                .filter(|id| **id != latest_deployment_id)
+                .copied()
+                .collect();
+
+            let invocation_ids = self
+                .find_migratable_invocations(
+                    &service_name,
+                    latest_deployment_id,
+                    &latest_deployment.supported_protocol_versions,
+                    service.handlers.keys(),
+                    &routed_deployment_ids,
+                    self.cursors.get(&service_name),
+                )
+                .await?;
+            // Start over once all the invocations have been considered
+            match invocation_ids.last() {
+                Some(last) if invocation_ids.len() == MAX_INVOCATIONS_PER_ROUND => {
+                    self.cursors.insert(service_name.clone(), *last);
+                }
+                _ => {
+                    self.cursors.remove(&service_name);
+                }
+            }
+
+            let mut migrated = 0;
+            for invocation_id in invocation_ids {
+                match self
+                    .invocation_client
+                    .migrate_invocation(
+                        PartitionProcessorRpcRequestId::new(),
+                        invocation_id,
+                        PatchDeploymentId::PinTo {
+                            id: latest_deployment_id,
+                        },
+                    )
+                    .await?
+                {
+                    MigrateInvocationResponse::Ok { .. } => migrated += 1,
+                    // The invocation needs to keep running on its deployment
+                    response @ (MigrateInvocationResponse::IncompatibleDeploymentId { .. }
+                    | MigrateInvocationResponse::MissingHandler { .. }) => {
+                        debug!(%invocation_id, ?response, "Cannot migrate invocation");
+                    }
+                    // The invocation changed status in the meantime
+                    _ => {}
+                }
+            }
+
+            if migrated > 0 {
+                info!(
+                    restate.deployment.id = %latest_deployment_id,
+                    "Migrated {migrated} invocations of service '{service_name}'"
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn find_migratable_invocations(
+        &self,
+        service_name: &str,
+        deployment_id: DeploymentId,
+        supported_protocol_versions: &RangeInclusive<i32>,
+        handler_names: impl IntoIterator<Item = &String>,
+        excluded_deployment_ids: &[DeploymentId],
+        after: Option<&InvocationId>,
+    ) -> anyhow::Result<Vec<InvocationId>> {
+        let handler_names = handler_names
+            .into_iter()
+            .map(|name| quote(name.as_str()))
+            .collect::<Vec<_>>();
+        if handler_names.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut query = format!(
+            "SELECT id FROM sys_invocation_status \
+            WHERE target_service_name = {} \
+            AND target_handler_name IN ({}) \
+            AND status IN ('suspended', 'paused') \
+            AND pinned_deployment_id IS NOT NULL \
+            AND pinned_deployment_id != '{deployment_id}' \
+            AND pinned_service_protocol_version BETWEEN {} AND {}",
+            quote(service_name),
+            handler_names.join(", "),
+            (*supported_protocol_versions.start()).max(0),
+            (*supported_protocol_versions.end()).max(0),
+        );
+        for excluded_deployment_id in excluded_deployment_ids {
+            write!(
+                query,
+                " AND pinned_deployment_id != '{excluded_deployment_id}'"
+            )?;
+        }
+        if let Some(after) = after {
+            write!(query, " AND id > '{after}'")?;
+        }
+        write!(query, " ORDER BY id LIMIT {MAX_INVOCATIONS_PER_ROUND}")?;
+
+        let batches: Vec<RecordBatch> = self
+            .query_context
+            .execute(&query)
+            .await?
+            .try_collect()
+            .await?;
+
+        let mut invocation_ids = Vec::new();
+        for batch in batches {
+            let ids = cast(
+                batch.column_by_name("id").context("Missing id column")?,
+                &DataType::Utf8,
+            )?;
+            for id in ids.as_string::<i32>().iter().flatten() {
+                invocation_ids.push(id.parse::<InvocationId>()?);
+            }
+        }
+
+        Ok(invocation_ids)
+    }
+}
+
+/// Quotes the given string as a SQL literal.
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
@@ -9,7 +9,9 @@
 // by the Apache License, Version 2.0.
 
 pub mod cluster_controller;
+mod deployment_draining;
 mod error;
+mod invocation_migration;
 #[cfg(feature = "metadata-api")]
 mod metadata_api;
 mod metric_definitions;
@@ -24,6 +26,8 @@ mod storage_query;
 #[cfg(feature = "serve-web-ui")]
 mod web_ui;
 
+pub use crate::deployment_draining::DrainedDeploymentsCleanupTask;
+pub use crate::invocation_migration::InvocationMigrationTask;
 pub use crate::storage_accounting::StorageAccountingTask;
 
 pub use error::Error;
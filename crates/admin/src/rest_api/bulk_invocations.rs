@@ -0,0 +1,774 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+
+use axum::Json;
+use axum::body::Body;
+use axum::extract::State;
+use axum::response::Response;
+use bytes::Bytes;
+use datafusion::arrow::array::AsArray;
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::DFSchema;
+use datafusion::common::tree_node::{TreeNode, TreeNodeRecursion};
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::{Expr, ExprSchemable, LogicalPlan};
+use datafusion::prelude::DataFrame;
+use futures::channel::mpsc;
+use futures::{FutureExt, StreamExt, TryStreamExt, future, stream};
+use gardal::StreamExt as GardalStreamExt;
+use gardal::{Limit, PaddedAtomicSharedStorage, TokioClock};
+use okapi_operation::*;
+use tracing::{debug, warn};
+
+use restate_admin_rest_model::invocations::{
+    BulkInvocationOperation, BulkInvocationProgress, BulkInvocationRequest,
+};
+use restate_storage_query_datafusion::context::QueryContext;
+use restate_types::identifiers::{
+    InvocationId, PartitionId, PartitionProcessorRpcRequestId, WithPartitionKey,
+};
+use restate_types::invocation::client::{
+    CancelInvocationResponse, InvocationClient, KillInvocationResponse, PatchDeploymentId,
+    PauseInvocationResponse, PurgeInvocationResponse, ResumeInvocationResponse,
+};
+use restate_types::partition_table::FindPartition;
+
+use super::error::*;
+use super::invocations::MigrateInvocationError;
+use crate::generate_meta_api_error;
+use crate::state::AdminServiceState;
+
+type TokenBucket = gardal::TokenBucket<PaddedAtomicSharedStorage, TokioClock>;
+
+generate_meta_api_error!(BulkInvocationError: [
+    InvalidFieldError,
+    QueryEngineUnavailableError,
+]);
+
+/// Apply an operation to all invocations matching a filter
+#[openapi(
+    summary = "Bulk invocation operation",
+    description = "Evaluate the given SQL predicate against the sys_invocation table, and apply the operation to every matching invocation. \
+    Alternatively, apply the operation to the given list of invocation ids, e.g. the ones selected by a previous dry run. \
+    The operation is fanned out to the partitions owning the invocations, optionally rate limited. \
+    The response is a stream of newline delimited JSON progress events, terminated by a 'completed' event.",
+    operation_id = "bulk_invocation_operation",
+    tags = "invocation",
+    responses(
+        ignore_return_type = true,
+        response(
+            status = "200",
+            description = "Stream of progress events",
+            content = "Json<BulkInvocationProgress>",
+        ),
+        from_type = "BulkInvocationError",
+    )
+)]
+pub async fn bulk_invocation_operation<Metadata, Discovery, Telemetry, Invocations>(
+    State(state): State<AdminServiceState<Metadata, Discovery, Telemetry, Invocations>>,
+    #[request_body(required = true)] Json(request): Json<BulkInvocationRequest>,
+) -> Result<Response, BulkInvocationError>
+where
+    Invocations: InvocationClient + Clone + Send + Sync + 'static,
+{
+    let (field, invocation_ids) = match (request.filter, request.invocation_ids) {
+        (Some(filter), None) => {
+            let query_context = state
+                .query_context
+                .as_ref()
+                .ok_or(QueryEngineUnavailableError)?;
+            debug!(%filter, operation = %request.operation, "Selecting invocations for bulk operation");
+            (
+                "filter",
+                select_invocations(query_context, &filter, request.limit).await?,
+            )
+        }
+        (None, Some(mut invocation_ids)) => {
+            if let Some(limit) = request.limit {
+                invocation_ids.truncate(usize::try_from(limit).unwrap_or(usize::MAX));
+            }
+            ("invocation_ids", invocation_ids)
+        }
+        _ => {
+            return Err(InvalidFieldError(
+                "filter",
+                "exactly one of 'filter' and 'invocation_ids' must be set".to_owned(),
+            )
+            .into());
+        }
+    };
+
+    let partition_table = restate_core::Metadata::with_current(|m| m.partition_table_ref());
+    let mut invocations_per_partition: BTreeMap<PartitionId, Vec<InvocationId>> = BTreeMap::new();
+    let matched = invocation_ids.len() as u64;
+    for invocation_id in invocation_ids {
+        let partition_id = partition_table
+            .find_partition_id(invocation_id.partition_key())
+            .map_err(|err| InvalidFieldError(field, err.to_string()))?;
+        invocations_per_partition
+            .entry(partition_id)
+            .or_default()
+            .push(invocation_id);
+    }
+
+    let token_bucket = request.rate_limit.map(|rate| {
+        let limit = Limit::per_second(rate);
+        let capacity = limit.burst();
+        let bucket = TokenBucket::from_parts(limit, TokioClock::default());
+        bucket.add_tokens(capacity.get());
+        bucket
+    });
+
+    // The progress events are produced by the driver future, which is polled together with the
+    // response body. Dropping the response (e.g. the client disconnecting) aborts the operation.
+    let (tx, rx) = mpsc::unbounded();
+    let _ = tx.unbounded_send(BulkInvocationProgress::Matched {
+        invocations: matched,
+        partitions: invocations_per_partition.len() as u64,
+    });
+
+    let invocation_client = state.invocation_client.clone();
+    let operation = request.operation;
+    let dry_run = request.dry_run;
+    let driver = async move {
+        let partitions =
+            invocations_per_partition
+                .into_iter()
+                .map(|(partition_id, invocation_ids)| {
+                    apply_to_partition(
+                        &invocation_client,
+                        operation,
+                        dry_run,
+                        partition_id,
+                        invocation_ids,
+                        token_bucket.clone(),
+                        &tx,
+                    )
+                });
+        let (succeeded, failed) = future::join_all(partitions)
+            .await
+            .into_iter()
+            .fold((0, 0), |(s, f), (succeeded, failed)| {
+                (s + succeeded, f + failed)
+            });
+        let _ = tx.unbounded_send(BulkInvocationProgress::Completed { succeeded, failed });
+    };
+
+    let events = stream::select(rx.map(Some), driver.into_stream().map(|()| None))
+        .filter_map(future::ready)
+        .map(|event| {
+            let mut line = serde_json::to_vec(&event).expect("progress events are serializable");
+            line.push(b'\n');
+            Ok::<_, Infallible>(Bytes::from(line))
+        });
+
+    Ok(Response::builder()
+        .header(http::header::CONTENT_TYPE, "application/x-ndjson")
+        .body(Body::from_stream(events))
+        .expect("content-type header is correct"))
+}
+
+/// Selects the ids of the invocations matching the filter.
+async fn select_invocations(
+    query_context: &QueryContext,
+    filter: &str,
+    limit: Option<u64>,
+) -> Result<Vec<InvocationId>, InvalidFieldError> {
+    let invalid_filter = |err: DataFusionError| InvalidFieldError("filter", err.to_string());
+
+    let table = query_context
+        .table("sys_invocation")
+        .await
+        .map_err(invalid_filter)?;
+    let plan = selection_plan(table, filter, limit)?;
+    let batches: Vec<RecordBatch> = query_context
+        .execute_plan(plan)
+        .await
+        .map_err(invalid_filter)?
+        .try_collect()
+        .await
+        .map_err(invalid_filter)?;
+
+    let mut invocation_ids = Vec::new();
+    for batch in batches {
+        let ids =
+            cast(batch.column(0), &DataType::Utf8).map_err(|err| invalid_filter(err.into()))?;
+        for id in ids.as_string::<i32>().iter().flatten() {
+            invocation_ids.push(
+                id.parse::<InvocationId>()
+                    .map_err(|err| InvalidFieldError("filter", err.to_string()))?,
+            );
+        }
+    }
+    Ok(invocation_ids)
+}
+
+/// Builds the plan selecting the `id` column of the table rows matching the filter.
+///
+/// The filter is parsed as a single SQL expression, and is never spliced into a query string.
+/// It must be a boolean predicate over the columns of the table: subqueries, aggregate and
+/// window functions, and placeholders are rejected.
+fn selection_plan(
+    table: DataFrame,
+    filter: &str,
+    limit: Option<u64>,
+) -> Result<LogicalPlan, InvalidFieldError> {
+    let invalid_filter = |reason: String| InvalidFieldError("filter", reason);
+
+    let predicate = table
+        .parse_sql_expr(filter)
+        .map_err(|err| invalid_filter(err.to_string()))?;
+    validate_predicate(&predicate, table.schema()).map_err(invalid_filter)?;
+
+    table
+        .filter(predicate)
+        .and_then(|df| df.select_columns(&["id"]))
+        .and_then(|df| {
+            df.limit(
+                0,
+                limit.map(|limit| usize::try_from(limit).unwrap_or(usize::MAX)),
+            )
+        })
+        .map(DataFrame::into_unoptimized_plan)
+        .map_err(|err| invalid_filter(err.to_string()))
+}
+
+fn validate_predicate(predicate: &Expr, schema: &DFSchema) -> Result<(), String> {
+    let mut rejected = None;
+    predicate
+        .apply(|expr| {
+            rejected = match expr {
+                Expr::Column(column) if !schema.has_column(column) => {
+                    Some(format!("unknown column '{column}'"))
+                }
+                Expr::ScalarSubquery(_) | Expr::Exists(_) | Expr::InSubquery(_) => {
+                    Some("subqueries are not supported".to_owned())
+                }
+                Expr::OuterReferenceColumn(..) => {
+                    Some("outer column references are not supported".to_owned())
+                }
+                Expr::AggregateFunction(_) | Expr::WindowFunction(_) => {
+                    Some("aggregate and window functions are not supported".to_owned())
+                }
+                Expr::Placeholder(_) => Some("placeholders are not supported".to_owned()),
+                _ => None,
+            };
+            Ok(if rejected.is_some() {
+                TreeNodeRecursion::Stop
+            } else {
+                TreeNodeRecursion::Continue
+            })
+        })
+        .map_err(|err| err.to_string())?;
+    if let Some(reason) = rejected {
+        return Err(reason);
+    }
+
+    match predicate.get_type(schema).map_err(|err| err.to_string())? {
+        DataType::Boolean => Ok(()),
+        other => Err(format!(
+            "expected a boolean predicate, got an expression of type {other}"
+        )),
+    }
+}
+
+/// Applies the operation to the invocations of a single partition, one at a time.
+/// Returns the number of succeeded and failed operations.
+async fn apply_to_partition<Invocations: InvocationClient>(
+    invocation_client: &Invocations,
+    operation: BulkInvocationOperation,
+    dry_run: bool,
+    partition_id: PartitionId,
+    invocation_ids: Vec<InvocationId>,
+    token_bucket: Option<TokenBucket>,
+    tx: &mpsc::UnboundedSender<BulkInvocationProgress>,
+) -> (u64, u64) {
+    let mut succeeded = 0;
+    let mut failed = 0;
+
+    if dry_run {
+        for invocation_id in invocation_ids {
+            let _ = tx.unbounded_send(BulkInvocationProgress::Selected {
+                invocation_id,
+                partition_id,
+            });
+        }
+    } else {
+        let mut invocation_ids =
+            std::pin::pin!(stream::iter(invocation_ids).throttle(token_bucket));
+        while let Some(invocation_id) = invocation_ids.next().await {
+            match apply_operation(invocation_client, operation, invocation_id).await {
+                Ok(()) => succeeded += 1,
+                Err(reason) => {
+                    failed += 1;
+                    let _ = tx.unbounded_send(BulkInvocationProgress::Failed {
+                        invocation_id,
+                        reason,
+                    });
+                }
+            }
+        }
+    }
+
+    if failed > 0 {
+        warn!(%partition_id, %operation, "Bulk operation failed for {failed} invocations");
+    }
+    let _ = tx.unbounded_send(BulkInvocationProgress::PartitionCompleted {
+        partition_id,
+        succeeded,
+        failed,
+    });
+
+    (succeeded, failed)
+}
+
+async fn apply_operation<Invocations: InvocationClient>(
+    invocation_client: &Invocations,
+    operation: BulkInvocationOperation,
+    invocation_id: InvocationId,
+) -> Result<(), String> {
+    let request_id = PartitionProcessorRpcRequestId::new();
+    let not_found = || InvocationNotFoundError(invocation_id.to_string()).to_string();
+    let client_error = |err| InvocationClientError(err).to_string();
+
+    match operation {
+        BulkInvocationOperation::Cancel => {
+            match invocation_client
+                .cancel_invocation(request_id, invocation_id)
+                .await
+                .map_err(client_error)?
+            {
+                CancelInvocationResponse::Done | CancelInvocationResponse::Appended => Ok(()),
+                CancelInvocationResponse::NotFound => Err(not_found()),
+                CancelInvocationResponse::AlreadyCompleted => {
+                    Err(InvocationWasAlreadyCompletedError(invocation_id.to_string()).to_string())
+                }
+            }
+        }
+        BulkInvocationOperation::Kill => {
+            match invocation_client
+                .kill_invocation(request_id, invocation_id)
+                .await
+                .map_err(client_error)?
+            {
+                KillInvocationResponse::Ok => Ok(()),
+                KillInvocationResponse::NotFound => Err(not_found()),
+                KillInvocationResponse::AlreadyCompleted => {
+                    Err(InvocationWasAlreadyCompletedError(invocation_id.to_string()).to_string())
+                }
+            }
+        }
+        BulkInvocationOperation::Purge | BulkInvocationOperation::PurgeJournal => {
+            let response = if operation == BulkInvocationOperation::Purge {
+                invocation_client
+                    .purge_invocation(request_id, invocation_id)
+                    .await
+            } else {
+                invocation_client
+                    .purge_journal(request_id, invocation_id)
+                    .await
+            };
+            match response.map_err(client_error)? {
+                PurgeInvocationResponse::Ok => Ok(()),
+                PurgeInvocationResponse::NotFound => Err(not_found()),
+                PurgeInvocationResponse::NotCompleted => {
+                    Err(PurgeInvocationNotCompletedError(invocation_id.to_string()).to_string())
+                }
+            }
+        }
+        BulkInvocationOperation::Resume => {
+            match invocation_client
+                .resume_invocation(request_id, invocation_id, PatchDeploymentId::KeepPinned)
+                .await
+                .map_err(client_error)?
+            {
+                ResumeInvocationResponse::Ok => Ok(()),
+                ResumeInvocationResponse::NotFound => Err(not_found()),
+                ResumeInvocationResponse::NotStarted => {
+                    Err(ResumeInvocationNotStartedError(invocation_id.to_string()).to_string())
+                }
+                ResumeInvocationResponse::Completed => {
+                    Err(ResumeInvocationCompletedError(invocation_id.to_string()).to_string())
+                }
+                other => Err(format!("Cannot resume invocation: {other:?}")),
+            }
+        }
+        BulkInvocationOperation::Pause => {
+            match invocation_client
+                .pause_invocation(request_id, invocation_id)
+                .await
+                .map_err(client_error)?
+            {
+                PauseInvocationResponse::Accepted | PauseInvocationResponse::AlreadyPaused => {
+                    Ok(())
+                }
+                PauseInvocationResponse::NotFound => Err(not_found()),
+                PauseInvocationResponse::NotRunning => {
+                    Err(PauseInvocationNotRunningError(invocation_id.to_string()).to_string())
+                }
+            }
+        }
+        BulkInvocationOperation::Migrate => {
+            let response = invocation_client
+                .migrate_invocation(request_id, invocation_id, PatchDeploymentId::PinToLatest)
+                .await
+                .map_err(client_error)?;
+            MigrateInvocationError::from_response(invocation_id, response)
+                .map(|_| ())
+                .map_err(|err| err.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::{Arc, Mutex};
+
+    use datafusion::arrow::array::StringArray;
+    use datafusion::arrow::datatypes::{Field, Schema};
+    use datafusion::datasource::MemTable;
+    use datafusion::prelude::SessionContext;
+    use googletest::prelude::*;
+    use test_log::test;
+
+    use restate_types::invocation::client::{
+        AttachInvocationResponse, GetInvocationOutputResponse, InvocationClientError,
+        InvocationOutput, MigrateInvocationResponse, RestartAsNewInvocationResponse,
+        SubmittedInvocationNotification,
+    };
+    use restate_types::invocation::{InvocationQuery, InvocationRequest, InvocationResponse};
+    use restate_types::journal::EntryIndex;
+    use restate_types::journal_v2::Signal;
+
+    fn sys_invocation(rows: &[(&str, &str)]) -> SessionContext {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Utf8, false),
+            Field::new("status", DataType::Utf8, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(StringArray::from_iter_values(rows.iter().map(|(id, _)| id))),
+                Arc::new(StringArray::from_iter_values(
+                    rows.iter().map(|(_, status)| status),
+                )),
+            ],
+        )
+        .unwrap();
+
+        let ctx = SessionContext::new();
+        ctx.register_table(
+            "sys_invocation",
+            Arc::new(MemTable::try_new(schema, vec![vec![batch]]).unwrap()),
+        )
+        .unwrap();
+        ctx.register_table(
+            "state",
+            Arc::new(
+                MemTable::try_new(
+                    Arc::new(Schema::new(vec![Field::new("key", DataType::Utf8, false)])),
+                    vec![vec![]],
+                )
+                .unwrap(),
+            ),
+        )
+        .unwrap();
+        ctx
+    }
+
+    async fn selected_ids(ctx: &SessionContext, plan: LogicalPlan) -> Vec<String> {
+        let batches = ctx
+            .execute_logical_plan(plan)
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        batches
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .column(0)
+                    .as_string::<i32>()
+                    .iter()
+                    .flatten()
+                    .map(str::to_owned)
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    #[test(tokio::test)]
+    async fn selection_plan_selects_matching_ids() {
+        let ctx = sys_invocation(&[
+            ("inv_1", "running"),
+            ("inv_2", "backing-off"),
+            ("inv_3", "backing-off"),
+        ]);
+        let table = ctx.table("sys_invocation").await.unwrap();
+
+        let plan = selection_plan(table.clone(), "status = 'backing-off'", None).unwrap();
+        assert_that!(
+            selected_ids(&ctx, plan).await,
+            elements_are![eq("inv_2"), eq("inv_3")]
+        );
+
+        let plan = selection_plan(table.clone(), "status = 'backing-off'", Some(1)).unwrap();
+        assert_that!(selected_ids(&ctx, plan).await, len(eq(1)));
+
+        // The filter is parsed as a single expression, so it cannot escape the predicate
+        assert_that!(
+            selection_plan(table, "status = 'running') OR (true", None),
+            err(anything())
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn selection_plan_rejects_non_predicates() {
+        let ctx = sys_invocation(&[("inv_1", "running")]);
+        let table = ctx.table("sys_invocation").await.unwrap();
+
+        for filter in [
+            "status",
+            "1",
+            "unknown_column = 'a'",
+            "id IN (SELECT key FROM state)",
+            "EXISTS (SELECT key FROM state)",
+            "count(*) > 0",
+            "status = $1",
+        ] {
+            assert_that!(
+                selection_plan(table.clone(), filter, None),
+                err(pat!(InvalidFieldError(eq("filter"), anything())))
+            );
+        }
+    }
+
+    /// Cancels every invocation, except the ones in `not_found`.
+    #[derive(Default)]
+    struct CancelClient {
+        not_found: Vec<InvocationId>,
+        cancelled: Mutex<Vec<InvocationId>>,
+    }
+
+    impl InvocationClient for CancelClient {
+        async fn append_invocation_and_wait_submit_notification(
+            &self,
+            _: PartitionProcessorRpcRequestId,
+            _: Arc<InvocationRequest>,
+        ) -> Result<SubmittedInvocationNotification, InvocationClientError> {
+            unimplemented!()
+        }
+
+        async fn append_invocation_and_wait_output(
+            &self,
+            _: PartitionProcessorRpcRequestId,
+            _: Arc<InvocationRequest>,
+        ) -> Result<InvocationOutput, InvocationClientError> {
+            unimplemented!()
+        }
+
+        async fn attach_invocation(
+            &self,
+            _: PartitionProcessorRpcRequestId,
+            _: InvocationQuery,
+        ) -> Result<AttachInvocationResponse, InvocationClientError> {
+            unimplemented!()
+        }
+
+        async fn get_invocation_output(
+            &self,
+            _: PartitionProcessorRpcRequestId,
+            _: InvocationQuery,
+        ) -> Result<GetInvocationOutputResponse, InvocationClientError> {
+            unimplemented!()
+        }
+
+        async fn append_invocation_response(
+            &self,
+            _: PartitionProcessorRpcRequestId,
+            _: InvocationResponse,
+        ) -> Result<(), InvocationClientError> {
+            unimplemented!()
+        }
+
+        async fn append_signal(
+            &self,
+            _: PartitionProcessorRpcRequestId,
+            _: InvocationId,
+            _: Signal,
+        ) -> Result<(), InvocationClientError> {
+            unimplemented!()
+        }
+
+        async fn cancel_invocation(
+            &self,
+            _: PartitionProcessorRpcRequestId,
+            invocation_id: InvocationId,
+        ) -> Result<CancelInvocationResponse, InvocationClientError> {
+            if self.not_found.contains(&invocation_id) {
+                return Ok(CancelInvocationResponse::NotFound);
+            }
+            self.cancelled.lock().unwrap().push(invocation_id);
+            Ok(CancelInvocationResponse::Appended)
+        }
+
+        async fn kill_invocation(
+            &self,
+            _: PartitionProcessorRpcRequestId,
+            _: InvocationId,
+        ) -> Result<KillInvocationResponse, InvocationClientError> {
+            unimplemented!()
+        }
+
+        async fn purge_invocation(
+            &self,
+            _: PartitionProcessorRpcRequestId,
+            _: InvocationId,
+        ) -> Result<PurgeInvocationResponse, InvocationClientError> {
+            unimplemented!()
+        }
+
+        async fn purge_journal(
+            &self,
+            _: PartitionProcessorRpcRequestId,
+            _: InvocationId,
+        ) -> Result<PurgeInvocationResponse, InvocationClientError> {
+            unimplemented!()
+        }
+
+        async fn restart_as_new_invocation(
+            &self,
+            _: PartitionProcessorRpcRequestId,
+            _: InvocationId,
+            _: EntryIndex,
+            _: PatchDeploymentId,
+        ) -> Result<RestartAsNewInvocationResponse, InvocationClientError> {
+            unimplemented!()
+        }
+
+        async fn resume_invocation(
+            &self,
+            _: PartitionProcessorRpcRequestId,
+            _: InvocationId,
+            _: PatchDeploymentId,
+        ) -> Result<ResumeInvocationResponse, InvocationClientError> {
+            unimplemented!()
+        }
+
+        async fn migrate_invocation(
+            &self,
+            _: PartitionProcessorRpcRequestId,
+            _: InvocationId,
+            _: PatchDeploymentId,
+        ) -> Result<MigrateInvocationResponse, InvocationClientError> {
+            unimplemented!()
+        }
+
+        async fn pause_invocation(
+            &self,
+            _: PartitionProcessorRpcRequestId,
+            _: InvocationId,
+        ) -> Result<PauseInvocationResponse, InvocationClientError> {
+            unimplemented!()
+        }
+    }
+
+    #[test(tokio::test)]
+    async fn applies_operation_to_listed_invocations() {
+        let invocation_ids: Vec<_> = (0..3).map(|_| InvocationId::mock_random()).collect();
+        let client = CancelClient {
+            not_found: vec![invocation_ids[1]],
+            ..Default::default()
+        };
+        let partition_id = PartitionId::from(0);
+        let (tx, rx) = mpsc::unbounded();
+
+        let (succeeded, failed) = apply_to_partition(
+            &client,
+            BulkInvocationOperation::Cancel,
+            false,
+            partition_id,
+            invocation_ids.clone(),
+            None,
+            &tx,
+        )
+        .await;
+        drop(tx);
+
+        assert_that!((succeeded, failed), eq((2, 1)));
+        assert_that!(
+            client.cancelled.lock().unwrap().clone(),
+            elements_are![eq(invocation_ids[0]), eq(invocation_ids[2])]
+        );
+        assert_that!(
+            rx.collect::<Vec<_>>().await,
+            elements_are![
+                pat!(BulkInvocationProgress::Failed {
+                    invocation_id: eq(invocation_ids[1]),
+                    reason: anything(),
+                }),
+                pat!(BulkInvocationProgress::PartitionCompleted {
+                    partition_id: eq(partition_id),
+                    succeeded: eq(2),
+                    failed: eq(1),
+                }),
+            ]
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn dry_run_only_reports_selected_invocations() {
+        let invocation_ids: Vec<_> = (0..2).map(|_| InvocationId::mock_random()).collect();
+        let client = CancelClient::default();
+        let partition_id = PartitionId::from(0);
+        let (tx, rx) = mpsc::unbounded();
+
+        let (succeeded, failed) = apply_to_partition(
+            &client,
+            BulkInvocationOperation::Cancel,
+            true,
+            partition_id,
+            invocation_ids.clone(),
+            None,
+            &tx,
+        )
+        .await;
+        drop(tx);
+
+        assert_that!((succeeded, failed), eq((0, 0)));
+        assert_that!(client.cancelled.lock().unwrap().clone(), empty());
+        assert_that!(
+            rx.collect::<Vec<_>>().await,
+            elements_are![
+                pat!(BulkInvocationProgress::Selected {
+                    invocation_id: eq(invocation_ids[0]),
+                    partition_id: eq(partition_id),
+                }),
+                pat!(BulkInvocationProgress::Selected {
+                    invocation_id: eq(invocation_ids[1]),
+                    partition_id: eq(partition_id),
+                }),
+                pat!(BulkInvocationProgress::PartitionCompleted {
+                    partition_id: eq(partition_id),
+                    succeeded: eq(0),
+                    failed: eq(0),
+                }),
+            ]
+        );
+    }
+}
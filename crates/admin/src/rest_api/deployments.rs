@@ -9,6 +9,9 @@
 // by the Apache License, Version 2.0.
 
 use super::error::*;
+use crate::deployment_draining::{
+    count_pinned_invocations, delete_drained_deployment_after, deletion_grace_period,
+};
 use crate::state::AdminServiceState;
 use std::time::SystemTime;
 
@@ -16,21 +19,27 @@ use axum::extract::{Path, Query, State};
 use axum::http::{StatusCode, header};
 use axum::response::IntoResponse;
 use axum::{Extension, Json};
+use futures::TryFutureExt;
 use http::{Method, Uri};
 use okapi_operation::*;
 use restate_admin_rest_model::deployments::*;
 use restate_admin_rest_model::version::AdminApiVersion;
+use restate_core::{TaskCenter, TaskKind};
 use restate_errors::warn_it;
-use restate_types::deployment::{HttpDeploymentAddress, LambdaDeploymentAddress};
+use restate_storage_query_datafusion::context::QueryContext;
+use restate_types::deployment::{
+    HttpDeploymentAddress, LambdaDeploymentAddress, WasmDeploymentAddress,
+};
 use restate_types::identifiers::{DeploymentId, InvalidLambdaARN, ServiceRevision};
 use restate_types::schema;
-use restate_types::schema::deployment::{Deployment, DeploymentType};
+use restate_types::schema::deployment::{Deployment, DeploymentType, HttpConnectionPoolSettings};
 use restate_types::schema::registry::{
     AddDeploymentResult, AllowBreakingChanges, ApplyMode, DiscoveryClient, MetadataService,
     Overwrite, TelemetryClient,
 };
 use restate_types::schema::service::ServiceMetadata;
 use serde::Deserialize;
+use tracing::warn;
 
 /// Create deployment and return discovered services.
 #[openapi(
@@ -82,6 +91,12 @@ where
             force,
             ..
         } => (*force, *breaking, *dry_run),
+        RegisterDeploymentRequest::Wasm {
+            breaking,
+            dry_run,
+            force,
+            ..
+        } => (*force, *breaking, *dry_run),
     };
     let (allow_breaking, overwrite) =
         // Force defaults to true only in admin api version 1 or 2
@@ -103,12 +118,17 @@ where
             additional_headers,
             metadata,
             use_http_11,
+            connection_pool,
             ..
         } => {
             validate_uri(&uri)?;
+            let connection_pool = connection_pool.unwrap_or_default();
+            validate_connection_pool(&connection_pool)?;
 
             schema::registry::RegisterDeploymentRequest {
-                deployment_address: HttpDeploymentAddress::new(uri).into(),
+                deployment_address: HttpDeploymentAddress::new(uri)
+                    .with_connection_pool(connection_pool)
+                    .into(),
                 additional_headers: additional_headers.unwrap_or_default().into(),
                 metadata,
                 use_http_11,
@@ -138,6 +158,39 @@ where
             overwrite,
             apply_mode,
         },
+        RegisterDeploymentRequest::Wasm {
+            module_path,
+            fuel_limit,
+            memory_limit,
+            additional_headers,
+            metadata,
+            ..
+        } => {
+            if !module_path.is_absolute() {
+                return Err(MetaApiError::InvalidField(
+                    "module_path",
+                    format!(
+                        "The provided module path {} is not absolute, only absolute paths can be used.",
+                        module_path.display()
+                    ),
+                ));
+            }
+
+            schema::registry::RegisterDeploymentRequest {
+                deployment_address: WasmDeploymentAddress::new(
+                    module_path,
+                    fuel_limit,
+                    memory_limit,
+                )
+                .into(),
+                additional_headers: additional_headers.unwrap_or_default().into(),
+                metadata,
+                use_http_11: false,
+                allow_breaking,
+                overwrite,
+                apply_mode,
+            }
+        }
     };
 
     // -- Perform the registration with the schema registry
@@ -203,6 +256,57 @@ where
     Ok(to_detailed_deployment_response(deployment, services).into())
 }
 
+/// Return the drain status of the deployment
+#[openapi(
+    summary = "Get deployment drain status",
+    description = "Get whether the deployment still receives new invocations, and how many in-flight invocations are still pinned to it. \
+    A deployment is drained, and can be safely deleted, when it doesn't receive new invocations anymore and no in-flight invocation is pinned to it.",
+    operation_id = "get_deployment_drain_status",
+    tags = "deployment",
+    parameters(path(
+        name = "deployment",
+        description = "Deployment identifier",
+        schema = "std::string::String"
+    ))
+)]
+pub async fn get_deployment_drain_status<Metadata, Discovery, Telemetry, Invocations>(
+    State(state): State<AdminServiceState<Metadata, Discovery, Telemetry, Invocations>>,
+    Path(deployment_id): Path<DeploymentId>,
+) -> Result<Json<DeploymentDrainStatusResponse>, MetaApiError>
+where
+    Metadata: MetadataService,
+{
+    let draining = state
+        .schema_registry
+        .is_deployment_draining(deployment_id)
+        .ok_or_else(|| MetaApiError::DeploymentNotFound(deployment_id))?;
+
+    let pinned_invocations = if let Some(query_context) = &state.query_context {
+        Some(pinned_invocations(query_context, deployment_id).await?)
+    } else {
+        None
+    };
+
+    Ok(DeploymentDrainStatusResponse {
+        id: deployment_id,
+        draining,
+        pinned_invocations,
+        drained: draining && pinned_invocations == Some(0),
+    }
+    .into())
+}
+
+async fn pinned_invocations(
+    query_context: &QueryContext,
+    deployment_id: DeploymentId,
+) -> Result<u64, MetaApiError> {
+    Ok(count_pinned_invocations(query_context, Some(deployment_id))
+        .await
+        .map_err(|e| MetaApiError::Internal(format!("failed counting pinned invocations: {e}")))?
+        .remove(&deployment_id)
+        .unwrap_or_default())
+}
+
 /// List deployments
 #[openapi(
     summary = "List deployments",
@@ -234,7 +338,10 @@ pub struct DeleteDeploymentParams {
 /// Discover endpoint and return discovered endpoints.
 #[openapi(
     summary = "Delete deployment",
-    description = "Delete deployment. Currently it's supported to remove a deployment only using the force flag",
+    description = "Delete deployment. Without the force flag, only drained deployments can be deleted, \
+    that is deployments which don't receive new invocations anymore and have no in-flight invocation pinned to them. \
+    Such deployments are deleted after a grace period, if they are still drained by then. \
+    The grace period is the configured drained deployments cleanup duration, or 1 minute if not configured.",
     operation_id = "delete_deployment",
     tags = "deployment",
     parameters(
@@ -261,7 +368,7 @@ pub struct DeleteDeploymentParams {
         ),
         response(
             status = "501",
-            description = "Not implemented. Only using the force flag is supported at the moment.",
+            description = "Not implemented. Deleting without the force flag requires the query engine, which is not available on this node.",
             content = "okapi_operation::Empty",
         ),
         from_type = "MetaApiError",
@@ -273,7 +380,9 @@ pub async fn delete_deployment<Metadata, Discovery, Telemetry, Invocations>(
     Query(DeleteDeploymentParams { force }): Query<DeleteDeploymentParams>,
 ) -> Result<StatusCode, MetaApiError>
 where
-    Metadata: MetadataService,
+    Metadata: MetadataService + Send + Sync + Clone + 'static,
+    Discovery: Send + Sync + Clone + 'static,
+    Telemetry: Send + Sync + Clone + 'static,
 {
     if let Some(true) = force {
         state
@@ -282,6 +391,41 @@ where
             .await
             .inspect_err(|e| warn_it!(e))?;
         Ok(StatusCode::ACCEPTED)
+    } else if let Some(query_context) = &state.query_context {
+        match state.schema_registry.is_deployment_draining(deployment_id) {
+            None => return Err(MetaApiError::DeploymentNotFound(deployment_id)),
+            Some(false) => {
+                return Err(MetaApiError::Conflict(format!(
+                    "the deployment {deployment_id} still receives new invocations. Use the force flag to delete it anyway, at the risk of breaking these invocations"
+                )));
+            }
+            Some(true) => {}
+        }
+        let pinned_invocations = pinned_invocations(query_context, deployment_id).await?;
+        if pinned_invocations > 0 {
+            return Err(MetaApiError::Conflict(format!(
+                "the deployment {deployment_id} still has {pinned_invocations} in-flight invocations pinned to it. Use the force flag to delete it anyway, at the risk of breaking these invocations"
+            )));
+        }
+
+        // Invocations might be about to be pinned to a deployment which was just superseded,
+        // hence the deletion is applied after the same grace period as the automatic cleanup.
+        let grace_period = deletion_grace_period();
+        TaskCenter::spawn(
+            TaskKind::Disposable,
+            "delete-drained-deployment",
+            delete_drained_deployment_after(
+                state.schema_registry.clone(),
+                query_context.clone(),
+                deployment_id,
+                grace_period,
+            )
+            .inspect_err(
+                move |err| warn!(%deployment_id, %err, "Failed deleting drained deployment"),
+            ),
+        )
+        .map_err(|_| MetaApiError::Internal("the node is shutting down".to_owned()))?;
+        Ok(StatusCode::ACCEPTED)
     } else {
         Ok(StatusCode::NOT_IMPLEMENTED)
     }
@@ -343,9 +487,14 @@ where
             uri,
             additional_headers,
             use_http_11,
+            connection_pool,
             ..
         } => {
-            if uri.is_none() && additional_headers.is_none() && use_http_11.is_none() {
+            if uri.is_none()
+                && additional_headers.is_none()
+                && use_http_11.is_none()
+                && connection_pool.is_none()
+            {
                 // No changes to do, just return 200
                 let (deployment, services) = state
                     .schema_registry
@@ -358,12 +507,19 @@ where
             if let Some(uri) = &uri {
                 validate_uri(uri)?;
             }
+            if let Some(connection_pool) = &connection_pool {
+                validate_connection_pool(connection_pool)?;
+            }
 
             (
-                if uri.is_none() && use_http_11.is_none() {
+                if uri.is_none() && use_http_11.is_none() && connection_pool.is_none() {
                     None
                 } else {
-                    Some(schema::registry::UpdateDeploymentAddress::Http { uri, use_http_11 })
+                    Some(schema::registry::UpdateDeploymentAddress::Http {
+                        uri,
+                        use_http_11,
+                        connection_pool,
+                    })
                 },
                 additional_headers,
             )
@@ -460,11 +616,13 @@ fn to_deployment_response(
             http_version,
             protocol_type,
             address,
+            connection_pool,
         } => DeploymentResponse::Http {
             id,
             uri: address,
             protocol_type,
             http_version,
+            connection_pool,
             additional_headers: additional_headers.into(),
             metadata,
             created_at: SystemTime::from(created_at).into(),
@@ -498,6 +656,27 @@ fn to_deployment_response(
                 .collect(),
             info,
         },
+        DeploymentType::Wasm {
+            module_path,
+            fuel_limit,
+            memory_limit,
+        } => DeploymentResponse::Wasm {
+            id,
+            module_path,
+            fuel_limit,
+            memory_limit,
+            additional_headers: additional_headers.into(),
+            metadata,
+            created_at: SystemTime::from(created_at).into(),
+            min_protocol_version: *supported_protocol_versions.start(),
+            max_protocol_version: *supported_protocol_versions.end(),
+            sdk_version,
+            services: services
+                .into_iter()
+                .map(|(name, revision)| ServiceNameRevPair { name, revision })
+                .collect(),
+            info,
+        },
     }
 }
 
@@ -520,11 +699,13 @@ fn to_detailed_deployment_response(
             http_version,
             protocol_type,
             address,
+            connection_pool,
         } => DetailedDeploymentResponse::Http {
             id,
             uri: address,
             protocol_type,
             http_version,
+            connection_pool,
             additional_headers: additional_headers.into(),
             metadata,
             created_at: SystemTime::from(created_at).into(),
@@ -552,6 +733,24 @@ fn to_detailed_deployment_response(
             services,
             info,
         },
+        DeploymentType::Wasm {
+            module_path,
+            fuel_limit,
+            memory_limit,
+        } => DetailedDeploymentResponse::Wasm {
+            id,
+            module_path,
+            fuel_limit,
+            memory_limit,
+            additional_headers: additional_headers.into(),
+            metadata,
+            created_at: SystemTime::from(created_at).into(),
+            min_protocol_version: *supported_protocol_versions.start(),
+            max_protocol_version: *supported_protocol_versions.end(),
+            sdk_version,
+            services,
+            info,
+        },
     }
 }
 
@@ -566,3 +765,27 @@ fn validate_uri(uri: &Uri) -> Result<(), MetaApiError> {
     }
     Ok(())
 }
+
+fn validate_connection_pool(
+    connection_pool: &HttpConnectionPoolSettings,
+) -> Result<(), MetaApiError> {
+    for (field, duration) in [
+        ("connection_pool.idle_timeout", connection_pool.idle_timeout),
+        (
+            "connection_pool.keep_alive_interval",
+            connection_pool.keep_alive_interval,
+        ),
+        (
+            "connection_pool.keep_alive_timeout",
+            connection_pool.keep_alive_timeout,
+        ),
+    ] {
+        if duration.is_some_and(|duration| duration.is_zero()) {
+            return Err(MetaApiError::InvalidField(
+                field,
+                "The duration must be greater than zero.".to_owned(),
+            ));
+        }
+    }
+    Ok(())
+}
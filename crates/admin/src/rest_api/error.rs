@@ -273,6 +273,58 @@ pub(crate) struct RestartAsNewInvocationIncompatibleDeploymentIdError {
 }
 impl_meta_api_error!(RestartAsNewInvocationIncompatibleDeploymentIdError: BAD_REQUEST "The selected deployment id to restart as new the invocation doesn't support the currently pinned service protocol version.");
 
+#[derive(Debug, thiserror::Error)]
+#[error("The invocation '{0}' is running, cannot be migrated.")]
+pub(crate) struct MigrateInvocationRunningError(pub(crate) String);
+impl_meta_api_error!(MigrateInvocationRunningError: CONFLICT "The invocation is running. An invocation can be migrated only when paused or suspended.");
+
+#[derive(Debug, thiserror::Error)]
+#[error("The invocation '{0}' is either inboxed or scheduled, cannot be migrated.")]
+pub(crate) struct MigrateInvocationNotStartedError(pub(crate) String);
+impl_meta_api_error!(MigrateInvocationNotStartedError: TOO_EARLY "The invocation is either inboxed or scheduled. An invocation can be migrated only when paused or suspended.");
+
+#[derive(Debug, thiserror::Error)]
+#[error("The invocation '{0}' is completed, cannot be migrated.")]
+pub(crate) struct MigrateInvocationCompletedError(pub(crate) String);
+impl_meta_api_error!(MigrateInvocationCompletedError: CONFLICT "The invocation is completed. An invocation can be migrated only when paused or suspended.");
+
+#[derive(Debug, thiserror::Error)]
+#[error("The invocation '{0}' is not pinned to any deployment yet, cannot be migrated.")]
+pub(crate) struct MigrateInvocationNotPinnedError(pub(crate) String);
+impl_meta_api_error!(MigrateInvocationNotPinnedError: CONFLICT "The invocation is not pinned to any deployment yet. It will be pinned to a deployment when resumed.");
+
+#[derive(Debug, thiserror::Error)]
+#[error("The given deployment was not found when trying to migrate the invocation '{0}'.")]
+pub(crate) struct MigrateInvocationDeploymentNotFoundError(pub(crate) String);
+impl_meta_api_error!(MigrateInvocationDeploymentNotFoundError: BAD_REQUEST "The given deployment was not found.");
+
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "The invocation '{invocation_id}' is running on protocol version '{pinned_protocol_version}', while the chosen deployment '{deployment_id}' supports the range {supported_protocol_versions:?}."
+)]
+pub(crate) struct MigrateInvocationIncompatibleDeploymentIdError {
+    pub(crate) invocation_id: String,
+    pub(crate) pinned_protocol_version: i32,
+    pub(crate) deployment_id: String,
+    pub(crate) supported_protocol_versions: RangeInclusive<i32>,
+}
+impl_meta_api_error!(MigrateInvocationIncompatibleDeploymentIdError: BAD_REQUEST "The selected deployment id to migrate the invocation to doesn't support the currently pinned service protocol version.");
+
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "The chosen deployment '{deployment_id}' doesn't contain the handler of the invocation '{invocation_id}'."
+)]
+pub(crate) struct MigrateInvocationMissingHandlerError {
+    pub(crate) invocation_id: String,
+    pub(crate) deployment_id: String,
+}
+impl_meta_api_error!(MigrateInvocationMissingHandlerError: BAD_REQUEST "The selected deployment id to migrate the invocation to doesn't contain the invoked handler.");
+
+#[derive(Debug, thiserror::Error)]
+#[error("The query engine is not available on this node.")]
+pub(crate) struct QueryEngineUnavailableError;
+impl_meta_api_error!(QueryEngineUnavailableError: SERVICE_UNAVAILABLE "The query engine required to select the invocations is not available on this node.");
+
 // --- Old Meta API errors. Please don't use these anymore.
 
 /// This error is used by handlers to propagate API errors,
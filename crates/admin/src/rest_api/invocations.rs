@@ -22,7 +22,8 @@ use restate_types::identifiers::{
 };
 use restate_types::invocation::client::{
     self, CancelInvocationResponse, InvocationClient, KillInvocationResponse,
-    PauseInvocationResponse, PurgeInvocationResponse, ResumeInvocationResponse,
+    MigrateInvocationResponse, PauseInvocationResponse, PurgeInvocationResponse,
+    ResumeInvocationResponse,
 };
 use restate_types::invocation::{InvocationTermination, PurgeInvocationRequest, TerminationFlavor};
 use restate_types::journal_v2::EntryIndex;
@@ -625,3 +626,122 @@ where
 
     Ok(StatusCode::ACCEPTED)
 }
+
+#[derive(Debug, Default, Deserialize)]
+pub struct MigrateInvocationQueryParams {
+    pub deployment: Option<PatchDeploymentId>,
+}
+
+generate_meta_api_error!(MigrateInvocationError: [
+    InvocationNotFoundError,
+    InvocationClientError,
+    InvalidFieldError,
+    MigrateInvocationRunningError,
+    MigrateInvocationNotStartedError,
+    MigrateInvocationCompletedError,
+    MigrateInvocationNotPinnedError,
+    MigrateInvocationDeploymentNotFoundError,
+    MigrateInvocationIncompatibleDeploymentIdError,
+    MigrateInvocationMissingHandlerError,
+]);
+
+impl MigrateInvocationError {
+    /// Maps the response of the invocation client, returning the deployment the invocation is
+    /// pinned to after the migration, if it changed.
+    pub(crate) fn from_response(
+        invocation_id: InvocationId,
+        response: MigrateInvocationResponse,
+    ) -> Result<Option<DeploymentId>, Self> {
+        match response {
+            MigrateInvocationResponse::Ok { deployment_id } => Ok(Some(deployment_id)),
+            MigrateInvocationResponse::AlreadyPinned => Ok(None),
+            MigrateInvocationResponse::NotFound => {
+                Err(InvocationNotFoundError(invocation_id.to_string()))?
+            }
+            MigrateInvocationResponse::NotStarted => {
+                Err(MigrateInvocationNotStartedError(invocation_id.to_string()))?
+            }
+            MigrateInvocationResponse::Running => {
+                Err(MigrateInvocationRunningError(invocation_id.to_string()))?
+            }
+            MigrateInvocationResponse::Completed => {
+                Err(MigrateInvocationCompletedError(invocation_id.to_string()))?
+            }
+            MigrateInvocationResponse::NotPinned => {
+                Err(MigrateInvocationNotPinnedError(invocation_id.to_string()))?
+            }
+            MigrateInvocationResponse::DeploymentNotFound => Err(
+                MigrateInvocationDeploymentNotFoundError(invocation_id.to_string()),
+            )?,
+            MigrateInvocationResponse::IncompatibleDeploymentId {
+                pinned_protocol_version,
+                deployment_id,
+                supported_protocol_versions,
+            } => Err(MigrateInvocationIncompatibleDeploymentIdError {
+                invocation_id: invocation_id.to_string(),
+                pinned_protocol_version,
+                deployment_id: deployment_id.to_string(),
+                supported_protocol_versions,
+            })?,
+            MigrateInvocationResponse::MissingHandler { deployment_id } => {
+                Err(MigrateInvocationMissingHandlerError {
+                    invocation_id: invocation_id.to_string(),
+                    deployment_id: deployment_id.to_string(),
+                })?
+            }
+        }
+    }
+}
+
+/// Migrate an invocation
+#[openapi(
+    summary = "Migrate an invocation",
+    description = "Pin the given suspended or paused invocation to another deployment, without resuming it. \
+    The invocation will continue on the new deployment once resumed. \
+    The deployment must support the service protocol version the invocation is running on, and contain the invoked handler.",
+    operation_id = "migrate_invocation",
+    tags = "invocation",
+    parameters(
+        path(
+            name = "invocation_id",
+            description = "Invocation identifier.",
+            schema = "std::string::String"
+        ),
+        query(
+            name = "deployment",
+            description = "Deployment id to pin the invocation to. \
+            If 'latest' or not provided, use the deployment of the latest revision of the invoked service.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "String",
+        )
+    )
+)]
+pub async fn migrate_invocation<Metadata, Discovery, Telemetry, Invocations>(
+    State(state): State<AdminServiceState<Metadata, Discovery, Telemetry, Invocations>>,
+    Path(invocation_id): Path<String>,
+    Query(MigrateInvocationQueryParams { deployment }): Query<MigrateInvocationQueryParams>,
+) -> Result<(), MigrateInvocationError>
+where
+    Invocations: InvocationClient,
+{
+    let invocation_id = invocation_id
+        .parse::<InvocationId>()
+        .map_err(|e| InvalidFieldError("invocation_id", e.to_string()))?;
+
+    let response = state
+        .invocation_client
+        .migrate_invocation(
+            PartitionProcessorRpcRequestId::new(),
+            invocation_id,
+            deployment
+                .unwrap_or(PatchDeploymentId::Latest)
+                .into_client()?,
+        )
+        .await
+        .map_err(InvocationClientError)?;
+    MigrateInvocationError::from_response(invocation_id, response)?;
+
+    Ok(())
+}
@@ -10,6 +10,7 @@
 
 //! This module implements the Meta API endpoint.
 
+mod bulk_invocations;
 mod cluster_health;
 mod deployments;
 mod error;
@@ -20,7 +21,7 @@ mod services;
 mod subscriptions;
 mod version;
 
-use okapi_operation::axum_integration::{delete, get, patch, post};
+use okapi_operation::axum_integration::{delete, get, patch, post, put};
 use okapi_operation::okapi::openapi3::{ExternalDocs, Tag};
 use okapi_operation::*;
 use restate_types::identifiers::PartitionKey;
@@ -58,6 +59,10 @@ where
             "/deployments/{deployment}",
             delete(openapi_handler!(deployments::delete_deployment)),
         )
+        .route(
+            "/deployments/{deployment}/drain-status",
+            get(openapi_handler!(deployments::get_deployment_drain_status)),
+        )
         .route(
             "/deployments/{deployment}",
             patch(openapi_handler!(deployments::update_deployment)),
@@ -79,6 +84,22 @@ where
             "/services/{service}",
             patch(openapi_handler!(services::modify_service)),
         )
+        .route(
+            "/services/{service}/routing",
+            get(openapi_handler!(services::get_service_routing)),
+        )
+        .route(
+            "/services/{service}/routing",
+            put(openapi_handler!(services::set_service_routing)),
+        )
+        .route(
+            "/services/{service}/migration-policy",
+            get(openapi_handler!(services::get_service_migration_policy)),
+        )
+        .route(
+            "/services/{service}/migration-policy",
+            put(openapi_handler!(services::set_service_migration_policy)),
+        )
         .route(
             "/services/{service}/state",
             post(openapi_handler!(services::modify_service_state)),
@@ -91,6 +112,12 @@ where
             "/services/{service}/handlers/{handler}",
             get(openapi_handler!(handlers::get_service_handler)),
         )
+        .route(
+            "/invocations/bulk",
+            post(openapi_handler!(
+                bulk_invocations::bulk_invocation_operation
+            )),
+        )
         .route(
             "/invocations/{invocation_id}",
             delete(openapi_handler!(invocations::delete_invocation)),
@@ -123,6 +150,10 @@ where
             "/invocations/{invocation_id}/pause",
             patch(openapi_handler!(invocations::pause_invocation)),
         )
+        .route(
+            "/invocations/{invocation_id}/migrate",
+            patch(openapi_handler!(invocations::migrate_invocation)),
+        )
         .route(
             "/subscriptions",
             post(openapi_handler!(subscriptions::create_subscription)),
@@ -209,7 +240,7 @@ where
         .with_state(state)
 }
 
-fn create_envelope_header(partition_key: PartitionKey) -> Header {
+pub(crate) fn create_envelope_header(partition_key: PartitionKey) -> Header {
     Header {
         source: Source::ControlPlane {},
         dest: Destination::Processor {
@@ -24,7 +24,9 @@ use restate_errors::warn_it;
 use restate_types::config::Configuration;
 use restate_types::identifiers::{ServiceId, WithPartitionKey};
 use restate_types::schema;
+use restate_types::schema::migration::MigrationPolicy;
 use restate_types::schema::registry::MetadataService;
+use restate_types::schema::routing::ServiceRouting;
 use restate_types::schema::service::ServiceMetadata;
 use restate_types::state_mut::ExternalStateMutation;
 use restate_wal_protocol::{Command, Envelope};
@@ -141,6 +143,7 @@ pub async fn modify_service<Metadata, Discovery, Telemetry, Invocations>(
         journal_retention,
         inactivity_timeout,
         abort_timeout,
+        handler_retry_rules,
     }): Json<ModifyServiceRequest>,
 ) -> Result<Json<ServiceMetadata>, MetaApiError>
 where
@@ -153,6 +156,7 @@ where
         workflow_completion_retention,
         inactivity_timeout,
         abort_timeout,
+        handler_retry_rules,
     };
 
     if modify_request.public.is_none()
@@ -161,6 +165,7 @@ where
         && modify_request.workflow_completion_retention.is_none()
         && modify_request.inactivity_timeout.is_none()
         && modify_request.abort_timeout.is_none()
+        && modify_request.handler_retry_rules.is_empty()
     {
         // No need to do anything
         return get_service(State(state), Path(service_name)).await;
@@ -175,6 +180,116 @@ where
     Ok(response.into())
 }
 
+/// Get the service routing
+#[openapi(
+    summary = "Get service routing",
+    description = "Get the rules routing the new invocations of the service among its deployments. Without rules, new invocations are routed to the deployment of the latest service revision.",
+    operation_id = "get_service_routing",
+    tags = "service",
+    parameters(path(
+        name = "service",
+        description = "Fully qualified service name.",
+        schema = "std::string::String"
+    ))
+)]
+pub async fn get_service_routing<Metadata, Discovery, Telemetry, Invocations>(
+    State(state): State<AdminServiceState<Metadata, Discovery, Telemetry, Invocations>>,
+    Path(service_name): Path<String>,
+) -> Result<Json<ServiceRouting>, MetaApiError>
+where
+    Metadata: MetadataService,
+{
+    state
+        .schema_registry
+        .get_service_routing(&service_name)
+        .map(Into::into)
+        .ok_or_else(|| MetaApiError::ServiceNotFound(service_name))
+}
+
+/// Set the service routing
+#[openapi(
+    summary = "Set service routing",
+    description = "Replace the rules routing the new invocations of the service among its deployments. Routes can only point to deployments containing the service, and are removed together with the deployment. Empty rules route all the new invocations to the deployment of the latest service revision. Already pinned invocations are not affected.",
+    operation_id = "set_service_routing",
+    tags = "service",
+    parameters(path(
+        name = "service",
+        description = "Fully qualified service name.",
+        schema = "std::string::String"
+    ))
+)]
+pub async fn set_service_routing<Metadata, Discovery, Telemetry, Invocations>(
+    State(state): State<AdminServiceState<Metadata, Discovery, Telemetry, Invocations>>,
+    Path(service_name): Path<String>,
+    #[request_body(required = true)] Json(service_routing): Json<ServiceRouting>,
+) -> Result<Json<ServiceRouting>, MetaApiError>
+where
+    Metadata: MetadataService,
+{
+    let response = state
+        .schema_registry
+        .set_service_routing(service_name, service_routing)
+        .await
+        .inspect_err(|e| warn_it!(e))?;
+
+    Ok(response.into())
+}
+
+/// Get the service migration policy
+#[openapi(
+    summary = "Get service migration policy",
+    description = "Get the policy migrating the suspended and paused invocations of the service to newer deployments. Without policy, invocations stay pinned to the deployment they started on.",
+    operation_id = "get_service_migration_policy",
+    tags = "service",
+    parameters(path(
+        name = "service",
+        description = "Fully qualified service name.",
+        schema = "std::string::String"
+    ))
+)]
+pub async fn get_service_migration_policy<Metadata, Discovery, Telemetry, Invocations>(
+    State(state): State<AdminServiceState<Metadata, Discovery, Telemetry, Invocations>>,
+    Path(service_name): Path<String>,
+) -> Result<Json<MigrationPolicy>, MetaApiError>
+where
+    Metadata: MetadataService,
+{
+    state
+        .schema_registry
+        .get_migration_policy(&service_name)
+        .map(Into::into)
+        .ok_or_else(|| MetaApiError::ServiceNotFound(service_name))
+}
+
+/// Set the service migration policy
+#[openapi(
+    summary = "Set service migration policy",
+    description = "Replace the policy migrating the suspended and paused invocations of the service to newer deployments. With `migrate_to_latest`, the invocations are periodically re-pinned to the deployment of the latest service revision, provided it supports the protocol version they use and contains their handler. Invocations pinned to a deployment the routing rules of the service route to are not migrated. The invocations are not resumed by the migration. The policy is removed together with the service.",
+    operation_id = "set_service_migration_policy",
+    tags = "service",
+    parameters(path(
+        name = "service",
+        description = "Fully qualified service name.",
+        schema = "std::string::String"
+    ))
+)]
+pub async fn set_service_migration_policy<Metadata, Discovery, Telemetry, Invocations>(
+    State(state): State<AdminServiceState<Metadata, Discovery, Telemetry, Invocations>>,
+    Path(service_name): Path<String>,
+    #[request_body(required = true)] Json(migration_policy): Json<MigrationPolicy>,
+) -> Result<Json<MigrationPolicy>, MetaApiError>
+where
+    Metadata: MetadataService,
+{
+    let response = state
+        .schema_registry
+        .set_migration_policy(service_name, migration_policy)
+        .await
+        .inspect_err(|e| warn_it!(e))?;
+
+    Ok(response.into())
+}
+
 /// Modify a service state
 #[openapi(
     summary = "Modify a service state",
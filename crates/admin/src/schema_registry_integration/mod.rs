@@ -92,6 +92,7 @@ impl restate_types::schema::registry::TelemetryClient for TelemetryClient {
                 .request(
                     uri,
                     None,
+                    Default::default(),
                     http::Method::GET,
                     http_body_util::Empty::new(),
                     PathAndQuery::from_static("/"),
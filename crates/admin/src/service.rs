@@ -95,17 +95,62 @@ where
     ) -> anyhow::Result<()> {
         let opts = updateable_config.live_load();
 
+        #[cfg(feature = "storage-query")]
+        let query_context = self.query_context.clone();
+        #[cfg(not(feature = "storage-query"))]
+        let query_context = None;
+
+        #[cfg(feature = "storage-query")]
+        if let Some(cleanup_after) = opts.cleanup_drained_deployments_after {
+            if let Some(query_context) = &query_context {
+                TaskCenter::spawn_child(
+                    restate_core::TaskKind::Background,
+                    "drained-deployments-cleanup",
+                    crate::deployment_draining::DrainedDeploymentsCleanupTask::new(
+                        self.schema_registry.clone(),
+                        query_context.clone(),
+                        cleanup_after.into(),
+                    )
+                    .run(),
+                )?;
+            } else {
+                tracing::warn!(
+                    "Automatic cleanup of drained deployments is configured, but the query engine is not available. Drained deployments won't be deleted."
+                );
+            }
+        }
+
+        // Applies the per-service migration policies. Services without policy are skipped, so
+        // the task is cheap to run unconditionally.
+        #[cfg(feature = "storage-query")]
+        if let Some(query_context) = &query_context {
+            TaskCenter::spawn_child(
+                restate_core::TaskKind::Background,
+                "invocation-migration",
+                crate::invocation_migration::InvocationMigrationTask::new(
+                    self.schema_registry.clone(),
+                    query_context.clone(),
+                    self.invocation_client.clone(),
+                )
+                .run(),
+            )?;
+        }
+
         let rest_state = state::AdminServiceState::new(
             self.schema_registry,
             self.invocation_client,
             self.bifrost,
+            query_context,
         );
 
         let router = axum::Router::new();
 
         #[cfg(feature = "storage-query")]
         let router = if let Some(query_context) = self.query_context {
-            router.merge(crate::storage_query::router(query_context))
+            router.merge(crate::storage_query::router(
+                query_context,
+                rest_state.bifrost.clone(),
+            )?)
         } else {
             router
         };
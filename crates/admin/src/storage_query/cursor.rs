@@ -0,0 +1,427 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::DataFusionError;
+use datafusion::execution::SendableRecordBatchStream;
+use futures::StreamExt;
+use parking_lot::Mutex;
+use tokio::time::MissedTickBehavior;
+use tracing::debug;
+
+use restate_core::cancellation_watcher;
+use restate_types::config::QueryEngineOptions;
+
+use super::error::StorageQueryError;
+
+/// An open query whose results are read one page at a time.
+///
+/// At most one page (plus the remainder of a split record batch) is buffered in memory, the rest
+/// of the results are produced lazily by the underlying stream. Dropping the cursor drops the
+/// stream, which cancels the query.
+pub struct Cursor {
+    stream: SendableRecordBatchStream,
+    /// Rows read from the stream that didn't fit in the previous page
+    pending: Option<RecordBatch>,
+    page_size: NonZeroUsize,
+}
+
+impl Cursor {
+    pub fn new(stream: SendableRecordBatchStream, page_size: NonZeroUsize) -> Self {
+        Self {
+            stream,
+            pending: None,
+            page_size,
+        }
+    }
+
+    pub fn schema(&self) -> SchemaRef {
+        self.stream.schema()
+    }
+
+    /// Memory held by the rows buffered between pages.
+    pub fn buffered_bytes(&self) -> usize {
+        self.pending
+            .as_ref()
+            .map_or(0, RecordBatch::get_array_memory_size)
+    }
+
+    /// Reads the next page of at most `page_size` rows.
+    /// Returns the page and whether the cursor has been exhausted.
+    pub async fn next_page(&mut self) -> Result<(Vec<RecordBatch>, bool), DataFusionError> {
+        let page_size = self.page_size.get();
+        let mut page = Vec::new();
+        let mut rows = 0;
+
+        loop {
+            let batch = match self.pending.take() {
+                Some(batch) => batch,
+                None => match self.stream.next().await {
+                    Some(batch) => batch?,
+                    None => return Ok((page, true)),
+                },
+            };
+
+            let remaining = page_size - rows;
+            if batch.num_rows() > remaining {
+                page.push(batch.slice(0, remaining));
+                self.pending = Some(batch.slice(remaining, batch.num_rows() - remaining));
+                return Ok((page, false));
+            }
+
+            rows += batch.num_rows();
+            page.push(batch);
+
+            if rows == page_size {
+                // read ahead to find out whether this was the last page
+                return match self.stream.next().await {
+                    Some(batch) => {
+                        self.pending = Some(batch?);
+                        Ok((page, false))
+                    }
+                    None => Ok((page, true)),
+                };
+            }
+        }
+    }
+}
+
+/// Registry of the open cursors of this node.
+///
+/// Idle cursors are closed once they exceed the idle timeout, or when the rows buffered by all
+/// idle cursors exceed the memory budget, starting from the least recently used one.
+pub struct QueryCursors {
+    idle_timeout: Duration,
+    max_cursors: usize,
+    memory_budget: usize,
+    cursors: Mutex<HashMap<String, Slot>>,
+}
+
+struct Slot {
+    /// `None` while a request is reading a page from the cursor
+    cursor: Option<Cursor>,
+    last_access: Instant,
+}
+
+impl Slot {
+    fn idle_cursor_bytes(&self) -> Option<usize> {
+        self.cursor.as_ref().map(Cursor::buffered_bytes)
+    }
+}
+
+impl QueryCursors {
+    pub fn new(options: &QueryEngineOptions) -> Self {
+        Self {
+            idle_timeout: options.query_cursor_idle_timeout.to_std(),
+            // the query of an open cursor holds its admission permit, leave a permit to the
+            // other queries unless there's a single one
+            max_cursors: options
+                .max_query_cursors
+                .get()
+                .min(options.max_concurrent_queries.get() - 1)
+                .max(1),
+            memory_budget: options.query_cursors_memory_budget.get(),
+            cursors: Mutex::default(),
+        }
+    }
+
+    /// Periodically closes the expired cursors, so that abandoned queries release their
+    /// resources even if no other cursor is opened or advanced.
+    pub async fn run_eviction(self: Arc<Self>) -> anyhow::Result<()> {
+        let mut eviction_interval =
+            tokio::time::interval((self.idle_timeout / 2).max(Duration::from_secs(1)));
+        eviction_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        let mut cancel = std::pin::pin!(cancellation_watcher());
+        loop {
+            tokio::select! {
+                _ = eviction_interval.tick() => {
+                    self.evict_idle(&mut self.cursors.lock());
+                }
+                _ = &mut cancel => {
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Registers a new cursor, leased to the caller until the lease is released.
+    pub fn open(self: &Arc<Self>, cursor: Cursor) -> Result<CursorLease, StorageQueryError> {
+        let mut cursors = self.cursors.lock();
+        self.evict_idle(&mut cursors);
+        if cursors.len() >= self.max_cursors {
+            return Err(StorageQueryError::TooManyCursors);
+        }
+
+        let id = format!("{:032x}", rand::random::<u128>());
+        cursors.insert(
+            id.clone(),
+            Slot {
+                cursor: None,
+                last_access: Instant::now(),
+            },
+        );
+
+        Ok(CursorLease {
+            cursors: Arc::clone(self),
+            id,
+            cursor: Some(cursor),
+        })
+    }
+
+    /// Leases an existing cursor to read its next page.
+    pub fn lease(self: &Arc<Self>, id: &str) -> Result<CursorLease, StorageQueryError> {
+        let mut cursors = self.cursors.lock();
+        self.evict_idle(&mut cursors);
+        let slot = cursors
+            .get_mut(id)
+            .ok_or_else(|| StorageQueryError::CursorNotFound(id.to_owned()))?;
+        let cursor = slot
+            .cursor
+            .take()
+            .ok_or_else(|| StorageQueryError::CursorBusy(id.to_owned()))?;
+
+        Ok(CursorLease {
+            cursors: Arc::clone(self),
+            id: id.to_owned(),
+            cursor: Some(cursor),
+        })
+    }
+
+    /// Closes the cursor, cancelling its query.
+    pub fn close(&self, id: &str) -> Result<(), StorageQueryError> {
+        let mut cursors = self.cursors.lock();
+        match cursors.get(id) {
+            None => Err(StorageQueryError::CursorNotFound(id.to_owned())),
+            Some(Slot { cursor: None, .. }) => Err(StorageQueryError::CursorBusy(id.to_owned())),
+            Some(_) => {
+                cursors.remove(id);
+                Ok(())
+            }
+        }
+    }
+
+    fn evict_idle(&self, cursors: &mut HashMap<String, Slot>) {
+        cursors.retain(|id, slot| {
+            let expired = slot.cursor.is_some() && slot.last_access.elapsed() >= self.idle_timeout;
+            if expired {
+                debug!(cursor = %id, "Closing idle query cursor");
+            }
+            !expired
+        });
+    }
+
+    /// Closes the least recently used idle cursors until the memory they buffer fits the budget.
+    fn enforce_memory_budget(&self, cursors: &mut HashMap<String, Slot>) {
+        let mut buffered: usize = cursors.values().filter_map(Slot::idle_cursor_bytes).sum();
+        if buffered <= self.memory_budget {
+            return;
+        }
+
+        let mut idle: Vec<_> = cursors
+            .iter()
+            .filter(|(_, slot)| slot.cursor.is_some())
+            .map(|(id, slot)| (slot.last_access, id.clone()))
+            .collect();
+        idle.sort_unstable();
+
+        for (_, id) in idle {
+            if buffered <= self.memory_budget {
+                break;
+            }
+            if let Some(slot) = cursors.remove(&id) {
+                debug!(cursor = %id, "Closing query cursor to respect the cursors memory budget");
+                buffered -= slot.idle_cursor_bytes().unwrap_or_default();
+            }
+        }
+    }
+}
+
+/// Exclusive access to a registered cursor.
+///
+/// If the lease is dropped without being released, e.g. because the client disconnected while
+/// the page was being read, the cursor is closed and its query cancelled.
+pub struct CursorLease {
+    cursors: Arc<QueryCursors>,
+    id: String,
+    cursor: Option<Cursor>,
+}
+
+impl CursorLease {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn cursor(&mut self) -> &mut Cursor {
+        self.cursor
+            .as_mut()
+            .expect("cursor is present until released")
+    }
+
+    /// Gives the cursor back to the registry, so that the next page can be requested.
+    pub fn release(mut self) {
+        let cursor = self.cursor.take();
+        let mut cursors = self.cursors.cursors.lock();
+        if let Some(slot) = cursors.get_mut(&self.id) {
+            slot.cursor = cursor;
+            slot.last_access = Instant::now();
+        }
+        self.cursors.enforce_memory_budget(&mut cursors);
+    }
+}
+
+impl Drop for CursorLease {
+    fn drop(&mut self) {
+        if self.cursor.is_some() {
+            self.cursors.cursors.lock().remove(&self.id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use datafusion::arrow::array::{AsArray, Int32Array};
+    use datafusion::arrow::datatypes::{DataType, Field, Int32Type, Schema};
+    use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+    use futures::stream;
+    use googletest::prelude::*;
+    use test_log::test;
+
+    fn cursor(batches: Vec<Vec<i32>>, page_size: usize) -> Cursor {
+        let schema = Arc::new(Schema::new(vec![Field::new("n", DataType::Int32, false)]));
+        let batches: Vec<_> = batches
+            .into_iter()
+            .map(|values| {
+                Ok(
+                    RecordBatch::try_new(schema.clone(), vec![Arc::new(Int32Array::from(values))])
+                        .unwrap(),
+                )
+            })
+            .collect();
+        Cursor::new(
+            Box::pin(RecordBatchStreamAdapter::new(schema, stream::iter(batches))),
+            NonZeroUsize::new(page_size).unwrap(),
+        )
+    }
+
+    fn values(page: &[RecordBatch]) -> Vec<i32> {
+        page.iter()
+            .flat_map(|batch| {
+                batch
+                    .column(0)
+                    .as_primitive::<Int32Type>()
+                    .values()
+                    .to_vec()
+            })
+            .collect()
+    }
+
+    #[test(tokio::test)]
+    async fn pages_split_record_batches() -> googletest::Result<()> {
+        let mut cursor = cursor(vec![vec![1, 2, 3], vec![4, 5], vec![6]], 2);
+
+        let (page, exhausted) = cursor.next_page().await?;
+        assert_that!(values(&page), eq(vec![1, 2]));
+        assert_that!(exhausted, eq(false));
+
+        let (page, exhausted) = cursor.next_page().await?;
+        assert_that!(values(&page), eq(vec![3, 4]));
+        assert_that!(exhausted, eq(false));
+
+        let (page, exhausted) = cursor.next_page().await?;
+        assert_that!(values(&page), eq(vec![5, 6]));
+        assert_that!(exhausted, eq(true));
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn last_page_is_detected_with_exact_page_size() -> googletest::Result<()> {
+        let mut cursor = cursor(vec![vec![1, 2], vec![3, 4]], 4);
+
+        let (page, exhausted) = cursor.next_page().await?;
+        assert_that!(values(&page), eq(vec![1, 2, 3, 4]));
+        assert_that!(exhausted, eq(true));
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn least_recently_used_cursors_are_closed_over_memory_budget() -> googletest::Result<()> {
+        let mut first = cursor(vec![(0..1024).collect()], 1);
+        first.next_page().await?;
+        let buffered = first.buffered_bytes();
+        assert_that!(buffered, gt(0));
+
+        let mut options = QueryEngineOptions::default();
+        options.query_cursors_memory_budget = NonZeroUsize::new(buffered + buffered / 2).unwrap();
+        let cursors = Arc::new(QueryCursors::new(&options));
+
+        let first = {
+            let lease = cursors.open(first)?;
+            let id = lease.id().to_owned();
+            lease.release();
+            id
+        };
+
+        let mut second = cursors.open(cursor(vec![(0..1024).collect()], 1))?;
+        let second_id = second.id().to_owned();
+        second.cursor().next_page().await?;
+        second.release();
+
+        assert_that!(
+            cursors.lease(&first).err(),
+            some(pat!(StorageQueryError::CursorNotFound(anything())))
+        );
+        assert_that!(cursors.lease(&second_id).map(|_| ()), ok(anything()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn cursors_leave_a_query_slot_to_other_queries() -> googletest::Result<()> {
+        let mut options = QueryEngineOptions::default();
+        options.max_concurrent_queries = NonZeroUsize::new(2).unwrap();
+        let cursors = Arc::new(QueryCursors::new(&options));
+
+        cursors.open(cursor(vec![vec![1]], 1))?.release();
+        assert_that!(
+            cursors.open(cursor(vec![vec![1]], 1)).err(),
+            some(pat!(StorageQueryError::TooManyCursors))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn single_query_slot_allows_a_cursor() -> googletest::Result<()> {
+        let mut options = QueryEngineOptions::default();
+        options.max_concurrent_queries = NonZeroUsize::new(1).unwrap();
+        let cursors = Arc::new(QueryCursors::new(&options));
+
+        cursors.open(cursor(vec![vec![1]], 1))?.release();
+        assert_that!(
+            cursors.open(cursor(vec![vec![1]], 1)).err(),
+            some(pat!(StorageQueryError::TooManyCursors))
+        );
+
+        Ok(())
+    }
+}
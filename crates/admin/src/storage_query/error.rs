@@ -11,6 +11,7 @@
 use axum::Json;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
+use datafusion::arrow::error::ArrowError;
 use datafusion::error::DataFusionError;
 use okapi_operation::anyhow::Error;
 use okapi_operation::okapi::map;
@@ -25,6 +26,26 @@ use serde::Serialize;
 pub enum StorageQueryError {
     #[error("datafusion failed: {0}")]
     DataFusion(#[from] DataFusionError),
+    #[error("query cursor '{0}' not found, it might have expired")]
+    CursorNotFound(String),
+    #[error("query cursor '{0}' is being read by another request")]
+    CursorBusy(String),
+    #[error("too many open query cursors, close some cursors or retry later")]
+    TooManyCursors,
+    #[error(
+        "state mutations are disabled, set 'admin.query-engine.allow-state-mutations' to enable them"
+    )]
+    StateMutationsDisabled,
+    #[error("invalid state mutation: {0}")]
+    InvalidStateMutation(String),
+    #[error("failed to patch the state of '{0}': {1}")]
+    StateMutationFailed(String, String),
+}
+
+impl From<ArrowError> for StorageQueryError {
+    fn from(err: ArrowError) -> Self {
+        StorageQueryError::DataFusion(err.into())
+    }
 }
 
 /// # Error description response
@@ -37,7 +58,19 @@ struct ErrorDescriptionResponse {
 
 impl IntoResponse for StorageQueryError {
     fn into_response(self) -> Response {
-        let status_code = StatusCode::INTERNAL_SERVER_ERROR;
+        let status_code = match &self {
+            // the query exceeded its memory or time limits, or couldn't be admitted
+            StorageQueryError::DataFusion(DataFusionError::ResourcesExhausted(_)) => {
+                StatusCode::SERVICE_UNAVAILABLE
+            }
+            StorageQueryError::DataFusion(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            StorageQueryError::CursorNotFound(_) => StatusCode::NOT_FOUND,
+            StorageQueryError::CursorBusy(_) => StatusCode::CONFLICT,
+            StorageQueryError::TooManyCursors => StatusCode::SERVICE_UNAVAILABLE,
+            StorageQueryError::StateMutationsDisabled => StatusCode::FORBIDDEN,
+            StorageQueryError::InvalidStateMutation(_) => StatusCode::BAD_REQUEST,
+            StorageQueryError::StateMutationFailed(..) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
 
         (
             status_code,
@@ -0,0 +1,605 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Arrow Flight SQL frontend for the storage query engine.
+//!
+//! Statements are stateless: the ticket of a statement and the handle of a prepared statement
+//! both carry the SQL text, which is planned again when the results are requested. Any node
+//! serving the admin API can therefore answer a `DoGet`, and there is nothing to clean up when
+//! a client goes away.
+
+use std::pin::Pin;
+use std::sync::{Arc, LazyLock};
+
+use arrow_flight::encode::FlightDataEncoderBuilder;
+use arrow_flight::error::FlightError;
+use arrow_flight::flight_service_server::{FlightService, FlightServiceServer};
+use arrow_flight::sql::metadata::{SqlInfoData, SqlInfoDataBuilder};
+use arrow_flight::sql::server::FlightSqlService;
+use arrow_flight::sql::{
+    ActionClosePreparedStatementRequest, ActionCreatePreparedStatementRequest,
+    ActionCreatePreparedStatementResult, CommandGetCatalogs, CommandGetDbSchemas,
+    CommandGetSqlInfo, CommandGetTableTypes, CommandGetTables, CommandPreparedStatementQuery,
+    CommandStatementQuery, ProstMessageExt, SqlInfo, TicketStatementQuery,
+};
+use arrow_flight::{
+    Action, FlightDescriptor, FlightEndpoint, FlightInfo, HandshakeRequest, HandshakeResponse,
+    IpcMessage, SchemaAsIpc, Ticket,
+};
+use bytes::Bytes;
+use datafusion::arrow::array::{RecordBatch, StringArray};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::arrow::error::ArrowError;
+use datafusion::arrow::ipc::writer::IpcWriteOptions;
+use datafusion::datasource::TableType;
+use datafusion::error::DataFusionError;
+use futures::{Stream, TryStreamExt, stream};
+use prost::Message;
+use tonic::{Request, Response, Status, Streaming};
+use tracing::debug;
+
+use restate_storage_query_datafusion::context::QueryContext;
+
+type DoGetStream = <FlightSqlServiceImpl as FlightService>::DoGetStream;
+
+static SQL_INFO: LazyLock<SqlInfoData> = LazyLock::new(|| {
+    let mut builder = SqlInfoDataBuilder::new();
+    builder.append(SqlInfo::FlightSqlServerName, "Restate");
+    builder.append(SqlInfo::FlightSqlServerVersion, env!("CARGO_PKG_VERSION"));
+    builder.append(SqlInfo::FlightSqlServerArrowVersion, "1.3");
+    builder.append(SqlInfo::FlightSqlServerReadOnly, true);
+    builder.append(SqlInfo::FlightSqlServerSql, true);
+    builder.append(SqlInfo::FlightSqlServerSubstrait, false);
+    builder.append(SqlInfo::FlightSqlServerTransaction, 0_i32);
+    builder.append(SqlInfo::FlightSqlServerCancel, false);
+    builder.build().expect("valid sql info")
+});
+
+static TABLE_TYPES: [TableType; 3] = [TableType::Base, TableType::View, TableType::Temporary];
+
+pub fn service(query_context: QueryContext) -> FlightServiceServer<FlightSqlServiceImpl> {
+    FlightServiceServer::new(FlightSqlServiceImpl { query_context })
+}
+
+pub struct FlightSqlServiceImpl {
+    query_context: QueryContext,
+}
+
+impl FlightSqlServiceImpl {
+    async fn statement_info(
+        &self,
+        sql: &str,
+        ticket: Ticket,
+        descriptor: FlightDescriptor,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let schema = self
+            .query_context
+            .result_schema(sql)
+            .await
+            .map_err(datafusion_to_status)?;
+
+        let info = FlightInfo::new()
+            .try_with_schema(&schema)
+            .map_err(arrow_to_status)?
+            .with_endpoint(FlightEndpoint::new().with_ticket(ticket))
+            .with_descriptor(descriptor);
+
+        Ok(Response::new(info))
+    }
+
+    async fn execute(&self, sql: &str) -> Result<Response<DoGetStream>, Status> {
+        debug!(%sql, "Executing Flight SQL statement");
+
+        let stream = self
+            .query_context
+            .execute(sql)
+            .await
+            .map_err(datafusion_to_status)?;
+
+        let flight_data = FlightDataEncoderBuilder::new()
+            .with_schema(stream.schema())
+            .build(stream.map_err(|err| FlightError::ExternalError(Box::new(err))))
+            .map_err(Status::from);
+
+        Ok(Response::new(Box::pin(flight_data)))
+    }
+
+    /// Describes a metadata command, whose ticket is the command itself.
+    fn metadata_info(
+        command: impl ProstMessageExt,
+        schema: &Schema,
+        descriptor: FlightDescriptor,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let ticket = Ticket::new(command.as_any().encode_to_vec());
+
+        let info = FlightInfo::new()
+            .try_with_schema(schema)
+            .map_err(arrow_to_status)?
+            .with_endpoint(FlightEndpoint::new().with_ticket(ticket))
+            .with_descriptor(descriptor);
+
+        Ok(Response::new(info))
+    }
+
+    /// Collects the schemas of all the tables registered in the query engine.
+    async fn tables(&self) -> Result<Vec<TableEntry>, Status> {
+        let ctx = self.query_context.as_ref();
+        let mut tables = Vec::new();
+
+        for catalog_name in ctx.catalog_names() {
+            let Some(catalog) = ctx.catalog(&catalog_name) else {
+                continue;
+            };
+            for schema_name in catalog.schema_names() {
+                let Some(schema) = catalog.schema(&schema_name) else {
+                    continue;
+                };
+                for table_name in schema.table_names() {
+                    let Some(table) = schema
+                        .table(&table_name)
+                        .await
+                        .map_err(datafusion_to_status)?
+                    else {
+                        continue;
+                    };
+                    tables.push(TableEntry {
+                        catalog_name: catalog_name.clone(),
+                        schema_name: schema_name.clone(),
+                        table_name,
+                        table_type: table.table_type(),
+                        schema: table.schema(),
+                    });
+                }
+            }
+        }
+
+        Ok(tables)
+    }
+}
+
+struct TableEntry {
+    catalog_name: String,
+    schema_name: String,
+    table_name: String,
+    table_type: TableType,
+    schema: SchemaRef,
+}
+
+#[tonic::async_trait]
+impl FlightSqlService for FlightSqlServiceImpl {
+    type FlightService = FlightSqlServiceImpl;
+
+    async fn do_handshake(
+        &self,
+        _request: Request<Streaming<HandshakeRequest>>,
+    ) -> Result<
+        Response<Pin<Box<dyn Stream<Item = Result<HandshakeResponse, Status>> + Send>>>,
+        Status,
+    > {
+        // the admin API is not authenticated, accept every client
+        let response = HandshakeResponse {
+            protocol_version: 0,
+            payload: Bytes::new(),
+        };
+        Ok(Response::new(Box::pin(stream::once(async {
+            Ok(response)
+        }))))
+    }
+
+    async fn get_flight_info_statement(
+        &self,
+        query: CommandStatementQuery,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let ticket = TicketStatementQuery {
+            statement_handle: Bytes::from(query.query.clone()),
+        };
+        self.statement_info(
+            &query.query,
+            Ticket::new(ticket.as_any().encode_to_vec()),
+            request.into_inner(),
+        )
+        .await
+    }
+
+    async fn get_flight_info_prepared_statement(
+        &self,
+        query: CommandPreparedStatementQuery,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let sql = handle_to_sql(&query.prepared_statement_handle)?;
+        self.statement_info(
+            &sql,
+            Ticket::new(query.as_any().encode_to_vec()),
+            request.into_inner(),
+        )
+        .await
+    }
+
+    async fn get_flight_info_catalogs(
+        &self,
+        query: CommandGetCatalogs,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let schema = query.clone().into_builder().schema();
+        Self::metadata_info(query, &schema, request.into_inner())
+    }
+
+    async fn get_flight_info_schemas(
+        &self,
+        query: CommandGetDbSchemas,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let schema = query.clone().into_builder().schema();
+        Self::metadata_info(query, &schema, request.into_inner())
+    }
+
+    async fn get_flight_info_tables(
+        &self,
+        query: CommandGetTables,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let schema = query.clone().into_builder().schema();
+        Self::metadata_info(query, &schema, request.into_inner())
+    }
+
+    async fn get_flight_info_table_types(
+        &self,
+        query: CommandGetTableTypes,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Self::metadata_info(query, &table_types_schema(), request.into_inner())
+    }
+
+    async fn get_flight_info_sql_info(
+        &self,
+        query: CommandGetSqlInfo,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let schema = query.clone().into_builder(&SQL_INFO).schema();
+        Self::metadata_info(query, &schema, request.into_inner())
+    }
+
+    async fn do_get_statement(
+        &self,
+        ticket: TicketStatementQuery,
+        _request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        let sql = handle_to_sql(&ticket.statement_handle)?;
+        self.execute(&sql).await
+    }
+
+    async fn do_get_prepared_statement(
+        &self,
+        query: CommandPreparedStatementQuery,
+        _request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        let sql = handle_to_sql(&query.prepared_statement_handle)?;
+        self.execute(&sql).await
+    }
+
+    async fn do_get_catalogs(
+        &self,
+        query: CommandGetCatalogs,
+        _request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        let mut builder = query.into_builder();
+        for catalog_name in self.query_context.as_ref().catalog_names() {
+            builder.append(catalog_name);
+        }
+
+        let schema = builder.schema();
+        Ok(batch_response(schema, builder.build()))
+    }
+
+    async fn do_get_schemas(
+        &self,
+        query: CommandGetDbSchemas,
+        _request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        let ctx = self.query_context.as_ref();
+        // the builder filters the schemas by catalog and name pattern
+        let mut builder = query.into_builder();
+        for catalog_name in ctx.catalog_names() {
+            let Some(catalog) = ctx.catalog(&catalog_name) else {
+                continue;
+            };
+            for schema_name in catalog.schema_names() {
+                builder.append(&catalog_name, schema_name);
+            }
+        }
+
+        let schema = builder.schema();
+        Ok(batch_response(schema, builder.build()))
+    }
+
+    async fn do_get_tables(
+        &self,
+        query: CommandGetTables,
+        _request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        // the builder filters the tables by catalog, schema, name pattern and type
+        let mut builder = query.into_builder();
+        for table in self.tables().await? {
+            builder
+                .append(
+                    table.catalog_name,
+                    table.schema_name,
+                    table.table_name,
+                    table_type_name(table.table_type),
+                    &table.schema,
+                )
+                .map_err(arrow_to_status)?;
+        }
+
+        let schema = builder.schema();
+        Ok(batch_response(schema, builder.build()))
+    }
+
+    async fn do_get_table_types(
+        &self,
+        _query: CommandGetTableTypes,
+        _request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        let schema = Arc::new(table_types_schema());
+        let table_types = StringArray::from_iter_values(TABLE_TYPES.map(table_type_name));
+        let batch = RecordBatch::try_new(Arc::clone(&schema), vec![Arc::new(table_types)]);
+
+        Ok(batch_response(schema, batch))
+    }
+
+    async fn do_get_sql_info(
+        &self,
+        query: CommandGetSqlInfo,
+        _request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        let builder = query.into_builder(&SQL_INFO);
+        let schema = builder.schema();
+        Ok(batch_response(schema, builder.build()))
+    }
+
+    async fn do_action_create_prepared_statement(
+        &self,
+        query: ActionCreatePreparedStatementRequest,
+        _request: Request<Action>,
+    ) -> Result<ActionCreatePreparedStatementResult, Status> {
+        let schema = self
+            .query_context
+            .result_schema(&query.query)
+            .await
+            .map_err(datafusion_to_status)?;
+        let IpcMessage(dataset_schema) = SchemaAsIpc::new(&schema, &IpcWriteOptions::default())
+            .try_into()
+            .map_err(arrow_to_status)?;
+
+        Ok(ActionCreatePreparedStatementResult {
+            prepared_statement_handle: Bytes::from(query.query),
+            dataset_schema,
+            // parameters are not supported
+            parameter_schema: Bytes::new(),
+        })
+    }
+
+    async fn do_action_close_prepared_statement(
+        &self,
+        _query: ActionClosePreparedStatementRequest,
+        _request: Request<Action>,
+    ) -> Result<(), Status> {
+        // prepared statements don't hold any server side state
+        Ok(())
+    }
+
+    async fn register_sql_info(&self, _id: i32, _result: &SqlInfo) {}
+}
+
+fn batch_response(
+    schema: SchemaRef,
+    batch: Result<RecordBatch, ArrowError>,
+) -> Response<DoGetStream> {
+    let flight_data = FlightDataEncoderBuilder::new()
+        .with_schema(schema)
+        .build(stream::once(async { batch.map_err(FlightError::from) }))
+        .map_err(Status::from);
+
+    Response::new(Box::pin(flight_data))
+}
+
+fn table_types_schema() -> Schema {
+    Schema::new(vec![Field::new("table_type", DataType::Utf8, false)])
+}
+
+/// Table type names, as reported by the `information_schema.tables` view.
+fn table_type_name(table_type: TableType) -> &'static str {
+    match table_type {
+        TableType::Base => "BASE TABLE",
+        TableType::View => "VIEW",
+        TableType::Temporary => "LOCAL TEMPORARY",
+    }
+}
+
+fn handle_to_sql(handle: &Bytes) -> Result<String, Status> {
+    String::from_utf8(handle.to_vec())
+        .map_err(|_| Status::invalid_argument("statement handle is not valid UTF-8"))
+}
+
+fn datafusion_to_status(err: DataFusionError) -> Status {
+    match err {
+        DataFusionError::SQL(..)
+        | DataFusionError::Plan(_)
+        | DataFusionError::SchemaError(..)
+        | DataFusionError::NotImplemented(_) => Status::invalid_argument(err.to_string()),
+        DataFusionError::ResourcesExhausted(_) => Status::resource_exhausted(err.to_string()),
+        err => Status::internal(err.to_string()),
+    }
+}
+
+fn arrow_to_status(err: ArrowError) -> Status {
+    Status::internal(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow_flight::decode::FlightRecordBatchStream;
+    use datafusion::arrow::array::{AsArray, Int64Array};
+    use datafusion::arrow::datatypes::Int64Type;
+    use datafusion::datasource::MemTable;
+    use googletest::prelude::*;
+    use test_log::test;
+
+    use restate_storage_query_datafusion::BuildError;
+    use restate_storage_query_datafusion::context::RegisterTable;
+    use restate_types::config::QueryEngineOptions;
+
+    struct NumbersTable(Arc<MemTable>);
+
+    impl RegisterTable for NumbersTable {
+        async fn register(&self, ctx: &QueryContext) -> std::result::Result<(), BuildError> {
+            ctx.as_ref()
+                .register_table("numbers", Arc::clone(&self.0) as _)?;
+            Ok(())
+        }
+    }
+
+    async fn flight_sql_service() -> FlightSqlServiceImpl {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("n", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2, 3])),
+                Arc::new(StringArray::from(vec!["one", "two", "three"])),
+            ],
+        )
+        .unwrap();
+
+        let query_context = QueryContext::create(
+            &QueryEngineOptions::default(),
+            NumbersTable(Arc::new(
+                MemTable::try_new(schema, vec![vec![batch]]).unwrap(),
+            )),
+        )
+        .await
+        .unwrap();
+
+        FlightSqlServiceImpl { query_context }
+    }
+
+    #[test(restate_core::test)]
+    async fn statement_round_trip() -> googletest::Result<()> {
+        let service = flight_sql_service().await;
+
+        let command = CommandStatementQuery {
+            query: "SELECT n, name FROM numbers WHERE n >= 2 ORDER BY n".to_owned(),
+            transaction_id: None,
+        };
+        let info = FlightService::get_flight_info(
+            &service,
+            Request::new(FlightDescriptor::new_cmd(command.as_any().encode_to_vec())),
+        )
+        .await?
+        .into_inner();
+
+        let expected_schema = Arc::new(Schema::new(vec![
+            Field::new("n", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, false),
+        ]));
+        assert_that!(
+            Arc::new(info.clone().try_decode_schema()?),
+            eq(Arc::clone(&expected_schema))
+        );
+        assert_that!(info.endpoint, len(eq(1)));
+
+        let ticket = info.endpoint[0]
+            .ticket
+            .clone()
+            .expect("endpoint has a ticket");
+        let flight_data = FlightService::do_get(&service, Request::new(ticket))
+            .await?
+            .into_inner();
+        let batches: Vec<RecordBatch> =
+            FlightRecordBatchStream::new_from_flight_data(flight_data.map_err(FlightError::from))
+                .try_collect()
+                .await?;
+
+        assert_that!(batches, not(empty()));
+        for batch in &batches {
+            assert_that!(batch.schema(), eq(Arc::clone(&expected_schema)));
+        }
+        let numbers: Vec<i64> = batches
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .column(0)
+                    .as_primitive::<Int64Type>()
+                    .values()
+                    .to_vec()
+            })
+            .collect();
+        let names: Vec<String> = batches
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .column(1)
+                    .as_string::<i32>()
+                    .iter()
+                    .map(|name| name.unwrap_or_default().to_owned())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        assert_that!(numbers, eq(vec![2, 3]));
+        assert_that!(names, eq(vec!["two".to_owned(), "three".to_owned()]));
+
+        Ok(())
+    }
+
+    #[test(restate_core::test)]
+    async fn invalid_statement_is_rejected() {
+        let service = flight_sql_service().await;
+
+        let command = CommandStatementQuery {
+            query: "SELECT * FROM unknown_table".to_owned(),
+            transaction_id: None,
+        };
+        let status = FlightService::get_flight_info(
+            &service,
+            Request::new(FlightDescriptor::new_cmd(command.as_any().encode_to_vec())),
+        )
+        .await
+        .unwrap_err();
+
+        assert_that!(status.code(), eq(tonic::Code::InvalidArgument));
+    }
+
+    #[test]
+    fn statement_handle_round_trip() {
+        let sql = "SELECT id FROM sys_invocation WHERE status = 'running'";
+        assert_that!(
+            handle_to_sql(&Bytes::from(sql.to_owned())),
+            ok(eq(sql.to_owned()))
+        );
+        assert_that!(
+            handle_to_sql(&Bytes::from_static(&[0xff, 0xfe])),
+            err(anything())
+        );
+    }
+
+    #[test]
+    fn planning_errors_are_invalid_arguments() {
+        let status = datafusion_to_status(DataFusionError::Plan("table not found".to_owned()));
+        assert_that!(status.code(), eq(tonic::Code::InvalidArgument));
+
+        let status = datafusion_to_status(DataFusionError::Internal("boom".to_owned()));
+        assert_that!(status.code(), eq(tonic::Code::Internal));
+    }
+}
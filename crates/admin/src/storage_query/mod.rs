@@ -8,24 +8,53 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+mod cursor;
 mod error;
+mod flight_sql;
 mod query;
+mod state_mutation;
 
 use axum::{Router, routing::post};
 use std::sync::Arc;
 
+use restate_bifrost::Bifrost;
+use restate_core::{ShutdownError, TaskCenter, TaskKind};
 use restate_storage_query_datafusion::context::QueryContext;
+use restate_types::config::Configuration;
+
+use self::cursor::QueryCursors;
 
 #[derive(Clone)]
 pub struct QueryServiceState {
     pub query_context: QueryContext,
+    pub cursors: Arc<QueryCursors>,
+    pub bifrost: Bifrost,
 }
 
-pub fn router(query_context: QueryContext) -> Router {
-    let query_state = Arc::new(QueryServiceState { query_context });
+pub fn router(query_context: QueryContext, bifrost: Bifrost) -> Result<Router, ShutdownError> {
+    let cursors = Arc::new(QueryCursors::new(
+        &Configuration::pinned().admin.query_engine,
+    ));
+    TaskCenter::spawn_child(
+        TaskKind::Background,
+        "query-cursors-eviction",
+        Arc::clone(&cursors).run_eviction(),
+    )?;
+    let flight_sql = flight_sql::service(query_context.clone());
+    let query_state = Arc::new(QueryServiceState {
+        query_context,
+        cursors,
+        bifrost,
+    });
 
     // Setup the router
-    axum::Router::new()
+    Ok(axum::Router::new()
         .route("/query", post(query::query))
-        .with_state(query_state)
+        .route(
+            "/query/cursors/{cursor}",
+            post(query::next_page).delete(query::close_cursor),
+        )
+        // Arrow Flight SQL, for JDBC/ADBC clients and BI tools
+        .route_service("/arrow.flight.protocol.FlightService/{*rpc}", flight_sql)
+        .with_state(query_state))
 }
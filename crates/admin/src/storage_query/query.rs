@@ -9,17 +9,21 @@
 // by the Apache License, Version 2.0.
 
 use std::io::Write;
+use std::num::NonZeroUsize;
 use std::pin::Pin;
 use std::sync::Arc;
 
 use crate::query_utils::{RecordBatchWriter, WriteRecordBatchStream};
 
 use super::QueryServiceState;
+use super::cursor::{Cursor, CursorLease};
 use super::error::StorageQueryError;
-use axum::extract::State;
-use axum::response::{IntoResponse, Response};
+use super::state_mutation::StateMutation;
+use axum::body::Body;
+use axum::extract::{Path, State};
+use axum::response::Response;
 use axum::{Json, http};
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
 use datafusion::arrow::array::RecordBatch;
 use datafusion::arrow::datatypes::Schema;
 use datafusion::arrow::ipc::writer::StreamWriter;
@@ -44,12 +48,25 @@ pub struct QueryRequest {
     #[serde_as(as = "serde_with::DisplayFromStr")]
     #[schemars(with = "String")]
     pub query: String,
+
+    /// # Page size
+    ///
+    /// If set, the response contains at most this many rows. When more rows are available, the
+    /// response carries a cursor that can be used to fetch the next page.
+    #[serde(default)]
+    pub page_size: Option<NonZeroUsize>,
 }
 
+/// Header carrying the cursor to fetch the next page of a paginated query
+pub const QUERY_CURSOR_HEADER: &str = "x-restate-query-cursor";
+
 /// Query storage
 #[openapi(
     summary = "Query storage",
-    description = "Query the storage API",
+    description = "Query the storage API. Results are streamed as they are produced, unless a page size is set, \
+    in which case the response contains a single page and a cursor to fetch the following pages. \
+    UPDATE and DELETE statements on the state table are applied as state patches of the affected Virtual Objects, \
+    if enabled in the query engine options.",
     operation_id = "query",
     tags = "storage",
     responses(ignore_return_type = true, from_type = "StorageQueryError")
@@ -58,25 +75,34 @@ pub async fn query(
     State(state): State<Arc<QueryServiceState>>,
     headers: HeaderMap,
     #[request_body(required = true)] Json(payload): Json<QueryRequest>,
-) -> Result<impl IntoResponse, StorageQueryError> {
-    let record_batch_stream = state.query_context.execute(&payload.query).await?;
+) -> Result<Response, StorageQueryError> {
+    let format = ResultFormat::from_headers(&headers);
+    let record_batch_stream = match StateMutation::parse(&payload.query)? {
+        Some(state_mutation) => state_mutation.apply(&state).await?,
+        None => state.query_context.execute(&payload.query).await?,
+    };
+
+    if let Some(page_size) = payload.page_size {
+        let lease = state
+            .cursors
+            .open(Cursor::new(record_batch_stream, page_size))?;
+        return next_page_response(lease, format).await;
+    }
 
-    let (result_stream, content_type) = match headers.get(http::header::ACCEPT) {
-        Some(v) if v == HeaderValue::from_static("application/json") => (
+    // The response body owns the record batch stream: if the client disconnects, the body is
+    // dropped together with the stream, cancelling the query.
+    let result_stream = match format {
+        ResultFormat::Json => {
             WriteRecordBatchStream::<JsonWriter>::new(record_batch_stream, payload.query)?
                 .map_ok(Frame::data)
-                .left_stream(),
-            "application/json",
-        ),
-        _ => (
-            WriteRecordBatchStream::<StreamWriter<Vec<u8>>>::new(
-                record_batch_stream,
-                payload.query,
-            )?
-            .map_ok(Frame::data)
-            .right_stream(),
-            "application/vnd.apache.arrow.stream",
-        ),
+                .left_stream()
+        }
+        ResultFormat::ArrowStream => WriteRecordBatchStream::<StreamWriter<Vec<u8>>>::new(
+            record_batch_stream,
+            payload.query,
+        )?
+        .map_ok(Frame::data)
+        .right_stream(),
     };
 
     let mut result_stream = result_stream.peekable();
@@ -88,11 +114,118 @@ pub async fn query(
     }
 
     Ok(Response::builder()
-        .header(http::header::CONTENT_TYPE, content_type)
-        .body(StreamBody::new(result_stream))
+        .header(http::header::CONTENT_TYPE, format.content_type())
+        .body(Body::new(StreamBody::new(result_stream)))
         .expect("content-type header is correct"))
 }
 
+/// Fetch the next page of a query
+#[openapi(
+    summary = "Fetch query page",
+    description = "Fetch the next page of a paginated query. The cursor is closed once the last page is returned.",
+    operation_id = "query_next_page",
+    tags = "storage",
+    parameters(path(
+        name = "cursor",
+        description = "Cursor returned by the previous page.",
+        schema = "std::string::String"
+    )),
+    responses(ignore_return_type = true, from_type = "StorageQueryError")
+)]
+pub async fn next_page(
+    State(state): State<Arc<QueryServiceState>>,
+    Path(cursor): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, StorageQueryError> {
+    let lease = state.cursors.lease(&cursor)?;
+    next_page_response(lease, ResultFormat::from_headers(&headers)).await
+}
+
+/// Close a query cursor
+#[openapi(
+    summary = "Close query cursor",
+    description = "Close a paginated query before reading all its pages, releasing its resources.",
+    operation_id = "close_query_cursor",
+    tags = "storage",
+    parameters(path(
+        name = "cursor",
+        description = "Cursor returned by the last page.",
+        schema = "std::string::String"
+    )),
+    responses(ignore_return_type = true, from_type = "StorageQueryError")
+)]
+pub async fn close_cursor(
+    State(state): State<Arc<QueryServiceState>>,
+    Path(cursor): Path<String>,
+) -> Result<http::StatusCode, StorageQueryError> {
+    state.cursors.close(&cursor)?;
+    Ok(http::StatusCode::NO_CONTENT)
+}
+
+async fn next_page_response(
+    mut lease: CursorLease,
+    format: ResultFormat,
+) -> Result<Response, StorageQueryError> {
+    let schema = lease.cursor().schema();
+    // dropping the lease without releasing it, e.g. on errors, closes the cursor
+    let (page, exhausted) = lease.cursor().next_page().await?;
+
+    let cursor = (!exhausted).then(|| lease.id().to_owned());
+    let body = match format {
+        ResultFormat::Json => {
+            let mut writer = JsonWriter::new(&schema)?;
+            writer.cursor = cursor.clone();
+            write_page(writer, &page)?
+        }
+        ResultFormat::ArrowStream => write_page(StreamWriter::<Vec<u8>>::new(&schema)?, &page)?,
+    };
+
+    let mut response =
+        Response::builder().header(http::header::CONTENT_TYPE, format.content_type());
+    if let Some(cursor) = cursor {
+        response = response.header(QUERY_CURSOR_HEADER, cursor);
+        lease.release();
+    }
+
+    Ok(response
+        .body(Body::from(body))
+        .expect("headers are correct"))
+}
+
+fn write_page<W: RecordBatchWriter>(
+    mut writer: W,
+    page: &[RecordBatch],
+) -> Result<Bytes, DataFusionError> {
+    let mut buffer = BytesMut::new();
+    for batch in page {
+        buffer.extend_from_slice(&writer.write(batch)?);
+    }
+    buffer.extend_from_slice(&writer.finish()?);
+    Ok(buffer.freeze())
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ResultFormat {
+    Json,
+    ArrowStream,
+}
+
+impl ResultFormat {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        match headers.get(http::header::ACCEPT) {
+            Some(v) if v == HeaderValue::from_static("application/json") => ResultFormat::Json,
+            _ => ResultFormat::ArrowStream,
+        }
+    }
+
+    fn content_type(&self) -> &'static str {
+        match self {
+            ResultFormat::Json => "application/json",
+            ResultFormat::ArrowStream => "application/vnd.apache.arrow.stream",
+        }
+    }
+}
+
 #[derive(Clone)]
 // unfortunately the json writer doesnt give a way to get a mutable reference to the underlying writer, so we need another pointer in to its buffer
 // we use a lock here to help make the writer send/sync, despite it being totally uncontended :(
@@ -123,6 +256,8 @@ impl Write for LockWriter {
 pub struct JsonWriter {
     json_writer: datafusion::arrow::json::Writer<LockWriter, JsonArray>,
     lock_writer: LockWriter,
+    /// Cursor of the next page, written under the 'cursor' key
+    cursor: Option<String>,
     finished: bool,
 }
 
@@ -134,6 +269,7 @@ impl RecordBatchWriter for JsonWriter {
         Ok(Self {
             json_writer: datafusion::arrow::json::Writer::new(lock_writer.clone()),
             lock_writer,
+            cursor: None,
             finished: false,
         })
     }
@@ -148,6 +284,9 @@ impl RecordBatchWriter for JsonWriter {
             self.finished = true;
 
             self.json_writer.finish()?;
+            if let Some(cursor) = &self.cursor {
+                write!(self.lock_writer, r#","cursor":"{cursor}""#)?;
+            }
             self.lock_writer.write_all(b"}")?;
         }
         Ok(Bytes::from(self.lock_writer.take()))
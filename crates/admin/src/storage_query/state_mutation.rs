@@ -0,0 +1,643 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! `UPDATE` and `DELETE` statements on the `state` table.
+//!
+//! The query engine itself is read-only. A mutation first selects the affected Virtual Objects.
+//! The whole state of each object is then read with a single scan, which also evaluates the
+//! statement on every entry, and applied through a [`Command::PatchState`]. The patch carries the
+//! version of the state it was computed from, so the partition processor discards it if the state
+//! changed in the meantime.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use datafusion::arrow::array::{Array, AsArray, RecordBatch, UInt64Array};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::error::DataFusionError;
+use datafusion::execution::SendableRecordBatchStream;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::sql::parser::{DFParser, Statement as DFStatement};
+use datafusion::sql::sqlparser::ast::{
+    Delete, Expr, FromTable, Statement, TableFactor, TableWithJoins,
+};
+use datafusion::sql::sqlparser::dialect::PostgreSqlDialect;
+use futures::{TryStreamExt, stream};
+use tracing::debug;
+
+use restate_storage_query_datafusion::context::QueryContext;
+use restate_types::config::Configuration;
+use restate_types::identifiers::{ServiceId, WithPartitionKey};
+use restate_types::state_mut::{ExternalStateMutation, StateMutationVersion};
+use restate_wal_protocol::{Command, Envelope};
+
+use super::QueryServiceState;
+use super::error::StorageQueryError;
+use crate::rest_api::create_envelope_header;
+
+/// An `UPDATE` or `DELETE` statement targeting the `state` table.
+#[derive(Debug, PartialEq)]
+pub struct StateMutation {
+    /// The table as written in the statement, including its alias
+    relation: String,
+    filter: String,
+    /// The new value of the selected entries, or `None` to delete them
+    new_value: Option<String>,
+}
+
+impl StateMutation {
+    /// Parses the statement, returning `None` if it is not a mutation of the `state` table.
+    pub fn parse(sql: &str) -> Result<Option<Self>, StorageQueryError> {
+        // statements that don't parse are left to the query engine to report
+        let Ok(mut statements) = DFParser::parse_sql_with_dialect(sql, &PostgreSqlDialect {})
+        else {
+            return Ok(None);
+        };
+        if statements.len() != 1 {
+            return Ok(None);
+        }
+        let Some(DFStatement::Statement(statement)) = statements.pop_front() else {
+            return Ok(None);
+        };
+
+        match *statement {
+            Statement::Delete(Delete {
+                from,
+                using,
+                selection,
+                returning,
+                ..
+            }) => {
+                let (FromTable::WithFromKeyword(tables) | FromTable::WithoutKeyword(tables)) = from;
+                let Some(relation) = state_relation(&tables) else {
+                    return Ok(None);
+                };
+                if using.is_some() || returning.is_some() {
+                    return Err(invalid("USING and RETURNING clauses are not supported"));
+                }
+
+                Ok(Some(Self {
+                    relation,
+                    filter: required_filter(selection)?,
+                    new_value: None,
+                }))
+            }
+            Statement::Update {
+                table,
+                assignments,
+                from,
+                selection,
+                returning,
+                ..
+            } => {
+                let Some(relation) = state_relation(std::slice::from_ref(&table)) else {
+                    return Ok(None);
+                };
+                if from.is_some() || returning.is_some() {
+                    return Err(invalid("FROM and RETURNING clauses are not supported"));
+                }
+                let [assignment] = assignments.as_slice() else {
+                    return Err(invalid("only the 'value' column can be updated"));
+                };
+                if !assignment.target.to_string().eq_ignore_ascii_case("value") {
+                    return Err(invalid("only the 'value' column can be updated"));
+                }
+
+                Ok(Some(Self {
+                    relation,
+                    filter: required_filter(selection)?,
+                    new_value: Some(assignment.value.to_string()),
+                }))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Applies the mutation, returning a single row with the number of patched objects and
+    /// mutated entries.
+    pub async fn apply(
+        self,
+        state: &QueryServiceState,
+    ) -> Result<SendableRecordBatchStream, StorageQueryError> {
+        if !Configuration::pinned()
+            .admin
+            .query_engine
+            .allow_state_mutations
+        {
+            return Err(StorageQueryError::StateMutationsDisabled);
+        }
+
+        let mut patched_objects = 0;
+        let mut mutated_entries = 0;
+        for service_id in self.select_objects(&state.query_context).await? {
+            let object = self.read_object(&state.query_context, &service_id).await?;
+            if object.changes.is_empty() {
+                // the state changed since the object was selected
+                continue;
+            }
+            patched_objects += 1;
+            mutated_entries += object.changes.len() as u64;
+
+            let version = StateMutationVersion::from_user_state(&object.state);
+            let mut new_state: HashMap<_, _> = object.state.into_iter().collect();
+            for (key, value) in object.changes {
+                match value {
+                    Some(value) => new_state.insert(key, value),
+                    None => new_state.remove(&key),
+                };
+            }
+
+            debug!(
+                rpc.service = %service_id.service_name,
+                %version,
+                "Patching state of '{}' via SQL",
+                service_id
+            );
+            restate_bifrost::append_to_bifrost(
+                &state.bifrost,
+                Arc::new(Envelope::new(
+                    create_envelope_header(service_id.partition_key()),
+                    Command::PatchState(ExternalStateMutation {
+                        service_id: service_id.clone(),
+                        version: Some(version.into_inner()),
+                        state: new_state,
+                    }),
+                )),
+            )
+            .await
+            .map_err(|err| {
+                StorageQueryError::StateMutationFailed(service_id.to_string(), err.to_string())
+            })?;
+        }
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("patched_objects", DataType::UInt64, false),
+            Field::new("mutated_entries", DataType::UInt64, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(UInt64Array::from(vec![patched_objects])),
+                Arc::new(UInt64Array::from(vec![mutated_entries])),
+            ],
+        )?;
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            schema,
+            stream::iter([Ok::<_, DataFusionError>(batch)]),
+        )))
+    }
+
+    /// Returns the Virtual Objects with entries selected by the statement.
+    async fn select_objects(
+        &self,
+        query_context: &QueryContext,
+    ) -> Result<Vec<ServiceId>, StorageQueryError> {
+        let query = format!(
+            "SELECT DISTINCT service_name, service_key FROM {} WHERE ({})",
+            self.relation, self.filter
+        );
+        debug!(%query, "Selecting Virtual Objects to patch");
+
+        let batches: Vec<RecordBatch> = query_context
+            .execute(&query)
+            .await
+            .map_err(invalid)?
+            .try_collect()
+            .await
+            .map_err(invalid)?;
+
+        let mut objects = Vec::new();
+        for batch in batches {
+            let service_names = cast(batch.column(0), &DataType::Utf8)?;
+            let service_keys = cast(batch.column(1), &DataType::Utf8)?;
+            for row in 0..batch.num_rows() {
+                objects.push(ServiceId::new(
+                    service_names.as_string::<i32>().value(row).to_owned(),
+                    service_keys.as_string::<i32>().value(row).to_owned(),
+                ));
+            }
+        }
+
+        Ok(objects)
+    }
+
+    /// Reads the whole state of the Virtual Object, as it is needed to compute its version, and
+    /// evaluates the statement on the same scan.
+    async fn read_object(
+        &self,
+        query_context: &QueryContext,
+        service_id: &ServiceId,
+    ) -> Result<ObjectState, StorageQueryError> {
+        let new_value = self
+            .new_value
+            .as_ref()
+            .map(|value| {
+                format!(
+                    ", CASE WHEN ({}) THEN ({value}) END AS new_value",
+                    self.filter
+                )
+            })
+            .unwrap_or_default();
+        let query = format!(
+            "SELECT key, value, ({}) AS selected{new_value} FROM {} \
+            WHERE partition_key = {} AND service_name = {} AND service_key = {}",
+            self.filter,
+            self.relation,
+            service_id.partition_key(),
+            string_literal(&service_id.service_name),
+            string_literal(&service_id.key),
+        );
+
+        let batches: Vec<RecordBatch> = query_context
+            .execute(&query)
+            .await
+            .map_err(invalid)?
+            .try_collect()
+            .await
+            .map_err(invalid)?;
+
+        let mut object = ObjectState::default();
+        for batch in batches {
+            let keys = cast(batch.column(0), &DataType::Utf8)?;
+            let values = cast(batch.column(1), &DataType::Binary)?;
+            let selected = cast(batch.column(2), &DataType::Boolean)
+                .map_err(|err| invalid(format!("WHERE clause must be a predicate: {err}")))?;
+            let new_values =
+                if self.new_value.is_some() {
+                    Some(cast(batch.column(3), &DataType::Binary).map_err(|err| {
+                        invalid(format!("new value must be binary or text: {err}"))
+                    })?)
+                } else {
+                    None
+                };
+
+            for row in 0..batch.num_rows() {
+                if keys.is_null(row) {
+                    return Err(invalid(format!(
+                        "'{service_id}' has state keys which are not valid UTF-8"
+                    )));
+                }
+                let key = Bytes::copy_from_slice(keys.as_string::<i32>().value(row).as_bytes());
+                object.state.push((
+                    key.clone(),
+                    Bytes::copy_from_slice(values.as_binary::<i32>().value(row)),
+                ));
+
+                let selected = selected.as_boolean();
+                if !selected.is_valid(row) || !selected.value(row) {
+                    continue;
+                }
+                let value = match &new_values {
+                    Some(values) if values.is_null(row) => {
+                        return Err(invalid("new value cannot be NULL, use DELETE instead"));
+                    }
+                    Some(values) => {
+                        Some(Bytes::copy_from_slice(values.as_binary::<i32>().value(row)))
+                    }
+                    None => None,
+                };
+                object.changes.insert(key, value);
+            }
+        }
+
+        Ok(object)
+    }
+}
+
+/// A snapshot of the state of a Virtual Object, with the changes of the statement.
+#[derive(Default)]
+struct ObjectState {
+    state: Vec<(Bytes, Bytes)>,
+    /// The new values of the selected entries, `None` to delete them
+    changes: HashMap<Bytes, Option<Bytes>>,
+}
+
+/// Returns the table, including its alias, if the statement targets only the `state` table.
+fn state_relation(tables: &[TableWithJoins]) -> Option<String> {
+    let [table] = tables else {
+        return None;
+    };
+    if !table.joins.is_empty() {
+        return None;
+    }
+    let TableFactor::Table { name, .. } = &table.relation else {
+        return None;
+    };
+
+    matches!(
+        name.to_string().to_lowercase().as_str(),
+        "state" | "public.state" | "restate.public.state"
+    )
+    .then(|| table.relation.to_string())
+}
+
+fn required_filter(selection: Option<Expr>) -> Result<String, StorageQueryError> {
+    selection
+        .map(|expr| expr.to_string())
+        .ok_or_else(|| invalid("a WHERE clause is required"))
+}
+
+fn string_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+fn invalid(reason: impl ToString) -> StorageQueryError {
+    StorageQueryError::InvalidStateMutation(reason.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use datafusion::arrow::array::{BinaryArray, StringArray};
+    use datafusion::arrow::datatypes::UInt64Type;
+    use datafusion::datasource::MemTable;
+    use googletest::prelude::*;
+    use test_log::test;
+
+    use restate_bifrost::Bifrost;
+    use restate_core::TestCoreEnvBuilder;
+    use restate_storage_query_datafusion::BuildError;
+    use restate_storage_query_datafusion::context::RegisterTable;
+    use restate_types::Version;
+    use restate_types::config::{QueryEngineOptions, set_current_config};
+    use restate_types::logs::{LogId, Lsn, SequenceNumber};
+    use restate_types::partition_table::PartitionTable;
+
+    use super::super::cursor::QueryCursors;
+
+    struct StateTable(Arc<MemTable>);
+
+    impl RegisterTable for StateTable {
+        async fn register(&self, ctx: &QueryContext) -> std::result::Result<(), BuildError> {
+            ctx.as_ref()
+                .register_table("state", Arc::clone(&self.0) as _)?;
+            Ok(())
+        }
+    }
+
+    /// (service name, service key, key, value)
+    type StateEntry = (&'static str, &'static str, &'static str, &'static str);
+
+    async fn query_service_state(entries: &[StateEntry]) -> QueryServiceState {
+        let env = TestCoreEnvBuilder::with_incoming_only_connector()
+            .set_partition_table(PartitionTable::with_equally_sized_partitions(
+                Version::MIN,
+                1,
+            ))
+            .build()
+            .await;
+        let bifrost = Bifrost::init_in_memory(env.metadata_writer).await;
+
+        let mut config = Configuration::default();
+        config.admin.query_engine.allow_state_mutations = true;
+        set_current_config(config);
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("partition_key", DataType::UInt64, false),
+            Field::new("service_name", DataType::Utf8, false),
+            Field::new("service_key", DataType::Utf8, false),
+            Field::new("key", DataType::Utf8, false),
+            Field::new("value", DataType::Binary, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(UInt64Array::from_iter_values(entries.iter().map(
+                    |(service_name, service_key, _, _)| {
+                        ServiceId::new(*service_name, *service_key).partition_key()
+                    },
+                ))),
+                Arc::new(StringArray::from_iter_values(
+                    entries.iter().map(|(service_name, _, _, _)| service_name),
+                )),
+                Arc::new(StringArray::from_iter_values(
+                    entries.iter().map(|(_, service_key, _, _)| service_key),
+                )),
+                Arc::new(StringArray::from_iter_values(
+                    entries.iter().map(|(_, _, key, _)| key),
+                )),
+                Arc::new(BinaryArray::from_iter_values(
+                    entries.iter().map(|(_, _, _, value)| value.as_bytes()),
+                )),
+            ],
+        )
+        .unwrap();
+
+        let options = QueryEngineOptions::default();
+        let query_context = QueryContext::create(
+            &options,
+            StateTable(Arc::new(
+                MemTable::try_new(schema, vec![vec![batch]]).unwrap(),
+            )),
+        )
+        .await
+        .unwrap();
+
+        QueryServiceState {
+            query_context,
+            cursors: Arc::new(QueryCursors::new(&options)),
+            bifrost,
+        }
+    }
+
+    async fn apply(state: &QueryServiceState, sql: &str) -> (u64, u64) {
+        let batches: Vec<RecordBatch> = StateMutation::parse(sql)
+            .unwrap()
+            .unwrap()
+            .apply(state)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        let [batch] = batches.as_slice() else {
+            panic!("expected a single result row");
+        };
+        (
+            batch.column(0).as_primitive::<UInt64Type>().value(0),
+            batch.column(1).as_primitive::<UInt64Type>().value(0),
+        )
+    }
+
+    async fn read_patches(bifrost: &Bifrost, count: u64) -> Vec<ExternalStateMutation> {
+        let mut patches = Vec::new();
+        for offset in 0..count {
+            let envelope = bifrost
+                .read(LogId::new(0), Lsn::new(Lsn::OLDEST.as_u64() + offset))
+                .await
+                .unwrap()
+                .expect("patch to be appended")
+                .try_decode::<Envelope>()
+                .unwrap()
+                .unwrap();
+            let Command::PatchState(patch) = envelope.command else {
+                panic!("expected a state patch, got {:?}", envelope.command);
+            };
+            patches.push(patch);
+        }
+        patches
+    }
+
+    fn state_of(entries: &[(&str, &str)]) -> Vec<(Bytes, Bytes)> {
+        entries
+            .iter()
+            .map(|(key, value)| {
+                (
+                    Bytes::copy_from_slice(key.as_bytes()),
+                    Bytes::copy_from_slice(value.as_bytes()),
+                )
+            })
+            .collect()
+    }
+
+    const STATE: &[StateEntry] = &[
+        ("Counter", "a", "count", "1"),
+        ("Counter", "a", "other", "x"),
+        ("Counter", "b", "count", "5"),
+    ];
+
+    #[test(restate_core::test)]
+    async fn update_patches_the_whole_state_of_selected_objects() {
+        let state = query_service_state(STATE).await;
+
+        assert_that!(
+            apply(
+                &state,
+                "UPDATE state SET value = 'updated' WHERE service_key = 'a' AND key = 'count'"
+            )
+            .await,
+            eq((1, 1))
+        );
+
+        let current_state = state_of(&[("count", "1"), ("other", "x")]);
+        assert_that!(
+            read_patches(&state.bifrost, 1).await,
+            elements_are![eq(ExternalStateMutation {
+                service_id: ServiceId::new("Counter", "a"),
+                version: Some(StateMutationVersion::from_user_state(&current_state).into_inner()),
+                state: state_of(&[("count", "updated"), ("other", "x")])
+                    .into_iter()
+                    .collect(),
+            })]
+        );
+    }
+
+    #[test(restate_core::test)]
+    async fn delete_removes_selected_entries_of_each_object() {
+        let state = query_service_state(STATE).await;
+
+        assert_that!(
+            apply(&state, "DELETE FROM state AS s WHERE s.key = 'count'").await,
+            eq((2, 2))
+        );
+
+        assert_that!(
+            read_patches(&state.bifrost, 2).await,
+            unordered_elements_are![
+                eq(ExternalStateMutation {
+                    service_id: ServiceId::new("Counter", "a"),
+                    version: Some(
+                        StateMutationVersion::from_user_state(&state_of(&[
+                            ("count", "1"),
+                            ("other", "x")
+                        ]))
+                        .into_inner()
+                    ),
+                    state: state_of(&[("other", "x")]).into_iter().collect(),
+                }),
+                eq(ExternalStateMutation {
+                    service_id: ServiceId::new("Counter", "b"),
+                    version: Some(
+                        StateMutationVersion::from_user_state(&state_of(&[("count", "5")]))
+                            .into_inner()
+                    ),
+                    state: HashMap::new(),
+                }),
+            ]
+        );
+    }
+
+    #[test(restate_core::test)]
+    async fn update_rejects_null_values() {
+        let state = query_service_state(STATE).await;
+
+        assert_that!(
+            StateMutation::parse("UPDATE state SET value = NULL WHERE key = 'count'")
+                .unwrap()
+                .unwrap()
+                .apply(&state)
+                .await
+                .map(|_| ()),
+            err(pat!(StorageQueryError::InvalidStateMutation(anything())))
+        );
+    }
+
+    #[test]
+    fn parse_delete() {
+        assert_that!(
+            StateMutation::parse(
+                "DELETE FROM state WHERE service_name = 'Counter' AND service_key = 'a' AND key = 'count'"
+            ),
+            ok(some(eq(StateMutation {
+                relation: "state".to_owned(),
+                filter: "service_name = 'Counter' AND service_key = 'a' AND key = 'count'"
+                    .to_owned(),
+                new_value: None,
+            })))
+        );
+    }
+
+    #[test]
+    fn parse_update() {
+        assert_that!(
+            StateMutation::parse("UPDATE state AS s SET value = '1' WHERE s.key = 'count'"),
+            ok(some(eq(StateMutation {
+                relation: "state AS s".to_owned(),
+                filter: "s.key = 'count'".to_owned(),
+                new_value: Some("'1'".to_owned()),
+            })))
+        );
+    }
+
+    #[test]
+    fn other_statements_are_left_to_the_query_engine() {
+        assert_that!(StateMutation::parse("SELECT * FROM state"), ok(none()));
+        assert_that!(
+            StateMutation::parse("DELETE FROM sys_invocation WHERE id = 'inv_1'"),
+            ok(none())
+        );
+        assert_that!(StateMutation::parse("not sql"), ok(none()));
+    }
+
+    #[test]
+    fn reject_unsupported_mutations() {
+        assert_that!(
+            StateMutation::parse("DELETE FROM state"),
+            err(pat!(StorageQueryError::InvalidStateMutation(anything())))
+        );
+        assert_that!(
+            StateMutation::parse("UPDATE state SET key = 'other' WHERE key = 'count'"),
+            err(pat!(StorageQueryError::InvalidStateMutation(anything())))
+        );
+        assert_that!(
+            StateMutation::parse("UPDATE state SET value = '1', key = 'a' WHERE key = 'count'"),
+            err(pat!(StorageQueryError::InvalidStateMutation(anything())))
+        );
+    }
+
+    #[test]
+    fn escape_string_literals() {
+        assert_that!(string_literal("it's"), eq("'it''s'"));
+    }
+}
@@ -87,6 +87,7 @@ fn invoke_cmd() -> Command {
             "application/json",
         )],
         execution_time: Some(MillisSinceEpoch::after(Duration::from_secs(10))),
+        deadline: None,
         completion_retention_duration: Duration::from_secs(10),
         journal_retention_duration: Default::default(),
         idempotency_key: Some(idempotency_key),
@@ -26,7 +26,7 @@ use restate_core::my_node_id;
 use restate_core::{Metadata, ShutdownError};
 use restate_types::config::Configuration;
 use restate_types::logs::metadata::SealMetadata;
-use restate_types::logs::metadata::{LogletParams, Logs, SegmentIndex};
+use restate_types::logs::metadata::{InternalKind, LogletParams, Logs, SegmentIndex};
 use restate_types::logs::metadata::{MaybeSegment, ProviderKind, Segment};
 use restate_types::logs::{KeyFilter, LogId, Lsn, SequenceNumber, TailState};
 use restate_types::storage::StorageEncode;
@@ -316,6 +316,9 @@ pub struct BifrostInner {
     watchdog: WatchdogSender,
     // Initialized after BifrostService::start completes.
     pub(crate) providers: OnceLock<EnumMap<ProviderKind, Option<Arc<dyn LogletProvider>>>>,
+    // Serves segments that were offloaded to an object store. Initialized after
+    // BifrostService::start completes if bifrost is built with object-store loglet support.
+    pub(crate) object_store_provider: OnceLock<Arc<dyn LogletProvider>>,
     shutting_down: AtomicBool,
 }
 
@@ -324,6 +327,7 @@ impl BifrostInner {
         Self {
             watchdog,
             providers: Default::default(),
+            object_store_provider: Default::default(),
             shutting_down: AtomicBool::new(false),
         }
     }
@@ -600,6 +604,20 @@ impl BifrostInner {
         response_rx.await.map_err(|_| ShutdownError)?
     }
 
+    /// Replaces the loglet of a sealed segment with an object-store loglet. The segment keeps
+    /// its base_lsn and segment index.
+    pub(crate) async fn offload_segment(
+        &self,
+        log_id: LogId,
+        segment_index: SegmentIndex,
+        params: LogletParams,
+    ) -> std::result::Result<(), Error> {
+        let (response_rx, cmd) = LogChainCommand::offload_segment(log_id, segment_index, params);
+        let _ = self.watchdog.send(WatchdogCommand::ChainCommand(cmd));
+
+        response_rx.await.map_err(|_| ShutdownError)?
+    }
+
     // --- Helper functions --- //
     /// Get the provider for a given kind. A provider must be enabled and BifrostService **must**
     /// be started before calling this.
@@ -615,6 +633,14 @@ impl BifrostInner {
             .ok_or_else(|| Error::Disabled(kind.to_string()))
     }
 
+    /// Get the provider of offloaded segments. Fails if bifrost is built without object-store
+    /// loglet support.
+    pub(crate) fn object_store_provider(&self) -> Result<&Arc<dyn LogletProvider>> {
+        self.object_store_provider
+            .get()
+            .ok_or_else(|| Error::Disabled(InternalKind::ObjectStore.to_string()))
+    }
+
     /// Checks if the log_id exists and that the provider is not disabled (can be created).
     pub(crate) fn check_log_id(&self, log_id: LogId) -> Result<(), Error> {
         let logs = Metadata::with_current(|metadata| metadata.logs_ref());
@@ -668,19 +694,19 @@ impl BifrostInner {
         log_id: LogId,
         segment: Segment<'_>,
     ) -> Result<LogletWrapper, Error> {
-        let loglet = if segment.config.kind.is_seal_marker() {
-            SealedLoglet::get()
-        } else {
-            let provider = self.provider_for(
-                segment
-                    .config
-                    .kind
-                    .try_into()
-                    .expect("non-special provider"),
-            )?;
-            provider
-                .get_loglet(log_id, segment.index(), &segment.config.params)
-                .await?
+        let loglet = match segment.config.kind {
+            InternalKind::Sealed => SealedLoglet::get(),
+            InternalKind::ObjectStore => {
+                self.object_store_provider()?
+                    .get_loglet(log_id, segment.index(), &segment.config.params)
+                    .await?
+            }
+            kind => {
+                let provider = self.provider_for(kind.try_into().expect("non-special provider"))?;
+                provider
+                    .get_loglet(log_id, segment.index(), &segment.config.params)
+                    .await?
+            }
         };
 
         Ok(LogletWrapper::new(
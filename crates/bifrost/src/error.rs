@@ -12,9 +12,10 @@ use std::sync::Arc;
 
 use restate_core::{ShutdownError, SyncError};
 use restate_metadata_store::ReadWriteError;
+use restate_types::SemanticRestateVersion;
 use restate_types::errors::MaybeRetryableError;
 use restate_types::logs::builder::BuilderError;
-use restate_types::logs::metadata::SegmentIndex;
+use restate_types::logs::metadata::{InternalKind, SegmentIndex};
 use restate_types::logs::{LogId, Lsn};
 
 use crate::loglet::OperationError;
@@ -65,6 +66,10 @@ pub enum AdminError {
     LogAlreadyExists(LogId),
     #[error("segment conflicts with existing segment with base_lsn={0}")]
     SegmentConflict(Lsn),
+    #[error("segment {0} was not found in the log chain")]
+    UnknownSegment(SegmentIndex),
+    #[error("segment {0} is not sealed")]
+    SegmentNotSealed(SegmentIndex),
     #[error("segment index found in metadata does not match expected {expected}!={found}")]
     SegmentMismatch {
         expected: SegmentIndex,
@@ -72,6 +77,11 @@ pub enum AdminError {
     },
     #[error("loglet params could not be deserialized: {0}")]
     ParamsSerde(#[from] Arc<serde_json::Error>),
+    #[error("segments of kind {kind} require all the nodes to run at least version {required}")]
+    UnsupportedByCluster {
+        kind: InternalKind,
+        required: SemanticRestateVersion,
+    },
 }
 
 impl From<OperationError> for Error {
@@ -92,6 +102,8 @@ impl From<BuilderError> for AdminError {
             }
             BuilderError::ParamsSerde(error) => AdminError::ParamsSerde(Arc::new(error)),
             BuilderError::SegmentConflict(lsn) => AdminError::SegmentConflict(lsn),
+            BuilderError::UnknownSegment(index) => AdminError::UnknownSegment(index),
+            BuilderError::SegmentNotSealed(index) => AdminError::SegmentNotSealed(index),
         }
     }
 }
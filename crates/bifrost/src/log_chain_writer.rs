@@ -13,11 +13,13 @@ use std::sync::Arc;
 use tokio::sync::{mpsc, oneshot};
 use tracing::{debug, trace};
 
-use restate_core::{MetadataWriter, TaskCenter, TaskHandle, TaskKind, cancellation_token};
+use restate_core::{
+    Metadata, MetadataWriter, TaskCenter, TaskHandle, TaskKind, cancellation_token,
+};
 use restate_metadata_store::ReadModifyWriteError;
 use restate_types::logs::builder::{BuilderError, LogsBuilder};
 use restate_types::logs::metadata::{
-    Chain, LogletParams, Logs, ProviderKind, SealMetadata, SegmentIndex,
+    Chain, InternalKind, LogletParams, Logs, ProviderKind, SealMetadata, SegmentIndex,
 };
 use restate_types::logs::{LogId, Lsn, SequenceNumber};
 
@@ -123,6 +125,26 @@ impl LogChainCommand {
         (rx, cmd)
     }
 
+    pub fn offload_segment(
+        log_id: LogId,
+        segment_index: SegmentIndex,
+        params: LogletParams,
+    ) -> (oneshot::Receiver<Result<(), Error>>, Self) {
+        let (tx, rx) = oneshot::channel();
+        let cmd = Self {
+            log_id,
+            op: ChainOp::OffloadSegment {
+                segment_index,
+                params,
+                response: OpOutput {
+                    tx,
+                    staged_result: None,
+                },
+            },
+        };
+        (rx, cmd)
+    }
+
     pub fn trim_prefix(log_id: LogId, trim_point: Lsn) -> Self {
         Self {
             log_id,
@@ -135,6 +157,7 @@ impl LogChainCommand {
             ChainOp::Extend { response, .. } => response.fail(err),
             ChainOp::SealChain { response, .. } => response.fail(err),
             ChainOp::AddLog { response, .. } => response.fail(err),
+            ChainOp::OffloadSegment { response, .. } => response.fail(err),
             ChainOp::TrimPrefix { .. } => { /* do nothing */ }
         }
     }
@@ -144,6 +167,7 @@ impl LogChainCommand {
             ChainOp::Extend { response, .. } => response.complete(),
             ChainOp::SealChain { response, .. } => response.complete(),
             ChainOp::AddLog { response, .. } => response.complete(),
+            ChainOp::OffloadSegment { response, .. } => response.complete(),
             ChainOp::TrimPrefix { trim_point } => {
                 debug!(
                     "Log {} chain has been trimmed to trim-point {}",
@@ -179,6 +203,13 @@ enum ChainOp {
         #[debug(skip)]
         response: OpOutput<()>,
     },
+    OffloadSegment {
+        segment_index: SegmentIndex,
+        #[debug(skip)]
+        params: LogletParams,
+        #[debug(skip)]
+        response: OpOutput<()>,
+    },
     TrimPrefix {
         trim_point: Lsn,
     },
@@ -283,6 +314,18 @@ impl LogChainWriter {
                                     metadata,
                                 ));
                             }
+                            ChainOp::OffloadSegment {
+                                segment_index,
+                                ref params,
+                                ref mut response,
+                            } => {
+                                response.stage_output(Self::offload_segment(
+                                    &mut builder,
+                                    cmd.log_id,
+                                    segment_index,
+                                    params,
+                                ));
+                            }
                             ChainOp::TrimPrefix { trim_point } => {
                                 // ignores the error if the log is unknown.
                                 let _ = Self::trim_prefix(&mut builder, cmd.log_id, trim_point);
@@ -375,6 +418,32 @@ impl LogChainWriter {
         Ok(lsn)
     }
 
+    fn offload_segment(
+        builder: &mut LogsBuilder,
+        log_id: LogId,
+        segment_index: SegmentIndex,
+        params: &LogletParams,
+    ) -> Result<(), Error> {
+        let min_binary_version =
+            Metadata::with_current(|metadata| metadata.nodes_config_ref().min_binary_version());
+        if !InternalKind::ObjectStore.is_supported_by(min_binary_version.as_ref()) {
+            Err(AdminError::UnsupportedByCluster {
+                kind: InternalKind::ObjectStore,
+                required: InternalKind::ObjectStore
+                    .min_restate_version()
+                    .expect("object-store segments are version gated"),
+            })?;
+        }
+
+        let mut chain_builder = builder.chain(log_id).ok_or(Error::UnknownLogId(log_id))?;
+
+        chain_builder
+            .offload_segment(segment_index, params.clone())
+            .map_err(AdminError::from)?;
+
+        Ok(())
+    }
+
     fn trim_prefix(builder: &mut LogsBuilder, log_id: LogId, trim_point: Lsn) -> Result<(), Error> {
         let mut chain_builder = builder.chain(log_id).ok_or(Error::UnknownLogId(log_id))?;
 
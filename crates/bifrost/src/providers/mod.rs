@@ -13,5 +13,7 @@ pub mod local_loglet;
 
 #[cfg(any(test, feature = "memory-loglet"))]
 pub mod memory_loglet;
+#[cfg(feature = "object-store-loglet")]
+pub(crate) mod object_store_loglet;
 #[cfg(feature = "replicated-loglet")]
 pub mod replicated_loglet;
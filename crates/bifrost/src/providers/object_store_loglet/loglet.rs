@@ -0,0 +1,387 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::borrow::Cow;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use futures::stream::{self, BoxStream};
+use tracing::{debug, warn};
+
+use restate_types::logs::{KeyFilter, LogletOffset, Record, SequenceNumber, TailState};
+
+use super::read_stream::ObjectStoreReadStream;
+use super::store::ObjectStoreLogletStore;
+use super::{ObjectStoreLogletError, ObjectStoreLogletParams};
+use crate::loglet::{
+    FindTailOptions, Loglet, LogletCommit, OperationError, SendableLogletReadStream,
+};
+
+/// A sealed, read-only loglet that serves records from an object store.
+#[derive(derive_more::Debug)]
+pub(super) struct ObjectStoreLoglet {
+    params: ObjectStoreLogletParams,
+    #[debug(skip)]
+    store: ObjectStoreLogletStore,
+    // Trims that happened after the loglet was offloaded. The effective trim point is the max
+    // of this and the trim point in params. Trims are persisted in the object store, this caches
+    // the highest trim point observed by this node.
+    trim_point_offset: AtomicU32,
+    // Serializes trims so that a lower trim point never overwrites a higher persisted one
+    #[debug(skip)]
+    trim_lock: tokio::sync::Mutex<()>,
+}
+
+impl ObjectStoreLoglet {
+    fn new(params: ObjectStoreLogletParams, store: ObjectStoreLogletStore) -> Self {
+        let trim_point_offset = AtomicU32::new(*params.trim_point);
+        Self {
+            params,
+            store,
+            trim_point_offset,
+            trim_lock: Default::default(),
+        }
+    }
+
+    /// Creates the loglet and loads the trim point persisted in the object store.
+    pub async fn load(
+        params: ObjectStoreLogletParams,
+        store: ObjectStoreLogletStore,
+    ) -> Result<Self, ObjectStoreLogletError> {
+        let loglet = Self::new(params, store);
+        loglet.refresh_trim_point().await?;
+        Ok(loglet)
+    }
+
+    pub fn params(&self) -> &ObjectStoreLogletParams {
+        &self.params
+    }
+
+    pub fn store(&self) -> &ObjectStoreLogletStore {
+        &self.store
+    }
+
+    pub fn trim_point(&self) -> LogletOffset {
+        LogletOffset::new(self.trim_point_offset.load(Ordering::Relaxed))
+    }
+
+    /// Picks up trims persisted by other nodes and returns the effective trim point.
+    pub async fn refresh_trim_point(&self) -> Result<LogletOffset, ObjectStoreLogletError> {
+        if let Some(persisted) = self.store.get_trim_point(self.params.loglet_id).await? {
+            self.trim_point_offset
+                .fetch_max(*persisted, Ordering::Relaxed);
+        }
+        Ok(self.trim_point())
+    }
+}
+
+#[async_trait]
+impl Loglet for ObjectStoreLoglet {
+    fn debug_str(&self) -> Cow<'static, str> {
+        Cow::from(format!("object-store/{}", self.params.loglet_id))
+    }
+
+    async fn create_read_stream(
+        self: Arc<Self>,
+        filter: KeyFilter,
+        from: LogletOffset,
+        to: Option<LogletOffset>,
+    ) -> Result<SendableLogletReadStream, OperationError> {
+        Ok(Box::pin(ObjectStoreReadStream::new(self, filter, from, to)))
+    }
+
+    fn watch_tail(&self) -> BoxStream<'static, TailState<LogletOffset>> {
+        let tail = self.params.tail;
+        Box::pin(
+            stream::once(async move { TailState::Sealed(tail) })
+                // The stream must continue to be pending. If the stream is terminated
+                // bifrost will consider the system to be shutting down.
+                .chain(stream::pending()),
+        )
+    }
+
+    async fn enqueue_batch(
+        &self,
+        _payloads: Arc<[Record]>,
+    ) -> Result<LogletCommit, OperationError> {
+        Ok(LogletCommit::sealed())
+    }
+
+    async fn find_tail(
+        &self,
+        _: FindTailOptions,
+    ) -> Result<TailState<LogletOffset>, OperationError> {
+        Ok(TailState::Sealed(self.params.tail))
+    }
+
+    async fn get_trim_point(&self) -> Result<Option<LogletOffset>, OperationError> {
+        let trim_point = self.refresh_trim_point().await?;
+        if trim_point == LogletOffset::INVALID {
+            Ok(None)
+        } else {
+            Ok(Some(trim_point))
+        }
+    }
+
+    async fn trim(&self, new_trim_point: LogletOffset) -> Result<(), OperationError> {
+        let new_trim_point = new_trim_point.min(self.params.tail.prev_unchecked());
+        let _trim_guard = self.trim_lock.lock().await;
+        let previous_trim_point = self.refresh_trim_point().await?;
+        if previous_trim_point >= new_trim_point {
+            return Ok(());
+        }
+
+        // The trim point must be persisted before any object is deleted, readers only treat
+        // missing objects at or before the trim point as trimmed.
+        self.store
+            .put_trim_point(self.params.loglet_id, new_trim_point)
+            .await?;
+        self.trim_point_offset
+            .fetch_max(*new_trim_point, Ordering::Relaxed);
+
+        // Delete the objects that only hold trimmed records. Deletion is best-effort, leftover
+        // objects are never read again.
+        let mut object_start = self.params.object_start(previous_trim_point.next());
+        while object_start <= new_trim_point
+            && self.params.object_end(object_start) <= new_trim_point
+        {
+            if let Err(err) = self.store.delete(self.params.loglet_id, object_start).await {
+                warn!(
+                    loglet_id = %self.params.loglet_id,
+                    %object_start,
+                    %err,
+                    "Failed to delete trimmed object of offloaded loglet"
+                );
+            }
+            object_start = object_start + self.params.records_per_object;
+        }
+        debug!(
+            loglet_id = %self.params.loglet_id,
+            "Trimmed offloaded loglet to {}", new_trim_point
+        );
+
+        Ok(())
+    }
+
+    async fn seal(&self) -> Result<(), OperationError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::TryStreamExt;
+    use googletest::prelude::*;
+    use object_store::memory::InMemory;
+
+    use restate_types::logs::LogletId;
+    use restate_types::time::MillisSinceEpoch;
+
+    use crate::LogEntry;
+    use crate::loglet::LogletReadStream;
+
+    async fn offloaded_loglet(trim_point: u32, tail: u32) -> Arc<ObjectStoreLoglet> {
+        let store = ObjectStoreLogletStore::new(Arc::new(InMemory::new()));
+        let params = ObjectStoreLogletParams {
+            loglet_id: LogletId::new_unchecked(7),
+            trim_point: LogletOffset::new(trim_point),
+            tail: LogletOffset::new(tail),
+            records_per_object: 10,
+            offloaded_at: MillisSinceEpoch::now(),
+            destination: None,
+            source: None,
+        };
+
+        let mut batch_start = trim_point + 1;
+        while batch_start < tail {
+            let object_start = params.object_start(LogletOffset::new(batch_start));
+            let object_end = params.object_end(object_start);
+            let records = (batch_start..=*object_end)
+                .map(|offset| Record::from(format!("record-{offset}")))
+                .collect();
+            store
+                .put(
+                    params.loglet_id,
+                    object_start,
+                    LogletOffset::new(batch_start),
+                    records,
+                )
+                .await
+                .unwrap();
+            batch_start = *object_end + 1;
+        }
+
+        Arc::new(ObjectStoreLoglet::new(params, store))
+    }
+
+    #[restate_core::test]
+    async fn read_offloaded_records() -> googletest::Result<()> {
+        let loglet = offloaded_loglet(3, 26).await;
+        assert_that!(
+            loglet.find_tail(FindTailOptions::default()).await?,
+            eq(TailState::Sealed(LogletOffset::new(26)))
+        );
+
+        let entries: Vec<LogEntry<LogletOffset>> = Arc::clone(&loglet)
+            .create_read_stream(
+                KeyFilter::Any,
+                LogletOffset::OLDEST,
+                Some(LogletOffset::new(25)),
+            )
+            .await?
+            .try_collect()
+            .await?;
+
+        // records before the trim point are reported as a trim gap
+        assert_that!(entries.len(), eq(23));
+        assert_that!(entries[0].is_trim_gap(), eq(true));
+        assert_that!(
+            entries[0].trim_gap_to_sequence_number(),
+            some(eq(LogletOffset::new(3)))
+        );
+        for (entry, offset) in entries[1..].iter().zip(4..) {
+            assert_that!(entry.sequence_number(), eq(LogletOffset::new(offset)));
+            assert_that!(
+                entry.clone().decode_unchecked::<String>(),
+                eq(format!("record-{offset}"))
+            );
+        }
+
+        // appends are rejected
+        let commit = loglet
+            .enqueue_batch(vec![Record::from("foo")].into())
+            .await?;
+        assert_that!(commit.await, err(anything()));
+
+        Ok(())
+    }
+
+    #[restate_core::test]
+    async fn trim_offloaded_records() -> googletest::Result<()> {
+        let loglet = offloaded_loglet(0, 26).await;
+
+        loglet.trim(LogletOffset::new(15)).await?;
+        assert_that!(
+            loglet.get_trim_point().await?,
+            some(eq(LogletOffset::new(15)))
+        );
+        // the first object only holds trimmed records and is deleted
+        assert_that!(
+            loglet
+                .store()
+                .get(loglet.params().loglet_id, LogletOffset::new(1))
+                .await?,
+            none()
+        );
+        assert_that!(
+            loglet
+                .store()
+                .get(loglet.params().loglet_id, LogletOffset::new(11))
+                .await?,
+            some(anything())
+        );
+
+        let entries: Vec<LogEntry<LogletOffset>> = Arc::clone(&loglet)
+            .create_read_stream(
+                KeyFilter::Any,
+                LogletOffset::OLDEST,
+                Some(LogletOffset::new(25)),
+            )
+            .await?
+            .try_collect()
+            .await?;
+        assert_that!(entries.len(), eq(11));
+        assert_that!(
+            entries[0].trim_gap_to_sequence_number(),
+            some(eq(LogletOffset::new(15)))
+        );
+        assert_that!(entries[1].sequence_number(), eq(LogletOffset::new(16)));
+
+        // trimming is capped at the last record
+        loglet.trim(LogletOffset::MAX).await?;
+        assert_that!(
+            loglet.get_trim_point().await?,
+            some(eq(LogletOffset::new(25)))
+        );
+
+        Ok(())
+    }
+
+    #[restate_core::test]
+    async fn trim_point_is_persisted() -> googletest::Result<()> {
+        let loglet = offloaded_loglet(0, 26).await;
+        // a loglet instance of another node that was created before the trim
+        let stale =
+            ObjectStoreLoglet::load(loglet.params().clone(), loglet.store().clone()).await?;
+
+        loglet.trim(LogletOffset::new(15)).await?;
+
+        // the trim point survives restarts
+        let reloaded =
+            ObjectStoreLoglet::load(loglet.params().clone(), loglet.store().clone()).await?;
+        assert_that!(reloaded.trim_point(), eq(LogletOffset::new(15)));
+
+        // readers that missed the trim pick it up once they find a deleted object
+        assert_that!(stale.trim_point(), eq(LogletOffset::INVALID));
+        let entries: Vec<LogEntry<LogletOffset>> = Arc::new(stale)
+            .create_read_stream(
+                KeyFilter::Any,
+                LogletOffset::OLDEST,
+                Some(LogletOffset::new(25)),
+            )
+            .await?
+            .try_collect()
+            .await?;
+        assert_that!(entries.len(), eq(11));
+        assert_that!(
+            entries[0].trim_gap_to_sequence_number(),
+            some(eq(LogletOffset::new(15)))
+        );
+        assert_that!(entries[1].sequence_number(), eq(LogletOffset::new(16)));
+
+        Ok(())
+    }
+
+    #[restate_core::test]
+    async fn missing_object_after_trim_point_fails_read() -> googletest::Result<()> {
+        let loglet = offloaded_loglet(0, 26).await;
+        loglet.trim(LogletOffset::new(5)).await?;
+        loglet
+            .store()
+            .delete(loglet.params().loglet_id, LogletOffset::new(11))
+            .await?;
+
+        let mut read_stream = Arc::clone(&loglet)
+            .create_read_stream(
+                KeyFilter::Any,
+                LogletOffset::OLDEST,
+                Some(LogletOffset::new(25)),
+            )
+            .await?;
+        let entry = read_stream.try_next().await?.expect("trim gap");
+        assert_that!(
+            entry.trim_gap_to_sequence_number(),
+            some(eq(LogletOffset::new(5)))
+        );
+        for offset in 6..=10 {
+            let entry = read_stream.try_next().await?.expect("record");
+            assert_that!(entry.sequence_number(), eq(LogletOffset::new(offset)));
+        }
+        // records after the trim point are never reported as trimmed
+        assert_that!(read_stream.try_next().await, err(anything()));
+        assert_that!(read_stream.is_terminated(), eq(true));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,220 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! A read-only loglet that serves sealed segments which were offloaded to an object store.
+//!
+//! Sealed segments of replicated loglets are uploaded by the [`Offloader`] in objects of a fixed
+//! number of records each. Once all records are uploaded, the segment in the log chain is
+//! replaced by an [`InternalKind::ObjectStore`] segment, readers that observe the updated chain
+//! transparently continue reading from the object store. The records are released from the
+//! log-servers after a grace period.
+//!
+//! Object layout: `[<prefix>/]<loglet_id>/<first_offset>` where `first_offset` is zero-padded
+//! for correct lexicographical sorting. Trims after offloading are recorded in
+//! `[<prefix>/]<loglet_id>/trim-point`.
+//!
+//! Offloaded segments record their destination in the loglet params, so every node can read
+//! them regardless of its own destination configuration.
+//!
+//! [`InternalKind::ObjectStore`]: restate_types::logs::metadata::InternalKind::ObjectStore
+
+mod loglet;
+mod offloader;
+mod provider;
+mod read_stream;
+mod store;
+
+pub(crate) use self::offloader::Offloader;
+pub(crate) use self::provider::ObjectStoreLogletProvider;
+pub(crate) use self::store::ObjectStoreLogletStore;
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use restate_types::errors::MaybeRetryableError;
+use restate_types::logs::LogletId;
+use restate_types::logs::LogletOffset;
+use restate_types::logs::metadata::{LogletParams, SegmentIndex};
+use restate_types::logs::{LogId, SequenceNumber};
+use restate_types::time::MillisSinceEpoch;
+
+use crate::loglet::OperationError;
+
+/// Loglet params of an offloaded segment, stored as json in the log chain.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ObjectStoreLogletParams {
+    /// Identifies the objects of this loglet in the object store
+    pub loglet_id: LogletId,
+    /// Records at or before this offset were trimmed before offloading
+    pub trim_point: LogletOffset,
+    /// The sealed tail of the loglet, the last offloaded record is at `tail.prev()`
+    pub tail: LogletOffset,
+    /// Number of records stored in each object
+    pub records_per_object: u32,
+    pub offloaded_at: MillisSinceEpoch,
+    /// The destination URL the records were offloaded to. Segments offloaded by older versions
+    /// don't record it and are read from the locally configured destination.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination: Option<String>,
+    /// Params of the loglet the records were offloaded from. Cleared once the source loglet
+    /// has been trimmed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+impl ObjectStoreLogletParams {
+    pub fn deserialize_from(slice: &[u8]) -> Result<Self, serde_json::Error> {
+        serde_json::from_slice(slice)
+    }
+
+    pub fn serialize(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// The offset of the first record of the object that holds `offset`
+    pub fn object_start(&self, offset: LogletOffset) -> LogletOffset {
+        debug_assert!(offset >= LogletOffset::OLDEST);
+        let index = (*offset - 1) / self.records_per_object;
+        LogletOffset::new(index * self.records_per_object + 1)
+    }
+
+    /// The offset of the last record of the object that starts at `object_start`
+    pub fn object_end(&self, object_start: LogletOffset) -> LogletOffset {
+        LogletOffset::new(
+            (*object_start)
+                .saturating_add(self.records_per_object - 1)
+                .min(*self.tail.prev_unchecked()),
+        )
+    }
+
+    /// Returns true if no records are available in this loglet
+    pub fn is_empty(&self) -> bool {
+        self.trim_point.next() >= self.tail
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum ObjectStoreLogletError {
+    #[error("cannot parse loglet configuration for log_id={0} at segment_index={1}: {2}")]
+    LogletParamsParsingError(LogId, SegmentIndex, serde_json::Error),
+    #[error("object store error: {0}")]
+    ObjectStore(#[from] object_store::Error),
+    #[error("failed decoding object {0}: {1}")]
+    Decode(String, restate_types::storage::StorageDecodeError),
+    #[error("object {path} holds records starting at {found} but expected {expected} or later")]
+    UnexpectedObject {
+        path: String,
+        expected: LogletOffset,
+        found: LogletOffset,
+    },
+    #[error("object {path} is missing but holds records after the trim point {trim_point}")]
+    MissingObject {
+        path: String,
+        trim_point: LogletOffset,
+    },
+    #[error(
+        "offloaded segment of log_id={0} at segment_index={1} has no destination and no \
+        object-store destination is configured on this node"
+    )]
+    UnknownDestination(LogId, SegmentIndex),
+    #[error("cannot access object-store destination {0}: {1}")]
+    Destination(String, anyhow::Error),
+    #[error("object-store loglets are read-only and cannot be used for new segments")]
+    ReadOnly,
+}
+
+impl MaybeRetryableError for ObjectStoreLogletError {
+    fn retryable(&self) -> bool {
+        match self {
+            Self::LogletParamsParsingError(..) => false,
+            Self::ObjectStore(_) => true,
+            Self::Decode(..) => false,
+            Self::UnexpectedObject { .. } => false,
+            Self::MissingObject { .. } => false,
+            Self::UnknownDestination(..) => false,
+            Self::Destination(..) => false,
+            Self::ReadOnly => false,
+        }
+    }
+}
+
+impl From<ObjectStoreLogletError> for OperationError {
+    fn from(value: ObjectStoreLogletError) -> Self {
+        OperationError::Other(Arc::new(value))
+    }
+}
+
+impl From<ObjectStoreLogletError> for crate::Error {
+    fn from(value: ObjectStoreLogletError) -> Self {
+        crate::Error::LogletError(Arc::new(value))
+    }
+}
+
+pub(crate) fn parse_params(
+    log_id: LogId,
+    segment_index: SegmentIndex,
+    params: &LogletParams,
+) -> Result<ObjectStoreLogletParams, ObjectStoreLogletError> {
+    ObjectStoreLogletParams::deserialize_from(params.as_bytes())
+        .map_err(|e| ObjectStoreLogletError::LogletParamsParsingError(log_id, segment_index, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn object_boundaries() {
+        let params = ObjectStoreLogletParams {
+            loglet_id: LogletId::new_unchecked(1),
+            trim_point: LogletOffset::INVALID,
+            tail: LogletOffset::new(26),
+            records_per_object: 10,
+            offloaded_at: MillisSinceEpoch::now(),
+            destination: None,
+            source: None,
+        };
+
+        assert_eq!(
+            LogletOffset::new(1),
+            params.object_start(LogletOffset::OLDEST)
+        );
+        assert_eq!(
+            LogletOffset::new(1),
+            params.object_start(LogletOffset::new(10))
+        );
+        assert_eq!(
+            LogletOffset::new(11),
+            params.object_start(LogletOffset::new(11))
+        );
+        assert_eq!(
+            LogletOffset::new(21),
+            params.object_start(LogletOffset::new(25))
+        );
+
+        assert_eq!(
+            LogletOffset::new(10),
+            params.object_end(LogletOffset::new(1))
+        );
+        // the last object is cut at the tail
+        assert_eq!(
+            LogletOffset::new(25),
+            params.object_end(LogletOffset::new(21))
+        );
+        assert!(!params.is_empty());
+
+        let empty = ObjectStoreLogletParams {
+            trim_point: LogletOffset::new(25),
+            ..params
+        };
+        assert!(empty.is_empty());
+    }
+}
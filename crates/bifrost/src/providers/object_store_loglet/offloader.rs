@@ -0,0 +1,295 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Context;
+use futures::StreamExt;
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, info, instrument, warn};
+
+use restate_core::{Metadata, TaskCenter, TaskKind, cancellation_watcher};
+use restate_types::config::{Configuration, ObjectStoreLogletOptions};
+use restate_types::logs::metadata::{InternalKind, LogletParams, ProviderKind, SegmentIndex};
+use restate_types::logs::{KeyFilter, LogId, LogletOffset, SequenceNumber};
+use restate_types::replicated_loglet::ReplicatedLogletParams;
+use restate_types::time::MillisSinceEpoch;
+
+use super::store::ObjectStoreLogletStore;
+use super::{ObjectStoreLogletParams, parse_params};
+use crate::bifrost::BifrostInner;
+use crate::types::LsnExt;
+
+/// A segment that can be moved forward in its offloading lifecycle
+enum Candidate {
+    /// A sealed replicated segment whose records need to be uploaded
+    Upload {
+        log_id: LogId,
+        segment_index: SegmentIndex,
+        tail: LogletOffset,
+        params: LogletParams,
+    },
+    /// An offloaded segment whose source loglet can be released after the grace period
+    Release {
+        log_id: LogId,
+        segment_index: SegmentIndex,
+        params: ObjectStoreLogletParams,
+    },
+}
+
+/// Periodically uploads the records of sealed replicated segments to the object store and
+/// replaces them in the log chain with object-store segments.
+///
+/// Multiple offloaders may run concurrently in a cluster (one per admin node). This is safe
+/// because the uploaded objects are deterministic and chain updates are idempotent.
+pub(crate) struct Offloader {
+    inner: Arc<BifrostInner>,
+    store: ObjectStoreLogletStore,
+}
+
+impl Offloader {
+    pub fn start(inner: Arc<BifrostInner>, store: ObjectStoreLogletStore) -> anyhow::Result<()> {
+        let offloader = Self { inner, store };
+        TaskCenter::spawn(
+            TaskKind::BifrostBackgroundLowPriority,
+            "bifrost-object-store-offloader",
+            offloader.run(),
+        )?;
+        Ok(())
+    }
+
+    async fn run(self) -> anyhow::Result<()> {
+        let mut cancel = std::pin::pin!(cancellation_watcher());
+        let check_interval: Duration = Configuration::pinned()
+            .bifrost
+            .object_store_loglet
+            .offload_check_interval
+            .into();
+        let mut interval = tokio::time::interval(check_interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        debug!("Object-store offloader started");
+
+        loop {
+            tokio::select! {
+                _ = &mut cancel => {
+                    debug!("Object-store offloader stopped");
+                    return Ok(());
+                }
+                _ = interval.tick() => {
+                    let options = Configuration::pinned().bifrost.object_store_loglet.clone();
+                    for candidate in self.find_candidates(&options) {
+                        if let Err(err) = self.process(&options, candidate).await {
+                            warn!(%err, "Failed to offload segment, will retry in the next round");
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn find_candidates(&self, options: &ObjectStoreLogletOptions) -> Vec<Candidate> {
+        let (logs, min_binary_version) = Metadata::with_current(|metadata| {
+            (
+                metadata.logs_ref(),
+                metadata.nodes_config_ref().min_binary_version(),
+            )
+        });
+        // Nodes running an older version can't decode offloaded segments
+        let can_offload = InternalKind::ObjectStore.is_supported_by(min_binary_version.as_ref());
+        if !can_offload {
+            debug!(
+                "Not offloading sealed segments until all the nodes run at least version {}",
+                InternalKind::ObjectStore
+                    .min_restate_version()
+                    .expect("object-store segments are version gated")
+            );
+        }
+        let mut candidates = Vec::new();
+        for (log_id, chain) in logs.iter() {
+            for segment in chain.iter() {
+                // only sealed segments that are not the tail of the chain
+                let Some(tail_lsn) = segment.tail_lsn else {
+                    continue;
+                };
+                match segment.config.kind {
+                    InternalKind::Replicated if can_offload => candidates.push(Candidate::Upload {
+                        log_id: *log_id,
+                        segment_index: segment.index(),
+                        tail: tail_lsn.into_offset(segment.base_lsn),
+                        params: segment.config.params.clone(),
+                    }),
+                    InternalKind::ObjectStore => {
+                        match parse_params(*log_id, segment.index(), &segment.config.params) {
+                            Ok(params)
+                                if params.source.is_some()
+                                    && params.offloaded_at.elapsed()
+                                        >= *options.release_grace_period =>
+                            {
+                                candidates.push(Candidate::Release {
+                                    log_id: *log_id,
+                                    segment_index: segment.index(),
+                                    params,
+                                })
+                            }
+                            Ok(_) => {}
+                            Err(err) => warn!(%err, "Ignoring offloaded segment"),
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        candidates
+    }
+
+    async fn process(
+        &self,
+        options: &ObjectStoreLogletOptions,
+        candidate: Candidate,
+    ) -> anyhow::Result<()> {
+        match candidate {
+            Candidate::Upload {
+                log_id,
+                segment_index,
+                tail,
+                params,
+            } => {
+                self.upload(options, log_id, segment_index, tail, params)
+                    .await
+            }
+            Candidate::Release {
+                log_id,
+                segment_index,
+                params,
+            } => self.release(log_id, segment_index, params).await,
+        }
+    }
+
+    #[instrument(level = "debug", skip_all, fields(%log_id, %segment_index))]
+    async fn upload(
+        &self,
+        options: &ObjectStoreLogletOptions,
+        log_id: LogId,
+        segment_index: SegmentIndex,
+        tail: LogletOffset,
+        source: LogletParams,
+    ) -> anyhow::Result<()> {
+        let loglet_id = ReplicatedLogletParams::deserialize_from(source.as_bytes())?.loglet_id;
+        let loglet = self
+            .inner
+            .provider_for(ProviderKind::Replicated)?
+            .get_loglet(log_id, segment_index, &source)
+            .await?;
+        let trim_point = loglet
+            .get_trim_point()
+            .await?
+            .unwrap_or(LogletOffset::INVALID)
+            .min(tail.prev_unchecked());
+
+        let params = ObjectStoreLogletParams {
+            loglet_id,
+            trim_point,
+            tail,
+            records_per_object: options.records_per_object.get(),
+            offloaded_at: MillisSinceEpoch::now(),
+            // Recorded in the log chain so that nodes with a different or no destination
+            // configured can read the segment.
+            destination: Some(self.store.destination().to_owned()),
+            source: Some(source.to_string()),
+        };
+
+        if !params.is_empty() {
+            let mut read_stream = loglet
+                .create_read_stream(KeyFilter::Any, trim_point.next(), Some(tail.prev()))
+                .await?;
+            let mut batch_start = trim_point.next();
+            let mut records = Vec::with_capacity(params.records_per_object as usize);
+
+            while let Some(entry) = read_stream.next().await {
+                let entry = entry?;
+                let offset = entry.sequence_number();
+                let Some(record) = entry.into_record() else {
+                    // The source got trimmed while we were reading, start over next round
+                    anyhow::bail!("encountered a gap at offset {offset} while uploading records");
+                };
+                records.push(record);
+
+                let object_start = params.object_start(offset);
+                if offset == params.object_end(object_start) {
+                    self.store
+                        .put(
+                            loglet_id,
+                            params.object_start(batch_start),
+                            batch_start,
+                            std::mem::take(&mut records),
+                        )
+                        .await?;
+                    batch_start = offset.next();
+                }
+                if offset >= tail.prev() {
+                    break;
+                }
+            }
+            anyhow::ensure!(
+                batch_start == tail,
+                "read stream ended at {batch_start} before reaching the tail {tail}"
+            );
+        }
+
+        let offloaded_params = LogletParams::from(params.serialize()?);
+        self.inner
+            .offload_segment(log_id, segment_index, offloaded_params)
+            .await
+            .context("failed updating the log chain")?;
+        info!(
+            %loglet_id,
+            "Offloaded {} records of sealed segment to object store",
+            *tail - *trim_point - 1
+        );
+        Ok(())
+    }
+
+    #[instrument(level = "debug", skip_all, fields(%log_id, %segment_index))]
+    async fn release(
+        &self,
+        log_id: LogId,
+        segment_index: SegmentIndex,
+        params: ObjectStoreLogletParams,
+    ) -> anyhow::Result<()> {
+        let source = LogletParams::from(params.source.clone().expect("source is set"));
+        if !params.is_empty() {
+            let loglet = self
+                .inner
+                .provider_for(ProviderKind::Replicated)?
+                .get_loglet(log_id, segment_index, &source)
+                .await?;
+            loglet.trim(params.tail.prev()).await?;
+        }
+
+        let released_params = ObjectStoreLogletParams {
+            source: None,
+            ..params
+        };
+        self.inner
+            .offload_segment(
+                log_id,
+                segment_index,
+                LogletParams::from(released_params.serialize()?),
+            )
+            .await
+            .context("failed updating the log chain")?;
+        debug!(
+            loglet_id = %released_params.loglet_id,
+            "Released records of offloaded segment from its source loglet"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,170 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use dashmap::DashMap;
+
+use restate_types::config::Configuration;
+use restate_types::logs::LogId;
+use restate_types::logs::metadata::{Chain, LogletParams, ProviderConfiguration, SegmentIndex};
+
+use super::loglet::ObjectStoreLoglet;
+use super::store::ObjectStoreLogletStore;
+use super::{ObjectStoreLogletError, ObjectStoreLogletParams, parse_params};
+use crate::Error;
+use crate::loglet::{Loglet, LogletProvider, OperationError};
+
+/// Serves offloaded segments. Unlike other providers, this one is not selectable as the
+/// provider for new segments; segments only become object-store segments by offloading.
+///
+/// The provider is available on every node. Segments are read from the destination recorded
+/// in their params, using the object-store options of the local configuration.
+pub(crate) struct ObjectStoreLogletProvider {
+    /// Store of the locally configured destination, used for segments that don't record
+    /// their destination.
+    local_store: Option<ObjectStoreLogletStore>,
+    stores: DashMap<String, ObjectStoreLogletStore>,
+    loglets: DashMap<(LogId, SegmentIndex), Arc<ObjectStoreLoglet>>,
+}
+
+impl ObjectStoreLogletProvider {
+    pub fn new(local_store: Option<ObjectStoreLogletStore>) -> Self {
+        let stores = DashMap::default();
+        if let Some(ref store) = local_store {
+            stores.insert(store.destination().to_owned(), store.clone());
+        }
+        Self {
+            local_store,
+            stores,
+            loglets: Default::default(),
+        }
+    }
+
+    async fn store_for(
+        &self,
+        log_id: LogId,
+        segment_index: SegmentIndex,
+        params: &ObjectStoreLogletParams,
+    ) -> Result<ObjectStoreLogletStore, Error> {
+        let Some(ref destination) = params.destination else {
+            return self.local_store.clone().ok_or_else(|| {
+                ObjectStoreLogletError::UnknownDestination(log_id, segment_index).into()
+            });
+        };
+        if let Some(store) = self.stores.get(destination) {
+            return Ok(store.clone());
+        }
+
+        let options = Configuration::pinned().bifrost.object_store_loglet.clone();
+        let store = ObjectStoreLogletStore::create(destination, &options)
+            .await
+            .map_err(|err| ObjectStoreLogletError::Destination(destination.clone(), err))?;
+        Ok(self
+            .stores
+            .entry(destination.clone())
+            .or_insert(store)
+            .clone())
+    }
+}
+
+#[async_trait]
+impl LogletProvider for ObjectStoreLogletProvider {
+    async fn get_loglet(
+        &self,
+        log_id: LogId,
+        segment_index: SegmentIndex,
+        params: &LogletParams,
+    ) -> Result<Arc<dyn Loglet>, Error> {
+        let params = parse_params(log_id, segment_index, params)?;
+        if let Some(loglet) = self.loglets.get(&(log_id, segment_index))
+            && loglet.params() == &params
+        {
+            return Ok(Arc::clone(&loglet) as Arc<dyn Loglet>);
+        }
+
+        // The params change when the source loglet is released, the replaced loglet picks up
+        // the persisted trim point again.
+        let store = self.store_for(log_id, segment_index, &params).await?;
+        let loglet = Arc::new(ObjectStoreLoglet::load(params, store).await?);
+        self.loglets
+            .insert((log_id, segment_index), Arc::clone(&loglet));
+        Ok(loglet as Arc<dyn Loglet>)
+    }
+
+    fn propose_new_loglet_params(
+        &self,
+        _log_id: LogId,
+        _chain: Option<&Chain>,
+        _defaults: &ProviderConfiguration,
+    ) -> Result<LogletParams, OperationError> {
+        Err(ObjectStoreLogletError::ReadOnly.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use googletest::prelude::*;
+    use object_store::memory::InMemory;
+
+    use restate_types::logs::{LogletId, LogletOffset};
+    use restate_types::time::MillisSinceEpoch;
+
+    #[restate_core::test]
+    async fn replaces_loglet_on_params_change() -> googletest::Result<()> {
+        let store = ObjectStoreLogletStore::new(Arc::new(InMemory::new()));
+        let provider = ObjectStoreLogletProvider::new(Some(store));
+        let log_id = LogId::new(1);
+        let segment_index = SegmentIndex::from(2);
+        let params = ObjectStoreLogletParams {
+            loglet_id: LogletId::new_unchecked(7),
+            trim_point: LogletOffset::INVALID,
+            tail: LogletOffset::new(26),
+            records_per_object: 10,
+            offloaded_at: MillisSinceEpoch::now(),
+            destination: None,
+            source: Some("source".to_owned()),
+        };
+        let loglet_params = LogletParams::from(params.serialize()?);
+
+        let loglet = provider
+            .get_loglet(log_id, segment_index, &loglet_params)
+            .await?;
+        let cached = provider
+            .get_loglet(log_id, segment_index, &loglet_params)
+            .await?;
+        assert_that!(Arc::ptr_eq(&loglet, &cached), eq(true));
+
+        let released_params = ObjectStoreLogletParams {
+            source: None,
+            ..params
+        };
+        let released = provider
+            .get_loglet(
+                log_id,
+                segment_index,
+                &LogletParams::from(released_params.serialize()?),
+            )
+            .await?;
+        assert_that!(Arc::ptr_eq(&loglet, &released), eq(false));
+        assert_that!(
+            provider
+                .loglets
+                .get(&(log_id, segment_index))
+                .map(|loglet| loglet.params().clone()),
+            some(eq(released_params))
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, ready};
+
+use futures::future::BoxFuture;
+use futures::{FutureExt, Stream};
+
+use restate_types::logs::{KeyFilter, LogletOffset, MatchKeyQuery, Record, SequenceNumber};
+
+use super::ObjectStoreLogletError;
+use super::loglet::ObjectStoreLoglet;
+use super::store::RecordBatch;
+use crate::LogEntry;
+use crate::loglet::{LogletReadStream, OperationError};
+
+type FetchFuture = BoxFuture<'static, Result<Option<RecordBatch>, ObjectStoreLogletError>>;
+
+/// Reads records of an offloaded loglet, fetching one object at a time.
+pub(super) struct ObjectStoreReadStream {
+    loglet: Arc<ObjectStoreLoglet>,
+    filter: KeyFilter,
+    /// Next offset to read
+    read_pointer: LogletOffset,
+    /// Last offset to read (inclusive)
+    read_to: LogletOffset,
+    /// If the reader didn't set an upper bound, the stream stays pending after the last record
+    /// like any other sealed loglet.
+    terminate_at_read_to: bool,
+    /// Records of the current object, the front record is at `read_pointer`
+    buffer: VecDeque<Record>,
+    fetch: Option<FetchFuture>,
+    terminated: bool,
+}
+
+impl ObjectStoreReadStream {
+    pub fn new(
+        loglet: Arc<ObjectStoreLoglet>,
+        filter: KeyFilter,
+        from: LogletOffset,
+        to: Option<LogletOffset>,
+    ) -> Self {
+        let last_offset = loglet.params().tail.prev_unchecked();
+        let read_to = to.map_or(last_offset, |to| to.min(last_offset));
+        Self {
+            loglet,
+            filter,
+            read_pointer: from.max(LogletOffset::OLDEST),
+            read_to,
+            terminate_at_read_to: to.is_some(),
+            buffer: VecDeque::new(),
+            fetch: None,
+            terminated: false,
+        }
+    }
+}
+
+impl LogletReadStream for ObjectStoreReadStream {
+    /// Current read pointer. This points to the next offset to be read.
+    fn read_pointer(&self) -> LogletOffset {
+        self.read_pointer
+    }
+    /// Returns true if the stream is terminated.
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+impl Stream for ObjectStoreReadStream {
+    type Item = Result<LogEntry<LogletOffset>, OperationError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.terminated {
+            return Poll::Ready(None);
+        }
+
+        loop {
+            if self.read_pointer > self.read_to {
+                if self.terminate_at_read_to {
+                    self.terminated = true;
+                    return Poll::Ready(None);
+                }
+                // No records will ever be added to this loglet
+                return Poll::Pending;
+            }
+
+            // Trim point might have moved since the last poll
+            let trim_point = self.loglet.trim_point();
+            if self.read_pointer <= trim_point {
+                let gap_to = trim_point.min(self.read_to);
+                let trim_gap = LogEntry::new_trim_gap(self.read_pointer, gap_to);
+                self.read_pointer = gap_to.next();
+                self.buffer.clear();
+                self.fetch = None;
+                return Poll::Ready(Some(Ok(trim_gap)));
+            }
+
+            if let Some(record) = self.buffer.pop_front() {
+                let offset = self.read_pointer;
+                self.read_pointer = offset.next();
+                // If this is a filtered record, skip it.
+                if !record.matches_key_query(&self.filter) {
+                    continue;
+                }
+                return Poll::Ready(Some(Ok(LogEntry::new_data(offset, record))));
+            }
+
+            let object_start = self.loglet.params().object_start(self.read_pointer);
+            if self.fetch.is_none() {
+                let loglet = Arc::clone(&self.loglet);
+                self.fetch = Some(
+                    async move {
+                        let batch = loglet
+                            .store()
+                            .get(loglet.params().loglet_id, object_start)
+                            .await?;
+                        if batch.is_none() {
+                            // The object might have been deleted by a trim on another node
+                            loglet.refresh_trim_point().await?;
+                        }
+                        Ok(batch)
+                    }
+                    .boxed(),
+                );
+            }
+
+            let result = ready!(self.fetch.as_mut().expect("fetch is set").poll_unpin(cx));
+            self.fetch = None;
+            match result {
+                Ok(Some(batch)) => {
+                    // Records before the batch's first offset were trimmed before offloading,
+                    // the trim point check above skips them.
+                    let skip = (*self.read_pointer)
+                        .checked_sub(*batch.first_offset)
+                        .map(|skip| skip as usize);
+                    match skip {
+                        Some(skip) if skip < batch.records.len() => {
+                            self.buffer = batch.records.into_iter().skip(skip).collect();
+                        }
+                        _ => {
+                            self.terminated = true;
+                            let err = ObjectStoreLogletError::UnexpectedObject {
+                                path: format!(
+                                    "{}/{}",
+                                    self.loglet.params().loglet_id,
+                                    object_start
+                                ),
+                                expected: self.read_pointer,
+                                found: batch.first_offset,
+                            };
+                            return Poll::Ready(Some(Err(err.into())));
+                        }
+                    }
+                }
+                Ok(None) => {
+                    // Objects are only deleted after the trim point was persisted past their
+                    // records, the trim point check above reports the gap.
+                    let trim_point = self.loglet.trim_point();
+                    if self.read_pointer <= trim_point {
+                        continue;
+                    }
+                    self.terminated = true;
+                    let err = ObjectStoreLogletError::MissingObject {
+                        path: format!("{}/{}", self.loglet.params().loglet_id, object_start),
+                        trim_point,
+                    };
+                    return Poll::Ready(Some(Err(err.into())));
+                }
+                Err(err) => return Poll::Ready(Some(Err(err.into()))),
+            }
+        }
+    }
+}
@@ -0,0 +1,223 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::sync::Arc;
+
+use anyhow::Context;
+use bytes::BytesMut;
+use object_store::path::Path as ObjectPath;
+use object_store::{ObjectStore, PutPayload};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use url::Url;
+
+use restate_object_store_util::create_object_store_client;
+use restate_types::config::ObjectStoreLogletOptions;
+use restate_types::flexbuffers_storage_encode_decode;
+use restate_types::logs::{LogletId, LogletOffset, Record};
+use restate_types::storage::StorageCodec;
+
+use super::ObjectStoreLogletError;
+
+/// A batch of consecutive records stored in a single object. The first object of a loglet
+/// starts after the trim point, so `first_offset` can be past the start of the object.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct RecordBatch {
+    pub first_offset: LogletOffset,
+    pub records: Vec<Record>,
+}
+
+flexbuffers_storage_encode_decode!(RecordBatch);
+
+/// Records at or before `trim_point` were trimmed after the loglet was offloaded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PersistedTrimPoint {
+    trim_point: LogletOffset,
+}
+
+flexbuffers_storage_encode_decode!(PersistedTrimPoint);
+
+/// Read and write access to the objects of offloaded loglets.
+#[derive(Clone)]
+pub(crate) struct ObjectStoreLogletStore {
+    destination: String,
+    object_store: Arc<dyn ObjectStore>,
+    prefix: ObjectPath,
+}
+
+impl ObjectStoreLogletStore {
+    /// Creates the store if an object-store destination is configured.
+    pub async fn create_if_configured(
+        options: &ObjectStoreLogletOptions,
+    ) -> anyhow::Result<Option<Self>> {
+        match options.destination {
+            Some(ref destination) => Self::create(destination, options).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Creates the store for the given destination URL. The remaining options, such as the
+    /// credentials, are taken from the local configuration.
+    pub async fn create(
+        destination: &str,
+        options: &ObjectStoreLogletOptions,
+    ) -> anyhow::Result<Self> {
+        let original_destination = destination.to_owned();
+        let mut destination = Url::parse(destination)
+            .context("Failed parsing object-store loglet destination URL")?;
+        // Prevent passing configuration options to object_store via the destination URL.
+        destination
+            .query()
+            .inspect(|params| info!("Destination parameters ignored: {params}"));
+        destination.set_query(None);
+
+        let prefix = ObjectPath::from(destination.path());
+        let object_store = create_object_store_client(
+            destination,
+            &options.object_store,
+            &options.object_store_retry_policy,
+        )
+        .await?;
+
+        Ok(Self {
+            destination: original_destination,
+            object_store,
+            prefix,
+        })
+    }
+
+    #[cfg(test)]
+    pub fn new(object_store: Arc<dyn ObjectStore>) -> Self {
+        Self {
+            destination: "memory:///".to_owned(),
+            object_store,
+            prefix: ObjectPath::default(),
+        }
+    }
+
+    /// The destination URL of this store
+    pub fn destination(&self) -> &str {
+        &self.destination
+    }
+
+    fn object_path(&self, loglet_id: LogletId, first_offset: LogletOffset) -> ObjectPath {
+        self.prefix
+            .child(loglet_id.to_string())
+            .child(format!("{:010}", *first_offset))
+    }
+
+    fn trim_point_path(&self, loglet_id: LogletId) -> ObjectPath {
+        self.prefix.child(loglet_id.to_string()).child("trim-point")
+    }
+
+    /// Uploads a batch of consecutive records starting at `first_offset` to the object that
+    /// starts at `object_start`. Re-uploading the same batch is idempotent.
+    pub async fn put(
+        &self,
+        loglet_id: LogletId,
+        object_start: LogletOffset,
+        first_offset: LogletOffset,
+        records: Vec<Record>,
+    ) -> Result<(), ObjectStoreLogletError> {
+        let path = self.object_path(loglet_id, object_start);
+        let mut buf = BytesMut::new();
+        let batch = RecordBatch {
+            first_offset,
+            records,
+        };
+        StorageCodec::encode(&batch, &mut buf).expect("record batch serde is infallible");
+
+        self.object_store
+            .put(&path, PutPayload::from_bytes(buf.freeze()))
+            .await?;
+        Ok(())
+    }
+
+    /// Fetches the object that starts at `object_start`. Returns `None` if the object does not
+    /// exist (e.g. it was deleted after being trimmed).
+    pub async fn get(
+        &self,
+        loglet_id: LogletId,
+        object_start: LogletOffset,
+    ) -> Result<Option<RecordBatch>, ObjectStoreLogletError> {
+        let path = self.object_path(loglet_id, object_start);
+        let bytes = match self.object_store.get(&path).await {
+            Ok(result) => result.bytes().await?,
+            Err(object_store::Error::NotFound { .. }) => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut buf = std::io::Cursor::new(bytes);
+        let batch: RecordBatch = StorageCodec::decode(&mut buf)
+            .map_err(|err| ObjectStoreLogletError::Decode(path.to_string(), err))?;
+
+        if batch.first_offset < object_start {
+            return Err(ObjectStoreLogletError::UnexpectedObject {
+                path: path.to_string(),
+                expected: object_start,
+                found: batch.first_offset,
+            });
+        }
+
+        Ok(Some(batch))
+    }
+
+    /// Persists the trim point of a loglet. Callers must not lower a previously persisted trim
+    /// point.
+    pub async fn put_trim_point(
+        &self,
+        loglet_id: LogletId,
+        trim_point: LogletOffset,
+    ) -> Result<(), ObjectStoreLogletError> {
+        let mut buf = BytesMut::new();
+        StorageCodec::encode(&PersistedTrimPoint { trim_point }, &mut buf)
+            .expect("trim point serde is infallible");
+
+        self.object_store
+            .put(
+                &self.trim_point_path(loglet_id),
+                PutPayload::from_bytes(buf.freeze()),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Fetches the trim point of a loglet. Returns `None` if the loglet was never trimmed after
+    /// offloading.
+    pub async fn get_trim_point(
+        &self,
+        loglet_id: LogletId,
+    ) -> Result<Option<LogletOffset>, ObjectStoreLogletError> {
+        let path = self.trim_point_path(loglet_id);
+        let bytes = match self.object_store.get(&path).await {
+            Ok(result) => result.bytes().await?,
+            Err(object_store::Error::NotFound { .. }) => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut buf = std::io::Cursor::new(bytes);
+        let persisted: PersistedTrimPoint = StorageCodec::decode(&mut buf)
+            .map_err(|err| ObjectStoreLogletError::Decode(path.to_string(), err))?;
+        Ok(Some(persisted.trim_point))
+    }
+
+    /// Deletes the object that starts at `object_start`. Deleting a missing object is a no-op.
+    pub async fn delete(
+        &self,
+        loglet_id: LogletId,
+        object_start: LogletOffset,
+    ) -> Result<(), ObjectStoreLogletError> {
+        let path = self.object_path(loglet_id, object_start);
+        match self.object_store.delete(&path).await {
+            Ok(_) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
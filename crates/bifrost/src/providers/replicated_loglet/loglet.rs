@@ -519,6 +519,7 @@ mod tests {
             sequencer: GenerationalNodeId::new(1, 1),
             replication: ReplicationProperty::new(NonZeroU8::new(1).unwrap()),
             nodeset: NodeSet::from_single(PlainNodeId::new(1)),
+            erasure_coding: None,
         };
         let record_cache = RecordCache::new(1_000_000);
 
@@ -562,6 +563,7 @@ mod tests {
             sequencer: GenerationalNodeId::new(1, 1),
             replication: ReplicationProperty::new(NonZeroU8::new(1).unwrap()),
             nodeset: NodeSet::from_single(PlainNodeId::new(1)),
+            erasure_coding: None,
         };
 
         let record_cache = RecordCache::new(1_000_000);
@@ -611,6 +613,7 @@ mod tests {
             sequencer: GenerationalNodeId::new(1, 1),
             replication: ReplicationProperty::new(NonZeroU8::new(1).unwrap()),
             nodeset: NodeSet::from_single(PlainNodeId::new(1)),
+            erasure_coding: None,
         };
         run_in_test_env(Configuration::default(), params, record_cache, |env| {
             crate::loglet::loglet_tests::gapless_loglet_smoke_test(env.loglet)
@@ -626,6 +629,7 @@ mod tests {
             sequencer: GenerationalNodeId::new(1, 1),
             replication: ReplicationProperty::new(NonZeroU8::new(1).unwrap()),
             nodeset: NodeSet::from_single(PlainNodeId::new(1)),
+            erasure_coding: None,
         };
         let record_cache = RecordCache::new(1_000_000);
         run_in_test_env(Configuration::default(), params, record_cache, |env| {
@@ -642,6 +646,7 @@ mod tests {
             sequencer: GenerationalNodeId::new(1, 1),
             replication: ReplicationProperty::new(NonZeroU8::new(1).unwrap()),
             nodeset: NodeSet::from_single(PlainNodeId::new(1)),
+            erasure_coding: None,
         };
         // For this test to work, we need to disable the record cache to ensure we
         // observer the moving trimpoint.
@@ -665,6 +670,7 @@ mod tests {
             sequencer: GenerationalNodeId::new(1, 1),
             replication: ReplicationProperty::new(NonZeroU8::new(1).unwrap()),
             nodeset: NodeSet::from_single(PlainNodeId::new(1)),
+            erasure_coding: None,
         };
         let record_cache = RecordCache::new(1_000_000);
         run_in_test_env(Configuration::default(), params, record_cache, |env| {
@@ -681,6 +687,7 @@ mod tests {
             sequencer: GenerationalNodeId::new(1, 1),
             replication: ReplicationProperty::new(NonZeroU8::new(1).unwrap()),
             nodeset: NodeSet::from_single(PlainNodeId::new(1)),
+            erasure_coding: None,
         };
 
         let record_cache = RecordCache::new(1_000_000);
@@ -698,6 +705,7 @@ mod tests {
             sequencer: GenerationalNodeId::new(1, 1),
             replication: ReplicationProperty::new(NonZeroU8::new(1).unwrap()),
             nodeset: NodeSet::from_single(PlainNodeId::new(1)),
+            erasure_coding: None,
         };
         let record_cache = RecordCache::new(1_000_000);
         run_in_test_env(Configuration::default(), params, record_cache, |env| {
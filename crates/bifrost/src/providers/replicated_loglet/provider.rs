@@ -25,7 +25,9 @@ use restate_types::logs::metadata::{
 use restate_types::logs::{LogId, LogletId, RecordCache};
 use restate_types::net::replicated_loglet::{SequencerDataService, SequencerMetaService};
 use restate_types::nodes_config::{Role, StorageState};
-use restate_types::replicated_loglet::{ReplicatedLogletParams, logserver_candidate_filter};
+use restate_types::replicated_loglet::{
+    ErasureCoding, ReplicatedLogletParams, logserver_candidate_filter,
+};
 use restate_types::replication::{
     NodeSet, NodeSetChecker, NodeSetSelector, NodeSetSelectorOptions, ReplicationProperty,
 };
@@ -226,13 +228,25 @@ impl<T: TransportConnect> LogletProvider for ReplicatedLogletProvider<T> {
         let mut preferred_nodes = current_params.nodeset.clone();
 
         let my_node = my_node_id();
+        let replication = defaults.loglet_replication();
+
+        // improvement to apply the erasure coding
+        if current_params.erasure_coding != defaults.erasure_coding {
+            return Ok(Improvement::Possible {
+                reason: format!(
+                    "erasure coding can change from {} to {}",
+                    display_erasure_coding(current_params.erasure_coding),
+                    display_erasure_coding(defaults.erasure_coding),
+                ),
+            });
+        }
 
         // improvement to apply the replication property
-        if current_params.replication != defaults.replication_property {
+        if current_params.replication != replication {
             return Ok(Improvement::Possible {
                 reason: format!(
                     "replication can change from {} to {}",
-                    current_params.replication, defaults.replication_property
+                    current_params.replication, replication
                 ),
             });
         }
@@ -253,7 +267,7 @@ impl<T: TransportConnect> LogletProvider for ReplicatedLogletProvider<T> {
         }
 
         let opts = NodeSetSelectorOptions::new(u32::from(log_id) as u64)
-            .with_target_size(defaults.target_nodeset_size)
+            .with_target_size(defaults.nodeset_target_size())
             .with_preferred_nodes(&preferred_nodes)
             .with_top_priority_node(my_node);
 
@@ -261,7 +275,7 @@ impl<T: TransportConnect> LogletProvider for ReplicatedLogletProvider<T> {
 
         let selection = NodeSetSelector::select(
             &nodes_config,
-            &defaults.replication_property,
+            &replication,
             logserver_candidate_filter,
             |_, config| {
                 matches!(
@@ -273,9 +287,12 @@ impl<T: TransportConnect> LogletProvider for ReplicatedLogletProvider<T> {
         );
 
         let new_nodeset = selection.map_err(OperationError::retryable)?;
+        let Some(new_nodeset) = defaults.fit_nodeset(new_nodeset) else {
+            // not enough nodes to store all fragments
+            return Ok(Improvement::None);
+        };
 
-        let mut node_set_checker =
-            NodeSetChecker::new(&new_nodeset, &nodes_config, &defaults.replication_property);
+        let mut node_set_checker = NodeSetChecker::new(&new_nodeset, &nodes_config, &replication);
         node_set_checker.fill_with(true);
 
         // check that the new node set fulfills the replication property
@@ -340,15 +357,16 @@ impl<T: TransportConnect> LogletProvider for ReplicatedLogletProvider<T> {
         }
 
         let opts = NodeSetSelectorOptions::new(u32::from(log_id) as u64)
-            .with_target_size(defaults.target_nodeset_size)
+            .with_target_size(defaults.nodeset_target_size())
             .with_preferred_nodes(&preferred_nodes)
             .with_top_priority_node(my_node);
 
         let nodes_config = Metadata::with_current(|m| m.nodes_config_ref());
+        let replication = defaults.loglet_replication();
 
         let selection = NodeSetSelector::select(
             &nodes_config,
-            &defaults.replication_property,
+            &replication,
             logserver_candidate_filter,
             |_, config| {
                 matches!(
@@ -361,24 +379,30 @@ impl<T: TransportConnect> LogletProvider for ReplicatedLogletProvider<T> {
 
         match selection {
             Ok(nodeset) => {
+                let Some(nodeset) = defaults.fit_nodeset(nodeset) else {
+                    // not enough nodes to store all fragments
+                    return Err(OperationError::terminal(InsufficientNodesError(
+                        replication,
+                    )));
+                };
                 let mut node_set_checker =
-                    NodeSetChecker::new(&nodeset, &nodes_config, &defaults.replication_property);
+                    NodeSetChecker::new(&nodeset, &nodes_config, &replication);
                 node_set_checker.fill_with(true);
 
                 // check that the new node set fulfills the replication property
                 if !node_set_checker.check_write_quorum(|attr| *attr) {
                     // we couldn't find a nodeset that fulfills the desired replication property
                     return Err(OperationError::terminal(InsufficientNodesError(
-                        defaults.replication_property.clone(),
+                        replication,
                     )));
                 }
 
-                if defaults.replication_property.num_copies() > 1
-                    && nodeset.len() == defaults.replication_property.num_copies() as usize
+                if replication.num_copies() > 1
+                    && nodeset.len() == replication.num_copies() as usize
                 {
                     warn!(
                         ?log_id,
-                        replication = %defaults.replication_property,
+                        replication = %replication,
                         generated_nodeset_size = nodeset.len(),
                         "The number of writeable log-servers is too small for the configured \
                         replication, there will be no fault-tolerance until you add more nodes."
@@ -387,8 +411,9 @@ impl<T: TransportConnect> LogletProvider for ReplicatedLogletProvider<T> {
                 let new_params = ReplicatedLogletParams {
                     loglet_id: LogletId::new(log_id, new_segment_index),
                     sequencer: my_node,
-                    replication: defaults.replication_property.clone(),
+                    replication,
                     nodeset,
+                    erasure_coding: defaults.erasure_coding,
                 };
 
                 let new_params = new_params
@@ -418,6 +443,12 @@ impl<T: TransportConnect> LogletProvider for ReplicatedLogletProvider<T> {
     }
 }
 
+fn display_erasure_coding(erasure_coding: Option<ErasureCoding>) -> String {
+    erasure_coding
+        .map(|ec| ec.to_string())
+        .unwrap_or_else(|| "none".to_owned())
+}
+
 #[derive(Debug, thiserror::Error)]
 #[error("not enough candidate nodes to form a node set that fulfills the replication property {0}")]
 pub struct InsufficientNodesError(ReplicationProperty);
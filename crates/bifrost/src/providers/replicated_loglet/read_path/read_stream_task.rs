@@ -8,6 +8,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::collections::BTreeMap;
 use std::time::Duration;
 
 use metrics::{Counter, counter};
@@ -21,8 +22,10 @@ use restate_types::config::{Configuration, ReplicatedLogletOptions};
 use restate_types::logs::{
     KeyFilter, LogletOffset, MatchKeyQuery, RecordCache, SequenceNumber, TailOffsetWatch,
 };
-use restate_types::net::log_server::{GetRecords, LogServerRequestHeader, MaybeRecord};
-use restate_types::replicated_loglet::{EffectiveNodeSet, LogNodeSetExt, ReplicatedLogletParams};
+use restate_types::net::log_server::{Gap, GetRecords, LogServerRequestHeader, MaybeRecord};
+use restate_types::replicated_loglet::{
+    EffectiveNodeSet, ErasureCoding, LogNodeSetExt, ReplicatedLogletParams,
+};
 use restate_types::replication::NodeSet;
 
 use crate::LogEntry;
@@ -346,33 +349,64 @@ impl ReadStreamTask {
                 }
 
                 let to_offset = self.calculate_read_ahead_to_offset(permits.len());
-                // If we (my node) are in the nodeset, we'll be the first to try
-                let Some(server) = mutable_effective_nodeset.pop() else {
-                    // no more servers to try. Going back and retrying the main loop to start over.
-                    info!(
-                        loglet_id = %self.my_params.loglet_id,
-                        from_offset = %self.read_pointer,
-                        %to_offset,
-                        "Could not request record batch, exhausted all servers in the nodeset. Retrying.."
-                    );
-                    tokio::time::sleep(Duration::from_secs(2)).await;
-                    continue 'main;
-                };
-
-                let ServerReadResult::Records {
-                    records,
-                    next_offset,
-                } = self
-                    .readahead_from_server(
-                        server,
-                        to_offset,
-                        &networking,
-                        &configuration.live_load().bifrost.replicated_loglet,
-                    )
-                    .await?
-                else {
-                    // move to the next server
-                    continue 'attempt_from_servers;
+                // The server to read from again if it has more records for us after shipping
+                let mut retry_server = None;
+                let (servers, records) = if let Some(erasure_coding) = self.my_params.erasure_coding
+                {
+                    // Records of erasure-coded loglets need to be reconstructed from the
+                    // fragments of multiple servers.
+                    let Some(result) = self
+                        .read_fragments_from_servers(
+                            &erasure_coding,
+                            &mut mutable_effective_nodeset,
+                            to_offset,
+                            &networking,
+                            &configuration.live_load().bifrost.replicated_loglet,
+                        )
+                        .await?
+                    else {
+                        info!(
+                            loglet_id = %self.my_params.loglet_id,
+                            from_offset = %self.read_pointer,
+                            %to_offset,
+                            %erasure_coding,
+                            "Could not reconstruct records, exhausted all servers in the nodeset. Retrying.."
+                        );
+                        tokio::time::sleep(Duration::from_secs(2)).await;
+                        continue 'main;
+                    };
+                    result
+                } else {
+                    // If we (my node) are in the nodeset, we'll be the first to try
+                    let Some(server) = mutable_effective_nodeset.pop() else {
+                        // no more servers to try. Going back and retrying the main loop to start over.
+                        info!(
+                            loglet_id = %self.my_params.loglet_id,
+                            from_offset = %self.read_pointer,
+                            %to_offset,
+                            "Could not request record batch, exhausted all servers in the nodeset. Retrying.."
+                        );
+                        tokio::time::sleep(Duration::from_secs(2)).await;
+                        continue 'main;
+                    };
+
+                    let ServerReadResult::Records {
+                        records,
+                        next_offset,
+                    } = self
+                        .readahead_from_server(
+                            server,
+                            to_offset,
+                            &networking,
+                            &configuration.live_load().bifrost.replicated_loglet,
+                        )
+                        .await?
+                    else {
+                        // move to the next server
+                        continue 'attempt_from_servers;
+                    };
+                    retry_server = next_offset.map(|next_offset| (server, next_offset));
+                    (NodeSet::from_single(server), records)
                 };
 
                 // Note that returned records can have gaps
@@ -392,8 +426,8 @@ impl ReadStreamTask {
                                 trace!(
                                     loglet_id = %self.my_params.loglet_id,
                                     offset = %self.read_pointer,
-                                    "Shipping a trim gap from node {} to offset {}",
-                                    server,
+                                    "Shipping a trim gap from node(s) {} to offset {}",
+                                    servers,
                                     gap.to
                                 );
                                 permit.send(Ok(LogEntry::new_trim_gap(self.read_pointer, gap.to)));
@@ -417,8 +451,8 @@ impl ReadStreamTask {
                                 trace!(
                                     loglet_id = %self.my_params.loglet_id,
                                     offset = %self.read_pointer,
-                                    "Shipping a filtered gap from node {} to offset {}",
-                                    server,
+                                    "Shipping a filtered gap from node(s) {} to offset {}",
+                                    servers,
                                     gap_to
                                 );
                                 permit.send(Ok(LogEntry::new_filtered_gap(
@@ -432,8 +466,8 @@ impl ReadStreamTask {
                                 trace!(
                                     loglet_id = %self.my_params.loglet_id,
                                     offset = %self.read_pointer,
-                                    "Shipping a data record acquired from node {}",
-                                    server,
+                                    "Shipping a data record acquired from node(s) {}",
+                                    servers,
                                 );
                                 // We do not cache this record since it's rare that we go back and
                                 // read the same records that we shipped. If this assumption
@@ -446,7 +480,9 @@ impl ReadStreamTask {
                     }
                 }
                 // we should try the last server again if the new read_pointer is the next_offset this server can supply.
-                if next_offset.is_some_and(|next_offset| next_offset == self.read_pointer) {
+                if let Some((server, next_offset)) = retry_server
+                    && next_offset == self.read_pointer
+                {
                     // this server has more to send us, let's use it in the next attempt
                     mutable_effective_nodeset.push(server);
                 }
@@ -454,6 +490,97 @@ impl ReadStreamTask {
         }
     }
 
+    /// Reads fragments from servers until at least one record or gap at the read pointer can be
+    /// reconstructed. Servers that responded are put back into `servers` so that they are tried
+    /// first in the next attempt.
+    ///
+    /// Returns the responding servers and the reconstructed records, or `None` if the servers were
+    /// exhausted before any record could be reconstructed.
+    async fn read_fragments_from_servers<T: TransportConnect>(
+        &self,
+        erasure_coding: &ErasureCoding,
+        servers: &mut Vec<PlainNodeId>,
+        to_offset: LogletOffset,
+        networking: &Networking<T>,
+        options: &ReplicatedLogletOptions,
+    ) -> Result<Option<(NodeSet, Vec<(LogletOffset, MaybeRecord)>)>, OperationError> {
+        let mut responses: Vec<(PlainNodeId, BTreeMap<LogletOffset, MaybeRecord>)> = Vec::new();
+        let merged = loop {
+            let Some(server) = servers.pop() else {
+                break None;
+            };
+            let ServerReadResult::Records { records, .. } = self
+                .readahead_from_server(server, to_offset, networking, options)
+                .await?
+            else {
+                continue;
+            };
+            responses.push((server, records.into_iter().collect()));
+
+            let merged = self.merge_fragments(erasure_coding, &responses, to_offset)?;
+            if !merged.is_empty() {
+                break Some(merged);
+            }
+        };
+
+        servers.extend(responses.iter().rev().map(|(server, _)| *server));
+        Ok(merged.map(|merged| {
+            (
+                responses.iter().map(|(server, _)| *server).collect(),
+                merged,
+            )
+        }))
+    }
+
+    /// Merges the responses of multiple servers into the contiguous range of records and gaps
+    /// starting at the read pointer that can be determined from them.
+    fn merge_fragments(
+        &self,
+        erasure_coding: &ErasureCoding,
+        responses: &[(PlainNodeId, BTreeMap<LogletOffset, MaybeRecord>)],
+        to_offset: LogletOffset,
+    ) -> Result<Vec<(LogletOffset, MaybeRecord)>, OperationError> {
+        let mut merged = Vec::new();
+        let mut offset = self.read_pointer;
+        'offsets: while offset <= to_offset {
+            let mut fragments = Vec::with_capacity(erasure_coding.data_fragments());
+            for (_, records) in responses {
+                let Some((start, maybe_record)) = records.range(..=offset).next_back() else {
+                    continue;
+                };
+                match maybe_record {
+                    // Trim gaps and filtered gaps of one server are sufficient since fragments
+                    // carry the keys of the original record.
+                    MaybeRecord::TrimGap(gap) if gap.to >= offset => {
+                        merged.push((offset, MaybeRecord::TrimGap(Gap { to: gap.to })));
+                        offset = gap.to.next();
+                        continue 'offsets;
+                    }
+                    MaybeRecord::FilteredGap(gap) if gap.to >= offset => {
+                        merged.push((offset, MaybeRecord::FilteredGap(Gap { to: gap.to })));
+                        offset = gap.to.next();
+                        continue 'offsets;
+                    }
+                    MaybeRecord::Data(fragment) if *start == offset => {
+                        fragments.push(fragment);
+                    }
+                    _ => {}
+                }
+            }
+
+            if fragments.len() < erasure_coding.data_fragments() {
+                // we need fragments from more servers to make progress
+                break;
+            }
+            let record = erasure_coding
+                .decode_record(fragments)
+                .map_err(OperationError::terminal)?;
+            merged.push((offset, MaybeRecord::Data(record)));
+            offset = offset.next();
+        }
+        Ok(merged)
+    }
+
     fn add_to_cache(&self, offset: LogletOffset, maybe_record: &MaybeRecord) {
         if let MaybeRecord::Data(record) = maybe_record {
             self.record_cache
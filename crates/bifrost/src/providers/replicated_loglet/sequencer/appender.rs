@@ -11,6 +11,7 @@
 use std::ops::Deref;
 use std::{cmp::Ordering, fmt::Display, sync::Arc, time::Duration};
 
+use bytes::BytesMut;
 use tokio::time::Instant;
 use tokio::{sync::OwnedSemaphorePermit, task::JoinSet};
 use tokio_util::sync::CancellationToken;
@@ -21,7 +22,7 @@ use restate_core::{
     Metadata, TaskCenterFutureExt,
     network::{Networking, TransportConnect},
 };
-use restate_types::replicated_loglet::Spread;
+use restate_types::replicated_loglet::{ErasureCoding, Spread};
 use restate_types::retries::with_jitter;
 use restate_types::{
     Merge, PlainNodeId,
@@ -64,6 +65,9 @@ pub(crate) struct SequencerAppender<T> {
     networking: Networking<T>,
     first_offset: LogletOffset,
     records: Arc<[Record]>,
+    /// Batches of erasure-coded fragments of `records`, indexed by fragment index. Only set if
+    /// the loglet is erasure-coded.
+    fragments: Option<Vec<Arc<[Record]>>>,
     checker: NodeSetChecker<NodeAttributes>,
     nodeset_status: DecoratedNodeSet<PerNodeStatus>,
     current_wave: usize,
@@ -99,6 +103,11 @@ impl<T: TransportConnect> SequencerAppender<T> {
         let nodeset_status =
             DecoratedNodeSet::from(sequencer_shared_state.selector.nodeset().clone());
 
+        let fragments = sequencer_shared_state
+            .my_params()
+            .erasure_coding
+            .map(|erasure_coding| encode_fragments(&erasure_coding, &records));
+
         Self {
             sequencer_shared_state,
             networking,
@@ -107,6 +116,7 @@ impl<T: TransportConnect> SequencerAppender<T> {
             current_wave: 0,
             first_offset,
             records,
+            fragments,
             permit: Some(permit),
             commit_resolver: Some(commit_resolver),
             configuration: Configuration::live(),
@@ -248,6 +258,22 @@ impl<T: TransportConnect> SequencerAppender<T> {
         }
     }
 
+    /// The records that should be stored on the given node. That's either the records themselves
+    /// or the node's fragments of the records if the loglet is erasure-coded.
+    fn records_for(&self, node_id: PlainNodeId) -> Arc<[Record]> {
+        match &self.fragments {
+            Some(fragments) => {
+                let index = self
+                    .sequencer_shared_state
+                    .my_params()
+                    .fragment_index(node_id)
+                    .expect("spread nodes are part of the nodeset");
+                Arc::clone(&fragments[index])
+            }
+            None => Arc::clone(&self.records),
+        }
+    }
+
     fn reset_graylist(&mut self) {
         self.graylist.clear();
         // add back the sealed nodes to the gray list, those will never be writeable again.
@@ -328,7 +354,7 @@ impl<T: TransportConnect> SequencerAppender<T> {
                         sequencer_shared_state: self.sequencer_shared_state.clone(),
                         networking: self.networking.clone(),
                         first_offset: self.first_offset,
-                        records: self.records.clone(),
+                        records: self.records_for(node_id),
                         store_timeout,
                     };
                     async move { (node_id, store_task.run().await) }.in_current_tc()
@@ -445,6 +471,21 @@ impl<T: TransportConnect> SequencerAppender<T> {
     }
 }
 
+/// Encodes the records and groups their fragments into one batch per fragment index
+fn encode_fragments(erasure_coding: &ErasureCoding, records: &[Record]) -> Vec<Arc<[Record]>> {
+    let mut scratch = BytesMut::new();
+    let mut batches: Vec<Vec<Record>> = (0..erasure_coding.total_fragments())
+        .map(|_| Vec::with_capacity(records.len()))
+        .collect();
+    for record in records {
+        let fragments = erasure_coding.encode_record(record, &mut scratch);
+        for (batch, fragment) in batches.iter_mut().zip(fragments) {
+            batch.push(fragment);
+        }
+    }
+    batches.into_iter().map(Arc::from).collect()
+}
+
 #[derive(Default, Debug, PartialEq, Clone, Copy)]
 enum PerNodeStatus {
     #[default]
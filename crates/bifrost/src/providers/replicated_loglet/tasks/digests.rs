@@ -10,6 +10,7 @@
 
 use std::collections::BTreeMap;
 
+use bytes::BytesMut;
 use rand::rng;
 use tokio::task::JoinSet;
 use tracing::{debug, trace, warn};
@@ -21,7 +22,7 @@ use restate_types::net::log_server::{
     Digest, LogServerRequestHeader, RecordStatus, Status, Store, StoreFlags,
 };
 use restate_types::nodes_config::NodesConfiguration;
-use restate_types::replicated_loglet::ReplicatedLogletParams;
+use restate_types::replicated_loglet::{ErasureCoding, ReplicatedLogletParams};
 use restate_types::replication::{NodeSet, NodeSetChecker};
 use restate_types::{GenerationalNodeId, PlainNodeId};
 
@@ -47,6 +48,9 @@ pub struct Digests {
     offsets_under_repair: BTreeMap<LogletOffset, NodeSet>,
     known_nodes: NodeSet,
     spread_selector: SpreadSelector,
+    /// Set if the loglet is erasure-coded, in this case each node stores its own fragment of the
+    /// records.
+    erasure_coding: Option<ErasureCoding>,
     /// number of records we have re-replicated
     num_fixups: usize,
 }
@@ -80,6 +84,7 @@ impl Digests {
             known_nodes: Default::default(),
             offsets_under_repair: offsets,
             spread_selector,
+            erasure_coding: my_params.erasure_coding,
             num_fixups: 0,
         }
     }
@@ -227,6 +232,10 @@ impl Digests {
         // record is already replicated on those nodes
         replication_checker.set_attribute_on_each(known_copies.iter().copied(), true);
 
+        // Nodes of erasure-coded loglets need their own fragment of the record
+        let fragments = self
+            .erasure_coding
+            .map(|erasure_coding| erasure_coding.encode_record(&record, &mut BytesMut::new()));
         let payloads = vec![record].into();
 
         let msg = Store {
@@ -252,7 +261,14 @@ impl Digests {
                 .name("replicate-records")
                 .spawn({
                     let networking = networking.clone();
-                    let msg = msg.clone();
+                    let mut msg = msg.clone();
+                    if let Some(fragments) = &fragments {
+                        let fragment = self
+                            .fragment_index(node)
+                            .map(|index| fragments[index].clone())
+                            .expect("fixup nodes are part of the nodeset");
+                        msg.payloads = vec![fragment].into();
+                    }
                     let loglet_id = self.loglet_id;
 
                     async move {
@@ -375,15 +391,26 @@ impl Digests {
         }
         // when can we advance to repair from digest?
         // - When we have enough digests to find at least a single copy of the oldest record in the
-        // repair range.
+        // repair range. For erasure-coded loglets, we need enough fragments to reconstruct it.
         // - we have write-quorum of "writeable" nodes that responded to our digest requests (even if they
         // don't have the records)
-        !self
-            .offsets_under_repair
+        let required_copies = self
+            .erasure_coding
+            .map_or(1, |erasure_coding| erasure_coding.data_fragments());
+        self.offsets_under_repair
             .first_key_value()
             .expect("must have at least one if we are not finished")
             .1
-            .is_empty()
+            .len()
+            >= required_copies
+    }
+
+    /// The index of the fragment that the given node stores
+    fn fragment_index(&self, node_id: PlainNodeId) -> Option<usize> {
+        self.spread_selector
+            .nodeset()
+            .iter()
+            .position(|n| *n == node_id)
     }
 
     fn update_start_offset(&mut self, new_start_offset: LogletOffset) {
@@ -16,11 +16,15 @@ use tokio::sync::mpsc;
 use tracing::{debug, error, trace};
 
 use restate_core::{MetadataWriter, TaskCenterFutureExt, TaskKind, cancellation_watcher};
+#[cfg(feature = "object-store-loglet")]
+use restate_types::config::Configuration;
 #[cfg(feature = "local-loglet")]
 use restate_types::config::LocalLogletOptions;
 #[cfg(feature = "local-loglet")]
 use restate_types::live::BoxLiveLoad;
 use restate_types::logs::metadata::ProviderKind;
+#[cfg(feature = "object-store-loglet")]
+use restate_types::nodes_config::Role;
 
 use crate::bifrost::BifrostInner;
 #[cfg(any(test, feature = "memory-loglet"))]
@@ -149,8 +153,39 @@ impl BifrostService {
             .set(providers.clone())
             .map_err(|_| anyhow::anyhow!("bifrost must be initialized only once"))?;
 
+        #[cfg(feature = "object-store-loglet")]
+        let offloader_store = {
+            use crate::providers::object_store_loglet::{
+                ObjectStoreLogletProvider, ObjectStoreLogletStore,
+            };
+
+            let (options, is_admin) = {
+                let config = Configuration::pinned();
+                (
+                    config.bifrost.object_store_loglet.clone(),
+                    config.has_role(Role::Admin),
+                )
+            };
+            let local_store = ObjectStoreLogletStore::create_if_configured(&options).await?;
+            // Every node serves offloaded segments, they record the destination they were
+            // offloaded to.
+            self.inner
+                .object_store_provider
+                .set(Arc::new(ObjectStoreLogletProvider::new(
+                    local_store.clone(),
+                )))
+                .map_err(|_| anyhow::anyhow!("bifrost must be initialized only once"))?;
+            // Offloading runs on admin nodes only
+            local_store.filter(|_| is_admin && options.offload_sealed_segments)
+        };
+
         // We spawn the watchdog as a background long-running task
-        Watchdog::start(self.inner, self.watchdog_rx, self.metadata_writer)?;
+        Watchdog::start(self.inner.clone(), self.watchdog_rx, self.metadata_writer)?;
+
+        #[cfg(feature = "object-store-loglet")]
+        if let Some(store) = offloader_store {
+            crate::providers::object_store_loglet::Offloader::start(self.inner, store)?;
+        }
 
         // Bifrost started!
         Ok(())
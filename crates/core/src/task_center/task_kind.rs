@@ -118,6 +118,7 @@ pub enum TaskKind {
     LocalReactor,
     Shuffle,
     Cleaner,
+    ChangeShipper,
     LogTrimmer,
     MetadataServer,
     Background,
@@ -21,7 +21,7 @@ use restate_types::identifiers::{
 use restate_types::invocation::client::{
     AttachInvocationResponse, CancelInvocationResponse, GetInvocationOutputResponse,
     InvocationClient, InvocationClientError, InvocationOutput, KillInvocationResponse,
-    PatchDeploymentId, PauseInvocationResponse, PurgeInvocationResponse,
+    MigrateInvocationResponse, PatchDeploymentId, PauseInvocationResponse, PurgeInvocationResponse,
     RestartAsNewInvocationResponse, ResumeInvocationResponse, SubmittedInvocationNotification,
 };
 use restate_types::invocation::{InvocationQuery, InvocationRequest, InvocationResponse};
@@ -547,6 +547,32 @@ where
         })
     }
 
+    async fn migrate_invocation(
+        &self,
+        request_id: PartitionProcessorRpcRequestId,
+        invocation_id: InvocationId,
+        deployment_id: PatchDeploymentId,
+    ) -> Result<MigrateInvocationResponse, InvocationClientError> {
+        let response = self
+            .resolve_partition_id_and_send(
+                request_id,
+                PartitionProcessorRpcRequestInner::MigrateInvocation {
+                    invocation_id,
+                    deployment_id,
+                },
+            )
+            .await?;
+
+        Ok(match response {
+            PartitionProcessorRpcResponse::MigrateInvocation(migrate_invocation_response) => {
+                migrate_invocation_response.into()
+            }
+            _ => {
+                panic!("Expecting MigrateInvocation rpc response")
+            }
+        })
+    }
+
     async fn pause_invocation(
         &self,
         request_id: PartitionProcessorRpcRequestId,
@@ -13,8 +13,10 @@ use super::APPLICATION_JSON;
 use crate::RequestDispatcherError;
 use bytes::Bytes;
 use http::{Response, StatusCode, header};
+use restate_object_store_util::PayloadStoreError;
 use restate_types::errors::{IdDecodeError, InvocationError};
 use restate_types::identifiers::DeploymentId;
+use restate_types::invocation::offloaded_payload::ReservedPayloadPrefixError;
 use restate_types::schema::invocation_target::InputValidationError;
 use serde::Serialize;
 use std::string;
@@ -57,12 +59,18 @@ pub(crate) enum HandlerError {
     BadHeader(header::HeaderName, #[source] header::ToStrError),
     #[error("bad delay query parameter, must be a ISO8601 duration: {0}")]
     BadDelayDuration(String),
+    #[error("bad x-restate-deadline header, must be a ISO8601 or friendly duration: {0}")]
+    BadDeadlineDuration(String),
     #[error("bad path, cannot decode key: {0:?}")]
     UrlDecodingError(string::FromUtf8Error),
     #[error("the invoked service is not public")]
     PrivateService,
     #[error("cannot read body: {0:?}")]
     Body(anyhow::Error),
+    #[error("cannot access the offloaded payload: {0}")]
+    PayloadOffloading(#[from] PayloadStoreError),
+    #[error("bad body: {0}")]
+    ReservedPayloadPrefix(#[from] ReservedPayloadPrefixError),
     #[error("unavailable")]
     Unavailable,
     #[error("the invocation exists but has not completed yet")]
@@ -124,6 +132,7 @@ impl HandlerError {
             | HandlerError::PrivateService
             | HandlerError::UrlDecodingError(_)
             | HandlerError::BadDelayDuration(_)
+            | HandlerError::BadDeadlineDuration(_)
             | HandlerError::BadAwakeablesPath
             | HandlerError::UnsupportedDelay
             | HandlerError::BadHeader(_, _)
@@ -132,6 +141,7 @@ impl HandlerError {
             | HandlerError::BadInvocationId(_, _)
             | HandlerError::BadWorkflowPath
             | HandlerError::InputValidation(_)
+            | HandlerError::ReservedPayloadPrefix(_)
             | HandlerError::UnsupportedIdempotencyKey
             | HandlerError::UnsupportedGetOutput
             | HandlerError::DeploymentDeprecated(_, _) => StatusCode::BAD_REQUEST,
@@ -139,7 +149,9 @@ impl HandlerError {
                 // TODO add more distinctions between different dispatcher errors (unavailable, etc)
                 StatusCode::INTERNAL_SERVER_ERROR
             }
-            HandlerError::Body(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            HandlerError::Body(_) | HandlerError::PayloadOffloading(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
             HandlerError::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
             HandlerError::MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
             HandlerError::NotImplemented => StatusCode::NOT_IMPLEMENTED,
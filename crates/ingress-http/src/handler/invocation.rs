@@ -105,6 +105,8 @@ where
             }
             AttachInvocationResponse::Ready(response) => response,
         };
+        let response =
+            Self::rehydrate_invocation_response(self.payload_store.as_ref(), response).await?;
 
         Self::reply_with_invocation_response(response, move |invocation_target| {
             self.schemas
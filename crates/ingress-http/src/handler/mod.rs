@@ -30,6 +30,7 @@ use http_body_util::Full;
 use hyper::http::HeaderValue;
 use hyper::{Request, Response};
 use path_parsing::RequestType;
+use restate_object_store_util::PayloadStore;
 use restate_types::live::Live;
 use restate_types::schema::invocation_target::InvocationTargetResolver;
 use restate_types::schema::service::ServiceMetadataResolver;
@@ -42,13 +43,19 @@ const APPLICATION_JSON: HeaderValue = HeaderValue::from_static("application/json
 pub(crate) struct Handler<Schemas, Dispatcher> {
     schemas: Live<Schemas>,
     dispatcher: Dispatcher,
+    payload_store: Option<PayloadStore>,
 }
 
 impl<Schemas, Dispatcher> Handler<Schemas, Dispatcher> {
-    pub(crate) fn new(schemas: Live<Schemas>, dispatcher: Dispatcher) -> Self {
+    pub(crate) fn new(
+        schemas: Live<Schemas>,
+        dispatcher: Dispatcher,
+        payload_store: Option<PayloadStore>,
+    ) -> Self {
         Self {
             schemas,
             dispatcher,
+            payload_store,
         }
     }
 }
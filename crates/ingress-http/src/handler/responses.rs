@@ -14,6 +14,7 @@ use bytes::Bytes;
 use chrono::DateTime;
 use http::{HeaderName, Response, header};
 use http_body_util::Full;
+use restate_object_store_util::PayloadStore;
 use restate_types::invocation::InvocationTarget;
 use restate_types::invocation::client::{InvocationOutput, InvocationOutputResponse};
 use restate_types::schema::invocation_target::InvocationTargetMetadata;
@@ -24,6 +25,24 @@ pub(crate) const IDEMPOTENCY_EXPIRES: HeaderName = HeaderName::from_static("idem
 pub(crate) const X_RESTATE_ID: HeaderName = HeaderName::from_static("x-restate-id");
 
 impl<Schemas, Dispatcher> Handler<Schemas, Dispatcher> {
+    /// Loads the response payload from the object store if the partition processor offloaded it.
+    pub(crate) async fn rehydrate_invocation_response(
+        payload_store: Option<&PayloadStore>,
+        mut output: InvocationOutput,
+    ) -> Result<InvocationOutput, HandlerError> {
+        if let (
+            Some(payload_store),
+            Some(invocation_id),
+            InvocationOutputResponse::Success(_, payload),
+        ) = (payload_store, output.invocation_id, &mut output.response)
+        {
+            *payload = payload_store
+                .rehydrate(&invocation_id, std::mem::take(payload))
+                .await?;
+        }
+        Ok(output)
+    }
+
     pub(crate) fn reply_with_invocation_response(
         InvocationOutput {
             response,
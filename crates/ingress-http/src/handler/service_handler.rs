@@ -28,7 +28,9 @@ use super::{APPLICATION_JSON, Handler};
 use crate::RequestDispatcher;
 use crate::handler::responses::{IDEMPOTENCY_EXPIRES, X_RESTATE_ID};
 use crate::metric_definitions::{INGRESS_REQUEST_DURATION, INGRESS_REQUESTS, REQUEST_COMPLETED};
+use restate_object_store_util::PayloadStore;
 use restate_types::identifiers::{InvocationId, WithInvocationId};
+use restate_types::invocation::offloaded_payload::OffloadedPayload;
 use restate_types::invocation::{
     Header, InvocationRequest, InvocationRequestHeader, InvocationTarget, InvocationTargetType,
     SpanRelation, WorkflowHandlerType,
@@ -39,6 +41,7 @@ use restate_types::schema::invocation_target::{
 use restate_types::time::MillisSinceEpoch;
 
 pub(crate) const IDEMPOTENCY_KEY: HeaderName = HeaderName::from_static("idempotency-key");
+pub(crate) const X_RESTATE_DEADLINE: HeaderName = HeaderName::from_static("x-restate-deadline");
 const DELAY_QUERY_PARAM: &str = "delay";
 const X_RESTATE_INGRESS_PATH: ByteString = ByteString::from_static("x-restate-ingress-path");
 
@@ -118,6 +121,9 @@ where
         let invocation_retention =
             invocation_target_meta.compute_retention(idempotency_key.is_some());
 
+        // Check if the deadline is overridden by the request, otherwise use the handler default
+        let deadline = parse_deadline(req.headers())?;
+
         // Craft Invocation Target and Id
         let invocation_target = if let TargetType::Keyed { key } = target {
             match invocation_target_meta.target_ty {
@@ -180,6 +186,16 @@ where
                     .transpose()?,
                 &body,
             )?;
+            // Bodies must not be mistaken for references to offloaded payloads
+            OffloadedPayload::check_not_reference(&body)?;
+
+            // Offload large inputs to the object store
+            let body = match &self.payload_store {
+                Some(payload_store) if payload_store.should_offload(body.len()) => {
+                    payload_store.offload_input(&invocation_id, body).await?
+                }
+                _ => body,
+            };
 
             // Parse delay query parameter
             let delay = parse_delay(parts.uri.query())?;
@@ -197,6 +213,14 @@ where
             }
             invocation_request_header.headers = headers;
 
+            let execution_time = delay.map(|d| MillisSinceEpoch::from(SystemTime::now() + d));
+            invocation_request_header.deadline = match deadline {
+                Some(deadline) => {
+                    Some(execution_time.unwrap_or_else(MillisSinceEpoch::now) + deadline)
+                }
+                None => invocation_target_meta.compute_deadline(execution_time),
+            };
+
             match invoke_ty {
                 InvokeType::Call => {
                     if delay.is_some() {
@@ -206,12 +230,12 @@ where
                         Arc::new(InvocationRequest::new(invocation_request_header, body)),
                         invocation_target_meta,
                         self.dispatcher,
+                        self.payload_store,
                     )
                     .await
                 }
                 InvokeType::Send => {
-                    invocation_request_header.execution_time =
-                        delay.map(|d| SystemTime::now() + d).map(Into::into);
+                    invocation_request_header.execution_time = execution_time;
 
                     Self::handle_service_send(
                         Arc::new(InvocationRequest::new(invocation_request_header, body)),
@@ -244,11 +268,14 @@ where
         invocation_request: Arc<InvocationRequest>,
         invocation_target_metadata: InvocationTargetMetadata,
         dispatcher: Dispatcher,
+        payload_store: Option<PayloadStore>,
     ) -> Result<Response<Full<Bytes>>, HandlerError> {
         let response = dispatcher
             .call(invocation_request)
             .instrument(trace_span!("Waiting for response"))
             .await?;
+        let response =
+            Self::rehydrate_invocation_response(payload_store.as_ref(), response).await?;
 
         Self::reply_with_invocation_response(response, move |_| Ok(invocation_target_metadata))
     }
@@ -311,6 +338,7 @@ fn parse_headers(parts: http::request::Parts) -> Result<Vec<Header>, HandlerErro
             || k == header::HOST
             || k == IDEMPOTENCY_KEY
             || k == IDEMPOTENCY_EXPIRES
+            || k == X_RESTATE_DEADLINE
         {
             continue;
         }
@@ -349,6 +377,21 @@ fn parse_delay(query: Option<&str>) -> Result<Option<Duration>, HandlerError> {
     Ok(None)
 }
 
+fn parse_deadline(headers: &HeaderMap) -> Result<Option<Duration>, HandlerError> {
+    let Some(deadline) = headers.get(X_RESTATE_DEADLINE) else {
+        return Ok(None);
+    };
+
+    let deadline = deadline
+        .to_str()
+        .map_err(|e| HandlerError::BadHeader(X_RESTATE_DEADLINE, e))?;
+    Ok(Some(
+        DurationQueryParam::deserialize(deadline.into_deserializer())
+            .map_err(|e: serde::de::value::Error| HandlerError::BadDeadlineDuration(e.to_string()))?
+            .0,
+    ))
+}
+
 fn parse_idempotency(headers: &HeaderMap) -> Result<Option<ByteString>, HandlerError> {
     let idempotency_key = if let Some(idempotency_key) = headers.get(IDEMPOTENCY_KEY) {
         ByteString::from(
@@ -390,4 +433,28 @@ mod tests {
             Duration::from_millis(60000),
         );
     }
+
+    #[test]
+    fn deadline() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(parse_deadline(&headers).unwrap(), None);
+
+        headers.insert(X_RESTATE_DEADLINE, "PT60S".parse().unwrap());
+        assert_eq!(
+            parse_deadline(&headers).unwrap().unwrap(),
+            Duration::from_secs(60),
+        );
+
+        headers.insert(X_RESTATE_DEADLINE, "5 minutes".parse().unwrap());
+        assert_eq!(
+            parse_deadline(&headers).unwrap().unwrap(),
+            Duration::from_secs(300),
+        );
+
+        headers.insert(X_RESTATE_DEADLINE, "not a duration".parse().unwrap());
+        assert!(matches!(
+            parse_deadline(&headers),
+            Err(HandlerError::BadDeadlineDuration(_))
+        ));
+    }
 }
@@ -28,6 +28,7 @@ use restate_types::invocation::client::{
     AttachInvocationResponse, GetInvocationOutputResponse, InvocationOutput,
     InvocationOutputResponse, SubmittedInvocationNotification,
 };
+use restate_types::invocation::offloaded_payload::OffloadedPayload;
 use restate_types::invocation::{
     InvocationQuery, InvocationTarget, InvocationTargetType, VirtualObjectHandlerType,
     WorkflowHandlerType,
@@ -972,6 +973,25 @@ async fn invalid_input() {
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
 
+#[restate_core::test]
+#[traced_test]
+async fn reject_body_with_offloaded_payload_prefix() {
+    let response = handle_with_schemas_and_dispatcher(
+        hyper::Request::post("http://localhost/greeter.Greeter/greet")
+            .body(Full::new(OffloadedPayload::input(1024).encode()))
+            .unwrap(),
+        MockSchemas::default().with_service_and_target(
+            "greeter.Greeter",
+            "greet",
+            InvocationTargetMetadata::mock(InvocationTargetType::Service),
+        ),
+        // the request must not be dispatched
+        MockRequestDispatcher::default(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
 #[restate_core::test]
 #[traced_test]
 async fn set_custom_content_type_on_response() {
@@ -1143,7 +1163,8 @@ where
         .insert(ConnectInfo::new(SocketAddress::Anonymous));
     req.extensions_mut().insert(opentelemetry::Context::new());
 
-    let handler_fut = Handler::new(Live::from_value(schemas), Arc::new(dispatcher)).oneshot(req);
+    let handler_fut =
+        Handler::new(Live::from_value(schemas), Arc::new(dispatcher), None).oneshot(req);
 
     handler_fut.await.unwrap()
 }
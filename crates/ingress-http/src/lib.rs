@@ -120,7 +120,7 @@ mod mocks {
     use restate_types::schema::invocation_target::test_util::MockInvocationTargetResolver;
     use restate_types::schema::invocation_target::{
         DEFAULT_IDEMPOTENCY_RETENTION, InvocationAttemptOptions, InvocationTargetMetadata,
-        InvocationTargetResolver, OnMaxAttempts,
+        InvocationTargetResolver, OnMaxAttempts, ResolvedRetryRule,
     };
     use restate_types::schema::service::test_util::MockServiceMetadataResolver;
     use restate_types::schema::service::{
@@ -174,6 +174,8 @@ mod mocks {
                         input_json_schema: None,
                         output_json_schema: None,
                         retry_policy: Default::default(),
+                        retry_rules: vec![],
+                        deadline: None,
                         info: vec![],
                     },
                 )]),
@@ -269,6 +271,16 @@ mod mocks {
             self.1
                 .resolve_invocation_retry_policy(deployment_id, service_name, handler_name)
         }
+
+        fn resolve_invocation_retry_rules(
+            &self,
+            deployment_id: Option<&DeploymentId>,
+            service_name: impl AsRef<str>,
+            handler_name: impl AsRef<str>,
+        ) -> Vec<ResolvedRetryRule> {
+            self.1
+                .resolve_invocation_retry_rules(deployment_id, service_name, handler_name)
+        }
     }
 
     pub(super) fn mock_schemas() -> MockSchemas {
@@ -27,6 +27,7 @@ use tower_http::trace::TraceLayer;
 use tracing::{Span, debug, info, info_span, instrument};
 
 use restate_core::{TaskCenter, TaskKind, cancellation_watcher};
+use restate_object_store_util::PayloadStore;
 use restate_time_util::DurationExt;
 use restate_types::config::IngressOptions;
 use restate_types::health::HealthStatus;
@@ -54,6 +55,7 @@ pub struct HyperServerIngress<Schemas, Dispatcher> {
     // Parameters to build the layers
     schemas: Live<Schemas>,
     dispatcher: Dispatcher,
+    payload_store: Option<PayloadStore>,
 
     health: HealthStatus<IngressStatus>,
 }
@@ -68,6 +70,7 @@ where
         listeners: Listeners<HttpIngressPort>,
         dispatcher: Dispatcher,
         schemas: Live<Schemas>,
+        payload_store: Option<PayloadStore>,
         health: HealthStatus<IngressStatus>,
     ) -> HyperServerIngress<Schemas, Dispatcher> {
         crate::metric_definitions::describe_metrics();
@@ -76,6 +79,7 @@ where
             ingress_options.concurrent_api_requests_limit(),
             schemas,
             dispatcher,
+            payload_store,
             health,
         )
     }
@@ -91,6 +95,7 @@ where
         concurrency_limit: usize,
         schemas: Live<Schemas>,
         dispatcher: Dispatcher,
+        payload_store: Option<PayloadStore>,
         health: HealthStatus<IngressStatus>,
     ) -> Self {
         health.update(IngressStatus::StartingUp);
@@ -100,6 +105,7 @@ where
             concurrency_limit,
             schemas,
             dispatcher,
+            payload_store,
             health,
         }
     }
@@ -116,6 +122,7 @@ where
             concurrency_limit,
             schemas,
             dispatcher,
+            payload_store,
             health,
         } = self;
 
@@ -172,7 +179,7 @@ where
             .layer(layers::load_shed::LoadShedLayer::new(concurrency_limit))
             .layer(CorsLayer::very_permissive())
             .layer(layers::tracing_context_extractor::HttpTraceContextExtractorLayer)
-            .service(Handler::new(schemas, dispatcher));
+            .service(Handler::new(schemas, dispatcher, payload_store));
 
         let mut shutdown = std::pin::pin!(cancellation_watcher());
 
@@ -404,6 +411,7 @@ mod tests {
             Semaphore::MAX_PERMITS,
             Live::from_value(mock_schemas()),
             Arc::new(mock_request_dispatcher),
+            None,
             health.ingress_status(),
         );
         TaskCenter::spawn(TaskKind::SystemService, "ingress", ingress.run()).unwrap();
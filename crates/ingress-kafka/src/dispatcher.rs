@@ -20,6 +20,7 @@ use tracing::debug;
 use restate_bifrost::Bifrost;
 use restate_storage_api::deduplication_table::DedupInformation;
 use restate_types::identifiers::{InvocationId, PartitionKey, WithPartitionKey, partitioner};
+use restate_types::invocation::offloaded_payload::OffloadedPayload;
 use restate_types::invocation::{InvocationTarget, ServiceInvocation, SpanRelation};
 use restate_types::live;
 use restate_types::message::MessageIndex;
@@ -132,6 +133,9 @@ impl KafkaIngressEvent {
             offset,
         );
 
+        // Records must not be mistaken for references to offloaded payloads
+        OffloadedPayload::check_not_reference(&payload)?;
+
         // Finally generate service invocation
         let mut service_invocation = Box::new(ServiceInvocation::initialize(
             invocation_id,
@@ -142,6 +146,7 @@ impl KafkaIngressEvent {
         service_invocation.argument = payload;
         service_invocation.headers = headers;
         service_invocation.with_retention(invocation_retention);
+        service_invocation.deadline = target.compute_deadline(None);
 
         Ok(KafkaIngressEvent {
             service_invocation,
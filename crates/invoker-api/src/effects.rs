@@ -61,6 +61,13 @@ pub enum EffectKind {
     },
     JournalEvent {
         event: RawEvent,
+        /// The same event was already sent for the previous attempt. It's accounted for in the
+        /// invocation status, but not stored again.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "std::ops::Not::not")
+        )]
+        duplicate: bool,
     },
     SuspendedV2 {
         waiting_for_notifications: HashSet<journal_v2::NotificationId>,
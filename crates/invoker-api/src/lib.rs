@@ -18,7 +18,10 @@ pub use effects::*;
 pub use entry_enricher::EntryEnricher;
 pub use handle::*;
 pub use invocation_reader::JournalMetadata;
-pub use status_handle::{InvocationErrorReport, InvocationStatusReport, StatusHandle};
+pub use status_handle::{
+    CircuitBreakerReport, CircuitBreakerState, InvocationErrorReport, InvocationStatusReport,
+    StatusHandle,
+};
 
 #[cfg(any(test, feature = "test-util"))]
 pub mod test_util {
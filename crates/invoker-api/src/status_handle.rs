@@ -14,6 +14,7 @@ use restate_types::identifiers::{DeploymentId, InvocationId, PartitionKey};
 use restate_types::identifiers::{LeaderEpoch, PartitionId, PartitionLeaderEpoch};
 use restate_types::journal::{EntryIndex, EntryType};
 use restate_types::service_protocol::ServiceProtocolVersion;
+use std::fmt;
 use std::future::Future;
 use std::ops::RangeInclusive;
 use std::time::SystemTime;
@@ -117,6 +118,40 @@ pub struct InvocationErrorReport {
     pub related_entry_type: Option<EntryType>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitBreakerState {
+    /// Invocations reach the deployment.
+    Closed,
+    /// The deployment is considered down, invocations are parked.
+    Open,
+    /// A probe request is checking whether the deployment recovered.
+    HalfOpen,
+}
+
+impl CircuitBreakerState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CircuitBreakerState::Closed => "closed",
+            CircuitBreakerState::Open => "open",
+            CircuitBreakerState::HalfOpen => "half_open",
+        }
+    }
+}
+
+impl fmt::Display for CircuitBreakerState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerReport {
+    pub deployment_id: DeploymentId,
+    pub state: CircuitBreakerState,
+    pub consecutive_failures: u32,
+    pub last_transition_at: SystemTime,
+}
+
 /// Struct to access the status of the invocations currently handled by the invoker
 pub trait StatusHandle {
     type Iterator: Iterator<Item = InvocationStatusReport> + Send;
@@ -129,6 +164,10 @@ pub trait StatusHandle {
         &self,
         keys: RangeInclusive<PartitionKey>,
     ) -> impl Future<Output = Self::Iterator> + Send;
+
+    /// This method returns a snapshot of the circuit breakers of the deployments this invoker
+    /// has talked to. Deployments without a report have a closed circuit breaker.
+    fn read_circuit_breakers(&self) -> Vec<CircuitBreakerReport>;
 }
 
 #[cfg(any(test, feature = "test-util"))]
@@ -151,5 +190,9 @@ pub mod test_util {
         async fn read_status(&self, _keys: RangeInclusive<PartitionKey>) -> Self::Iterator {
             self.0.clone().into_iter()
         }
+
+        fn read_circuit_breakers(&self) -> Vec<CircuitBreakerReport> {
+            Vec::new()
+        }
     }
 }
@@ -0,0 +1,326 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+use dashmap::DashMap;
+use metrics::counter;
+
+use restate_invoker_api::{CircuitBreakerReport, CircuitBreakerState};
+use restate_types::config::CircuitBreakerOptions;
+use restate_types::identifiers::DeploymentId;
+
+use crate::metric_definitions::INVOKER_CIRCUIT_BREAKER_TRANSITIONS;
+
+/// Circuit breakers of the deployments, shared by all the invokers running on this node.
+///
+/// A circuit breaker opens after [`CircuitBreakerOptions::failure_threshold`] consecutive attempts
+/// failed because the deployment could not be reached or answered with a server error. While
+/// open, invocation tasks targeting the deployment fail fast without connecting to it. Once
+/// [`CircuitBreakerOptions::open_duration`] elapsed, a single probe attempt is let through: if it
+/// succeeds the circuit breaker closes again, otherwise it re-opens.
+#[derive(Debug, Clone, Default)]
+pub struct DeploymentCircuitBreakers(Arc<DashMap<DeploymentId, CircuitBreaker>>);
+
+impl DeploymentCircuitBreakers {
+    /// Checks whether an attempt can contact the given deployment. If not, returns how long to
+    /// wait before trying again.
+    pub(crate) fn acquire(
+        &self,
+        deployment_id: DeploymentId,
+        options: &CircuitBreakerOptions,
+    ) -> Result<(), Duration> {
+        if !options.enabled {
+            return Ok(());
+        }
+        match self.0.get_mut(&deployment_id) {
+            Some(mut circuit_breaker) => circuit_breaker.acquire(Instant::now(), options),
+            None => Ok(()),
+        }
+    }
+
+    /// Records an attempt that failed because the deployment was unavailable. Returns how long
+    /// the affected invocation should be parked for, if the circuit breaker is open.
+    pub(crate) fn record_failure(
+        &self,
+        deployment_id: DeploymentId,
+        options: &CircuitBreakerOptions,
+    ) -> Option<Duration> {
+        if !options.enabled {
+            return None;
+        }
+        self.0
+            .entry(deployment_id)
+            .or_insert_with(CircuitBreaker::new)
+            .record_failure(Instant::now(), options)
+    }
+
+    /// Records an attempt that reached the deployment. Only the circuit breakers of the
+    /// deployments currently failing are kept.
+    pub(crate) fn record_success(&self, deployment_id: DeploymentId) {
+        if let Some((_, mut circuit_breaker)) = self.0.remove(&deployment_id) {
+            circuit_breaker.record_success();
+        }
+    }
+
+    /// Drops the circuit breakers of the deployments which are no longer registered.
+    pub fn retain_deployments(&self, is_registered: impl Fn(&DeploymentId) -> bool) {
+        self.0
+            .retain(|deployment_id, _| is_registered(deployment_id));
+    }
+
+    pub fn reports(&self) -> Vec<CircuitBreakerReport> {
+        self.0
+            .iter()
+            .map(|entry| CircuitBreakerReport {
+                deployment_id: *entry.key(),
+                state: entry.value().state(),
+                consecutive_failures: entry.value().consecutive_failures,
+                last_transition_at: entry.value().last_transition_at,
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Closed,
+    Open { until: Instant },
+    HalfOpen { probe_started_at: Instant },
+}
+
+#[derive(Debug)]
+struct CircuitBreaker {
+    state: State,
+    consecutive_failures: u32,
+    last_transition_at: SystemTime,
+}
+
+impl CircuitBreaker {
+    fn new() -> Self {
+        Self {
+            state: State::Closed,
+            consecutive_failures: 0,
+            last_transition_at: SystemTime::now(),
+        }
+    }
+
+    fn state(&self) -> CircuitBreakerState {
+        match self.state {
+            State::Closed => CircuitBreakerState::Closed,
+            State::Open { .. } => CircuitBreakerState::Open,
+            State::HalfOpen { .. } => CircuitBreakerState::HalfOpen,
+        }
+    }
+
+    fn acquire(&mut self, now: Instant, options: &CircuitBreakerOptions) -> Result<(), Duration> {
+        let open_duration = options.open_duration.to_std();
+        match self.state {
+            State::Closed => Ok(()),
+            State::Open { until } if now < until => Err(until - now),
+            State::Open { .. } => {
+                // Let a single probe through
+                self.transition_to(State::HalfOpen {
+                    probe_started_at: now,
+                });
+                Ok(())
+            }
+            State::HalfOpen { probe_started_at } => {
+                let elapsed = now.saturating_duration_since(probe_started_at);
+                if elapsed < open_duration {
+                    Err(open_duration - elapsed)
+                } else {
+                    // The probe never reported back, most likely it was aborted. Let another one through.
+                    self.state = State::HalfOpen {
+                        probe_started_at: now,
+                    };
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    fn record_failure(
+        &mut self,
+        now: Instant,
+        options: &CircuitBreakerOptions,
+    ) -> Option<Duration> {
+        let open_duration = options.open_duration.to_std();
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        match self.state {
+            State::Closed if self.consecutive_failures < options.failure_threshold.get() => None,
+            State::Closed | State::HalfOpen { .. } => {
+                self.transition_to(State::Open {
+                    until: now + open_duration,
+                });
+                Some(open_duration)
+            }
+            State::Open { until } => Some(until.saturating_duration_since(now)),
+        }
+    }
+
+    fn record_success(&mut self) {
+        self.consecutive_failures = 0;
+        if self.state != State::Closed {
+            self.transition_to(State::Closed);
+        }
+    }
+
+    fn transition_to(&mut self, state: State) {
+        self.state = state;
+        self.last_transition_at = SystemTime::now();
+        counter!(INVOKER_CIRCUIT_BREAKER_TRANSITIONS, "state" => self.state().as_str())
+            .increment(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::num::NonZeroU32;
+
+    use restate_time_util::NonZeroFriendlyDuration;
+    use restate_types::config::CircuitBreakerOptionsBuilder;
+
+    const OPEN_DURATION: Duration = Duration::from_secs(10);
+
+    fn options() -> CircuitBreakerOptions {
+        CircuitBreakerOptionsBuilder::default()
+            .enabled(true)
+            .failure_threshold(NonZeroU32::new(3).unwrap())
+            .open_duration(NonZeroFriendlyDuration::from_secs_unchecked(
+                OPEN_DURATION.as_secs(),
+            ))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn opens_after_consecutive_failures() {
+        let options = options();
+        let now = Instant::now();
+        let mut circuit_breaker = CircuitBreaker::new();
+
+        assert_eq!(circuit_breaker.record_failure(now, &options), None);
+        assert_eq!(circuit_breaker.record_failure(now, &options), None);
+        assert_eq!(circuit_breaker.acquire(now, &options), Ok(()));
+
+        assert_eq!(
+            circuit_breaker.record_failure(now, &options),
+            Some(OPEN_DURATION)
+        );
+        assert_eq!(circuit_breaker.state(), CircuitBreakerState::Open);
+        assert_eq!(
+            circuit_breaker.acquire(now + Duration::from_secs(4), &options),
+            Err(Duration::from_secs(6))
+        );
+    }
+
+    #[test]
+    fn success_resets_consecutive_failures() {
+        let options = options();
+        let now = Instant::now();
+        let mut circuit_breaker = CircuitBreaker::new();
+
+        circuit_breaker.record_failure(now, &options);
+        circuit_breaker.record_failure(now, &options);
+        circuit_breaker.record_success();
+        assert_eq!(circuit_breaker.record_failure(now, &options), None);
+        assert_eq!(circuit_breaker.state(), CircuitBreakerState::Closed);
+        assert_eq!(circuit_breaker.consecutive_failures, 1);
+    }
+
+    #[test]
+    fn half_open_lets_a_single_probe_through() {
+        let options = options();
+        let now = Instant::now();
+        let mut circuit_breaker = CircuitBreaker::new();
+        for _ in 0..3 {
+            circuit_breaker.record_failure(now, &options);
+        }
+
+        let after_open = now + OPEN_DURATION;
+        assert_eq!(circuit_breaker.acquire(after_open, &options), Ok(()));
+        assert_eq!(circuit_breaker.state(), CircuitBreakerState::HalfOpen);
+        assert_eq!(
+            circuit_breaker.acquire(after_open + Duration::from_secs(1), &options),
+            Err(Duration::from_secs(9))
+        );
+
+        // Probe never reported back, let another one through
+        assert_eq!(
+            circuit_breaker.acquire(after_open + OPEN_DURATION, &options),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn probe_outcome_closes_or_reopens() {
+        let options = options();
+        let now = Instant::now();
+        let mut circuit_breaker = CircuitBreaker::new();
+        for _ in 0..3 {
+            circuit_breaker.record_failure(now, &options);
+        }
+
+        let after_open = now + OPEN_DURATION;
+        circuit_breaker.acquire(after_open, &options).unwrap();
+        assert_eq!(
+            circuit_breaker.record_failure(after_open, &options),
+            Some(OPEN_DURATION)
+        );
+        assert_eq!(circuit_breaker.state(), CircuitBreakerState::Open);
+
+        let after_reopen = after_open + OPEN_DURATION;
+        circuit_breaker.acquire(after_reopen, &options).unwrap();
+        circuit_breaker.record_success();
+        assert_eq!(circuit_breaker.state(), CircuitBreakerState::Closed);
+        assert_eq!(circuit_breaker.acquire(after_reopen, &options), Ok(()));
+    }
+
+    #[test]
+    fn disabled_circuit_breaker_never_opens() {
+        let mut options = options();
+        options.enabled = false;
+        let circuit_breakers = DeploymentCircuitBreakers::default();
+        let deployment_id = DeploymentId::new();
+
+        for _ in 0..10 {
+            assert_eq!(
+                circuit_breakers.record_failure(deployment_id, &options),
+                None
+            );
+        }
+        assert_eq!(circuit_breakers.acquire(deployment_id, &options), Ok(()));
+        assert!(circuit_breakers.reports().is_empty());
+    }
+
+    #[test]
+    fn only_failing_registered_deployments_are_tracked() {
+        let options = options();
+        let circuit_breakers = DeploymentCircuitBreakers::default();
+        let recovered = DeploymentId::new();
+        let removed = DeploymentId::new();
+        let failing = DeploymentId::new();
+
+        for deployment_id in [recovered, removed, failing] {
+            circuit_breakers.record_failure(deployment_id, &options);
+        }
+        circuit_breakers.record_success(recovered);
+        circuit_breakers.retain_deployments(|deployment_id| *deployment_id != removed);
+
+        let reports = circuit_breakers.reports();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].deployment_id, failing);
+        assert_eq!(reports[0].consecutive_failures, 1);
+    }
+}
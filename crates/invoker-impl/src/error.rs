@@ -10,16 +10,18 @@
 
 use http::{HeaderName, HeaderValue};
 use restate_invoker_api::InvocationErrorReport;
-use restate_service_client::ServiceClientError;
+use restate_service_client::{HttpError, ServiceClientError};
 use restate_service_protocol::message::{EncodingError, MessageType};
-use restate_time_util::FriendlyDuration;
+use restate_time_util::{DurationExt, FriendlyDuration};
 use restate_types::errors::{InvocationError, InvocationErrorCode, codes};
 use restate_types::identifiers::DeploymentId;
 use restate_types::invocation::InvocationEpoch;
+use restate_types::invocation::offloaded_payload::ReservedPayloadPrefixError;
 use restate_types::journal::raw::RawEntryCodecError;
 use restate_types::journal::{EntryIndex, EntryType};
 use restate_types::journal_v2;
 use restate_types::journal_v2::CommandIndex;
+use restate_types::schema::service::ErrorClass;
 use restate_types::service_protocol::{
     MAX_INFLIGHT_SERVICE_PROTOCOL_VERSION, MIN_INFLIGHT_SERVICE_PROTOCOL_VERSION,
     ServiceProtocolVersion,
@@ -111,6 +113,9 @@ pub(crate) enum InvokerError {
     #[error("error when trying to read the service instance state: {0}")]
     #[code(restate_errors::RT0006)]
     StateReader(anyhow::Error),
+    #[error("error when trying to access the offloaded payload: {0}")]
+    #[code(restate_errors::RT0006)]
+    OffloadedPayload(anyhow::Error),
     #[error(
         "error when reading the journal: actual epoch {actual} != expected epoch {expected}. This is expected to happen while a trim and restart is being processed."
     )]
@@ -172,6 +177,11 @@ pub(crate) enum InvokerError {
     #[error("service is temporary unavailable '{0}'")]
     #[code(restate_errors::RT0010)]
     ServiceUnavailable(http::StatusCode),
+    #[error(
+        "the circuit breaker of deployment '{0}' is open because of consecutive failures, not going to contact the deployment for the next {}", .1.friendly()
+    )]
+    #[code(restate_errors::RT0010)]
+    DeploymentCircuitBreakerOpen(DeploymentId, Duration),
 }
 
 impl InvokerError {
@@ -193,12 +203,47 @@ impl InvokerError {
         !matches!(self, InvokerError::NotInvoked)
     }
 
+    /// Returns true if the error indicates the deployment could not be reached or failed to
+    /// handle the request, and should therefore count towards tripping its circuit breaker.
+    pub(crate) fn is_deployment_unavailable(&self) -> bool {
+        match self {
+            InvokerError::Client(e) => e.is_retryable(),
+            InvokerError::ServiceUnavailable(_) => true,
+            InvokerError::UnexpectedResponse(status_code) => status_code.is_server_error(),
+            _ => false,
+        }
+    }
+
+    /// Returns the class of this error, together with its HTTP status code or error code if any.
+    /// Used to match the retry rules of the handler.
+    pub(crate) fn error_class(&self) -> Option<(ErrorClass, Option<u16>)> {
+        match self {
+            InvokerError::UnexpectedResponse(status_code)
+            | InvokerError::ServiceUnavailable(status_code) => {
+                Some((ErrorClass::HttpStatus, Some(status_code.as_u16())))
+            }
+            InvokerError::Client(e)
+                if matches!(**e, ServiceClientError::Http(_, HttpError::Connect(_))) =>
+            {
+                Some((ErrorClass::ConnectionRefused, None))
+            }
+            InvokerError::AbortTimeoutFired(_) => Some((ErrorClass::Timeout, None)),
+            InvokerError::Sdk(SdkInvocationError { error, .. })
+            | InvokerError::SdkV2(SdkInvocationErrorV2 { error, .. }) => {
+                Some((ErrorClass::ErrorCode, Some(error.code().into())))
+            }
+            _ => None,
+        }
+    }
+
     pub(crate) fn should_bump_start_message_retry_count_since_last_stored_entry(&self) -> bool {
         !matches!(
             self,
             InvokerError::NotInvoked
+                | InvokerError::DeploymentCircuitBreakerOpen(_, _)
                 | InvokerError::JournalReader(_)
                 | InvokerError::StateReader(_)
+                | InvokerError::OffloadedPayload(_)
                 | InvokerError::NoDeploymentForService
                 | InvokerError::BadNegotiatedServiceProtocolVersion(_)
                 | InvokerError::UnknownDeployment(_)
@@ -296,6 +341,8 @@ pub(crate) enum CommandPreconditionError {
     #[error("the service {0} is exposed by the deprecated deployment {1}.")]
     #[code(restate_errors::RT0020)]
     DeploymentDeprecated(String, DeploymentId),
+    #[error(transparent)]
+    ReservedPayloadPrefix(#[from] ReservedPayloadPrefixError),
 }
 
 #[derive(Debug)]
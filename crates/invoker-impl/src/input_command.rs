@@ -9,7 +9,9 @@
 // by the Apache License, Version 2.0.
 
 use restate_errors::NotRunningError;
-use restate_invoker_api::{Effect, InvocationStatusReport, InvokeInputJournal, StatusHandle};
+use restate_invoker_api::{
+    CircuitBreakerReport, Effect, InvocationStatusReport, InvokeInputJournal, StatusHandle,
+};
 use restate_types::identifiers::{InvocationId, PartitionKey, PartitionLeaderEpoch};
 use restate_types::invocation::{InvocationEpoch, InvocationTarget};
 use restate_types::journal::Completion;
@@ -17,6 +19,8 @@ use restate_types::journal_v2::CommandIndex;
 use restate_types::journal_v2::raw::RawNotification;
 use std::ops::RangeInclusive;
 use tokio::sync::mpsc;
+
+use crate::circuit_breaker::DeploymentCircuitBreakers;
 // -- Input messages
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -243,6 +247,7 @@ pub struct ChannelStatusReader(
             Vec<InvocationStatusReport>,
         >,
     >,
+    pub(super) DeploymentCircuitBreakers,
 );
 
 impl StatusHandle for ChannelStatusReader {
@@ -264,4 +269,8 @@ impl StatusHandle for ChannelStatusReader {
             itertools::Either::Left(std::iter::empty::<InvocationStatusReport>())
         }
     }
+
+    fn read_circuit_breakers(&self) -> Vec<CircuitBreakerReport> {
+        self.1.reports()
+    }
 }
@@ -13,7 +13,10 @@ use super::*;
 use restate_types::journal::Completion;
 use restate_types::journal_v2::raw::RawEntry;
 use restate_types::retries;
-use restate_types::schema::invocation_target::OnMaxAttempts;
+use restate_types::schema::invocation_target::{
+    OnMaxAttempts, ResolvedRetryRule, ResolvedRetryRuleAction,
+};
+use restate_types::schema::service::ErrorClass;
 use std::fmt;
 use std::time::Duration;
 use tokio::sync::mpsc;
@@ -168,6 +171,9 @@ struct RetryPolicyState {
     selected_from_deployment_id: Option<DeploymentId>,
     retry_iter: retries::RetryIter<'static>,
     on_max_attempts: OnMaxAttempts,
+    /// Rules overriding the retry policy for specific classes of errors.
+    /// Each rule with the Retry action tracks its own retry attempts.
+    retry_rules: Vec<ResolvedRetryRule>,
 }
 
 impl InvocationStateMachine {
@@ -186,6 +192,7 @@ impl InvocationStateMachine {
                 selected_from_deployment_id: None,
                 retry_iter,
                 on_max_attempts,
+                retry_rules: vec![],
             },
             start_message_retry_count_since_last_stored_command: 0,
             requested_pause: false,
@@ -237,10 +244,16 @@ impl InvocationStateMachine {
             self.invocation_target.service_name(),
             self.invocation_target.handler_name(),
         );
+        let retry_rules = target_resolver.resolve_invocation_retry_rules(
+            Some(&selected_deployment_id),
+            self.invocation_target.service_name(),
+            self.invocation_target.handler_name(),
+        );
         self.retry_policy_state = RetryPolicyState {
             selected_from_deployment_id: Some(selected_deployment_id),
             retry_iter,
             on_max_attempts,
+            retry_rules,
         }
     }
 
@@ -414,37 +427,46 @@ impl InvocationStateMachine {
     pub(super) fn handle_task_error(
         &mut self,
         error_is_transient: bool,
+        error_class: Option<(ErrorClass, Option<u16>)>,
         next_retry_interval_override: Option<Duration>,
         should_bump_start_message_retry_count_since_last_stored_command: bool,
     ) -> OnTaskError {
-        let journal_tracker = match &self.invocation_state {
-            AttemptState::InFlight {
-                journal_tracker, ..
-            } => journal_tracker.clone(),
-            AttemptState::New => JournalTracker::default(),
-            AttemptState::WaitingRetry {
-                journal_tracker,
-                timer_fired,
-            } => {
-                // TODO: https://github.com/restatedev/restate/issues/538
-                assert!(
-                    timer_fired,
-                    "Restate does not support multiple retry timers yet. This would require \
-                        deduplicating timers by some mean (e.g. fencing them off, overwriting \
-                        old timers, not registering a new timer if an old timer has not fired yet, etc.)"
-                );
-                journal_tracker.clone()
-            }
-        };
+        let journal_tracker = self.journal_tracker_for_retry();
 
         if self.requested_pause {
             // Shortcircuit to pause, as this is what the user asked for
             return OnTaskError::Pause;
         }
 
+        let matching_rule = match error_class {
+            Some((error_class, code)) if error_is_transient => self
+                .retry_policy_state
+                .retry_rules
+                .iter_mut()
+                .find(|rule| rule.matches(error_class, code)),
+            _ => None,
+        };
+        let (retry_iter, on_max_attempts) = match matching_rule {
+            Some(ResolvedRetryRule {
+                action: ResolvedRetryRuleAction::Fail,
+                ..
+            }) => return OnTaskError::Kill,
+            Some(ResolvedRetryRule {
+                action: ResolvedRetryRuleAction::Pause,
+                ..
+            }) => return OnTaskError::Pause,
+            Some(ResolvedRetryRule {
+                action: ResolvedRetryRuleAction::Retry(retry_iter, on_max_attempts),
+                ..
+            }) => (retry_iter, *on_max_attempts),
+            None => (
+                &mut self.retry_policy_state.retry_iter,
+                self.retry_policy_state.on_max_attempts,
+            ),
+        };
+
         if error_is_transient
-            && let Some(next_timer) =
-                next_retry_interval_override.or_else(|| self.retry_policy_state.retry_iter.next())
+            && let Some(next_timer) = next_retry_interval_override.or_else(|| retry_iter.next())
         {
             if should_bump_start_message_retry_count_since_last_stored_command {
                 self.start_message_retry_count_since_last_stored_command += 1;
@@ -455,13 +477,51 @@ impl InvocationStateMachine {
             };
             OnTaskError::ScheduleRetry(next_timer)
         } else {
-            match self.retry_policy_state.on_max_attempts {
+            match on_max_attempts {
                 OnMaxAttempts::Pause => OnTaskError::Pause,
                 OnMaxAttempts::Kill => OnTaskError::Kill,
             }
         }
     }
 
+    /// Waits for the given duration before the next attempt, without consuming a retry attempt.
+    /// Used when the circuit breaker of the deployment is open.
+    pub(super) fn park(&mut self, duration: Duration) -> OnTaskError {
+        let journal_tracker = self.journal_tracker_for_retry();
+
+        if self.requested_pause {
+            return OnTaskError::Pause;
+        }
+
+        self.invocation_state = AttemptState::WaitingRetry {
+            timer_fired: false,
+            journal_tracker,
+        };
+        OnTaskError::Park(duration)
+    }
+
+    fn journal_tracker_for_retry(&self) -> JournalTracker {
+        match &self.invocation_state {
+            AttemptState::InFlight {
+                journal_tracker, ..
+            } => journal_tracker.clone(),
+            AttemptState::New => JournalTracker::default(),
+            AttemptState::WaitingRetry {
+                journal_tracker,
+                timer_fired,
+            } => {
+                // TODO: https://github.com/restatedev/restate/issues/538
+                assert!(
+                    timer_fired,
+                    "Restate does not support multiple retry timers yet. This would require \
+                        deduplicating timers by some mean (e.g. fencing them off, overwriting \
+                        old timers, not registering a new timer if an old timer has not fired yet, etc.)"
+                );
+                journal_tracker.clone()
+            }
+        }
+    }
+
     pub(super) fn is_ready_to_retry(&self) -> bool {
         match &self.invocation_state {
             AttemptState::WaitingRetry {
@@ -531,12 +591,20 @@ impl InvocationStateMachine {
 #[derive(Debug)]
 pub(super) enum OnTaskError {
     ScheduleRetry(Duration),
+    /// Like [`OnTaskError::ScheduleRetry`], but doesn't count as a retry attempt
+    Park(Duration),
     Pause,
     Kill,
 }
 
 pub(super) struct AttemptDeploymentId(Option<DeploymentId>);
 
+impl AttemptDeploymentId {
+    pub(super) fn deployment_id(&self) -> Option<DeploymentId> {
+        self.0
+    }
+}
+
 impl fmt::Display for AttemptDeploymentId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self.0 {
@@ -579,7 +647,7 @@ mod tests {
 
         let_assert!(
             OnTaskError::ScheduleRetry(_) =
-                invocation_state_machine.handle_task_error(true, None, true)
+                invocation_state_machine.handle_task_error(true, None, None, true)
         );
         check!(let AttemptState::WaitingRetry { .. } = invocation_state_machine.invocation_state);
 
@@ -588,11 +656,121 @@ mod tests {
         // We stay in `WaitingForRetry`
         let_assert!(
             OnTaskError::ScheduleRetry(_) =
-                invocation_state_machine.handle_task_error(true, None, true)
+                invocation_state_machine.handle_task_error(true, None, None, true)
         );
         check!(let AttemptState::WaitingRetry { .. } = invocation_state_machine.invocation_state);
     }
 
+    #[test]
+    fn retry_rules_override_retry_policy() {
+        let mut invocation_state_machine = InvocationStateMachine::create(
+            InvocationTarget::mock_virtual_object(),
+            0,
+            RetryPolicy::fixed_delay(Duration::from_secs(1), Some(10)).into_iter(),
+            OnMaxAttempts::Kill,
+        );
+        invocation_state_machine.retry_policy_state.retry_rules = vec![
+            ResolvedRetryRule {
+                error_class: ErrorClass::HttpStatus,
+                codes: vec![400, 404],
+                action: ResolvedRetryRuleAction::Fail,
+            },
+            ResolvedRetryRule {
+                error_class: ErrorClass::ErrorCode,
+                codes: vec![503],
+                action: ResolvedRetryRuleAction::Retry(
+                    RetryPolicy::fixed_delay(Duration::from_secs(30), Some(1)).into_iter(),
+                    OnMaxAttempts::Pause,
+                ),
+            },
+            ResolvedRetryRule {
+                error_class: ErrorClass::Timeout,
+                codes: vec![],
+                action: ResolvedRetryRuleAction::Pause,
+            },
+        ];
+
+        let_assert!(
+            OnTaskError::Kill = invocation_state_machine.handle_task_error(
+                true,
+                Some((ErrorClass::HttpStatus, Some(404))),
+                None,
+                true
+            )
+        );
+        let_assert!(
+            OnTaskError::Pause = invocation_state_machine.handle_task_error(
+                true,
+                Some((ErrorClass::Timeout, None)),
+                None,
+                true
+            )
+        );
+
+        // Rule with retry action uses its own retry policy, then its own on max attempts behavior
+        let_assert!(
+            OnTaskError::ScheduleRetry(next_retry) = invocation_state_machine.handle_task_error(
+                true,
+                Some((ErrorClass::ErrorCode, Some(503))),
+                None,
+                true
+            )
+        );
+        assert_eq!(next_retry, Duration::from_secs(30));
+        invocation_state_machine.notify_retry_timer_fired();
+        let_assert!(
+            OnTaskError::Pause = invocation_state_machine.handle_task_error(
+                true,
+                Some((ErrorClass::ErrorCode, Some(503))),
+                None,
+                true
+            )
+        );
+
+        // Errors not matching any rule use the handler retry policy
+        let_assert!(
+            OnTaskError::ScheduleRetry(next_retry) = invocation_state_machine.handle_task_error(
+                true,
+                Some((ErrorClass::HttpStatus, Some(500))),
+                None,
+                true
+            )
+        );
+        assert_eq!(next_retry, Duration::from_secs(1));
+    }
+
+    #[test]
+    fn park_does_not_consume_retry_attempts() {
+        let mut invocation_state_machine = InvocationStateMachine::create(
+            InvocationTarget::mock_virtual_object(),
+            0,
+            RetryPolicy::fixed_delay(Duration::from_secs(1), Some(1)).into_iter(),
+            OnMaxAttempts::Kill,
+        );
+
+        for _ in 0..3 {
+            let_assert!(
+                OnTaskError::Park(_) = invocation_state_machine.park(Duration::from_secs(10))
+            );
+            check!(let AttemptState::WaitingRetry { .. } = invocation_state_machine.invocation_state);
+            invocation_state_machine.notify_retry_timer_fired();
+        }
+        assert_eq!(
+            invocation_state_machine.start_message_retry_count_since_last_stored_command,
+            0
+        );
+
+        // The single retry attempt is still available
+        let_assert!(
+            OnTaskError::ScheduleRetry(_) =
+                invocation_state_machine.handle_task_error(true, None, None, true)
+        );
+        invocation_state_machine.notify_retry_timer_fired();
+        let_assert!(
+            OnTaskError::Kill = invocation_state_machine.handle_task_error(true, None, None, true)
+        );
+    }
+
     #[test(tokio::test)]
     async fn handle_error_counts_attempts_on_same_entry() {
         let mut invocation_state_machine = InvocationStateMachine::create(
@@ -611,7 +789,7 @@ mod tests {
         // Notify error
         let_assert!(
             OnTaskError::ScheduleRetry(_) =
-                invocation_state_machine.handle_task_error(true, None, true)
+                invocation_state_machine.handle_task_error(true, None, None, true)
         );
         assert_eq!(
             invocation_state_machine.start_message_retry_count_since_last_stored_command,
@@ -627,7 +805,7 @@ mod tests {
         // Get error again
         let_assert!(
             OnTaskError::ScheduleRetry(_) =
-                invocation_state_machine.handle_task_error(true, None, true)
+                invocation_state_machine.handle_task_error(true, None, None, true)
         );
         assert_eq!(
             invocation_state_machine.start_message_retry_count_since_last_stored_command,
@@ -702,7 +880,7 @@ mod tests {
         invocation_state_machine.notify_new_command(1, false);
         let_assert!(
             OnTaskError::ScheduleRetry(_) =
-                invocation_state_machine.handle_task_error(true, None, true)
+                invocation_state_machine.handle_task_error(true, None, None, true)
         );
 
         // PP sends ack for command 1
@@ -734,7 +912,7 @@ mod tests {
         invocation_state_machine.notify_new_notification_proposal(NotificationId::CompletionId(1));
         let_assert!(
             OnTaskError::ScheduleRetry(_) =
-                invocation_state_machine.handle_task_error(true, None, true)
+                invocation_state_machine.handle_task_error(true, None, None, true)
         );
 
         // Waiting notifications acks and retry timer fired
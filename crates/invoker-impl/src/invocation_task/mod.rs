@@ -21,38 +21,46 @@ use std::task::{Context, Poll, ready};
 use std::time::{Duration, Instant};
 
 use bytes::Bytes;
-use futures::{FutureExt, Stream, future, stream};
+use futures::{FutureExt, Stream, StreamExt, future, stream};
 use http::response::Parts as ResponseParts;
 use http::{HeaderName, HeaderValue, Response};
 use http_body::{Body, Frame};
-use metrics::histogram;
+use metrics::{counter, histogram};
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
 use tokio_util::task::AbortOnDropHandle;
 use tracing::instrument;
 
 use restate_invoker_api::invocation_reader::{
-    EagerState, InvocationReader, InvocationReaderTransaction,
+    EagerState, InvocationReader, InvocationReaderTransaction, JournalEntry,
 };
 use restate_invoker_api::{EntryEnricher, InvokeInputJournal};
+use restate_object_store_util::PayloadStore;
 use restate_service_client::{Request, ResponseBody, ServiceClient, ServiceClientError};
+use restate_service_protocol::codec::ProtobufRawEntryCodec;
+use restate_service_protocol_v4::entry_codec::ServiceProtocolV4Codec;
+use restate_types::config::CircuitBreakerOptions;
 use restate_types::deployment::PinnedDeployment;
 use restate_types::identifiers::{InvocationId, PartitionLeaderEpoch};
-use restate_types::invocation::{InvocationEpoch, InvocationTarget};
+use restate_types::invocation::offloaded_payload::OffloadedPayload;
+use restate_types::invocation::{Header, InvocationEpoch, InvocationTarget};
+use restate_types::journal;
 use restate_types::journal::EntryIndex;
 use restate_types::journal::enriched::EnrichedRawEntry;
 use restate_types::journal_v2;
 use restate_types::journal_v2::raw::RawNotification;
-use restate_types::journal_v2::{CommandIndex, NotificationId};
+use restate_types::journal_v2::{CommandIndex, CompletionId, NotificationId};
 use restate_types::live::Live;
-use restate_types::schema::deployment::DeploymentResolver;
+use restate_types::schema::deployment::{Deployment, DeploymentResolver};
 use restate_types::schema::invocation_target::InvocationTargetResolver;
+use restate_types::schema::routing::ServiceRouting;
 use restate_types::service_protocol::ServiceProtocolVersion;
 
 use crate::TokenBucket;
+use crate::circuit_breaker::DeploymentCircuitBreakers;
 use crate::error::InvokerError;
 use crate::invocation_task::service_protocol_runner::ServiceProtocolRunner;
-use crate::metric_definitions::{ID_LOOKUP, INVOKER_TASK_DURATION};
+use crate::metric_definitions::{ID_LOOKUP, INVOKER_DEPLOYMENT_ROUTING, INVOKER_TASK_DURATION};
 
 // Clippy false positive, might be caused by Bytes contained within HeaderValue.
 // https://github.com/rust-lang/rust/issues/40543#issuecomment-1212981256
@@ -149,6 +157,7 @@ pub(super) struct InvocationTask<IR, EE, DMR> {
     disable_eager_state: bool,
     message_size_warning: usize,
     message_size_limit: Option<usize>,
+    message_compression_threshold: usize,
     retry_count_since_last_stored_entry: u32,
 
     // Invoker tx/rx
@@ -160,6 +169,12 @@ pub(super) struct InvocationTask<IR, EE, DMR> {
 
     // throttling
     action_token_bucket: Option<TokenBucket>,
+
+    circuit_breakers: DeploymentCircuitBreakers,
+    circuit_breaker_options: CircuitBreakerOptions,
+
+    // to offload large run results and load offloaded payloads
+    payload_store: Option<PayloadStore>,
 }
 
 /// This is needed to split the run_internal in multiple loop functions and have shortcircuiting.
@@ -212,6 +227,7 @@ where
         disable_eager_state: bool,
         message_size_warning: usize,
         message_size_limit: Option<usize>,
+        message_compression_threshold: usize,
         retry_count_since_last_stored_entry: u32,
         invocation_reader: IR,
         entry_enricher: EE,
@@ -219,6 +235,9 @@ where
         invoker_tx: mpsc::UnboundedSender<InvocationTaskOutput>,
         invoker_rx: mpsc::UnboundedReceiver<Notification>,
         action_token_bucket: Option<TokenBucket>,
+        circuit_breakers: DeploymentCircuitBreakers,
+        circuit_breaker_options: CircuitBreakerOptions,
+        payload_store: Option<PayloadStore>,
     ) -> Self {
         Self {
             client,
@@ -236,8 +255,43 @@ where
             invoker_rx,
             message_size_limit,
             message_size_warning,
+            message_compression_threshold,
             retry_count_since_last_stored_entry,
             action_token_bucket,
+            circuit_breakers,
+            circuit_breaker_options,
+            payload_store,
+        }
+    }
+
+    /// Loads the payload from the object store if it was offloaded.
+    async fn rehydrate(&self, payload: Bytes) -> Result<Bytes, InvokerError> {
+        if !OffloadedPayload::is_offloaded(&payload) {
+            return Ok(payload);
+        }
+        let Some(payload_store) = &self.payload_store else {
+            return Err(InvokerError::OffloadedPayload(anyhow::anyhow!(
+                "payload offloading is not configured on this node"
+            )));
+        };
+        payload_store
+            .rehydrate(&self.invocation_id, payload)
+            .await
+            .map_err(|err| InvokerError::OffloadedPayload(err.into()))
+    }
+
+    /// Offloads the result of a run to the object store if it exceeds the offloading threshold.
+    async fn offload_run_result(
+        &self,
+        completion_id: CompletionId,
+        payload: Bytes,
+    ) -> Result<Bytes, InvokerError> {
+        match &self.payload_store {
+            Some(payload_store) if payload_store.should_offload(payload.len()) => payload_store
+                .offload_run_result(&self.invocation_id, completion_id, payload)
+                .await
+                .map_err(|err| InvokerError::OffloadedPayload(err.into())),
+            _ => Ok(payload),
         }
     }
 
@@ -296,6 +350,7 @@ where
                 future::Either::Right(stream::iter(journal_items)),
             ),
         };
+        let mut journal_stream = journal_stream.peekable();
 
         if self.invocation_epoch != journal_metadata.invocation_epoch {
             shortcircuit!(Err(InvokerError::StaleJournalRead {
@@ -336,14 +391,30 @@ where
                 )
             } else {
                 // We can choose the freshest deployment for the latest revision
-                // of the registered service.
-                let deployment = shortcircuit!(
-                    schemas
-                        .resolve_latest_deployment_for_service(
-                            self.invocation_target.service_name()
-                        )
-                        .ok_or(InvokerError::NoDeploymentForService)
-                );
+                // of the registered service, unless the service routing rules
+                // route this invocation to another deployment.
+                let service_routing =
+                    schemas.resolve_service_routing(self.invocation_target.service_name());
+                let headers = if service_routing
+                    .as_ref()
+                    .is_some_and(ServiceRouting::has_header_routes)
+                {
+                    shortcircuit!(input_headers(Pin::new(&mut journal_stream).peek().await))
+                } else {
+                    vec![]
+                };
+                let (deployment, route) = shortcircuit!(route_new_invocation(
+                    schemas,
+                    &self.invocation_target,
+                    service_routing,
+                    &headers,
+                    journal_metadata.random_seed,
+                ));
+                counter!(INVOKER_DEPLOYMENT_ROUTING,
+                    "deployment_id" => deployment.id.to_string(),
+                    "route" => route
+                )
+                .increment(1);
 
                 let chosen_service_protocol_version = shortcircuit!(
                     ServiceProtocolVersion::pick(&deployment.supported_protocol_versions,)
@@ -403,6 +474,17 @@ where
             deployment_changed,
         ));
 
+        // Fail fast if the deployment is known to be down
+        if let Err(retry_after) = self
+            .circuit_breakers
+            .acquire(deployment.id, &self.circuit_breaker_options)
+        {
+            shortcircuit!(Err(InvokerError::DeploymentCircuitBreakerOpen(
+                deployment.id,
+                retry_after
+            )));
+        }
+
         if chosen_service_protocol_version <= ServiceProtocolVersion::V3 {
             // Protocol runner for service protocol <= v3
             let service_protocol_runner =
@@ -415,6 +497,7 @@ where
             let service_protocol_runner = service_protocol_runner_v4::ServiceProtocolRunner::new(
                 self,
                 chosen_service_protocol_version,
+                deployment.message_compression,
             );
             service_protocol_runner
                 .run(journal_metadata, deployment, journal_stream, state_iter)
@@ -434,6 +517,67 @@ impl<IR, EE, DMR> InvocationTask<IR, EE, DMR> {
     }
 }
 
+/// Picks the deployment of a new invocation, applying the routing rules of its service. Returns
+/// the deployment, together with the kind of route which picked it.
+fn route_new_invocation<Schemas: DeploymentResolver>(
+    schemas: &Schemas,
+    invocation_target: &InvocationTarget,
+    service_routing: Option<ServiceRouting>,
+    headers: &[Header],
+    random_seed: u64,
+) -> Result<(Deployment, &'static str), InvokerError> {
+    let service_name = invocation_target.service_name();
+    let handler_name = invocation_target.handler_name();
+
+    let routed = service_routing.and_then(|service_routing| {
+        service_routing.pick(
+            headers.iter().map(|h| (&*h.name, &*h.value)),
+            random_seed,
+            // Skip the deployments which can't run this invocation
+            |deployment_id| {
+                let (deployment, services) = schemas.get_deployment_and_services(deployment_id)?;
+                let has_handler = services.iter().any(|service| {
+                    service.name == **service_name && service.handlers.contains_key(&**handler_name)
+                });
+                (has_handler
+                    && ServiceProtocolVersion::pick(&deployment.supported_protocol_versions)
+                        .is_some())
+                .then_some(deployment)
+            },
+        )
+    });
+
+    match routed {
+        Some((deployment, route_kind)) => Ok((deployment, route_kind.as_str())),
+        None => Ok((
+            schemas
+                .resolve_latest_deployment_for_service(service_name)
+                .ok_or(InvokerError::NoDeploymentForService)?,
+            "latest",
+        )),
+    }
+}
+
+/// Returns the headers of the invocation, given the head of its journal.
+fn input_headers(first_entry: Option<&JournalEntry>) -> Result<Vec<Header>, InvokerError> {
+    match first_entry {
+        Some(JournalEntry::JournalV1(entry)) if entry.ty() == journal::EntryType::Input => {
+            match entry.deserialize_entry_ref::<ProtobufRawEntryCodec>()? {
+                journal::Entry::Input(input_entry) => Ok(input_entry.headers),
+                _ => Ok(vec![]),
+            }
+        }
+        Some(JournalEntry::JournalV2(entry))
+            if entry.ty() == journal_v2::EntryType::Command(journal_v2::CommandType::Input) =>
+        {
+            Ok(entry
+                .decode::<ServiceProtocolV4Codec, journal_v2::command::InputCommand>()?
+                .headers)
+        }
+        _ => Ok(vec![]),
+    }
+}
+
 fn service_protocol_version_to_header_value(
     service_protocol_version: ServiceProtocolVersion,
 ) -> HeaderValue {
@@ -536,3 +680,110 @@ impl Stream for ResponseStream {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use googletest::prelude::*;
+
+    use restate_types::identifiers::DeploymentId;
+    use restate_types::schema::deployment::test_util::MockDeploymentMetadataRegistry;
+    use restate_types::schema::routing::{HeaderRoute, WeightedRoute};
+    use restate_types::schema::service::ServiceMetadata;
+
+    fn mock_deployment(
+        schemas: &mut MockDeploymentMetadataRegistry,
+        handlers: &[&str],
+    ) -> DeploymentId {
+        let mut deployment = Deployment::mock();
+        deployment.id = DeploymentId::new();
+        let deployment_id = deployment.id;
+        schemas.mock_deployment_with_services(
+            deployment,
+            vec![ServiceMetadata::mock_service("greeter", handlers)],
+        );
+        deployment_id
+    }
+
+    fn canary_routing(canary: DeploymentId) -> ServiceRouting {
+        ServiceRouting {
+            header_routes: vec![HeaderRoute {
+                name: "x-canary".to_owned(),
+                value: "true".to_owned(),
+                deployment_id: canary,
+            }],
+            weighted_routes: vec![WeightedRoute {
+                deployment_id: canary,
+                weight: 1,
+            }],
+        }
+    }
+
+    fn route(
+        schemas: &MockDeploymentMetadataRegistry,
+        seed: u64,
+    ) -> Result<(DeploymentId, &'static str), InvokerError> {
+        route_new_invocation(
+            schemas,
+            &InvocationTarget::service("greeter", "greet"),
+            schemas.resolve_service_routing("greeter"),
+            &[Header::new("x-canary", "true")],
+            seed,
+        )
+        .map(|(deployment, route)| (deployment.id, route))
+    }
+
+    #[test]
+    fn routes_to_the_deployment_picked_by_the_rules() {
+        let mut schemas = MockDeploymentMetadataRegistry::default();
+        let latest = mock_deployment(&mut schemas, &["greet"]);
+        schemas.mock_latest_service("greeter", latest);
+        let canary = mock_deployment(&mut schemas, &["greet"]);
+        schemas.mock_service_routing("greeter", canary_routing(canary));
+
+        assert_that!(route(&schemas, 0), ok(eq((canary, "header"))));
+    }
+
+    #[test]
+    fn routes_to_deployments_without_the_handler_fall_back_to_latest() {
+        let mut schemas = MockDeploymentMetadataRegistry::default();
+        let latest = mock_deployment(&mut schemas, &["greet"]);
+        schemas.mock_latest_service("greeter", latest);
+        let canary = mock_deployment(&mut schemas, &["other"]);
+        schemas.mock_service_routing("greeter", canary_routing(canary));
+
+        // whatever the seed, so retries don't keep picking the same broken route
+        for seed in 0..10 {
+            assert_that!(route(&schemas, seed), ok(eq((latest, "latest"))));
+        }
+    }
+
+    #[test]
+    fn routes_to_deployments_without_a_supported_protocol_fall_back_to_latest() {
+        let mut schemas = MockDeploymentMetadataRegistry::default();
+        let latest = mock_deployment(&mut schemas, &["greet"]);
+        schemas.mock_latest_service("greeter", latest);
+        let canary = mock_deployment(&mut schemas, &["greet"]);
+        schemas
+            .deployments
+            .get_mut(&canary)
+            .unwrap()
+            .supported_protocol_versions = i32::MAX - 1..=i32::MAX;
+        schemas.mock_service_routing("greeter", canary_routing(canary));
+
+        assert_that!(route(&schemas, 0), ok(eq((latest, "latest"))));
+    }
+
+    #[test]
+    fn no_usable_deployment() {
+        let mut schemas = MockDeploymentMetadataRegistry::default();
+        let canary = mock_deployment(&mut schemas, &["other"]);
+        schemas.mock_service_routing("greeter", canary_routing(canary));
+
+        assert_that!(
+            route(&schemas, 0),
+            err(pat!(InvokerError::NoDeploymentForService))
+        );
+    }
+}
@@ -33,6 +33,8 @@ use restate_service_protocol_v4::entry_codec::ServiceProtocolV4Codec;
 use restate_types::errors::InvocationError;
 use restate_types::identifiers::{EntryIndex, InvocationId};
 use restate_types::invocation::ServiceInvocationSpanContext;
+use restate_types::invocation::offloaded_payload::OffloadedPayload;
+use restate_types::journal;
 use restate_types::journal::EntryType;
 use restate_types::journal::raw::RawEntryCodec;
 use restate_types::journal_v2;
@@ -269,8 +271,14 @@ where
             DeploymentType::Http {
                 address,
                 http_version,
+                connection_pool,
                 ..
-            } => Endpoint::Http(address, Some(http_version)),
+            } => Endpoint::Http(address, Some(http_version), connection_pool),
+            DeploymentType::Wasm {
+                module_path,
+                fuel_limit,
+                memory_limit,
+            } => Endpoint::Wasm(module_path, fuel_limit, memory_limit),
         };
 
         headers.extend(deployment.additional_headers);
@@ -316,17 +324,28 @@ where
                 },
                 opt_je = journal_stream.next() => {
                     match opt_je {
-                        Some(JournalEntry::JournalV1(je)) => {
+                        Some(JournalEntry::JournalV1(mut je)) => {
+                            if je.ty() == EntryType::Input
+                                && let journal::Entry::Input(input_entry) = crate::shortcircuit!(je.deserialize_entry_ref::<ProtobufRawEntryCodec>())
+                                && OffloadedPayload::is_offloaded(&input_entry.value)
+                            {
+                                let value = crate::shortcircuit!(self.invocation_task.rehydrate(input_entry.value).await);
+                                je = ProtobufRawEntryCodec::serialize_as_input_entry(
+                                    input_entry.headers,
+                                    value
+                                ).erase_enrichment();
+                            }
                             crate::shortcircuit!(self.write(http_stream_tx, ProtocolMessage::UnparsedEntry(je)).await);
                             self.next_journal_index += 1;
                         },
                         Some(JournalEntry::JournalV2(re)) => {
                             if re.ty() == journal_v2::EntryType::Command(journal_v2::CommandType::Input) {
                                 let input_entry = crate::shortcircuit!(re.decode::<ServiceProtocolV4Codec, journal_v2::command::InputCommand>());
+                                let payload = crate::shortcircuit!(self.invocation_task.rehydrate(input_entry.payload).await);
                                   crate::shortcircuit!(self.write(http_stream_tx, ProtocolMessage::UnparsedEntry(
                                     ProtobufRawEntryCodec::serialize_as_input_entry(
                                         input_entry.headers,
-                                        input_entry.payload
+                                        payload
                                     ).erase_enrichment()
                                 )).await);
                             self.next_journal_index += 1;
@@ -37,21 +37,26 @@ use restate_service_protocol_v4::message_codec::{
 };
 use restate_types::errors::InvocationError;
 use restate_types::identifiers::InvocationId;
+use restate_types::invocation::offloaded_payload::OffloadedPayload;
 use restate_types::invocation::{
     Header, InvocationTarget, InvocationTargetType, ServiceInvocationSpanContext, ServiceType,
     SpanRelation,
 };
 use restate_types::journal;
 use restate_types::journal_v2::command::{
-    CallCommand, CallRequest, InputCommand, OneWayCallCommand,
+    CallCommand, CallRequest, InputCommand, OneWayCallCommand, OutputCommand, OutputResult,
 };
 use restate_types::journal_v2::raw::{RawCommand, RawEntry, RawNotification};
 use restate_types::journal_v2::{
-    CommandIndex, CommandType, Entry, EntryType, NotificationId, RunCompletion, RunResult, SignalId,
+    CommandIndex, CommandType, CompletionType, Entry, EntryMetadata, EntryType, NotificationId,
+    NotificationType, RunCompletion, RunResult, SignalId,
+};
+use restate_types::schema::deployment::{
+    Deployment, DeploymentType, MessageCompression, ProtocolType,
 };
-use restate_types::schema::deployment::{Deployment, DeploymentType, ProtocolType};
 use restate_types::schema::invocation_target::{DeploymentStatus, InvocationTargetResolver};
 use restate_types::service_protocol::ServiceProtocolVersion;
+use restate_types::time::MillisSinceEpoch;
 
 use crate::Notification;
 use crate::error::{
@@ -92,8 +97,13 @@ where
     pub fn new(
         invocation_task: &'a mut InvocationTask<IR, EE, Schemas>,
         service_protocol_version: ServiceProtocolVersion,
+        message_compression: Option<MessageCompression>,
     ) -> Self {
-        let encoder = Encoder::new(service_protocol_version);
+        let mut encoder = Encoder::new(service_protocol_version);
+        // Compress large messages only if the deployment advertised support for it
+        if let Some(MessageCompression::Zstd) = message_compression {
+            encoder = encoder.with_compression(invocation_task.message_compression_threshold);
+        }
 
         Self {
             invocation_task,
@@ -285,8 +295,14 @@ where
             DeploymentType::Http {
                 address,
                 http_version,
+                connection_pool,
                 ..
-            } => Endpoint::Http(address, Some(http_version)),
+            } => Endpoint::Http(address, Some(http_version), connection_pool),
+            DeploymentType::Wasm {
+                module_path,
+                fuel_limit,
+                memory_limit,
+            } => Endpoint::Wasm(module_path, fuel_limit, memory_limit),
         };
 
         headers.extend(deployment_metadata.additional_headers);
@@ -334,16 +350,18 @@ where
                 opt_je = journal_stream.next() => {
                     match opt_je {
                         Some(JournalEntry::JournalV2(entry)) => {
-                            crate::shortcircuit!(self.write_entry(http_stream_tx, entry.inner).await);
+                            let raw_entry = crate::shortcircuit!(self.rehydrate_entry(entry.inner).await);
+                            crate::shortcircuit!(self.write_entry(http_stream_tx, raw_entry).await);
 
                         }
                         Some(JournalEntry::JournalV1(old_entry)) => {
                             if let journal::Entry::Input(input_entry) = crate::shortcircuit!(old_entry.deserialize_entry::<ProtobufRawEntryCodec>()) {
+                                let payload = crate::shortcircuit!(self.invocation_task.rehydrate(input_entry.value).await);
                                 crate::shortcircuit!(self.write_entry(
                                     http_stream_tx,
                                     Entry::Command(InputCommand {
                                         headers: input_entry.headers,
-                                        payload: input_entry.value,
+                                        payload,
                                         name: Default::default()
                                     }.into()).encode::<ServiceProtocolV4Codec>()
                                 ).await);
@@ -378,6 +396,7 @@ where
                     match opt_completion {
                         Some(Notification::Entry(entry)) => {
                             trace!("Sending the entry to the wire");
+                            let entry = crate::shortcircuit!(self.rehydrate_entry(entry).await);
                             crate::shortcircuit!(self.write_entry(&mut http_stream_tx, entry).await);
                         }
                         Some(Notification::Completion(_)) => {
@@ -402,7 +421,7 @@ where
                         }
                         Some(DecoderStreamItem::Parts(parts)) => crate::shortcircuit!(self.handle_response_headers(parts)),
                         Some(DecoderStreamItem::Message(message_header, message)) => {
-                            crate::shortcircuit!(self.handle_message(parent_span_context, message_header, message));
+                            crate::shortcircuit!(self.handle_message(parent_span_context, message_header, message).await);
                         }
                     }
                 },
@@ -435,7 +454,7 @@ where
                             return TerminalLoopState::Failed(InvokerError::SdkV2(SdkInvocationErrorV2::unknown()));
                         }
                         Some(DecoderStreamItem::Parts(parts)) => crate::shortcircuit!(self.handle_response_headers(parts)),
-                        Some(DecoderStreamItem::Message(message_header, message)) => crate::shortcircuit!(self.handle_message(parent_span_context, message_header, message)),
+                        Some(DecoderStreamItem::Message(message_header, message)) => crate::shortcircuit!(self.handle_message(parent_span_context, message_header, message).await),
                     }
                 },
                 _ = tokio::time::sleep(self.invocation_task.abort_timeout) => {
@@ -480,6 +499,40 @@ where
         .await
     }
 
+    /// Loads the payloads of the entry which were offloaded to the object store.
+    async fn rehydrate_entry(&self, raw_entry: RawEntry) -> Result<RawEntry, InvokerError> {
+        match raw_entry.ty() {
+            EntryType::Command(CommandType::Input) => {
+                let mut input_command =
+                    raw_entry.decode::<ServiceProtocolV4Codec, InputCommand>()?;
+                if !OffloadedPayload::is_offloaded(&input_command.payload) {
+                    return Ok(raw_entry);
+                }
+                input_command.payload = self
+                    .invocation_task
+                    .rehydrate(input_command.payload)
+                    .await?;
+                Ok(Entry::Command(input_command.into()).encode::<ServiceProtocolV4Codec>())
+            }
+            EntryType::Notification(NotificationType::Completion(CompletionType::Run)) => {
+                let mut run_completion =
+                    raw_entry.decode::<ServiceProtocolV4Codec, RunCompletion>()?;
+                let RunResult::Success(value) = &mut run_completion.result else {
+                    return Ok(raw_entry);
+                };
+                if !OffloadedPayload::is_offloaded(value) {
+                    return Ok(raw_entry);
+                }
+                *value = self
+                    .invocation_task
+                    .rehydrate(std::mem::take(value))
+                    .await?;
+                Ok(Entry::from(run_completion).encode::<ServiceProtocolV4Codec>())
+            }
+            _ => Ok(raw_entry),
+        }
+    }
+
     async fn write_entry(
         &mut self,
         http_stream_tx: &mut InvokerRequestStreamSender,
@@ -609,7 +662,7 @@ where
         self.command_index += 1;
     }
 
-    fn handle_message(
+    async fn handle_message(
         &mut self,
         parent_span_context: &ServiceInvocationSpanContext,
         mh: MessageHeader,
@@ -642,7 +695,22 @@ where
                             .ok_or(InvokerError::MalformedProposeRunCompletion)
                     ) {
                         proto::propose_run_completion_message::Result::Value(b) => {
-                            RunResult::Success(b)
+                            crate::shortcircuit!(
+                                OffloadedPayload::check_not_reference(&b).map_err(|e| {
+                                    InvokerError::CommandPrecondition(
+                                        self.command_index,
+                                        EntryType::Notification(NotificationType::Completion(
+                                            CompletionType::Run,
+                                        )),
+                                        e.into(),
+                                    )
+                                })
+                            );
+                            RunResult::Success(crate::shortcircuit!(
+                                self.invocation_task
+                                    .offload_run_result(run_completion.result_completion_id, b)
+                                    .await
+                            ))
                         }
                         proto::propose_run_completion_message::Result::Failure(f) => {
                             RunResult::Failure(f.into())
@@ -667,7 +735,21 @@ where
 
             // Commands
             Message::OutputCommand(cmd) => {
-                self.handle_new_command(mh, RawCommand::new(CommandType::Output, cmd));
+                let raw_command = RawCommand::new(CommandType::Output, cmd);
+                // The output is handed to callers and the ingress as is, it must not be
+                // mistaken for a reference to an offloaded payload
+                let output: OutputCommand =
+                    crate::shortcircuit!(raw_command.decode::<ServiceProtocolV4Codec, _>());
+                if let OutputResult::Success(value) = &output.result {
+                    crate::shortcircuit!(OffloadedPayload::check_not_reference(value).map_err(
+                        |e| InvokerError::CommandPrecondition(
+                            self.command_index,
+                            EntryType::Command(CommandType::Output),
+                            e.into()
+                        )
+                    ));
+                }
+                self.handle_new_command(mh, raw_command);
                 TerminalLoopState::Continue(())
             }
             Message::InputCommand(cmd) => {
@@ -717,7 +799,9 @@ where
                                 headers: cmd.headers.into_iter().map(Into::into).collect(),
                                 key: cmd.key.into(),
                                 idempotency_key: cmd.idempotency_key.map(|s| s.into()),
-                                span_relation: parent_span_context.as_linked()
+                                span_relation: parent_span_context.as_linked(),
+                                execution_time: (cmd.invoke_time != 0)
+                                    .then(|| MillisSinceEpoch::new(cmd.invoke_time)),
                             }
                         )
                         .map_err(|e| InvokerError::CommandPrecondition(
@@ -752,7 +836,8 @@ where
                                 headers: cmd.headers.into_iter().map(Into::into).collect(),
                                 key: cmd.key.into(),
                                 idempotency_key: cmd.idempotency_key.map(|s| s.into()),
-                                span_relation: parent_span_context.as_parent()
+                                span_relation: parent_span_context.as_parent(),
+                                execution_time: None,
                             }
                         )
                         .map_err(|e| InvokerError::CommandPrecondition(
@@ -1012,12 +1097,18 @@ pub struct InvokeRequest {
     idempotency_key: Option<ByteString>,
     span_relation: SpanRelation,
     parameter: Bytes,
+    /// Set only for delayed one way calls.
+    execution_time: Option<MillisSinceEpoch>,
 }
 
 fn resolve_call_request(
     invocation_target_resolver: &impl InvocationTargetResolver,
     request: InvokeRequest,
 ) -> Result<CallRequest, CommandPreconditionError> {
+    // The parameter becomes the input of the callee, it must not be mistaken for a reference to
+    // an offloaded payload
+    OffloadedPayload::check_not_reference(&request.parameter)?;
+
     let meta = invocation_target_resolver
         .resolve_latest_invocation_target(&request.service_name, &request.handler_name)
         .ok_or_else(|| {
@@ -1083,6 +1174,7 @@ fn resolve_call_request(
         idempotency_key: request.idempotency_key,
         completion_retention_duration: invocation_retention.completion_retention,
         journal_retention_duration: invocation_retention.journal_retention,
+        deadline: meta.compute_deadline(request.execution_time),
     })
 }
 
@@ -8,6 +8,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+mod circuit_breaker;
 mod error;
 mod input_command;
 mod invocation_state_machine;
@@ -23,7 +24,7 @@ use std::io::ErrorKind;
 use std::ops::RangeInclusive;
 use std::path::PathBuf;
 use std::pin::Pin;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 use std::{cmp, panic};
 
 use futures::StreamExt;
@@ -43,6 +44,7 @@ use restate_invoker_api::{
     Effect, EffectKind, EntryEnricher, InvocationErrorReport, InvocationStatusReport,
     InvokeInputJournal,
 };
+use restate_object_store_util::PayloadStore;
 use restate_queue::SegmentQueue;
 use restate_service_client::{AssumeRoleCacheMode, ServiceClient};
 use restate_timer_queue::TimerQueue;
@@ -70,11 +72,12 @@ use crate::invocation_state_machine::OnTaskError;
 use crate::invocation_task::InvocationTask;
 use crate::invocation_task::{InvocationTaskOutput, InvocationTaskOutputInner};
 use crate::metric_definitions::{
-    ID_LOOKUP, INVOKER_ENQUEUE, INVOKER_INVOCATION_TASKS, TASK_OP_COMPLETED, TASK_OP_FAILED,
-    TASK_OP_STARTED, TASK_OP_SUSPENDED,
+    ID_LOOKUP, INVOKER_CIRCUIT_BREAKER_PARKED, INVOKER_ENQUEUE, INVOKER_INVOCATION_TASKS,
+    TASK_OP_COMPLETED, TASK_OP_FAILED, TASK_OP_STARTED, TASK_OP_SUSPENDED,
 };
 use crate::status_store::InvocationStatusStore;
 
+pub use circuit_breaker::DeploymentCircuitBreakers;
 pub use input_command::ChannelStatusReader;
 pub use input_command::InvokerHandle;
 
@@ -113,6 +116,8 @@ struct DefaultInvocationTaskRunner<EE, Schemas> {
     entry_enricher: EE,
     schemas: Live<Schemas>,
     action_token_bucket: Option<TokenBucket>,
+    circuit_breakers: DeploymentCircuitBreakers,
+    payload_store: Option<PayloadStore>,
 }
 
 impl<IR, EE, Schemas> InvocationTaskRunner<IR> for DefaultInvocationTaskRunner<EE, Schemas>
@@ -150,6 +155,7 @@ where
                     opts.disable_eager_state,
                     opts.message_size_warning.get(),
                     opts.message_size_limit(),
+                    opts.message_compression_threshold.get(),
                     retry_count_since_last_stored_entry,
                     storage_reader,
                     self.entry_enricher.clone(),
@@ -157,6 +163,9 @@ where
                     invoker_tx,
                     invoker_rx,
                     self.action_token_bucket.clone(),
+                    self.circuit_breakers.clone(),
+                    opts.circuit_breaker.clone(),
+                    self.payload_store.clone(),
                 )
                 .run(input_journal),
             )
@@ -225,6 +234,8 @@ impl<StorageReader, TEntryEnricher, Schemas> Service<StorageReader, TEntryEnrich
         entry_enricher: TEntryEnricher,
         invocation_token_bucket: Option<TokenBucket>,
         action_token_bucket: Option<TokenBucket>,
+        circuit_breakers: DeploymentCircuitBreakers,
+        payload_store: Option<PayloadStore>,
     ) -> Service<StorageReader, TEntryEnricher, Schemas>
     where
         StorageReader: InvocationReader + Clone + Send + Sync + 'static,
@@ -249,6 +260,8 @@ impl<StorageReader, TEntryEnricher, Schemas> Service<StorageReader, TEntryEnrich
                     entry_enricher,
                     schemas: Live::clone(&schemas),
                     action_token_bucket,
+                    circuit_breakers: circuit_breakers.clone(),
+                    payload_store,
                 },
                 schemas,
                 invocation_tasks: Default::default(),
@@ -259,6 +272,7 @@ impl<StorageReader, TEntryEnricher, Schemas> Service<StorageReader, TEntryEnrich
                 ),
                 status_store: Default::default(),
                 invocation_state_machine_manager: Default::default(),
+                circuit_breakers,
             },
             invocation_token_bucket,
         }
@@ -272,6 +286,8 @@ impl<StorageReader, TEntryEnricher, Schemas> Service<StorageReader, TEntryEnrich
         schemas: Live<Schemas>,
         invocation_token_bucket: Option<TokenBucket>,
         action_token_bucket: Option<TokenBucket>,
+        circuit_breakers: DeploymentCircuitBreakers,
+        payload_store: Option<PayloadStore>,
     ) -> Result<Service<StorageReader, TEntryEnricher, Schemas>, BuildError>
     where
         StorageReader: InvocationReader + Clone + Send + Sync + 'static,
@@ -290,6 +306,8 @@ impl<StorageReader, TEntryEnricher, Schemas> Service<StorageReader, TEntryEnrich
             entry_enricher,
             invocation_token_bucket,
             action_token_bucket,
+            circuit_breakers,
+            payload_store,
         ))
     }
 }
@@ -313,7 +331,7 @@ where
     }
 
     pub fn status_reader(&self) -> ChannelStatusReader {
-        ChannelStatusReader(self.status_tx.clone())
+        ChannelStatusReader(self.status_tx.clone(), self.inner.circuit_breakers.clone())
     }
 
     pub async fn run(self, mut updateable_options: impl LiveLoad<Live = InvokerOptions>) {
@@ -399,6 +417,7 @@ struct ServiceInner<InvocationTaskRunner, Schemas, StorageReader> {
     status_store: InvocationStatusStore,
     invocation_state_machine_manager:
         state_machine_manager::InvocationStateMachineManager<StorageReader>,
+    circuit_breakers: DeploymentCircuitBreakers,
 }
 
 impl<ITR, Schemas, IR> ServiceInner<ITR, Schemas, IR>
@@ -521,7 +540,7 @@ where
                         self.handle_invocation_task_closed(partition, invocation_id, invocation_epoch).await
                     },
                     InvocationTaskOutputInner::Failed(e) => {
-                        self.handle_invocation_task_failed(partition, invocation_id, invocation_epoch, e).await
+                        self.handle_invocation_task_failed(options, partition, invocation_id, invocation_epoch, e).await
                     },
                     InvocationTaskOutputInner::Suspended(indexes) => {
                         self.handle_invocation_task_suspended(partition, invocation_id, invocation_epoch, indexes).await
@@ -1043,6 +1062,9 @@ where
             .remove_invocation_with_epoch(partition, &invocation_id, invocation_epoch)
         {
             debug_assert_eq!(invocation_epoch, ism.invocation_epoch);
+            if let Some(deployment_id) = ism.attempt_deployment_id().deployment_id() {
+                self.circuit_breakers.record_success(deployment_id);
+            }
             counter!(INVOKER_INVOCATION_TASKS, "status" => TASK_OP_COMPLETED, "partition_id" => ID_LOOKUP.get(partition.0)).increment(1);
             trace!(
                 restate.invocation.target = %ism.invocation_target,
@@ -1083,6 +1105,9 @@ where
             .remove_invocation_with_epoch(partition, &invocation_id, invocation_epoch)
         {
             debug_assert_eq!(invocation_epoch, ism.invocation_epoch);
+            if let Some(deployment_id) = ism.attempt_deployment_id().deployment_id() {
+                self.circuit_breakers.record_success(deployment_id);
+            }
             counter!(INVOKER_INVOCATION_TASKS, "status" => TASK_OP_SUSPENDED, "partition_id" => ID_LOOKUP.get(partition.0)).increment(1);
             self.quota.unreserve_slot();
             self.status_store.on_end(&partition, &invocation_id);
@@ -1148,6 +1173,9 @@ where
             .remove_invocation_with_epoch(partition, &invocation_id, invocation_epoch)
         {
             debug_assert_eq!(invocation_epoch, ism.invocation_epoch);
+            if let Some(deployment_id) = ism.attempt_deployment_id().deployment_id() {
+                self.circuit_breakers.record_success(deployment_id);
+            }
             counter!(INVOKER_INVOCATION_TASKS, "status" => TASK_OP_SUSPENDED, "partition_id" => ID_LOOKUP.get(partition.0))
                 .increment(1);
             self.quota.unreserve_slot();
@@ -1204,6 +1232,7 @@ where
     )]
     async fn handle_invocation_task_failed(
         &mut self,
+        options: &InvokerOptions,
         partition: PartitionLeaderEpoch,
         invocation_id: InvocationId,
         invocation_epoch: InvocationEpoch,
@@ -1214,7 +1243,7 @@ where
             .remove_invocation_with_epoch(partition, &invocation_id, invocation_epoch)
         {
             debug_assert_eq!(invocation_epoch, ism.invocation_epoch);
-            self.handle_error_event(partition, invocation_id, error, ism)
+            self.handle_error_event(options, partition, invocation_id, error, ism)
                 .await;
         } else {
             // If no state machine, this might be a result for an aborted invocation.
@@ -1363,17 +1392,30 @@ where
 
     async fn handle_error_event(
         &mut self,
+        options: &InvokerOptions,
         partition: PartitionLeaderEpoch,
         invocation_id: InvocationId,
         error: InvokerError,
         mut ism: InvocationStateMachine,
     ) {
         let attempt_deployment_id = ism.attempt_deployment_id();
-        match ism.handle_task_error(
-            error.is_transient(),
-            error.next_retry_interval_override(),
-            error.should_bump_start_message_retry_count_since_last_stored_entry(),
-        ) {
+        let on_task_error =
+            if let InvokerError::DeploymentCircuitBreakerOpen(_, retry_after) = &error {
+                ism.park(*retry_after)
+            } else if let Some(park_duration) =
+                self.record_attempt_outcome(options, attempt_deployment_id.deployment_id(), &error)
+            {
+                ism.park(park_duration)
+            } else {
+                ism.handle_task_error(
+                    error.is_transient(),
+                    error.error_class(),
+                    error.next_retry_interval_override(),
+                    error.should_bump_start_message_retry_count_since_last_stored_entry(),
+                )
+            };
+
+        match on_task_error {
             OnTaskError::ScheduleRetry(next_retry_timer_duration) => {
                 counter!(INVOKER_INVOCATION_TASKS,
                     "status" => TASK_OP_FAILED,
@@ -1436,21 +1478,22 @@ where
                     related_command_type: journal_v2_related_command_type,
                 };
 
-                // Some trivial deduplication here: if we already sent this transient error in the previous retry, don't send it again
-                if ism.should_emit_transient_error_event(&event) {
-                    let _ = self
-                        .invocation_state_machine_manager
-                        .resolve_partition_sender(partition)
-                        .expect("Partition should be registered")
-                        .send(Box::new(Effect {
-                            invocation_id,
-                            invocation_epoch: ism.invocation_epoch,
-                            kind: EffectKind::JournalEvent {
-                                event: RawEvent::from(Event::TransientError(event)),
-                            },
-                        }))
-                        .await;
-                }
+                // Every failed attempt is sent, so the partition processor can count the retries.
+                // Some trivial deduplication here: if we already sent this transient error in the previous retry, mark it as duplicate so it's not stored again
+                let duplicate = !ism.should_emit_transient_error_event(&event);
+                let _ = self
+                    .invocation_state_machine_manager
+                    .resolve_partition_sender(partition)
+                    .expect("Partition should be registered")
+                    .send(Box::new(Effect {
+                        invocation_id,
+                        invocation_epoch: ism.invocation_epoch,
+                        kind: EffectKind::JournalEvent {
+                            event: RawEvent::from(Event::TransientError(event)),
+                            duplicate,
+                        },
+                    }))
+                    .await;
 
                 self.status_store.on_failure(
                     partition,
@@ -1467,6 +1510,36 @@ where
                 self.retry_timers
                     .sleep_until(next_retry_at, (partition, invocation_id, epoch));
             }
+            OnTaskError::Park(park_duration) => {
+                counter!(INVOKER_CIRCUIT_BREAKER_PARKED,
+                    "partition_id" => ID_LOOKUP.get(partition.0)
+                )
+                .increment(1);
+                debug!(
+                    restate.invocation.id = %invocation_id,
+                    restate.invocation.target = %ism.invocation_target,
+                    restate.deployment.id = %attempt_deployment_id,
+                    "Circuit breaker of the deployment is open, parking the invocation for {}: {}",
+                    park_duration.friendly(),
+                    error
+                );
+                let next_retry_at = SystemTime::now() + park_duration;
+
+                self.status_store.on_failure(
+                    partition,
+                    invocation_id,
+                    error.into_invocation_error_report(),
+                    Some(next_retry_at),
+                );
+                let epoch = ism.invocation_epoch;
+                self.invocation_state_machine_manager.register_invocation(
+                    partition,
+                    invocation_id,
+                    ism,
+                );
+                self.retry_timers
+                    .sleep_until(next_retry_at, (partition, invocation_id, epoch));
+            }
             OnTaskError::Pause => {
                 counter!(INVOKER_INVOCATION_TASKS,
                     "status" => TASK_OP_FAILED,
@@ -1560,6 +1633,25 @@ where
         }
     }
 
+    /// Feeds the outcome of a failed attempt to the circuit breaker of the deployment it used.
+    /// Returns how long to park the invocation for, if the circuit breaker is open.
+    fn record_attempt_outcome(
+        &self,
+        options: &InvokerOptions,
+        deployment_id: Option<DeploymentId>,
+        error: &InvokerError,
+    ) -> Option<Duration> {
+        let deployment_id = deployment_id?;
+        if error.is_deployment_unavailable() {
+            self.circuit_breakers
+                .record_failure(deployment_id, &options.circuit_breaker)
+        } else {
+            // The deployment was reachable, the failure is unrelated to its availability
+            self.circuit_breakers.record_success(deployment_id);
+            None
+        }
+    }
+
     fn start_invocation_task(
         &mut self,
         options: &InvokerOptions,
@@ -1651,7 +1743,7 @@ mod tests {
     use super::*;
 
     use std::future::{pending, ready};
-    use std::num::NonZeroUsize;
+    use std::num::{NonZeroU32, NonZeroUsize};
     use std::sync::Arc;
     use std::sync::atomic::{AtomicUsize, Ordering};
     use std::time::Duration;
@@ -1668,10 +1760,11 @@ mod tests {
     use restate_invoker_api::InvokerHandle;
     use restate_invoker_api::entry_enricher;
     use restate_invoker_api::test_util::EmptyStorageReader;
+    use restate_invoker_api::{CircuitBreakerReport, CircuitBreakerState};
     use restate_service_protocol_v4::entry_codec::ServiceProtocolV4Codec;
     use restate_test_util::check;
     use restate_time_util::FriendlyDuration;
-    use restate_types::config::InvokerOptionsBuilder;
+    use restate_types::config::{CircuitBreakerOptionsBuilder, InvokerOptionsBuilder};
     use restate_types::deployment::{DeploymentAddress, Headers};
     use restate_types::errors::{InvocationError, codes};
     use restate_types::identifiers::{LeaderEpoch, PartitionId, ServiceRevision};
@@ -1686,8 +1779,9 @@ mod tests {
     use restate_types::retries::{RetryIter, RetryPolicy};
     use restate_types::schema::deployment::Deployment;
     use restate_types::schema::invocation_target::{
-        InvocationAttemptOptions, InvocationTargetMetadata, OnMaxAttempts,
+        InvocationAttemptOptions, InvocationTargetMetadata, OnMaxAttempts, ResolvedRetryRule,
     };
+    use restate_types::schema::routing::ServiceRouting;
     use restate_types::schema::service::ServiceMetadata;
     use restate_types::service_protocol::ServiceProtocolVersion;
 
@@ -1734,6 +1828,7 @@ mod tests {
                 quota: InvokerConcurrencyQuota::new(0, concurrency_limit),
                 status_store: Default::default(),
                 invocation_state_machine_manager: Default::default(),
+                circuit_breakers: Default::default(),
             };
             (input_tx, status_tx, service_inner)
         }
@@ -1851,6 +1946,10 @@ mod tests {
             None
         }
 
+        fn resolve_service_routing(&self, _: impl AsRef<str>) -> Option<ServiceRouting> {
+            None
+        }
+
         fn find_deployment(
             &self,
             _: &DeploymentAddress,
@@ -1913,6 +2012,15 @@ mod tests {
                 self.1.unwrap_or(OnMaxAttempts::Kill),
             )
         }
+
+        fn resolve_invocation_retry_rules(
+            &self,
+            _: Option<&DeploymentId>,
+            _: impl AsRef<str>,
+            _: impl AsRef<str>,
+        ) -> Vec<ResolvedRetryRule> {
+            vec![]
+        }
     }
 
     #[test(restate_core::test)]
@@ -1942,6 +2050,8 @@ mod tests {
             entry_enricher::test_util::MockEntryEnricher,
             None,
             None,
+            Default::default(),
+            None,
         );
 
         let mut handle = service.handle();
@@ -2172,6 +2282,7 @@ mod tests {
         // Handle error coming after the abort (this should be noop)
         service_inner
             .handle_invocation_task_failed(
+                &invoker_options,
                 MOCK_PARTITION,
                 invocation_id,
                 0,
@@ -2225,6 +2336,7 @@ mod tests {
         // Also handle error on epoch 0 should have no effect
         service_inner
             .handle_invocation_task_failed(
+                &InvokerOptions::default(),
                 MOCK_PARTITION,
                 invocation_id,
                 0,
@@ -2431,7 +2543,7 @@ mod tests {
         ism.notify_new_notification_proposal(NotificationId::CompletionId(1));
 
         // Put the state machine in the WaitingRetry state
-        ism.handle_task_error(true, None, true);
+        ism.handle_task_error(true, None, None, true);
 
         // Register the invocation state machine
         service_inner
@@ -2488,6 +2600,7 @@ mod tests {
         // Simulate a transient failure to populate last_retry_attempt_failure
         service_inner
             .handle_invocation_task_failed(
+                &InvokerOptions::default(),
                 MOCK_PARTITION,
                 invocation_id,
                 0,
@@ -2592,7 +2705,13 @@ mod tests {
             error: InvocationError::new(codes::INTERNAL, "boom").into(),
         });
         service_inner
-            .handle_invocation_task_failed(MOCK_PARTITION, invocation_id, 0, error_a)
+            .handle_invocation_task_failed(
+                &invoker_options,
+                MOCK_PARTITION,
+                invocation_id,
+                0,
+                error_a,
+            )
             .await;
         assert_that!(
             *effects_rx
@@ -2602,7 +2721,8 @@ mod tests {
                 invocation_id: eq(invocation_id),
                 invocation_epoch: eq(0),
                 kind: pat!(EffectKind::JournalEvent {
-                    event: predicate(|e: &RawEvent| e.ty() == EventType::TransientError)
+                    event: predicate(|e: &RawEvent| e.ty() == EventType::TransientError),
+                    duplicate: eq(false)
                 })
             })
         );
@@ -2610,18 +2730,33 @@ mod tests {
         // Fire the timer to let the invocation go back to in flight
         service_inner.handle_retry_timer_fired(&invoker_options, MOCK_PARTITION, invocation_id, 0);
 
-        // Same transient error (A again) -> should NOT propose a new event
+        // Same transient error (A again) -> should propose the event marked as duplicate
         let error_a_same = InvokerError::SdkV2(SdkInvocationErrorV2 {
             related_command: None,
             next_retry_interval_override: Some(Duration::from_millis(1)),
             error: InvocationError::new(codes::INTERNAL, "boom").into(),
         });
         service_inner
-            .handle_invocation_task_failed(MOCK_PARTITION, invocation_id, 0, error_a_same)
+            .handle_invocation_task_failed(
+                &invoker_options,
+                MOCK_PARTITION,
+                invocation_id,
+                0,
+                error_a_same,
+            )
             .await;
-        assert!(
-            effects_rx.try_recv().is_err(),
-            "duplicate transient error event should not be proposed"
+        assert_that!(
+            *effects_rx
+                .try_recv()
+                .expect("expected a proposed duplicate transient error event"),
+            pat!(Effect {
+                invocation_id: eq(invocation_id),
+                invocation_epoch: eq(0),
+                kind: pat!(EffectKind::JournalEvent {
+                    event: predicate(|e: &RawEvent| e.ty() == EventType::TransientError),
+                    duplicate: eq(true)
+                })
+            })
         );
 
         // Fire the timer to let the invocation go back to in flight
@@ -2634,7 +2769,13 @@ mod tests {
             error: InvocationError::new(codes::INTERNAL, "boom-2").into(),
         });
         service_inner
-            .handle_invocation_task_failed(MOCK_PARTITION, invocation_id, 0, error_b)
+            .handle_invocation_task_failed(
+                &invoker_options,
+                MOCK_PARTITION,
+                invocation_id,
+                0,
+                error_b,
+            )
             .await;
         assert_that!(
             *effects_rx
@@ -2644,7 +2785,8 @@ mod tests {
                 invocation_id: eq(invocation_id),
                 invocation_epoch: eq(0),
                 kind: pat!(EffectKind::JournalEvent {
-                    event: predicate(|e: &RawEvent| e.ty() == EventType::TransientError)
+                    event: predicate(|e: &RawEvent| e.ty() == EventType::TransientError),
+                    duplicate: eq(false)
                 })
             })
         );
@@ -2673,6 +2815,7 @@ mod tests {
         // Abort error
         service_inner
             .handle_invocation_task_failed(
+                &InvokerOptions::default(),
                 MOCK_PARTITION,
                 invocation_id,
                 0,
@@ -2691,6 +2834,102 @@ mod tests {
         );
     }
 
+    #[test(restate_core::test)]
+    async fn open_circuit_breaker_parks_invocation_without_consuming_retries() {
+        let invoker_options = InvokerOptionsBuilder::default()
+            .circuit_breaker(
+                CircuitBreakerOptionsBuilder::default()
+                    .enabled(true)
+                    .failure_threshold(NonZeroU32::new(1).unwrap())
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        let invocation_id = InvocationId::mock_random();
+        let deployment_id = DeploymentId::new();
+
+        // Without the circuit breaker, the first failure would kill the invocation
+        let (_, _status_tx, mut service_inner) = ServiceInner::mock(
+            (),
+            MockSchemas(Some(RetryPolicy::None), Some(OnMaxAttempts::Kill)),
+            None,
+        );
+        let mut effects_rx = service_inner.register_mock_partition(EmptyStorageReader);
+
+        service_inner.handle_invoke(
+            &invoker_options,
+            MOCK_PARTITION,
+            invocation_id,
+            0,
+            InvocationTarget::mock_virtual_object(),
+            InvokeInputJournal::NoCachedJournal,
+        );
+        service_inner.handle_pinned_deployment(
+            MOCK_PARTITION,
+            invocation_id,
+            0,
+            PinnedDeployment::new(deployment_id, ServiceProtocolVersion::V4),
+            false,
+        );
+
+        // The deployment is down, this trips the circuit breaker
+        service_inner
+            .handle_invocation_task_failed(
+                &invoker_options,
+                MOCK_PARTITION,
+                invocation_id,
+                0,
+                InvokerError::ServiceUnavailable(http::StatusCode::SERVICE_UNAVAILABLE),
+            )
+            .await;
+        assert!(effects_rx.try_recv().is_err());
+        assert!(
+            service_inner
+                .invocation_state_machine_manager
+                .resolve_invocation(MOCK_PARTITION, &invocation_id)
+                .unwrap()
+                .1
+                .is_waiting_retry()
+        );
+        assert_that!(
+            service_inner.circuit_breakers.reports(),
+            elements_are![pat!(CircuitBreakerReport {
+                deployment_id: eq(deployment_id),
+                state: eq(CircuitBreakerState::Open),
+                consecutive_failures: eq(1),
+            })]
+        );
+
+        // Next attempt fails fast because the circuit breaker is open, the invocation is parked again
+        service_inner.handle_retry_timer_fired(&invoker_options, MOCK_PARTITION, invocation_id, 0);
+        service_inner.handle_pinned_deployment(
+            MOCK_PARTITION,
+            invocation_id,
+            0,
+            PinnedDeployment::new(deployment_id, ServiceProtocolVersion::V4),
+            false,
+        );
+        service_inner
+            .handle_invocation_task_failed(
+                &invoker_options,
+                MOCK_PARTITION,
+                invocation_id,
+                0,
+                InvokerError::DeploymentCircuitBreakerOpen(deployment_id, Duration::from_secs(5)),
+            )
+            .await;
+        assert!(effects_rx.try_recv().is_err());
+        assert!(
+            service_inner
+                .invocation_state_machine_manager
+                .resolve_invocation(MOCK_PARTITION, &invocation_id)
+                .unwrap()
+                .1
+                .is_waiting_retry()
+        );
+    }
+
     #[test(restate_core::test)]
     async fn pause_effect_emitted_when_pause_on_max_attempts_and_max_attempts_one() {
         // Configure invoker to propose events to flush transient error event (not strictly needed for pause)
@@ -2726,7 +2965,13 @@ mod tests {
         // First transient error -> schedules retry (because 1 attempt available)
         let error_a = InvokerError::SdkV2(SdkInvocationErrorV2::unknown());
         service_inner
-            .handle_invocation_task_failed(MOCK_PARTITION, invocation_id, 0, error_a)
+            .handle_invocation_task_failed(
+                &invoker_options,
+                MOCK_PARTITION,
+                invocation_id,
+                0,
+                error_a,
+            )
             .await;
         // There might be an extra transient error event proposed; drain if present
         let _ = effects_rx.try_recv();
@@ -2737,7 +2982,13 @@ mod tests {
         // Second transient error -> retries exhausted and Pause behavior -> expect Paused effect
         let error_b = InvokerError::SdkV2(SdkInvocationErrorV2::unknown());
         service_inner
-            .handle_invocation_task_failed(MOCK_PARTITION, invocation_id, 0, error_b)
+            .handle_invocation_task_failed(
+                &invoker_options,
+                MOCK_PARTITION,
+                invocation_id,
+                0,
+                error_b,
+            )
             .await;
 
         let effect = effects_rx
@@ -2798,6 +3049,15 @@ mod tests {
                     Some(_) => (iter, OnMaxAttempts::Kill),
                 }
             }
+
+            fn resolve_invocation_retry_rules(
+                &self,
+                _: Option<&DeploymentId>,
+                _: impl AsRef<str>,
+                _: impl AsRef<str>,
+            ) -> Vec<ResolvedRetryRule> {
+                vec![]
+            }
         }
 
         let invoker_options = InvokerOptionsBuilder::default()
@@ -2827,7 +3087,7 @@ mod tests {
         // First transient failure after pin -> schedules retry
         let err1 = InvokerError::SdkV2(SdkInvocationErrorV2::unknown());
         service_inner
-            .handle_invocation_task_failed(MOCK_PARTITION, invocation_id, 0, err1)
+            .handle_invocation_task_failed(&invoker_options, MOCK_PARTITION, invocation_id, 0, err1)
             .await;
         // Drain any proposed event
         effects_rx.try_recv().unwrap();
@@ -2836,7 +3096,7 @@ mod tests {
         // Second transient failure after pin -> schedules retry (attempts now exhausted)
         let err2 = InvokerError::SdkV2(SdkInvocationErrorV2::unknown());
         service_inner
-            .handle_invocation_task_failed(MOCK_PARTITION, invocation_id, 0, err2)
+            .handle_invocation_task_failed(&invoker_options, MOCK_PARTITION, invocation_id, 0, err2)
             .await;
         effects_rx.try_recv().unwrap_err();
         service_inner.handle_retry_timer_fired(&invoker_options, MOCK_PARTITION, invocation_id, 0);
@@ -2848,7 +3108,7 @@ mod tests {
         // Next failure should hit OnMaxAttempts::Kill immediately (no more retries)
         let err3 = InvokerError::SdkV2(SdkInvocationErrorV2::unknown());
         service_inner
-            .handle_invocation_task_failed(MOCK_PARTITION, invocation_id, 0, err3)
+            .handle_invocation_task_failed(&invoker_options, MOCK_PARTITION, invocation_id, 0, err3)
             .await;
 
         let effect = effects_rx
@@ -2892,7 +3152,13 @@ mod tests {
         // Simulate a transient error to put invocation in WaitingRetry state
         let error = InvokerError::SdkV2(SdkInvocationErrorV2::unknown());
         service_inner
-            .handle_invocation_task_failed(MOCK_PARTITION, invocation_id, 0, error)
+            .handle_invocation_task_failed(
+                &invoker_options,
+                MOCK_PARTITION,
+                invocation_id,
+                0,
+                error,
+            )
             .await;
         // Drain any proposed event
         let _ = effects_rx.try_recv();
@@ -3028,7 +3294,13 @@ mod tests {
         // Simulate the invocation task failing with a transient error
         let error = InvokerError::SdkV2(SdkInvocationErrorV2::unknown());
         service_inner
-            .handle_invocation_task_failed(MOCK_PARTITION, invocation_id, 0, error)
+            .handle_invocation_task_failed(
+                &invoker_options,
+                MOCK_PARTITION,
+                invocation_id,
+                0,
+                error,
+            )
             .await;
 
         // Should emit Paused effect (not Kill or ScheduleRetry) with last_failure set
@@ -3079,7 +3351,13 @@ mod tests {
         // Simulate a transient error to put invocation in WaitingRetry state
         let error = InvokerError::SdkV2(SdkInvocationErrorV2::unknown());
         service_inner
-            .handle_invocation_task_failed(MOCK_PARTITION, invocation_id, 0, error)
+            .handle_invocation_task_failed(
+                &invoker_options,
+                MOCK_PARTITION,
+                invocation_id,
+                0,
+                error,
+            )
             .await;
         // Drain any proposed event
         let _ = effects_rx.try_recv();
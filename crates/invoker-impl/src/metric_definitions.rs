@@ -68,6 +68,10 @@ pub const INVOKER_INVOCATION_TASKS: &str = "restate.invoker.invocation_tasks.tot
 pub const INVOKER_AVAILABLE_SLOTS: &str = "restate.invoker.available_slots";
 pub const INVOKER_CONCURRENCY_LIMIT: &str = "restate.invoker.concurrency_limit";
 pub const INVOKER_TASK_DURATION: &str = "restate.invoker.task_duration.seconds";
+pub const INVOKER_CIRCUIT_BREAKER_TRANSITIONS: &str =
+    "restate.invoker.circuit_breaker.transitions.total";
+pub const INVOKER_CIRCUIT_BREAKER_PARKED: &str = "restate.invoker.circuit_breaker.parked.total";
+pub const INVOKER_DEPLOYMENT_ROUTING: &str = "restate.invoker.deployment_routing.total";
 
 pub const TASK_OP_STARTED: &str = "started";
 pub const TASK_OP_SUSPENDED: &str = "suspended";
@@ -104,4 +108,22 @@ pub(crate) fn describe_metrics() {
         Unit::Seconds,
         "Time taken to complete an invoker task"
     );
+
+    describe_counter!(
+        INVOKER_CIRCUIT_BREAKER_TRANSITIONS,
+        Unit::Count,
+        "Number of deployment circuit breaker state transitions, by target state"
+    );
+
+    describe_counter!(
+        INVOKER_CIRCUIT_BREAKER_PARKED,
+        Unit::Count,
+        "Number of invocation attempts parked because of an open deployment circuit breaker"
+    );
+
+    describe_counter!(
+        INVOKER_DEPLOYMENT_ROUTING,
+        Unit::Count,
+        "Number of new invocations pinned to a deployment, by deployment and routing rule"
+    );
 }
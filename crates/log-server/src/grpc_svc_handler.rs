@@ -21,7 +21,9 @@ use crate::logstore::LogStore;
 use crate::metadata::LogletStateMap;
 use crate::protobuf::log_server_svc_server::{LogServerSvc, LogServerSvcServer};
 use crate::protobuf::{
-    GetDigestRequest, GetDigestResponse, GetLogletInfoRequest, GetLogletInfoResponse,
+    CompactLogletRequest, CompactLogletResponse, GetDigestRequest, GetDigestResponse,
+    GetLogletInfoRequest, GetLogletInfoResponse, ListLogletStorageRequest,
+    ListLogletStorageResponse, LogletStorage,
 };
 
 pub struct LogServerSvcHandler<S> {
@@ -119,4 +121,57 @@ where
         };
         Ok(Response::new(response))
     }
+
+    async fn list_loglet_storage(
+        &self,
+        request: Request<ListLogletStorageRequest>,
+    ) -> Result<Response<ListLogletStorageResponse>, Status> {
+        let request = request.into_inner();
+        let usages = if let Some(loglet_id) = request.loglet_id {
+            vec![
+                self.log_store
+                    .get_loglet_storage_usage(LogletId::from(loglet_id))
+                    .await
+                    .map_err(|e| Status::internal(e.to_string()))?,
+            ]
+        } else {
+            self.log_store
+                .list_loglet_storage_usage()
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?
+        };
+
+        let loglets = usages
+            .into_iter()
+            .map(|usage| LogletStorage {
+                loglet_id: usage.loglet_id.into(),
+                records: usage.records,
+                bytes: usage.bytes,
+            })
+            .collect();
+
+        Ok(Response::new(ListLogletStorageResponse { loglets }))
+    }
+
+    async fn compact_loglet(
+        &self,
+        request: Request<CompactLogletRequest>,
+    ) -> Result<Response<CompactLogletResponse>, Status> {
+        let request = request.into_inner();
+        let loglet_id = LogletId::from(request.loglet_id);
+        let state = self
+            .state_map
+            .get_or_load(loglet_id, &self.log_store)
+            .await
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        self.log_store
+            .compact_loglet(loglet_id, &state)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(CompactLogletResponse {
+            trim_point: state.trim_point().into(),
+        }))
+    }
 }
@@ -398,7 +398,7 @@ impl<S: LogStore> LogletWorker<S> {
         // exhausted.
         match self
             .log_store
-            .enqueue_store(body, set_sequencer_in_metadata)
+            .enqueue_store(body, set_sequencer_in_metadata, *staging_local_tail)
             .await
         {
             Ok(store_token) => {
@@ -15,10 +15,10 @@ use tokio::sync::oneshot;
 
 use restate_bifrost::loglet::OperationError;
 use restate_core::ShutdownError;
-use restate_types::logs::LogletId;
+use restate_types::logs::{LogletId, LogletOffset};
 use restate_types::net::log_server::{Digest, GetDigest, GetRecords, Records, Seal, Store, Trim};
 
-use crate::metadata::{LogStoreMarker, LogletState};
+use crate::metadata::{LogStoreMarker, LogletState, LogletStorageUsage};
 
 pub type Result<T, E = OperationError> = std::result::Result<T, E>;
 
@@ -35,10 +35,13 @@ pub trait LogStore: Clone + Send + 'static {
         loglet_id: LogletId,
     ) -> impl Future<Output = Result<LogletState, OperationError>> + Send;
 
+    /// Records before `local_tail` might be stored already, storing them again doesn't add to
+    /// the storage usage of the loglet.
     fn enqueue_store(
         &self,
         store_message: Store,
         set_sequencer_in_metadata: bool,
+        local_tail: LogletOffset,
     ) -> impl Future<Output = Result<AsyncToken, OperationError>> + Send;
 
     fn enqueue_seal(
@@ -62,6 +65,25 @@ pub trait LogStore: Clone + Send + 'static {
         get_records_message: GetDigest,
         loglet_state: &LogletState,
     ) -> impl Future<Output = Result<Digest, OperationError>> + Send;
+
+    /// Returns the storage usage of the records of a loglet on this node
+    fn get_loglet_storage_usage(
+        &self,
+        loglet_id: LogletId,
+    ) -> impl Future<Output = Result<LogletStorageUsage, OperationError>> + Send;
+
+    /// Returns the storage usage of all loglets that have records stored on this node
+    fn list_loglet_storage_usage(
+        &self,
+    ) -> impl Future<Output = Result<Vec<LogletStorageUsage>, OperationError>> + Send;
+
+    /// Compacts the storage of the loglet's records up to, and including, the trim point to reclaim
+    /// the disk space of trimmed records.
+    fn compact_loglet(
+        &self,
+        loglet_id: LogletId,
+        loglet_state: &LogletState,
+    ) -> impl Future<Output = Result<(), OperationError>> + Send;
 }
 
 /// A future that resolves when a log-store operation is completed
@@ -53,6 +53,21 @@ impl LogletStateMap {
     }
 }
 
+/// Storage consumed by the records of a loglet on this node.
+///
+/// The counters are maintained by the log-store writer as records are stored and trimmed. They
+/// are approximate, records that are stored more than once (e.g. during repairs) are counted
+/// every time they are written, and trimmed records are only released once all records of
+/// their range of offsets have been trimmed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogletStorageUsage {
+    pub loglet_id: LogletId,
+    /// Number of records that are currently stored
+    pub records: u64,
+    /// Bytes of keys and values of the currently stored records
+    pub bytes: u64,
+}
+
 /// Metadata stored for every loglet-id known to this node.
 /// Cheap to clone. Clones share the state.
 #[derive(Clone)]
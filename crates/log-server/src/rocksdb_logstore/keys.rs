@@ -31,6 +31,7 @@ pub(super) enum KeyPrefixKind {
     // metadata column family
     Sequencer = b's',
     TrimPoint = b't',
+    Usage = b'u',
     Seal = b'Z',
     // Do not use u8::MAX
     Invalid = 0xFF,
@@ -151,6 +152,58 @@ impl DataRecordKey {
     }
 }
 
+/// Key of the storage usage of a bucket of offsets of a loglet. Lives in the metadata column
+/// family and shares the key prefix of [`MetadataKey`], so usage keys are handled by the same
+/// merge operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct UsageKey {
+    prefix: KeyPrefix,
+    bucket: u32,
+}
+
+impl UsageKey {
+    pub fn new(loglet_id: LogletId, bucket: u32) -> Self {
+        Self {
+            prefix: KeyPrefix::new(KeyPrefixKind::Usage, loglet_id),
+            bucket,
+        }
+    }
+
+    pub fn loglet_id(&self) -> LogletId {
+        self.prefix.loglet_id
+    }
+
+    /// The exclusive upper bound of the usage keys of the given loglet
+    pub fn exclusive_upper_bound(loglet_id: LogletId) -> [u8; Self::size()] {
+        let mut buf = [0u8; Self::size()];
+        let mut b = &mut buf[..];
+        KeyPrefix::new(KeyPrefixKind::Usage, loglet_id).encode_exclusive_upper_bound(&mut b);
+        (&mut b).put_u32(0);
+        buf
+    }
+
+    /// Encodes this value into its binary representation on the stack
+    pub fn to_binary_array(self) -> [u8; Self::size()] {
+        let mut buf = [0u8; Self::size()];
+        let mut b = &mut buf[..];
+        self.prefix.encode(&mut b);
+        b.put_u32(self.bucket);
+        buf
+    }
+
+    pub fn from_slice<B: Buf>(mut data: B) -> Self {
+        let prefix = KeyPrefix::decode(&mut data);
+        debug_assert_eq!(prefix.kind, KeyPrefixKind::Usage);
+        let bucket = data.get_u32();
+        Self { prefix, bucket }
+    }
+
+    /// The number of bytes required for the binary representation of this value
+    pub const fn size() -> usize {
+        KeyPrefix::size() + size_of::<u32>()
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(super) struct MetadataKey {
     prefix: KeyPrefix,
@@ -165,7 +218,6 @@ impl MetadataKey {
         }
     }
 
-    #[allow(unused)]
     pub fn loglet_id(&self) -> LogletId {
         self.prefix.loglet_id
     }
@@ -179,6 +231,14 @@ impl MetadataKey {
         KeyPrefix::size()
     }
 
+    /// The exclusive upper bound of the metadata keys of all loglets for the given kind
+    pub fn exclusive_upper_bound(kind: KeyPrefixKind) -> [u8; Self::size()] {
+        let mut buf = [0u8; Self::size()];
+        KeyPrefix::new(kind, LogletId::from(u64::MAX))
+            .encode_exclusive_upper_bound(&mut &mut buf[..]);
+        buf
+    }
+
     /// Encodes this value into its binary representation on the stack
     pub fn to_binary_array(self) -> [u8; Self::size()] {
         self.prefix.to_binary_array()
@@ -213,6 +273,23 @@ mod tests {
         assert_eq!(key, key2);
     }
 
+    #[test]
+    fn test_usage_key() {
+        let key = UsageKey::new(1.into(), 2);
+        let bytes = key.to_binary_array();
+        assert_eq!(key, UsageKey::from_slice(&mut bytes.as_slice()));
+        // usage keys sort by bucket within a loglet
+        assert!(UsageKey::new(1.into(), 3).to_binary_array() > bytes);
+        assert!(
+            UsageKey::exclusive_upper_bound(1.into())
+                > UsageKey::new(1.into(), u32::MAX).to_binary_array()
+        );
+        assert!(
+            UsageKey::exclusive_upper_bound(1.into())
+                <= UsageKey::new(2.into(), 0).to_binary_array()
+        );
+    }
+
     #[test]
     fn test_upper_bound() {
         // loglet is within bounds
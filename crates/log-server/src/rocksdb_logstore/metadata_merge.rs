@@ -14,11 +14,12 @@ use tracing::{error, trace};
 use restate_types::logs::{LogletOffset, SequenceNumber};
 
 use crate::rocksdb_logstore::keys::{KeyPrefixKind, MetadataKey};
+use crate::rocksdb_logstore::usage::UsageDelta;
 
 /// The merge operator for the metadata column family.
 ///
 /// This merges some metadata updates to ensure that trimpoints can be processed out of order but
-/// it strictly moves forward on the storage layer.
+/// it strictly moves forward on the storage layer. Storage usage deltas are summed up.
 pub(super) fn metadata_full_merge(
     mut key_buf: &[u8],
     existing_val: Option<&[u8]>,
@@ -26,11 +27,17 @@ pub(super) fn metadata_full_merge(
 ) -> Option<Vec<u8>> {
     let key = MetadataKey::from_slice(&mut key_buf);
     trace!(key = ?key, "metadata_full_merge");
-    if key.kind() != KeyPrefixKind::TrimPoint {
-        error!(key = ?key, "Merge is only supported for trim-points");
-        return None;
+    match key.kind() {
+        KeyPrefixKind::TrimPoint => Some(merge_trim_point(existing_val, operands)),
+        KeyPrefixKind::Usage => Some(merge_usage(existing_val, operands)),
+        _ => {
+            error!(key = ?key, "Merge is only supported for trim-points and usage");
+            None
+        }
     }
+}
 
+fn merge_trim_point(existing_val: Option<&[u8]>, operands: &MergeOperands) -> Vec<u8> {
     let mut current_trim_point = existing_val
         .map(LogletOffset::decode)
         .unwrap_or(LogletOffset::INVALID);
@@ -40,7 +47,16 @@ pub(super) fn metadata_full_merge(
         // trim point can only move forward
         current_trim_point = updated_trim_point.max(current_trim_point);
     }
-    Some(current_trim_point.to_binary_array().into())
+    current_trim_point.to_binary_array().into()
+}
+
+fn merge_usage(existing_val: Option<&[u8]>, operands: &MergeOperands) -> Vec<u8> {
+    let mut usage = existing_val.map(UsageDelta::decode).unwrap_or_default();
+
+    for op in operands {
+        usage = usage.merge(UsageDelta::decode(op));
+    }
+    usage.to_binary_array().into()
 }
 
 pub(super) fn metadata_partial_merge(
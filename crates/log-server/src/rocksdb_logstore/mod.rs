@@ -14,6 +14,7 @@ mod keys;
 mod metadata_merge;
 mod record_format;
 mod store;
+mod usage;
 mod writer;
 
 pub use self::builder::RocksDbLogStoreBuilder;
@@ -16,7 +16,7 @@ use tokio::time::Instant;
 use tracing::trace;
 
 use restate_bifrost::loglet::OperationError;
-use restate_rocksdb::{IoMode, Priority, RocksDb};
+use restate_rocksdb::{CfName, IoMode, Priority, RocksDb};
 use restate_types::GenerationalNodeId;
 use restate_types::health::HealthStatus;
 use restate_types::logs::{LogletId, LogletOffset, SequenceNumber};
@@ -26,12 +26,13 @@ use restate_types::net::log_server::{
 };
 use restate_types::protobuf::common::LogServerStatus;
 
-use super::keys::{KeyPrefixKind, MARKER_KEY, MetadataKey};
+use super::keys::{KeyPrefixKind, MARKER_KEY, MetadataKey, UsageKey};
 use super::record_format::DataRecordDecoder;
+use super::usage::UsageDelta;
 use super::writer::RocksDbLogWriterHandle;
 use super::{DATA_CF, METADATA_CF, RocksDbLogStoreError};
 use crate::logstore::{AsyncToken, LogStore};
-use crate::metadata::{LogStoreMarker, LogletState};
+use crate::metadata::{LogStoreMarker, LogletState, LogletStorageUsage};
 use crate::rocksdb_logstore::keys::DataRecordKey;
 
 #[derive(Clone)]
@@ -59,6 +60,51 @@ impl RocksDbLogStore {
     pub fn db(&self) -> &DB {
         self.rocksdb.inner().as_raw_db()
     }
+
+    /// Sums up the usage buckets within the given key range per loglet
+    async fn read_usage(
+        &self,
+        lower_bound: [u8; UsageKey::size()],
+        upper_bound: impl Into<Vec<u8>>,
+    ) -> Result<Vec<LogletStorageUsage>, OperationError> {
+        let metadata_cf = self.metadata_cf();
+        let mut readopts = ReadOptions::default();
+        readopts.fill_cache(false);
+        readopts.set_total_order_seek(true);
+        readopts.set_iterate_lower_bound(lower_bound);
+        readopts.set_iterate_upper_bound(upper_bound);
+        let mut iterator = self.db().raw_iterator_cf_opt(&metadata_cf, readopts);
+
+        let mut usages = Vec::new();
+        let mut current: Option<(LogletId, UsageDelta)> = None;
+        iterator.seek(lower_bound);
+        while let Some((key, value)) = iterator.item() {
+            let loglet_id = UsageKey::from_slice(key).loglet_id();
+            let delta = UsageDelta::decode(value);
+            current = match current {
+                Some((current_loglet_id, usage)) if current_loglet_id == loglet_id => {
+                    Some((loglet_id, usage.merge(delta)))
+                }
+                Some((current_loglet_id, usage)) => {
+                    usages.push(usage.into_usage(current_loglet_id));
+                    Some((loglet_id, delta))
+                }
+                None => Some((loglet_id, delta)),
+            };
+            iterator.next();
+            tokio::task::consume_budget().await;
+        }
+
+        if let Err(e) = iterator.status() {
+            self.health_status.update(LogServerStatus::Failsafe);
+            return Err(RocksDbLogStoreError::Rocksdb(e).into());
+        }
+
+        if let Some((loglet_id, usage)) = current {
+            usages.push(usage.into_usage(loglet_id));
+        }
+        Ok(usages)
+    }
 }
 
 impl LogStore for RocksDbLogStore {
@@ -196,13 +242,14 @@ impl LogStore for RocksDbLogStore {
         &self,
         store_message: Store,
         set_sequencer_in_metadata: bool,
+        local_tail: LogletOffset,
     ) -> Result<AsyncToken, OperationError> {
         // do not accept INVALID offsets
         if store_message.first_offset == LogletOffset::INVALID {
             return Err(RocksDbLogStoreError::InvalidOffset(store_message.first_offset).into());
         }
         self.writer_handle
-            .enqueue_put_records(store_message, set_sequencer_in_metadata)
+            .enqueue_put_records(store_message, set_sequencer_in_metadata, local_tail)
             .await
     }
 
@@ -490,6 +537,60 @@ impl LogStore for RocksDbLogStore {
             entries,
         })
     }
+
+    async fn get_loglet_storage_usage(
+        &self,
+        loglet_id: LogletId,
+    ) -> Result<LogletStorageUsage, OperationError> {
+        let usages = self
+            .read_usage(
+                UsageKey::new(loglet_id, 0).to_binary_array(),
+                UsageKey::exclusive_upper_bound(loglet_id),
+            )
+            .await?;
+
+        Ok(usages
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| UsageDelta::default().into_usage(loglet_id)))
+    }
+
+    async fn list_loglet_storage_usage(&self) -> Result<Vec<LogletStorageUsage>, OperationError> {
+        let usages = self
+            .read_usage(
+                UsageKey::new(LogletId::from(0), 0).to_binary_array(),
+                MetadataKey::exclusive_upper_bound(KeyPrefixKind::Usage),
+            )
+            .await?;
+
+        // loglets that got trimmed entirely are not interesting
+        Ok(usages
+            .into_iter()
+            .filter(|usage| usage.records > 0)
+            .collect())
+    }
+
+    async fn compact_loglet(
+        &self,
+        loglet_id: LogletId,
+        loglet_state: &LogletState,
+    ) -> Result<(), OperationError> {
+        let trim_point = loglet_state.trim_point();
+        if trim_point == LogletOffset::INVALID {
+            // nothing was trimmed, nothing to reclaim
+            return Ok(());
+        }
+
+        let from = DataRecordKey::new(loglet_id, LogletOffset::INVALID).to_binary_array();
+        let to = DataRecordKey::new(loglet_id, trim_point).to_binary_array();
+        self.rocksdb
+            .clone()
+            .compact_range_cf(CfName::new(DATA_CF), from.to_vec(), to.to_vec())
+            .await
+            .map_err(RocksDbLogStoreError::from)?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -502,6 +603,7 @@ mod tests {
     use restate_types::logs::{LogletId, LogletOffset, Record, SequenceNumber};
     use restate_types::net::log_server::{
         DigestEntry, GetDigest, LogServerRequestHeader, RecordStatus, Status, Store, StoreFlags,
+        Trim,
     };
     use restate_types::{GenerationalNodeId, PlainNodeId};
 
@@ -568,7 +670,7 @@ mod tests {
         };
         // add record at offset=1, no sequencer set.
         log_store
-            .enqueue_store(store_msg_1.clone(), false)
+            .enqueue_store(store_msg_1.clone(), false, LogletOffset::OLDEST)
             .await?
             .await?;
 
@@ -584,7 +686,10 @@ mod tests {
         };
 
         // add record at end of range (4B) (and set sequencer)
-        log_store.enqueue_store(store_msg_2, true).await?.await?;
+        log_store
+            .enqueue_store(store_msg_2, true, LogletOffset::new(2))
+            .await?
+            .await?;
 
         let state = log_store.load_loglet_state(loglet_id_1).await?;
         assert!(!state.is_sealed());
@@ -604,7 +709,10 @@ mod tests {
             sequencer: sequencer_2,
             ..store_msg_1
         };
-        log_store.enqueue_store(store_msg_3, true).await?.await?;
+        log_store
+            .enqueue_store(store_msg_3, true, LogletOffset::OLDEST)
+            .await?
+            .await?;
 
         let state = log_store.load_loglet_state(loglet_id_1).await?;
         assert!(!state.is_sealed());
@@ -676,7 +784,7 @@ mod tests {
                 payloads: payloads.clone().into(),
             };
             log_store
-                .enqueue_store(store_msg.clone(), true)
+                .enqueue_store(store_msg.clone(), true, offset)
                 .await?
                 .await?;
         }
@@ -699,7 +807,10 @@ mod tests {
             flags: StoreFlags::empty(),
             payloads: payloads.into(),
         };
-        log_store.enqueue_store(store_msg, true).await?.await?;
+        log_store
+            .enqueue_store(store_msg, true, LogletOffset::OLDEST)
+            .await?
+            .await?;
         // the adjacent log is at 2
         let state2 = log_store.load_loglet_state(loglet_id_2).await?;
         assert!(!state2.is_sealed());
@@ -797,4 +908,170 @@ mod tests {
         RocksDbManager::get().shutdown().await;
         Ok(())
     }
+
+    #[test(restate_core::test(start_paused = true))]
+    async fn test_loglet_storage_usage() -> Result<()> {
+        let log_store = setup().await?;
+        let loglet_id_1 = LogletId::new_unchecked(88);
+        let loglet_id_2 = LogletId::new_unchecked(89);
+        let sequencer = GenerationalNodeId::new(5, 213);
+
+        let usage = log_store.get_loglet_storage_usage(loglet_id_1).await?;
+        assert_that!(usage.records, eq(0));
+        assert_that!(usage.bytes, eq(0));
+
+        let payloads = vec![Record::from("a sample record".to_owned())];
+        // loglet 1 has records in two usage buckets, 4 in the first and 6 in the second
+        for (loglet_id, offsets) in [(loglet_id_1, 1020..=1029), (loglet_id_2, 1..=2)] {
+            for offset in offsets {
+                let offset = LogletOffset::new(offset);
+                let store_msg = Store {
+                    header: LogServerRequestHeader::new(loglet_id, offset),
+                    timeout_at: None,
+                    sequencer,
+                    known_archived: LogletOffset::INVALID,
+                    first_offset: offset,
+                    flags: StoreFlags::empty(),
+                    payloads: payloads.clone().into(),
+                };
+                log_store
+                    .enqueue_store(store_msg, true, offset)
+                    .await?
+                    .await?;
+            }
+        }
+
+        let usage_1 = log_store.get_loglet_storage_usage(loglet_id_1).await?;
+        assert_that!(usage_1.records, eq(10));
+        assert_that!(usage_1.bytes, gt(0));
+        let usage_2 = log_store.get_loglet_storage_usage(loglet_id_2).await?;
+        assert_that!(usage_2.records, eq(2));
+        assert_that!(usage_2.bytes * 5, eq(usage_1.bytes));
+
+        let usages = log_store.list_loglet_storage_usage().await?;
+        assert_that!(usages, elements_are![eq(usage_1), eq(usage_2)]);
+
+        // trimming part of a bucket keeps its usage
+        let trim_msg = Trim {
+            header: LogServerRequestHeader::new(loglet_id_1, LogletOffset::new(1030)),
+            trim_point: LogletOffset::new(1021),
+        };
+        log_store.enqueue_trim(trim_msg).await?.await?;
+        let usage = log_store.get_loglet_storage_usage(loglet_id_1).await?;
+        assert_that!(usage, eq(usage_1));
+
+        // trimming a bucket entirely removes its records from the usage
+        let trim_msg = Trim {
+            header: LogServerRequestHeader::new(loglet_id_1, LogletOffset::new(1030)),
+            trim_point: LogletOffset::new(1025),
+        };
+        log_store.enqueue_trim(trim_msg).await?.await?;
+
+        let usage = log_store.get_loglet_storage_usage(loglet_id_1).await?;
+        assert_that!(usage.records, eq(6));
+        assert_that!(usage.bytes, eq(usage_2.bytes * 3));
+
+        let usages = log_store.list_loglet_storage_usage().await?;
+        assert_that!(usages, elements_are![eq(usage), eq(usage_2)]);
+
+        // compaction of the trimmed records doesn't change the usage
+        let state = log_store.load_loglet_state(loglet_id_1).await?;
+        assert_that!(state.trim_point(), eq(LogletOffset::new(1025)));
+        log_store.compact_loglet(loglet_id_1, &state).await?;
+        let usage = log_store.get_loglet_storage_usage(loglet_id_1).await?;
+        assert_that!(usage.records, eq(6));
+
+        // trimming everything releases all usage
+        let trim_msg = Trim {
+            header: LogServerRequestHeader::new(loglet_id_1, LogletOffset::new(1030)),
+            trim_point: LogletOffset::MAX,
+        };
+        log_store.enqueue_trim(trim_msg).await?.await?;
+        let usages = log_store.list_loglet_storage_usage().await?;
+        assert_that!(usages, elements_are![eq(usage_2)]);
+
+        TaskCenter::shutdown_node("test completed", 0).await;
+        RocksDbManager::get().shutdown().await;
+        Ok(())
+    }
+
+    #[test(restate_core::test(start_paused = true))]
+    async fn test_storage_usage_of_restored_records() -> Result<()> {
+        let log_store = setup().await?;
+        let loglet_id = LogletId::new_unchecked(88);
+        let store_msg = Store {
+            header: LogServerRequestHeader::new(loglet_id, LogletOffset::OLDEST),
+            timeout_at: None,
+            sequencer: GenerationalNodeId::new(5, 213),
+            known_archived: LogletOffset::INVALID,
+            first_offset: LogletOffset::OLDEST,
+            flags: StoreFlags::empty(),
+            payloads: vec![
+                Record::from("a sample record".to_owned()),
+                Record::from("another sample record".to_owned()),
+            ]
+            .into(),
+        };
+        log_store
+            .enqueue_store(store_msg.clone(), true, LogletOffset::OLDEST)
+            .await?
+            .await?;
+        let usage = log_store.get_loglet_storage_usage(loglet_id).await?;
+        assert_that!(usage.records, eq(2));
+
+        // storing the same offsets again (e.g. a repair store) doesn't change the usage
+        log_store
+            .enqueue_store(store_msg.clone(), false, LogletOffset::new(3))
+            .await?
+            .await?;
+        assert_that!(
+            log_store.get_loglet_storage_usage(loglet_id).await?,
+            eq(usage)
+        );
+
+        // also if they are overwritten within the same write batch
+        let first = log_store
+            .enqueue_store(store_msg.clone(), false, LogletOffset::new(3))
+            .await?;
+        let second = log_store
+            .enqueue_store(store_msg.clone(), false, LogletOffset::new(3))
+            .await?;
+        first.await?;
+        second.await?;
+        assert_that!(
+            log_store.get_loglet_storage_usage(loglet_id).await?,
+            eq(usage)
+        );
+
+        // overwriting a record with a different one accounts for the new size
+        let repair_msg = Store {
+            first_offset: LogletOffset::new(2),
+            flags: StoreFlags::IgnoreSeal,
+            payloads: vec![Record::from("a repaired record".to_owned())].into(),
+            ..store_msg.clone()
+        };
+        log_store
+            .enqueue_store(repair_msg, false, LogletOffset::new(3))
+            .await?
+            .await?;
+        let repaired_usage = log_store.get_loglet_storage_usage(loglet_id).await?;
+        assert_that!(repaired_usage.records, eq(2));
+        assert_that!(repaired_usage.bytes, lt(usage.bytes));
+
+        // new records after the local tail are added
+        let append_msg = Store {
+            first_offset: LogletOffset::new(3),
+            ..store_msg
+        };
+        log_store
+            .enqueue_store(append_msg, false, LogletOffset::new(3))
+            .await?
+            .await?;
+        let usage = log_store.get_loglet_storage_usage(loglet_id).await?;
+        assert_that!(usage.records, eq(4));
+
+        TaskCenter::shutdown_node("test completed", 0).await;
+        RocksDbManager::get().shutdown().await;
+        Ok(())
+    }
 }
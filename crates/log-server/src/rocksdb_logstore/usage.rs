@@ -0,0 +1,141 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::mem::size_of;
+
+use bytes::{Buf, BufMut};
+
+use restate_types::logs::{LogletId, LogletOffset};
+
+use crate::metadata::LogletStorageUsage;
+
+/// Storage usage is accounted per bucket of consecutive offsets. Trims release the usage of all
+/// buckets whose offsets have been trimmed entirely, without having to read the trimmed records.
+/// The usage of a partially trimmed bucket is released once the trim point moves past its end.
+pub(super) const OFFSETS_PER_USAGE_BUCKET: u64 = 1024;
+
+/// The bucket that accounts for the record at `offset`
+pub(super) fn usage_bucket(offset: LogletOffset) -> u32 {
+    u32::try_from(u64::from(*offset) / OFFSETS_PER_USAGE_BUCKET).expect("fits u32")
+}
+
+/// The first bucket that still contains offsets after trimming up to, and including,
+/// `trim_point`
+pub(super) fn first_retained_usage_bucket(trim_point: LogletOffset) -> u32 {
+    u32::try_from((u64::from(*trim_point) + 1) / OFFSETS_PER_USAGE_BUCKET).expect("fits u32")
+}
+
+/// A change to the storage usage counters of a loglet.
+///
+/// Deltas are merged into the usage record of the loglet by the metadata merge operator, the
+/// stored value is the sum of all deltas and uses the same binary representation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(super) struct UsageDelta {
+    pub records: i64,
+    pub bytes: i64,
+}
+
+impl UsageDelta {
+    pub fn added(records: usize, bytes: usize) -> Self {
+        Self {
+            records: i64::try_from(records).unwrap_or(i64::MAX),
+            bytes: i64::try_from(bytes).unwrap_or(i64::MAX),
+        }
+    }
+
+    pub fn removed(records: usize, bytes: usize) -> Self {
+        let added = Self::added(records, bytes);
+        Self {
+            records: -added.records,
+            bytes: -added.bytes,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records == 0 && self.bytes == 0
+    }
+
+    pub fn merge(self, other: UsageDelta) -> Self {
+        Self {
+            records: self.records.saturating_add(other.records),
+            bytes: self.bytes.saturating_add(other.bytes),
+        }
+    }
+
+    pub fn into_usage(self, loglet_id: LogletId) -> LogletStorageUsage {
+        // counters can become negative if records that were never accounted for got trimmed
+        LogletStorageUsage {
+            loglet_id,
+            records: u64::try_from(self.records).unwrap_or(0),
+            bytes: u64::try_from(self.bytes).unwrap_or(0),
+        }
+    }
+
+    /// Encodes this value into its binary representation on the stack
+    pub fn to_binary_array(self) -> [u8; Self::size()] {
+        let mut buf = [0u8; Self::size()];
+        let mut b = &mut buf[..];
+        b.put_i64(self.records);
+        b.put_i64(self.bytes);
+        buf
+    }
+
+    pub fn decode<B: Buf>(mut data: B) -> Self {
+        let records = data.get_i64();
+        let bytes = data.get_i64();
+        Self { records, bytes }
+    }
+
+    /// The number of bytes required for the binary representation of this value
+    pub const fn size() -> usize {
+        size_of::<i64>() * 2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use restate_types::logs::SequenceNumber;
+
+    #[test]
+    fn merge_usage_deltas() {
+        let total = UsageDelta::added(10, 1000)
+            .merge(UsageDelta::removed(4, 400))
+            .merge(UsageDelta::added(1, 100));
+        let decoded = UsageDelta::decode(&total.to_binary_array()[..]);
+        assert_eq!(total, decoded);
+
+        let usage = decoded.into_usage(LogletId::from(1));
+        assert_eq!(7, usage.records);
+        assert_eq!(700, usage.bytes);
+
+        // trimming more than what we accounted for doesn't underflow
+        let usage = UsageDelta::removed(1, 1).into_usage(LogletId::from(1));
+        assert_eq!(0, usage.records);
+        assert_eq!(0, usage.bytes);
+    }
+
+    #[test]
+    fn usage_buckets() {
+        assert_eq!(0, usage_bucket(LogletOffset::OLDEST));
+        assert_eq!(0, usage_bucket(LogletOffset::new(1023)));
+        assert_eq!(1, usage_bucket(LogletOffset::new(1024)));
+
+        // a bucket is only released once all of its offsets are trimmed
+        assert_eq!(0, first_retained_usage_bucket(LogletOffset::INVALID));
+        assert_eq!(0, first_retained_usage_bucket(LogletOffset::new(1022)));
+        assert_eq!(1, first_retained_usage_bucket(LogletOffset::new(1023)));
+        assert_eq!(
+            usage_bucket(LogletOffset::MAX) + 1,
+            first_retained_usage_bucket(LogletOffset::MAX)
+        );
+    }
+}
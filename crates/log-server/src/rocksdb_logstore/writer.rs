@@ -8,13 +8,14 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::collections::HashMap;
 use std::io::IoSlice;
 use std::sync::Arc;
 
 use bytes::BytesMut;
 use futures::StreamExt as FutureStreamExt;
 use metrics::histogram;
-use rocksdb::{BoundColumnFamily, WriteBatch};
+use rocksdb::{BoundColumnFamily, DB, WriteBatch};
 use tokio::sync::{mpsc, oneshot};
 use tokio_stream::StreamExt as TokioStreamExt;
 use tokio_stream::wrappers::ReceiverStream;
@@ -30,8 +31,9 @@ use restate_types::logs::{LogletId, LogletOffset, SequenceNumber};
 use restate_types::net::log_server::{Seal, Store, Trim};
 use restate_types::protobuf::common::LogServerStatus;
 
-use super::keys::{DataRecordKey, KeyPrefixKind, MetadataKey};
+use super::keys::{DataRecordKey, KeyPrefixKind, MetadataKey, UsageKey};
 use super::record_format::DataRecordEncoder;
+use super::usage::{UsageDelta, first_retained_usage_bucket, usage_bucket};
 use super::{DATA_CF, METADATA_CF};
 use crate::logstore::AsyncToken;
 use crate::metric_definitions::LOG_SERVER_WRITE_BATCH_SIZE_BYTES;
@@ -48,8 +50,14 @@ pub struct LogStoreWriteCommand {
 }
 
 enum DataUpdate {
-    StoreBatch { store_message: Store },
-    TrimLogRecords { trim_point: LogletOffset },
+    StoreBatch {
+        store_message: Store,
+        /// Records before the local tail might be stored already
+        local_tail: LogletOffset,
+    },
+    TrimLogRecords {
+        trim_point: LogletOffset,
+    },
 }
 
 enum MetadataUpdate {
@@ -61,6 +69,9 @@ enum MetadataUpdate {
 pub(crate) struct LogStoreWriter {
     rocksdb: Arc<RocksDb>,
     batch_acks_buf: Vec<Ack>,
+    /// Sizes of the records written in the current batch, needed to account for records that
+    /// are overwritten within the same batch.
+    batch_record_sizes: HashMap<(LogletId, LogletOffset), usize>,
     buffer: BytesMut,
     health_status: HealthStatus<LogServerStatus>,
 }
@@ -70,6 +81,7 @@ impl LogStoreWriter {
         Self {
             rocksdb,
             batch_acks_buf: Vec::default(),
+            batch_record_sizes: HashMap::default(),
             buffer: BytesMut::with_capacity(INITIAL_SERDE_BUFFER_SIZE),
             health_status,
         }
@@ -114,8 +126,11 @@ impl LogStoreWriter {
         self.batch_acks_buf.clear();
         self.batch_acks_buf.reserve(commands.len());
         let batch_acks = &mut self.batch_acks_buf;
+        self.batch_record_sizes.clear();
+        let batch_record_sizes = &mut self.batch_record_sizes;
         let buffer = &mut self.buffer;
         {
+            let db = self.rocksdb.inner().as_raw_db();
             let data_cf = self
                 .rocksdb
                 .inner()
@@ -129,14 +144,22 @@ impl LogStoreWriter {
 
             for command in commands {
                 match command.data_update {
-                    Some(DataUpdate::StoreBatch { store_message }) => Self::process_store_message(
+                    Some(DataUpdate::StoreBatch {
                         store_message,
+                        local_tail,
+                    }) => Self::process_store_message(
+                        store_message,
+                        local_tail,
+                        db,
                         &data_cf,
+                        &metadata_cf,
                         &mut write_batch,
+                        batch_record_sizes,
                         buffer,
                     ),
                     Some(DataUpdate::TrimLogRecords { trim_point }) => Self::trim_log_records(
                         &data_cf,
+                        &metadata_cf,
                         &mut write_batch,
                         command.loglet_id,
                         trim_point,
@@ -163,13 +186,21 @@ impl LogStoreWriter {
         self.commit(opts, write_batch).await;
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn process_store_message(
         store_message: Store,
+        local_tail: LogletOffset,
+        db: &DB,
         data_cf: &Arc<BoundColumnFamily>,
+        metadata_cf: &Arc<BoundColumnFamily>,
         write_batch: &mut WriteBatch,
+        batch_record_sizes: &mut HashMap<(LogletId, LogletOffset), usize>,
         buffer: &mut BytesMut,
     ) {
+        let loglet_id = store_message.header.loglet_id;
         let mut offset = store_message.first_offset;
+        let mut bucket = usage_bucket(offset);
+        let mut bucket_usage = UsageDelta::default();
         for payload in store_message.payloads.iter() {
             let key_bytes =
                 DataRecordKey::new(store_message.header.loglet_id, offset).to_binary_array();
@@ -182,10 +213,72 @@ impl LogStoreWriter {
                 IoSlice::new(value_bytes.last_ref()),
             ];
             write_batch.put_cf_vectored(data_cf, &[IoSlice::new(&key_bytes)], &dst);
+
+            if usage_bucket(offset) != bucket {
+                Self::update_usage(metadata_cf, write_batch, loglet_id, bucket, bucket_usage);
+                bucket = usage_bucket(offset);
+                bucket_usage = UsageDelta::default();
+            }
+            let record_size = key_bytes.len() + dst.iter().map(|slice| slice.len()).sum::<usize>();
+            bucket_usage = bucket_usage.merge(UsageDelta::added(1, record_size));
+            // Records before the local tail are re-stored by repairs or retries, the usage of
+            // the record they overwrite is released.
+            let overwritten_size = if offset < local_tail {
+                Self::stored_record_size(db, data_cf, batch_record_sizes, loglet_id, offset)
+            } else {
+                None
+            };
+            if let Some(overwritten_size) = overwritten_size {
+                bucket_usage = bucket_usage.merge(UsageDelta::removed(1, overwritten_size));
+            }
+            batch_record_sizes.insert((loglet_id, offset), record_size);
+
             buffer.clear();
             // advance the offset for the next record
             offset = offset.next();
         }
+
+        Self::update_usage(metadata_cf, write_batch, loglet_id, bucket, bucket_usage);
+    }
+
+    /// Size of the record at `offset` if it is stored already or was written earlier in the
+    /// current batch
+    fn stored_record_size(
+        db: &DB,
+        data_cf: &Arc<BoundColumnFamily>,
+        batch_record_sizes: &HashMap<(LogletId, LogletOffset), usize>,
+        loglet_id: LogletId,
+        offset: LogletOffset,
+    ) -> Option<usize> {
+        if let Some(size) = batch_record_sizes.get(&(loglet_id, offset)) {
+            return Some(*size);
+        }
+        let key_bytes = DataRecordKey::new(loglet_id, offset).to_binary_array();
+        match db.get_pinned_cf(data_cf, key_bytes) {
+            Ok(value) => value.map(|value| key_bytes.len() + value.len()),
+            Err(err) => {
+                warn!(
+                    %loglet_id,
+                    %offset,
+                    "Failed to read the overwritten record, its storage usage is kept: {err}"
+                );
+                None
+            }
+        }
+    }
+
+    fn update_usage(
+        metadata_cf: &Arc<BoundColumnFamily>,
+        write_batch: &mut WriteBatch,
+        loglet_id: LogletId,
+        bucket: u32,
+        delta: UsageDelta,
+    ) {
+        if delta.is_empty() {
+            return;
+        }
+        let key = UsageKey::new(loglet_id, bucket).to_binary_array();
+        write_batch.merge_cf(metadata_cf, key, delta.to_binary_array());
     }
 
     fn update_metadata(
@@ -215,6 +308,7 @@ impl LogStoreWriter {
 
     fn trim_log_records(
         data_cf: &Arc<BoundColumnFamily>,
+        metadata_cf: &Arc<BoundColumnFamily>,
         write_batch: &mut WriteBatch,
         loglet_id: LogletId,
         trim_point: LogletOffset,
@@ -224,6 +318,16 @@ impl LogStoreWriter {
         let to_key = DataRecordKey::new(loglet_id, trim_point.next()).to_binary_array();
 
         write_batch.delete_range_cf(data_cf, from_key, to_key);
+
+        // release the usage of all buckets that have been trimmed entirely
+        let first_retained_bucket = first_retained_usage_bucket(trim_point);
+        if first_retained_bucket > 0 {
+            write_batch.delete_range_cf(
+                metadata_cf,
+                UsageKey::new(loglet_id, 0).to_binary_array(),
+                UsageKey::new(loglet_id, first_retained_bucket).to_binary_array(),
+            );
+        }
     }
 
     async fn commit(&mut self, opts: &LogServerOptions, write_batch: WriteBatch) {
@@ -293,13 +397,17 @@ impl RocksDbLogWriterHandle {
         &self,
         store_message: Store,
         set_sequencer_in_metadata: bool,
+        local_tail: LogletOffset,
     ) -> Result<AsyncToken, OperationError> {
         let (ack, receiver) = oneshot::channel();
         let loglet_id = store_message.header.loglet_id;
         let metadata_update = set_sequencer_in_metadata.then_some(MetadataUpdate::SetSequencer {
             sequencer: store_message.sequencer,
         });
-        let data_update = DataUpdate::StoreBatch { store_message };
+        let data_update = DataUpdate::StoreBatch {
+            store_message,
+            local_tail,
+        };
 
         self.send_command(LogStoreWriteCommand {
             loglet_id,
@@ -11,7 +11,6 @@
 use crate::cluster_marker::mark_cluster_as_provisioned;
 use restate_core::{MetadataWriter, ShutdownError, TaskCenter, cancellation_token};
 use restate_metadata_store::{MetadataStoreClient, ReadWriteError};
-use restate_types::PlainNodeId;
 use restate_types::config::Configuration;
 use restate_types::errors::MaybeRetryableError;
 use restate_types::metadata_store::keys::NODES_CONFIG_KEY;
@@ -19,6 +18,7 @@ use restate_types::nodes_config::{
     ClusterFingerprint, MetadataServerConfig, MetadataServerState, NodeConfig, NodesConfiguration,
 };
 use restate_types::retries::RetryPolicy;
+use restate_types::{PlainNodeId, SemanticRestateVersion};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tracing::{Level, debug, enabled, info, trace, warn};
@@ -271,6 +271,8 @@ impl<'a> NodeInit<'a> {
                         // update node_config
                         node_config.roles = common.roles;
                         node_config.address = my_advertised_address.clone();
+                        node_config.binary_version =
+                            Some(SemanticRestateVersion::current().clone());
                         node_config.current_generation.bump_generation();
 
                         node_config
@@ -304,6 +306,7 @@ impl<'a> NodeInit<'a> {
                             .metadata_server_config(MetadataServerConfig {
                                 metadata_server_state,
                             })
+                            .binary_version(Some(SemanticRestateVersion::current().clone()))
                             .build()
                     };
 
@@ -36,6 +36,7 @@ use restate_metadata_server::{
     BoxedMetadataServer, MetadataServer, MetadataStoreClient, ReadModifyWriteError,
 };
 use restate_metadata_store::{ReadWriteError, WriteError, retry_on_retryable_error};
+use restate_object_store_util::PayloadStore;
 use restate_partition_store::PartitionStoreManager;
 use restate_tracing_instrumentation::prometheus_metrics::Prometheus;
 use restate_types::config::{CommonOptions, Configuration};
@@ -120,6 +121,10 @@ pub enum BuildError {
     #[code(unknown)]
     MetadataStoreClient(anyhow::Error),
 
+    #[error("failed to initialize payload offloading store: {0}")]
+    #[code(unknown)]
+    PayloadStore(anyhow::Error),
+
     #[error("building metadata store failed: {0}")]
     #[code(unknown)]
     MetadataStore(#[from] anyhow::Error),
@@ -256,6 +261,15 @@ impl Node {
             None
         };
 
+        // Both ingress and workers access offloaded invocation payloads
+        let payload_store = if config.has_role(Role::Worker) || config.has_role(Role::HttpIngress) {
+            PayloadStore::create_if_configured(&config.invocation.payload_offloading)
+                .await
+                .map_err(BuildError::PayloadStore)?
+        } else {
+            None
+        };
+
         let worker_role = if config.has_role(Role::Worker) {
             Some(
                 WorkerRole::create(
@@ -266,6 +280,7 @@ impl Node {
                     networking.clone(),
                     bifrost_svc.handle(),
                     metadata_manager.writer(),
+                    payload_store.clone(),
                 )
                 .await?,
             )
@@ -285,6 +300,7 @@ impl Node {
                 metadata.updateable_schema(),
                 metadata.updateable_partition_table(),
                 PartitionRouting::new(replica_set_states.clone(), tc.clone()),
+                payload_store,
             ))
         } else {
             None
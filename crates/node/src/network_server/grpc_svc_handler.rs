@@ -106,7 +106,8 @@ impl NodeCtlSvcHandler {
             .unwrap_or_else(|| config.common.default_replication.clone());
 
         let provider_configuration =
-            ProviderConfiguration::from((log_provider, log_replication, target_nodeset_size));
+            ProviderConfiguration::from((log_provider, log_replication, target_nodeset_size))
+                .with_erasure_coding(config.bifrost.replicated_loglet.default_erasure_coding);
 
         Ok(ClusterConfiguration {
             num_partitions,
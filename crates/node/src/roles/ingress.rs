@@ -13,6 +13,7 @@ use restate_core::partitions::PartitionRouting;
 use restate_core::worker_api::PartitionProcessorInvocationClient;
 use restate_core::{TaskCenter, TaskKind};
 use restate_ingress_http::{HyperServerIngress, InvocationClientRequestDispatcher};
+use restate_object_store_util::PayloadStore;
 use restate_types::config::IngressOptions;
 use restate_types::health::HealthStatus;
 use restate_types::live::{BoxLiveLoad, Live};
@@ -39,6 +40,7 @@ impl<T: TransportConnect> IngressRole<T> {
         schema: Live<Schema>,
         partition_table: Live<PartitionTable>,
         partition_routing: PartitionRouting,
+        payload_store: Option<PayloadStore>,
     ) -> Self {
         let dispatcher = InvocationClientRequestDispatcher::new(
             PartitionProcessorInvocationClient::new(networking, partition_table, partition_routing),
@@ -48,6 +50,7 @@ impl<T: TransportConnect> IngressRole<T> {
             address_book.take_listeners(),
             dispatcher,
             schema,
+            payload_store,
             health,
         );
 
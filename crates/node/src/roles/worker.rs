@@ -19,6 +19,7 @@ use restate_core::network::Networking;
 use restate_core::network::TransportConnect;
 use restate_core::worker_api::ProcessorsManagerHandle;
 use restate_core::{MetadataWriter, TaskCenter};
+use restate_object_store_util::PayloadStore;
 use restate_partition_store::PartitionStoreManager;
 use restate_storage_query_datafusion::context::QueryContext;
 use restate_types::health::HealthStatus;
@@ -49,6 +50,7 @@ impl WorkerRole {
         networking: Networking<T>,
         bifrost: Bifrost,
         metadata_writer: MetadataWriter,
+        payload_store: Option<PayloadStore>,
     ) -> Result<Self, WorkerRoleBuildError> {
         let worker = Worker::create(
             health_status,
@@ -58,6 +60,7 @@ impl WorkerRole {
             bifrost,
             router_builder,
             metadata_writer,
+            payload_store,
         )
         .await?;
 
@@ -8,6 +8,10 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+mod payload_store;
+
+pub use payload_store::{PayloadStore, PayloadStoreError};
+
 use std::num::NonZeroUsize;
 use std::pin::Pin;
 use std::sync::Arc;
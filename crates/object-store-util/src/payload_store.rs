@@ -0,0 +1,443 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use anyhow::Context;
+use bytes::Bytes;
+use futures::{Stream, StreamExt, TryStreamExt};
+use object_store::path::Path as ObjectPath;
+use object_store::{ObjectStore, PutMode, PutPayload};
+use tracing::info;
+use url::Url;
+
+use restate_types::config::PayloadOffloadingOptions;
+use restate_types::identifiers::{InvocationId, PartitionProcessorRpcRequestId};
+use restate_types::invocation::offloaded_payload::OffloadedPayload;
+use restate_types::journal_v2::CompletionId;
+
+use crate::create_object_store_client;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PayloadStoreError {
+    #[error("malformed offloaded payload reference")]
+    MalformedReference,
+    #[error("offloaded payload '{key}' has length {found}, expected {expected}")]
+    LengthMismatch {
+        key: String,
+        expected: u64,
+        found: u64,
+    },
+    #[error(transparent)]
+    ObjectStore(#[from] object_store::Error),
+}
+
+/// An object stored by the [`PayloadStore`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredPayload {
+    pub invocation_id: InvocationId,
+    /// Key of the object, relative to the objects of the invocation.
+    pub key: String,
+    pub last_modified: SystemTime,
+}
+
+/// Stores large invocation payloads in an object store, replacing them in-band with an
+/// [`OffloadedPayload`] reference.
+///
+/// The objects of an invocation are stored under `{destination}/{invocation_id}/`.
+#[derive(Debug, Clone)]
+pub struct PayloadStore {
+    object_store: Arc<dyn ObjectStore>,
+    prefix: ObjectPath,
+    threshold: usize,
+}
+
+impl PayloadStore {
+    /// Creates the store if a payload offloading destination is configured.
+    pub async fn create_if_configured(
+        options: &PayloadOffloadingOptions,
+    ) -> anyhow::Result<Option<Self>> {
+        let mut destination = if let Some(ref destination) = options.destination {
+            Url::parse(destination).context("Failed parsing payload offloading destination URL")?
+        } else {
+            return Ok(None);
+        };
+        // Prevent passing configuration options to object_store via the destination URL.
+        destination
+            .query()
+            .inspect(|params| info!("Destination parameters ignored: {params}"));
+        destination.set_query(None);
+
+        let prefix = ObjectPath::from(destination.path());
+        let object_store = create_object_store_client(
+            destination,
+            &options.object_store,
+            &options.object_store_retry_policy,
+        )
+        .await?;
+
+        Ok(Some(Self {
+            object_store,
+            prefix,
+            threshold: options.threshold.as_usize(),
+        }))
+    }
+
+    pub fn new(object_store: Arc<dyn ObjectStore>, threshold: usize) -> Self {
+        Self {
+            object_store,
+            prefix: ObjectPath::default(),
+            threshold,
+        }
+    }
+
+    /// Returns true if a payload of the given length exceeds the offloading threshold.
+    pub fn should_offload(&self, len: usize) -> bool {
+        len > self.threshold
+    }
+
+    fn invocation_path(&self, invocation_id: &InvocationId) -> ObjectPath {
+        self.prefix.child(invocation_id.to_string())
+    }
+
+    fn object_path(&self, invocation_id: &InvocationId, key: &str) -> ObjectPath {
+        key.split('/')
+            .fold(self.invocation_path(invocation_id), |path, part| {
+                path.child(part)
+            })
+    }
+
+    async fn offload(
+        &self,
+        invocation_id: &InvocationId,
+        reference: OffloadedPayload,
+        payload: Bytes,
+        mode: PutMode,
+    ) -> Result<Bytes, PayloadStoreError> {
+        match self
+            .object_store
+            .put_opts(
+                &self.object_path(invocation_id, &reference.key),
+                PutPayload::from_bytes(payload),
+                mode.into(),
+            )
+            .await
+        {
+            Ok(_) | Err(object_store::Error::AlreadyExists { .. }) => Ok(reference.encode()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Uploads the input of the given invocation, returning the reference to use in its place.
+    ///
+    /// The same invocation id is generated again when a request is retried with the same
+    /// idempotency key. Such duplicates are deduplicated by the partition processor, hence an
+    /// existing object is never overwritten, as it might be the input of the running invocation.
+    pub async fn offload_input(
+        &self,
+        invocation_id: &InvocationId,
+        payload: Bytes,
+    ) -> Result<Bytes, PayloadStoreError> {
+        let reference = OffloadedPayload::input(payload.len() as u64);
+        self.offload(invocation_id, reference, payload, PutMode::Create)
+            .await
+    }
+
+    /// Uploads the result of a run proposed by the given invocation, returning the reference to
+    /// use in its place.
+    pub async fn offload_run_result(
+        &self,
+        invocation_id: &InvocationId,
+        completion_id: CompletionId,
+        payload: Bytes,
+    ) -> Result<Bytes, PayloadStoreError> {
+        let reference =
+            OffloadedPayload::run_result(completion_id, ulid::Ulid::new(), payload.len() as u64);
+        self.offload(invocation_id, reference, payload, PutMode::Create)
+            .await
+    }
+
+    /// Uploads the response of the given invocation to an ingress request, returning the
+    /// reference to use in its place.
+    pub async fn offload_response(
+        &self,
+        invocation_id: &InvocationId,
+        request_id: &PartitionProcessorRpcRequestId,
+        payload: Bytes,
+    ) -> Result<Bytes, PayloadStoreError> {
+        let reference = OffloadedPayload::response(request_id, payload.len() as u64);
+        self.offload(invocation_id, reference, payload, PutMode::Overwrite)
+            .await
+    }
+
+    /// Resolves the given payload of the given invocation if it is a reference to an offloaded
+    /// payload, otherwise returns it unchanged.
+    pub async fn rehydrate(
+        &self,
+        invocation_id: &InvocationId,
+        payload: Bytes,
+    ) -> Result<Bytes, PayloadStoreError> {
+        if !OffloadedPayload::is_offloaded(&payload) {
+            return Ok(payload);
+        }
+        let reference =
+            OffloadedPayload::decode(&payload).ok_or(PayloadStoreError::MalformedReference)?;
+
+        let bytes = self
+            .object_store
+            .get(&self.object_path(invocation_id, &reference.key))
+            .await?
+            .bytes()
+            .await?;
+        if bytes.len() as u64 != reference.length {
+            return Err(PayloadStoreError::LengthMismatch {
+                key: reference.key,
+                expected: reference.length,
+                found: bytes.len() as u64,
+            });
+        }
+
+        Ok(bytes)
+    }
+
+    /// Lists the objects of the given invocation which are retained together with its journal.
+    fn list_retained_paths(
+        &self,
+        invocation_id: &InvocationId,
+    ) -> impl Stream<Item = Result<(String, ObjectPath), PayloadStoreError>> + use<> {
+        let invocation_path = self.invocation_path(invocation_id);
+        self.object_store
+            .list(Some(&invocation_path))
+            .map_err(PayloadStoreError::from)
+            .try_filter_map(move |meta| {
+                let key = meta
+                    .location
+                    .prefix_match(&invocation_path)
+                    .map(|parts| {
+                        parts
+                            .map(|part| part.as_ref().to_owned())
+                            .collect::<Vec<_>>()
+                            .join("/")
+                    })
+                    .filter(|key| OffloadedPayload::is_retained_with_journal(key));
+                futures::future::ready(Ok(key.map(|key| (key, meta.location))))
+            })
+    }
+
+    /// Copies the objects retained together with the journal of an invocation to another
+    /// invocation, as needed when the journal is copied by restart-as-new.
+    pub async fn copy_invocation_payloads(
+        &self,
+        from: &InvocationId,
+        to: &InvocationId,
+    ) -> Result<(), PayloadStoreError> {
+        let mut objects = std::pin::pin!(self.list_retained_paths(from));
+        while let Some((key, path)) = objects.try_next().await? {
+            self.object_store
+                .copy(&path, &self.object_path(to, &key))
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Deletes the offloaded payloads retained together with the journal of the given
+    /// invocation. The responses to the ingress are left to [`Self::list_payloads`] users, as
+    /// the ingress might not have read them yet.
+    pub async fn delete_invocation_payloads(
+        &self,
+        invocation_id: &InvocationId,
+    ) -> Result<(), PayloadStoreError> {
+        let mut objects = std::pin::pin!(self.list_retained_paths(invocation_id));
+        while let Some((_, path)) = objects.try_next().await? {
+            match self.object_store.delete(&path).await {
+                Ok(_) | Err(object_store::Error::NotFound { .. }) => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok(())
+    }
+
+    /// Lists all the stored payloads, skipping the objects which were not stored by this store.
+    pub fn list_payloads(&self) -> impl Stream<Item = Result<StoredPayload, PayloadStoreError>> {
+        let prefix = self.prefix.clone();
+        self.object_store
+            .list(Some(&self.prefix))
+            .map_err(PayloadStoreError::from)
+            .try_filter_map(move |meta| {
+                let stored_payload = meta.location.prefix_match(&prefix).and_then(|mut parts| {
+                    let invocation_id = InvocationId::from_str(parts.next()?.as_ref()).ok()?;
+                    let key = parts
+                        .map(|part| part.as_ref().to_owned())
+                        .collect::<Vec<_>>()
+                        .join("/");
+                    (!key.is_empty()).then(|| StoredPayload {
+                        invocation_id,
+                        key,
+                        last_modified: meta.last_modified.into(),
+                    })
+                });
+                futures::future::ready(Ok(stored_payload))
+            })
+    }
+
+    /// Deletes the given payload. Deleting missing objects is a no-op.
+    pub async fn delete_payload(&self, payload: &StoredPayload) -> Result<(), PayloadStoreError> {
+        match self
+            .object_store
+            .delete(&self.object_path(&payload.invocation_id, &payload.key))
+            .await
+        {
+            Ok(_) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use object_store::memory::InMemory;
+
+    #[tokio::test]
+    async fn offload_rehydrate_and_delete() {
+        let store = PayloadStore::new(Arc::new(InMemory::new()), 4);
+        let invocation_id = InvocationId::mock_random();
+        let payload = Bytes::from_static(b"a large payload");
+
+        assert!(store.should_offload(payload.len()));
+        assert!(!store.should_offload(4));
+
+        let reference = store
+            .offload_input(&invocation_id, payload.clone())
+            .await
+            .unwrap();
+        assert!(OffloadedPayload::is_offloaded(&reference));
+        assert_eq!(
+            store
+                .rehydrate(&invocation_id, reference.clone())
+                .await
+                .unwrap(),
+            payload
+        );
+
+        // a duplicate request doesn't overwrite the input of the existing invocation
+        store
+            .offload_input(&invocation_id, Bytes::from_static(b"another payload"))
+            .await
+            .unwrap();
+        assert_eq!(
+            store
+                .rehydrate(&invocation_id, reference.clone())
+                .await
+                .unwrap(),
+            payload
+        );
+
+        store
+            .delete_invocation_payloads(&invocation_id)
+            .await
+            .unwrap();
+        assert!(matches!(
+            store.rehydrate(&invocation_id, reference).await,
+            Err(PayloadStoreError::ObjectStore(
+                object_store::Error::NotFound { .. }
+            ))
+        ));
+        // deleting again is a no-op
+        store
+            .delete_invocation_payloads(&invocation_id)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn inline_payloads_pass_through() {
+        let store = PayloadStore::new(Arc::new(InMemory::new()), 4);
+        let payload = Bytes::from_static(b"{}");
+
+        assert_eq!(
+            store
+                .rehydrate(&InvocationId::mock_random(), payload.clone())
+                .await
+                .unwrap(),
+            payload
+        );
+    }
+
+    #[tokio::test]
+    async fn copied_payloads_outlive_the_original_invocation() {
+        let store = PayloadStore::new(Arc::new(InMemory::new()), 4);
+        let original_id = InvocationId::mock_random();
+        let new_id = InvocationId::mock_random();
+        let input = Bytes::from_static(b"a large input");
+        let run_result = Bytes::from_static(b"a large run result");
+
+        let input_reference = store
+            .offload_input(&original_id, input.clone())
+            .await
+            .unwrap();
+        let run_result_reference = store
+            .offload_run_result(&original_id, 1, run_result.clone())
+            .await
+            .unwrap();
+        store
+            .offload_response(
+                &original_id,
+                &PartitionProcessorRpcRequestId::new(),
+                Bytes::from_static(b"a large response"),
+            )
+            .await
+            .unwrap();
+
+        store
+            .copy_invocation_payloads(&original_id, &new_id)
+            .await
+            .unwrap();
+        store
+            .delete_invocation_payloads(&original_id)
+            .await
+            .unwrap();
+
+        // the copied journal entries resolve to the copies
+        assert_eq!(
+            store.rehydrate(&new_id, input_reference).await.unwrap(),
+            input
+        );
+        assert_eq!(
+            store
+                .rehydrate(&new_id, run_result_reference)
+                .await
+                .unwrap(),
+            run_result
+        );
+
+        // the response is neither copied nor deleted together with the journal
+        let payloads: Vec<_> = store.list_payloads().try_collect().await.unwrap();
+        assert_eq!(payloads.len(), 3);
+        let response = payloads
+            .iter()
+            .find(|payload| payload.invocation_id == original_id)
+            .unwrap();
+        assert!(!OffloadedPayload::is_retained_with_journal(&response.key));
+        assert!(
+            payloads
+                .iter()
+                .filter(|payload| payload.invocation_id == new_id)
+                .all(|payload| OffloadedPayload::is_retained_with_journal(&payload.key))
+        );
+
+        store.delete_payload(response).await.unwrap();
+        let payloads: Vec<_> = store.list_payloads().try_collect().await.unwrap();
+        assert_eq!(payloads.len(), 2);
+    }
+}
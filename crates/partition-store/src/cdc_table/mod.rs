@@ -0,0 +1,151 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use bytes::Bytes;
+use bytestring::ByteString;
+
+use restate_rocksdb::RocksDbPerfGuard;
+use restate_storage_api::cdc_table::{ChangePosition, ReadCdcTable, StateChange, WriteCdcTable};
+use restate_storage_api::{Result, StorageError};
+use restate_types::identifiers::PartitionId;
+use restate_types::logs::Lsn;
+use restate_types::storage::StorageCodec;
+
+use crate::TableKind::ChangeCapture;
+use crate::keys::{KeyKind, TableKey, define_table_key};
+use crate::{
+    PaddedPartitionId, PartitionStore, PartitionStoreTransaction, StorageAccess, TableScan,
+    TableScanIterationDecision,
+};
+
+define_table_key!(
+    ChangeCapture,
+    KeyKind::ChangeCapture,
+    ChangeCaptureKey(partition_id: PaddedPartitionId, lsn: u64, index: u32)
+);
+
+/// Writes `change` at the next change position of the transaction. This is a no-op if the
+/// transaction is not capturing changes.
+pub(crate) fn capture_change(
+    transaction: &mut PartitionStoreTransaction<'_>,
+    change: &StateChange,
+) -> Result<()> {
+    let Some(position) = transaction.next_change_position() else {
+        return Ok(());
+    };
+
+    let key = ChangeCaptureKey {
+        partition_id: transaction.partition_id().into(),
+        lsn: u64::from(position.lsn),
+        index: position.index,
+    };
+    transaction.put_kv_storage_codec(key, change)
+}
+
+fn get_next_changes<S: StorageAccess>(
+    storage: &mut S,
+    partition_id: PartitionId,
+    from: ChangePosition,
+    limit: usize,
+) -> Result<Vec<(ChangePosition, StateChange)>> {
+    let _x = RocksDbPerfGuard::new("get-next-changes");
+    let start = ChangeCaptureKey::builder()
+        .partition_id(partition_id.into())
+        .lsn(u64::from(from.lsn))
+        .index(from.index);
+
+    let end = ChangeCaptureKey::builder()
+        .partition_id(partition_id.into())
+        .lsn(u64::MAX)
+        .index(u32::MAX);
+
+    let mut remaining = limit;
+    storage
+        .for_each_key_value_in_place(
+            TableScan::KeyRangeInclusiveInSinglePartition(partition_id, start, end),
+            |k, v| {
+                if remaining == 0 {
+                    return TableScanIterationDecision::Break;
+                }
+                remaining -= 1;
+                TableScanIterationDecision::Emit(decode_key_value(k, v))
+            },
+        )?
+        .into_iter()
+        .collect()
+}
+
+fn truncate_changes<S: StorageAccess>(
+    storage: &mut S,
+    partition_id: PartitionId,
+    up_to_lsn: Lsn,
+) -> Result<()> {
+    let _x = RocksDbPerfGuard::new("truncate-changes");
+    let start = ChangeCaptureKey::builder().partition_id(partition_id.into());
+
+    let end = ChangeCaptureKey::builder()
+        .partition_id(partition_id.into())
+        .lsn(u64::from(up_to_lsn))
+        .index(u32::MAX);
+
+    let keys = storage.for_each_key_value_in_place(
+        TableScan::KeyRangeInclusiveInSinglePartition(partition_id, start, end),
+        |k, _| TableScanIterationDecision::Emit(Ok(Bytes::copy_from_slice(k))),
+    )?;
+
+    for k in keys {
+        let key = k?;
+        storage.delete_cf(ChangeCapture, &key)?;
+    }
+
+    Ok(())
+}
+
+impl ReadCdcTable for PartitionStore {
+    async fn get_next_changes(
+        &mut self,
+        from: ChangePosition,
+        limit: usize,
+    ) -> Result<Vec<(ChangePosition, StateChange)>> {
+        get_next_changes(self, self.partition_id(), from, limit)
+    }
+}
+
+impl ReadCdcTable for PartitionStoreTransaction<'_> {
+    async fn get_next_changes(
+        &mut self,
+        from: ChangePosition,
+        limit: usize,
+    ) -> Result<Vec<(ChangePosition, StateChange)>> {
+        get_next_changes(self, self.partition_id(), from, limit)
+    }
+}
+
+impl WriteCdcTable for PartitionStoreTransaction<'_> {
+    fn capture_changes_at(&mut self, lsn: Lsn, excluded_service: Option<ByteString>) {
+        self.set_change_capture(Some(lsn), excluded_service);
+    }
+
+    fn stop_capturing_changes(&mut self) {
+        self.set_change_capture(None, None);
+    }
+
+    fn truncate_changes(&mut self, up_to_lsn: Lsn) -> Result<()> {
+        truncate_changes(self, self.partition_id(), up_to_lsn)
+    }
+}
+
+fn decode_key_value(mut k: &[u8], mut v: &[u8]) -> Result<(ChangePosition, StateChange)> {
+    let key = ChangeCaptureKey::deserialize_from(&mut k)?;
+    let change =
+        StorageCodec::decode(&mut v).map_err(|err| StorageError::Conversion(err.into()))?;
+
+    Ok((ChangePosition::new(Lsn::from(key.lsn), key.index), change))
+}
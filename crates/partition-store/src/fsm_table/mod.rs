@@ -8,19 +8,26 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use restate_storage_api::Result;
+use std::time::Duration;
+
+use bytes::Bytes;
+use bytestring::ByteString;
+
 use restate_storage_api::fsm_table::{
     PartitionDurability, ReadFsmTable, SequenceNumber, WriteFsmTable,
 };
-use restate_storage_api::protobuf_types::PartitionStoreProtobufValue;
+use restate_storage_api::protobuf_types::{PartitionStoreProtobufValue, ProtobufStorageWrapper};
+use restate_storage_api::{Result, StorageError};
 use restate_types::SemanticRestateVersion;
 use restate_types::identifiers::PartitionId;
 use restate_types::logs::Lsn;
 use restate_types::message::MessageIndex;
 use restate_types::schema::Schema;
+use restate_types::storage::StorageCodec;
+use restate_types::time::MillisSinceEpoch;
 
 use crate::TableKind::PartitionStateMachine;
-use crate::keys::{KeyKind, define_table_key};
+use crate::keys::{KeyKind, TableKey, define_table_key};
 use crate::{PaddedPartitionId, PartitionStore, PartitionStoreTransaction, StorageAccess};
 
 define_table_key!(
@@ -53,6 +60,17 @@ pub(crate) mod fsm_variable {
     pub(crate) const STORAGE_VERSION: u64 = 5;
 
     pub(crate) const SERVICES_SCHEMA_METADATA: u64 = 6;
+
+    /// Stored as a sequence number of 0 (disabled) or 1 (enabled).
+    pub(crate) const CHANGE_CAPTURE_ENABLED: u64 = 7;
+
+    /// Stored as a sequence number of milliseconds, 0 if the invocation history is disabled.
+    pub(crate) const INVOCATION_HISTORY_RETENTION: u64 = 8;
+    /// Stored as a sequence number of milliseconds since the unix epoch.
+    pub(crate) const INVOCATION_HISTORY_TRIM_POINT: u64 = 9;
+
+    /// Stored as the UTF-8 service name, absent if no service is excluded.
+    pub(crate) const CHANGE_CAPTURE_EXCLUDED_SERVICE: u64 = 10;
 }
 
 fn get<T: PartitionStoreProtobufValue, S: StorageAccess>(
@@ -100,6 +118,25 @@ pub async fn get_locally_durable_lsn(partition_store: &mut PartitionStore) -> Re
     .map(|opt| opt.map(|seq_number| Lsn::from(u64::from(seq_number))))
 }
 
+/// Like [`ReadFsmTable::get_invocation_history_trim_point`], but without requiring exclusive
+/// access to the storage, so that it can be used by scans.
+pub(crate) fn read_invocation_history_trim_point<S: StorageAccess>(
+    storage: &S,
+    partition_id: PartitionId,
+) -> Result<MillisSinceEpoch> {
+    let key = create_key(partition_id, fsm_variable::INVOCATION_HISTORY_TRIM_POINT).serialize();
+    let Some(value) = storage.get(PartitionStateMachine, &key)? else {
+        return Ok(MillisSinceEpoch::UNIX_EPOCH);
+    };
+
+    let ProtobufStorageWrapper(trim_point) = StorageCodec::decode::<
+        ProtobufStorageWrapper<<SequenceNumber as PartitionStoreProtobufValue>::ProtobufType>,
+        _,
+    >(&mut value.as_ref())
+    .map_err(|err| StorageError::Generic(err.into()))?;
+    Ok(MillisSinceEpoch::new(trim_point.sequence_number))
+}
+
 pub(crate) async fn get_storage_version<S: StorageAccess>(
     storage: &mut S,
     partition_id: PartitionId,
@@ -158,6 +195,45 @@ impl ReadFsmTable for PartitionStore {
         let key = create_key(self.partition_id(), fsm_variable::SERVICES_SCHEMA_METADATA);
         self.get_value_storage_codec(key)
     }
+
+    async fn get_change_capture_enabled(&mut self) -> Result<bool> {
+        get::<SequenceNumber, _>(
+            self,
+            self.partition_id(),
+            fsm_variable::CHANGE_CAPTURE_ENABLED,
+        )
+        .map(|opt| opt.is_some_and(|enabled| enabled.0 != 0))
+    }
+
+    async fn get_change_capture_excluded_service(&mut self) -> Result<Option<ByteString>> {
+        let key = create_key(
+            self.partition_id(),
+            fsm_variable::CHANGE_CAPTURE_EXCLUDED_SERVICE,
+        );
+        self.get_kv_raw(key, |_, v| {
+            v.map(|v| {
+                ByteString::try_from(Bytes::copy_from_slice(v))
+                    .map_err(|err| StorageError::Conversion(err.into()))
+            })
+            .transpose()
+        })
+    }
+
+    async fn get_invocation_history_retention(&mut self) -> Result<Option<Duration>> {
+        get::<SequenceNumber, _>(
+            self,
+            self.partition_id(),
+            fsm_variable::INVOCATION_HISTORY_RETENTION,
+        )
+        .map(|opt| {
+            opt.filter(|retention| retention.0 != 0)
+                .map(|retention| Duration::from_millis(retention.0))
+        })
+    }
+
+    async fn get_invocation_history_trim_point(&mut self) -> Result<MillisSinceEpoch> {
+        read_invocation_history_trim_point(self, self.partition_id())
+    }
 }
 
 impl WriteFsmTable for PartitionStoreTransaction<'_> {
@@ -210,4 +286,45 @@ impl WriteFsmTable for PartitionStoreTransaction<'_> {
         let key = create_key(self.partition_id(), fsm_variable::SERVICES_SCHEMA_METADATA);
         self.put_kv_storage_codec(key, schema)
     }
+
+    fn put_change_capture_enabled(&mut self, enabled: bool) -> Result<()> {
+        put(
+            self,
+            self.partition_id(),
+            fsm_variable::CHANGE_CAPTURE_ENABLED,
+            &SequenceNumber::from(u64::from(enabled)),
+        )
+    }
+
+    fn put_change_capture_excluded_service(&mut self, service_name: Option<&str>) -> Result<()> {
+        let key = create_key(
+            self.partition_id(),
+            fsm_variable::CHANGE_CAPTURE_EXCLUDED_SERVICE,
+        );
+        match service_name {
+            Some(service_name) => self.put_kv_raw(key, service_name.as_bytes()),
+            None => self.delete_key(&key),
+        }
+    }
+
+    fn put_invocation_history_retention(&mut self, retention: Option<Duration>) -> Result<()> {
+        let retention_millis = retention.map_or(0, |retention| {
+            u64::try_from(retention.as_millis()).unwrap_or(u64::MAX)
+        });
+        put(
+            self,
+            self.partition_id(),
+            fsm_variable::INVOCATION_HISTORY_RETENTION,
+            &SequenceNumber::from(retention_millis),
+        )
+    }
+
+    fn put_invocation_history_trim_point(&mut self, trim_point: MillisSinceEpoch) -> Result<()> {
+        put(
+            self,
+            self.partition_id(),
+            fsm_variable::INVOCATION_HISTORY_TRIM_POINT,
+            &SequenceNumber::from(trim_point.as_u64()),
+        )
+    }
 }
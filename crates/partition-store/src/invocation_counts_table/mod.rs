@@ -0,0 +1,242 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashMap;
+use std::ops::ControlFlow;
+use std::str::FromStr;
+
+use bytestring::ByteString;
+use futures::StreamExt;
+
+use restate_rocksdb::{Priority, RocksDbPerfGuard};
+use restate_storage_api::invocation_counts_table::{
+    InvocationCountKey, ReadInvocationCountsTable, ScanInvocationCountsTable,
+};
+use restate_storage_api::invocation_status_table::{
+    InvocationStatusDiscriminants, ScanInvocationStatusTable,
+};
+use restate_storage_api::{Result, StorageError, Transaction};
+use restate_types::identifiers::{DeploymentId, PartitionId};
+
+use crate::TableKind::InvocationCounts;
+use crate::keys::{KeyKind, TableKey, define_table_key};
+use crate::{
+    PaddedPartitionId, PartitionStore, PartitionStoreTransaction, StorageAccess, TableScan,
+    TableScanIterationDecision, break_on_err,
+};
+
+// Invocations without a pinned deployment are counted with an empty deployment id.
+define_table_key!(
+    InvocationCounts,
+    KeyKind::InvocationCounts,
+    InvocationCountsKey(
+        partition_id: PaddedPartitionId,
+        service_name: ByteString,
+        handler_name: ByteString,
+        status: u8,
+        deployment_id: ByteString
+    )
+);
+
+/// A once assigned byte representation of a status must never be changed!
+fn status_to_u8(status: InvocationStatusDiscriminants) -> u8 {
+    match status {
+        InvocationStatusDiscriminants::Scheduled => 0,
+        InvocationStatusDiscriminants::Inboxed => 1,
+        InvocationStatusDiscriminants::Invoked => 2,
+        InvocationStatusDiscriminants::Suspended => 3,
+        InvocationStatusDiscriminants::Paused => 4,
+        InvocationStatusDiscriminants::Killed => 5,
+        InvocationStatusDiscriminants::Completed => 6,
+    }
+}
+
+fn status_from_u8(status: u8) -> Result<InvocationStatusDiscriminants> {
+    Ok(match status {
+        0 => InvocationStatusDiscriminants::Scheduled,
+        1 => InvocationStatusDiscriminants::Inboxed,
+        2 => InvocationStatusDiscriminants::Invoked,
+        3 => InvocationStatusDiscriminants::Suspended,
+        4 => InvocationStatusDiscriminants::Paused,
+        5 => InvocationStatusDiscriminants::Killed,
+        6 => InvocationStatusDiscriminants::Completed,
+        _ => {
+            return Err(StorageError::Conversion(anyhow::anyhow!(
+                "unknown invocation status '{status}'"
+            )));
+        }
+    })
+}
+
+fn create_invocation_counts_key(
+    partition_id: PartitionId,
+    key: &InvocationCountKey,
+) -> InvocationCountsKey {
+    InvocationCountsKey {
+        partition_id: partition_id.into(),
+        service_name: key.service_name.clone(),
+        handler_name: key.handler_name.clone(),
+        status: status_to_u8(key.status),
+        deployment_id: key
+            .deployment_id
+            .map(|deployment_id| ByteString::from(deployment_id.to_string()))
+            .unwrap_or_default(),
+    }
+}
+
+fn decode_key_value(mut k: &[u8], v: &[u8]) -> Result<(InvocationCountKey, u64)> {
+    let key = InvocationCountsKey::deserialize_from(&mut k)?;
+    let deployment_id = if key.deployment_id.is_empty() {
+        None
+    } else {
+        Some(
+            DeploymentId::from_str(&key.deployment_id)
+                .map_err(|err| StorageError::Conversion(err.into()))?,
+        )
+    };
+
+    Ok((
+        InvocationCountKey {
+            service_name: key.service_name,
+            handler_name: key.handler_name,
+            status: status_from_u8(key.status)?,
+            deployment_id,
+        },
+        decode_count(v)?,
+    ))
+}
+
+fn decode_count(bytes: &[u8]) -> Result<u64> {
+    bytes
+        .try_into()
+        .map(u64::from_be_bytes)
+        .map_err(|err| StorageError::Conversion(err.into()))
+}
+
+fn add_to_invocation_count<S: StorageAccess>(
+    storage: &mut S,
+    partition_id: PartitionId,
+    key: &InvocationCountKey,
+    delta: i64,
+) -> Result<()> {
+    let key = create_invocation_counts_key(partition_id, key);
+    let count = storage.get_kv_raw(key.clone(), |_, value| value.map(decode_count).transpose())?;
+
+    match count.unwrap_or_default().saturating_add_signed(delta) {
+        0 => storage.delete_key(&key),
+        count => storage.put_kv_raw(key, count.to_be_bytes()),
+    }
+}
+
+/// Moves the invocation from the count of its old status to the count of its new status. The
+/// change is applied to the stored counts by [`flush_invocation_count_deltas`].
+pub(crate) fn update_invocation_counts(
+    transaction: &mut PartitionStoreTransaction<'_>,
+    old_key: Option<InvocationCountKey>,
+    new_key: Option<InvocationCountKey>,
+) {
+    if old_key == new_key {
+        // e.g. the journal of a running invocation grew
+        return;
+    }
+
+    if let Some(old_key) = old_key {
+        transaction.add_invocation_count_delta(old_key, -1);
+    }
+    if let Some(new_key) = new_key {
+        transaction.add_invocation_count_delta(new_key, 1);
+    }
+}
+
+/// Applies the invocation count changes of the transaction, reading and writing every changed
+/// count only once.
+pub(crate) fn flush_invocation_count_deltas(
+    transaction: &mut PartitionStoreTransaction<'_>,
+) -> Result<()> {
+    let partition_id = transaction.partition_id();
+    for (key, delta) in transaction.take_invocation_count_deltas() {
+        if delta != 0 {
+            add_to_invocation_count(transaction, partition_id, &key, delta)?;
+        }
+    }
+    Ok(())
+}
+
+fn get_invocation_counts<S: StorageAccess>(
+    storage: &mut S,
+    partition_id: PartitionId,
+) -> Result<Vec<(InvocationCountKey, u64)>> {
+    let _x = RocksDbPerfGuard::new("get-invocation-counts");
+    storage
+        .for_each_key_value_in_place(
+            TableScan::SinglePartition::<InvocationCountsKey>(partition_id),
+            |k, v| TableScanIterationDecision::Emit(decode_key_value(k, v)),
+        )?
+        .into_iter()
+        .collect()
+}
+
+/// Counts the invocation statuses which have been written before the invocation counts were
+/// maintained.
+pub(crate) async fn run_invocation_counts_migration(storage: &mut PartitionStore) -> Result<()> {
+    let partition_key_range = storage.partition_key_range().clone();
+    let partition_id = storage.partition_id();
+
+    let mut counts: HashMap<InvocationCountKey, u64> = HashMap::new();
+    {
+        let invocation_statuses = storage.scan_invocation_statuses(partition_key_range)?;
+        tokio::pin!(invocation_statuses);
+        while let Some(res) = invocation_statuses.next().await {
+            let (_, invocation_status) = res?;
+            if let Some(key) = InvocationCountKey::of(&invocation_status) {
+                *counts.entry(key).or_default() += 1;
+            }
+        }
+    }
+
+    let mut tx = storage.transaction();
+    for (key, count) in counts {
+        tx.put_kv_raw(
+            create_invocation_counts_key(partition_id, &key),
+            count.to_be_bytes(),
+        )?;
+    }
+    tx.commit().await
+}
+
+impl ReadInvocationCountsTable for PartitionStore {
+    async fn get_invocation_counts(&mut self) -> Result<Vec<(InvocationCountKey, u64)>> {
+        get_invocation_counts(self, self.partition_id())
+    }
+}
+
+impl ReadInvocationCountsTable for PartitionStoreTransaction<'_> {
+    async fn get_invocation_counts(&mut self) -> Result<Vec<(InvocationCountKey, u64)>> {
+        flush_invocation_count_deltas(self)?;
+        get_invocation_counts(self, self.partition_id())
+    }
+}
+
+impl ScanInvocationCountsTable for PartitionStore {
+    fn for_each_invocation_count<
+        F: FnMut((InvocationCountKey, u64)) -> ControlFlow<()> + Send + Sync + 'static,
+    >(
+        &self,
+        mut f: F,
+    ) -> Result<impl Future<Output = Result<()>> + Send> {
+        self.iterator_for_each(
+            "df-invocation-counts",
+            Priority::Low,
+            TableScan::SinglePartition::<InvocationCountsKey>(self.partition_id()),
+            move |(key, value)| f(break_on_err(decode_key_value(key, value))?).map_break(Ok),
+        )
+        .map_err(|_| StorageError::OperationalError)
+    }
+}
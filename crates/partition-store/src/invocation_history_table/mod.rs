@@ -0,0 +1,171 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::ops::{ControlFlow, RangeInclusive};
+
+use bytes::Bytes;
+
+use restate_rocksdb::{Priority, RocksDbPerfGuard};
+use restate_storage_api::invocation_history_table::{
+    InvocationSummary, ScanInvocationHistoryTable, WriteInvocationHistoryTable,
+};
+use restate_storage_api::{Result, StorageError};
+use restate_types::identifiers::{InvocationUuid, PartitionId, PartitionKey, WithPartitionKey};
+use restate_types::storage::StorageCodec;
+use restate_types::time::MillisSinceEpoch;
+
+use crate::TableKind::InvocationHistory;
+use crate::fsm_table::read_invocation_history_trim_point;
+use crate::keys::{KeyKind, TableKey, define_table_key};
+use crate::{
+    PaddedPartitionId, PartitionStore, PartitionStoreTransaction, StorageAccess, TableScan,
+    TableScanIterationDecision, break_on_err,
+};
+
+// Summaries are ordered by completion time within a partition, so that trimming the history
+// removes a prefix of the table.
+define_table_key!(
+    InvocationHistory,
+    KeyKind::InvocationHistory,
+    InvocationHistoryKey(
+        partition_id: PaddedPartitionId,
+        completed_at: u64,
+        partition_key: PartitionKey,
+        invocation_uuid: InvocationUuid
+    )
+);
+
+fn put_invocation_summary<S: StorageAccess>(
+    storage: &mut S,
+    partition_id: PartitionId,
+    summary: &InvocationSummary,
+) -> Result<()> {
+    let key = InvocationHistoryKey {
+        partition_id: partition_id.into(),
+        completed_at: summary.completed_at.as_u64(),
+        partition_key: summary.invocation_id.partition_key(),
+        invocation_uuid: summary.invocation_id.invocation_uuid(),
+    };
+    storage.put_kv_storage_codec(key, summary)
+}
+
+fn trim_invocation_history<S: StorageAccess>(
+    storage: &mut S,
+    partition_id: PartitionId,
+    completed_before: MillisSinceEpoch,
+    limit: usize,
+) -> Result<bool> {
+    let _x = RocksDbPerfGuard::new("trim-invocation-history");
+    let Some(last_trimmed) = completed_before.as_u64().checked_sub(1) else {
+        return Ok(true);
+    };
+
+    let start = InvocationHistoryKey::builder().partition_id(partition_id.into());
+    let end = InvocationHistoryKey::builder()
+        .partition_id(partition_id.into())
+        .completed_at(last_trimmed)
+        .partition_key(PartitionKey::MAX)
+        .invocation_uuid(InvocationUuid::from_u128(u128::MAX));
+
+    // Read one more key than the limit, to find out whether the trim is complete
+    let mut remaining = limit + 1;
+    let keys = storage.for_each_key_value_in_place(
+        TableScan::KeyRangeInclusiveInSinglePartition(partition_id, start, end),
+        |k, _| {
+            remaining -= 1;
+            if remaining == 0 {
+                TableScanIterationDecision::BreakWith(Ok(Bytes::copy_from_slice(k)))
+            } else {
+                TableScanIterationDecision::Emit(Ok(Bytes::copy_from_slice(k)))
+            }
+        },
+    )?;
+
+    let mut trimmed = 0;
+    for k in keys {
+        if trimmed == limit {
+            return Ok(false);
+        }
+        storage.delete_cf(InvocationHistory, &k?)?;
+        trimmed += 1;
+    }
+
+    Ok(true)
+}
+
+impl ScanInvocationHistoryTable for PartitionStore {
+    fn for_each_invocation_summary<
+        F: FnMut(InvocationSummary) -> ControlFlow<()> + Send + Sync + 'static,
+    >(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+        mut f: F,
+    ) -> Result<impl Future<Output = Result<()>> + Send> {
+        // Summaries below the trim point might not have been removed yet
+        let trim_point = read_invocation_history_trim_point(self, self.partition_id())?;
+        let start = InvocationHistoryKey::builder()
+            .partition_id(self.partition_id().into())
+            .completed_at(trim_point.as_u64())
+            .partition_key(0)
+            .invocation_uuid(InvocationUuid::from_u128(0));
+        let end = InvocationHistoryKey::builder()
+            .partition_id(self.partition_id().into())
+            .completed_at(u64::MAX)
+            .partition_key(PartitionKey::MAX)
+            .invocation_uuid(InvocationUuid::from_u128(u128::MAX));
+
+        self.iterator_for_each(
+            "df-invocation-history",
+            Priority::Low,
+            TableScan::KeyRangeInclusiveInSinglePartition(self.partition_id(), start, end),
+            move |(_key, mut value)| {
+                let summary: InvocationSummary = break_on_err(
+                    StorageCodec::decode(&mut value)
+                        .map_err(|err| StorageError::Conversion(err.into())),
+                )?;
+                if !range.contains(&summary.invocation_id.partition_key()) {
+                    return ControlFlow::Continue(());
+                }
+                f(summary).map_break(Ok)
+            },
+        )
+        .map_err(|_| StorageError::OperationalError)
+    }
+}
+
+impl WriteInvocationHistoryTable for PartitionStoreTransaction<'_> {
+    fn record_invocation_history(&mut self) {
+        self.set_record_invocation_history(true);
+    }
+
+    fn stop_recording_invocation_history(&mut self) {
+        self.set_record_invocation_history(false);
+    }
+
+    fn is_recording_invocation_history(&self) -> bool {
+        self.is_recording_invocation_history()
+    }
+
+    fn put_invocation_summary(&mut self, summary: &InvocationSummary) -> Result<()> {
+        if !self.is_recording_invocation_history() {
+            return Ok(());
+        }
+        self.assert_partition_key(&summary.invocation_id)?;
+        put_invocation_summary(self, self.partition_id(), summary)
+    }
+
+    fn trim_invocation_history(
+        &mut self,
+        completed_before: MillisSinceEpoch,
+        limit: usize,
+    ) -> Result<bool> {
+        trim_invocation_history(self, self.partition_id(), completed_before, limit)
+    }
+}
@@ -15,6 +15,8 @@ use restate_storage_api::protobuf_types::v1::lazy::InvocationStatusV2Lazy;
 use tokio_stream::StreamExt;
 
 use restate_rocksdb::{Priority, RocksDbPerfGuard};
+use restate_storage_api::cdc_table::{InvocationStatusSnapshot, StateChange};
+use restate_storage_api::invocation_counts_table::InvocationCountKey;
 use restate_storage_api::invocation_status_table::{
     InvocationLite, InvocationStatus, InvocationStatusDiscriminants, InvocationStatusV1,
     InvokedInvocationStatusLite, ReadInvocationStatusTable, ScanInvocationStatusTable,
@@ -25,6 +27,8 @@ use restate_storage_api::{Result, StorageError, Transaction};
 use restate_types::identifiers::{InvocationId, InvocationUuid, PartitionKey, WithPartitionKey};
 
 use crate::TableScan::FullScanPartitionKeyRange;
+use crate::cdc_table::capture_change;
+use crate::invocation_counts_table::update_invocation_counts;
 use crate::keys::{KeyKind, TableKey, define_table_key};
 use crate::scan::TableScan;
 use crate::{PartitionStore, PartitionStoreTransaction, StorageAccess, TableKind, break_on_err};
@@ -168,6 +172,55 @@ impl ReadInvocationStatusTable for PartitionStore {
     }
 }
 
+fn visit_invocation_status_lazy<E, F>(
+    (mut key, mut value): (&[u8], &[u8]),
+    f: &mut F,
+) -> ControlFlow<Result<()>>
+where
+    E: Into<anyhow::Error>,
+    F: for<'a> FnMut(
+        (InvocationId, InvocationStatusV2Lazy<'a>),
+    ) -> ControlFlow<std::result::Result<(), E>>,
+{
+    let status_key = break_on_err(InvocationStatusKey::deserialize_from(&mut key))?;
+
+    if value.len() < std::mem::size_of::<u8>() {
+        return ControlFlow::Break(Err(StorageError::Conversion(
+            restate_types::storage::StorageDecodeError::ReadingCodec(format!(
+                "remaining bytes in buf '{}' < version bytes '{}'",
+                value.len(),
+                std::mem::size_of::<u8>()
+            ))
+            .into(),
+        )));
+    }
+
+    // read version
+    let codec = break_on_err(
+        restate_types::storage::StorageCodecKind::try_from(bytes::Buf::get_u8(&mut value))
+            .map_err(|e| StorageError::Conversion(e.into())),
+    )?;
+
+    let restate_types::storage::StorageCodecKind::Protobuf = codec else {
+        return ControlFlow::Break(Err(StorageError::Conversion(
+            restate_types::storage::StorageDecodeError::UnsupportedCodecKind(codec).into(),
+        )));
+    };
+
+    let inv_status_v2_lazy = break_on_err(
+        InvocationStatusV2Lazy::decode(value).map_err(|e| StorageError::Conversion(e.into())),
+    )?;
+
+    let (partition_key, invocation_uuid) = status_key.split();
+
+    let result = f((
+        InvocationId::from_parts(partition_key, invocation_uuid),
+        inv_status_v2_lazy,
+    ));
+
+    result.map_break(|result| result.map_err(|err| StorageError::Conversion(err.into())))
+}
+
 impl ScanInvocationStatusTable for PartitionStore {
     fn scan_invoked_invocations(
         &self,
@@ -221,45 +274,34 @@ impl ScanInvocationStatusTable for PartitionStore {
                 "df-for-each-invocation-status",
                 Priority::Low,
                 TableScan::FullScanPartitionKeyRange::<InvocationStatusKey>(range.clone()),
-                {
-                    move |(mut key, mut value)| {
-                        let status_key =
-                            break_on_err(InvocationStatusKey::deserialize_from(&mut key))?;
-
-                        if value.len() < std::mem::size_of::<u8>() {
-                            return ControlFlow::Break(Err(StorageError::Conversion(restate_types::storage::StorageDecodeError::ReadingCodec(format!(
-                                "remaining bytes in buf '{}' < version bytes '{}'",
-                                value.len(),
-                                std::mem::size_of::<u8>()
-                            )).into())));
-                        }
-
-                        // read version
-                        let codec = break_on_err(restate_types::storage::StorageCodecKind::try_from(bytes::Buf::get_u8(&mut value)).map_err(|e|StorageError::Conversion(e.into())))?;
-
-                        let restate_types::storage::StorageCodecKind::Protobuf = codec else {
-                            return ControlFlow::Break(Err(StorageError::Conversion(restate_types::storage::StorageDecodeError::UnsupportedCodecKind(codec).into())));
-                        };
-
-                        let inv_status_v2_lazy = break_on_err(restate_storage_api::protobuf_types::v1::lazy::InvocationStatusV2Lazy::decode(value).map_err(|e| StorageError::Conversion(e.into())))?;
-
-                        let (partition_key, invocation_uuid) = status_key.split();
-
-                        let result = f((
-                            InvocationId::from_parts(partition_key, invocation_uuid),
-                            inv_status_v2_lazy,
-                        ));
-
-                        result.map_break(|result| {
-                            result.map_err(|err| StorageError::Conversion(err.into()))
-                        })
-                    }
-                },
+                move |kv| visit_invocation_status_lazy(kv, &mut f),
             )
             .map_err(|_| StorageError::OperationalError)?;
 
         Ok(new_status_keys)
     }
+
+    fn for_each_invocation_status_lazy_of<
+        E: Into<anyhow::Error>,
+        F: for<'a> FnMut(
+                (InvocationId, InvocationStatusV2Lazy<'a>),
+            ) -> ControlFlow<std::result::Result<(), E>>
+            + Send
+            + Sync
+            + 'static,
+    >(
+        &self,
+        invocation_ids: Vec<InvocationId>,
+        mut f: F,
+    ) -> Result<impl Future<Output = Result<()>> + Send> {
+        self.iterator_for_each_prefix(
+            "df-for-each-invocation-status-of",
+            Priority::Low,
+            invocation_ids.iter().map(create_invocation_status_key),
+            move |kv| visit_invocation_status_lazy(kv, &mut f),
+        )
+        .map_err(|_| StorageError::OperationalError)
+    }
 }
 
 impl ReadInvocationStatusTable for PartitionStoreTransaction<'_> {
@@ -268,7 +310,9 @@ impl ReadInvocationStatusTable for PartitionStoreTransaction<'_> {
         invocation_id: &InvocationId,
     ) -> Result<InvocationStatus> {
         self.assert_partition_key(invocation_id)?;
-        get_invocation_status(self, invocation_id)
+        let status = get_invocation_status(self, invocation_id)?;
+        self.remember_read_invocation_count_key(*invocation_id, InvocationCountKey::of(&status));
+        Ok(status)
     }
 }
 
@@ -279,15 +323,74 @@ impl WriteInvocationStatusTable for PartitionStoreTransaction<'_> {
         status: &InvocationStatus,
     ) -> Result<()> {
         self.assert_partition_key(invocation_id)?;
+        let old_key = previous_invocation_count_key(self, invocation_id)?;
+        let new_key = InvocationCountKey::of(status);
+        if is_capturing_status_change(self, old_key.as_ref(), new_key.as_ref()) {
+            capture_status_change(self, invocation_id, status)?;
+        }
+        update_invocation_counts(self, old_key, new_key);
         put_invocation_status(self, invocation_id, status)
     }
 
     fn delete_invocation_status(&mut self, invocation_id: &InvocationId) -> Result<()> {
         self.assert_partition_key(invocation_id)?;
+        let old_key = previous_invocation_count_key(self, invocation_id)?;
+        if is_capturing_status_change(self, old_key.as_ref(), None) {
+            capture_status_change(self, invocation_id, &InvocationStatus::Free)?;
+        }
+        update_invocation_counts(self, old_key, None);
         delete_invocation_status(self, invocation_id)
     }
 }
 
+/// The state machine reads an invocation status before it writes it, so the kind of the
+/// previous status is usually known to the transaction already.
+fn previous_invocation_count_key(
+    transaction: &mut PartitionStoreTransaction<'_>,
+    invocation_id: &InvocationId,
+) -> Result<Option<InvocationCountKey>> {
+    match transaction.take_read_invocation_count_key(invocation_id) {
+        Some(count_key) => Ok(count_key),
+        None => Ok(InvocationCountKey::of(&get_invocation_status(
+            transaction,
+            invocation_id,
+        )?)),
+    }
+}
+
+/// Only transitions between status kinds are captured, updates that don't change the status
+/// itself (e.g. a growing journal) are not.
+fn is_capturing_status_change(
+    transaction: &PartitionStoreTransaction<'_>,
+    old_key: Option<&InvocationCountKey>,
+    new_key: Option<&InvocationCountKey>,
+) -> bool {
+    if old_key.map(|key| key.status) == new_key.map(|key| key.status) {
+        return false;
+    }
+    new_key
+        .or(old_key)
+        .is_some_and(|key| transaction.is_capturing_changes_of(&key.service_name))
+}
+
+/// Captures a status transition of the given invocation. The previous status is read again,
+/// since only the transactions capturing changes need more than its kind.
+fn capture_status_change(
+    transaction: &mut PartitionStoreTransaction<'_>,
+    invocation_id: &InvocationId,
+    new_status: &InvocationStatus,
+) -> Result<()> {
+    let old_status = get_invocation_status(transaction, invocation_id)?;
+    capture_change(
+        transaction,
+        &StateChange::InvocationStatus {
+            invocation_id: *invocation_id,
+            old_status: InvocationStatusSnapshot::new(&old_status),
+            new_status: InvocationStatusSnapshot::new(new_status),
+        },
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use crate::keys::TableKeyPrefix;
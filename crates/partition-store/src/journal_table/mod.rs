@@ -137,6 +137,23 @@ impl ReadJournalTable for PartitionStore {
     }
 }
 
+fn visit_journal_entry(
+    (mut key, mut value): (&[u8], &[u8]),
+    f: &mut impl FnMut((JournalEntryId, JournalEntry)) -> std::ops::ControlFlow<()>,
+) -> std::ops::ControlFlow<Result<()>> {
+    let journal_key = break_on_err(JournalKey::deserialize_from(&mut key))?;
+    let journal_entry = break_on_err(JournalEntry::decode(&mut value))?;
+
+    let (partition_key, invocation_uuid, entry_index) = journal_key.split();
+
+    let journal_entry_id = JournalEntryId::from_parts(
+        InvocationId::from_parts(partition_key, invocation_uuid),
+        entry_index,
+    );
+
+    f((journal_entry_id, journal_entry)).map_break(Ok)
+}
+
 impl ScanJournalTable for PartitionStore {
     fn for_each_journal<
         F: FnMut((JournalEntryId, JournalEntry)) -> std::ops::ControlFlow<()> + Send + Sync + 'static,
@@ -149,19 +166,27 @@ impl ScanJournalTable for PartitionStore {
             "df-v1-journal",
             Priority::Low,
             TableScan::FullScanPartitionKeyRange::<JournalKey>(range),
-            move |(mut key, mut value)| {
-                let journal_key = break_on_err(JournalKey::deserialize_from(&mut key))?;
-                let journal_entry = break_on_err(JournalEntry::decode(&mut value))?;
-
-                let (partition_key, invocation_uuid, entry_index) = journal_key.split();
-
-                let journal_entry_id = JournalEntryId::from_parts(
-                    InvocationId::from_parts(partition_key, invocation_uuid),
-                    entry_index,
-                );
+            move |kv| visit_journal_entry(kv, &mut f),
+        )
+        .map_err(|_| StorageError::OperationalError)
+    }
 
-                f((journal_entry_id, journal_entry)).map_break(Ok)
-            },
+    fn for_each_journal_of<
+        F: FnMut((JournalEntryId, JournalEntry)) -> std::ops::ControlFlow<()> + Send + Sync + 'static,
+    >(
+        &self,
+        invocation_ids: Vec<InvocationId>,
+        mut f: F,
+    ) -> Result<impl Future<Output = Result<()>> + Send> {
+        self.iterator_for_each_prefix(
+            "df-v1-journal-of",
+            Priority::Low,
+            invocation_ids.iter().map(|invocation_id| {
+                JournalKey::builder()
+                    .partition_key(invocation_id.partition_key())
+                    .invocation_uuid(invocation_id.invocation_uuid())
+            }),
+            move |kv| visit_journal_entry(kv, &mut f),
         )
         .map_err(|_| StorageError::OperationalError)
     }
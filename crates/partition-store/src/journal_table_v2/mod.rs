@@ -29,6 +29,7 @@ use restate_storage_api::journal_table_v2::{
 };
 use restate_storage_api::protobuf_types::PartitionStoreProtobufValue;
 use restate_storage_api::{Result, StorageError};
+use restate_types::config::Configuration;
 use restate_types::identifiers::{
     EntryIndex, InvocationId, InvocationUuid, JournalEntryId, PartitionKey, WithPartitionKey,
 };
@@ -103,9 +104,13 @@ fn put_journal_entry<S: StorageAccess>(
         }
     }
 
-    storage.put_kv_proto(
+    storage.put_kv_proto_with_compression(
         write_journal_entry_key(invocation_id, journal_index),
         &StoredEntry(journal_entry.clone()),
+        Configuration::pinned()
+            .worker
+            .storage
+            .journal_compression_threshold(),
     )
 }
 
@@ -346,6 +351,25 @@ impl ReadJournalTable for PartitionStore {
     }
 }
 
+fn visit_journal_entry(
+    (mut key, mut value): (&[u8], &[u8]),
+    f: &mut impl FnMut((JournalEntryId, StoredRawEntry)) -> std::ops::ControlFlow<()>,
+) -> std::ops::ControlFlow<Result<()>> {
+    let journal_key = break_on_err(JournalKey::deserialize_from(&mut key))?;
+    let journal_entry = break_on_err(
+        StoredEntry::decode(&mut value).map_err(|err| StorageError::Conversion(err.into())),
+    )?;
+
+    let (partition_key, invocation_uuid, entry_index) = journal_key.split();
+
+    let journal_entry_id = JournalEntryId::from_parts(
+        InvocationId::from_parts(partition_key, invocation_uuid),
+        entry_index,
+    );
+
+    f((journal_entry_id, journal_entry.0)).map_break(Ok)
+}
+
 impl ScanJournalTable for PartitionStore {
     fn for_each_journal<
         F: FnMut(
@@ -363,22 +387,32 @@ impl ScanJournalTable for PartitionStore {
             "df-v2-journal",
             Priority::Low,
             TableScan::FullScanPartitionKeyRange::<JournalKey>(range),
-            move |(mut key, mut value)| {
-                let journal_key = break_on_err(JournalKey::deserialize_from(&mut key))?;
-                let journal_entry = break_on_err(
-                    StoredEntry::decode(&mut value)
-                        .map_err(|err| StorageError::Conversion(err.into())),
-                )?;
-
-                let (partition_key, invocation_uuid, entry_index) = journal_key.split();
-
-                let journal_entry_id = JournalEntryId::from_parts(
-                    InvocationId::from_parts(partition_key, invocation_uuid),
-                    entry_index,
-                );
+            move |kv| visit_journal_entry(kv, &mut f),
+        )
+        .map_err(|_| StorageError::OperationalError)
+    }
 
-                f((journal_entry_id, journal_entry.0)).map_break(Ok)
-            },
+    fn for_each_journal_of<
+        F: FnMut(
+                (restate_types::identifiers::JournalEntryId, StoredRawEntry),
+            ) -> std::ops::ControlFlow<()>
+            + Send
+            + Sync
+            + 'static,
+    >(
+        &self,
+        invocation_ids: Vec<InvocationId>,
+        mut f: F,
+    ) -> Result<impl Future<Output = Result<()>> + Send> {
+        self.iterator_for_each_prefix(
+            "df-v2-journal-of",
+            Priority::Low,
+            invocation_ids.iter().map(|invocation_id| {
+                JournalKey::builder()
+                    .partition_key(invocation_id.partition_key())
+                    .invocation_uuid(invocation_id.invocation_uuid())
+            }),
+            move |kv| visit_journal_entry(kv, &mut f),
         )
         .map_err(|_| StorageError::OperationalError)
     }
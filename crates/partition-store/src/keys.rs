@@ -42,6 +42,9 @@ pub enum KeyKind {
     State,
     Timers,
     Promise,
+    ChangeCapture,
+    InvocationHistory,
+    InvocationCounts,
 }
 
 impl KeyKind {
@@ -86,6 +89,9 @@ impl KeyKind {
             KeyKind::State => b"st",
             KeyKind::Timers => b"ti",
             KeyKind::Promise => b"pr",
+            KeyKind::ChangeCapture => b"cc",
+            KeyKind::InvocationHistory => b"ih",
+            KeyKind::InvocationCounts => b"ic",
         }
     }
 
@@ -115,6 +121,9 @@ impl KeyKind {
             b"st" => Some(KeyKind::State),
             b"ti" => Some(KeyKind::Timers),
             b"pr" => Some(KeyKind::Promise),
+            b"cc" => Some(KeyKind::ChangeCapture),
+            b"ih" => Some(KeyKind::InvocationHistory),
+            b"ic" => Some(KeyKind::InvocationCounts),
             _ => None,
         }
     }
@@ -584,6 +593,10 @@ impl KeyCodec for TimerKeyKind {
                 target.put_u8(3);
                 invocation_uuid.encode(target);
             }
+            TimerKeyKind::CancelInvocation { invocation_uuid } => {
+                target.put_u8(4);
+                invocation_uuid.encode(target);
+            }
         }
     }
 
@@ -615,6 +628,10 @@ impl KeyCodec for TimerKeyKind {
                 let invocation_uuid = InvocationUuid::decode(source)?;
                 TimerKeyKind::NeoInvoke { invocation_uuid }
             }
+            4 => {
+                let invocation_uuid = InvocationUuid::decode(source)?;
+                TimerKeyKind::CancelInvocation { invocation_uuid }
+            }
             i => {
                 return Err(StorageError::Generic(anyhow!(
                     "Unknown discriminator for TimerKind: '{}'",
@@ -642,6 +659,9 @@ impl KeyCodec for TimerKeyKind {
             TimerKeyKind::CleanInvocationStatus { invocation_uuid } => {
                 KeyCodec::serialized_length(invocation_uuid)
             }
+            TimerKeyKind::CancelInvocation { invocation_uuid } => {
+                KeyCodec::serialized_length(invocation_uuid)
+            }
         }
     }
 }
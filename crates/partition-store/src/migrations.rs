@@ -10,6 +10,7 @@
 
 use strum::EnumCount;
 
+use crate::invocation_counts_table::run_invocation_counts_migration;
 use crate::invocation_status_table::run_invocation_status_v1_migration;
 use crate::{PartitionStore, Result};
 
@@ -22,6 +23,9 @@ pub(crate) enum SchemaVersion {
     /// Migrations:
     /// * Invocation status V1 -> V2
     V1_5 = 1,
+    /// Migrations:
+    /// * Count the existing invocations in the invocation counts table
+    V1_6 = 2,
 }
 
 pub(crate) const LATEST_VERSION: SchemaVersion =
@@ -29,7 +33,7 @@ pub(crate) const LATEST_VERSION: SchemaVersion =
 
 impl From<u16> for SchemaVersion {
     fn from(value: u16) -> Self {
-        SchemaVersion::from_repr(value).unwrap_or(SchemaVersion::V1_5)
+        SchemaVersion::from_repr(value).unwrap_or(SchemaVersion::V1_6)
     }
 }
 
@@ -52,7 +56,10 @@ impl SchemaVersion {
             SchemaVersion::None => {
                 run_invocation_status_v1_migration(storage).await?;
             }
-            SchemaVersion::V1_5 => {}
+            SchemaVersion::V1_5 => {
+                run_invocation_counts_migration(storage).await?;
+            }
+            SchemaVersion::V1_6 => {}
         }
         Ok(())
     }
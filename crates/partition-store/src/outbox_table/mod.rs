@@ -8,18 +8,21 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use std::ops::RangeInclusive;
+use std::ops::{ControlFlow, RangeInclusive};
 
-use restate_rocksdb::RocksDbPerfGuard;
-use restate_storage_api::Result;
-use restate_storage_api::outbox_table::{OutboxMessage, ReadOutboxTable, WriteOutboxTable};
+use restate_rocksdb::{Priority, RocksDbPerfGuard};
+use restate_storage_api::outbox_table::{
+    OutboxMessage, ReadOutboxTable, ScanOutboxTable, WriteOutboxTable,
+};
 use restate_storage_api::protobuf_types::PartitionStoreProtobufValue;
+use restate_storage_api::{Result, StorageError};
 use restate_types::identifiers::PartitionId;
 
 use crate::TableKind::Outbox;
 use crate::keys::{KeyKind, TableKey, define_table_key};
 use crate::{
     PaddedPartitionId, PartitionStore, PartitionStoreTransaction, StorageAccess, TableScan,
+    break_on_err,
 };
 
 define_table_key!(
@@ -141,6 +144,23 @@ impl ReadOutboxTable for PartitionStore {
     }
 }
 
+impl ScanOutboxTable for PartitionStore {
+    fn for_each_outbox<
+        F: FnMut((u64, OutboxMessage)) -> ControlFlow<()> + Send + Sync + 'static,
+    >(
+        &self,
+        mut f: F,
+    ) -> Result<impl Future<Output = Result<()>> + Send> {
+        self.iterator_for_each(
+            "df-outbox",
+            Priority::Low,
+            TableScan::SinglePartition::<OutboxKey>(self.partition_id()),
+            move |(key, value)| f(break_on_err(decode_key_value(key, value))?).map_break(Ok),
+        )
+        .map_err(|_| StorageError::OperationalError)
+    }
+}
+
 impl ReadOutboxTable for PartitionStoreTransaction<'_> {
     async fn get_outbox_head_seq_number(&mut self) -> Result<Option<u64>> {
         get_outbox_head_seq_number(self, self.partition_id())
@@ -8,6 +8,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::collections::HashMap;
 use std::ops::ControlFlow;
 use std::ops::RangeInclusive;
 use std::path::Path;
@@ -16,6 +17,7 @@ use std::sync::Arc;
 use anyhow::anyhow;
 use bytes::Bytes;
 use bytes::BytesMut;
+use bytestring::ByteString;
 use enum_map::Enum;
 use rocksdb::{
     BoundColumnFamily, DBPinnableSlice, DBRawIteratorWithThreadMode, PrefixRange, ReadOptions,
@@ -30,11 +32,15 @@ use tracing::{debug, trace};
 
 use restate_core::ShutdownError;
 use restate_rocksdb::{IoMode, IterAction, Priority, RocksDb, RocksError};
+use restate_storage_api::cdc_table::ChangePosition;
 use restate_storage_api::fsm_table::ReadFsmTable;
+use restate_storage_api::invocation_counts_table::InvocationCountKey;
 use restate_storage_api::protobuf_types::{PartitionStoreProtobufValue, ProtobufStorageWrapper};
 use restate_storage_api::{IsolationLevel, Storage, StorageError, Transaction};
 use restate_types::config::Configuration;
-use restate_types::identifiers::{PartitionId, PartitionKey, SnapshotId, WithPartitionKey};
+use restate_types::identifiers::{
+    InvocationId, PartitionId, PartitionKey, SnapshotId, WithPartitionKey,
+};
 use restate_types::logs::Lsn;
 use restate_types::partitions::Partition;
 use restate_types::storage::StorageCodec;
@@ -42,6 +48,7 @@ use restate_types::storage::StorageDecode;
 use restate_types::storage::StorageEncode;
 
 use crate::fsm_table::{get_locally_durable_lsn, get_storage_version, put_storage_version};
+use crate::invocation_counts_table::flush_invocation_count_deltas;
 use crate::keys::KeyKind;
 use crate::keys::TableKey;
 use crate::keys::TableKeyPrefix;
@@ -130,6 +137,9 @@ pub enum TableKind {
     Deduplication,
     Outbox,
     Timers,
+    ChangeCapture,
+    InvocationHistory,
+    InvocationCounts,
     // By Partition Key
     State,
     InvocationStatus,
@@ -153,6 +163,9 @@ impl TableKind {
             Self::Deduplication => &[KeyKind::Deduplication],
             Self::PartitionStateMachine => &[KeyKind::Fsm],
             Self::Timers => &[KeyKind::Timers],
+            Self::ChangeCapture => &[KeyKind::ChangeCapture],
+            Self::InvocationHistory => &[KeyKind::InvocationHistory],
+            Self::InvocationCounts => &[KeyKind::InvocationCounts],
             Self::Journal => &[
                 KeyKind::Journal,
                 KeyKind::InvocationStatus,
@@ -465,6 +478,77 @@ impl PartitionStore {
         })
     }
 
+    /// Iterates over all the keys starting with one of the given key prefixes, in key order.
+    ///
+    /// All prefixes are visited by a single background iterator, which seeks from one prefix to
+    /// the next one. This allows to turn scans of whole partition key ranges into point lookups or
+    /// prefix scans, when the keys of interest are known upfront.
+    pub fn iterator_for_each_prefix<K: TableKeyPrefix>(
+        &self,
+        name: &'static str,
+        priority: Priority,
+        prefixes: impl IntoIterator<Item = K>,
+        f: impl FnMut((&[u8], &[u8])) -> ControlFlow<Result<()>> + Send + 'static,
+    ) -> Result<impl Future<Output = Result<()>>, ShutdownError> {
+        let mut prefixes: Vec<Bytes> = prefixes
+            .into_iter()
+            .map(|prefix| prefix.serialize().freeze())
+            .collect();
+        prefixes.sort();
+        // a prefix extending another one would yield the same keys twice
+        prefixes.dedup_by(|prefix, previous| prefix.starts_with(previous));
+
+        let (tx, rx) = oneshot::channel();
+        let mut step = Self::iterator_step_for_each(tx, f);
+
+        if let (Some(first), Some(last)) = (prefixes.first().cloned(), prefixes.last()) {
+            assert!(K::TABLE.has_key_kind(&first));
+            let mut end = BytesMut::from(last.as_ref());
+            if !crate::scan::try_increment(&mut end) {
+                // not allowed to happen since we guarantee that KeyKind is
+                // always incrementable.
+                panic!("Key prefix overflowed, prefix {:x?}", &last);
+            }
+            let opts =
+                self.new_range_iterator_opts(ScanMode::TotalOrder, first.clone(), end.freeze());
+
+            let mut current = 0;
+            let on_iter = move |item: Result<(&[u8], &[u8]), RocksError>| {
+                if let Ok((key, _)) = &item {
+                    while !key.starts_with(&prefixes[current]) {
+                        if *key < prefixes[current].as_ref() {
+                            return IterAction::Seek(prefixes[current].clone());
+                        }
+                        current += 1;
+                        if current == prefixes.len() {
+                            return IterAction::Stop;
+                        }
+                    }
+                }
+                step(item)
+            };
+
+            self.db.rocksdb().clone().run_background_iterator(
+                self.db.partition().cf_name().into(),
+                name,
+                priority,
+                IterAction::Seek(first),
+                opts,
+                on_iter,
+            )?;
+        }
+
+        Ok(async {
+            match rx.await {
+                Ok(storage_err) => Err(storage_err),
+                Err(_recv_err) => {
+                    // iterator was dropped without sending an error; this is actually a success condition
+                    Ok(())
+                }
+            }
+        })
+    }
+
     pub fn run_iterator<K: TableKey, O: Send + 'static>(
         &self,
         name: &'static str,
@@ -613,6 +697,11 @@ impl PartitionStore {
             value_buffer: &mut self.value_buffer,
             meta: self.db.partition(),
             snapshot,
+            change_capture: None,
+            change_capture_excluded_service: None,
+            record_invocation_history: false,
+            read_invocation_count_keys: HashMap::new(),
+            invocation_count_deltas: HashMap::new(),
         }
     }
 
@@ -809,6 +898,20 @@ pub struct PartitionStoreTransaction<'a> {
     key_buffer: &'a mut BytesMut,
     value_buffer: &'a mut BytesMut,
     snapshot: Option<SnapshotWithThreadMode<'a, rocksdb::DB>>,
+    /// Position of the next captured change, if change data capture is enabled for this
+    /// transaction.
+    change_capture: Option<ChangePosition>,
+    /// Service whose invocations and state are not captured, see
+    /// [`restate_storage_api::cdc_table::WriteCdcTable::capture_changes_at`].
+    change_capture_excluded_service: Option<ByteString>,
+    /// Whether finished invocations are recorded in the invocation history archive.
+    record_invocation_history: bool,
+    /// Dimensions under which the invocation statuses read within this transaction are counted,
+    /// `None` for free invocations. Writes take the previous status from here to maintain the
+    /// invocation counts, instead of reading and decoding it a second time.
+    read_invocation_count_keys: HashMap<InvocationId, Option<InvocationCountKey>>,
+    /// Changes of the invocation counts, applied once per key when the transaction is committed.
+    invocation_count_deltas: HashMap<InvocationCountKey, i64>,
 }
 
 impl PartitionStoreTransaction<'_> {
@@ -883,6 +986,67 @@ impl PartitionStoreTransaction<'_> {
     pub(crate) fn assert_partition_key(&self, partition_key: &impl WithPartitionKey) -> Result<()> {
         assert_partition_key_or_err(&self.meta.key_range, partition_key)
     }
+
+    /// Whether the mutations of the invocations and state of the given service are captured.
+    #[inline]
+    pub(crate) fn is_capturing_changes_of(&self, service_name: &str) -> bool {
+        self.change_capture.is_some()
+            && self
+                .change_capture_excluded_service
+                .as_deref()
+                .is_none_or(|excluded_service| excluded_service != service_name)
+    }
+
+    pub(crate) fn set_change_capture(
+        &mut self,
+        lsn: Option<Lsn>,
+        excluded_service: Option<ByteString>,
+    ) {
+        self.change_capture = lsn.map(|lsn| ChangePosition::new(lsn, 0));
+        self.change_capture_excluded_service = excluded_service;
+    }
+
+    #[inline]
+    pub(crate) fn is_recording_invocation_history(&self) -> bool {
+        self.record_invocation_history
+    }
+
+    pub(crate) fn set_record_invocation_history(&mut self, record: bool) {
+        self.record_invocation_history = record;
+    }
+
+    pub(crate) fn remember_read_invocation_count_key(
+        &mut self,
+        invocation_id: InvocationId,
+        count_key: Option<InvocationCountKey>,
+    ) {
+        self.read_invocation_count_keys
+            .insert(invocation_id, count_key);
+    }
+
+    /// Returns the count key of the given invocation as it was last read within this
+    /// transaction, if its status hasn't been overwritten since.
+    pub(crate) fn take_read_invocation_count_key(
+        &mut self,
+        invocation_id: &InvocationId,
+    ) -> Option<Option<InvocationCountKey>> {
+        self.read_invocation_count_keys.remove(invocation_id)
+    }
+
+    pub(crate) fn add_invocation_count_delta(&mut self, count_key: InvocationCountKey, delta: i64) {
+        *self.invocation_count_deltas.entry(count_key).or_default() += delta;
+    }
+
+    pub(crate) fn take_invocation_count_deltas(&mut self) -> HashMap<InvocationCountKey, i64> {
+        std::mem::take(&mut self.invocation_count_deltas)
+    }
+
+    /// Returns the position for the next captured change and advances it.
+    pub(crate) fn next_change_position(&mut self) -> Option<ChangePosition> {
+        let position = self.change_capture?;
+        self.change_capture = Some(ChangePosition::new(position.lsn, position.index + 1));
+        Some(position)
+    }
 }
 
 fn assert_partition_key_or_err(
@@ -899,7 +1063,9 @@ fn assert_partition_key_or_err(
 }
 
 impl Transaction for PartitionStoreTransaction<'_> {
-    async fn commit(self) -> Result<()> {
+    async fn commit(mut self) -> Result<()> {
+        flush_invocation_count_deltas(&mut self)?;
+
         // We cannot directly commit the txn because it might fail because of unrelated concurrent
         // writes to RocksDB. However, it is safe to write the WriteBatch for a given partition,
         // because there can only be a single writer (the leading PartitionProcessor).
@@ -1085,18 +1251,48 @@ pub(crate) trait StorageAccess {
         )
     }
 
+    /// Like [`Self::put_kv_proto`], but compresses the encoded value if it's larger than the
+    /// given `compression_threshold`.
+    #[inline]
+    fn put_kv_proto_with_compression<
+        K: TableKey,
+        V: PartitionStoreProtobufValue + Clone + 'static,
+    >(
+        &mut self,
+        key: K,
+        value: &V,
+        compression_threshold: Option<usize>,
+    ) -> Result<()> {
+        self.put_kv_storage_codec_with_compression(
+            key,
+            &ProtobufStorageWrapper::<V::ProtobufType>(value.clone().into()),
+            compression_threshold,
+        )
+    }
+
     #[inline]
     fn put_kv_storage_codec<K: TableKey, V: StorageEncode + 'static>(
         &mut self,
         key: K,
         value: &V,
+    ) -> Result<()> {
+        self.put_kv_storage_codec_with_compression(key, value, value.compression_threshold())
+    }
+
+    #[inline]
+    fn put_kv_storage_codec_with_compression<K: TableKey, V: StorageEncode + 'static>(
+        &mut self,
+        key: K,
+        value: &V,
+        compression_threshold: Option<usize>,
     ) -> Result<()> {
         let key_buffer = self.cleared_key_buffer_mut(key.serialized_length());
         key.serialize_to(key_buffer);
         let key_buffer = key_buffer.split();
 
         let value_buffer = self.cleared_value_buffer_mut(0);
-        StorageCodec::encode(value, value_buffer).map_err(|e| StorageError::Generic(e.into()))?;
+        StorageCodec::encode_with_compression(value, value_buffer, compression_threshold)
+            .map_err(|e| StorageError::Generic(e.into()))?;
         let value_buffer = value_buffer.split();
 
         self.put_cf(K::TABLE, key_buffer, value_buffer)
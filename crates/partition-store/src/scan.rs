@@ -125,7 +125,7 @@ impl<K: TableKey> TableScan<K> {
 ///```
 /// returns true iff the successor doesn't generate a carry.
 #[inline]
-fn try_increment(bytes: &mut BytesMut) -> bool {
+pub(crate) fn try_increment(bytes: &mut BytesMut) -> bool {
     for byte in bytes.iter_mut().rev() {
         if let Some(incremented) = byte.checked_add(1) {
             *byte = incremented;
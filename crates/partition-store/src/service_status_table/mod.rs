@@ -87,6 +87,20 @@ impl ReadVirtualObjectStatusTable for PartitionStore {
     }
 }
 
+fn visit_virtual_object_status(
+    (mut key, mut value): (&[u8], &[u8]),
+    f: &mut impl FnMut((ServiceId, VirtualObjectStatus)) -> std::ops::ControlFlow<()>,
+) -> std::ops::ControlFlow<Result<()>> {
+    let state_key = break_on_err(ServiceStatusKey::deserialize_from(&mut key))?;
+    let state_value = break_on_err(VirtualObjectStatus::decode(&mut value))?;
+
+    let (partition_key, service_name, service_key) = state_key.split();
+
+    let service_id = ServiceId::from_parts(partition_key, service_name, service_key);
+
+    f((service_id, state_value)).map_break(Ok)
+}
+
 impl ScanVirtualObjectStatusTable for PartitionStore {
     fn for_each_virtual_object_status<
         F: FnMut((ServiceId, VirtualObjectStatus)) -> std::ops::ControlFlow<()>
@@ -102,16 +116,26 @@ impl ScanVirtualObjectStatusTable for PartitionStore {
             "df-vo-status",
             Priority::Low,
             TableScan::FullScanPartitionKeyRange::<ServiceStatusKey>(range),
-            move |(mut key, mut value)| {
-                let state_key = break_on_err(ServiceStatusKey::deserialize_from(&mut key))?;
-                let state_value = break_on_err(VirtualObjectStatus::decode(&mut value))?;
-
-                let (partition_key, service_name, service_key) = state_key.split();
-
-                let service_id = ServiceId::from_parts(partition_key, service_name, service_key);
+            move |kv| visit_virtual_object_status(kv, &mut f),
+        )
+        .map_err(|_| StorageError::OperationalError)
+    }
 
-                f((service_id, state_value)).map_break(Ok)
-            },
+    fn for_each_virtual_object_status_of<
+        F: FnMut((ServiceId, VirtualObjectStatus)) -> std::ops::ControlFlow<()>
+            + Send
+            + Sync
+            + 'static,
+    >(
+        &self,
+        service_ids: Vec<ServiceId>,
+        mut f: F,
+    ) -> Result<impl Future<Output = Result<()>> + Send> {
+        self.iterator_for_each_prefix(
+            "df-vo-status-of",
+            Priority::Low,
+            service_ids.iter().map(write_status_key),
+            move |kv| visit_virtual_object_status(kv, &mut f),
         )
         .map_err(|_| StorageError::OperationalError)
     }
@@ -16,11 +16,13 @@ use futures::Stream;
 use futures_util::stream;
 
 use restate_rocksdb::{Priority, RocksDbPerfGuard};
+use restate_storage_api::cdc_table::StateChange;
 use restate_storage_api::state_table::{ReadStateTable, ScanStateTable, WriteStateTable};
 use restate_storage_api::{Result, StorageError};
 use restate_types::identifiers::{PartitionKey, ServiceId, WithPartitionKey};
 
 use crate::TableKind::State;
+use crate::cdc_table::capture_change;
 use crate::keys::{KeyKind, TableKey, define_table_key};
 use crate::{PartitionStore, PartitionStoreTransaction, StorageAccess, break_on_err};
 use crate::{TableScan, TableScanIterationDecision};
@@ -136,6 +138,18 @@ impl ReadStateTable for PartitionStore {
     }
 }
 
+fn visit_user_state(
+    (mut key, value): (&[u8], &[u8]),
+    f: &mut impl FnMut((ServiceId, Bytes, &[u8])) -> std::ops::ControlFlow<()>,
+) -> std::ops::ControlFlow<Result<()>> {
+    let row_key = break_on_err(StateKey::deserialize_from(&mut key))?;
+    let (partition_key, service_name, service_key, state_key) = row_key.split();
+
+    let service_id = ServiceId::from_parts(partition_key, service_name, service_key);
+
+    f((service_id, state_key, value)).map_break(Ok)
+}
+
 impl ScanStateTable for PartitionStore {
     fn for_each_user_state<
         F: FnMut((ServiceId, Bytes, &[u8])) -> std::ops::ControlFlow<()> + Send + Sync + 'static,
@@ -148,14 +162,28 @@ impl ScanStateTable for PartitionStore {
             "df-user-state",
             Priority::Low,
             TableScan::FullScanPartitionKeyRange::<StateKey>(range),
-            move |(mut key, value)| {
-                let row_key = break_on_err(StateKey::deserialize_from(&mut key))?;
-                let (partition_key, service_name, service_key, state_key) = row_key.split();
-
-                let service_id = ServiceId::from_parts(partition_key, service_name, service_key);
+            move |kv| visit_user_state(kv, &mut f),
+        )
+        .map_err(|_| StorageError::OperationalError)
+    }
 
-                f((service_id, state_key, value)).map_break(Ok)
-            },
+    fn for_each_user_state_of<
+        F: FnMut((ServiceId, Bytes, &[u8])) -> std::ops::ControlFlow<()> + Send + Sync + 'static,
+    >(
+        &self,
+        service_ids: Vec<ServiceId>,
+        mut f: F,
+    ) -> Result<impl Future<Output = Result<()>> + Send> {
+        self.iterator_for_each_prefix(
+            "df-user-state-of",
+            Priority::Low,
+            service_ids.into_iter().map(|service_id| {
+                StateKey::builder()
+                    .partition_key(service_id.partition_key())
+                    .service_name(service_id.service_name)
+                    .service_key(service_id.key)
+            }),
+            move |kv| visit_user_state(kv, &mut f),
         )
         .map_err(|_| StorageError::OperationalError)
     }
@@ -190,6 +218,18 @@ impl WriteStateTable for PartitionStoreTransaction<'_> {
         state_value: impl AsRef<[u8]>,
     ) -> Result<()> {
         self.assert_partition_key(service_id)?;
+        if self.is_capturing_changes_of(&service_id.service_name) {
+            let old_value = get_user_state(self, service_id, state_key.as_ref())?;
+            capture_change(
+                self,
+                &StateChange::UserState {
+                    service_id: service_id.clone(),
+                    key: Bytes::copy_from_slice(state_key.as_ref()),
+                    old_value,
+                    new_value: Some(Bytes::copy_from_slice(state_value.as_ref())),
+                },
+            )?;
+        }
         put_user_state(self, service_id, state_key, state_value)
     }
 
@@ -199,11 +239,39 @@ impl WriteStateTable for PartitionStoreTransaction<'_> {
         state_key: impl AsRef<[u8]>,
     ) -> Result<()> {
         self.assert_partition_key(service_id)?;
+        if self.is_capturing_changes_of(&service_id.service_name) {
+            let old_value = get_user_state(self, service_id, state_key.as_ref())?;
+            if old_value.is_some() {
+                capture_change(
+                    self,
+                    &StateChange::UserState {
+                        service_id: service_id.clone(),
+                        key: Bytes::copy_from_slice(state_key.as_ref()),
+                        old_value,
+                        new_value: None,
+                    },
+                )?;
+            }
+        }
         delete_user_state(self, service_id, state_key)
     }
 
     fn delete_all_user_state(&mut self, service_id: &ServiceId) -> Result<()> {
         self.assert_partition_key(service_id)?;
+        if self.is_capturing_changes_of(&service_id.service_name) {
+            for entry in get_all_user_states_for_service(self, service_id)? {
+                let (key, old_value) = entry?;
+                capture_change(
+                    self,
+                    &StateChange::UserState {
+                        service_id: service_id.clone(),
+                        key,
+                        old_value: Some(old_value),
+                        new_value: None,
+                    },
+                )?;
+            }
+        }
         delete_all_user_state(self, service_id)
     }
 }
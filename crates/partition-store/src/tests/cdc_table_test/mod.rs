@@ -0,0 +1,178 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::storage_test_environment;
+
+use bytes::Bytes;
+use bytestring::ByteString;
+use restate_rocksdb::RocksDbManager;
+use restate_storage_api::Transaction;
+use restate_storage_api::cdc_table::{ChangePosition, ReadCdcTable, StateChange, WriteCdcTable};
+use restate_storage_api::fsm_table::{ReadFsmTable, WriteFsmTable};
+use restate_storage_api::state_table::WriteStateTable;
+use restate_types::identifiers::ServiceId;
+use restate_types::logs::Lsn;
+
+fn user_state_change(
+    service_id: &ServiceId,
+    key: &'static [u8],
+    old_value: Option<&'static [u8]>,
+    new_value: Option<&'static [u8]>,
+) -> StateChange {
+    StateChange::UserState {
+        service_id: service_id.clone(),
+        key: Bytes::from_static(key),
+        old_value: old_value.map(Bytes::from_static),
+        new_value: new_value.map(Bytes::from_static),
+    }
+}
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn capture_and_truncate_changes() {
+    let mut rocksdb = storage_test_environment().await;
+    let service_id = ServiceId::with_partition_key(1337, "svc-1", "key-1");
+
+    // mutations are not captured unless requested
+    let mut txn = rocksdb.transaction();
+    txn.put_user_state(&service_id, b"k0", b"v0").unwrap();
+    txn.commit().await.expect("should not fail");
+
+    let mut txn = rocksdb.transaction();
+    txn.capture_changes_at(Lsn::new(10), None);
+    txn.put_user_state(&service_id, b"k1", b"v1").unwrap();
+    txn.put_user_state(&service_id, b"k1", b"v2").unwrap();
+    txn.delete_user_state(&service_id, b"k1").unwrap();
+    txn.capture_changes_at(Lsn::new(11), None);
+    txn.delete_all_user_state(&service_id).unwrap();
+    txn.commit().await.expect("should not fail");
+
+    let changes = rocksdb
+        .get_next_changes(ChangePosition::MIN, 10)
+        .await
+        .expect("should not fail");
+    assert_eq!(
+        changes,
+        vec![
+            (
+                ChangePosition::new(Lsn::new(10), 0),
+                user_state_change(&service_id, b"k1", None, Some(b"v1"))
+            ),
+            (
+                ChangePosition::new(Lsn::new(10), 1),
+                user_state_change(&service_id, b"k1", Some(b"v1"), Some(b"v2"))
+            ),
+            (
+                ChangePosition::new(Lsn::new(10), 2),
+                user_state_change(&service_id, b"k1", Some(b"v2"), None)
+            ),
+            (
+                ChangePosition::new(Lsn::new(11), 0),
+                user_state_change(&service_id, b"k0", Some(b"v0"), None)
+            ),
+        ]
+    );
+
+    // resume reading from a position
+    let changes = rocksdb
+        .get_next_changes(ChangePosition::new(Lsn::new(10), 2), 1)
+        .await
+        .expect("should not fail");
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].0, ChangePosition::new(Lsn::new(10), 2));
+
+    let mut txn = rocksdb.transaction();
+    txn.truncate_changes(Lsn::new(10)).unwrap();
+    txn.commit().await.expect("should not fail");
+
+    let changes = rocksdb
+        .get_next_changes(ChangePosition::MIN, 10)
+        .await
+        .expect("should not fail");
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].0, ChangePosition::new(Lsn::new(11), 0));
+
+    RocksDbManager::get().shutdown().await;
+}
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn excluded_service_is_not_captured() {
+    let mut rocksdb = storage_test_environment().await;
+    let service_id = ServiceId::with_partition_key(1337, "svc-1", "key-1");
+    let sink_service_id = ServiceId::with_partition_key(1337, "sink", "key-1");
+
+    let mut txn = rocksdb.transaction();
+    txn.capture_changes_at(Lsn::new(10), Some(ByteString::from_static("sink")));
+    txn.put_user_state(&sink_service_id, b"k1", b"v1").unwrap();
+    txn.put_user_state(&service_id, b"k1", b"v1").unwrap();
+    txn.delete_all_user_state(&sink_service_id).unwrap();
+    txn.commit().await.expect("should not fail");
+
+    let changes = rocksdb
+        .get_next_changes(ChangePosition::MIN, 10)
+        .await
+        .expect("should not fail");
+    assert_eq!(
+        changes,
+        vec![(
+            ChangePosition::new(Lsn::new(10), 0),
+            user_state_change(&service_id, b"k1", None, Some(b"v1"))
+        )]
+    );
+
+    RocksDbManager::get().shutdown().await;
+}
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn change_capture_excluded_service() {
+    let mut rocksdb = storage_test_environment().await;
+
+    assert_eq!(
+        rocksdb.get_change_capture_excluded_service().await.unwrap(),
+        None
+    );
+
+    let mut txn = rocksdb.transaction();
+    txn.put_change_capture_excluded_service(Some("sink"))
+        .unwrap();
+    txn.commit().await.expect("should not fail");
+    assert_eq!(
+        rocksdb.get_change_capture_excluded_service().await.unwrap(),
+        Some(ByteString::from_static("sink"))
+    );
+
+    let mut txn = rocksdb.transaction();
+    txn.put_change_capture_excluded_service(None).unwrap();
+    txn.commit().await.expect("should not fail");
+    assert_eq!(
+        rocksdb.get_change_capture_excluded_service().await.unwrap(),
+        None
+    );
+
+    RocksDbManager::get().shutdown().await;
+}
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn change_capture_enabled_flag() {
+    let mut rocksdb = storage_test_environment().await;
+
+    assert!(!rocksdb.get_change_capture_enabled().await.unwrap());
+
+    let mut txn = rocksdb.transaction();
+    txn.put_change_capture_enabled(true).unwrap();
+    txn.commit().await.expect("should not fail");
+    assert!(rocksdb.get_change_capture_enabled().await.unwrap());
+
+    let mut txn = rocksdb.transaction();
+    txn.put_change_capture_enabled(false).unwrap();
+    txn.commit().await.expect("should not fail");
+    assert!(!rocksdb.get_change_capture_enabled().await.unwrap());
+
+    RocksDbManager::get().shutdown().await;
+}
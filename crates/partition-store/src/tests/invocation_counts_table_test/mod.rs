@@ -0,0 +1,175 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::storage_test_environment;
+
+use bytestring::ByteString;
+use restate_rocksdb::RocksDbManager;
+use restate_storage_api::Transaction;
+use restate_storage_api::invocation_counts_table::{InvocationCountKey, ReadInvocationCountsTable};
+use restate_storage_api::invocation_status_table::{
+    CompletedInvocation, InFlightInvocationMetadata, InvocationStatus,
+    InvocationStatusDiscriminants, ReadInvocationStatusTable, WriteInvocationStatusTable,
+};
+use restate_types::identifiers::{InvocationId, InvocationUuid};
+
+fn count_key(status: InvocationStatusDiscriminants) -> InvocationCountKey {
+    // the target of the mocked statuses
+    InvocationCountKey {
+        service_name: ByteString::from_static("MyService"),
+        handler_name: ByteString::from_static("mock"),
+        status,
+        deployment_id: None,
+    }
+}
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn counts_follow_status_transitions() {
+    let mut rocksdb = storage_test_environment().await;
+    let invocation_id_1 = InvocationId::from_parts(1337, InvocationUuid::mock_random());
+    let invocation_id_2 = InvocationId::from_parts(1337, InvocationUuid::mock_random());
+
+    let mut txn = rocksdb.transaction();
+    txn.put_invocation_status(
+        &invocation_id_1,
+        &InvocationStatus::Invoked(InFlightInvocationMetadata::mock()),
+    )
+    .unwrap();
+    txn.put_invocation_status(
+        &invocation_id_2,
+        &InvocationStatus::Invoked(InFlightInvocationMetadata::mock()),
+    )
+    .unwrap();
+    // updates which don't change the counted dimensions are not counted twice
+    txn.put_invocation_status(
+        &invocation_id_1,
+        &InvocationStatus::Invoked(InFlightInvocationMetadata::mock()),
+    )
+    .unwrap();
+    txn.commit().await.expect("should not fail");
+
+    assert_eq!(
+        rocksdb.get_invocation_counts().await.unwrap(),
+        vec![(count_key(InvocationStatusDiscriminants::Invoked), 2)]
+    );
+
+    let mut txn = rocksdb.transaction();
+    txn.put_invocation_status(
+        &invocation_id_1,
+        &InvocationStatus::Completed(CompletedInvocation::mock_neo()),
+    )
+    .unwrap();
+    txn.delete_invocation_status(&invocation_id_2).unwrap();
+    txn.commit().await.expect("should not fail");
+
+    assert_eq!(
+        rocksdb.get_invocation_counts().await.unwrap(),
+        vec![(count_key(InvocationStatusDiscriminants::Completed), 1)]
+    );
+
+    let mut txn = rocksdb.transaction();
+    txn.put_invocation_status(&invocation_id_1, &InvocationStatus::Free)
+        .unwrap();
+    txn.commit().await.expect("should not fail");
+
+    assert!(rocksdb.get_invocation_counts().await.unwrap().is_empty());
+
+    RocksDbManager::get().shutdown().await;
+}
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn counts_follow_transitions_of_statuses_read_in_transaction() {
+    let mut rocksdb = storage_test_environment().await;
+    let invocation_id = InvocationId::from_parts(1337, InvocationUuid::mock_random());
+
+    let mut txn = rocksdb.transaction();
+    txn.put_invocation_status(
+        &invocation_id,
+        &InvocationStatus::Invoked(InFlightInvocationMetadata::mock()),
+    )
+    .unwrap();
+    txn.commit().await.expect("should not fail");
+
+    let mut txn = rocksdb.transaction();
+    let status = txn.get_invocation_status(&invocation_id).await.unwrap();
+    assert_eq!(
+        status.discriminant(),
+        Some(InvocationStatusDiscriminants::Invoked)
+    );
+    txn.put_invocation_status(
+        &invocation_id,
+        &InvocationStatus::Completed(CompletedInvocation::mock_neo()),
+    )
+    .unwrap();
+    // the previous status of a second write within the same transaction is the first write
+    txn.put_invocation_status(
+        &invocation_id,
+        &InvocationStatus::Invoked(InFlightInvocationMetadata::mock()),
+    )
+    .unwrap();
+    let status = txn.get_invocation_status(&invocation_id).await.unwrap();
+    txn.put_invocation_status(&invocation_id, &status).unwrap();
+    txn.commit().await.expect("should not fail");
+
+    assert_eq!(
+        rocksdb.get_invocation_counts().await.unwrap(),
+        vec![(count_key(InvocationStatusDiscriminants::Invoked), 1)]
+    );
+
+    let mut txn = rocksdb.transaction();
+    txn.get_invocation_status(&invocation_id).await.unwrap();
+    txn.delete_invocation_status(&invocation_id).unwrap();
+    txn.commit().await.expect("should not fail");
+
+    assert!(rocksdb.get_invocation_counts().await.unwrap().is_empty());
+
+    RocksDbManager::get().shutdown().await;
+}
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn pending_count_changes_are_visible_in_transaction() {
+    let mut rocksdb = storage_test_environment().await;
+    let invocation_id_1 = InvocationId::from_parts(1337, InvocationUuid::mock_random());
+    let invocation_id_2 = InvocationId::from_parts(1337, InvocationUuid::mock_random());
+
+    let mut txn = rocksdb.transaction();
+    txn.put_invocation_status(
+        &invocation_id_1,
+        &InvocationStatus::Invoked(InFlightInvocationMetadata::mock()),
+    )
+    .unwrap();
+    txn.put_invocation_status(
+        &invocation_id_2,
+        &InvocationStatus::Invoked(InFlightInvocationMetadata::mock()),
+    )
+    .unwrap();
+    assert_eq!(
+        txn.get_invocation_counts().await.unwrap(),
+        vec![(count_key(InvocationStatusDiscriminants::Invoked), 2)]
+    );
+
+    // changes after reading the counts are applied on commit
+    txn.put_invocation_status(
+        &invocation_id_2,
+        &InvocationStatus::Completed(CompletedInvocation::mock_neo()),
+    )
+    .unwrap();
+    txn.commit().await.expect("should not fail");
+
+    assert_eq!(
+        rocksdb.get_invocation_counts().await.unwrap(),
+        vec![
+            (count_key(InvocationStatusDiscriminants::Invoked), 1),
+            (count_key(InvocationStatusDiscriminants::Completed), 1)
+        ]
+    );
+
+    RocksDbManager::get().shutdown().await;
+}
@@ -0,0 +1,132 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::storage_test_environment;
+
+use std::ops::ControlFlow;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use restate_rocksdb::RocksDbManager;
+use restate_storage_api::Transaction;
+use restate_storage_api::fsm_table::{ReadFsmTable, WriteFsmTable};
+use restate_storage_api::invocation_history_table::{
+    InvocationOutcome, InvocationSummary, ScanInvocationHistoryTable, WriteInvocationHistoryTable,
+};
+use restate_types::identifiers::{InvocationId, InvocationUuid};
+use restate_types::invocation::InvocationTarget;
+use restate_types::time::MillisSinceEpoch;
+
+use crate::PartitionStore;
+
+fn summary(uuid: u128, completed_at: u64) -> InvocationSummary {
+    InvocationSummary {
+        invocation_id: InvocationId::from_parts(1337, InvocationUuid::from_u128(uuid)),
+        invocation_target: InvocationTarget::service("svc", "handler"),
+        outcome: InvocationOutcome::Success,
+        created_at: MillisSinceEpoch::new(completed_at - 10),
+        running_at: Some(MillisSinceEpoch::new(completed_at - 5)),
+        completed_at: MillisSinceEpoch::new(completed_at),
+        transient_failures: 0,
+        deployment_id: None,
+        journal_length: 2,
+    }
+}
+
+async fn scan_history(partition_store: &PartitionStore) -> Vec<InvocationSummary> {
+    let summaries = Arc::new(Mutex::new(Vec::new()));
+    let collected = Arc::clone(&summaries);
+    partition_store
+        .for_each_invocation_summary(0..=u64::MAX, move |summary| {
+            collected.lock().unwrap().push(summary);
+            ControlFlow::Continue(())
+        })
+        .expect("should not fail")
+        .await
+        .expect("should not fail");
+    Arc::try_unwrap(summaries).unwrap().into_inner().unwrap()
+}
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn record_and_trim_invocation_history() {
+    let mut rocksdb = storage_test_environment().await;
+
+    assert_eq!(
+        rocksdb.get_invocation_history_retention().await.unwrap(),
+        None
+    );
+    let mut txn = rocksdb.transaction();
+    txn.put_invocation_history_retention(Some(Duration::from_secs(3600)))
+        .unwrap();
+    txn.commit().await.expect("should not fail");
+    assert_eq!(
+        rocksdb.get_invocation_history_retention().await.unwrap(),
+        Some(Duration::from_secs(3600))
+    );
+
+    // summaries are not recorded unless requested
+    let mut txn = rocksdb.transaction();
+    txn.put_invocation_summary(&summary(1, 100)).unwrap();
+    txn.commit().await.expect("should not fail");
+    assert!(scan_history(&rocksdb).await.is_empty());
+
+    let mut txn = rocksdb.transaction();
+    txn.record_invocation_history();
+    txn.put_invocation_summary(&summary(2, 300)).unwrap();
+    txn.put_invocation_summary(&summary(3, 100)).unwrap();
+    txn.put_invocation_summary(&summary(4, 200)).unwrap();
+    txn.commit().await.expect("should not fail");
+
+    // summaries are ordered by completion time
+    assert_eq!(
+        scan_history(&rocksdb).await,
+        vec![summary(3, 100), summary(4, 200), summary(2, 300)]
+    );
+
+    // summaries below the trim point are hidden, even if not removed yet
+    let mut txn = rocksdb.transaction();
+    txn.put_invocation_history_trim_point(MillisSinceEpoch::new(200))
+        .unwrap();
+    txn.commit().await.expect("should not fail");
+    assert_eq!(
+        rocksdb.get_invocation_history_trim_point().await.unwrap(),
+        MillisSinceEpoch::new(200)
+    );
+    assert_eq!(
+        scan_history(&rocksdb).await,
+        vec![summary(4, 200), summary(2, 300)]
+    );
+
+    // trimming is bounded
+    let mut txn = rocksdb.transaction();
+    assert!(
+        !txn.trim_invocation_history(MillisSinceEpoch::new(301), 1)
+            .unwrap()
+    );
+    txn.commit().await.expect("should not fail");
+
+    let mut txn = rocksdb.transaction();
+    assert!(
+        !txn.trim_invocation_history(MillisSinceEpoch::new(301), 1)
+            .unwrap()
+    );
+    txn.commit().await.expect("should not fail");
+    assert_eq!(scan_history(&rocksdb).await, vec![summary(2, 300)]);
+
+    let mut txn = rocksdb.transaction();
+    assert!(
+        txn.trim_invocation_history(MillisSinceEpoch::new(301), 1)
+            .unwrap()
+    );
+    txn.commit().await.expect("should not fail");
+    assert!(scan_history(&rocksdb).await.is_empty());
+
+    RocksDbManager::get().shutdown().await;
+}
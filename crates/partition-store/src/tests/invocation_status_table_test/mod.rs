@@ -101,6 +101,8 @@ fn invoked_status(invocation_target: InvocationTarget) -> InvocationStatus {
         current_invocation_epoch: 1,
         completion_range_epoch_map: CompletionRangeEpochMap::from_trim_points([(5, 1)]),
         random_seed: None,
+        deadline: Some(MillisSinceEpoch::new(1_000)),
+        transient_failures: 3,
     })
 }
 
@@ -129,6 +131,8 @@ fn suspended_status(invocation_target: InvocationTarget) -> InvocationStatus {
             current_invocation_epoch: 1,
             completion_range_epoch_map: CompletionRangeEpochMap::from_trim_points([(5, 1)]),
             random_seed: None,
+            deadline: None,
+            transient_failures: 0,
         },
         waiting_for_notifications: HashSet::default(),
     }
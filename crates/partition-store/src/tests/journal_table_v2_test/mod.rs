@@ -62,6 +62,7 @@ fn mock_call_command(
             idempotency_key: Some(ByteString::from_static("my-idempotency-key")),
             completion_retention_duration: Duration::from_secs(10),
             journal_retention_duration: Duration::from_secs(11),
+            deadline: None,
         },
         invocation_id_completion_id,
         name: Default::default(),
@@ -83,6 +84,7 @@ fn mock_one_way_call_command(invocation_id_completion_id: CompletionId) -> Entry
             idempotency_key: Some(ByteString::from_static("my-idempotency-key")),
             completion_retention_duration: Duration::from_secs(10),
             journal_retention_duration: Duration::from_secs(11),
+            deadline: None,
         },
         invoke_time: 0.into(),
         invocation_id_completion_id,
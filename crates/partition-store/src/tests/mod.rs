@@ -29,9 +29,12 @@ use restate_types::invocation::{InvocationTarget, ServiceInvocation, Source};
 use restate_types::state_mut::ExternalStateMutation;
 
 mod barrier_test;
+mod cdc_table_test;
 mod durable_lsn_tracking_test;
 mod idempotency_table_test;
 mod inbox_table_test;
+mod invocation_counts_table_test;
+mod invocation_history_table_test;
 mod invocation_status_table_test;
 mod journal_events_table_test;
 mod journal_table_test;
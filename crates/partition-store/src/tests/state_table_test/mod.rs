@@ -10,11 +10,13 @@
 
 use super::{assert_stream_eq, storage_test_environment};
 
+use std::sync::{Arc, Mutex};
+
 use crate::PartitionStore;
 use bytes::Bytes;
 use restate_rocksdb::RocksDbManager;
 use restate_storage_api::Transaction;
-use restate_storage_api::state_table::{ReadStateTable, WriteStateTable};
+use restate_storage_api::state_table::{ReadStateTable, ScanStateTable, WriteStateTable};
 use restate_types::identifiers::ServiceId;
 
 fn populate_data<T: WriteStateTable>(table: &mut T) {
@@ -97,6 +99,48 @@ async fn verify_prefix_scan_after_delete<T: ReadStateTable>(table: &mut T) {
     assert_stream_eq(result, expected).await;
 }
 
+async fn verify_scan_of_services(rocksdb: &PartitionStore) {
+    let rows = Arc::new(Mutex::new(Vec::new()));
+    rocksdb
+        .for_each_user_state_of(
+            vec![
+                ServiceId::with_partition_key(1337, "svc-1", "key-2"),
+                ServiceId::with_partition_key(1337, "svc-1", "unknown"),
+                ServiceId::with_partition_key(1337, "svc-1", "key-1"),
+            ],
+            {
+                let rows = Arc::clone(&rows);
+                move |(service_id, key, value)| {
+                    rows.lock().unwrap().push((
+                        service_id.key.to_string(),
+                        key,
+                        Bytes::copy_from_slice(value),
+                    ));
+                    std::ops::ControlFlow::Continue(())
+                }
+            },
+        )
+        .expect("should not fail")
+        .await
+        .expect("should not fail");
+
+    assert_eq!(
+        *rows.lock().unwrap(),
+        vec![
+            (
+                "key-1".to_owned(),
+                Bytes::from_static(b"k1"),
+                Bytes::from_static(b"v1")
+            ),
+            (
+                "key-2".to_owned(),
+                Bytes::from_static(b"k2"),
+                Bytes::from_static(b"v2")
+            ),
+        ]
+    );
+}
+
 pub(crate) async fn run_tests(mut rocksdb: PartitionStore) {
     let mut txn = rocksdb.transaction();
 
@@ -110,6 +154,9 @@ pub(crate) async fn run_tests(mut rocksdb: PartitionStore) {
     let mut txn = rocksdb.transaction();
     verify_delete(&mut txn).await;
     verify_prefix_scan_after_delete(&mut txn).await;
+    drop(txn);
+
+    verify_scan_of_services(&rocksdb).await;
 }
 
 #[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
@@ -8,23 +8,25 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::ops::{ControlFlow, RangeInclusive};
+
 use futures::Stream;
 use futures_util::stream;
 
-use restate_rocksdb::RocksDbPerfGuard;
-use restate_storage_api::Result;
+use restate_rocksdb::{Priority, RocksDbPerfGuard};
 use restate_storage_api::protobuf_types::PartitionStoreProtobufValue;
 use restate_storage_api::timer_table::{
-    ReadTimerTable, Timer, TimerKey, TimerKeyKind, WriteTimerTable,
+    ReadTimerTable, ScanTimerTable, Timer, TimerKey, TimerKeyKind, WriteTimerTable,
 };
-use restate_types::identifiers::{InvocationUuid, PartitionId};
+use restate_storage_api::{Result, StorageError};
+use restate_types::identifiers::{InvocationUuid, PartitionId, PartitionKey, WithPartitionKey};
 
 use crate::TableKind::Timers;
 use crate::TableScanIterationDecision::Emit;
 use crate::keys::{KeyKind, TableKey, define_table_key};
 use crate::{
     PaddedPartitionId, PartitionStore, PartitionStoreTransaction, StorageAccess, TableScan,
-    TableScanIterationDecision,
+    TableScanIterationDecision, break_on_err,
 };
 
 define_table_key!(
@@ -108,6 +110,15 @@ fn exclusive_start_key_range(
                     },
                 }
             }
+            TimerKeyKind::CancelInvocation { invocation_uuid } => {
+                let incremented_invocation_uuid = increment_invocation_uuid(invocation_uuid);
+                TimerKey {
+                    timestamp: timer_key.timestamp,
+                    kind: TimerKeyKind::CancelInvocation {
+                        invocation_uuid: incremented_invocation_uuid,
+                    },
+                }
+            }
         };
 
         let lower_bound = write_timer_key(partition_id, &next_timer_key);
@@ -189,6 +200,29 @@ impl ReadTimerTable for PartitionStore {
     }
 }
 
+impl ScanTimerTable for PartitionStore {
+    fn for_each_timer<F: FnMut((TimerKey, Timer)) -> ControlFlow<()> + Send + Sync + 'static>(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+        mut f: F,
+    ) -> Result<impl Future<Output = Result<()>> + Send> {
+        self.iterator_for_each(
+            "df-timer",
+            Priority::Low,
+            TableScan::SinglePartition::<TimersKey>(self.partition_id()),
+            move |(key, value)| {
+                let (timer_key, timer) = break_on_err(decode_seq_timer_key_value(key, value))?;
+                if !range.contains(&timer.partition_key()) {
+                    return ControlFlow::Continue(());
+                }
+
+                f((timer_key, timer)).map_break(Ok)
+            },
+        )
+        .map_err(|_| StorageError::OperationalError)
+    }
+}
+
 impl ReadTimerTable for PartitionStoreTransaction<'_> {
     fn next_timers_greater_than(
         &mut self,
@@ -288,6 +322,21 @@ mod tests {
         assert_eq!(got, key);
     }
 
+    #[test]
+    fn round_trip_cancel_invocation_kind() {
+        let key = TimerKey {
+            kind: TimerKeyKind::CancelInvocation {
+                invocation_uuid: FIXTURE_INVOCATION,
+            },
+            timestamp: 87654321,
+        };
+
+        let key_bytes = write_timer_key(PartitionId::from(1337), &key).serialize();
+        let got = timer_key_from_key_slice(&key_bytes).expect("should not fail");
+
+        assert_eq!(got, key);
+    }
+
     #[test]
     fn test_lexicographical_sorting_by_timestamp() {
         let kinds = [
@@ -304,6 +353,9 @@ mod tests {
             TimerKeyKind::NeoInvoke {
                 invocation_uuid: FIXTURE_INVOCATION,
             },
+            TimerKeyKind::CancelInvocation {
+                invocation_uuid: FIXTURE_INVOCATION,
+            },
         ];
 
         for first_kind in &kinds {
@@ -395,6 +447,24 @@ mod tests {
         assert_in_range(&a, &b);
     }
 
+    #[test]
+    fn test_lexicographical_sorting_by_invocation_uuid_cancel_invocation_kind() {
+        // Higher random part should be sorted correctly in bytes
+        let a = TimerKey {
+            kind: TimerKeyKind::CancelInvocation {
+                invocation_uuid: FIXTURE_INVOCATION,
+            },
+            timestamp: 300,
+        };
+        let b = TimerKey {
+            kind: TimerKeyKind::CancelInvocation {
+                invocation_uuid: InvocationUuid::from_u128(u128::from(FIXTURE_INVOCATION) + 1),
+            },
+            timestamp: 300,
+        };
+        assert_in_range(&a, &b);
+    }
+
     #[test]
     fn test_lexicographical_sorting_by_journal_index() {
         let a = TimerKey {
@@ -516,6 +586,9 @@ mod tests {
                         invocation_uuid: InvocationUuid::mock_random(),
                     }
                 }
+                TimerKeyKindDiscriminants::CancelInvocation => TimerKeyKind::CancelInvocation {
+                    invocation_uuid: InvocationUuid::mock_random(),
+                },
             }
         };
 
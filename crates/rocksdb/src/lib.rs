@@ -449,6 +449,27 @@ impl RocksDb {
         let _ = manager.async_spawn_unchecked(task).await;
     }
 
+    /// Compacts the key range `[from, to]` of a column family
+    #[tracing::instrument(skip_all, fields(db = %self.name(), cf = %cf))]
+    pub async fn compact_range_cf(
+        self: Arc<Self>,
+        cf: CfName,
+        from: Vec<u8>,
+        to: Vec<u8>,
+    ) -> Result<(), RocksError> {
+        let manager = self.manager;
+        let task = StorageTask::default()
+            .kind(StorageTaskKind::Compaction)
+            .op(move || {
+                let _x = RocksDbPerfGuard::new("manual-range-compaction");
+                self.db.compact_range_cf(&cf, &from, &to)
+            })
+            .build()
+            .unwrap();
+
+        manager.async_spawn_unchecked(task).await?
+    }
+
     pub fn get_histogram_data(&self, histogram: Histogram) -> HistogramData {
         self.db.db_options().get_histogram_data(histogram)
     }
@@ -294,6 +294,16 @@ impl RocksAccess {
             });
     }
 
+    pub fn compact_range_cf(&self, cf: &CfName, from: &[u8], to: &[u8]) -> Result<(), RocksError> {
+        let Some(handle) = self.cf_handle(cf.as_ref()) else {
+            return Err(RocksError::UnknownColumnFamily(cf.clone()));
+        };
+        let opts = CompactOptions::default();
+        self.db
+            .compact_range_cf_opt(&handle, Some(from), Some(to), &opts);
+        Ok(())
+    }
+
     pub fn set_options_cf(
         &self,
         cf: impl AsRef<str>,
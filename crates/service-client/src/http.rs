@@ -10,28 +10,42 @@
 
 use super::proxy::ProxyConnector;
 
+use crate::metric_definitions::{
+    HTTP_POOL_CONNECTIONS, HTTP_POOL_SATURATED, HTTP_POOL_STREAMS, describe_metrics,
+};
 use crate::utils::ErrorExt;
 
 use bytes::Bytes;
 use futures::FutureExt;
-use futures::future::Either;
+use futures::future::{BoxFuture, Either};
 use http::Version;
 use http_body_util::BodyExt;
-use hyper::body::Body;
+use hyper::body::{Body, Frame, Incoming, SizeHint};
 use hyper::http::HeaderValue;
 use hyper::http::uri::PathAndQuery;
+use hyper::rt::ReadBufCursor;
 use hyper::{HeaderMap, Method, Request, Response, Uri};
 use hyper_rustls::{ConfigBuilderExt, HttpsConnector};
-use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::client::legacy::connect::{Connected, Connection, HttpConnector};
+use metrics::{Counter, Gauge, counter, gauge};
 use restate_types::config::HttpOptions;
+use restate_types::schema::deployment::HttpConnectionPoolSettings;
 use rustls::ClientConfig;
+use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::Debug;
 use std::future::Future;
-use std::sync::{Arc, LazyLock};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
 use std::{fmt, future};
+use tower_service::Service;
 
 type ProxiedHttpsConnector = ProxyConnector<HttpsConnector<HttpConnector>>;
+type HyperClient = hyper_util::client::legacy::Client<ProxiedHttpsConnector, BoxBody>;
+type PooledHyperClient = hyper_util::client::legacy::Client<CountingConnector, BoxBody>;
 
 static TLS_CLIENT_CONFIG: LazyLock<ClientConfig> = LazyLock::new(|| {
     // We need to explicitly configure the crypto provider since we activate the ring as well as
@@ -53,22 +67,18 @@ type BoxBody = http_body_util::combinators::BoxBody<Bytes, BoxError>;
 
 #[derive(Clone, Debug)]
 pub struct HttpClient {
-    /// Client used for HTTPS as long as HTTP1.1 or HTTP2 was not specifically requested.
-    /// All HTTP versions are possible.
-    alpn_client: hyper_util::client::legacy::Client<ProxiedHttpsConnector, BoxBody>,
-
     /// Client when HTTP1.1 was specifically requested - even if the ALPN advertises
     /// h2, we will not use it.
-    h1_client: hyper_util::client::legacy::Client<ProxiedHttpsConnector, BoxBody>,
+    h1_client: HyperClient,
 
-    /// Client when HTTP2 was specifically requested - for cleartext, we use h2c,
-    /// and for HTTPS, we will fail unless the ALPN supports h2.
-    /// In practice, at discovery time we never force h2 for HTTPS.
-    h2_client: hyper_util::client::legacy::Client<ProxiedHttpsConnector, BoxBody>,
+    /// Connection pools used for all the other requests.
+    pools: Arc<EndpointPools>,
 }
 
 impl HttpClient {
     pub fn from_options(options: &HttpOptions) -> HttpClient {
+        describe_metrics();
+
         let mut builder =
             hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::default());
         builder.timer(hyper_util::rt::TokioTimer::default());
@@ -76,6 +86,7 @@ impl HttpClient {
         builder
             .http2_initial_max_send_streams(options.initial_max_send_streams)
             .http2_adaptive_window(true)
+            .pool_idle_timeout(Duration::from(options.pool_idle_timeout))
             .http2_keep_alive_timeout(options.http_keep_alive_options.timeout.into())
             .http2_keep_alive_interval(Some(options.http_keep_alive_options.interval.into()));
 
@@ -103,25 +114,45 @@ impl HttpClient {
             .enable_http2()
             .wrap_connector(http_connector.clone());
 
-        HttpClient {
-            alpn_client: builder.clone().build::<_, BoxBody>(ProxyConnector::new(
+        let h1_client = builder.clone().build::<_, BoxBody>(ProxyConnector::new(
+            options.http_proxy.clone(),
+            options.no_proxy.clone(),
+            https_h1_connector,
+        ));
+        let alpn = ClientFactory {
+            builder: builder.clone(),
+            connector: ProxyConnector::new(
                 options.http_proxy.clone(),
                 options.no_proxy.clone(),
                 https_alpn_connector,
-            )),
-            h1_client: builder.clone().build::<_, BoxBody>(ProxyConnector::new(
+            ),
+        };
+        builder.http2_only(true);
+        let h2 = ClientFactory {
+            builder,
+            connector: ProxyConnector::new(
                 options.http_proxy.clone(),
                 options.no_proxy.clone(),
-                https_h1_connector,
-            )),
-            h2_client: {
-                builder.http2_only(true);
-                builder.build::<_, BoxBody>(ProxyConnector::new(
-                    options.http_proxy.clone(),
-                    options.no_proxy.clone(),
-                    https_h2_connector,
-                ))
-            },
+                https_h2_connector,
+            ),
+        };
+
+        HttpClient {
+            h1_client,
+            pools: Arc::new(EndpointPools {
+                alpn,
+                h2,
+                defaults: PoolOptions {
+                    max_connections: options.max_connections_per_endpoint.get(),
+                    max_streams_per_connection: options
+                        .max_streams_per_connection
+                        .map(|max| max.get()),
+                    idle_timeout: options.pool_idle_timeout.into(),
+                    keep_alive_interval: options.http_keep_alive_options.interval.into(),
+                    keep_alive_timeout: options.http_keep_alive_options.timeout.into(),
+                },
+                pools: Mutex::default(),
+            }),
         }
     }
 
@@ -174,15 +205,17 @@ impl HttpClient {
         http_request_builder.body(BoxBody::new(body.map_err(|e| e.into())))
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn request<B>(
         &self,
         uri: Uri,
         version: Option<Version>,
+        connection_pool: HttpConnectionPoolSettings,
         method: Method,
         body: B,
         path: PathAndQuery,
         headers: HeaderMap<HeaderValue>,
-    ) -> impl Future<Output = Result<Response<hyper::body::Incoming>, HttpError>> + Send + 'static
+    ) -> impl Future<Output = Result<Response<HttpResponseBody>, HttpError>> + Send + 'static
     where
         B: Body<Data = Bytes> + Send + Sync + Unpin + Sized + 'static,
         <B as Body>::Error: Error + Send + Sync + 'static,
@@ -192,26 +225,443 @@ impl HttpClient {
             Err(err) => return future::ready(Err(err.into())).right_future(),
         };
 
-        let fut = match version {
+        let (fut, stream) = match version {
             // version is set to http1.1 when use_http1.1 is set
-            Some(Version::HTTP_11) => self.h1_client.request(request),
+            Some(Version::HTTP_11) => (self.h1_client.request(request), None),
             // version is set to http2 for cleartext urls when use_http1.1 is not set
-            Some(Version::HTTP_2) => self.h2_client.request(request),
-            // version is currently set to none for https urls when use_http1.1 is not set
-            None => self.alpn_client.request(request),
-            // nothing currently sets a different version, but the alpn client is a sensible default
-            Some(_) => self.alpn_client.request(request),
+            Some(Version::HTTP_2) => {
+                let (client, stream) = self.pools.acquire(request.uri(), true, connection_pool);
+                (client.request(request), Some(stream))
+            }
+            // version is currently set to none for https urls when use_http1.1 is not set.
+            // Nothing currently sets a different version, but the alpn client is a sensible default
+            None | Some(_) => {
+                let (client, stream) = self.pools.acquire(request.uri(), false, connection_pool);
+                (client.request(request), Some(stream))
+            }
         };
 
         Either::Left(async move {
             match fut.await {
-                Ok(res) => Ok(res),
+                Ok(res) => Ok(res.map(|inner| HttpResponseBody {
+                    inner,
+                    _stream: stream,
+                })),
                 Err(err) => Err(err.into()),
             }
         })
     }
 }
 
+/// Body of a response received through [`HttpClient`].
+///
+/// The stream is accounted to its pooled connection until the body is dropped.
+pub struct HttpResponseBody {
+    inner: Incoming,
+    _stream: Option<StreamGuard>,
+}
+
+impl Body for HttpResponseBody {
+    type Data = Bytes;
+    type Error = hyper::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        Pin::new(&mut self.get_mut().inner).poll_frame(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+impl fmt::Debug for HttpResponseBody {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("HttpResponseBody")
+            .field(&self.inner)
+            .finish()
+    }
+}
+
+#[derive(Clone)]
+struct ClientFactory {
+    builder: hyper_util::client::legacy::Builder,
+    connector: ProxiedHttpsConnector,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct PoolOptions {
+    max_connections: usize,
+    max_streams_per_connection: Option<usize>,
+    idle_timeout: Duration,
+    keep_alive_interval: Duration,
+    keep_alive_timeout: Duration,
+}
+
+impl PoolOptions {
+    fn with_overrides(self, settings: HttpConnectionPoolSettings) -> Self {
+        Self {
+            max_connections: settings
+                .max_connections
+                .map_or(self.max_connections, |max| max.get()),
+            max_streams_per_connection: settings
+                .max_streams_per_connection
+                .map(|max| max.get())
+                .or(self.max_streams_per_connection),
+            idle_timeout: settings.idle_timeout.unwrap_or(self.idle_timeout),
+            keep_alive_interval: settings
+                .keep_alive_interval
+                .unwrap_or(self.keep_alive_interval),
+            keep_alive_timeout: settings
+                .keep_alive_timeout
+                .unwrap_or(self.keep_alive_timeout),
+        }
+    }
+}
+
+impl fmt::Display for PoolOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "max_connections={}", self.max_connections)?;
+        if let Some(max_streams) = self.max_streams_per_connection {
+            write!(f, ",max_streams={max_streams}")?;
+        }
+        write!(
+            f,
+            ",idle_timeout={:?},keep_alive={:?}/{:?}",
+            self.idle_timeout, self.keep_alive_interval, self.keep_alive_timeout
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct PoolKey {
+    endpoint: String,
+    http2_only: bool,
+    options: PoolOptions,
+}
+
+/// How often the pools without any connection are dropped.
+const POOLS_PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Connection pools of the HTTP/2 endpoints, either negotiated with ALPN or with prior knowledge.
+///
+/// A hyper client multiplexes all the requests to an HTTP/2 endpoint on a single connection, hence
+/// requests queue up once the concurrent streams limit of the server is reached. Each endpoint
+/// pool spreads its streams over multiple clients instead, each one holding its own connection.
+///
+/// Clients are dropped once their connection is closed by the idle timeout, and so are the pools
+/// left without any client, like those of removed deployments.
+struct EndpointPools {
+    /// Used for HTTPS as long as HTTP1.1 or HTTP2 was not specifically requested.
+    /// All HTTP versions are possible.
+    alpn: ClientFactory,
+    /// Used when HTTP2 was specifically requested - for cleartext, we use h2c,
+    /// and for HTTPS, we will fail unless the ALPN supports h2.
+    /// In practice, at discovery time we never force h2 for HTTPS.
+    h2: ClientFactory,
+    defaults: PoolOptions,
+    pools: Mutex<Pools>,
+}
+
+struct Pools {
+    by_key: HashMap<PoolKey, EndpointPool>,
+    last_pruned: Instant,
+}
+
+impl Default for Pools {
+    fn default() -> Self {
+        Self {
+            by_key: HashMap::default(),
+            last_pruned: Instant::now(),
+        }
+    }
+}
+
+impl fmt::Debug for EndpointPools {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EndpointPools")
+            .field("defaults", &self.defaults)
+            .finish_non_exhaustive()
+    }
+}
+
+impl EndpointPools {
+    fn acquire(
+        &self,
+        uri: &Uri,
+        http2_only: bool,
+        settings: HttpConnectionPoolSettings,
+    ) -> (PooledHyperClient, StreamGuard) {
+        let key = PoolKey {
+            endpoint: format!(
+                "{}://{}",
+                uri.scheme_str().unwrap_or_default(),
+                uri.authority().map(|a| a.as_str()).unwrap_or_default()
+            ),
+            http2_only,
+            options: self.defaults.with_overrides(settings),
+        };
+
+        let mut pools = self.pools.lock().unwrap();
+        if pools.last_pruned.elapsed() >= POOLS_PRUNE_INTERVAL {
+            pools.prune();
+        }
+        // Acquire while holding the lock, so a new pool can't be pruned before its first stream
+        pools
+            .by_key
+            .entry(key)
+            .or_insert_with_key(|key| {
+                let factory = if key.http2_only { &self.h2 } else { &self.alpn };
+                EndpointPool::new(factory, key)
+            })
+            .acquire()
+    }
+}
+
+impl Pools {
+    fn prune(&mut self) {
+        self.by_key.retain(|_, pool| !pool.prune());
+        self.last_pruned = Instant::now();
+    }
+}
+
+/// State of a pooled client, shared with the streams and the connections it opens.
+#[derive(Default)]
+struct ConnectionState {
+    /// In-flight streams.
+    streams: AtomicUsize,
+    /// Open connections. The client holds at most one, but the previous one might still be
+    /// closing while the client opens a new one.
+    open: AtomicUsize,
+}
+
+impl ConnectionState {
+    fn is_idle(&self) -> bool {
+        self.streams.load(Ordering::Relaxed) == 0 && self.open.load(Ordering::Relaxed) == 0
+    }
+}
+
+#[derive(Clone)]
+struct PooledConnection {
+    client: PooledHyperClient,
+    state: Arc<ConnectionState>,
+}
+
+struct EndpointPool {
+    factory: ClientFactory,
+    max_connections: usize,
+    max_streams_per_connection: Option<usize>,
+    connections: Mutex<Vec<PooledConnection>>,
+    connections_gauge: Gauge,
+    streams_gauge: Gauge,
+    saturated_counter: Counter,
+}
+
+impl EndpointPool {
+    fn new(factory: &ClientFactory, key: &PoolKey) -> Self {
+        let mut factory = factory.clone();
+        factory
+            .builder
+            .pool_idle_timeout(key.options.idle_timeout)
+            .http2_keep_alive_interval(Some(key.options.keep_alive_interval))
+            .http2_keep_alive_timeout(key.options.keep_alive_timeout);
+
+        let options = key.options.to_string();
+        Self {
+            factory,
+            max_connections: key.options.max_connections,
+            max_streams_per_connection: key.options.max_streams_per_connection,
+            connections: Mutex::default(),
+            connections_gauge: gauge!(HTTP_POOL_CONNECTIONS,
+                "endpoint" => key.endpoint.clone(),
+                "options" => options.clone()
+            ),
+            streams_gauge: gauge!(HTTP_POOL_STREAMS,
+                "endpoint" => key.endpoint.clone(),
+                "options" => options.clone()
+            ),
+            saturated_counter: counter!(HTTP_POOL_SATURATED,
+                "endpoint" => key.endpoint.clone(),
+                "options" => options.clone()
+            ),
+        }
+    }
+
+    /// Picks the least loaded connection, opening a new one if all of them reached the streams
+    /// limit. Once the connections limit is reached, the least loaded connection is used anyway.
+    fn acquire(&self) -> (PooledHyperClient, StreamGuard) {
+        let mut connections = self.connections.lock().unwrap();
+        connections.retain(|connection| !connection.state.is_idle());
+        let least_loaded = connections
+            .iter()
+            .enumerate()
+            .map(|(idx, connection)| (idx, connection.state.streams.load(Ordering::Relaxed)))
+            .min_by_key(|(_, streams)| *streams);
+
+        let connection = match least_loaded {
+            Some((idx, streams))
+                if self
+                    .max_streams_per_connection
+                    .is_none_or(|max_streams| streams < max_streams) =>
+            {
+                connections[idx].clone()
+            }
+            _ if connections.len() < self.max_connections => {
+                let state = Arc::new(ConnectionState::default());
+                let connection = PooledConnection {
+                    client: self.factory.builder.build::<_, BoxBody>(CountingConnector {
+                        inner: self.factory.connector.clone(),
+                        state: Arc::clone(&state),
+                        connections_gauge: self.connections_gauge.clone(),
+                    }),
+                    state,
+                };
+                connections.push(connection.clone());
+                connection
+            }
+            Some((idx, _)) => {
+                self.saturated_counter.increment(1);
+                connections[idx].clone()
+            }
+            None => unreachable!("the pool allows at least one connection"),
+        };
+        // Accounted while holding the lock, so the connection can't be pruned in the meantime
+        connection.state.streams.fetch_add(1, Ordering::Relaxed);
+        drop(connections);
+
+        self.streams_gauge.increment(1.0);
+        (
+            connection.client,
+            StreamGuard {
+                state: connection.state,
+                streams_gauge: self.streams_gauge.clone(),
+            },
+        )
+    }
+
+    /// Drops the clients without in-flight streams nor open connections. Returns whether the pool
+    /// is left without any client.
+    fn prune(&self) -> bool {
+        let mut connections = self.connections.lock().unwrap();
+        connections.retain(|connection| !connection.state.is_idle());
+        connections.is_empty()
+    }
+}
+
+/// Accounts a stream to its pooled connection, until dropped.
+struct StreamGuard {
+    state: Arc<ConnectionState>,
+    streams_gauge: Gauge,
+}
+
+impl Drop for StreamGuard {
+    fn drop(&mut self) {
+        self.state.streams.fetch_sub(1, Ordering::Relaxed);
+        self.streams_gauge.decrement(1.0);
+    }
+}
+
+/// Connector of the pooled clients, accounting the connections they open until they're closed.
+#[derive(Clone)]
+struct CountingConnector {
+    inner: ProxiedHttpsConnector,
+    state: Arc<ConnectionState>,
+    connections_gauge: Gauge,
+}
+
+impl Service<Uri> for CountingConnector {
+    type Response = CountedConnection<<ProxiedHttpsConnector as Service<Uri>>::Response>;
+    type Error = <ProxiedHttpsConnector as Service<Uri>>::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        let connecting = self.inner.call(uri);
+        let state = Arc::clone(&self.state);
+        let connections_gauge = self.connections_gauge.clone();
+        async move {
+            let io = connecting.await?;
+            state.open.fetch_add(1, Ordering::Relaxed);
+            connections_gauge.increment(1.0);
+            Ok(CountedConnection {
+                io,
+                state,
+                connections_gauge,
+            })
+        }
+        .boxed()
+    }
+}
+
+/// A connection opened by [`CountingConnector`], accounted until dropped.
+struct CountedConnection<T> {
+    io: T,
+    state: Arc<ConnectionState>,
+    connections_gauge: Gauge,
+}
+
+impl<T> Drop for CountedConnection<T> {
+    fn drop(&mut self) {
+        self.state.open.fetch_sub(1, Ordering::Relaxed);
+        self.connections_gauge.decrement(1.0);
+    }
+}
+
+impl<T: Connection> Connection for CountedConnection<T> {
+    fn connected(&self) -> Connected {
+        self.io.connected()
+    }
+}
+
+impl<T: hyper::rt::Read + Unpin> hyper::rt::Read for CountedConnection<T> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: ReadBufCursor<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.get_mut().io).poll_read(cx, buf)
+    }
+}
+
+impl<T: hyper::rt::Write + Unpin> hyper::rt::Write for CountedConnection<T> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Pin::new(&mut self.get_mut().io).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.get_mut().io).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.get_mut().io).poll_shutdown(cx)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.io.is_write_vectored()
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[std::io::IoSlice<'_>],
+    ) -> Poll<std::io::Result<usize>> {
+        Pin::new(&mut self.get_mut().io).poll_write_vectored(cx, bufs)
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum HttpError {
     #[error(transparent)]
@@ -313,3 +763,104 @@ impl fmt::Display for FormatHyperError<'_> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::num::NonZeroUsize;
+
+    fn connections(client: &HttpClient) -> Vec<usize> {
+        let pools = client.pools.pools.lock().unwrap();
+        let pool = pools.by_key.values().next().unwrap();
+        pool.connections
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|connection| connection.state.streams.load(Ordering::Relaxed))
+            .collect()
+    }
+
+    #[test]
+    fn spreads_streams_over_connections() {
+        let client = HttpClient::from_options(&HttpOptions {
+            max_connections_per_endpoint: NonZeroUsize::new(2).unwrap(),
+            max_streams_per_connection: NonZeroUsize::new(2),
+            ..HttpOptions::default()
+        });
+        let uri = Uri::from_static("http://localhost:9080/");
+        let acquire = || {
+            client
+                .pools
+                .acquire(&uri, true, HttpConnectionPoolSettings::default())
+                .1
+        };
+
+        let first = acquire();
+        let _second = acquire();
+        assert_eq!(connections(&client), vec![2]);
+
+        // the first connection reached the streams limit
+        let _third = acquire();
+        assert_eq!(connections(&client), vec![2, 1]);
+
+        // the pool reached the connections limit, streams go to the least loaded connection
+        let _fourth = acquire();
+        let _fifth = acquire();
+        assert_eq!(connections(&client), vec![3, 2]);
+
+        drop(first);
+        assert_eq!(connections(&client), vec![2, 2]);
+    }
+
+    #[test]
+    fn idle_connections_and_pools_are_pruned() {
+        let client = HttpClient::from_options(&HttpOptions::default());
+        let uri = Uri::from_static("http://localhost:9080/");
+        let prune = || client.pools.pools.lock().unwrap().prune();
+
+        let stream = client
+            .pools
+            .acquire(&uri, true, HttpConnectionPoolSettings::default())
+            .1;
+
+        // in-flight streams keep their connection
+        prune();
+        assert_eq!(connections(&client), vec![1]);
+
+        // without streams nor open connections, both the connection and its pool are dropped
+        drop(stream);
+        prune();
+        assert!(client.pools.pools.lock().unwrap().by_key.is_empty());
+    }
+
+    #[test]
+    fn deployment_settings_override_defaults() {
+        let defaults = PoolOptions {
+            max_connections: 1,
+            max_streams_per_connection: None,
+            idle_timeout: Duration::from_secs(90),
+            keep_alive_interval: Duration::from_secs(40),
+            keep_alive_timeout: Duration::from_secs(20),
+        };
+
+        assert_eq!(
+            defaults.with_overrides(HttpConnectionPoolSettings::default()),
+            defaults
+        );
+        assert_eq!(
+            defaults.with_overrides(HttpConnectionPoolSettings {
+                max_connections: NonZeroUsize::new(4),
+                max_streams_per_connection: NonZeroUsize::new(100),
+                keep_alive_interval: Some(Duration::from_secs(10)),
+                ..HttpConnectionPoolSettings::default()
+            }),
+            PoolOptions {
+                max_connections: 4,
+                max_streams_per_connection: Some(100),
+                keep_alive_interval: Duration::from_secs(10),
+                ..defaults
+            }
+        );
+    }
+}
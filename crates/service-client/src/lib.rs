@@ -8,12 +8,14 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-pub use crate::http::HttpClient;
+pub use crate::http::{HttpClient, HttpResponseBody};
 use crate::lambda::LambdaClient;
+use crate::wasm::WasmClient;
 
 pub use crate::http::HttpError;
 pub use crate::lambda::AssumeRoleCacheMode;
 use crate::request_identity::SignRequest;
+pub use crate::wasm::WasmError;
 use ::http::{HeaderName, HeaderValue, Version};
 use arc_swap::ArcSwapOption;
 use bytes::Bytes;
@@ -26,28 +28,30 @@ use hyper::http::uri::PathAndQuery;
 use hyper::{HeaderMap, Response, Uri};
 use restate_types::config::ServiceClientOptions;
 use restate_types::identifiers::LambdaARN;
-use restate_types::schema::deployment::EndpointLambdaCompression;
+use restate_types::schema::deployment::{EndpointLambdaCompression, HttpConnectionPoolSettings};
 use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::Formatter;
 use std::future;
 use std::future::Future;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 mod http;
 mod lambda;
+mod metric_definitions;
 mod proxy;
 mod request_identity;
 mod utils;
+mod wasm;
 
-pub type ResponseBody = http_body_util::Either<hyper::body::Incoming, Full<Bytes>>;
+pub type ResponseBody = http_body_util::Either<HttpResponseBody, Full<Bytes>>;
 
 #[derive(Debug, Clone)]
 pub struct ServiceClient {
-    // TODO a single client uses the pooling provided by hyper, but this is not enough.
-    //  See https://github.com/restatedev/restate/issues/76 for more background on the topic.
     http: HttpClient,
     lambda: LambdaClient,
+    wasm: WasmClient,
     // this can be changed to re-read periodically if necessary
     request_identity_key: Arc<ArcSwapOption<request_identity::v1::SigningKey>>,
     additional_request_headers: HashMap<HeaderName, HeaderValue>,
@@ -57,12 +61,14 @@ impl ServiceClient {
     pub(crate) fn new(
         http: HttpClient,
         lambda: LambdaClient,
+        wasm: WasmClient,
         request_identity_key: Arc<ArcSwapOption<request_identity::v1::SigningKey>>,
         additional_request_headers: HashMap<HeaderName, HeaderValue>,
     ) -> Self {
         Self {
             http,
             lambda,
+            wasm,
             request_identity_key,
             additional_request_headers,
         }
@@ -87,6 +93,7 @@ impl ServiceClient {
         Ok(Self::new(
             HttpClient::from_options(&options.http),
             LambdaClient::from_options(&options.lambda, assume_role_cache_mode),
+            WasmClient::from_options(&options.wasm).map_err(BuildError::WasmEngine)?,
             request_identity_key,
             options
                 .additional_request_headers
@@ -101,6 +108,8 @@ impl ServiceClient {
 pub enum BuildError {
     #[error("Failed to read request identity private key: {0}")]
     SigningPrivateKeyReadError(#[from] request_identity::v1::SigningPrivateKeyReadError),
+    #[error("Failed to create the WebAssembly engine: {0:#}")]
+    WasmEngine(wasmtime::Error),
 }
 
 impl ServiceClient {
@@ -137,10 +146,11 @@ impl ServiceClient {
         );
 
         match parts.address {
-            Endpoint::Http(uri, version) => {
+            Endpoint::Http(uri, version, connection_pool) => {
                 let fut = self.http.request(
                     uri.clone(),
                     version,
+                    connection_pool,
                     parts.method.into(),
                     body,
                     parts.path,
@@ -153,6 +163,7 @@ impl ServiceClient {
                         .map(http_body_util::Either::Left))
                 }
                 .left_future()
+                .left_future()
             }
             Endpoint::Lambda(arn, assume_role_arn, compression) => {
                 let fut = self.lambda.invoke(
@@ -171,6 +182,25 @@ impl ServiceClient {
                         .map(http_body_util::Either::Right))
                 }
                 .right_future()
+                .left_future()
+            }
+            Endpoint::Wasm(module_path, fuel_limit, memory_limit) => {
+                let fut = self.wasm.invoke(
+                    module_path.clone(),
+                    fuel_limit,
+                    memory_limit,
+                    parts.method.into(),
+                    body,
+                    parts.path,
+                    parts.headers,
+                );
+                async move {
+                    Ok(fut
+                        .await
+                        .map_err(|e| ServiceClientError::Wasm(module_path, e))?
+                        .map(http_body_util::Either::Right))
+                }
+                .right_future()
             }
         }
         .left_future()
@@ -183,6 +213,8 @@ pub enum ServiceClientError {
     Http(Uri, #[source] http::HttpError),
     #[error("error when calling '{0}': {1}")]
     Lambda(LambdaARN, #[source] lambda::LambdaError),
+    #[error("error when calling 'wasm://{}': {1}", .0.display())]
+    Wasm(PathBuf, #[source] wasm::WasmError),
     #[error(transparent)]
     IdentityV1(#[from] <request_identity::v1::Signer<'static, 'static> as SignRequest>::Error),
 }
@@ -194,6 +226,7 @@ impl ServiceClientError {
         match self {
             ServiceClientError::Http(_, http_error) => http_error.is_retryable(),
             ServiceClientError::Lambda(_, lambda_error) => lambda_error.is_retryable(),
+            ServiceClientError::Wasm(_, wasm_error) => wasm_error.is_retryable(),
             ServiceClientError::IdentityV1(_) => false, // this really should never happen
         }
     }
@@ -270,19 +303,22 @@ impl Parts {
 
 #[derive(Clone, Debug)]
 pub enum Endpoint {
-    Http(Uri, Option<Version>),
+    Http(Uri, Option<Version>, HttpConnectionPoolSettings),
     Lambda(
         LambdaARN,
         Option<ByteString>,
         Option<EndpointLambdaCompression>,
     ),
+    /// Module path, fuel limit and memory limit
+    Wasm(PathBuf, Option<u64>, Option<u64>),
 }
 
 impl fmt::Display for Endpoint {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Http(uri, _) => uri.fmt(f),
+            Self::Http(uri, _, _) => uri.fmt(f),
             Self::Lambda(arn, _, _) => write!(f, "lambda://{arn}"),
+            Self::Wasm(module_path, _, _) => write!(f, "wasm://{}", module_path.display()),
         }
     }
 }
@@ -0,0 +1,37 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+/// Optional to have but adds description/help message to the metrics emitted to
+/// the metrics' sink.
+use metrics::{Unit, describe_counter, describe_gauge};
+
+pub const HTTP_POOL_CONNECTIONS: &str = "restate.service_client.http.pool.connections";
+pub const HTTP_POOL_STREAMS: &str = "restate.service_client.http.pool.streams";
+pub const HTTP_POOL_SATURATED: &str = "restate.service_client.http.pool.saturated.total";
+
+pub(crate) fn describe_metrics() {
+    describe_gauge!(
+        HTTP_POOL_CONNECTIONS,
+        Unit::Count,
+        "Number of open connections of the HTTP/2 connection pool, by endpoint and pool options"
+    );
+
+    describe_gauge!(
+        HTTP_POOL_STREAMS,
+        Unit::Count,
+        "Number of in-flight streams in the HTTP/2 connection pool, by endpoint and pool options"
+    );
+
+    describe_counter!(
+        HTTP_POOL_SATURATED,
+        Unit::Count,
+        "Number of requests sent while all the connections of the HTTP/2 connection pool were at their streams limit, by endpoint and pool options"
+    );
+}
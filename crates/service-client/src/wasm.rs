@@ -0,0 +1,432 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! In-process execution of WebAssembly deployments.
+//!
+//! Modules speak the service protocol in request/response mode, exactly like Lambda deployments:
+//! each request is handed to the module as the same JSON API Gateway proxy event used for Lambda,
+//! and the module replies with an API Gateway proxy response. A module must export:
+//!
+//! * `memory`: its linear memory.
+//! * `restate_alloc(len: i32) -> i32`: allocates `len` bytes, returning their offset.
+//! * `restate_handle(ptr: i32, len: i32) -> i64`: handles the event stored at `ptr`, returning the
+//!   offset of the response in the upper 32 bits and its length in the lower 32 bits.
+//!
+//! Modules can't import any host function. Every request runs in a fresh instance, bounded by the
+//! fuel and memory limits of the deployment.
+//!
+//! Why the API Gateway envelope rather than a dedicated in-memory protocol: without host functions
+//! a module can't block on further input, so it can only run to completion on a single request,
+//! which is precisely the request/response mode of the service protocol. That mode already
+//! carries the whole journal in the request and suspends the invocation at the end of every
+//! response, and the SDKs already implement it for Lambda behind this envelope. Reusing it lets
+//! modules be built with the unchanged Lambda handler of an SDK, and lets the invoker treat them
+//! like any other request/response deployment. The cost of the JSON and base64 encoding is small
+//! compared to instantiating the module.
+//!
+//! Compiled modules are cached by path, and recompiled whenever the modification time or the
+//! length of the file change, so replacing the file of a deployment doesn't run a stale module.
+//! Modules which haven't run for a while, like those of removed deployments, are evicted from the
+//! cache.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use bytes::Bytes;
+use http::uri::PathAndQuery;
+use http::{HeaderMap, HeaderValue, Method, Response};
+use http_body_util::{BodyExt, Full};
+use hyper::body::Body;
+use tracing::debug;
+use wasmtime::{Config, Engine, Instance, Module, Store, StoreLimits, StoreLimitsBuilder, Trap};
+
+use restate_types::config::WasmOptions;
+
+use crate::lambda::{ApiGatewayProxyRequest, ApiGatewayProxyResponse, LambdaError};
+
+/// Compiled modules which didn't run for this long are evicted from the cache.
+const MODULE_IDLE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+struct CachedModule {
+    module: Module,
+    /// Modification time of the file the module was compiled from.
+    modified: SystemTime,
+    /// Length of the file the module was compiled from.
+    len: u64,
+    last_used: Instant,
+}
+
+impl CachedModule {
+    fn is_compiled_from(&self, metadata: &std::fs::Metadata) -> bool {
+        metadata
+            .modified()
+            .is_ok_and(|modified| modified == self.modified)
+            && metadata.len() == self.len
+    }
+}
+
+#[derive(Clone)]
+pub struct WasmClient {
+    engine: Engine,
+    /// Compiled modules by the path of their file.
+    modules: Arc<Mutex<HashMap<PathBuf, CachedModule>>>,
+    default_fuel_limit: u64,
+    default_memory_limit: usize,
+}
+
+impl fmt::Debug for WasmClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WasmClient")
+            .field("default_fuel_limit", &self.default_fuel_limit)
+            .field("default_memory_limit", &self.default_memory_limit)
+            .finish_non_exhaustive()
+    }
+}
+
+impl WasmClient {
+    pub fn from_options(options: &WasmOptions) -> Result<Self, wasmtime::Error> {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+
+        Ok(Self {
+            engine: Engine::new(&config)?,
+            modules: Default::default(),
+            default_fuel_limit: options.wasm_fuel_limit,
+            default_memory_limit: options.wasm_memory_limit.as_usize(),
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn invoke<B>(
+        &self,
+        module_path: PathBuf,
+        fuel_limit: Option<u64>,
+        memory_limit: Option<u64>,
+        method: Method,
+        body: B,
+        path: PathAndQuery,
+        headers: HeaderMap<HeaderValue>,
+    ) -> impl Future<Output = Result<Response<Full<Bytes>>, WasmError>> + Send + 'static
+    where
+        B: Body + Send + Unpin + 'static,
+        <B as Body>::Data: Send,
+        <B as Body>::Error: Error + Send + Sync + 'static,
+    {
+        let client = self.clone();
+        let fuel_limit = fuel_limit.unwrap_or(self.default_fuel_limit);
+        let memory_limit = memory_limit
+            .and_then(|limit| usize::try_from(limit).ok())
+            .unwrap_or(self.default_memory_limit);
+
+        async move {
+            let body = body
+                .map_err(|e| WasmError::Body(Box::new(e)))
+                .collect()
+                .await?
+                .to_bytes();
+            let event = serde_json::to_vec(&ApiGatewayProxyRequest {
+                path: Some(path.path()),
+                http_method: method,
+                headers,
+                body,
+                is_base64_encoded: true,
+            })
+            .map_err(WasmError::SerializationError)?;
+
+            // Compilation and execution are CPU bound
+            let response = tokio::task::spawn_blocking(move || {
+                client.run(&module_path, fuel_limit, memory_limit, &event)
+            })
+            .await
+            .map_err(|e| WasmError::Execution(e.into()))??;
+
+            let response: ApiGatewayProxyResponse =
+                serde_json::from_slice(&response).map_err(WasmError::DeserializationError)?;
+            response.try_into().map_err(WasmError::InvalidResponse)
+        }
+    }
+
+    fn module(&self, module_path: &Path) -> Result<Module, WasmError> {
+        let metadata = std::fs::metadata(module_path)?;
+        let now = Instant::now();
+
+        {
+            let mut modules = self.modules.lock().unwrap();
+            modules.retain(|_, cached| now.duration_since(cached.last_used) < MODULE_IDLE_TIMEOUT);
+            if let Some(cached) = modules.get_mut(module_path)
+                && cached.is_compiled_from(&metadata)
+            {
+                cached.last_used = now;
+                return Ok(cached.module.clone());
+            }
+        }
+
+        debug!("Compiling WebAssembly module {}", module_path.display());
+        let module_bytes = std::fs::read(module_path)?;
+        let module = Module::new(&self.engine, &module_bytes).map_err(WasmError::Load)?;
+        self.modules.lock().unwrap().insert(
+            module_path.to_owned(),
+            CachedModule {
+                module: module.clone(),
+                // If the file changes between reading its metadata and its content, the next
+                // request compiles it again.
+                modified: metadata.modified()?,
+                len: metadata.len(),
+                last_used: now,
+            },
+        );
+        Ok(module)
+    }
+
+    fn run(
+        &self,
+        module_path: &Path,
+        fuel_limit: u64,
+        memory_limit: usize,
+        event: &[u8],
+    ) -> Result<Vec<u8>, WasmError> {
+        let module = self.module(module_path)?;
+
+        let mut store = Store::new(
+            &self.engine,
+            StoreLimitsBuilder::new()
+                .memory_size(memory_limit)
+                .instances(1)
+                .build(),
+        );
+        store.limiter(|limits: &mut StoreLimits| limits);
+        store.set_fuel(fuel_limit).map_err(WasmError::Execution)?;
+
+        let instance =
+            Instance::new(&mut store, &module, &[]).map_err(WasmError::from_execution)?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or(WasmError::MissingExport("memory"))?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&mut store, "restate_alloc")
+            .map_err(|_| WasmError::MissingExport("restate_alloc"))?;
+        let handle = instance
+            .get_typed_func::<(i32, i32), i64>(&mut store, "restate_handle")
+            .map_err(|_| WasmError::MissingExport("restate_handle"))?;
+
+        let event_len = i32::try_from(event.len()).map_err(|_| WasmError::RequestTooLarge)?;
+        let event_ptr = alloc
+            .call(&mut store, event_len)
+            .map_err(WasmError::from_execution)?;
+        memory.write(&mut store, event_ptr as u32 as usize, event)?;
+
+        let response = handle
+            .call(&mut store, (event_ptr, event_len))
+            .map_err(WasmError::from_execution)? as u64;
+        let response_ptr = (response >> 32) as usize;
+        let response_len = (response & u64::from(u32::MAX)) as usize;
+        // Don't trust the module with the size of the allocation
+        if response_ptr
+            .checked_add(response_len)
+            .is_none_or(|response_end| response_end > memory.data_size(&store))
+        {
+            return Err(WasmError::ResponseOutOfBounds);
+        }
+
+        let mut buf = vec![0; response_len];
+        memory.read(&store, response_ptr, &mut buf)?;
+        Ok(buf)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum WasmError {
+    #[error("problem reading request body: {0}")]
+    Body(#[from] Box<dyn Error + Send + Sync>),
+    #[error("cannot read module: {0}")]
+    Read(#[from] std::io::Error),
+    #[error("cannot load module: {0:#}")]
+    Load(wasmtime::Error),
+    #[error("module doesn't export '{0}'")]
+    MissingExport(&'static str),
+    #[error("request doesn't fit in the 32 bits address space of the module")]
+    RequestTooLarge,
+    #[error("module accessed memory out of bounds: {0}")]
+    MemoryAccess(#[from] wasmtime::MemoryAccessError),
+    #[error("module returned a response out of the bounds of its memory")]
+    ResponseOutOfBounds,
+    #[error("module ran out of fuel")]
+    FuelExhausted,
+    #[error("module failed during execution: {0:#}")]
+    Execution(wasmtime::Error),
+    #[error("module request could not be serialized: {0}")]
+    SerializationError(serde_json::Error),
+    #[error("module response could not be deserialized: {0}")]
+    DeserializationError(serde_json::Error),
+    #[error("module returned an invalid response: {0}")]
+    InvalidResponse(LambdaError),
+}
+
+impl WasmError {
+    fn from_execution(err: wasmtime::Error) -> Self {
+        if err.downcast_ref::<Trap>() == Some(&Trap::OutOfFuel) {
+            WasmError::FuelExhausted
+        } else {
+            WasmError::Execution(err)
+        }
+    }
+
+    /// Retryable errors are those which can be caused by transient faults and where
+    /// retrying can succeed.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            // the module file might not be available yet on this node
+            WasmError::Read(_) => true,
+            WasmError::Body(_)
+            | WasmError::Load(_)
+            | WasmError::MissingExport(_)
+            | WasmError::RequestTooLarge
+            | WasmError::MemoryAccess(_)
+            | WasmError::ResponseOutOfBounds
+            | WasmError::FuelExhausted
+            | WasmError::Execution(_)
+            | WasmError::SerializationError(_)
+            | WasmError::DeserializationError(_)
+            | WasmError::InvalidResponse(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use googletest::prelude::*;
+    use tempfile::NamedTempFile;
+
+    /// Echoes the request back, or returns whatever response `handle_body` computes.
+    fn module(handle_body: &str) -> NamedTempFile {
+        let module = NamedTempFile::new().unwrap();
+        let wat = format!(
+            r#"(module
+                (memory (export "memory") 1)
+                (func (export "restate_alloc") (param i32) (result i32)
+                    i32.const 1024)
+                (func (export "restate_handle") (param $ptr i32) (param $len i32) (result i64)
+                    {handle_body}))"#
+        );
+        // compiled from the text format, thanks to the `wat` feature of wasmtime
+        std::fs::write(module.path(), wat).unwrap();
+        module
+    }
+
+    const ECHO: &str = r#"
+        local.get $ptr
+        i64.extend_i32_u
+        i64.const 32
+        i64.shl
+        local.get $len
+        i64.extend_i32_u
+        i64.or"#;
+
+    fn client() -> WasmClient {
+        WasmClient::from_options(&WasmOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let module = module(ECHO);
+        let client = client();
+
+        assert_that!(
+            client.run(module.path(), 1_000_000, 1 << 20, b"hello"),
+            ok(eq(b"hello"))
+        );
+        // served by the cached module
+        assert_that!(
+            client.run(module.path(), 1_000_000, 1 << 20, b"world"),
+            ok(eq(b"world"))
+        );
+    }
+
+    #[test]
+    fn replaced_module_is_recompiled() {
+        let module = module(ECHO);
+        let client = client();
+        assert_that!(
+            client.run(module.path(), 1_000_000, 1 << 20, b"hello"),
+            ok(eq(b"hello"))
+        );
+
+        // return the first byte of the request only
+        let replacement = self::module(
+            r#"
+            local.get $ptr
+            i64.extend_i32_u
+            i64.const 32
+            i64.shl
+            i64.const 1
+            i64.or"#,
+        );
+        std::fs::copy(replacement.path(), module.path()).unwrap();
+
+        assert_that!(
+            client.run(module.path(), 1_000_000, 1 << 20, b"hello"),
+            ok(eq(b"h"))
+        );
+        // the stale module is replaced
+        assert_that!(client.modules.lock().unwrap().len(), eq(1));
+    }
+
+    #[test]
+    fn fuel_exhaustion() {
+        let module = module("(loop br 0) unreachable");
+
+        assert_that!(
+            client().run(module.path(), 1_000_000, 1 << 20, b"hello"),
+            err(pat!(WasmError::FuelExhausted))
+        );
+    }
+
+    #[test]
+    fn memory_limit() {
+        // each page is 64KiB
+        let module = module(
+            r#"
+            i32.const 16
+            memory.grow
+            i32.const -1
+            i32.eq
+            if unreachable end
+            i64.const 0"#,
+        );
+        let client = client();
+
+        assert_that!(
+            client.run(module.path(), 1_000_000, 1 << 20, b"hello"),
+            err(pat!(WasmError::Execution(_)))
+        );
+        assert_that!(
+            client.run(module.path(), 1_000_000, 2 << 20, b"hello"),
+            ok(eq(b""))
+        );
+    }
+
+    #[test]
+    fn response_out_of_bounds() {
+        // 4GiB response, at offset 0
+        let module = module("i64.const 0xffffffff");
+
+        assert_that!(
+            client().run(module.path(), 1_000_000, 1 << 20, b"hello"),
+            err(pat!(WasmError::ResponseOutOfBounds))
+        );
+    }
+}
@@ -123,6 +123,7 @@ impl Encoder for ServiceProtocolV4Codec {
                         span_context,
                         completion_retention_duration,
                         journal_retention_duration,
+                        deadline,
                     },
                 invocation_id_completion_id,
                 result_completion_id,
@@ -152,6 +153,7 @@ impl Encoder for ServiceProtocolV4Codec {
                     span_context,
                     completion_retention_duration,
                     journal_retention_duration,
+                    deadline,
                 },
             )))
             .into(),
@@ -167,6 +169,7 @@ impl Encoder for ServiceProtocolV4Codec {
                         span_context,
                         completion_retention_duration,
                         journal_retention_duration,
+                        deadline,
                     },
                 invoke_time,
                 invocation_id_completion_id,
@@ -196,6 +199,7 @@ impl Encoder for ServiceProtocolV4Codec {
                     span_context,
                     completion_retention_duration,
                     journal_retention_duration,
+                    deadline,
                 },
             )))
             .into(),
@@ -690,6 +694,7 @@ impl Decoder for ServiceProtocolV4Codec {
                             idempotency_key: idempotency_key.map(|s| s.into()),
                             completion_retention_duration: metadata.completion_retention_duration,
                             journal_retention_duration: metadata.journal_retention_duration,
+                            deadline: metadata.deadline,
                         },
                         invocation_id_completion_id: invocation_id_notification_idx,
                         result_completion_id,
@@ -721,6 +726,7 @@ impl Decoder for ServiceProtocolV4Codec {
                             idempotency_key: idempotency_key.map(|s| s.into()),
                             completion_retention_duration: metadata.completion_retention_duration,
                             journal_retention_duration: metadata.journal_retention_duration,
+                            deadline: metadata.deadline,
                         },
                         invoke_time: invoke_time.into(),
                         invocation_id_completion_id: invocation_id_notification_idx,
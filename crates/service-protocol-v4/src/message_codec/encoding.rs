@@ -11,6 +11,7 @@
 use super::UnknownMessageType;
 use super::*;
 
+use std::io::Read;
 use std::mem;
 
 use bytes::{Buf, BufMut, Bytes, BytesMut};
@@ -25,6 +26,10 @@ use restate_types::service_protocol::ServiceProtocolVersion;
 pub enum EncodingError {
     #[error("cannot decode message type {0:?}. This looks like a bug of the SDK. Reason: {1:?}")]
     DecodeMessage(MessageType, #[source] prost::DecodeError),
+    #[error(
+        "cannot decompress message type {0:?}. This looks like a bug of the SDK. Reason: {1:?}"
+    )]
+    DecompressMessage(MessageType, #[source] std::io::Error),
     #[error(transparent)]
     UnknownMessageType(#[from] UnknownMessageType),
     #[error("hit message size limit: {0} >= {1}")]
@@ -32,10 +37,13 @@ pub enum EncodingError {
     MessageSizeLimit(usize, usize),
 }
 
+const ZSTD_COMPRESSION_LEVEL: i32 = 3;
+
 // --- Input message encoder
 
 pub struct Encoder {
     arena: BytesMut,
+    compression_threshold: Option<usize>,
 }
 
 impl Encoder {
@@ -47,11 +55,30 @@ impl Encoder {
         );
         Self {
             arena: BytesMut::with_capacity(1024),
+            compression_threshold: None,
         }
     }
 
+    /// Compresses with zstd the messages larger than the given threshold. This must be enabled
+    /// only if the other side advertised support for message compression.
+    pub fn with_compression(mut self, compression_threshold: usize) -> Self {
+        self.compression_threshold = Some(compression_threshold);
+        self
+    }
+
     /// Encodes a message to bytes
     pub fn encode(&mut self, msg: Message) -> Bytes {
+        if self.should_compress(msg.encoded_len()) {
+            let mut payload = BytesMut::with_capacity(msg.encoded_len());
+            let header = generate_header(&msg);
+            msg.encode(&mut payload).expect(
+                "Encoding messages should be infallible, \
+                this error indicates a bug in the invoker code. \
+                Please contact the Restate developers.",
+            );
+            return self.encode_compressed(header, &payload);
+        }
+
         self.arena.reserve(self.encoded_len(&msg));
         self.encode_to_arena(msg).expect(
             "Encoding messages should be infallible, \
@@ -63,16 +90,49 @@ impl Encoder {
 
     /// Encodes a raw message to bytes
     pub fn encode_raw(&mut self, msg_ty: MessageType, content: Bytes) -> Bytes {
-        self.arena.reserve(8 + content.len());
         let len: u32 = content
             .len()
             .try_into()
             .expect("Protocol messages can't be larger than u32");
+        if self.should_compress(content.len()) {
+            return self.encode_compressed(MessageHeader::new(msg_ty, len), &content);
+        }
+
+        self.arena.reserve(8 + content.len());
         self.arena.put_u64(MessageHeader::new(msg_ty, len).into());
         self.arena.put(content);
         self.arena.split().freeze()
     }
 
+    fn should_compress(&self, payload_len: usize) -> bool {
+        self.compression_threshold
+            .is_some_and(|compression_threshold| payload_len > compression_threshold)
+    }
+
+    /// Encodes the given payload compressed, unless compression doesn't reduce its size.
+    fn encode_compressed(&mut self, header: MessageHeader, payload: &[u8]) -> Bytes {
+        let compressed = zstd::bulk::compress(payload, ZSTD_COMPRESSION_LEVEL)
+            .expect("Compressing in-memory messages should be infallible");
+
+        let (header, payload) = if compressed.len() < payload.len() {
+            let len: u32 = compressed
+                .len()
+                .try_into()
+                .expect("Protocol messages can't be larger than u32");
+            (
+                MessageHeader::new(header.message_type(), len).compressed(),
+                &compressed[..],
+            )
+        } else {
+            (header, payload)
+        };
+
+        self.arena.reserve(8 + payload.len());
+        self.arena.put_u64(header.into());
+        self.arena.put_slice(payload);
+        self.arena.split().freeze()
+    }
+
     /// Includes header len
     fn encoded_len(&self, msg: &Message) -> usize {
         8 + msg.encoded_len()
@@ -207,6 +267,19 @@ impl DecoderState {
 
                 DecoderState::WaitingPayload(header)
             }
+            DecoderState::WaitingPayload(h) if h.is_compressed() => {
+                let payload = decompress(
+                    h.message_type(),
+                    buf.take(h.frame_length() as usize),
+                    message_size_limit,
+                )?;
+                let msg = h
+                    .message_type()
+                    .decode(payload)
+                    .map_err(|e| EncodingError::DecodeMessage(h.message_type(), e))?;
+                res = Some((h, msg));
+                DecoderState::WaitingHeader
+            }
             DecoderState::WaitingPayload(h) => {
                 let msg = h
                     .message_type()
@@ -221,6 +294,31 @@ impl DecoderState {
     }
 }
 
+/// Decompresses the frame payload, failing if the decompressed message hits the size limit.
+fn decompress(
+    ty: MessageType,
+    payload: impl Buf,
+    message_size_limit: usize,
+) -> Result<Bytes, EncodingError> {
+    let mut decompressed = Vec::new();
+    zstd::stream::read::Decoder::new(payload.reader())
+        .and_then(|decoder| {
+            decoder
+                .take(u64::try_from(message_size_limit).unwrap_or(u64::MAX))
+                .read_to_end(&mut decompressed)
+        })
+        .map_err(|e| EncodingError::DecompressMessage(ty, e))?;
+
+    if decompressed.len() >= message_size_limit {
+        return Err(EncodingError::MessageSizeLimit(
+            decompressed.len(),
+            message_size_limit,
+        ));
+    }
+
+    Ok(decompressed.into())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -321,4 +419,50 @@ mod tests {
         assert_eq!(msg_size, expected_msg_size);
         assert_eq!(limit, u8::MAX as usize)
     }
+
+    #[test]
+    fn compressed_messages() {
+        let mut encoder = Encoder::new(ServiceProtocolVersion::V1).with_compression(16);
+        let mut decoder = Decoder::new(ServiceProtocolVersion::V1, usize::MAX, None);
+
+        let small_msg = Message::InputCommand(Bytes::from_static(b"123"));
+        let large_msg = Message::InputCommand(Bytes::from(vec![b'a'; 1024]));
+        let large_raw_msg = Bytes::from(vec![b'b'; 1024]);
+
+        decoder.push(encoder.encode(small_msg.clone()));
+        let encoded_large_msg = encoder.encode(large_msg.clone());
+        assert!(encoded_large_msg.len() < large_msg.encoded_len());
+        decoder.push(encoded_large_msg);
+        decoder.push(encoder.encode_raw(MessageType::InputCommand, large_raw_msg.clone()));
+
+        let (header, msg) = decoder.consume_next().unwrap().unwrap();
+        assert!(!header.is_compressed());
+        assert_eq!(msg, small_msg);
+
+        let (header, msg) = decoder.consume_next().unwrap().unwrap();
+        assert!(header.is_compressed());
+        assert_eq!(msg, large_msg);
+
+        let (header, msg) = decoder.consume_next().unwrap().unwrap();
+        assert!(header.is_compressed());
+        assert_eq!(msg, Message::InputCommand(large_raw_msg));
+
+        assert!(decoder.consume_next().unwrap().is_none());
+    }
+
+    #[test]
+    fn hit_message_size_limit_after_decompression() {
+        let mut encoder = Encoder::new(ServiceProtocolVersion::V1).with_compression(16);
+        let mut decoder = Decoder::new(
+            ServiceProtocolVersion::V1,
+            usize::MAX,
+            Some(u8::MAX as usize),
+        );
+
+        decoder.push(encoder.encode(Message::InputCommand(Bytes::from(vec![b'a'; 1024]))));
+        let_assert!(
+            EncodingError::MessageSizeLimit(_, limit) = decoder.consume_next().unwrap_err()
+        );
+        assert_eq!(limit, u8::MAX as usize)
+    }
 }
@@ -11,6 +11,7 @@
 use super::{MessageType, UnknownMessageType};
 
 const REQUIRES_ACK_MASK: u64 = 0x8000_0000_0000;
+const COMPRESSED_MASK: u64 = 0x4000_0000_0000;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct MessageHeader {
@@ -19,6 +20,7 @@ pub struct MessageHeader {
 
     // --- Flags
     requires_ack_flag: Option<bool>,
+    compressed_flag: bool,
 }
 
 impl MessageHeader {
@@ -33,9 +35,17 @@ impl MessageHeader {
             ty,
             length,
             requires_ack_flag,
+            compressed_flag: false,
         }
     }
 
+    /// Marks the frame payload as zstd compressed.
+    #[inline]
+    pub fn compressed(mut self) -> Self {
+        self.compressed_flag = true;
+        self
+    }
+
     #[inline]
     pub fn message_type(&self) -> MessageType {
         self.ty
@@ -46,6 +56,12 @@ impl MessageHeader {
         self.requires_ack_flag
     }
 
+    #[inline]
+    pub fn is_compressed(&self) -> bool {
+        self.compressed_flag
+    }
+
+    /// Length of the frame payload, that is the compressed length if the frame is compressed.
     #[inline]
     pub fn frame_length(&self) -> u32 {
         self.length
@@ -74,7 +90,9 @@ impl TryFrom<u64> for MessageHeader {
         let requires_ack_flag = read_flag_if!(ty.allows_ack(), value, REQUIRES_ACK_MASK);
         let length = value as u32;
 
-        Ok(MessageHeader::_new(ty, requires_ack_flag, length))
+        let mut header = MessageHeader::_new(ty, requires_ack_flag, length);
+        header.compressed_flag = (value & COMPRESSED_MASK) != 0;
+        Ok(header)
     }
 }
 
@@ -98,6 +116,11 @@ impl From<MessageHeader> for u64 {
             &mut res,
             REQUIRES_ACK_MASK
         );
+        write_flag!(
+            Some(message_header.compressed_flag),
+            &mut res,
+            COMPRESSED_MASK
+        );
 
         res
     }
@@ -173,4 +196,21 @@ mod tests {
         10341,
         requires_ack: true
     );
+
+    #[test]
+    fn compressed_flag_roundtrip() {
+        let serialized: u64 = MessageHeader::_new(SetStateCommand, Some(true), 10341)
+            .compressed()
+            .into();
+        let header: MessageHeader = serialized.try_into().unwrap();
+
+        assert_eq!(header.message_type(), SetStateCommand);
+        assert_eq!(header.requires_ack(), Some(true));
+        assert!(header.is_compressed());
+        assert_eq!(header.frame_length(), 10341);
+
+        let serialized: u64 = MessageHeader::new(InputCommand, 10).into();
+        let header: MessageHeader = serialized.try_into().unwrap();
+        assert!(!header.is_compressed());
+    }
 }
@@ -31,7 +31,9 @@ use restate_types::deployment::DeploymentAddress;
 use restate_types::endpoint_manifest;
 use restate_types::errors::GenericError;
 use restate_types::retries::{RetryIter, RetryPolicy};
-use restate_types::schema::deployment::{EndpointLambdaCompression, ProtocolType};
+use restate_types::schema::deployment::{
+    EndpointLambdaCompression, MessageCompression, ProtocolType,
+};
 use restate_types::schema::registry::{
     DeploymentConnectionParameters, DiscoveryClient, DiscoveryRequest, DiscoveryResponse,
 };
@@ -122,7 +124,7 @@ pub enum DiscoveryError {
     )]
     UnsupportedServiceProtocol { min_version: i32, max_version: i32 },
     #[error(
-        "the SDK reports itself as being in bidirectional protocol mode, but we are not discovering over a transport that supports it. Discovering with Lambda or HTTP < 1.1 is not supported"
+        "the SDK reports itself as being in bidirectional protocol mode, but we are not discovering over a transport that supports it. Discovering with Lambda, WebAssembly or HTTP < 1.1 is not supported"
     )]
     BidirectionalNotSupported,
 }
@@ -213,11 +215,14 @@ impl DiscoveryClient for ServiceDiscovery {
                     Some(http::Version::HTTP_2)
                 };
 
-                Endpoint::Http(http.uri, version)
+                Endpoint::Http(http.uri, version, http.connection_pool)
             }
             DeploymentAddress::Lambda(lambda) => {
                 Endpoint::Lambda(lambda.arn, lambda.assume_role_arn.map(Into::into), None)
             }
+            DeploymentAddress::Wasm(wasm) => {
+                Endpoint::Wasm(wasm.module_path, wasm.fuel_limit, wasm.memory_limit)
+            }
         };
 
         let cloned_endpoint = endpoint.clone();
@@ -345,12 +350,15 @@ impl ServiceDiscovery {
             // all endpoints support request response
             (ProtocolType::RequestResponse, _, _) => {}
             // http2 upwards supports bidi
-            (ProtocolType::BidiStream, Endpoint::Http(_, _), Version::HTTP_2 | Version::HTTP_3) => {
-            }
+            (
+                ProtocolType::BidiStream,
+                Endpoint::Http(_, _, _),
+                Version::HTTP_2 | Version::HTTP_3,
+            ) => {}
             // http1.1 *can* support bidi depending on server implementation (and load balancers)
             // trust the user if this is what they advertise
-            (ProtocolType::BidiStream, Endpoint::Http(_, _), Version::HTTP_11) => {}
-            // lambda client and HTTP < 1.1 do not support bidi
+            (ProtocolType::BidiStream, Endpoint::Http(_, _, _), Version::HTTP_11) => {}
+            // lambda client, wasm client and HTTP < 1.1 do not support bidi
             (ProtocolType::BidiStream, _, _) => {
                 return Err(DiscoveryError::BidirectionalNotSupported);
             }
@@ -417,6 +425,7 @@ impl ServiceDiscovery {
                     protocol_type,
                     http_version: response_http_version,
                 },
+                Endpoint::Wasm { .. } => DeploymentConnectionParameters::Wasm,
                 Endpoint::Lambda { .. } => DeploymentConnectionParameters::Lambda {
                     compression: endpoint_response.lambda_compression.map(|compression| {
                         match compression {
@@ -431,6 +440,11 @@ impl ServiceDiscovery {
             // we need to store the raw representation since the runtime might not know the latest
             // version yet.
             supported_protocol_versions: min_version..=max_version,
+            message_compression: endpoint_response.message_compression.map(|compression| {
+                match compression {
+                    endpoint_manifest::EndpointMessageCompression::Zstd => MessageCompression::Zstd,
+                }
+            }),
             sdk_version,
         })
     }
@@ -522,6 +536,7 @@ mod tests {
     fn fail_on_invalid_min_protocol_version_with_bad_response() {
         let response = endpoint_manifest::Endpoint {
             lambda_compression: None,
+            message_compression: None,
             min_protocol_version: NonZeroU64::MAX,
             max_protocol_version: NonZeroU64::MAX,
             services: Vec::new(),
@@ -530,7 +545,7 @@ mod tests {
 
         assert!(matches!(
             ServiceDiscovery::create_discovered_metadata_from_endpoint_response(
-                Endpoint::Http(Uri::default(), None),
+                Endpoint::Http(Uri::default(), None, Default::default()),
                 Version::HTTP_2,
                 response,
                 None
@@ -543,6 +558,7 @@ mod tests {
     fn fail_on_bidirectional_with_lambda() {
         let response = endpoint_manifest::Endpoint {
             lambda_compression: None,
+            message_compression: None,
             min_protocol_version: NonZeroU64::MIN,
             max_protocol_version: NonZeroU64::MIN,
             services: Vec::new(),
@@ -570,6 +586,7 @@ mod tests {
     fn fail_on_invalid_max_protocol_version_with_bad_response() {
         let response = endpoint_manifest::Endpoint {
             lambda_compression: None,
+            message_compression: None,
             min_protocol_version: NonZeroU64::MIN,
             max_protocol_version: NonZeroU64::MAX,
             services: Vec::new(),
@@ -578,7 +595,7 @@ mod tests {
 
         assert!(matches!(
             ServiceDiscovery::create_discovered_metadata_from_endpoint_response(
-                Endpoint::Http(Uri::default(), None),
+                Endpoint::Http(Uri::default(), None, Default::default()),
                 Version::HTTP_2,
                 response,
                 None
@@ -591,6 +608,7 @@ mod tests {
     fn fail_on_max_protocol_version_smaller_than_min_protocol_version_with_bad_response() {
         let response = endpoint_manifest::Endpoint {
             lambda_compression: None,
+            message_compression: None,
             min_protocol_version: NonZeroU64::new(10).unwrap(),
             max_protocol_version: NonZeroU64::new(9).unwrap(),
             services: Vec::new(),
@@ -599,7 +617,7 @@ mod tests {
 
         assert!(matches!(
             ServiceDiscovery::create_discovered_metadata_from_endpoint_response(
-                Endpoint::Http(Uri::default(), None),
+                Endpoint::Http(Uri::default(), None, Default::default()),
                 Version::HTTP_2,
                 response,
                 None
@@ -613,6 +631,7 @@ mod tests {
         let unsupported_version = MAX_DISCOVERABLE_SERVICE_PROTOCOL_VERSION.as_repr() + 1;
         let response = endpoint_manifest::Endpoint {
             lambda_compression: None,
+            message_compression: None,
             min_protocol_version: NonZeroU64::new(unsupported_version as u64).unwrap(),
             max_protocol_version: NonZeroU64::new(unsupported_version as u64).unwrap(),
             services: Vec::new(),
@@ -621,7 +640,7 @@ mod tests {
 
         assert_that!(
             ServiceDiscovery::create_discovered_metadata_from_endpoint_response(
-                Endpoint::Http(Uri::default(), None),
+                Endpoint::Http(Uri::default(), None, Default::default()),
                 Version::HTTP_2,
                 response,
                 None
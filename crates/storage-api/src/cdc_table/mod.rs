@@ -0,0 +1,140 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use bytes::Bytes;
+use bytestring::ByteString;
+
+use restate_types::identifiers::{DeploymentId, InvocationId, ServiceId};
+use restate_types::invocation::{InvocationTarget, ResponseResult};
+use restate_types::logs::Lsn;
+use restate_types::time::MillisSinceEpoch;
+
+use crate::Result;
+use crate::invocation_status_table::{InvocationStatus, InvocationStatusDiscriminants};
+
+/// Position of a captured change in the change data capture table. Changes are ordered by the
+/// LSN of the record that caused them, and by the order in which they were applied within that
+/// record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ChangePosition {
+    pub lsn: Lsn,
+    pub index: u32,
+}
+
+impl ChangePosition {
+    pub const MIN: ChangePosition = ChangePosition {
+        lsn: Lsn::INVALID,
+        index: 0,
+    };
+
+    pub fn new(lsn: Lsn, index: u32) -> Self {
+        Self { lsn, index }
+    }
+}
+
+/// A committed mutation of the partition state.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StateChange {
+    /// A user state entry of a virtual object or workflow was written or deleted.
+    UserState {
+        service_id: ServiceId,
+        key: Bytes,
+        old_value: Option<Bytes>,
+        new_value: Option<Bytes>,
+    },
+    /// An invocation transitioned between statuses. A `None` status means that the invocation
+    /// did not exist before, or has been removed.
+    InvocationStatus {
+        invocation_id: InvocationId,
+        old_status: Option<InvocationStatusSnapshot>,
+        new_status: Option<InvocationStatusSnapshot>,
+    },
+}
+
+restate_types::flexbuffers_storage_encode_decode!(StateChange);
+
+/// The parts of an [`InvocationStatus`] that are exposed to change data capture consumers.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct InvocationStatusSnapshot {
+    pub status: InvocationStatusDiscriminants,
+    pub invocation_target: InvocationTarget,
+    pub idempotency_key: Option<ByteString>,
+    pub deployment_id: Option<DeploymentId>,
+    pub journal_length: Option<u32>,
+    pub created_at: MillisSinceEpoch,
+    pub modified_at: MillisSinceEpoch,
+    /// Only set for completed invocations
+    pub response_result: Option<ResponseResult>,
+}
+
+impl InvocationStatusSnapshot {
+    /// Returns `None` for [`InvocationStatus::Free`].
+    pub fn new(status: &InvocationStatus) -> Option<Self> {
+        let timestamps = status.get_timestamps()?;
+        let (deployment_id, response_result) = match status {
+            InvocationStatus::Invoked(metadata)
+            | InvocationStatus::Suspended { metadata, .. }
+            | InvocationStatus::Paused(metadata) => (
+                metadata
+                    .pinned_deployment
+                    .as_ref()
+                    .map(|pinned| pinned.deployment_id),
+                None,
+            ),
+            InvocationStatus::Completed(completed) => (
+                completed
+                    .pinned_deployment
+                    .as_ref()
+                    .map(|pinned| pinned.deployment_id),
+                Some(completed.response_result.clone()),
+            ),
+            _ => (None, None),
+        };
+
+        Some(Self {
+            status: status.discriminant()?,
+            invocation_target: status.invocation_target()?.clone(),
+            idempotency_key: status.idempotency_key().cloned(),
+            deployment_id,
+            journal_length: status
+                .get_journal_metadata()
+                .map(|journal_metadata| journal_metadata.length),
+            created_at: timestamps.creation_time(),
+            modified_at: timestamps.modification_time(),
+            response_result,
+        })
+    }
+}
+
+pub trait ReadCdcTable {
+    /// Returns up to `limit` captured changes, starting at `from` (inclusive).
+    fn get_next_changes(
+        &mut self,
+        from: ChangePosition,
+        limit: usize,
+    ) -> impl Future<Output = Result<Vec<(ChangePosition, StateChange)>>> + Send;
+}
+
+pub trait WriteCdcTable {
+    /// Starts capturing the state mutations of this transaction as changes caused by the record
+    /// at `lsn`. Mutations are not captured unless this has been called.
+    ///
+    /// The mutations of the invocations and state of `excluded_service` are not captured. This is
+    /// the service the changes are delivered to, whose invocations would otherwise cause new
+    /// changes to deliver, endlessly.
+    fn capture_changes_at(&mut self, lsn: Lsn, excluded_service: Option<ByteString>);
+
+    /// Stops capturing the state mutations of this transaction.
+    fn stop_capturing_changes(&mut self);
+
+    /// Removes all captured changes caused by records up to, and including, `up_to_lsn`.
+    fn truncate_changes(&mut self, up_to_lsn: Lsn) -> Result<()>;
+}
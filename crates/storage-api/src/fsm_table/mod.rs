@@ -9,6 +9,9 @@
 // by the Apache License, Version 2.0.
 
 use std::future::Future;
+use std::time::Duration;
+
+use bytestring::ByteString;
 
 use restate_types::SemanticRestateVersion;
 use restate_types::logs::Lsn;
@@ -35,6 +38,28 @@ pub trait ReadFsmTable {
     ) -> impl Future<Output = Result<Option<PartitionDurability>>> + Send + '_;
 
     fn get_schema(&mut self) -> impl Future<Output = Result<Option<Schema>>> + Send + '_;
+
+    /// Whether the partition processor captures state changes, see
+    /// [`crate::cdc_table::WriteCdcTable`].
+    fn get_change_capture_enabled(&mut self) -> impl Future<Output = Result<bool>> + Send + '_;
+
+    /// Service whose invocations and state are not captured, because it is the change capture
+    /// sink.
+    fn get_change_capture_excluded_service(
+        &mut self,
+    ) -> impl Future<Output = Result<Option<ByteString>>> + Send + '_;
+
+    /// Retention of the invocation history archive, `None` if the archive is disabled. See
+    /// [`crate::invocation_history_table::WriteInvocationHistoryTable`].
+    fn get_invocation_history_retention(
+        &mut self,
+    ) -> impl Future<Output = Result<Option<Duration>>> + Send + '_;
+
+    /// The summaries of the invocations completed before this point are dropped from the
+    /// invocation history archive.
+    fn get_invocation_history_trim_point(
+        &mut self,
+    ) -> impl Future<Output = Result<MillisSinceEpoch>> + Send + '_;
 }
 
 pub trait WriteFsmTable {
@@ -49,6 +74,14 @@ pub trait WriteFsmTable {
     fn put_partition_durability(&mut self, durability: &PartitionDurability) -> Result<()>;
 
     fn put_schema(&mut self, schema: &Schema) -> Result<()>;
+
+    fn put_change_capture_enabled(&mut self, enabled: bool) -> Result<()>;
+
+    fn put_change_capture_excluded_service(&mut self, service_name: Option<&str>) -> Result<()>;
+
+    fn put_invocation_history_retention(&mut self, retention: Option<Duration>) -> Result<()>;
+
+    fn put_invocation_history_trim_point(&mut self, trim_point: MillisSinceEpoch) -> Result<()>;
 }
 
 #[derive(Debug, Clone, Copy, derive_more::From, derive_more::Into)]
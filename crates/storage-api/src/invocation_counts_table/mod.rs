@@ -0,0 +1,71 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use bytestring::ByteString;
+
+use restate_types::identifiers::DeploymentId;
+
+use crate::Result;
+use crate::invocation_status_table::{InvocationStatus, InvocationStatusDiscriminants};
+
+/// Dimensions along which the invocations of a partition are counted.
+///
+/// The counts are maintained by the storage whenever an invocation status is written, so that
+/// aggregating them doesn't require scanning the invocation status table.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InvocationCountKey {
+    pub service_name: ByteString,
+    pub handler_name: ByteString,
+    pub status: InvocationStatusDiscriminants,
+    pub deployment_id: Option<DeploymentId>,
+}
+
+impl InvocationCountKey {
+    /// Returns the dimensions under which the given status is counted, or `None` if the status
+    /// is not counted at all, i.e. for [`InvocationStatus::Free`].
+    pub fn of(status: &InvocationStatus) -> Option<Self> {
+        let invocation_target = status.invocation_target()?;
+        let pinned_deployment = match status {
+            InvocationStatus::Completed(completed) => completed.pinned_deployment.as_ref(),
+            _ => status
+                .get_invocation_metadata()
+                .and_then(|metadata| metadata.pinned_deployment.as_ref()),
+        };
+
+        Some(Self {
+            service_name: invocation_target.service_name().clone(),
+            handler_name: invocation_target.handler_name().clone(),
+            status: status.discriminant()?,
+            deployment_id: pinned_deployment
+                .map(|pinned_deployment| pinned_deployment.deployment_id),
+        })
+    }
+}
+
+pub trait ReadInvocationCountsTable {
+    /// Returns the number of invocations of the partition per [`InvocationCountKey`]. Keys
+    /// without any invocation are omitted.
+    fn get_invocation_counts(
+        &mut self,
+    ) -> impl Future<Output = Result<Vec<(InvocationCountKey, u64)>>> + Send;
+}
+
+pub trait ScanInvocationCountsTable {
+    /// Iterates over the invocation counts of the partition.
+    ///
+    /// Like the outbox, the counts are kept per partition, hence they can only be scanned as a
+    /// whole.
+    fn for_each_invocation_count<
+        F: FnMut((InvocationCountKey, u64)) -> std::ops::ControlFlow<()> + Send + Sync + 'static,
+    >(
+        &self,
+        f: F,
+    ) -> Result<impl Future<Output = Result<()>> + Send>;
+}
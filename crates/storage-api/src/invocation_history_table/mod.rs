@@ -0,0 +1,80 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::ops::RangeInclusive;
+
+use restate_types::errors::InvocationErrorCode;
+use restate_types::identifiers::{DeploymentId, InvocationId, PartitionKey};
+use restate_types::invocation::InvocationTarget;
+use restate_types::time::MillisSinceEpoch;
+
+use crate::Result;
+
+/// Compact summary of a finished invocation, recorded in the invocation history archive.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct InvocationSummary {
+    pub invocation_id: InvocationId,
+    pub invocation_target: InvocationTarget,
+    pub outcome: InvocationOutcome,
+    pub created_at: MillisSinceEpoch,
+    /// First transition to running, if the invocation ever ran.
+    pub running_at: Option<MillisSinceEpoch>,
+    pub completed_at: MillisSinceEpoch,
+    /// Number of attempts of the invocation that failed with a transient error.
+    pub transient_failures: u32,
+    pub deployment_id: Option<DeploymentId>,
+    pub journal_length: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InvocationOutcome {
+    Success,
+    Failure {
+        code: InvocationErrorCode,
+        message: String,
+    },
+}
+
+restate_types::flexbuffers_storage_encode_decode!(InvocationSummary);
+
+pub trait ScanInvocationHistoryTable {
+    /// Scans the summaries of the archive, skipping the ones below the trim point that have not
+    /// been removed yet.
+    fn for_each_invocation_summary<
+        F: FnMut(InvocationSummary) -> std::ops::ControlFlow<()> + Send + Sync + 'static,
+    >(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+        f: F,
+    ) -> Result<impl Future<Output = Result<()>> + Send>;
+}
+
+pub trait WriteInvocationHistoryTable {
+    /// Starts recording the finished invocations of this transaction in the history archive.
+    /// [`WriteInvocationHistoryTable::put_invocation_summary`] is a no-op unless this has been
+    /// called.
+    fn record_invocation_history(&mut self);
+
+    /// Stops recording the finished invocations of this transaction.
+    fn stop_recording_invocation_history(&mut self);
+
+    fn is_recording_invocation_history(&self) -> bool;
+
+    fn put_invocation_summary(&mut self, summary: &InvocationSummary) -> Result<()>;
+
+    /// Removes up to `limit` summaries of invocations completed before `completed_before`,
+    /// oldest first. Returns whether all of them have been removed.
+    fn trim_invocation_history(
+        &mut self,
+        completed_before: MillisSinceEpoch,
+        limit: usize,
+    ) -> Result<bool>;
+}
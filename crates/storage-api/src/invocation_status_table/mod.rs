@@ -194,6 +194,19 @@ impl InvocationStatus {
         }
     }
 
+    /// Returns the deadline which is still to be enforced, hence `None` for completed invocations.
+    #[inline]
+    pub fn deadline(&self) -> Option<MillisSinceEpoch> {
+        match self {
+            InvocationStatus::Scheduled(metadata) => metadata.metadata.deadline,
+            InvocationStatus::Inboxed(metadata) => metadata.metadata.deadline,
+            InvocationStatus::Invoked(metadata)
+            | InvocationStatus::Suspended { metadata, .. }
+            | InvocationStatus::Paused(metadata) => metadata.deadline,
+            _ => None,
+        }
+    }
+
     #[inline]
     pub fn idempotency_key(&self) -> Option<&ByteString> {
         match self {
@@ -385,7 +398,8 @@ impl InvocationStatus {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum InvocationStatusDiscriminants {
     Scheduled,
     Inboxed,
@@ -480,6 +494,8 @@ pub struct PreFlightInvocationMetadata {
     pub source: Source,
     /// Time when the request should be executed
     pub execution_time: Option<MillisSinceEpoch>,
+    /// End-to-end deadline of the invocation. When exceeded, the invocation is cancelled.
+    pub deadline: Option<MillisSinceEpoch>,
 
     /// If zero, the invocation completion will not be retained.
     pub completion_retention_duration: Duration,
@@ -526,6 +542,7 @@ impl PreFlightInvocationMetadata {
             invocation_target: service_invocation.invocation_target,
             source: service_invocation.source,
             execution_time: service_invocation.execution_time,
+            deadline: service_invocation.deadline,
             completion_retention_duration: service_invocation.completion_retention_duration,
             journal_retention_duration: service_invocation.journal_retention_duration,
             idempotency_key: service_invocation.idempotency_key,
@@ -662,6 +679,8 @@ pub struct InFlightInvocationMetadata {
     pub source: Source,
     /// For invocations that were originally scheduled, retains the time when the request was originally scheduled to execute
     pub execution_time: Option<MillisSinceEpoch>,
+    /// End-to-end deadline of the invocation. When exceeded, the invocation is cancelled.
+    pub deadline: Option<MillisSinceEpoch>,
 
     /// If zero, the invocation completion will not be retained.
     pub completion_retention_duration: Duration,
@@ -681,6 +700,9 @@ pub struct InFlightInvocationMetadata {
     ///
     /// When None, infer the seed from the invocation id.
     pub random_seed: Option<u64>,
+
+    /// Number of attempts of this invocation that failed with a transient error.
+    pub transient_failures: u32,
 }
 
 impl InFlightInvocationMetadata {
@@ -708,6 +730,7 @@ impl InFlightInvocationMetadata {
                     timestamps: pre_flight_invocation_metadata.timestamps,
                     source: pre_flight_invocation_metadata.source,
                     execution_time: pre_flight_invocation_metadata.execution_time,
+                    deadline: pre_flight_invocation_metadata.deadline,
                     completion_retention_duration: pre_flight_invocation_metadata
                         .completion_retention_duration,
                     journal_retention_duration: pre_flight_invocation_metadata
@@ -717,6 +740,7 @@ impl InFlightInvocationMetadata {
                     current_invocation_epoch: 0,
                     completion_range_epoch_map: Default::default(),
                     random_seed: pre_flight_invocation_metadata.random_seed,
+                    transient_failures: 0,
                 },
                 Some(InvocationInput { argument, headers }),
             ),
@@ -734,6 +758,7 @@ impl InFlightInvocationMetadata {
                     timestamps: pre_flight_invocation_metadata.timestamps,
                     source: pre_flight_invocation_metadata.source,
                     execution_time: pre_flight_invocation_metadata.execution_time,
+                    deadline: pre_flight_invocation_metadata.deadline,
                     completion_retention_duration: pre_flight_invocation_metadata
                         .completion_retention_duration,
                     journal_retention_duration: pre_flight_invocation_metadata
@@ -743,6 +768,7 @@ impl InFlightInvocationMetadata {
                     current_invocation_epoch: 0,
                     completion_range_epoch_map: Default::default(),
                     random_seed: pre_flight_invocation_metadata.random_seed,
+                    transient_failures: 0,
                 },
                 None,
             ),
@@ -782,6 +808,9 @@ pub struct CompletedInvocation {
     pub source: Source,
     /// For invocations that were originally scheduled, retains the time when the request was originally scheduled to execute
     pub execution_time: Option<MillisSinceEpoch>,
+    /// End-to-end deadline the invocation had while running. It's not enforced anymore, but it's
+    /// carried over when restarting the invocation as new.
+    pub deadline: Option<MillisSinceEpoch>,
     pub idempotency_key: Option<ByteString>,
     pub timestamps: StatusTimestamps,
     pub response_result: ResponseResult,
@@ -825,6 +854,7 @@ impl CompletedInvocation {
                 .created_using_restate_version,
             source: in_flight_invocation_metadata.source,
             execution_time: in_flight_invocation_metadata.execution_time,
+            deadline: in_flight_invocation_metadata.deadline,
             idempotency_key: in_flight_invocation_metadata.idempotency_key,
             timestamps: in_flight_invocation_metadata.timestamps,
             response_result,
@@ -883,6 +913,22 @@ pub trait ScanInvocationStatusTable {
         f: F,
     ) -> Result<impl Future<Output = Result<()>> + Send>;
 
+    /// Like [`ScanInvocationStatusTable::for_each_invocation_status_lazy`], but only visiting the
+    /// given invocations, using point lookups.
+    fn for_each_invocation_status_lazy_of<
+        E: Into<anyhow::Error>,
+        F: for<'a> FnMut(
+                (InvocationId, InvocationStatusV2Lazy<'a>),
+            ) -> ControlFlow<std::result::Result<(), E>>
+            + Send
+            + Sync
+            + 'static,
+    >(
+        &self,
+        invocation_ids: Vec<InvocationId>,
+        f: F,
+    ) -> Result<impl Future<Output = Result<()>> + Send>;
+
     fn scan_invoked_invocations(
         &self,
     ) -> Result<impl Stream<Item = Result<InvokedInvocationStatusLite>> + Send>;
@@ -925,6 +971,7 @@ mod test_util {
                 timestamps: StatusTimestamps::mock(),
                 source: Source::Ingress(PartitionProcessorRpcRequestId::default()),
                 execution_time: None,
+                deadline: None,
                 completion_retention_duration: Duration::ZERO,
                 journal_retention_duration: Duration::ZERO,
                 idempotency_key: None,
@@ -954,6 +1001,7 @@ mod test_util {
                 timestamps: StatusTimestamps::mock(),
                 source: Source::Ingress(PartitionProcessorRpcRequestId::default()),
                 execution_time: None,
+                deadline: None,
                 completion_retention_duration: Duration::ZERO,
                 journal_retention_duration: Duration::ZERO,
                 idempotency_key: None,
@@ -961,6 +1009,7 @@ mod test_util {
                 current_invocation_epoch: 0,
                 completion_range_epoch_map: Default::default(),
                 random_seed: None,
+                transient_failures: 0,
             }
         }
     }
@@ -982,6 +1031,7 @@ mod test_util {
                 created_using_restate_version: RestateVersion::current(),
                 source: Source::Ingress(PartitionProcessorRpcRequestId::default()),
                 execution_time: None,
+                deadline: None,
                 idempotency_key: None,
                 timestamps,
                 response_result: ResponseResult::Success(Bytes::from_static(b"123")),
@@ -1004,6 +1054,7 @@ mod test_util {
                 created_using_restate_version: RestateVersion::current(),
                 source: Source::Ingress(PartitionProcessorRpcRequestId::default()),
                 execution_time: None,
+                deadline: None,
                 idempotency_key: None,
                 timestamps: StatusTimestamps::mock(),
                 response_result: ResponseResult::Success(Bytes::from_static(b"123")),
@@ -76,6 +76,16 @@ pub trait ScanJournalTable {
         range: RangeInclusive<PartitionKey>,
         f: F,
     ) -> Result<impl Future<Output = Result<()>> + Send>;
+
+    /// Like [`ScanJournalTable::for_each_journal`], but only visiting the journals of the given
+    /// invocations, using prefix scans.
+    fn for_each_journal_of<
+        F: FnMut((JournalEntryId, JournalEntry)) -> std::ops::ControlFlow<()> + Send + Sync + 'static,
+    >(
+        &self,
+        invocation_ids: Vec<InvocationId>,
+        f: F,
+    ) -> Result<impl Future<Output = Result<()>> + Send>;
 }
 
 pub trait WriteJournalTable {
@@ -29,10 +29,13 @@ pub enum StorageError {
 
 pub type Result<T> = std::result::Result<T, StorageError>;
 
+pub mod cdc_table;
 pub mod deduplication_table;
 pub mod fsm_table;
 pub mod idempotency_table;
 pub mod inbox_table;
+pub mod invocation_counts_table;
+pub mod invocation_history_table;
 pub mod invocation_status_table;
 pub mod journal_events;
 pub mod journal_table;
@@ -98,6 +101,8 @@ pub trait Transaction:
     + promise_table::ReadPromiseTable
     + promise_table::WritePromiseTable
     + journal_events::WriteJournalEventsTable
+    + cdc_table::WriteCdcTable
+    + invocation_history_table::WriteInvocationHistoryTable
     + Send
 {
     fn commit(self) -> impl Future<Output = Result<()>> + Send;
@@ -68,6 +68,19 @@ pub trait ReadOutboxTable {
     ) -> impl Future<Output = Result<Option<OutboxMessage>>> + Send;
 }
 
+pub trait ScanOutboxTable {
+    /// Iterates over all messages of the partition's outbox in sequence number order.
+    ///
+    /// The outbox is keyed by partition id rather than by partition key, since its messages
+    /// are addressed to other partitions. Hence, it can only be scanned as a whole.
+    fn for_each_outbox<
+        F: FnMut((u64, OutboxMessage)) -> std::ops::ControlFlow<()> + Send + Sync + 'static,
+    >(
+        &self,
+        f: F,
+    ) -> Result<impl Future<Output = Result<()>> + Send>;
+}
+
 pub trait WriteOutboxTable {
     fn put_outbox_message(
         &mut self,
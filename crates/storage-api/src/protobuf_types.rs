@@ -441,6 +441,7 @@ pub mod v1 {
                     argument,
                     headers,
                     execution_time,
+                    deadline,
                     completion_retention_duration,
                     journal_retention_duration,
                     idempotency_key,
@@ -452,6 +453,7 @@ pub mod v1 {
                     current_invocation_epoch,
                     trim_points,
                     random_seed,
+                    transient_failures,
                     waiting_for_completions,
                     waiting_for_signal_indexes,
                     waiting_for_signal_names,
@@ -521,6 +523,7 @@ pub mod v1 {
                                         source,
                                         input,
                                         execution_time: execution_time.map(MillisSinceEpoch::new),
+                                        deadline: deadline.map(MillisSinceEpoch::new),
                                         completion_retention_duration:
                                             completion_retention_duration
                                                 .unwrap_or_default()
@@ -569,6 +572,7 @@ pub mod v1 {
                                         source,
                                         input,
                                         execution_time: execution_time.map(MillisSinceEpoch::new),
+                                        deadline: deadline.map(MillisSinceEpoch::new),
                                         completion_retention_duration:
                                             completion_retention_duration
                                                 .unwrap_or_default()
@@ -600,6 +604,7 @@ pub mod v1 {
                                 )?,
                                 source,
                                 execution_time: execution_time.map(MillisSinceEpoch::new),
+                                deadline: deadline.map(MillisSinceEpoch::new),
                                 completion_retention_duration: completion_retention_duration
                                     .unwrap_or_default()
                                     .try_into()?,
@@ -616,6 +621,7 @@ pub mod v1 {
                                         }),
                                     ),
                                 random_seed,
+                                transient_failures,
                             },
                         ))
                     }
@@ -637,6 +643,7 @@ pub mod v1 {
                                 )?,
                                 source,
                                 execution_time: execution_time.map(MillisSinceEpoch::new),
+                                deadline: deadline.map(MillisSinceEpoch::new),
                                 completion_retention_duration: completion_retention_duration
                                     .unwrap_or_default()
                                     .try_into()?,
@@ -653,6 +660,7 @@ pub mod v1 {
                                         }),
                                     ),
                                 random_seed,
+                                transient_failures,
                             },
                             waiting_for_notifications: waiting_for_completions
                                 .into_iter()
@@ -690,6 +698,7 @@ pub mod v1 {
                                 )?,
                                 source,
                                 execution_time: execution_time.map(MillisSinceEpoch::new),
+                                deadline: deadline.map(MillisSinceEpoch::new),
                                 completion_retention_duration: completion_retention_duration
                                     .unwrap_or_default()
                                     .try_into()?,
@@ -706,6 +715,7 @@ pub mod v1 {
                                         }),
                                     ),
                                 random_seed,
+                                transient_failures,
                             },
                         ))
                     }
@@ -717,6 +727,7 @@ pub mod v1 {
                                 created_using_restate_version,
                                 source,
                                 execution_time: execution_time.map(MillisSinceEpoch::new),
+                                deadline: deadline.map(MillisSinceEpoch::new),
                                 idempotency_key: idempotency_key.map(ByteString::from),
                                 response_result: expect_or_fail!(result)?.try_into()?,
                                 completion_retention_duration: completion_retention_duration
@@ -758,6 +769,7 @@ pub mod v1 {
                                     created_using_restate_version,
                                     source,
                                     execution_time,
+                                    deadline,
                                     completion_retention_duration,
                                     journal_retention_duration,
                                     idempotency_key,
@@ -797,6 +809,7 @@ pub mod v1 {
                         argument: Some(argument),
                         headers: headers.into_iter().map(Into::into).collect(),
                         execution_time: execution_time.map(|t| t.as_u64()),
+                        deadline: deadline.map(|t| t.as_u64()),
                         completion_retention_duration: Some(completion_retention_duration.into()),
                         journal_retention_duration: Some(journal_retention_duration.into()),
                         idempotency_key: idempotency_key.map(|key| key.to_string()),
@@ -813,6 +826,7 @@ pub mod v1 {
                         waiting_for_signal_names: vec![],
                         result: None,
                         random_seed,
+                        transient_failures: 0,
                     },
                     crate::invocation_status_table::InvocationStatus::Scheduled(
                         crate::invocation_status_table::ScheduledInvocation {
@@ -824,6 +838,7 @@ pub mod v1 {
                                     created_using_restate_version,
                                     source,
                                     execution_time,
+                                    deadline,
                                     completion_retention_duration,
                                     journal_retention_duration,
                                     idempotency_key,
@@ -874,6 +889,7 @@ pub mod v1 {
                             argument: None,
                             headers: Default::default(),
                             execution_time: execution_time.map(|t| t.as_u64()),
+                            deadline: deadline.map(|t| t.as_u64()),
                             completion_retention_duration: Some(
                                 completion_retention_duration.into(),
                             ),
@@ -892,6 +908,7 @@ pub mod v1 {
                             waiting_for_signal_names: vec![],
                             result: None,
                             random_seed,
+                            transient_failures: 0,
                         }
                     }
                     crate::invocation_status_table::InvocationStatus::Inboxed(
@@ -904,6 +921,7 @@ pub mod v1 {
                                     created_using_restate_version,
                                     source,
                                     execution_time,
+                                    deadline,
                                     completion_retention_duration,
                                     journal_retention_duration,
                                     idempotency_key,
@@ -944,6 +962,7 @@ pub mod v1 {
                         argument: Some(argument),
                         headers: headers.into_iter().map(Into::into).collect(),
                         execution_time: execution_time.map(|t| t.as_u64()),
+                        deadline: deadline.map(|t| t.as_u64()),
                         completion_retention_duration: Some(completion_retention_duration.into()),
                         journal_retention_duration: Some(journal_retention_duration.into()),
                         idempotency_key: idempotency_key.map(|key| key.to_string()),
@@ -960,6 +979,7 @@ pub mod v1 {
                         waiting_for_signal_names: vec![],
                         result: None,
                         random_seed,
+                        transient_failures: 0,
                     },
                     crate::invocation_status_table::InvocationStatus::Inboxed(
                         crate::invocation_status_table::InboxedInvocation {
@@ -971,6 +991,7 @@ pub mod v1 {
                                     created_using_restate_version,
                                     source,
                                     execution_time,
+                                    deadline,
                                     completion_retention_duration,
                                     journal_retention_duration,
                                     idempotency_key,
@@ -1021,6 +1042,7 @@ pub mod v1 {
                             argument: None,
                             headers: Default::default(),
                             execution_time: execution_time.map(|t| t.as_u64()),
+                            deadline: deadline.map(|t| t.as_u64()),
                             completion_retention_duration: Some(
                                 completion_retention_duration.into(),
                             ),
@@ -1039,6 +1061,7 @@ pub mod v1 {
                             waiting_for_signal_names: vec![],
                             result: None,
                             random_seed,
+                            transient_failures: 0,
                         }
                     }
                     crate::invocation_status_table::InvocationStatus::Invoked(
@@ -1051,6 +1074,7 @@ pub mod v1 {
                             timestamps,
                             source,
                             execution_time,
+                            deadline,
                             completion_retention_duration,
                             journal_retention_duration,
                             idempotency_key,
@@ -1058,6 +1082,7 @@ pub mod v1 {
                             current_invocation_epoch,
                             completion_range_epoch_map,
                             random_seed,
+                            transient_failures,
                         },
                     ) => {
                         let (deployment_id, service_protocol_version) = match pinned_deployment {
@@ -1096,6 +1121,7 @@ pub mod v1 {
                             argument: None,
                             headers: vec![],
                             execution_time: execution_time.map(|t| t.as_u64()),
+                            deadline: deadline.map(|t| t.as_u64()),
                             completion_retention_duration: Some(
                                 completion_retention_duration.into(),
                             ),
@@ -1120,6 +1146,7 @@ pub mod v1 {
                                 })
                                 .collect(),
                             random_seed,
+                            transient_failures,
                         }
                     }
                     crate::invocation_status_table::InvocationStatus::Suspended {
@@ -1133,6 +1160,7 @@ pub mod v1 {
                                 timestamps,
                                 source,
                                 execution_time,
+                                deadline,
                                 completion_retention_duration,
                                 journal_retention_duration,
                                 idempotency_key,
@@ -1140,6 +1168,7 @@ pub mod v1 {
                                 current_invocation_epoch,
                                 completion_range_epoch_map,
                                 random_seed,
+                                transient_failures,
                             },
                         waiting_for_notifications,
                     } => {
@@ -1196,6 +1225,7 @@ pub mod v1 {
                             argument: None,
                             headers: vec![],
                             execution_time: execution_time.map(|t| t.as_u64()),
+                            deadline: deadline.map(|t| t.as_u64()),
                             completion_retention_duration: Some(
                                 completion_retention_duration.into(),
                             ),
@@ -1220,6 +1250,7 @@ pub mod v1 {
                                 })
                                 .collect(),
                             random_seed,
+                            transient_failures,
                         }
                     }
                     crate::invocation_status_table::InvocationStatus::Paused(
@@ -1232,6 +1263,7 @@ pub mod v1 {
                             timestamps,
                             source,
                             execution_time,
+                            deadline,
                             completion_retention_duration,
                             journal_retention_duration,
                             idempotency_key,
@@ -1239,6 +1271,7 @@ pub mod v1 {
                             current_invocation_epoch,
                             completion_range_epoch_map,
                             random_seed,
+                            transient_failures,
                         },
                     ) => {
                         let (deployment_id, service_protocol_version) = match pinned_deployment {
@@ -1277,6 +1310,7 @@ pub mod v1 {
                             argument: None,
                             headers: vec![],
                             execution_time: execution_time.map(|t| t.as_u64()),
+                            deadline: deadline.map(|t| t.as_u64()),
                             completion_retention_duration: Some(
                                 completion_retention_duration.into(),
                             ),
@@ -1301,6 +1335,7 @@ pub mod v1 {
                                 })
                                 .collect(),
                             random_seed,
+                            transient_failures,
                         }
                     }
                     crate::invocation_status_table::InvocationStatus::Completed(
@@ -1309,6 +1344,7 @@ pub mod v1 {
                             created_using_restate_version,
                             source,
                             execution_time,
+                            deadline,
                             idempotency_key,
                             timestamps,
                             response_result,
@@ -1352,6 +1388,7 @@ pub mod v1 {
                             argument: None,
                             headers: vec![],
                             execution_time: execution_time.map(|t| t.as_u64()),
+                            deadline: deadline.map(|t| t.as_u64()),
                             completion_retention_duration: Some(
                                 completion_retention_duration.into(),
                             ),
@@ -1370,6 +1407,7 @@ pub mod v1 {
                             waiting_for_signal_names: vec![],
                             result: Some(response_result.into()),
                             random_seed,
+                            transient_failures: 0,
                         }
                     }
                     crate::invocation_status_table::InvocationStatus::Free => {
@@ -1632,6 +1670,8 @@ pub mod v1 {
                     current_invocation_epoch: 0,
                     completion_range_epoch_map: Default::default(),
                     random_seed: None,
+                    deadline: None,
+                    transient_failures: 0,
                 })
             }
         }
@@ -1750,6 +1790,8 @@ pub mod v1 {
                         current_invocation_epoch: 0,
                         completion_range_epoch_map: Default::default(),
                         random_seed: None,
+                        deadline: None,
+                        transient_failures: 0,
                     },
                     waiting_for_completed_entries,
                 ))
@@ -1874,6 +1916,7 @@ pub mod v1 {
                         invocation_target,
                         journal_retention_duration: Default::default(),
                         random_seed: None,
+                        deadline: None,
                         input: PreFlightInvocationArgument::Input(PreFlightInvocationInput {
                             span_context,
                             headers,
@@ -1900,6 +1943,7 @@ pub mod v1 {
                             journal_retention_duration: _,
                             idempotency_key,
                             random_seed: _,
+                            deadline: _,
                         },
                     inbox_sequence_number,
                 } = value;
@@ -1973,6 +2017,7 @@ pub mod v1 {
                     // Look at crates/worker/src/partition/cleaner.rs for more details.
                     completion_retention_duration: std::time::Duration::MAX,
                     execution_time: None,
+                    deadline: None,
                     journal_retention_duration: Default::default(),
                     journal_metadata: JournalMetadata::empty(),
                     pinned_deployment: None,
@@ -1988,6 +2033,7 @@ pub mod v1 {
                     created_using_restate_version: _,
                     source,
                     execution_time: _,
+                    deadline: _,
                     idempotency_key,
                     timestamps,
                     response_result,
@@ -2228,6 +2274,7 @@ pub mod v1 {
                     source,
                     headers,
                     execution_time,
+                    deadline,
                     idempotency_key,
                     completion_retention_duration,
                     journal_retention_duration,
@@ -2292,6 +2339,7 @@ pub mod v1 {
                     span_context,
                     headers,
                     execution_time,
+                    deadline: deadline.map(MillisSinceEpoch::new),
                     completion_retention_duration,
                     journal_retention_duration,
                     idempotency_key,
@@ -2318,6 +2366,7 @@ pub mod v1 {
                     source: Some(source),
                     headers,
                     execution_time: value.execution_time.map(|m| m.as_u64()).unwrap_or_default(),
+                    deadline: value.deadline.map(|m| m.as_u64()),
                     completion_retention_duration: Some(value.completion_retention_duration.into()),
                     journal_retention_duration: Some(value.journal_retention_duration.into()),
                     idempotency_key: value.idempotency_key.map(|s| s.to_string()),
@@ -2344,6 +2393,7 @@ pub mod v1 {
                     source: Some(source),
                     headers,
                     execution_time: value.execution_time.map(|m| m.as_u64()).unwrap_or_default(),
+                    deadline: value.deadline.map(|m| m.as_u64()),
                     completion_retention_duration: Some(value.completion_retention_duration.into()),
                     journal_retention_duration: Some(value.journal_retention_duration.into()),
                     idempotency_key: value.idempotency_key.map(|s| s.to_string()),
@@ -2371,6 +2421,7 @@ pub mod v1 {
                     source: Some(source),
                     headers,
                     execution_time: value.execution_time.map(|m| m.as_u64()).unwrap_or_default(),
+                    deadline: value.deadline.map(|m| m.as_u64()),
                     completion_retention_duration: Some(value.completion_retention_duration.into()),
                     journal_retention_duration: Some(value.journal_retention_duration.into()),
                     idempotency_key: value.idempotency_key.as_ref().map(|s| s.to_string()),
@@ -3463,6 +3514,7 @@ pub mod v1 {
                     invocation_target,
                     completion_retention_duration,
                     journal_retention_duration,
+                    deadline: value.deadline.map(MillisSinceEpoch::new),
                 })
             }
         }
@@ -3479,6 +3531,7 @@ pub mod v1 {
                     journal_retention_duration: Some(Duration::from(
                         value.journal_retention_duration,
                     )),
+                    deadline: value.deadline.map(|d| d.as_u64()),
                 }
             }
         }
@@ -4273,6 +4326,15 @@ pub mod v1 {
                                 )?,
                             )
                         }
+                        timer::Value::CancelInvocation(cancel_invocation) => {
+                            crate::timer_table::Timer::CancelInvocation(
+                                restate_types::identifiers::InvocationId::try_from(
+                                    cancel_invocation
+                                        .invocation_id
+                                        .ok_or(ConversionError::missing_field("invocation_id"))?,
+                                )?,
+                            )
+                        }
                     },
                 )
             }
@@ -4302,6 +4364,11 @@ pub mod v1 {
                                 invocation_id: Some(InvocationId::from(invocation_id)),
                             })
                         }
+                        crate::timer_table::Timer::CancelInvocation(invocation_id) => {
+                            timer::Value::CancelInvocation(timer::CancelInvocation {
+                                invocation_id: Some(InvocationId::from(invocation_id)),
+                            })
+                        }
                     }),
                 }
             }
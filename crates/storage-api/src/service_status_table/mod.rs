@@ -45,6 +45,19 @@ pub trait ScanVirtualObjectStatusTable {
         range: RangeInclusive<PartitionKey>,
         f: F,
     ) -> Result<impl Future<Output = Result<()>> + Send>;
+
+    /// Like [`ScanVirtualObjectStatusTable::for_each_virtual_object_status`], but only visiting
+    /// the given services, using point lookups.
+    fn for_each_virtual_object_status_of<
+        F: FnMut((ServiceId, VirtualObjectStatus)) -> std::ops::ControlFlow<()>
+            + Send
+            + Sync
+            + 'static,
+    >(
+        &self,
+        service_ids: Vec<ServiceId>,
+        f: F,
+    ) -> Result<impl Future<Output = Result<()>> + Send>;
 }
 
 pub trait WriteVirtualObjectStatusTable {
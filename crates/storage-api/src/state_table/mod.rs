@@ -38,6 +38,16 @@ pub trait ScanStateTable {
         range: RangeInclusive<PartitionKey>,
         f: F,
     ) -> Result<impl Future<Output = Result<()>> + Send>;
+
+    /// Like [`ScanStateTable::for_each_user_state`], but only visiting the state of the given
+    /// services, using prefix scans.
+    fn for_each_user_state_of<
+        F: FnMut((ServiceId, Bytes, &[u8])) -> std::ops::ControlFlow<()> + Send + Sync + 'static,
+    >(
+        &self,
+        service_ids: Vec<ServiceId>,
+        f: F,
+    ) -> Result<impl Future<Output = Result<()>> + Send>;
 }
 
 pub trait WriteStateTable {
@@ -9,6 +9,7 @@
 // by the Apache License, Version 2.0.
 
 use std::cmp::Ordering;
+use std::ops::RangeInclusive;
 
 use futures::Stream;
 
@@ -65,6 +66,13 @@ impl TimerKey {
             kind: TimerKeyKind::CleanInvocationStatus { invocation_uuid },
         }
     }
+
+    pub fn cancel_invocation(timestamp: u64, invocation_uuid: InvocationUuid) -> Self {
+        TimerKey {
+            timestamp,
+            kind: TimerKeyKind::CancelInvocation { invocation_uuid },
+        }
+    }
 }
 
 impl PartialOrd for TimerKey {
@@ -104,6 +112,8 @@ pub enum TimerKeyKind {
     },
     /// Cleaning of invocation status
     CleanInvocationStatus { invocation_uuid: InvocationUuid },
+    /// Cancellation of an invocation which exceeded its deadline
+    CancelInvocation { invocation_uuid: InvocationUuid },
 }
 
 impl TimerKeyKind {
@@ -115,6 +125,7 @@ impl TimerKeyKind {
             } => invocation_uuid,
             TimerKeyKind::CleanInvocationStatus { invocation_uuid } => invocation_uuid,
             TimerKeyKind::NeoInvoke { invocation_uuid } => invocation_uuid,
+            TimerKeyKind::CancelInvocation { invocation_uuid } => invocation_uuid,
         }
     }
 }
@@ -134,7 +145,8 @@ impl Ord for TimerKeyKind {
                 } => invocation_uuid.cmp(other_invocation_uuid),
                 TimerKeyKind::CompleteJournalEntry { .. }
                 | TimerKeyKind::CleanInvocationStatus { .. }
-                | TimerKeyKind::NeoInvoke { .. } => Ordering::Less,
+                | TimerKeyKind::NeoInvoke { .. }
+                | TimerKeyKind::CancelInvocation { .. } => Ordering::Less,
             },
             TimerKeyKind::CompleteJournalEntry {
                 invocation_uuid,
@@ -147,9 +159,9 @@ impl Ord for TimerKeyKind {
                 } => invocation_uuid
                     .cmp(other_invocation_uuid)
                     .then_with(|| journal_index.cmp(other_journal_index)),
-                TimerKeyKind::CleanInvocationStatus { .. } | TimerKeyKind::NeoInvoke { .. } => {
-                    Ordering::Less
-                }
+                TimerKeyKind::CleanInvocationStatus { .. }
+                | TimerKeyKind::NeoInvoke { .. }
+                | TimerKeyKind::CancelInvocation { .. } => Ordering::Less,
             },
             TimerKeyKind::CleanInvocationStatus { invocation_uuid } => match other {
                 TimerKeyKind::Invoke { .. } | TimerKeyKind::CompleteJournalEntry { .. } => {
@@ -158,7 +170,9 @@ impl Ord for TimerKeyKind {
                 TimerKeyKind::CleanInvocationStatus {
                     invocation_uuid: other_invocation_uuid,
                 } => invocation_uuid.cmp(other_invocation_uuid),
-                TimerKeyKind::NeoInvoke { .. } => Ordering::Less,
+                TimerKeyKind::NeoInvoke { .. } | TimerKeyKind::CancelInvocation { .. } => {
+                    Ordering::Less
+                }
             },
             TimerKeyKind::NeoInvoke { invocation_uuid } => match other {
                 TimerKeyKind::Invoke { .. }
@@ -167,6 +181,16 @@ impl Ord for TimerKeyKind {
                 TimerKeyKind::NeoInvoke {
                     invocation_uuid: other_invocation_uuid,
                 } => invocation_uuid.cmp(other_invocation_uuid),
+                TimerKeyKind::CancelInvocation { .. } => Ordering::Less,
+            },
+            TimerKeyKind::CancelInvocation { invocation_uuid } => match other {
+                TimerKeyKind::Invoke { .. }
+                | TimerKeyKind::CompleteJournalEntry { .. }
+                | TimerKeyKind::CleanInvocationStatus { .. }
+                | TimerKeyKind::NeoInvoke { .. } => Ordering::Greater,
+                TimerKeyKind::CancelInvocation {
+                    invocation_uuid: other_invocation_uuid,
+                } => invocation_uuid.cmp(other_invocation_uuid),
             },
         }
     }
@@ -190,6 +214,8 @@ pub enum Timer {
     // TODO remove this variant when removing the old invocation status table
     CleanInvocationStatus(InvocationId),
     NeoInvoke(InvocationId),
+    /// Cancel the invocation because its deadline was exceeded
+    CancelInvocation(InvocationId),
 }
 
 impl Timer {
@@ -236,12 +262,20 @@ impl Timer {
         )
     }
 
+    pub fn cancel_invocation(timestamp: u64, invocation_id: InvocationId) -> (TimerKey, Self) {
+        (
+            TimerKey::cancel_invocation(timestamp, invocation_id.invocation_uuid()),
+            Timer::CancelInvocation(invocation_id),
+        )
+    }
+
     pub fn invocation_id(&self) -> InvocationId {
         match self {
             Timer::Invoke(service_invocation) => service_invocation.invocation_id,
             Timer::CompleteJournalEntry(invocation_id, _, _) => *invocation_id,
             Timer::CleanInvocationStatus(invocation_id) => *invocation_id,
             Timer::NeoInvoke(invocation_id) => *invocation_id,
+            Timer::CancelInvocation(invocation_id) => *invocation_id,
         }
     }
 }
@@ -257,6 +291,7 @@ impl WithPartitionKey for Timer {
             Timer::Invoke(service_invocation) => service_invocation.partition_key(),
             Timer::CleanInvocationStatus(invocation_id) => invocation_id.partition_key(),
             Timer::NeoInvoke(invocation_id) => invocation_id.partition_key(),
+            Timer::CancelInvocation(invocation_id) => invocation_id.partition_key(),
         }
     }
 }
@@ -269,6 +304,18 @@ pub trait ReadTimerTable {
     ) -> Result<impl Stream<Item = Result<(TimerKey, Timer)>> + Send>;
 }
 
+pub trait ScanTimerTable {
+    /// Iterates over all timers of the partition whose invocation falls into the given
+    /// partition key range. Timers are visited in fire time order.
+    fn for_each_timer<
+        F: FnMut((TimerKey, Timer)) -> std::ops::ControlFlow<()> + Send + Sync + 'static,
+    >(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+        f: F,
+    ) -> Result<impl Future<Output = Result<()>> + Send>;
+}
+
 pub trait WriteTimerTable {
     fn put_timer(&mut self, timer_key: &TimerKey, timer: &Timer) -> Result<()>;
 
@@ -8,21 +8,25 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use std::collections::HashMap;
 use std::fmt::Debug;
+use std::num::NonZeroUsize;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use tokio::sync::watch;
+use tokio::time::Instant;
 use tracing::warn;
 
+use datafusion::arrow::datatypes::SchemaRef;
 use datafusion::catalog::TableProvider;
 use datafusion::error::DataFusionError;
 use datafusion::execution::SessionStateBuilder;
 use datafusion::execution::context::SQLOptions;
-use datafusion::execution::runtime_env::RuntimeEnvBuilder;
-use datafusion::physical_plan::SendableRecordBatchStream;
-use datafusion::prelude::{SessionConfig, SessionContext};
+use datafusion::execution::runtime_env::{RuntimeEnv, RuntimeEnvBuilder};
+use datafusion::logical_expr::LogicalPlan;
+use datafusion::physical_plan::{SendableRecordBatchStream, execute_stream};
+use datafusion::prelude::{DataFrame, SessionConfig, SessionContext};
 use datafusion::sql::TableReference;
 
 use codederror::CodedError;
@@ -40,6 +44,9 @@ use restate_types::schema::deployment::DeploymentResolver;
 use restate_types::schema::service::ServiceMetadataResolver;
 
 use crate::analyzer;
+use crate::query_limits::{
+    GovernedRecordBatchStream, QueryAdmission, QueryMemoryPool, timeout_error,
+};
 use crate::remote_query_scanner_manager::RemoteScannerManager;
 
 const SYS_INVOCATION_VIEW: &str = "CREATE VIEW sys_invocation as SELECT
@@ -71,6 +78,7 @@ const SYS_INVOCATION_VIEW: &str = "CREATE VIEW sys_invocation as SELECT
             ss.completed_at,
             ss.completion_retention,
             ss.journal_retention,
+            ss.deadline,
 
             sis.retry_count,
             sis.last_start_at,
@@ -160,7 +168,7 @@ where
 {
     async fn register(&self, ctx: &QueryContext) -> Result<(), BuildError> {
         // ----- non partitioned tables -----
-        crate::deployment::register_self(ctx, self.schemas.clone())?;
+        crate::deployment::register_self(ctx, self.schemas.clone(), self.status.clone())?;
         crate::service::register_self(ctx, self.schemas.clone())?;
         // ----- partition-key-based -----
         crate::invocation_state::register_self(
@@ -206,6 +214,18 @@ where
             self.partition_store_manager.clone(),
             &self.remote_scanner_manager,
         )?;
+        crate::timer::register_self(
+            ctx,
+            self.partition_selector.clone(),
+            self.partition_store_manager.clone(),
+            &self.remote_scanner_manager,
+        )?;
+        crate::outbox::register_self(
+            ctx,
+            self.partition_selector.clone(),
+            self.partition_store_manager.clone(),
+            &self.remote_scanner_manager,
+        )?;
         crate::idempotency::register_self(
             ctx,
             self.partition_selector.clone(),
@@ -218,6 +238,18 @@ where
             self.partition_store_manager.clone(),
             &self.remote_scanner_manager,
         )?;
+        crate::invocation_history::register_self(
+            ctx,
+            self.partition_selector.clone(),
+            self.partition_store_manager.clone(),
+            &self.remote_scanner_manager,
+        )?;
+        crate::invocation_counts::register_self(
+            ctx,
+            self.partition_selector.clone(),
+            self.partition_store_manager.clone(),
+            &self.remote_scanner_manager,
+        )?;
 
         ctx.datafusion_context.sql(SYS_INVOCATION_VIEW).await?;
 
@@ -256,6 +288,7 @@ impl RegisterTable for ClusterTables {
             self.cluster_state.clone(),
             self.replica_set_states.clone(),
         )?;
+        crate::log_storage::register_self(ctx, metadata.clone(), self.cluster_state.clone())?;
         crate::log::register_self(ctx, metadata)?;
         crate::partition_state::register_self(ctx, self.cluster_state_watch.clone())?;
 
@@ -271,6 +304,9 @@ impl RegisterTable for ClusterTables {
 pub struct QueryContext {
     sql_options: SQLOptions,
     datafusion_context: SessionContext,
+    admission: Arc<QueryAdmission>,
+    query_memory_limit: Option<NonZeroUsize>,
+    query_timeout: Option<Duration>,
 }
 
 impl QueryContext {
@@ -278,12 +314,7 @@ impl QueryContext {
         options: &QueryEngineOptions,
         registerer: T,
     ) -> Result<Self, BuildError> {
-        let ctx = QueryContext::new(
-            options.memory_size.get(),
-            options.tmp_dir.clone(),
-            options.query_parallelism(),
-            &options.datafusion_options,
-        )?;
+        let ctx = QueryContext::new(options)?;
 
         registerer.register(&ctx).await?;
 
@@ -333,19 +364,14 @@ impl QueryContext {
             .map(|_| ())
     }
 
-    fn new(
-        memory_limit: usize,
-        temp_folder: Option<String>,
-        default_parallelism: Option<usize>,
-        datafusion_options: &HashMap<String, String>,
-    ) -> Result<Self, DataFusionError> {
+    fn new(options: &QueryEngineOptions) -> Result<Self, DataFusionError> {
         //
         // build the runtime
         //
         let mut runtime_config = RuntimeEnvBuilder::default();
-        runtime_config = runtime_config.with_memory_limit(memory_limit, 1.0);
+        runtime_config = runtime_config.with_memory_limit(options.memory_size.get(), 1.0);
 
-        if let Some(folder) = temp_folder {
+        if let Some(folder) = &options.tmp_dir {
             runtime_config = runtime_config.with_temp_file_path(folder);
         }
         let runtime = runtime_config.build_arc().expect("runtime");
@@ -353,7 +379,7 @@ impl QueryContext {
         // build the session
         //
         let mut session_config = SessionConfig::new();
-        if let Some(target_partitions) = default_parallelism {
+        if let Some(target_partitions) = options.query_parallelism() {
             session_config = session_config.with_target_partitions(target_partitions);
         }
 
@@ -362,7 +388,7 @@ impl QueryContext {
             .with_information_schema(true)
             .with_default_catalog_and_schema("restate", "public");
 
-        for (k, v) in datafusion_options {
+        for (k, v) in &options.datafusion_options {
             session_config.options_mut().set(k, v)?;
         }
 
@@ -398,6 +424,7 @@ impl QueryContext {
                 warn!("Unable to register json functions {}", err);
             }
         };
+        ctx.register_udf(crate::journal::decoding::decode_journal_entry_udf());
 
         let sql_options = SQLOptions::new()
             .with_allow_ddl(false)
@@ -406,19 +433,99 @@ impl QueryContext {
         Ok(Self {
             sql_options,
             datafusion_context: ctx,
+            admission: Arc::new(QueryAdmission::new(
+                options.max_concurrent_queries,
+                options.max_queued_queries,
+            )),
+            query_memory_limit: options.query_memory_limit,
+            query_timeout: options.query_timeout.map(|timeout| timeout.to_std()),
         })
     }
 
+    /// Executes the query once admitted, within the per query memory and time limits.
     pub async fn execute(
         &self,
         sql: &str,
     ) -> datafusion::common::Result<SendableRecordBatchStream> {
+        self.governed(async {
+            let plan = self.plan(sql).await?;
+            self.start(plan).await
+        })
+        .await
+    }
+
+    /// Returns the registered table as a [`DataFrame`], to build a query without going through
+    /// SQL. The resulting plan can be executed with [`Self::execute_plan`].
+    pub async fn table(&self, name: &str) -> datafusion::common::Result<DataFrame> {
+        self.datafusion_context.table(name).await
+    }
+
+    /// Executes the plan with the same restrictions, admission and limits of [`Self::execute`].
+    pub async fn execute_plan(
+        &self,
+        plan: LogicalPlan,
+    ) -> datafusion::common::Result<SendableRecordBatchStream> {
+        self.sql_options.verify_plan(&plan)?;
+        self.governed(self.start(plan)).await
+    }
+
+    async fn governed(
+        &self,
+        start: impl Future<Output = datafusion::common::Result<SendableRecordBatchStream>>,
+    ) -> datafusion::common::Result<SendableRecordBatchStream> {
+        let permit = self.admission.admit().await?;
+        let deadline = self
+            .query_timeout
+            .map(|timeout| (Instant::now() + timeout, timeout));
+
+        let stream = match deadline {
+            Some((deadline, timeout)) => tokio::time::timeout_at(deadline, start)
+                .await
+                .map_err(|_| timeout_error(timeout))??,
+            None => start.await?,
+        };
+
+        Ok(Box::pin(GovernedRecordBatchStream::new(
+            stream, permit, deadline,
+        )))
+    }
+
+    async fn start(
+        &self,
+        plan: LogicalPlan,
+    ) -> datafusion::common::Result<SendableRecordBatchStream> {
+        let df = self.datafusion_context.execute_logical_plan(plan).await?;
+        let mut task_ctx = df.task_ctx();
+        let physical_plan = df.create_physical_plan().await?;
+
+        if let Some(memory_limit) = self.query_memory_limit {
+            let runtime = self.datafusion_context.runtime_env();
+            task_ctx = task_ctx.with_runtime(Arc::new(RuntimeEnv {
+                memory_pool: Arc::new(QueryMemoryPool::new(
+                    Arc::clone(&runtime.memory_pool),
+                    memory_limit,
+                )),
+                disk_manager: Arc::clone(&runtime.disk_manager),
+                cache_manager: Arc::clone(&runtime.cache_manager),
+                object_store_registry: Arc::clone(&runtime.object_store_registry),
+            }));
+        }
+
+        execute_stream(physical_plan, Arc::new(task_ctx))
+    }
+
+    /// Plans the query without executing it, returning the schema of its results.
+    pub async fn result_schema(&self, sql: &str) -> datafusion::common::Result<SchemaRef> {
+        let plan = self.plan(sql).await?;
+        Ok(Arc::new(plan.schema().as_arrow().clone()))
+    }
+
+    async fn plan(&self, sql: &str) -> datafusion::common::Result<LogicalPlan> {
         let state = self.datafusion_context.state();
         let statement = state.sql_to_statement(sql, "postgres")?;
         let plan = state.statement_to_plan(statement).await?;
         self.sql_options.verify_plan(&plan)?;
-        let df = self.datafusion_context.execute_logical_plan(plan).await?;
-        df.execute_stream().await
+        Ok(plan)
     }
 }
 
@@ -9,10 +9,18 @@
 // by the Apache License, Version 2.0.
 
 use super::schema::SysDeploymentBuilder;
+use restate_invoker_api::{CircuitBreakerReport, CircuitBreakerState};
+use restate_types::identifiers::DeploymentId;
 use restate_types::schema::deployment::{Deployment, DeploymentType};
+use restate_types::time::MillisSinceEpoch;
+use std::collections::HashMap;
 
 #[inline]
-pub(crate) fn append_deployment_row(builder: &mut SysDeploymentBuilder, deployment: Deployment) {
+pub(crate) fn append_deployment_row(
+    builder: &mut SysDeploymentBuilder,
+    deployment: Deployment,
+    circuit_breakers: Option<&HashMap<DeploymentId, CircuitBreakerReport>>,
+) {
     let mut row = builder.row();
     row.fmt_id(deployment.id);
 
@@ -23,6 +31,9 @@ pub(crate) fn append_deployment_row(builder: &mut SysDeploymentBuilder, deployme
         DeploymentType::Lambda { .. } => {
             row.ty("lambda");
         }
+        DeploymentType::Wasm { .. } => {
+            row.ty("wasm");
+        }
     }
 
     row.fmt_endpoint(deployment.address_display());
@@ -34,4 +45,20 @@ pub(crate) fn append_deployment_row(builder: &mut SysDeploymentBuilder, deployme
             .unsigned_abs(),
     );
     row.max_service_protocol_version(deployment.supported_protocol_versions.end().unsigned_abs());
+
+    if let Some(circuit_breakers) = circuit_breakers {
+        match circuit_breakers.get(&deployment.id) {
+            Some(report) => {
+                row.circuit_breaker_state(report.state.as_str());
+                row.circuit_breaker_consecutive_failures(report.consecutive_failures);
+                row.circuit_breaker_last_transition_at(
+                    MillisSinceEpoch::from(report.last_transition_at).as_u64() as i64,
+                );
+            }
+            None => {
+                row.circuit_breaker_state(CircuitBreakerState::Closed.as_str());
+                row.circuit_breaker_consecutive_failures(0);
+            }
+        }
+    }
 }
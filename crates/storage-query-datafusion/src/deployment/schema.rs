@@ -16,10 +16,10 @@ define_table!(sys_deployment(
     /// The ID of the service deployment.
     id: DataType::LargeUtf8,
 
-    /// The type of the endpoint. Either `http` or `lambda`.
+    /// The type of the endpoint. Either `http`, `lambda` or `wasm`.
     ty: DataType::LargeUtf8,
 
-    /// The address of the endpoint. Either HTTP URL or Lambda ARN.
+    /// The address of the endpoint. Either HTTP URL, Lambda ARN or WebAssembly module path.
     endpoint: DataType::LargeUtf8,
 
     /// Timestamp indicating the deployment registration time.
@@ -29,5 +29,16 @@ define_table!(sys_deployment(
     min_service_protocol_version: DataType::UInt32,
 
     /// Maximum supported protocol version.
-    max_service_protocol_version: DataType::UInt32
+    max_service_protocol_version: DataType::UInt32,
+
+    /// State of the deployment circuit breaker in the invokers of the node running the query.
+    /// Either `closed`, `open` or `half_open`. Empty if the node doesn't run invokers.
+    circuit_breaker_state: DataType::LargeUtf8,
+
+    /// Number of consecutive attempts that failed because the deployment was unavailable.
+    circuit_breaker_consecutive_failures: DataType::UInt32,
+
+    /// Timestamp of the last circuit breaker state transition. Empty if the circuit breaker
+    /// never tripped.
+    circuit_breaker_last_transition_at: TimestampMillisecond
 ));
@@ -8,6 +8,8 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::collections::HashMap;
+use std::fmt::Debug;
 use std::sync::Arc;
 
 use datafusion::arrow::datatypes::SchemaRef;
@@ -18,7 +20,8 @@ use datafusion::physical_plan::stream::RecordBatchReceiverStream;
 use restate_types::live::Live;
 use tokio::sync::mpsc::Sender;
 
-use restate_types::identifiers::ServiceRevision;
+use restate_invoker_api::{CircuitBreakerReport, StatusHandle};
+use restate_types::identifiers::{DeploymentId, ServiceRevision};
 use restate_types::schema::deployment::{Deployment, DeploymentResolver};
 
 use super::schema::SysDeploymentBuilder;
@@ -31,6 +34,7 @@ use crate::table_util::Builder;
 pub(crate) fn register_self(
     ctx: &QueryContext,
     resolver: Live<impl DeploymentResolver + Send + Sync + 'static>,
+    status: Option<impl StatusHandle + Send + Sync + Debug + Clone + 'static>,
 ) -> datafusion::common::Result<()> {
     let schema = SysDeploymentBuilder::schema();
     let statistics = TableStatisticsBuilder::new(schema.clone())
@@ -38,7 +42,7 @@ pub(crate) fn register_self(
         .with_primary_key("id");
     let deployment_table = GenericTableProvider::new(
         SysDeploymentBuilder::schema(),
-        Arc::new(DeploymentMetadataScanner(resolver)),
+        Arc::new(DeploymentMetadataScanner(resolver, status)),
     )
     .with_statistics(statistics.build());
     ctx.register_non_partitioned_table("sys_deployment", Arc::new(deployment_table))
@@ -46,9 +50,13 @@ pub(crate) fn register_self(
 
 #[derive(Clone, derive_more::Debug)]
 #[debug("DeploymentMetadataScanner")]
-struct DeploymentMetadataScanner<DMR>(Live<DMR>);
+struct DeploymentMetadataScanner<DMR, S>(Live<DMR>, Option<S>);
 
-impl<DMR: DeploymentResolver + Sync + Send + 'static> Scan for DeploymentMetadataScanner<DMR> {
+impl<DMR, S> Scan for DeploymentMetadataScanner<DMR, S>
+where
+    DMR: DeploymentResolver + Sync + Send + 'static,
+    S: StatusHandle + Send + Sync + Debug + Clone + 'static,
+{
     fn scan(
         &self,
         projection: SchemaRef,
@@ -61,8 +69,15 @@ impl<DMR: DeploymentResolver + Sync + Send + 'static> Scan for DeploymentMetadat
         let tx = stream_builder.tx();
 
         let rows = self.0.pinned().get_deployments();
+        let circuit_breakers = self.1.as_ref().map(|status| {
+            status
+                .read_circuit_breakers()
+                .into_iter()
+                .map(|report| (report.deployment_id, report))
+                .collect()
+        });
         stream_builder.spawn(async move {
-            for_each_state(schema, tx, rows, batch_size).await;
+            for_each_state(schema, tx, rows, circuit_breakers, batch_size).await;
             Ok(())
         });
         stream_builder.build()
@@ -73,11 +88,12 @@ async fn for_each_state(
     schema: SchemaRef,
     tx: Sender<datafusion::common::Result<RecordBatch>>,
     rows: Vec<(Deployment, Vec<(String, ServiceRevision)>)>,
+    circuit_breakers: Option<HashMap<DeploymentId, CircuitBreakerReport>>,
     batch_size: usize,
 ) {
     let mut builder = SysDeploymentBuilder::new(schema.clone());
     for (deployment, _) in rows {
-        append_deployment_row(&mut builder, deployment);
+        append_deployment_row(&mut builder, deployment, circuit_breakers.as_ref());
         if builder.num_rows() >= batch_size {
             let batch = builder.finish_and_new();
             if tx.send(batch).await.is_err() {
@@ -8,7 +8,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use restate_invoker_api::{InvocationStatusReport, StatusHandle};
+use restate_invoker_api::{CircuitBreakerReport, InvocationStatusReport, StatusHandle};
 use restate_types::identifiers::PartitionKey;
 use std::future::Future;
 use std::ops::RangeInclusive;
@@ -26,4 +26,8 @@ impl StatusHandle for EmptyInvokerStatusHandle {
     ) -> impl Future<Output = Self::Iterator> + Send {
         future::ready(iter::empty())
     }
+
+    fn read_circuit_breakers(&self) -> Vec<CircuitBreakerReport> {
+        Vec::new()
+    }
 }
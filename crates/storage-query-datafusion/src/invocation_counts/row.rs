@@ -0,0 +1,43 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::schema::SysInvocationCountsBuilder;
+use restate_storage_api::invocation_counts_table::InvocationCountKey;
+use restate_storage_api::invocation_status_table::InvocationStatusDiscriminants;
+use restate_types::identifiers::PartitionId;
+
+#[inline]
+pub(crate) fn append_invocation_counts_row(
+    builder: &mut SysInvocationCountsBuilder,
+    partition_id: PartitionId,
+    key: InvocationCountKey,
+    count: u64,
+) {
+    let mut row = builder.row();
+
+    row.partition_id(u64::from(partition_id));
+    row.service_name(&key.service_name);
+    row.handler_name(&key.handler_name);
+    row.status(match key.status {
+        InvocationStatusDiscriminants::Scheduled => "scheduled",
+        InvocationStatusDiscriminants::Inboxed => "inboxed",
+        InvocationStatusDiscriminants::Invoked => "invoked",
+        InvocationStatusDiscriminants::Suspended => "suspended",
+        InvocationStatusDiscriminants::Paused => "paused",
+        InvocationStatusDiscriminants::Killed => "killed",
+        InvocationStatusDiscriminants::Completed => "completed",
+    });
+    if row.is_pinned_deployment_id_defined()
+        && let Some(deployment_id) = key.deployment_id
+    {
+        row.fmt_pinned_deployment_id(deployment_id);
+    }
+    row.count(count);
+}
@@ -0,0 +1,37 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::table_macro::*;
+
+use datafusion::arrow::datatypes::DataType;
+
+define_table!(sys_invocation_counts(
+    /// The partition whose invocations are counted. The counts of the different partitions
+    /// need to be summed up to get the totals of the cluster.
+    partition_id: DataType::UInt64,
+
+    /// The name of the invoked service.
+    service_name: DataType::LargeUtf8,
+
+    /// The invoked handler.
+    handler_name: DataType::LargeUtf8,
+
+    /// Either `inboxed` or `scheduled` or `invoked` or `suspended` or `paused` or `completed`,
+    /// like the `status` column of `sys_invocation_status`.
+    status: DataType::LargeUtf8,
+
+    /// The ID of the service deployment the counted invocations are pinned to. Null for
+    /// invocations which are not pinned to a deployment yet.
+    pinned_deployment_id: DataType::LargeUtf8,
+
+    /// The number of invocations of the partition with this service, handler, status and
+    /// pinned deployment.
+    count: DataType::UInt64,
+));
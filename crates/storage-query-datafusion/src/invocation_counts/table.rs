@@ -0,0 +1,88 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use restate_partition_store::{PartitionStore, PartitionStoreManager};
+use restate_storage_api::StorageError;
+use restate_storage_api::invocation_counts_table::{InvocationCountKey, ScanInvocationCountsTable};
+use restate_types::identifiers::{PartitionId, PartitionKey};
+
+use crate::context::{QueryContext, SelectPartitions};
+use crate::invocation_counts::row::append_invocation_counts_row;
+use crate::invocation_counts::schema::SysInvocationCountsBuilder;
+use crate::partition_filter::FirstMatchingPartitionKeyExtractor;
+use crate::partition_store_scanner::{LocalPartitionsScanner, ScanLocalPartition};
+use crate::remote_query_scanner_manager::RemoteScannerManager;
+use crate::table_providers::{PartitionedTableProvider, ScanPartition};
+
+const NAME: &str = "sys_invocation_counts";
+
+pub(crate) fn register_self(
+    ctx: &QueryContext,
+    partition_selector: impl SelectPartitions,
+    partition_store_manager: Arc<PartitionStoreManager>,
+    remote_scanner_manager: &RemoteScannerManager,
+) -> datafusion::common::Result<()> {
+    let local_partition_scanner = Arc::new(LocalPartitionsScanner::new(
+        partition_store_manager,
+        InvocationCountsScanner,
+    )) as Arc<dyn ScanPartition>;
+
+    let table = PartitionedTableProvider::new(
+        partition_selector,
+        SysInvocationCountsBuilder::schema(),
+        vec![],
+        remote_scanner_manager.create_distributed_scanner(NAME, local_partition_scanner),
+        // counts are kept per partition, so none of the columns allows to prune the scanned
+        // partitions
+        FirstMatchingPartitionKeyExtractor::default(),
+    );
+    ctx.register_partitioned_table(NAME, Arc::new(table))
+}
+
+#[derive(Debug, Clone)]
+struct InvocationCountsScanner;
+
+impl ScanLocalPartition for InvocationCountsScanner {
+    type Builder = SysInvocationCountsBuilder;
+    type Item<'a> = (PartitionId, InvocationCountKey, u64);
+    type ConversionError = std::convert::Infallible;
+
+    fn for_each_row<
+        F: for<'a> FnMut(
+                Self::Item<'a>,
+            ) -> std::ops::ControlFlow<Result<(), Self::ConversionError>>
+            + Send
+            + Sync
+            + 'static,
+    >(
+        partition_store: &PartitionStore,
+        _range: RangeInclusive<PartitionKey>,
+        mut f: F,
+    ) -> Result<impl Future<Output = restate_storage_api::Result<()>> + Send, StorageError> {
+        // the counts are kept per partition id and always scanned as a whole
+        let partition_id = partition_store.partition_id();
+        partition_store.for_each_invocation_count(move |(key, count)| {
+            f((partition_id, key, count)).map_break(Result::unwrap)
+        })
+    }
+
+    fn append_row<'a>(
+        row_builder: &mut Self::Builder,
+        value: Self::Item<'a>,
+    ) -> Result<(), Self::ConversionError> {
+        let (partition_id, key, count) = value;
+        append_invocation_counts_row(row_builder, partition_id, key, count);
+        Ok(())
+    }
+}
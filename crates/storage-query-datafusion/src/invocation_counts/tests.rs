@@ -0,0 +1,81 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::mocks::*;
+use crate::row;
+use datafusion::arrow::array::{LargeStringArray, UInt64Array};
+use datafusion::arrow::record_batch::RecordBatch;
+use futures::StreamExt;
+use googletest::all;
+use googletest::prelude::{assert_that, eq};
+use restate_storage_api::Transaction;
+use restate_storage_api::invocation_status_table::{
+    CompletedInvocation, InFlightInvocationMetadata, InvocationStatus, WriteInvocationStatusTable,
+};
+use restate_types::identifiers::InvocationId;
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_invocation_counts() {
+    let mut engine = MockQueryEngine::create().await;
+
+    let mut tx = engine.partition_store().transaction();
+    for _ in 0..2 {
+        tx.put_invocation_status(
+            &InvocationId::mock_random(),
+            &InvocationStatus::Invoked(InFlightInvocationMetadata::mock()),
+        )
+        .unwrap();
+    }
+    tx.put_invocation_status(
+        &InvocationId::mock_random(),
+        &InvocationStatus::Completed(CompletedInvocation::mock_neo()),
+    )
+    .unwrap();
+    tx.commit().await.unwrap();
+
+    let records = engine
+        .execute(
+            "SELECT service_name, handler_name, status, SUM(count) AS count
+            FROM sys_invocation_counts
+            GROUP BY service_name, handler_name, status
+            ORDER BY status",
+        )
+        .await
+        .unwrap()
+        .collect::<Vec<Result<RecordBatch, _>>>()
+        .await
+        .remove(0)
+        .unwrap();
+
+    assert_eq!(records.num_rows(), 2);
+    assert_that!(
+        records,
+        all!(
+            row!(
+                0,
+                {
+                    "service_name" => LargeStringArray: eq("MyService"),
+                    "handler_name" => LargeStringArray: eq("mock"),
+                    "status" => LargeStringArray: eq("completed"),
+                    "count" => UInt64Array: eq(1),
+                }
+            ),
+            row!(
+                1,
+                {
+                    "service_name" => LargeStringArray: eq("MyService"),
+                    "handler_name" => LargeStringArray: eq("mock"),
+                    "status" => LargeStringArray: eq("invoked"),
+                    "count" => UInt64Array: eq(2),
+                }
+            )
+        )
+    );
+}
@@ -0,0 +1,80 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+use super::schema::SysInvocationHistoryBuilder;
+use restate_storage_api::invocation_history_table::{InvocationOutcome, InvocationSummary};
+use restate_types::identifiers::WithPartitionKey;
+use restate_types::invocation::ServiceType;
+
+#[inline]
+pub(crate) fn append_invocation_history_row(
+    builder: &mut SysInvocationHistoryBuilder,
+    summary: InvocationSummary,
+) {
+    let mut row = builder.row();
+
+    row.partition_key(summary.invocation_id.partition_key());
+    if row.is_id_defined() {
+        row.fmt_id(summary.invocation_id);
+    }
+
+    let target = &summary.invocation_target;
+    if row.is_target_defined() {
+        row.fmt_target(target);
+    }
+    if row.is_target_service_name_defined() {
+        row.target_service_name(target.service_name());
+    }
+    if row.is_target_service_key_defined()
+        && let Some(key) = target.key()
+    {
+        row.target_service_key(key);
+    }
+    if row.is_target_handler_name_defined() {
+        row.target_handler_name(target.handler_name());
+    }
+    if row.is_target_service_ty_defined() {
+        row.target_service_ty(match target.service_ty() {
+            ServiceType::Service => "service",
+            ServiceType::VirtualObject => "virtual_object",
+            ServiceType::Workflow => "workflow",
+        });
+    }
+
+    match &summary.outcome {
+        InvocationOutcome::Success => {
+            row.completion_result("success");
+        }
+        InvocationOutcome::Failure { code, message } => {
+            row.completion_result("failure");
+            if row.is_completion_failure_defined() {
+                row.fmt_completion_failure(format_args!("[{code}] {message}"));
+            }
+        }
+    }
+
+    row.created_at(summary.created_at.as_u64() as i64);
+    if let Some(running_at) = summary.running_at {
+        row.running_at(running_at.as_u64() as i64);
+    }
+    row.completed_at(summary.completed_at.as_u64() as i64);
+    row.duration(
+        summary
+            .completed_at
+            .as_u64()
+            .saturating_sub(summary.created_at.as_u64()),
+    );
+    row.transient_failures(summary.transient_failures);
+    if row.is_pinned_deployment_id_defined()
+        && let Some(deployment_id) = summary.deployment_id
+    {
+        row.fmt_pinned_deployment_id(deployment_id);
+    }
+    row.journal_size(summary.journal_length);
+}
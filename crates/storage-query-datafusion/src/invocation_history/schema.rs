@@ -0,0 +1,64 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+use crate::table_macro::*;
+
+use datafusion::arrow::datatypes::DataType;
+
+define_table!(sys_invocation_history(
+    /// Internal column that is used for partitioning the services invocations. Can be ignored.
+    partition_key: DataType::UInt64,
+
+    /// [Invocation ID](/operate/invocation#invocation-identifier).
+    id: DataType::LargeUtf8,
+
+    /// Invocation Target. Format for plain services: `ServiceName/HandlerName`, e.g.
+    /// `Greeter/greet`. Format for virtual objects/workflows: `VirtualObjectName/Key/HandlerName`,
+    /// e.g. `Greeter/Francesco/greet`.
+    target: DataType::LargeUtf8,
+
+    /// The name of the invoked service.
+    target_service_name: DataType::LargeUtf8,
+
+    /// The key of the virtual object or the workflow ID. Null for regular services.
+    target_service_key: DataType::LargeUtf8,
+
+    /// The invoked handler.
+    target_handler_name: DataType::LargeUtf8,
+
+    /// The service type. Either `service` or `virtual_object` or `workflow`.
+    target_service_ty: DataType::LargeUtf8,
+
+    /// Either `success` or `failure`.
+    completion_result: DataType::LargeUtf8,
+
+    /// If `completion_result = 'failure'`, this contains the error cause.
+    completion_failure: DataType::LargeUtf8,
+
+    /// Timestamp indicating the start of this invocation.
+    created_at: TimestampMillisecond,
+
+    /// Timestamp indicating when the invocation first transitioned to running, if it ever ran.
+    running_at: TimestampMillisecond,
+
+    /// Timestamp indicating when the invocation completed.
+    completed_at: TimestampMillisecond,
+
+    /// Milliseconds between the start and the completion of this invocation.
+    duration: DataType::UInt64,
+
+    /// Number of attempts of this invocation that failed with a transient error.
+    transient_failures: DataType::UInt32,
+
+    /// The ID of the service deployment that processed this invocation, if any.
+    pinned_deployment_id: DataType::LargeUtf8,
+
+    /// The number of journal entries of this invocation.
+    journal_size: DataType::UInt32,
+));
@@ -0,0 +1,85 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use restate_partition_store::{PartitionStore, PartitionStoreManager};
+use restate_storage_api::StorageError;
+use restate_storage_api::invocation_history_table::{
+    InvocationSummary, ScanInvocationHistoryTable,
+};
+use restate_types::identifiers::PartitionKey;
+
+use crate::context::{QueryContext, SelectPartitions};
+use crate::invocation_history::row::append_invocation_history_row;
+use crate::invocation_history::schema::SysInvocationHistoryBuilder;
+use crate::partition_filter::FirstMatchingPartitionKeyExtractor;
+use crate::partition_store_scanner::{LocalPartitionsScanner, ScanLocalPartition};
+use crate::remote_query_scanner_manager::RemoteScannerManager;
+use crate::table_providers::{PartitionedTableProvider, ScanPartition};
+
+const NAME: &str = "sys_invocation_history";
+
+pub(crate) fn register_self(
+    ctx: &QueryContext,
+    partition_selector: impl SelectPartitions,
+    partition_store_manager: Arc<PartitionStoreManager>,
+    remote_scanner_manager: &RemoteScannerManager,
+) -> datafusion::common::Result<()> {
+    let local_partition_scanner = Arc::new(LocalPartitionsScanner::new(
+        partition_store_manager,
+        InvocationHistoryScanner,
+    )) as Arc<dyn ScanPartition>;
+
+    let table = PartitionedTableProvider::new(
+        partition_selector,
+        SysInvocationHistoryBuilder::schema(),
+        // summaries are stored in completion time order, hence rows are not sorted by partition key
+        vec![],
+        remote_scanner_manager.create_distributed_scanner(NAME, local_partition_scanner),
+        FirstMatchingPartitionKeyExtractor::default().with_invocation_id("id"),
+    );
+    ctx.register_partitioned_table(NAME, Arc::new(table))
+}
+
+#[derive(Debug, Clone)]
+struct InvocationHistoryScanner;
+
+impl ScanLocalPartition for InvocationHistoryScanner {
+    type Builder = SysInvocationHistoryBuilder;
+    type Item<'a> = InvocationSummary;
+    type ConversionError = std::convert::Infallible;
+
+    fn for_each_row<
+        F: for<'a> FnMut(
+                Self::Item<'a>,
+            ) -> std::ops::ControlFlow<Result<(), Self::ConversionError>>
+            + Send
+            + Sync
+            + 'static,
+    >(
+        partition_store: &PartitionStore,
+        range: RangeInclusive<PartitionKey>,
+        mut f: F,
+    ) -> Result<impl Future<Output = restate_storage_api::Result<()>> + Send, StorageError> {
+        partition_store
+            .for_each_invocation_summary(range, move |item| f(item).map_break(Result::unwrap))
+    }
+
+    fn append_row<'a>(
+        row_builder: &mut Self::Builder,
+        value: Self::Item<'a>,
+    ) -> Result<(), Self::ConversionError> {
+        append_invocation_history_row(row_builder, value);
+        Ok(())
+    }
+}
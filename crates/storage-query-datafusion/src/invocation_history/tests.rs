@@ -0,0 +1,111 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::mocks::*;
+use crate::row;
+use datafusion::arrow::array::{
+    LargeStringArray, TimestampMillisecondArray, UInt32Array, UInt64Array,
+};
+use datafusion::arrow::record_batch::RecordBatch;
+use futures::StreamExt;
+use googletest::all;
+use googletest::prelude::{assert_that, eq};
+use restate_storage_api::Transaction;
+use restate_storage_api::invocation_history_table::{
+    InvocationOutcome, InvocationSummary, WriteInvocationHistoryTable,
+};
+use restate_types::errors::KILLED_INVOCATION_ERROR;
+use restate_types::identifiers::{InvocationId, WithPartitionKey};
+use restate_types::invocation::InvocationTarget;
+use restate_types::time::MillisSinceEpoch;
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_invocation_history() {
+    let mut engine = MockQueryEngine::create().await;
+
+    let invocation_target_1 = InvocationTarget::mock_service();
+    let invocation_id_1 = InvocationId::mock_generate(&invocation_target_1);
+    let invocation_target_2 = InvocationTarget::mock_virtual_object();
+    let invocation_id_2 = InvocationId::mock_generate(&invocation_target_2);
+
+    let mut tx = engine.partition_store().transaction();
+    tx.record_invocation_history();
+    tx.put_invocation_summary(&InvocationSummary {
+        invocation_id: invocation_id_1,
+        invocation_target: invocation_target_1.clone(),
+        outcome: InvocationOutcome::Success,
+        created_at: MillisSinceEpoch::new(1000),
+        running_at: Some(MillisSinceEpoch::new(1100)),
+        completed_at: MillisSinceEpoch::new(1500),
+        transient_failures: 2,
+        deployment_id: None,
+        journal_length: 4,
+    })
+    .unwrap();
+    tx.put_invocation_summary(&InvocationSummary {
+        invocation_id: invocation_id_2,
+        invocation_target: invocation_target_2.clone(),
+        outcome: InvocationOutcome::Failure {
+            code: KILLED_INVOCATION_ERROR.code(),
+            message: KILLED_INVOCATION_ERROR.message().to_owned(),
+        },
+        created_at: MillisSinceEpoch::new(2000),
+        running_at: None,
+        completed_at: MillisSinceEpoch::new(2000),
+        transient_failures: 0,
+        deployment_id: None,
+        journal_length: 0,
+    })
+    .unwrap();
+    tx.commit().await.unwrap();
+
+    let records = engine
+        .execute("SELECT * FROM sys_invocation_history ORDER BY completed_at")
+        .await
+        .unwrap()
+        .collect::<Vec<Result<RecordBatch, _>>>()
+        .await
+        .remove(0)
+        .unwrap();
+
+    assert_that!(
+        records,
+        all!(
+            row!(
+                0,
+                {
+                    "partition_key" => UInt64Array: eq(invocation_id_1.partition_key()),
+                    "id" => LargeStringArray: eq(invocation_id_1.to_string()),
+                    "target" => LargeStringArray: eq(invocation_target_1.to_string()),
+                    "completion_result" => LargeStringArray: eq("success"),
+                    "created_at" => TimestampMillisecondArray: eq(1000),
+                    "running_at" => TimestampMillisecondArray: eq(1100),
+                    "completed_at" => TimestampMillisecondArray: eq(1500),
+                    "duration" => UInt64Array: eq(500),
+                    "transient_failures" => UInt32Array: eq(2),
+                    "journal_size" => UInt32Array: eq(4),
+                }
+            ),
+            row!(
+                1,
+                {
+                    "id" => LargeStringArray: eq(invocation_id_2.to_string()),
+                    "target_service_ty" => LargeStringArray: eq("virtual_object"),
+                    "completion_result" => LargeStringArray: eq("failure"),
+                    "completion_failure" => LargeStringArray: eq(format!(
+                        "[{}] killed",
+                        KILLED_INVOCATION_ERROR.code()
+                    )),
+                    "duration" => UInt64Array: eq(0),
+                }
+            )
+        )
+    );
+}
@@ -23,6 +23,7 @@ use tokio::sync::mpsc::Sender;
 use restate_invoker_api::{InvocationStatusReport, StatusHandle};
 use restate_partition_store::PartitionStoreManager;
 use restate_types::identifiers::{PartitionId, PartitionKey};
+use restate_types::net::remote_query_scanner::ScanKeyFilter;
 
 use crate::context::{QueryContext, SelectPartitions};
 use crate::invocation_state::row::append_invocation_state_row;
@@ -96,6 +97,7 @@ impl<S: StatusHandle + Send + Sync + Debug + Clone + 'static> ScanPartition for
         &self,
         partition_id: PartitionId,
         _range: RangeInclusive<PartitionKey>,
+        _key_filter: ScanKeyFilter,
         projection: SchemaRef,
         batch_size: usize,
         limit: Option<usize>,
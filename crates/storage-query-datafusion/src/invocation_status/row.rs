@@ -75,6 +75,12 @@ pub(crate) fn append_invocation_status_row<'a>(
 
     fill_timestamps(&mut row, &invocation_status);
 
+    if row.is_deadline_defined()
+        && let Some(deadline) = invocation_status.inner.deadline
+    {
+        row.deadline(deadline as i64)
+    }
+
     // Additional invocation metadata
     use restate_storage_api::protobuf_types::v1::invocation_status_v2::Status;
     match invocation_status.inner.status() {
@@ -122,4 +122,7 @@ define_table!(sys_invocation_status(
 
     /// For how long the journal is retained after completion.
     journal_retention: DataType::Duration,
+
+    /// End-to-end deadline of the invocation, if any. When exceeded, the invocation is cancelled.
+    deadline: TimestampMillisecond,
 ));
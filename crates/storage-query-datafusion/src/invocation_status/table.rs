@@ -12,19 +12,22 @@ use std::fmt::Debug;
 use std::ops::{ControlFlow, RangeInclusive};
 use std::sync::Arc;
 
+use futures::future::Either;
+
 use restate_partition_store::{PartitionStore, PartitionStoreManager};
 use restate_storage_api::StorageError;
 use restate_storage_api::invocation_status_table::ScanInvocationStatusTable;
 use restate_storage_api::protobuf_types::v1::lazy::InvocationStatusV2Lazy;
 use restate_types::errors::ConversionError;
 use restate_types::identifiers::{InvocationId, PartitionKey};
+use restate_types::net::remote_query_scanner::ScanKeyFilter;
 
 use crate::context::{QueryContext, SelectPartitions};
 use crate::invocation_status::row::append_invocation_status_row;
 use crate::invocation_status::schema::{
     SysInvocationStatusBuilder, sys_invocation_status_sort_order,
 };
-use crate::partition_filter::FirstMatchingPartitionKeyExtractor;
+use crate::partition_filter::{FirstMatchingPartitionKeyExtractor, ScanKeyFilterExtractor};
 use crate::partition_store_scanner::{LocalPartitionsScanner, ScanLocalPartition};
 use crate::remote_query_scanner_manager::RemoteScannerManager;
 use crate::statistics::{
@@ -62,6 +65,7 @@ pub(crate) fn register_self(
             .with_service_key("target_service_key")
             .with_invocation_id("id"),
     )
+    .with_key_filter(ScanKeyFilterExtractor::default().with_invocation_id("id"))
     .with_statistics(statistics.build());
     ctx.register_partitioned_table(NAME, Arc::new(status_table))
 }
@@ -87,6 +91,26 @@ impl ScanLocalPartition for StatusScanner {
         partition_store.for_each_invocation_status_lazy(range, f)
     }
 
+    fn for_each_matching_row<
+        F: for<'a> FnMut(Self::Item<'a>) -> ControlFlow<Result<(), Self::ConversionError>>
+            + Send
+            + Sync
+            + 'static,
+    >(
+        partition_store: &PartitionStore,
+        range: RangeInclusive<PartitionKey>,
+        key_filter: ScanKeyFilter,
+        f: F,
+    ) -> Result<impl Future<Output = Result<(), StorageError>> + Send, StorageError> {
+        if key_filter.invocation_ids.is_empty() {
+            return Ok(Either::Left(Self::for_each_row(partition_store, range, f)?));
+        }
+
+        Ok(Either::Right(
+            partition_store.for_each_invocation_status_lazy_of(key_filter.invocation_ids, f)?,
+        ))
+    }
+
     fn append_row<'a>(
         row_builder: &mut Self::Builder,
         (invocation_id, invocation_status): Self::Item<'a>,
@@ -0,0 +1,403 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Human readable projection of journal v2 entries, used by the decoded columns of `sys_journal`
+//! and by the `restate_decode_journal_entry` function.
+
+use std::sync::Arc;
+
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use bytes::Bytes;
+use datafusion::arrow::array::{AsArray, LargeStringArray};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::exec_err;
+use datafusion::logical_expr::{ColumnarValue, ScalarUDF, Volatility, create_udf};
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use restate_service_protocol_v4::entry_codec::ServiceProtocolV4Codec;
+use restate_service_protocol_v4::message_codec::{MessageHeader, MessageType};
+use restate_types::invocation::offloaded_payload::OffloadedPayload;
+use restate_types::journal_v2::raw::{RawCommand, RawEntry, RawNotification};
+use restate_types::journal_v2::{
+    AttachInvocationResult, CallResult, Command, CommandMetadata, CompleteAwakeableResult,
+    CompletePromiseResult, CompletePromiseValue, Completion, Decoder, Entry, EntryMetadata,
+    EntryType, Failure, GetInvocationOutputResult, GetPromiseResult, GetStateResult, Notification,
+    NotificationId, OutputResult, PeekPromiseResult, RunResult, SignalResult,
+};
+
+/// Payloads bigger than this are truncated when rendered as JSON.
+pub(crate) const MAX_DECODED_PAYLOAD_BYTES: usize = 8 * 1024;
+
+/// Name of the function decoding a service protocol message into a [`DecodedEntry`] JSON.
+pub(crate) const DECODE_JOURNAL_ENTRY_UDF_NAME: &str = "restate_decode_journal_entry";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct DecodedFailure {
+    pub code: u16,
+    pub message: String,
+}
+
+impl From<&Failure> for DecodedFailure {
+    fn from(failure: &Failure) -> Self {
+        Self {
+            code: failure.code.into(),
+            message: failure.message.to_string(),
+        }
+    }
+}
+
+/// The fields of a journal entry that are interesting when inspecting an invocation, with
+/// payloads rendered as JSON.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub(crate) struct DecodedEntry {
+    pub entry_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invoked_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invoked_target: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sleep_wakeup_at: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub promise_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_key: Option<String>,
+    /// Payload sent by the entry, e.g. the call parameter or the state value being set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input: Option<Value>,
+    /// Payload received by the entry, e.g. the call result or the state value being read.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure: Option<DecodedFailure>,
+}
+
+impl DecodedEntry {
+    pub fn new(entry: &Entry) -> Self {
+        let mut decoded = DecodedEntry {
+            entry_type: entry.ty().to_string(),
+            ..Default::default()
+        };
+
+        match entry {
+            Entry::Command(cmd) => {
+                if !cmd.name().is_empty() {
+                    decoded.name = Some(cmd.name().to_string());
+                }
+                decoded.fill_command(cmd);
+            }
+            Entry::Notification(Notification::Completion(completion)) => {
+                decoded.fill_completion(completion)
+            }
+            Entry::Notification(Notification::Signal(signal)) => match &signal.result {
+                SignalResult::Void => decoded.result = Some(Value::Null),
+                SignalResult::Success(value) => decoded.result = Some(payload_json(value)),
+                SignalResult::Failure(failure) => decoded.failure = Some(failure.into()),
+            },
+        }
+
+        decoded
+    }
+
+    fn fill_command(&mut self, cmd: &Command) {
+        match cmd {
+            Command::Input(input) => self.input = Some(payload_json(&input.payload)),
+            Command::Output(output) => match &output.result {
+                OutputResult::Success(value) => self.result = Some(payload_json(value)),
+                OutputResult::Failure(failure) => self.failure = Some(failure.into()),
+            },
+            Command::GetLazyState(get_state) => self.state_key = Some(get_state.key.to_string()),
+            Command::SetState(set_state) => {
+                self.state_key = Some(set_state.key.to_string());
+                self.input = Some(payload_json(&set_state.value));
+            }
+            Command::ClearState(clear_state) => self.state_key = Some(clear_state.key.to_string()),
+            Command::GetEagerState(get_state) => {
+                self.state_key = Some(get_state.key.to_string());
+                self.result = Some(state_result_json(&get_state.result));
+            }
+            Command::GetEagerStateKeys(get_state_keys) => {
+                self.result = Some(json!(get_state_keys.state_keys))
+            }
+            Command::GetPromise(get_promise) => {
+                self.promise_name = Some(get_promise.key.to_string())
+            }
+            Command::PeekPromise(peek_promise) => {
+                self.promise_name = Some(peek_promise.key.to_string())
+            }
+            Command::CompletePromise(complete_promise) => {
+                self.promise_name = Some(complete_promise.key.to_string());
+                match &complete_promise.value {
+                    CompletePromiseValue::Success(value) => self.input = Some(payload_json(value)),
+                    CompletePromiseValue::Failure(failure) => self.failure = Some(failure.into()),
+                }
+            }
+            Command::Sleep(sleep) => self.sleep_wakeup_at = Some(sleep.wake_up_time.as_u64()),
+            Command::Call(call) => {
+                self.invoked_id = Some(call.request.invocation_id.to_string());
+                self.invoked_target = Some(call.request.invocation_target.to_string());
+                self.input = Some(payload_json(&call.request.parameter));
+            }
+            Command::OneWayCall(call) => {
+                self.invoked_id = Some(call.request.invocation_id.to_string());
+                self.invoked_target = Some(call.request.invocation_target.to_string());
+                self.input = Some(payload_json(&call.request.parameter));
+            }
+            Command::SendSignal(send_signal) => {
+                self.invoked_id = Some(send_signal.target_invocation_id.to_string());
+                match &send_signal.result {
+                    SignalResult::Void => self.input = Some(Value::Null),
+                    SignalResult::Success(value) => self.input = Some(payload_json(value)),
+                    SignalResult::Failure(failure) => self.failure = Some(failure.into()),
+                }
+            }
+            Command::CompleteAwakeable(complete_awakeable) => match &complete_awakeable.result {
+                CompleteAwakeableResult::Success(value) => self.input = Some(payload_json(value)),
+                CompleteAwakeableResult::Failure(failure) => self.failure = Some(failure.into()),
+            },
+            Command::ClearAllState(_)
+            | Command::GetLazyStateKeys(_)
+            | Command::Run(_)
+            | Command::AttachInvocation(_)
+            | Command::GetInvocationOutput(_) => {}
+        }
+    }
+
+    fn fill_completion(&mut self, completion: &Completion) {
+        let (result, failure) = match completion {
+            Completion::GetLazyState(get_state) => {
+                (Some(state_result_json(&get_state.result)), None)
+            }
+            Completion::GetLazyStateKeys(get_state_keys) => {
+                (Some(json!(get_state_keys.state_keys)), None)
+            }
+            Completion::GetPromise(get_promise) => match &get_promise.result {
+                GetPromiseResult::Success(value) => (Some(payload_json(value)), None),
+                GetPromiseResult::Failure(failure) => (None, Some(failure)),
+            },
+            Completion::PeekPromise(peek_promise) => match &peek_promise.result {
+                PeekPromiseResult::Void => (Some(Value::Null), None),
+                PeekPromiseResult::Success(value) => (Some(payload_json(value)), None),
+                PeekPromiseResult::Failure(failure) => (None, Some(failure)),
+            },
+            Completion::CompletePromise(complete_promise) => match &complete_promise.result {
+                CompletePromiseResult::Void => (Some(Value::Null), None),
+                CompletePromiseResult::Failure(failure) => (None, Some(failure)),
+            },
+            Completion::Sleep(_) => (None, None),
+            Completion::CallInvocationId(call_invocation_id) => {
+                self.invoked_id = Some(call_invocation_id.invocation_id.to_string());
+                (None, None)
+            }
+            Completion::Call(call) => match &call.result {
+                CallResult::Success(value) => (Some(payload_json(value)), None),
+                CallResult::Failure(failure) => (None, Some(failure)),
+            },
+            Completion::Run(run) => match &run.result {
+                RunResult::Success(value) => (Some(payload_json(value)), None),
+                RunResult::Failure(failure) => (None, Some(failure)),
+            },
+            Completion::AttachInvocation(attach) => match &attach.result {
+                AttachInvocationResult::Success(value) => (Some(payload_json(value)), None),
+                AttachInvocationResult::Failure(failure) => (None, Some(failure)),
+            },
+            Completion::GetInvocationOutput(get_output) => match &get_output.result {
+                GetInvocationOutputResult::Void => (Some(Value::Null), None),
+                GetInvocationOutputResult::Success(value) => (Some(payload_json(value)), None),
+                GetInvocationOutputResult::Failure(failure) => (None, Some(failure)),
+            },
+        };
+
+        self.result = result;
+        self.failure = failure.map(Into::into);
+    }
+}
+
+fn state_result_json(result: &GetStateResult) -> Value {
+    match result {
+        GetStateResult::Void => Value::Null,
+        GetStateResult::Success(value) => payload_json(value),
+    }
+}
+
+/// Renders a payload as JSON: payloads which are valid JSON are embedded as is, other UTF-8
+/// payloads as strings and binary payloads as base64. Payloads bigger than
+/// [`MAX_DECODED_PAYLOAD_BYTES`] are replaced by their length and a prefix, and references to
+/// offloaded payloads by a marker with the length of the original payload.
+pub(crate) fn payload_json(payload: &[u8]) -> Value {
+    if OffloadedPayload::is_offloaded(payload) {
+        return json!({
+            "offloaded": true,
+            "length": OffloadedPayload::decode(payload).map(|reference| reference.length),
+        });
+    }
+
+    if payload.len() > MAX_DECODED_PAYLOAD_BYTES {
+        return json!({
+            "truncated": true,
+            "length": payload.len(),
+            "prefix": String::from_utf8_lossy(&payload[..MAX_DECODED_PAYLOAD_BYTES]),
+        });
+    }
+
+    if let Ok(value) = serde_json::from_slice(payload) {
+        return value;
+    }
+    match std::str::from_utf8(payload) {
+        Ok(payload) => Value::String(payload.to_owned()),
+        Err(_) => json!({ "base64": BASE64_STANDARD.encode(payload) }),
+    }
+}
+
+/// Frames a journal entry as a service protocol message, prepending the message header.
+pub(crate) fn encode_message(raw_entry: &RawEntry) -> Vec<u8> {
+    let (ty, body) = match raw_entry {
+        RawEntry::Command(cmd) => (
+            MessageType::from(cmd.command_type()),
+            cmd.serialized_content(),
+        ),
+        RawEntry::Notification(notification) => (
+            MessageType::from(notification.ty()),
+            notification.serialized_content(),
+        ),
+    };
+    let header = MessageHeader::new(
+        ty,
+        body.len()
+            .try_into()
+            .expect("Protocol messages can't be larger than u32"),
+    );
+
+    let mut message = Vec::with_capacity(8 + body.len());
+    message.extend_from_slice(&u64::from(header).to_be_bytes());
+    message.extend_from_slice(&body);
+    message
+}
+
+/// Decodes a service protocol message, including its 8 bytes header, as found in the `raw`
+/// column of `sys_journal` or captured on the wire.
+pub(crate) fn decode_message(message: &[u8]) -> Option<DecodedEntry> {
+    let (header, body) = message.split_first_chunk::<8>()?;
+    let header = MessageHeader::try_from(u64::from_be_bytes(*header)).ok()?;
+    if header.frame_length() as usize != body.len() {
+        return None;
+    }
+
+    let body = Bytes::copy_from_slice(body);
+    let raw_entry = match header.message_type().entry_type()? {
+        EntryType::Command(ty) => RawEntry::Command(RawCommand::new(ty, body)),
+        // the notification id is read from the message content
+        EntryType::Notification(ty) => RawEntry::Notification(RawNotification::new(
+            ty,
+            NotificationId::CompletionId(0),
+            body,
+        )),
+    };
+
+    let entry = ServiceProtocolV4Codec::decode_entry(&raw_entry).ok()?;
+    Some(DecodedEntry::new(&entry))
+}
+
+/// `restate_decode_journal_entry(message)`: decodes a service protocol message into a JSON
+/// string, or returns null if the message is not a valid journal entry.
+pub(crate) fn decode_journal_entry_udf() -> ScalarUDF {
+    create_udf(
+        DECODE_JOURNAL_ENTRY_UDF_NAME,
+        vec![DataType::LargeBinary],
+        DataType::LargeUtf8,
+        Volatility::Immutable,
+        Arc::new(decode_journal_entries),
+    )
+}
+
+fn decode_journal_entries(args: &[ColumnarValue]) -> datafusion::common::Result<ColumnarValue> {
+    let [messages] = ColumnarValue::values_to_arrays(args)?
+        .try_into()
+        .or_else(|_| exec_err!("{DECODE_JOURNAL_ENTRY_UDF_NAME} expects exactly one argument"))?;
+    let messages = cast(&messages, &DataType::LargeBinary)?;
+
+    let decoded: LargeStringArray = messages
+        .as_binary::<i64>()
+        .iter()
+        .map(|message| {
+            message
+                .and_then(decode_message)
+                .and_then(|decoded| serde_json::to_string(&decoded).ok())
+        })
+        .collect();
+
+    Ok(ColumnarValue::Array(Arc::new(decoded)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use googletest::prelude::*;
+
+    use restate_types::journal_v2::{Encoder, SetStateCommand};
+
+    #[test]
+    fn payloads_are_rendered_as_json() {
+        assert_that!(payload_json(br#"{"a": 1}"#), eq(json!({"a": 1})));
+        assert_that!(payload_json(b"hello"), eq(json!("hello")));
+        assert_that!(
+            payload_json(&[0xff, 0x00]),
+            eq(json!({"base64": BASE64_STANDARD.encode([0xff, 0x00])}))
+        );
+
+        let big = vec![b'a'; MAX_DECODED_PAYLOAD_BYTES + 1];
+        assert_that!(
+            payload_json(&big),
+            eq(json!({
+                "truncated": true,
+                "length": MAX_DECODED_PAYLOAD_BYTES + 1,
+                "prefix": "a".repeat(MAX_DECODED_PAYLOAD_BYTES),
+            }))
+        );
+
+        assert_that!(
+            payload_json(&OffloadedPayload::input(50 * 1024 * 1024).encode()),
+            eq(json!({"offloaded": true, "length": 50 * 1024 * 1024}))
+        );
+    }
+
+    #[test]
+    fn decode_set_state_message() {
+        let message = encode_message(&ServiceProtocolV4Codec::encode_entry(
+            SetStateCommand {
+                key: "counter".into(),
+                value: Bytes::from_static(b"42"),
+                name: Default::default(),
+            }
+            .into(),
+        ));
+
+        assert_that!(
+            decode_message(&message),
+            some(pat!(DecodedEntry {
+                state_key: some(eq("counter")),
+                input: some(eq(json!(42))),
+                result: none(),
+                failure: none(),
+            }))
+        );
+    }
+
+    #[test]
+    fn decode_garbage() {
+        assert_that!(decode_message(b"not a message"), none());
+        assert_that!(decode_message(&[]), none());
+    }
+}
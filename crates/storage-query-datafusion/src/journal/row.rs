@@ -8,7 +8,8 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use crate::journal::schema::SysJournalBuilder;
+use crate::journal::decoding::{self, DecodedEntry};
+use crate::journal::schema::{SysJournalBuilder, SysJournalRowBuilder};
 use crate::log_data_corruption_error;
 use restate_service_protocol::codec::ProtobufRawEntryCodec;
 use restate_service_protocol_v4::entry_codec::ServiceProtocolV4Codec;
@@ -155,6 +156,10 @@ pub(crate) fn append_journal_row_v2(
 
     row.appended_at(raw_entry.header.append_time.as_u64() as i64);
 
+    if row.is_raw_defined() {
+        row.raw(decoding::encode_message(&raw_entry.inner));
+    }
+
     if row.is_entry_lite_json_defined() {
         // We need to parse the entry
         let Ok(entry_lite) = ServiceProtocolV4Codec::decode_entry_lite(&raw_entry.inner) else {
@@ -178,7 +183,17 @@ pub(crate) fn append_journal_row_v2(
         row.entry_lite_json(entry_lite_json);
     }
 
-    if row.is_entry_json_defined() || row.is_name_defined() {
+    let decode_columns_defined = row.is_invoked_id_defined()
+        || row.is_invoked_target_defined()
+        || row.is_sleep_wakeup_at_defined()
+        || row.is_promise_name_defined()
+        || row.is_state_key_defined()
+        || row.is_input_json_defined()
+        || row.is_result_json_defined()
+        || row.is_failure_code_defined()
+        || row.is_failure_message_defined();
+
+    if row.is_entry_json_defined() || row.is_name_defined() || decode_columns_defined {
         // We need to parse the entry
         let Ok(entry) = raw_entry.decode::<ServiceProtocolV4Codec, journal_v2::Entry>() else {
             log_data_corruption_error!(
@@ -196,9 +211,44 @@ pub(crate) fn append_journal_row_v2(
             row.entry_json(json);
         }
         if row.is_name_defined()
-            && let journal_v2::Entry::Command(cmd) = entry
+            && let journal_v2::Entry::Command(cmd) = &entry
         {
             row.name(cmd.name());
         }
+        if decode_columns_defined {
+            append_decoded_columns(&mut row, DecodedEntry::new(&entry));
+        }
+    }
+}
+
+fn append_decoded_columns(row: &mut SysJournalRowBuilder<'_>, decoded: DecodedEntry) {
+    if let Some(invoked_id) = decoded.invoked_id {
+        row.invoked_id(invoked_id);
+    }
+    if let Some(invoked_target) = decoded.invoked_target {
+        row.invoked_target(invoked_target);
+    }
+    if let Some(sleep_wakeup_at) = decoded.sleep_wakeup_at {
+        row.sleep_wakeup_at(sleep_wakeup_at as i64);
+    }
+    if let Some(promise_name) = decoded.promise_name {
+        row.promise_name(promise_name);
+    }
+    if let Some(state_key) = decoded.state_key {
+        row.state_key(state_key);
+    }
+    if row.is_input_json_defined()
+        && let Some(input) = decoded.input
+    {
+        row.input_json(input.to_string());
+    }
+    if row.is_result_json_defined()
+        && let Some(result) = decoded.result
+    {
+        row.result_json(result.to_string());
+    }
+    if let Some(failure) = decoded.failure {
+        row.failure_code(u32::from(failure.code));
+        row.failure_message(failure.message);
     }
 }
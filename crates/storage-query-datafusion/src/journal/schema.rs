@@ -50,7 +50,8 @@ define_table!(sys_journal (
     promise_name: DataType::LargeUtf8,
 
     /// Raw binary representation of the entry. Check the [service protocol](https://github.com/restatedev/service-protocol)
-    /// for more details to decode it.
+    /// for more details to decode it. If journal version is 2, this is the entry framed as a service
+    /// protocol message, which can be decoded with `restate_decode_journal_entry(raw)`.
     raw: DataType::LargeBinary,
 
     /// The journal version.
@@ -64,4 +65,28 @@ define_table!(sys_journal (
 
     /// When the entry was appended to the journal. Filled only if journal version is 2.
     appended_at: TimestampMillisecond,
+
+    /// If this entry accesses the state (GetState, SetState, ClearState), indicates the state key.
+    /// Filled only if journal version is 2.
+    state_key: DataType::LargeUtf8,
+
+    /// The payload sent by this entry rendered as JSON, e.g. the invocation input, the call
+    /// parameter or the state value being set. Payloads that are not valid JSON are rendered as
+    /// strings, or as base64 if they are binary, and payloads bigger than 8 KiB are truncated.
+    /// Payloads offloaded to the object store are rendered as
+    /// `{"offloaded": true, "length": <length>}`. Filled only if journal version is 2.
+    input_json: DataType::LargeUtf8,
+
+    /// The payload received by this entry rendered as JSON, e.g. the invocation output, the call
+    /// result or the state value being read. Rendered like `input_json`. Filled only if journal
+    /// version is 2.
+    result_json: DataType::LargeUtf8,
+
+    /// If this entry carries a failure, e.g. a failed call result or a failed invocation output,
+    /// indicates the error code. Filled only if journal version is 2.
+    failure_code: DataType::UInt32,
+
+    /// If this entry carries a failure, indicates the error message. Filled only if journal
+    /// version is 2.
+    failure_message: DataType::LargeUtf8,
 ));
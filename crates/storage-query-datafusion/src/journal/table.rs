@@ -13,18 +13,21 @@ use std::ops::ControlFlow;
 use std::ops::RangeInclusive;
 use std::sync::Arc;
 
+use futures::future::Either;
+
 use restate_partition_store::{PartitionStore, PartitionStoreManager};
 use restate_storage_api::StorageError;
 use restate_storage_api::journal_table::JournalEntry;
 use restate_storage_api::journal_table::ScanJournalTable;
 use restate_storage_api::journal_table_v2::ScanJournalTable as ScanJournalTableV2;
-use restate_types::identifiers::{JournalEntryId, PartitionKey};
+use restate_types::identifiers::{InvocationId, JournalEntryId, PartitionKey};
+use restate_types::net::remote_query_scanner::ScanKeyFilter;
 use restate_types::storage::StoredRawEntry;
 
 use crate::context::{QueryContext, SelectPartitions};
 use crate::journal::row::{append_journal_row, append_journal_row_v2};
 use crate::journal::schema::{SysJournalBuilder, sys_journal_sort_order};
-use crate::partition_filter::FirstMatchingPartitionKeyExtractor;
+use crate::partition_filter::{FirstMatchingPartitionKeyExtractor, ScanKeyFilterExtractor};
 use crate::partition_store_scanner::{LocalPartitionsScanner, ScanLocalPartition};
 use crate::remote_query_scanner_manager::RemoteScannerManager;
 use crate::table_providers::{PartitionedTableProvider, ScanPartition};
@@ -48,7 +51,8 @@ pub(crate) fn register_self(
         sys_journal_sort_order(),
         remote_scanner_manager.create_distributed_scanner(NAME, local_scanner),
         FirstMatchingPartitionKeyExtractor::default().with_invocation_id("id"),
-    );
+    )
+    .with_key_filter(ScanKeyFilterExtractor::default().with_invocation_id("id"));
     ctx.register_partitioned_table(NAME, Arc::new(journal_table))
 }
 
@@ -77,48 +81,21 @@ impl ScanLocalPartition for JournalScanner {
         range: RangeInclusive<PartitionKey>,
         f: F,
     ) -> Result<impl Future<Output = restate_storage_api::Result<()>> + Send, StorageError> {
-        // these two iterators can run concurrently in theory.
-        // in practice, there are not typically any keys in the first iterator, but rust rightfully forces us to use a mutex to protect the FnMut
-        // the intent here is that iterators race to produce the first row, the winner gets an owned lock guard which it then holds until its done iterating
-        // and then the next iterator is able to get a lock guard.
-
-        let (v1, v2) = {
-            // once we've started iterating, this arc must be dropped from inside the io threads and not in an async context
-            let mut f_v1 = Some(Arc::new(tokio::sync::Mutex::new(f)));
-            let mut f_v2 = f_v1.clone();
-
-            let v1 = ScanJournalTable::for_each_journal(partition_store, range.clone(), {
-                let mut f_locked = None;
-                move |(id, entry)| {
-                    let f = f_locked.get_or_insert_with(|| {
-                        f_v1.take()
-                            .expect("we only take f_v1 once")
-                            .blocking_lock_owned()
-                    });
-                    f((id, ScannedEntry::V1(entry))).map_break(Result::unwrap)
-                }
-            })?;
-
-            let v2 = ScanJournalTableV2::for_each_journal(partition_store, range, {
-                let mut f_locked = None;
-                move |(id, entry)| {
-                    let f = f_locked.get_or_insert_with(|| {
-                        f_v2.take()
-                            .expect("we only take f_v2 once")
-                            .blocking_lock_owned()
-                    });
-                    f((id, ScannedEntry::V2(entry))).map_break(Result::unwrap)
-                }
-            })?;
-
-            (v1, v2)
-        };
+        for_each_journal_entry(partition_store, range, Vec::new(), f)
+    }
 
-        Ok(async {
-            v1.await?;
-            v2.await?;
-            Ok(())
-        })
+    fn for_each_matching_row<
+        F: for<'a> FnMut(Self::Item<'a>) -> ControlFlow<Result<(), Self::ConversionError>>
+            + Send
+            + Sync
+            + 'static,
+    >(
+        partition_store: &PartitionStore,
+        range: RangeInclusive<PartitionKey>,
+        key_filter: ScanKeyFilter,
+        f: F,
+    ) -> Result<impl Future<Output = restate_storage_api::Result<()>> + Send, StorageError> {
+        for_each_journal_entry(partition_store, range, key_filter.invocation_ids, f)
     }
 
     fn append_row<'a>(
@@ -137,3 +114,84 @@ impl ScanLocalPartition for JournalScanner {
         Ok(())
     }
 }
+
+/// Visits the journal entries in `range`, or only the ones of the given invocations if any.
+fn for_each_journal_entry<
+    F: FnMut((JournalEntryId, ScannedEntry)) -> ControlFlow<Result<(), std::convert::Infallible>>
+        + Send
+        + Sync
+        + 'static,
+>(
+    partition_store: &PartitionStore,
+    range: RangeInclusive<PartitionKey>,
+    invocation_ids: Vec<InvocationId>,
+    f: F,
+) -> Result<impl Future<Output = restate_storage_api::Result<()>> + Send, StorageError> {
+    // these two iterators can run concurrently in theory.
+    // in practice, there are not typically any keys in the first iterator, but rust rightfully forces us to use a mutex to protect the FnMut
+    // the intent here is that iterators race to produce the first row, the winner gets an owned lock guard which it then holds until its done iterating
+    // and then the next iterator is able to get a lock guard.
+
+    let (v1, v2) = {
+        // once we've started iterating, this arc must be dropped from inside the io threads and not in an async context
+        let mut f_v1 = Some(Arc::new(tokio::sync::Mutex::new(f)));
+        let mut f_v2 = f_v1.clone();
+
+        let f_v1 = {
+            let mut f_locked = None;
+            move |(id, entry): (JournalEntryId, JournalEntry)| {
+                let f = f_locked.get_or_insert_with(|| {
+                    f_v1.take()
+                        .expect("we only take f_v1 once")
+                        .blocking_lock_owned()
+                });
+                f((id, ScannedEntry::V1(entry))).map_break(Result::unwrap)
+            }
+        };
+        let f_v2 = {
+            let mut f_locked = None;
+            move |(id, entry): (JournalEntryId, StoredRawEntry)| {
+                let f = f_locked.get_or_insert_with(|| {
+                    f_v2.take()
+                        .expect("we only take f_v2 once")
+                        .blocking_lock_owned()
+                });
+                f((id, ScannedEntry::V2(entry))).map_break(Result::unwrap)
+            }
+        };
+
+        if invocation_ids.is_empty() {
+            (
+                Either::Left(ScanJournalTable::for_each_journal(
+                    partition_store,
+                    range.clone(),
+                    f_v1,
+                )?),
+                Either::Left(ScanJournalTableV2::for_each_journal(
+                    partition_store,
+                    range,
+                    f_v2,
+                )?),
+            )
+        } else {
+            (
+                Either::Right(ScanJournalTable::for_each_journal_of(
+                    partition_store,
+                    invocation_ids.clone(),
+                    f_v1,
+                )?),
+                Either::Right(ScanJournalTableV2::for_each_journal_of(
+                    partition_store,
+                    invocation_ids,
+                    f_v2,
+                )?),
+            )
+        }
+    };
+
+    Ok(async {
+        v1.await?;
+        v2.await?;
+        Ok(())
+    })
+}
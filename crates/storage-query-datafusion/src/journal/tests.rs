@@ -15,18 +15,27 @@ use datafusion::arrow::array::{Int64Array, LargeStringArray, UInt32Array};
 use datafusion::arrow::record_batch::RecordBatch;
 use futures::StreamExt;
 use googletest::all;
-use googletest::prelude::{assert_that, eq};
+use googletest::prelude::{assert_that, contains_substring, eq, predicate};
 use prost::Message;
 use restate_service_protocol::codec::ProtobufRawEntryCodec;
+use restate_service_protocol_v4::entry_codec::ServiceProtocolV4Codec;
 use restate_storage_api::Transaction;
 use restate_storage_api::journal_table::{JournalEntry, WriteJournalTable};
-use restate_types::identifiers::InvocationId;
+use restate_storage_api::journal_table_v2;
+use restate_types::identifiers::{InvocationId, InvocationUuid, WithPartitionKey};
 use restate_types::invocation::InvocationTarget;
+use restate_types::invocation::offloaded_payload::OffloadedPayload;
 use restate_types::journal::enriched::{
     CallEnrichmentResult, EnrichedEntryHeader, EnrichedRawEntry,
 };
 use restate_types::journal::{Entry, EntryType, InputEntry};
+use restate_types::journal_v2::raw::RawEntry;
+use restate_types::journal_v2::{
+    Encoder, Failure, InputCommand, OutputCommand, OutputResult, SetStateCommand,
+};
 use restate_types::service_protocol;
+use restate_types::storage::{StoredRawEntry, StoredRawEntryHeader};
+use restate_types::time::MillisSinceEpoch;
 
 #[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
 async fn get_entries() {
@@ -194,3 +203,158 @@ async fn select_count_star() {
         ),)
     );
 }
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn select_by_id() {
+    let mut engine = MockQueryEngine::create().await;
+
+    // both invocations share the same partition key, so only the key filter tells them apart
+    let invocation_id = InvocationId::mock_random();
+    let other_invocation_id =
+        InvocationId::from_parts(invocation_id.partition_key(), InvocationUuid::mock_random());
+
+    let mut tx = engine.partition_store().transaction();
+    for id in [&invocation_id, &other_invocation_id] {
+        for index in 0..2 {
+            tx.put_journal_entry(
+                id,
+                index,
+                &JournalEntry::Entry(ProtobufRawEntryCodec::serialize_enriched(Entry::Input(
+                    InputEntry {
+                        headers: vec![],
+                        value: Default::default(),
+                    },
+                ))),
+            )
+            .unwrap();
+        }
+    }
+    tx.commit().await.unwrap();
+
+    let records = engine
+        .execute(format!(
+            "SELECT id, index FROM sys_journal WHERE id = '{invocation_id}' ORDER BY index"
+        ))
+        .await
+        .unwrap()
+        .collect::<Vec<Result<RecordBatch, _>>>()
+        .await
+        .remove(0)
+        .unwrap();
+
+    assert_eq!(records.num_rows(), 2);
+    assert_that!(
+        records,
+        all!(
+            row!(
+                0,
+                {
+                    "id" => LargeStringArray: eq(invocation_id.to_string()),
+                    "index" => UInt32Array: eq(0),
+                }
+            ),
+            row!(
+                1,
+                {
+                    "id" => LargeStringArray: eq(invocation_id.to_string()),
+                    "index" => UInt32Array: eq(1),
+                }
+            )
+        )
+    );
+}
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_decoded_entries_v2() {
+    let mut engine = MockQueryEngine::create().await;
+
+    let invocation_id = InvocationId::mock_random();
+    let entries: [RawEntry; 3] = [
+        ServiceProtocolV4Codec::encode_entry(
+            InputCommand {
+                headers: vec![],
+                payload: OffloadedPayload::input(50 * 1024 * 1024).encode(),
+                name: Default::default(),
+            }
+            .into(),
+        ),
+        ServiceProtocolV4Codec::encode_entry(
+            SetStateCommand {
+                key: "counter".into(),
+                value: Bytes::from_static(b"42"),
+                name: Default::default(),
+            }
+            .into(),
+        ),
+        ServiceProtocolV4Codec::encode_entry(
+            OutputCommand {
+                result: OutputResult::Failure(Failure {
+                    code: 500u16.into(),
+                    message: "boom".into(),
+                    metadata: vec![],
+                }),
+                name: Default::default(),
+            }
+            .into(),
+        ),
+    ];
+
+    let mut tx = engine.partition_store().transaction();
+    for (index, entry) in entries.into_iter().enumerate() {
+        journal_table_v2::WriteJournalTable::put_journal_entry(
+            &mut tx,
+            invocation_id,
+            index as u32,
+            &StoredRawEntry::new(StoredRawEntryHeader::new(MillisSinceEpoch::now()), entry),
+            &[],
+        )
+        .unwrap();
+    }
+    tx.commit().await.unwrap();
+
+    let records = engine
+        .execute(
+            "SELECT index, state_key, input_json, failure_code, failure_message, \
+            restate_decode_journal_entry(raw) AS decoded FROM sys_journal ORDER BY index",
+        )
+        .await
+        .unwrap()
+        .collect::<Vec<Result<RecordBatch, _>>>()
+        .await
+        .remove(0)
+        .unwrap();
+
+    assert_eq!(records.num_rows(), 3);
+    assert_that!(
+        records,
+        all!(
+            row!(
+                0,
+                {
+                    "index" => UInt32Array: eq(0),
+                    "input_json" => LargeStringArray: predicate(|input: &String| {
+                        serde_json::from_str::<serde_json::Value>(input).ok()
+                            == Some(serde_json::json!({"offloaded": true, "length": 50 * 1024 * 1024}))
+                    }),
+                }
+            ),
+            row!(
+                1,
+                {
+                    "index" => UInt32Array: eq(1),
+                    "state_key" => LargeStringArray: eq("counter"),
+                    "input_json" => LargeStringArray: eq("42"),
+                    "decoded" => LargeStringArray: contains_substring(r#""state_key":"counter","input":42"#),
+                }
+            ),
+            row!(
+                2,
+                {
+                    "index" => UInt32Array: eq(2),
+                    "failure_code" => UInt32Array: eq(500),
+                    "failure_message" => LargeStringArray: eq("boom"),
+                }
+            )
+        )
+    );
+}
@@ -12,19 +12,22 @@ use std::fmt::Debug;
 use std::ops::{ControlFlow, RangeInclusive};
 use std::sync::Arc;
 
+use futures::future::Either;
+
 use restate_partition_store::{PartitionStore, PartitionStoreManager};
 use restate_storage_api::StorageError;
 use restate_storage_api::service_status_table::{
     ScanVirtualObjectStatusTable, VirtualObjectStatus,
 };
 use restate_types::identifiers::{PartitionKey, ServiceId};
+use restate_types::net::remote_query_scanner::ScanKeyFilter;
 
 use crate::context::{QueryContext, SelectPartitions};
 use crate::keyed_service_status::row::append_virtual_object_status_row;
 use crate::keyed_service_status::schema::{
     SysKeyedServiceStatusBuilder, sys_keyed_service_status_sort_order,
 };
-use crate::partition_filter::FirstMatchingPartitionKeyExtractor;
+use crate::partition_filter::{FirstMatchingPartitionKeyExtractor, ScanKeyFilterExtractor};
 use crate::partition_store_scanner::{LocalPartitionsScanner, ScanLocalPartition};
 use crate::remote_query_scanner_manager::RemoteScannerManager;
 use crate::table_providers::{PartitionedTableProvider, ScanPartition};
@@ -49,7 +52,8 @@ pub(crate) fn register_self(
         FirstMatchingPartitionKeyExtractor::default()
             .with_service_key("service_key")
             .with_invocation_id("invocation_id"),
-    );
+    )
+    .with_key_filter(ScanKeyFilterExtractor::default().with_service("service_name", "service_key"));
 
     ctx.register_partitioned_table(NAME, Arc::new(status_table))
 }
@@ -76,6 +80,29 @@ impl ScanLocalPartition for VirtualObjectStatusScanner {
             .for_each_virtual_object_status(range, move |item| f(item).map_break(Result::unwrap))
     }
 
+    fn for_each_matching_row<
+        F: for<'a> FnMut(Self::Item<'a>) -> ControlFlow<Result<(), Self::ConversionError>>
+            + Send
+            + Sync
+            + 'static,
+    >(
+        partition_store: &PartitionStore,
+        range: RangeInclusive<PartitionKey>,
+        key_filter: ScanKeyFilter,
+        mut f: F,
+    ) -> Result<impl Future<Output = restate_storage_api::Result<()>> + Send, StorageError> {
+        if key_filter.services.is_empty() {
+            return Ok(Either::Left(Self::for_each_row(partition_store, range, f)?));
+        }
+
+        Ok(Either::Right(
+            partition_store
+                .for_each_virtual_object_status_of(key_filter.service_ids(), move |item| {
+                    f(item).map_break(Result::unwrap)
+                })?,
+        ))
+    }
+
     fn append_row<'a>(
         row_builder: &mut Self::Builder,
         value: Self::Item<'a>,
@@ -16,18 +16,23 @@ pub mod remote_query_scanner_server;
 mod deployment;
 mod idempotency;
 mod inbox;
+mod invocation_counts;
+mod invocation_history;
 mod invocation_state;
 mod invocation_status;
 mod journal;
 mod journal_events;
 mod keyed_service_status;
 mod log;
+mod log_storage;
 mod node;
+mod outbox;
 mod partition;
 mod partition_replica_set;
 mod partition_state;
 mod partition_store_scanner;
 mod promise;
+mod query_limits;
 mod scanner_task;
 mod service;
 mod state;
@@ -37,6 +42,7 @@ pub mod table_docs;
 mod table_macro;
 mod table_providers;
 mod table_util;
+mod timer;
 
 pub use context::BuildError;
 use datafusion::arrow::datatypes::Schema;
@@ -0,0 +1,33 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use restate_log_server_grpc::LogletStorage;
+use restate_types::GenerationalNodeId;
+use restate_types::logs::LogletId;
+
+use super::schema::LogStorageBuilder;
+
+#[inline]
+pub(crate) fn append_log_storage_row(
+    builder: &mut LogStorageBuilder,
+    node_id: GenerationalNodeId,
+    storage: &LogletStorage,
+) {
+    let mut row = builder.row();
+    let loglet_id = LogletId::from(storage.loglet_id);
+
+    row.fmt_plain_node_id(node_id.as_plain());
+    row.fmt_gen_node_id(node_id);
+    row.log_id(loglet_id.log_id().into());
+    row.segment_index(loglet_id.segment_index().into());
+    row.fmt_loglet_id(loglet_id);
+    row.records(storage.records);
+    row.bytes(storage.bytes);
+}
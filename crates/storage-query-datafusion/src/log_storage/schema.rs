@@ -0,0 +1,39 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use datafusion::arrow::datatypes::DataType;
+
+use crate::table_macro::*;
+
+define_table!(
+    /// Storage usage of loglets on log-servers
+    log_storage(
+        /// Node ID of the log-server
+        plain_node_id: DataType::Utf8,
+
+        /// Node generation
+        gen_node_id: DataType::Utf8,
+
+        /// Log ID
+        log_id: DataType::UInt32,
+
+        /// Segment index
+        segment_index: DataType::UInt32,
+
+        /// Loglet ID
+        loglet_id: DataType::Utf8,
+
+        /// Number of records stored by the log-server
+        records: DataType::UInt64,
+
+        /// Approximate size of the stored records in bytes
+        bytes: DataType::UInt64,
+    )
+);
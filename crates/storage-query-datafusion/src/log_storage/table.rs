@@ -0,0 +1,127 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::sync::Arc;
+
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::logical_expr::Expr;
+use datafusion::physical_plan::SendableRecordBatchStream;
+use datafusion::physical_plan::stream::RecordBatchReceiverStream;
+use tokio::sync::mpsc::Sender;
+use tracing::debug;
+
+use restate_core::Metadata;
+use restate_core::network::net_util::create_tonic_channel;
+use restate_log_server_grpc::{ListLogletStorageRequest, new_log_server_client};
+use restate_types::cluster_state::ClusterState;
+use restate_types::config::Configuration;
+use restate_types::nodes_config::{NodesConfiguration, Role};
+
+use crate::context::QueryContext;
+use crate::table_providers::{GenericTableProvider, Scan};
+use crate::table_util::Builder;
+
+use super::row::append_log_storage_row;
+use super::schema::LogStorageBuilder;
+
+pub fn register_self(
+    ctx: &QueryContext,
+    metadata: Metadata,
+    cluster_state: ClusterState,
+) -> datafusion::common::Result<()> {
+    let table = GenericTableProvider::new(
+        LogStorageBuilder::schema(),
+        Arc::new(LogStorageScanner {
+            metadata,
+            cluster_state,
+        }),
+    );
+    ctx.register_non_partitioned_table("sys_log_storage", Arc::new(table))
+}
+
+#[derive(Clone, derive_more::Debug)]
+#[debug("LogStorageScanner")]
+struct LogStorageScanner {
+    metadata: Metadata,
+    cluster_state: ClusterState,
+}
+
+impl Scan for LogStorageScanner {
+    fn scan(
+        &self,
+        projection: SchemaRef,
+        _filters: &[Expr],
+        batch_size: usize,
+        _limit: Option<usize>,
+    ) -> SendableRecordBatchStream {
+        let schema = projection.clone();
+        let mut stream_builder = RecordBatchReceiverStream::builder(projection, 2);
+        let tx = stream_builder.tx();
+
+        let nodes_config = self.metadata.nodes_config_snapshot();
+        let cluster_state = self.cluster_state.clone();
+        stream_builder.spawn(async move {
+            for_each_log_server(schema, tx, nodes_config, cluster_state, batch_size).await;
+            Ok(())
+        });
+        stream_builder.build()
+    }
+}
+
+async fn for_each_log_server(
+    schema: SchemaRef,
+    tx: Sender<datafusion::common::Result<RecordBatch>>,
+    nodes_config: Arc<NodesConfiguration>,
+    cluster_state: ClusterState,
+    batch_size: usize,
+) {
+    let mut builder = LogStorageBuilder::new(schema.clone());
+    for (_, node_config) in nodes_config.iter() {
+        let node_id = node_config.current_generation;
+        if !node_config.has_role(Role::LogServer) || !cluster_state.is_alive(node_id.into()) {
+            continue;
+        }
+
+        let mut client = new_log_server_client(create_tonic_channel(
+            node_config.address.clone(),
+            &Configuration::pinned().networking,
+        ));
+        let loglets = match client
+            .list_loglet_storage(ListLogletStorageRequest { loglet_id: None })
+            .await
+        {
+            Ok(response) => response.into_inner().loglets,
+            Err(err) => {
+                // unreachable log-servers are skipped, the table shows what could be observed
+                debug!("Failed to list the loglet storage of node {node_id}: {err}");
+                continue;
+            }
+        };
+
+        for storage in &loglets {
+            append_log_storage_row(&mut builder, node_id, storage);
+            if builder.num_rows() >= batch_size {
+                let batch = builder.finish_and_new();
+                if tx.send(batch).await.is_err() {
+                    // not sure what to do here?
+                    // the other side has hung up on us.
+                    // we probably don't want to panic, is it will cause the entire process to exit
+                    return;
+                }
+            }
+        }
+    }
+
+    if !builder.empty() {
+        let result = builder.finish();
+        let _ = tx.send(result).await;
+    }
+}
@@ -35,6 +35,7 @@ use restate_types::net::remote_query_scanner::RemoteQueryScannerOpen;
 use restate_types::partition_table::Partition;
 use restate_types::schema::deployment::test_util::MockDeploymentMetadataRegistry;
 use restate_types::schema::deployment::{Deployment, DeploymentResolver};
+use restate_types::schema::routing::ServiceRouting;
 use restate_types::schema::service::test_util::MockServiceMetadataResolver;
 use restate_types::schema::service::{ServiceMetadata, ServiceMetadataResolver};
 
@@ -81,6 +82,10 @@ impl DeploymentResolver for MockSchemas {
         self.1.resolve_latest_deployment_for_service(service_name)
     }
 
+    fn resolve_service_routing(&self, service_name: impl AsRef<str>) -> Option<ServiceRouting> {
+        self.1.resolve_service_routing(service_name)
+    }
+
     fn find_deployment(
         &self,
         deployment_address: &DeploymentAddress,
@@ -0,0 +1,66 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::schema::SysOutboxBuilder;
+use restate_storage_api::outbox_table::OutboxMessage;
+use restate_types::identifiers::{PartitionId, WithPartitionKey};
+use restate_types::invocation::InvocationQuery;
+
+#[inline]
+pub(crate) fn append_outbox_row(
+    builder: &mut SysOutboxBuilder,
+    partition_id: PartitionId,
+    sequence_number: u64,
+    outbox_message: OutboxMessage,
+) {
+    let mut row = builder.row();
+
+    row.partition_id(u64::from(partition_id));
+    row.sequence_number(sequence_number);
+    row.target_partition_key(outbox_message.partition_key());
+
+    let target_id = match outbox_message {
+        OutboxMessage::ServiceInvocation(service_invocation) => {
+            row.kind("invocation");
+            let invocation_target = &service_invocation.invocation_target;
+            row.target_service_name(invocation_target.service_name());
+            if let Some(key) = invocation_target.key() {
+                row.target_service_key(key);
+            }
+            row.target_handler_name(invocation_target.handler_name());
+            Some(service_invocation.invocation_id)
+        }
+        OutboxMessage::ServiceResponse(response) => {
+            row.kind("response");
+            Some(response.target.caller_id)
+        }
+        OutboxMessage::InvocationTermination(termination) => {
+            row.kind("termination");
+            Some(termination.invocation_id)
+        }
+        OutboxMessage::AttachInvocation(attach) => {
+            row.kind("attach");
+            match attach.invocation_query {
+                InvocationQuery::Invocation(invocation_id) => Some(invocation_id),
+                InvocationQuery::Workflow(_) | InvocationQuery::IdempotencyId(_) => None,
+            }
+        }
+        OutboxMessage::NotifySignal(signal) => {
+            row.kind("signal");
+            Some(signal.invocation_id)
+        }
+    };
+
+    if row.is_target_id_defined()
+        && let Some(target_id) = target_id
+    {
+        row.fmt_target_id(target_id);
+    }
+}
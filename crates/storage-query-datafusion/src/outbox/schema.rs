@@ -0,0 +1,44 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::table_macro::*;
+
+use datafusion::arrow::datatypes::DataType;
+
+define_sort_order!(sys_outbox(partition_id, sequence_number));
+
+define_table!(sys_outbox(
+    /// The partition whose outbox contains this message.
+    partition_id: DataType::UInt64,
+
+    /// Sequence number in the outbox.
+    sequence_number: DataType::UInt64,
+
+    /// The kind of message. Either `invocation`, `response`, `termination`, `attach` or `signal`.
+    kind: DataType::LargeUtf8,
+
+    /// The partition key of the message destination. Use it to find the partition the message
+    /// is sent to.
+    target_partition_key: DataType::UInt64,
+
+    /// [Invocation ID](/operate/invocation#invocation-identifier) of the invocation the message is
+    /// addressed to. Null for `attach` messages which don't target a specific invocation.
+    target_id: DataType::LargeUtf8,
+
+    /// The name of the invoked service, if `kind = 'invocation'`.
+    target_service_name: DataType::LargeUtf8,
+
+    /// The key of the virtual object or the workflow ID, if `kind = 'invocation'`. Null for
+    /// regular services.
+    target_service_key: DataType::LargeUtf8,
+
+    /// The invoked handler, if `kind = 'invocation'`.
+    target_handler_name: DataType::LargeUtf8,
+));
@@ -0,0 +1,88 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use restate_partition_store::{PartitionStore, PartitionStoreManager};
+use restate_storage_api::StorageError;
+use restate_storage_api::outbox_table::{OutboxMessage, ScanOutboxTable};
+use restate_types::identifiers::{PartitionId, PartitionKey};
+
+use crate::context::{QueryContext, SelectPartitions};
+use crate::outbox::row::append_outbox_row;
+use crate::outbox::schema::{SysOutboxBuilder, sys_outbox_sort_order};
+use crate::partition_filter::FirstMatchingPartitionKeyExtractor;
+use crate::partition_store_scanner::{LocalPartitionsScanner, ScanLocalPartition};
+use crate::remote_query_scanner_manager::RemoteScannerManager;
+use crate::table_providers::{PartitionedTableProvider, ScanPartition};
+
+const NAME: &str = "sys_outbox";
+
+pub(crate) fn register_self(
+    ctx: &QueryContext,
+    partition_selector: impl SelectPartitions,
+    partition_store_manager: Arc<PartitionStoreManager>,
+    remote_scanner_manager: &RemoteScannerManager,
+) -> datafusion::common::Result<()> {
+    let local_partition_scanner = Arc::new(LocalPartitionsScanner::new(
+        partition_store_manager,
+        OutboxScanner,
+    )) as Arc<dyn ScanPartition>;
+
+    let table = PartitionedTableProvider::new(
+        partition_selector,
+        SysOutboxBuilder::schema(),
+        sys_outbox_sort_order(),
+        remote_scanner_manager.create_distributed_scanner(NAME, local_partition_scanner),
+        // outbox messages are addressed to other partitions, so none of the columns allows
+        // to prune the scanned partitions
+        FirstMatchingPartitionKeyExtractor::default(),
+    );
+    ctx.register_partitioned_table(NAME, Arc::new(table))
+}
+
+#[derive(Debug, Clone)]
+struct OutboxScanner;
+
+impl ScanLocalPartition for OutboxScanner {
+    type Builder = SysOutboxBuilder;
+    type Item<'a> = (PartitionId, u64, OutboxMessage);
+    type ConversionError = std::convert::Infallible;
+
+    fn for_each_row<
+        F: for<'a> FnMut(
+                Self::Item<'a>,
+            ) -> std::ops::ControlFlow<Result<(), Self::ConversionError>>
+            + Send
+            + Sync
+            + 'static,
+    >(
+        partition_store: &PartitionStore,
+        _range: RangeInclusive<PartitionKey>,
+        mut f: F,
+    ) -> Result<impl Future<Output = restate_storage_api::Result<()>> + Send, StorageError> {
+        // the outbox is keyed by partition id and always scanned as a whole
+        let partition_id = partition_store.partition_id();
+        partition_store.for_each_outbox(move |(sequence_number, outbox_message)| {
+            f((partition_id, sequence_number, outbox_message)).map_break(Result::unwrap)
+        })
+    }
+
+    fn append_row<'a>(
+        row_builder: &mut Self::Builder,
+        value: Self::Item<'a>,
+    ) -> Result<(), Self::ConversionError> {
+        let (partition_id, sequence_number, outbox_message) = value;
+        append_outbox_row(row_builder, partition_id, sequence_number, outbox_message);
+        Ok(())
+    }
+}
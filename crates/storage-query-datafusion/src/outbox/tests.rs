@@ -0,0 +1,80 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::mocks::*;
+use crate::row;
+use datafusion::arrow::array::{LargeStringArray, UInt64Array};
+use datafusion::arrow::record_batch::RecordBatch;
+use futures::StreamExt;
+use googletest::all;
+use googletest::prelude::{assert_that, eq};
+use restate_storage_api::Transaction;
+use restate_storage_api::outbox_table::{OutboxMessage, WriteOutboxTable};
+use restate_types::identifiers::WithPartitionKey;
+use restate_types::invocation::{InvocationTermination, ServiceInvocation, TerminationFlavor};
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_outbox() {
+    let mut engine = MockQueryEngine::create().await;
+
+    let mut tx = engine.partition_store().transaction();
+    let service_invocation = ServiceInvocation::mock();
+    tx.put_outbox_message(
+        0,
+        &OutboxMessage::ServiceInvocation(Box::new(service_invocation.clone())),
+    )
+    .unwrap();
+    let terminated_id = service_invocation.invocation_id;
+    tx.put_outbox_message(
+        1,
+        &OutboxMessage::InvocationTermination(InvocationTermination {
+            invocation_id: terminated_id,
+            flavor: TerminationFlavor::Cancel,
+            response_sink: None,
+        }),
+    )
+    .unwrap();
+    tx.commit().await.unwrap();
+
+    let records = engine
+        .execute("SELECT * FROM sys_outbox ORDER BY sequence_number")
+        .await
+        .unwrap()
+        .collect::<Vec<Result<RecordBatch, _>>>()
+        .await
+        .remove(0)
+        .unwrap();
+
+    assert_that!(
+        records,
+        all!(
+            row!(
+                0,
+                {
+                    "partition_id" => UInt64Array: eq(0),
+                    "sequence_number" => UInt64Array: eq(0),
+                    "kind" => LargeStringArray: eq("invocation"),
+                    "target_partition_key" => UInt64Array: eq(service_invocation.partition_key()),
+                    "target_id" => LargeStringArray: eq(service_invocation.invocation_id.to_string()),
+                    "target_service_name" => LargeStringArray: eq(service_invocation.invocation_target.service_name().to_string()),
+                    "target_handler_name" => LargeStringArray: eq(service_invocation.invocation_target.handler_name().to_string()),
+                }
+            ),
+            row!(
+                1,
+                {
+                    "sequence_number" => UInt64Array: eq(1),
+                    "kind" => LargeStringArray: eq("termination"),
+                    "target_id" => LargeStringArray: eq(terminated_id.to_string()),
+                }
+            )
+        )
+    );
+}
@@ -12,8 +12,10 @@ use anyhow::Context;
 use datafusion::common::ScalarValue;
 use datafusion::logical_expr::expr::InList;
 use datafusion::logical_expr::{BinaryExpr, Expr, Operator, col};
+use itertools::Itertools;
 use restate_types::identifiers::partitioner::HashPartitioner;
 use restate_types::identifiers::{InvocationId, PartitionKey, WithPartitionKey};
+use restate_types::net::remote_query_scanner::{ScanKeyFilter, ScanKeyFilterService};
 use std::borrow::Cow;
 use std::collections::{BTreeSet, HashSet};
 use std::fmt::{Debug, Formatter};
@@ -72,6 +74,81 @@ impl PartitionKeyExtractor for FirstMatchingPartitionKeyExtractor {
     }
 }
 
+/// Above this number of keys, scans are not restricted by a [`ScanKeyFilter`].
+const MAX_SCAN_KEY_FILTER_LEN: usize = 4096;
+
+/// Extracts the keys of point lookups and prefix scans from the query predicates.
+#[derive(Debug, Default)]
+pub struct ScanKeyFilterExtractor {
+    invocation_id: Option<Expr>,
+    service: Option<(Expr, Expr)>,
+}
+
+impl ScanKeyFilterExtractor {
+    pub fn with_invocation_id(self, column_name: impl Into<String>) -> Self {
+        Self {
+            invocation_id: Some(col(column_name.into())),
+            ..self
+        }
+    }
+
+    /// Services are only extracted if the predicates select both the service name and key.
+    pub fn with_service(
+        self,
+        service_name_column: impl Into<String>,
+        service_key_column: impl Into<String>,
+    ) -> Self {
+        Self {
+            service: Some((
+                col(service_name_column.into()),
+                col(service_key_column.into()),
+            )),
+            ..self
+        }
+    }
+
+    pub fn extract(&self, filters: &[Expr]) -> anyhow::Result<ScanKeyFilter> {
+        let mut key_filter = ScanKeyFilter::default();
+
+        if let Some(column) = &self.invocation_id {
+            match find_utf8_values(filters, column) {
+                Some(values) if values.len() <= MAX_SCAN_KEY_FILTER_LEN => {
+                    key_filter.invocation_ids = values
+                        .iter()
+                        .map(|value| {
+                            InvocationId::from_str(value).context("non valid invocation id")
+                        })
+                        .collect::<anyhow::Result<_>>()?;
+                }
+                _ => {}
+            }
+        }
+
+        if let Some((service_name_column, service_key_column)) = &self.service {
+            match (
+                find_utf8_values(filters, service_name_column),
+                find_utf8_values(filters, service_key_column),
+            ) {
+                (Some(service_names), Some(keys))
+                    if service_names.len() * keys.len() <= MAX_SCAN_KEY_FILTER_LEN =>
+                {
+                    key_filter.services = service_names
+                        .iter()
+                        .cartesian_product(&keys)
+                        .map(|(service_name, key)| ScanKeyFilterService {
+                            service_name: service_name.clone(),
+                            key: key.clone(),
+                        })
+                        .collect();
+                }
+                _ => {}
+            }
+        }
+
+        Ok(key_filter)
+    }
+}
+
 pub(crate) struct MatchingColumnExtractor<F> {
     column: Expr,
     extractor: F,
@@ -98,33 +175,47 @@ where
     /// Then use the provided extractor to convert the textual value to a
     /// partition_key
     fn try_extract(&self, filters: &[Expr]) -> anyhow::Result<Option<BTreeSet<PartitionKey>>> {
-        'filters: for filter in filters {
-            let Some(filter_as_inlist) = as_inlist(filter, 5) else {
-                continue;
-            };
+        let Some(values) = find_utf8_values(filters, &self.column) else {
+            return Ok(None);
+        };
 
-            if *filter_as_inlist.expr != self.column {
-                continue;
-            }
+        let f = &self.extractor;
+        let list_keys = values
+            .iter()
+            .map(|value| f(value))
+            .collect::<anyhow::Result<BTreeSet<_>>>()?;
 
-            let mut list_keys = BTreeSet::new();
+        Ok(Some(list_keys))
+    }
+}
 
-            for item in &filter_as_inlist.list {
-                if let Expr::Literal(ScalarValue::LargeUtf8(Some(value)), _) = item {
-                    let f = &self.extractor;
-                    let pk = f(value)?;
-                    list_keys.insert(pk);
-                } else {
-                    // items in the list are ORed. If we can't parse one, we can't apply this list
-                    continue 'filters;
-                }
-            }
+/// Find an expression in the form of `$column = '...'`, `$column IN ('...', ...)` or the
+/// equivalent disjunction, and return the string values the column is compared to.
+fn find_utf8_values(filters: &[Expr], column: &Expr) -> Option<Vec<String>> {
+    'filters: for filter in filters {
+        let Some(filter_as_inlist) = as_inlist(filter, 5) else {
+            continue;
+        };
 
-            return Ok(Some(list_keys));
+        if *filter_as_inlist.expr != *column {
+            continue;
         }
 
-        Ok(None)
+        let mut values = Vec::with_capacity(filter_as_inlist.list.len());
+
+        for item in &filter_as_inlist.list {
+            if let Expr::Literal(ScalarValue::LargeUtf8(Some(value)), _) = item {
+                values.push(value.clone());
+            } else {
+                // items in the list are ORed. If we can't parse one, we can't apply this list
+                continue 'filters;
+            }
+        }
+
+        return Some(values);
     }
+
+    None
 }
 
 /// Try to convert an expression to an in-list expression, recursively handling OR if needed
@@ -226,7 +317,9 @@ impl PartitionKeyExtractor for IdentityPartitionKeyExtractor {
 
 #[cfg(test)]
 mod tests {
-    use crate::partition_filter::{FirstMatchingPartitionKeyExtractor, PartitionKeyExtractor};
+    use crate::partition_filter::{
+        FirstMatchingPartitionKeyExtractor, PartitionKeyExtractor, ScanKeyFilterExtractor,
+    };
     use datafusion::common::ScalarValue;
     use datafusion::logical_expr::{Expr, col, or};
     use restate_types::identifiers::{InvocationId, ServiceId, WithPartitionKey};
@@ -439,6 +532,63 @@ mod tests {
         assert_eq!(None, got_keys);
     }
 
+    #[test]
+    fn test_scan_key_filter_invocation_ids() {
+        let extractor = ScanKeyFilterExtractor::default().with_invocation_id("id");
+
+        let invocation_id_1 = InvocationId::mock_random();
+        let invocation_id_2 = InvocationId::mock_random();
+
+        let key_filter = extractor
+            .extract(&[col("id").in_list(
+                vec![
+                    utf8_lit(invocation_id_1.to_string()),
+                    utf8_lit(invocation_id_2.to_string()),
+                ],
+                false,
+            )])
+            .expect("extract");
+
+        assert_eq!(
+            vec![invocation_id_1, invocation_id_2],
+            key_filter.invocation_ids
+        );
+        assert!(key_filter.services.is_empty());
+    }
+
+    #[test]
+    fn test_scan_key_filter_services() {
+        let extractor =
+            ScanKeyFilterExtractor::default().with_service("service_name", "service_key");
+
+        let key_filter = extractor
+            .extract(&[
+                col("service_name").eq(utf8_lit("greeter")),
+                col("service_key").in_list(vec![utf8_lit("key-1"), utf8_lit("key-2")], false),
+            ])
+            .expect("extract");
+
+        assert_eq!(
+            vec![
+                ServiceId::new("greeter", "key-1"),
+                ServiceId::new("greeter", "key-2")
+            ],
+            key_filter.service_ids()
+        );
+    }
+
+    #[test]
+    fn test_scan_key_filter_requires_service_name_and_key() {
+        let extractor =
+            ScanKeyFilterExtractor::default().with_service("service_name", "service_key");
+
+        let key_filter = extractor
+            .extract(&[col("service_key").eq(utf8_lit("key-1"))])
+            .expect("extract");
+
+        assert!(key_filter.is_empty());
+    }
+
     fn utf8_lit(value: impl Into<String>) -> Expr {
         Expr::Literal(ScalarValue::LargeUtf8(Some(value.into())), None)
     }
@@ -21,6 +21,7 @@ use datafusion::physical_plan::stream::RecordBatchReceiverStream;
 use restate_partition_store::{PartitionStore, PartitionStoreManager};
 use restate_storage_api::StorageError;
 use restate_types::identifiers::{PartitionId, PartitionKey};
+use restate_types::net::remote_query_scanner::ScanKeyFilter;
 
 use crate::table_providers::ScanPartition;
 use crate::table_util::BatchSender;
@@ -41,6 +42,23 @@ pub trait ScanLocalPartition: Send + Sync + Debug + 'static {
         f: F,
     ) -> Result<impl Future<Output = restate_storage_api::Result<()>> + Send, StorageError>;
 
+    /// Like [`ScanLocalPartition::for_each_row`], but allowed to skip the rows not matching the
+    /// `key_filter`. Tables keyed by invocation or service id override this to look up the
+    /// filtered keys directly, the default implementation scans the whole range.
+    fn for_each_matching_row<
+        F: for<'a> FnMut(Self::Item<'a>) -> ControlFlow<Result<(), Self::ConversionError>>
+            + Send
+            + Sync
+            + 'static,
+    >(
+        partition_store: &PartitionStore,
+        range: RangeInclusive<PartitionKey>,
+        _key_filter: ScanKeyFilter,
+        f: F,
+    ) -> Result<impl Future<Output = restate_storage_api::Result<()>> + Send, StorageError> {
+        Self::for_each_row(partition_store, range, f)
+    }
+
     fn append_row<'a>(
         row_builder: &mut Self::Builder,
         value: Self::Item<'a>,
@@ -75,6 +93,7 @@ where
         &self,
         partition_id: PartitionId,
         range: RangeInclusive<PartitionKey>,
+        key_filter: ScanKeyFilter,
         projection: SchemaRef,
         batch_size: usize,
         mut limit: Option<usize>,
@@ -95,7 +114,7 @@ where
             // will send the last batch on Drop.
             let mut batch_sender = BatchSender::new(projection.clone(), tx);
 
-            S::for_each_row(&partition_store, range, move |row| {
+            S::for_each_matching_row(&partition_store, range, key_filter, move |row| {
                 if let Some(0) = limit {
                     return ControlFlow::Break(Ok(()));
                 }
@@ -141,10 +160,18 @@ where
         &self,
         partition_id: PartitionId,
         range: RangeInclusive<PartitionKey>,
+        key_filter: ScanKeyFilter,
         projection: SchemaRef,
         batch_size: usize,
         limit: Option<usize>,
     ) -> anyhow::Result<SendableRecordBatchStream> {
-        self.scan_partition(partition_id, range, projection, batch_size, limit)
+        self.scan_partition(
+            partition_id,
+            range,
+            key_filter,
+            projection,
+            batch_size,
+            limit,
+        )
     }
 }
@@ -0,0 +1,280 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::num::NonZeroUsize;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Context, Poll, ready};
+use std::time::Duration;
+
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::DataFusionError;
+use datafusion::execution::memory_pool::{MemoryConsumer, MemoryPool, MemoryReservation};
+use datafusion::execution::{RecordBatchStream, SendableRecordBatchStream};
+use futures::Stream;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::{Instant, Sleep};
+
+/// Bounds the number of queries executing concurrently, queueing the ones in excess.
+#[derive(Debug)]
+pub(crate) struct QueryAdmission {
+    running: Arc<Semaphore>,
+    queued: AtomicUsize,
+    max_queued: usize,
+}
+
+impl QueryAdmission {
+    pub fn new(max_concurrent: NonZeroUsize, max_queued: usize) -> Self {
+        Self {
+            running: Arc::new(Semaphore::new(max_concurrent.get())),
+            queued: AtomicUsize::new(0),
+            max_queued,
+        }
+    }
+
+    /// Waits for the query to be admitted. The query runs until the returned permit is dropped.
+    pub async fn admit(&self) -> Result<OwnedSemaphorePermit, DataFusionError> {
+        if let Ok(permit) = Arc::clone(&self.running).try_acquire_owned() {
+            return Ok(permit);
+        }
+
+        if self.queued.fetch_add(1, Ordering::Relaxed) >= self.max_queued {
+            self.queued.fetch_sub(1, Ordering::Relaxed);
+            return Err(DataFusionError::ResourcesExhausted(format!(
+                "too many concurrent queries, {} queries are already waiting to be executed",
+                self.max_queued
+            )));
+        }
+        let _queued = QueuedGuard(&self.queued);
+
+        Ok(Arc::clone(&self.running)
+            .acquire_owned()
+            .await
+            .expect("query admission semaphore is never closed"))
+    }
+}
+
+struct QueuedGuard<'a>(&'a AtomicUsize);
+
+impl Drop for QueuedGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Memory pool of a single query, capping its share of the memory pool shared by all queries.
+#[derive(Debug)]
+pub(crate) struct QueryMemoryPool {
+    inner: Arc<dyn MemoryPool>,
+    limit: usize,
+    used: AtomicUsize,
+}
+
+impl QueryMemoryPool {
+    pub fn new(inner: Arc<dyn MemoryPool>, limit: NonZeroUsize) -> Self {
+        Self {
+            inner,
+            limit: limit.get(),
+            used: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl MemoryPool for QueryMemoryPool {
+    fn register(&self, consumer: &MemoryConsumer) {
+        self.inner.register(consumer)
+    }
+
+    fn unregister(&self, consumer: &MemoryConsumer) {
+        self.inner.unregister(consumer)
+    }
+
+    fn grow(&self, reservation: &MemoryReservation, additional: usize) {
+        self.inner.grow(reservation, additional);
+        self.used.fetch_add(additional, Ordering::Relaxed);
+    }
+
+    fn shrink(&self, reservation: &MemoryReservation, shrink: usize) {
+        self.inner.shrink(reservation, shrink);
+        self.used.fetch_sub(shrink, Ordering::Relaxed);
+    }
+
+    fn try_grow(
+        &self,
+        reservation: &MemoryReservation,
+        additional: usize,
+    ) -> datafusion::common::Result<()> {
+        self.used
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                used.checked_add(additional)
+                    .filter(|new_used| *new_used <= self.limit)
+            })
+            .map_err(|used| {
+                DataFusionError::ResourcesExhausted(format!(
+                    "Failed to allocate additional {additional} bytes for {}: the query already uses {used} bytes of its {} bytes memory limit",
+                    reservation.consumer().name(),
+                    self.limit
+                ))
+            })?;
+
+        if let Err(err) = self.inner.try_grow(reservation, additional) {
+            self.used.fetch_sub(additional, Ordering::Relaxed);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    fn reserved(&self) -> usize {
+        self.used.load(Ordering::Relaxed)
+    }
+}
+
+/// Results of an admitted query. Holds the admission permit until the results are dropped, and
+/// aborts the query once its deadline is reached.
+pub(crate) struct GovernedRecordBatchStream {
+    schema: SchemaRef,
+    /// Dropped on timeout, which cancels the query and its remote scans
+    inner: Option<SendableRecordBatchStream>,
+    deadline: Option<Pin<Box<Sleep>>>,
+    timeout: Duration,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl GovernedRecordBatchStream {
+    pub fn new(
+        inner: SendableRecordBatchStream,
+        permit: OwnedSemaphorePermit,
+        deadline: Option<(Instant, Duration)>,
+    ) -> Self {
+        let (deadline, timeout) = match deadline {
+            Some((deadline, timeout)) => {
+                (Some(Box::pin(tokio::time::sleep_until(deadline))), timeout)
+            }
+            None => (None, Duration::ZERO),
+        };
+
+        Self {
+            schema: inner.schema(),
+            inner: Some(inner),
+            deadline,
+            timeout,
+            _permit: permit,
+        }
+    }
+}
+
+pub(crate) fn timeout_error(timeout: Duration) -> DataFusionError {
+    DataFusionError::ResourcesExhausted(format!("query timed out after {timeout:?}"))
+}
+
+impl Stream for GovernedRecordBatchStream {
+    type Item = datafusion::common::Result<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let Some(inner) = this.inner.as_mut() else {
+            return Poll::Ready(None);
+        };
+
+        if let Some(deadline) = this.deadline.as_mut()
+            && deadline.as_mut().poll(cx).is_ready()
+        {
+            this.inner = None;
+            return Poll::Ready(Some(Err(timeout_error(this.timeout))));
+        }
+
+        let next = ready!(inner.as_mut().poll_next(cx));
+        if next.is_none() {
+            // release the resources of the query as soon as it completes
+            this.inner = None;
+        }
+        Poll::Ready(next)
+    }
+}
+
+impl RecordBatchStream for GovernedRecordBatchStream {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use datafusion::arrow::datatypes::Schema;
+    use datafusion::execution::memory_pool::GreedyMemoryPool;
+    use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+    use futures::{StreamExt, stream};
+    use googletest::prelude::*;
+
+    #[restate_core::test]
+    async fn queries_in_excess_are_queued_then_rejected() {
+        let admission = QueryAdmission::new(NonZeroUsize::new(1).unwrap(), 1);
+
+        let running = admission.admit().await.unwrap();
+        let mut queued = std::pin::pin!(admission.admit());
+        assert!(futures::poll!(queued.as_mut()).is_pending());
+
+        assert_that!(
+            admission.admit().await,
+            err(pat!(DataFusionError::ResourcesExhausted(anything())))
+        );
+
+        drop(running);
+        assert_that!(queued.await, ok(anything()));
+    }
+
+    #[test]
+    fn query_memory_is_capped() {
+        let shared: Arc<dyn MemoryPool> = Arc::new(GreedyMemoryPool::new(1024));
+        let pool: Arc<dyn MemoryPool> = Arc::new(QueryMemoryPool::new(
+            shared.clone(),
+            NonZeroUsize::new(100).unwrap(),
+        ));
+
+        let mut reservation = MemoryConsumer::new("test").register(&pool);
+        assert_that!(reservation.try_grow(100), ok(anything()));
+        assert_that!(
+            reservation.try_grow(1),
+            err(pat!(DataFusionError::ResourcesExhausted(anything())))
+        );
+        assert_that!(shared.reserved(), eq(100));
+
+        reservation.shrink(50);
+        assert_that!(reservation.try_grow(50), ok(anything()));
+        assert_that!(pool.reserved(), eq(100));
+    }
+
+    #[restate_core::test(start_paused = true)]
+    async fn query_is_aborted_at_deadline() {
+        let schema = Arc::new(Schema::empty());
+        let inner = Box::pin(RecordBatchStreamAdapter::new(
+            schema,
+            stream::pending::<datafusion::common::Result<RecordBatch>>(),
+        ));
+        let permit = Arc::new(Semaphore::new(1)).try_acquire_owned().unwrap();
+        let timeout = Duration::from_secs(10);
+
+        let mut stream = GovernedRecordBatchStream::new(
+            inner,
+            permit,
+            Some((Instant::now() + timeout, timeout)),
+        );
+
+        assert_that!(
+            stream.next().await,
+            some(err(pat!(DataFusionError::ResourcesExhausted(anything()))))
+        );
+        assert!(stream.next().await.is_none());
+    }
+}
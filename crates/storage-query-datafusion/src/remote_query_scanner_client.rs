@@ -25,7 +25,8 @@ use restate_types::NodeId;
 use restate_types::identifiers::{PartitionId, PartitionKey};
 use restate_types::net::remote_query_scanner::{
     RemoteQueryScannerClose, RemoteQueryScannerNext, RemoteQueryScannerNextResult,
-    RemoteQueryScannerOpen, RemoteQueryScannerOpened, ScannerBatch, ScannerFailure, ScannerId,
+    RemoteQueryScannerOpen, RemoteQueryScannerOpened, ScanKeyFilter, ScannerBatch, ScannerFailure,
+    ScannerId,
 };
 
 use crate::{decode_record_batch, encode_schema};
@@ -137,6 +138,7 @@ pub fn remote_scan_as_datafusion_stream(
     target_node_id: NodeId,
     partition_id: PartitionId,
     range: RangeInclusive<PartitionKey>,
+    key_filter: ScanKeyFilter,
     table_name: String,
     projection_schema: SchemaRef,
     batch_size: usize,
@@ -157,6 +159,7 @@ pub fn remote_scan_as_datafusion_stream(
             projection_schema_bytes: encode_schema(&projection_schema),
             limit: limit.map(|limit| u64::try_from(limit).expect("limit to fit in a u64")),
             batch_size: u64::try_from(batch_size).expect("batch_size to fit in a u64"),
+            key_filter,
         };
 
         // RemoteScanner will auto close on drop. Please call forget() if you don't need this
@@ -21,6 +21,7 @@ use restate_core::Metadata;
 use restate_core::partitions::PartitionRouting;
 use restate_types::NodeId;
 use restate_types::identifiers::{PartitionId, PartitionKey};
+use restate_types::net::remote_query_scanner::ScanKeyFilter;
 
 use crate::remote_query_scanner_client::{RemoteScannerService, remote_scan_as_datafusion_stream};
 use crate::table_providers::ScanPartition;
@@ -179,6 +180,7 @@ impl ScanPartition for RemotePartitionsScanner {
         &self,
         partition_id: PartitionId,
         range: RangeInclusive<PartitionKey>,
+        key_filter: ScanKeyFilter,
         projection: SchemaRef,
         batch_size: usize,
         limit: Option<usize>,
@@ -188,13 +190,21 @@ impl ScanPartition for RemotePartitionsScanner {
                 let scanner = self.manager.local_partition_scanner(&self.table_name).ok_or_else(
                     ||anyhow!("was expecting a local partition to be present on this node. It could be that this partition is being opened right now.")
                 )?;
-                Ok(scanner.scan_partition(partition_id, range, projection, batch_size, limit)?)
+                Ok(scanner.scan_partition(
+                    partition_id,
+                    range,
+                    key_filter,
+                    projection,
+                    batch_size,
+                    limit,
+                )?)
             }
             PartitionLocation::Remote { node_id } => Ok(remote_scan_as_datafusion_stream(
                 self.manager.remote_scanner.clone(),
                 node_id,
                 partition_id,
                 range,
+                key_filter,
                 self.table_name.clone(),
                 projection,
                 batch_size,
@@ -13,7 +13,7 @@ use std::time::Duration;
 
 use anyhow::Context;
 use datafusion::execution::SendableRecordBatchStream;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, oneshot};
 use tokio_stream::StreamExt as TokioStreamExt;
 use tracing::{debug, warn};
 
@@ -32,7 +32,21 @@ const SCANNER_EXPIRATION: Duration = Duration::from_secs(60);
 
 type NextReciprocal = Reciprocal<Oneshot<RemoteQueryScannerNextResult>>;
 
-pub(crate) type ScannerHandle = mpsc::UnboundedSender<NextReciprocal>;
+/// Handle of a scanner registered in the scanners map. Removing the handle from the map, e.g.
+/// when the client closes the scanner, cancels an in-progress scan.
+pub(crate) struct ScannerHandle {
+    requests: mpsc::UnboundedSender<NextReciprocal>,
+    _cancel_on_drop: oneshot::Sender<()>,
+}
+
+impl ScannerHandle {
+    pub fn send(
+        &self,
+        reciprocal: NextReciprocal,
+    ) -> Result<(), mpsc::error::SendError<NextReciprocal>> {
+        self.requests.send(reciprocal)
+    }
+}
 
 // Tracks a single scanner's lifecycle running in [`RemoteQueryScannerServer`]
 pub(crate) struct ScannerTask {
@@ -40,6 +54,8 @@ pub(crate) struct ScannerTask {
     scanner_id: ScannerId,
     stream: SendableRecordBatchStream,
     rx: mpsc::UnboundedReceiver<NextReciprocal>,
+    /// Resolves once the scanner has been removed from the scanners map
+    cancelled: oneshot::Receiver<()>,
     scanners: Weak<ScannerMap>,
 }
 
@@ -59,6 +75,7 @@ impl ScannerTask {
         let stream = scanner.scan_partition(
             request.partition_id,
             request.range.clone(),
+            request.key_filter.clone(),
             Arc::new(schema),
             usize::try_from(request.batch_size).expect("batch_size to fit in a usize"),
             request
@@ -67,15 +84,23 @@ impl ScannerTask {
         )?;
 
         let (tx, rx) = mpsc::unbounded_channel();
+        let (cancel_tx, cancel_rx) = oneshot::channel();
         let mut task = Self {
             peer,
             scanner_id,
             stream,
             rx,
+            cancelled: cancel_rx,
             scanners: Arc::downgrade(scanners),
         };
 
-        scanners.insert(scanner_id, tx);
+        scanners.insert(
+            scanner_id,
+            ScannerHandle {
+                requests: tx,
+                _cancel_on_drop: cancel_tx,
+            },
+        );
 
         // make sure we add before we spawn.
         TaskCenter::spawn_unmanaged(TaskKind::DfScanner, "df-scanner-task", async move {
@@ -124,7 +149,21 @@ impl ScannerTask {
                 return;
             }
 
-            let record_batch = match self.stream.next().await {
+            // Producing a batch can take long for selective scans, stop as soon as the query is
+            // aborted by the client, or the client is gone.
+            let next = tokio::select! {
+                next = self.stream.next() => next,
+                _ = &mut self.cancelled => {
+                    debug!("Scanner {} closed while scanning", self.scanner_id);
+                    return;
+                }
+                _ = &mut watch_fut => {
+                    debug!("Removing scanner due to peer {} being dead", self.peer);
+                    return;
+                }
+            };
+
+            let record_batch = match next {
                 Some(Ok(record_batch)) => record_batch,
                 Some(Err(e)) => {
                     warn!("Error while scanning {}: {e}", self.scanner_id);
@@ -13,14 +13,16 @@ use std::ops::RangeInclusive;
 use std::sync::Arc;
 
 use bytes::Bytes;
+use futures::future::Either;
 
 use restate_partition_store::{PartitionStore, PartitionStoreManager};
 use restate_storage_api::StorageError;
 use restate_storage_api::state_table::ScanStateTable;
 use restate_types::identifiers::{PartitionKey, ServiceId};
+use restate_types::net::remote_query_scanner::ScanKeyFilter;
 
 use crate::context::{QueryContext, SelectPartitions};
-use crate::partition_filter::FirstMatchingPartitionKeyExtractor;
+use crate::partition_filter::{FirstMatchingPartitionKeyExtractor, ScanKeyFilterExtractor};
 use crate::partition_store_scanner::{LocalPartitionsScanner, ScanLocalPartition};
 use crate::remote_query_scanner_manager::RemoteScannerManager;
 use crate::state::row::append_state_row;
@@ -46,7 +48,8 @@ pub(crate) fn register_self(
         state_sort_order(),
         remote_scanner_manager.create_distributed_scanner(NAME, local_scanner),
         FirstMatchingPartitionKeyExtractor::default().with_service_key("service_key"),
-    );
+    )
+    .with_key_filter(ScanKeyFilterExtractor::default().with_service("service_name", "service_key"));
     ctx.register_partitioned_table(NAME, Arc::new(table))
 }
 
@@ -73,6 +76,29 @@ impl ScanLocalPartition for StateScanner {
         partition_store.for_each_user_state(range, move |item| f(item).map_break(Result::unwrap))
     }
 
+    fn for_each_matching_row<
+        F: for<'a> FnMut(
+                Self::Item<'a>,
+            ) -> std::ops::ControlFlow<Result<(), Self::ConversionError>>
+            + Send
+            + Sync
+            + 'static,
+    >(
+        partition_store: &PartitionStore,
+        range: RangeInclusive<PartitionKey>,
+        key_filter: ScanKeyFilter,
+        mut f: F,
+    ) -> Result<impl Future<Output = restate_storage_api::Result<()>> + Send, StorageError> {
+        if key_filter.services.is_empty() {
+            return Ok(Either::Left(Self::for_each_row(partition_store, range, f)?));
+        }
+
+        Ok(Either::Right(partition_store.for_each_user_state_of(
+            key_filter.service_ids(),
+            move |item| f(item).map_break(Result::unwrap),
+        )?))
+    }
+
     fn append_row<'a>(
         row_builder: &mut Self::Builder,
         value: Self::Item<'a>,
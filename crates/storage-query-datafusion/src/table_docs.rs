@@ -9,8 +9,9 @@
 // by the Apache License, Version 2.0.
 
 use crate::{
-    deployment, idempotency, inbox, invocation_state, invocation_status, journal, journal_events,
-    keyed_service_status, promise, service, state,
+    deployment, idempotency, inbox, invocation_counts, invocation_history, invocation_state,
+    invocation_status, journal, journal_events, keyed_service_status, outbox, promise, service,
+    state, timer,
 };
 use std::borrow::Cow;
 
@@ -23,8 +24,12 @@ pub const ALL_TABLE_DOCS: &[StaticTableDocs] = &[
     journal_events::schema::TABLE_DOCS,
     keyed_service_status::schema::TABLE_DOCS,
     inbox::schema::TABLE_DOCS,
+    timer::schema::TABLE_DOCS,
+    outbox::schema::TABLE_DOCS,
     idempotency::schema::TABLE_DOCS,
     promise::schema::TABLE_DOCS,
+    invocation_history::schema::TABLE_DOCS,
+    invocation_counts::schema::TABLE_DOCS,
     service::schema::TABLE_DOCS,
     deployment::schema::TABLE_DOCS,
 ];
@@ -28,10 +28,13 @@ use datafusion::physical_plan::{
 use futures::stream::{self, StreamExt, TryStreamExt};
 
 use restate_types::identifiers::{PartitionId, PartitionKey};
+use restate_types::net::remote_query_scanner::ScanKeyFilter;
 use restate_types::partition_table::Partition;
 
 use crate::context::SelectPartitions;
-use crate::partition_filter::{FirstMatchingPartitionKeyExtractor, PartitionKeyExtractor};
+use crate::partition_filter::{
+    FirstMatchingPartitionKeyExtractor, PartitionKeyExtractor, ScanKeyFilterExtractor,
+};
 use crate::table_util::{find_sort_columns, make_ordering};
 
 pub trait ScanPartition: Send + Sync + Debug + 'static {
@@ -39,6 +42,7 @@ pub trait ScanPartition: Send + Sync + Debug + 'static {
         &self,
         partition_id: PartitionId,
         range: RangeInclusive<PartitionKey>,
+        key_filter: ScanKeyFilter,
         projection: SchemaRef,
         batch_size: usize,
         limit: Option<usize>,
@@ -52,6 +56,7 @@ pub(crate) struct PartitionedTableProvider<T, S> {
     ordering: Vec<String>,
     partition_scanner: T,
     partition_key_extractor: FirstMatchingPartitionKeyExtractor,
+    key_filter_extractor: ScanKeyFilterExtractor,
     statistics: Statistics,
 }
 
@@ -70,6 +75,7 @@ impl<T, S> PartitionedTableProvider<T, S> {
             ordering,
             partition_scanner,
             partition_key_extractor,
+            key_filter_extractor: ScanKeyFilterExtractor::default(),
             statistics,
         }
     }
@@ -77,6 +83,14 @@ impl<T, S> PartitionedTableProvider<T, S> {
     pub(crate) fn with_statistics(self, statistics: Statistics) -> Self {
         Self { statistics, ..self }
     }
+
+    /// Pushes down the keys selected by the query predicates into the partition scans.
+    pub(crate) fn with_key_filter(self, key_filter_extractor: ScanKeyFilterExtractor) -> Self {
+        Self {
+            key_filter_extractor,
+            ..self
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -153,6 +167,11 @@ where
             .try_extract(filters)
             .map_err(|e| DataFusionError::External(e.into()))?;
 
+        let key_filter = self
+            .key_filter_extractor
+            .extract(filters)
+            .map_err(|e| DataFusionError::External(e.into()))?;
+
         let physical_partitions: Vec<(PartitionId, Partition)> = self
             .partition_selector
             .get_live_partitions()
@@ -222,6 +241,7 @@ where
         Ok(Arc::new(PartitionedExecutionPlan {
             logical_partitions,
             projected_schema,
+            key_filter,
             limit,
             scanner: self.partition_scanner.clone(),
             plan,
@@ -246,6 +266,7 @@ where
 struct PartitionedExecutionPlan<T> {
     logical_partitions: Vec<LogicalPartition>,
     projected_schema: SchemaRef,
+    key_filter: ScanKeyFilter,
     limit: Option<usize>,
     scanner: T,
     plan: PlanProperties,
@@ -316,13 +337,16 @@ where
             .map({
                 let scanner = self.scanner.clone();
                 let schema = self.projected_schema.clone();
+                let key_filter = self.key_filter.clone();
                 let limit = self.limit;
                 let batch_size = context.session_config().batch_size();
                 move |(partition_id, partition)| {
+                    let key_filter = key_filter.for_range(&partition.key_range);
                     scanner
                         .scan_partition(
                             partition_id,
                             partition.key_range,
+                            key_filter,
                             schema.clone(),
                             batch_size,
                             limit,
@@ -0,0 +1,46 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::schema::SysTimerBuilder;
+use restate_storage_api::timer_table::{Timer, TimerKey};
+use restate_types::identifiers::WithPartitionKey;
+
+#[inline]
+pub(crate) fn append_timer_row(builder: &mut SysTimerBuilder, timer_key: TimerKey, timer: Timer) {
+    let mut row = builder.row();
+
+    row.partition_key(timer.partition_key());
+    if row.is_id_defined() {
+        row.fmt_id(timer.invocation_id());
+    }
+    row.fire_at(timer_key.timestamp as i64);
+
+    match timer {
+        Timer::Invoke(service_invocation) => {
+            row.kind("invoke");
+            if row.is_target_defined() {
+                row.fmt_target(&service_invocation.invocation_target);
+            }
+        }
+        Timer::NeoInvoke(_) => {
+            row.kind("invoke");
+        }
+        Timer::CompleteJournalEntry(_, journal_index, _) => {
+            row.kind("complete_journal_entry");
+            row.journal_index(journal_index);
+        }
+        Timer::CleanInvocationStatus(_) => {
+            row.kind("clean_invocation_status");
+        }
+        Timer::CancelInvocation(_) => {
+            row.kind("cancel_invocation");
+        }
+    }
+}
@@ -0,0 +1,36 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::table_macro::*;
+
+use datafusion::arrow::datatypes::DataType;
+
+define_table!(sys_timer(
+    /// Internal column that is used for partitioning the services invocations. Can be ignored.
+    partition_key: DataType::UInt64,
+
+    /// [Invocation ID](/operate/invocation#invocation-identifier) of the invocation this timer belongs to.
+    id: DataType::LargeUtf8,
+
+    /// The kind of timer. Either `invoke` for delayed invocations, `complete_journal_entry` for
+    /// sleeps, `clean_invocation_status` for the removal of completed invocations once their
+    /// retention expired, or `cancel_invocation` for invocations exceeding their deadline.
+    kind: DataType::LargeUtf8,
+
+    /// Timestamp indicating when this timer fires.
+    fire_at: TimestampMillisecond,
+
+    /// The journal entry completed by this timer, if `kind = 'complete_journal_entry'`.
+    journal_index: DataType::UInt32,
+
+    /// Formatted invocation target of a delayed invocation, if known. For most delayed
+    /// invocations the target is stored with the invocation, join with `sys_invocation` on `id`.
+    target: DataType::LargeUtf8,
+));
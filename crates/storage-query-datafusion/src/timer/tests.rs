@@ -0,0 +1,71 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::mocks::*;
+use crate::row;
+use datafusion::arrow::array::{
+    LargeStringArray, TimestampMillisecondArray, UInt32Array, UInt64Array,
+};
+use datafusion::arrow::record_batch::RecordBatch;
+use futures::StreamExt;
+use googletest::all;
+use googletest::prelude::{assert_that, eq};
+use restate_storage_api::Transaction;
+use restate_storage_api::timer_table::{Timer, WriteTimerTable};
+use restate_types::identifiers::{InvocationId, WithPartitionKey};
+use restate_types::invocation::InvocationTarget;
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_timers() {
+    let mut engine = MockQueryEngine::create().await;
+
+    let mut tx = engine.partition_store().transaction();
+    let invocation_id_1 = InvocationId::mock_generate(&InvocationTarget::mock_service());
+    let (timer_key_1, timer_1) = Timer::complete_journal_entry(2000, invocation_id_1, 3, 0);
+    tx.put_timer(&timer_key_1, &timer_1).unwrap();
+    let invocation_id_2 = InvocationId::mock_generate(&InvocationTarget::mock_virtual_object());
+    let (timer_key_2, timer_2) = Timer::neo_invoke(1000, invocation_id_2);
+    tx.put_timer(&timer_key_2, &timer_2).unwrap();
+    tx.commit().await.unwrap();
+
+    let records = engine
+        .execute("SELECT * FROM sys_timer ORDER BY fire_at")
+        .await
+        .unwrap()
+        .collect::<Vec<Result<RecordBatch, _>>>()
+        .await
+        .remove(0)
+        .unwrap();
+
+    assert_that!(
+        records,
+        all!(
+            row!(
+                0,
+                {
+                    "partition_key" => UInt64Array: eq(invocation_id_2.partition_key()),
+                    "id" => LargeStringArray: eq(invocation_id_2.to_string()),
+                    "kind" => LargeStringArray: eq("invoke"),
+                    "fire_at" => TimestampMillisecondArray: eq(1000),
+                }
+            ),
+            row!(
+                1,
+                {
+                    "partition_key" => UInt64Array: eq(invocation_id_1.partition_key()),
+                    "id" => LargeStringArray: eq(invocation_id_1.to_string()),
+                    "kind" => LargeStringArray: eq("complete_journal_entry"),
+                    "fire_at" => TimestampMillisecondArray: eq(2000),
+                    "journal_index" => UInt32Array: eq(3),
+                }
+            )
+        )
+    );
+}
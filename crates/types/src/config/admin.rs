@@ -75,6 +75,17 @@ pub struct AdminOptions {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[cfg_attr(feature = "schemars", schemars(skip))]
     pub storage_accounting_update_interval: Option<NonZeroFriendlyDuration>,
+
+    /// # Drained deployments cleanup
+    ///
+    /// If set, deployments which have been drained for at least this duration are deleted
+    /// automatically. A deployment is drained when it doesn't receive new invocations anymore,
+    /// because newer revisions of all its services have been registered and no routing rule points
+    /// to it, and no in-flight invocation is pinned to it anymore.
+    ///
+    /// Requires the query engine. Default is `None`, drained deployments are not deleted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cleanup_drained_deployments_after: Option<NonZeroFriendlyDuration>,
 }
 
 impl AdminOptions {
@@ -133,6 +144,7 @@ impl Default for AdminOptions {
             disable_cluster_controller: false,
             disable_web_ui: false,
             storage_accounting_update_interval: None,
+            cleanup_drained_deployments_after: None,
         }
     }
 }
@@ -8,7 +8,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use std::num::{NonZeroU16, NonZeroUsize};
+use std::num::{NonZeroU16, NonZeroU32, NonZeroUsize};
 use std::path::PathBuf;
 use std::time::Duration;
 
@@ -20,9 +20,10 @@ use restate_serde_util::{ByteCount, NonZeroByteCount};
 use restate_time_util::{FriendlyDuration, NonZeroFriendlyDuration};
 
 use crate::logs::metadata::{NodeSetSize, ProviderKind};
+use crate::replicated_loglet::ErasureCoding;
 use crate::retries::RetryPolicy;
 
-use super::{CommonOptions, RocksDbOptions, RocksDbOptionsBuilder};
+use super::{CommonOptions, ObjectStoreOptions, RocksDbOptions, RocksDbOptionsBuilder};
 
 /// # Bifrost options
 #[serde_as]
@@ -41,6 +42,8 @@ pub struct BifrostOptions {
     pub local: LocalLogletOptions,
     /// Configuration of replicated loglet provider
     pub replicated_loglet: ReplicatedLogletOptions,
+    /// Configuration of the object-store tier for sealed loglet segments
+    pub object_store_loglet: ObjectStoreLogletOptions,
 
     /// # Read retry policy
     ///
@@ -77,6 +80,18 @@ pub struct BifrostOptions {
     #[cfg_attr(feature = "schemars", schemars(with = "ByteCount"))]
     pub record_cache_memory_size: ByteCount,
 
+    /// # Record compression threshold
+    ///
+    /// Records larger than this size are compressed with zstd when they are encoded for
+    /// appending to the log. If unset, records are not compressed.
+    ///
+    /// Enable this only once all the nodes of the cluster run a version that can read
+    /// compressed records.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<NonZeroByteCount>")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<NonZeroByteCount>"))]
+    record_compression_threshold: Option<NonZeroUsize>,
+
     /// # Disable Automatic Improvement
     ///
     /// When enabled, automatic improvement periodically checks with the loglet provider
@@ -102,6 +117,10 @@ impl BifrostOptions {
     pub fn apply_common(&mut self, common: &CommonOptions) {
         self.local.apply_common(common);
     }
+
+    pub fn record_compression_threshold(&self) -> Option<usize> {
+        self.record_compression_threshold.map(NonZeroUsize::get)
+    }
 }
 
 impl Default for BifrostOptions {
@@ -109,6 +128,7 @@ impl Default for BifrostOptions {
         Self {
             default_provider: ProviderKind::Replicated,
             replicated_loglet: ReplicatedLogletOptions::default(),
+            object_store_loglet: ObjectStoreLogletOptions::default(),
             local: LocalLogletOptions::default(),
             read_retry_policy: RetryPolicy::exponential(
                 Duration::from_millis(50),
@@ -121,6 +141,7 @@ impl Default for BifrostOptions {
             auto_recovery_interval: NonZeroFriendlyDuration::from_secs_unchecked(15),
             seal_retry_interval: NonZeroFriendlyDuration::from_secs_unchecked(2),
             record_cache_memory_size: ByteCount::from(250u64 * 1024 * 1024), // 250 MiB
+            record_compression_threshold: None,
             disable_auto_improvement: false,
         }
     }
@@ -310,6 +331,19 @@ pub struct ReplicatedLogletOptions {
     // hide the configuration option by excluding it from the Json schema
     #[cfg_attr(feature = "schemars", schemars(skip))]
     pub default_nodeset_size: NodeSetSize,
+
+    /// # Default erasure coding
+    ///
+    /// If set (e.g. `4+2`), new replicated loglets split each record into `data` fragments plus
+    /// `parity` fragments and store one fragment per node instead of full copies on every node of
+    /// the spread. Any `data` fragments are sufficient to reconstruct a record. Nodesets of
+    /// erasure-coded loglets have exactly `data + parity` nodes.
+    ///
+    /// Like `default-nodeset-size`, this value only impacts the cluster initial provisioning. To
+    /// update existing clusters use the `restatectl` utility.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    pub default_erasure_coding: Option<ErasureCoding>,
 }
 
 fn nodeset_size_is_zero(i: &NodeSetSize) -> bool {
@@ -343,6 +377,87 @@ impl Default for ReplicatedLogletOptions {
             readahead_records: NonZeroU16::new(20).unwrap(),
             readahead_trigger_ratio: 0.5,
             default_nodeset_size: NodeSetSize::default(),
+            default_erasure_coding: None,
+        }
+    }
+}
+
+/// # Object-store loglet options
+///
+/// Sealed segments of replicated loglets can be offloaded to an object store to keep a long
+/// log history without paying for log-server disk. Offloaded segments are served to readers
+/// directly from the object store.
+///
+/// Note that every node that reads from offloaded segments must be configured with the same
+/// destination.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, derive_builder::Builder)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "ObjectStoreLoglet", default))]
+#[serde(rename_all = "kebab-case")]
+#[builder(default)]
+pub struct ObjectStoreLogletOptions {
+    /// # Destination URL
+    ///
+    /// Base URL to which sealed loglet segments are offloaded. Supports `s3://` protocol scheme.
+    /// S3-compatible object stores must support ETag-based conditional writes.
+    ///
+    /// Offloaded segments record their destination in the log chain, every node reads them from
+    /// there using its own object-store options (e.g. credentials).
+    ///
+    /// Default: `None` - offloading is disabled
+    pub destination: Option<String>,
+
+    /// # Offload sealed segments
+    ///
+    /// When enabled, nodes running the `admin` role will periodically upload the records of
+    /// sealed replicated segments to the destination and replace the segments in the log chain.
+    /// Nodes that only need to read offloaded segments can keep this disabled. Offloading only
+    /// starts once all the nodes of the cluster run a version able to read offloaded segments.
+    pub offload_sealed_segments: bool,
+
+    /// # Offload check interval
+    ///
+    /// The interval at which the log chains are checked for sealed segments that can be offloaded.
+    pub offload_check_interval: NonZeroFriendlyDuration,
+
+    /// # Release grace period
+    ///
+    /// Time to wait after a segment has been offloaded before its records are trimmed from the
+    /// log-servers. This gives readers that are still reading from log-servers the chance to
+    /// observe the updated log chain.
+    pub release_grace_period: FriendlyDuration,
+
+    /// # Records per object
+    ///
+    /// The number of records stored in a single object. Larger objects reduce the number of
+    /// object store requests but increase the latency of reading the first record.
+    pub records_per_object: NonZeroU32,
+
+    #[serde(flatten)]
+    pub object_store: ObjectStoreOptions,
+
+    /// # Error retry policy
+    ///
+    /// A retry policy for dealing with retryable object store errors.
+    pub object_store_retry_policy: RetryPolicy,
+}
+
+impl Default for ObjectStoreLogletOptions {
+    fn default() -> Self {
+        Self {
+            destination: None,
+            offload_sealed_segments: false,
+            offload_check_interval: NonZeroFriendlyDuration::from_secs_unchecked(60),
+            release_grace_period: FriendlyDuration::from_secs(300),
+            records_per_object: NonZeroU32::new(1000).unwrap(),
+            object_store: ObjectStoreOptions::default(),
+            object_store_retry_policy: RetryPolicy::exponential(
+                Duration::from_millis(100),
+                2.,
+                Some(10),
+                Some(Duration::from_secs(10)),
+            ),
         }
     }
 }
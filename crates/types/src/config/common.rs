@@ -23,7 +23,7 @@ use restate_time_util::NonZeroFriendlyDuration;
 
 use super::{
     AwsLambdaOptions, GossipOptions, HttpOptions, InvalidConfigurationError, ObjectStoreOptions,
-    PerfStatsLevel, RocksDbOptions,
+    PerfStatsLevel, RocksDbOptions, WasmOptions,
 };
 use crate::PlainNodeId;
 use crate::locality::NodeLocation;
@@ -662,6 +662,8 @@ pub struct ServiceClientOptions {
     pub http: HttpOptions,
     #[serde(flatten)]
     pub lambda: AwsLambdaOptions,
+    #[serde(flatten)]
+    pub wasm: WasmOptions,
 
     /// # Request identity private key PEM file
     ///
@@ -9,6 +9,7 @@
 // by the Apache License, Version 2.0.
 
 use std::fmt;
+use std::num::NonZeroUsize;
 use std::str::FromStr;
 
 use http::Uri;
@@ -67,6 +68,33 @@ pub struct HttpOptions {
     /// **NOTE**: Setting this value to None (default) users the default
     /// recommended value from HTTP2 specs
     pub initial_max_send_streams: Option<usize>,
+
+    /// # Max connections per endpoint
+    ///
+    /// Maximum number of HTTP/2 connections opened to the same deployment endpoint. Additional
+    /// connections are opened only when all the existing ones reached `max-streams-per-connection`.
+    ///
+    /// Can be overridden per deployment.
+    pub max_connections_per_endpoint: NonZeroUsize,
+
+    /// # Max streams per connection
+    ///
+    /// Maximum number of concurrent streams the invoker opens on an HTTP/2 connection before
+    /// opening a new connection to the same endpoint. Once `max-connections-per-endpoint` is reached,
+    /// new streams are opened on the least loaded connection, and might queue up if the server
+    /// limit of concurrent streams is reached.
+    ///
+    /// If unset, all the streams are opened on the same connection.
+    ///
+    /// Can be overridden per deployment.
+    pub max_streams_per_connection: Option<NonZeroUsize>,
+
+    /// # Connection idle timeout
+    ///
+    /// How long an idle connection to a deployment is kept open before closing it.
+    ///
+    /// Can be overridden per deployment.
+    pub pool_idle_timeout: NonZeroFriendlyDuration,
 }
 
 impl Default for HttpOptions {
@@ -77,6 +105,9 @@ impl Default for HttpOptions {
             no_proxy: Vec::new(),
             connect_timeout: NonZeroFriendlyDuration::from_secs_unchecked(10),
             initial_max_send_streams: None,
+            max_connections_per_endpoint: NonZeroUsize::MIN,
+            max_streams_per_connection: None,
+            pool_idle_timeout: NonZeroFriendlyDuration::from_secs_unchecked(90),
         }
     }
 }
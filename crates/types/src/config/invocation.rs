@@ -9,11 +9,16 @@
 // by the Apache License, Version 2.0.
 
 use std::num::NonZeroUsize;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
+use restate_serde_util::NonZeroByteCount;
 use restate_time_util::{FriendlyDuration, NonZeroFriendlyDuration};
 
+use super::ObjectStoreOptions;
+use crate::retries::RetryPolicy;
+
 #[derive(Debug, Clone, Serialize, Deserialize, derive_builder::Builder, PartialEq)]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "schemars", schemars(rename = "InvocationOptions", default))]
@@ -56,6 +61,13 @@ pub struct InvocationOptions {
     /// `None` means no limit, that is infinite retries is enabled.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max_retry_policy_max_attempts: Option<NonZeroUsize>,
+
+    /// # Payload offloading
+    ///
+    /// Offload large invocation payloads to an object store, instead of storing them in the log
+    /// and in the partition store.
+    #[serde(default)]
+    pub payload_offloading: PayloadOffloadingOptions,
 }
 
 impl Default for InvocationOptions {
@@ -65,6 +77,70 @@ impl Default for InvocationOptions {
             max_journal_retention: None,
             default_retry_policy: InvocationRetryPolicyOptions::default(),
             max_retry_policy_max_attempts: None,
+            payload_offloading: PayloadOffloadingOptions::default(),
+        }
+    }
+}
+
+/// # Payload offloading options
+///
+/// Invocation inputs and `ctx.run` results (with service protocol V4 or newer) larger than the
+/// configured threshold are uploaded to an object store, and only a reference to the object is
+/// stored in the log and in the journal.
+/// The payload is loaded back by the invoker when sending the journal to the service deployment,
+/// and the object is deleted once the journal of the invocation is removed. Large responses are
+/// passed to the ingress through the object store as well.
+///
+/// Objects whose delete was lost, for example because of a leader change, are swept by the
+/// partition leaders every `worker.cleanup-interval`.
+///
+/// Note that every node running the `http-ingress` or the `worker` role must be configured with
+/// the same destination.
+#[derive(Debug, Clone, Serialize, Deserialize, derive_builder::Builder, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "schemars",
+    schemars(rename = "PayloadOffloadingOptions", default)
+)]
+#[serde(rename_all = "kebab-case")]
+#[builder(default)]
+pub struct PayloadOffloadingOptions {
+    /// # Destination URL
+    ///
+    /// Base URL to which large payloads are offloaded. Supports `s3://` protocol scheme.
+    ///
+    /// Default: `None` - offloading is disabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination: Option<String>,
+
+    /// # Threshold
+    ///
+    /// Payloads larger than this size are offloaded to the object store.
+    pub threshold: NonZeroByteCount,
+
+    #[serde(flatten)]
+    pub object_store: ObjectStoreOptions,
+
+    /// # Error retry policy
+    ///
+    /// A retry policy for dealing with retryable object store errors.
+    pub object_store_retry_policy: RetryPolicy,
+}
+
+impl Default for PayloadOffloadingOptions {
+    fn default() -> Self {
+        Self {
+            destination: None,
+            threshold: NonZeroByteCount::new(
+                NonZeroUsize::new(4 * 1024 * 1024).expect("Non zero number"),
+            ),
+            object_store: ObjectStoreOptions::default(),
+            object_store_retry_policy: RetryPolicy::exponential(
+                Duration::from_millis(100),
+                2.,
+                Some(10),
+                Some(Duration::from_secs(10)),
+            ),
         }
     }
 }
@@ -28,6 +28,7 @@ mod networking;
 mod object_store;
 mod query_engine;
 mod rocksdb;
+mod wasm;
 mod worker;
 
 pub use admin::*;
@@ -47,6 +48,7 @@ pub use networking::*;
 pub use object_store::*;
 pub use query_engine::*;
 pub use rocksdb::*;
+pub use wasm::*;
 pub use worker::*;
 
 use std::fs;
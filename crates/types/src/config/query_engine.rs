@@ -15,6 +15,7 @@ use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
 use restate_serde_util::NonZeroByteCount;
+use restate_time_util::NonZeroFriendlyDuration;
 
 /// # Storage query engine options
 #[serde_as]
@@ -41,6 +42,64 @@ pub struct QueryEngineOptions {
     /// The degree of parallelism to use for query execution (Defaults to the number of available cores).
     query_parallelism: Option<NonZeroUsize>,
 
+    /// # Query memory limit
+    ///
+    /// The maximum memory in bytes a single query can use, out of the `memory-size` shared by
+    /// all queries. Unset means a single query can use all of it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<NonZeroByteCount>")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<NonZeroByteCount>"))]
+    pub query_memory_limit: Option<NonZeroUsize>,
+
+    /// # Query timeout
+    ///
+    /// The maximum time a query can take, from the moment it is admitted until its last result
+    /// is read. Queries exceeding it are aborted, together with their scans on the other nodes.
+    /// Unset means no limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query_timeout: Option<NonZeroFriendlyDuration>,
+
+    /// # Maximum concurrent queries
+    ///
+    /// The maximum number of queries executing at the same time on this node. Further queries
+    /// wait in the admission queue until a running query completes.
+    pub max_concurrent_queries: NonZeroUsize,
+
+    /// # Maximum queued queries
+    ///
+    /// The maximum number of queries waiting for admission. Queries arriving when the queue is
+    /// full are rejected.
+    pub max_queued_queries: usize,
+
+    /// # Query cursor idle timeout
+    ///
+    /// Paginated queries keep their result stream open between pages. A cursor that is not
+    /// advanced within this timeout is closed, and the resources held by its query are released.
+    pub query_cursor_idle_timeout: NonZeroFriendlyDuration,
+
+    /// # Maximum open query cursors
+    ///
+    /// The maximum number of paginated queries that can be open at the same time on this node.
+    /// An open cursor occupies one of the `max-concurrent-queries` slots until it is closed, so
+    /// at most `max-concurrent-queries - 1` cursors are opened, leaving a slot to other queries.
+    /// With a single query slot, one cursor can still be opened.
+    pub max_query_cursors: NonZeroUsize,
+
+    /// # Query cursors memory budget
+    ///
+    /// The maximum memory in bytes held by the rows that idle query cursors buffer between
+    /// pages. When exceeded, the least recently used idle cursors are closed.
+    #[serde_as(as = "NonZeroByteCount")]
+    #[cfg_attr(feature = "schemars", schemars(with = "NonZeroByteCount"))]
+    pub query_cursors_memory_budget: NonZeroUsize,
+
+    /// # Allow state mutations
+    ///
+    /// Allow `UPDATE` and `DELETE` statements on the `state` table through the admin query
+    /// endpoint. Each affected Virtual Object gets its state patched, provided the state didn't
+    /// change since it was read by the statement.
+    pub allow_state_mutations: bool,
+
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     #[cfg_attr(feature = "schemars", schemars(skip))]
     pub datafusion_options: HashMap<String, String>,
@@ -59,6 +118,14 @@ impl Default for QueryEngineOptions {
             memory_size: NonZeroUsize::new(4 * 1024 * 1024 * 1024).unwrap(), // 4GiB
             tmp_dir: None,
             query_parallelism: None,
+            query_memory_limit: None,
+            query_timeout: None,
+            max_concurrent_queries: NonZeroUsize::new(16).unwrap(),
+            max_queued_queries: 64,
+            query_cursor_idle_timeout: NonZeroFriendlyDuration::from_secs_unchecked(60),
+            max_query_cursors: NonZeroUsize::new(8).unwrap(),
+            query_cursors_memory_budget: NonZeroUsize::new(256 * 1024 * 1024).unwrap(), // 256MiB
+            allow_state_mutations: false,
             datafusion_options: HashMap::new(),
         }
     }
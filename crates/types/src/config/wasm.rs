@@ -0,0 +1,50 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::num::NonZeroUsize;
+
+use restate_serde_util::NonZeroByteCount;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+/// # WebAssembly options
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, derive_builder::Builder)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "WasmClientOptions", default))]
+#[serde(rename_all = "kebab-case")]
+#[builder(default)]
+pub struct WasmOptions {
+    /// # WebAssembly fuel limit
+    ///
+    /// Fuel available to each request to a WebAssembly deployment, unless the deployment
+    /// configures its own limit. Every executed WebAssembly instruction consumes roughly one unit
+    /// of fuel, and the request fails once the fuel is exhausted.
+    ///
+    /// Default: 1 billion.
+    pub wasm_fuel_limit: u64,
+
+    /// # WebAssembly memory limit
+    ///
+    /// Maximum linear memory a WebAssembly deployment can allocate when serving a request,
+    /// unless the deployment configures its own limit.
+    ///
+    /// Default: 64MiB.
+    pub wasm_memory_limit: NonZeroByteCount,
+}
+
+impl Default for WasmOptions {
+    fn default() -> Self {
+        Self {
+            wasm_fuel_limit: 1_000_000_000,
+            wasm_memory_limit: NonZeroByteCount::new(NonZeroUsize::new(64 * 1024 * 1024).unwrap()),
+        }
+    }
+}
@@ -64,6 +64,28 @@ pub struct WorkerOptions {
     #[serde(default)]
     pub snapshots: SnapshotsOptions,
 
+    /// # Change data capture
+    ///
+    /// Emits the committed changes of the user state and of the invocation statuses to an
+    /// external sink. Change data capture is disabled unless a sink is configured.
+    #[serde(default)]
+    pub change_capture: ChangeCaptureOptions,
+
+    /// # Invocation history retention
+    ///
+    /// When set, partition processors archive a compact summary of every finished invocation
+    /// (target, timing, outcome, deployment), queryable through the `sys_invocation_history`
+    /// table. Summaries are removed once they are older than this retention, independently of
+    /// the completion retention of the invocation. The history is trimmed at every cleanup
+    /// interval.
+    ///
+    /// The setting of the partition leader applies to all replicas of the partition, and is
+    /// adopted when a new leader is elected. Disabling the history drops all recorded summaries.
+    ///
+    /// Default: `None` - the invocation history is disabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    invocation_history_retention: Option<NonZeroFriendlyDuration>,
+
     /// # Durability mode
     ///
     /// Every partition store is backed up by a durable log that is used to recover the state of
@@ -118,6 +140,10 @@ impl WorkerOptions {
     pub fn trim_delay_interval(&self) -> Duration {
         self.trim_delay_interval.into()
     }
+
+    pub fn invocation_history_retention(&self) -> Option<Duration> {
+        self.invocation_history_retention.map(Into::into)
+    }
 }
 
 impl Default for WorkerOptions {
@@ -130,6 +156,8 @@ impl Default for WorkerOptions {
             invoker: Default::default(),
             max_command_batch_size: NonZeroUsize::new(32).expect("Non zero number"),
             snapshots: SnapshotsOptions::default(),
+            change_capture: ChangeCaptureOptions::default(),
+            invocation_history_retention: None,
             trim_delay_interval: FriendlyDuration::ZERO,
             durability_mode: None,
         }
@@ -256,6 +284,14 @@ pub struct InvokerOptions {
     #[cfg_attr(feature = "schemars", schemars(with = "Option<NonZeroByteCount>"))]
     message_size_limit: Option<NonZeroUsize>,
 
+    /// # Message compression threshold
+    ///
+    /// Protocol messages larger than the specified amount are compressed with zstd when sent to
+    /// service deployments that advertised support for message compression during discovery.
+    #[serde_as(as = "NonZeroByteCount")]
+    #[cfg_attr(feature = "schemars", schemars(with = "NonZeroByteCount"))]
+    pub message_compression_threshold: NonZeroUsize,
+
     /// # Temporary directory
     ///
     /// Temporary directory to use for the invoker temporary files.
@@ -304,6 +340,13 @@ pub struct InvokerOptions {
     /// When `unset`, no throttling is applied and actions are processed
     /// without throttling.
     pub action_throttling: Option<ThrottlingOptions>,
+
+    /// # Deployment circuit breaker
+    ///
+    /// Configures the circuit breaker kept for each deployment. The circuit breaker is shared
+    /// across all partitions running on this node.
+    #[serde(default)]
+    pub circuit_breaker: CircuitBreakerOptions,
 }
 
 impl InvokerOptions {
@@ -334,11 +377,56 @@ impl Default for InvokerOptions {
             abort_timeout: FriendlyDuration::new(DEFAULT_ABORT_TIMEOUT),
             message_size_warning: NonZeroUsize::new(10 * 1024 * 1024).unwrap(), // 10MiB
             message_size_limit: None,
+            message_compression_threshold: NonZeroUsize::new(64 * 1024).unwrap(), // 64KiB
             tmp_dir: None,
             concurrent_invocations_limit: Some(NonZeroUsize::new(1000).expect("is non zero")),
             disable_eager_state: false,
             invocation_throttling: None,
             action_throttling: None,
+            circuit_breaker: CircuitBreakerOptions::default(),
+        }
+    }
+}
+
+/// # Circuit breaker options
+///
+/// After `failure-threshold` consecutive connection failures or 5xx responses from a deployment,
+/// its circuit breaker opens: invocations targeting the deployment are parked without consuming
+/// their retry attempts. Once `open-duration` has elapsed, a single probe request is let through,
+/// closing the circuit breaker again if it succeeds.
+#[derive(Debug, Clone, Serialize, Deserialize, derive_builder::Builder)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "schemars",
+    schemars(rename = "CircuitBreakerOptions", default)
+)]
+#[builder(default)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct CircuitBreakerOptions {
+    /// # Enable the circuit breaker
+    ///
+    /// If false, every invocation attempt contacts the deployment, regardless of the previous
+    /// failures of other invocations.
+    pub enabled: bool,
+
+    /// # Failure threshold
+    ///
+    /// Number of consecutive connection failures or 5xx responses after which the circuit breaker
+    /// of a deployment opens.
+    pub failure_threshold: NonZeroU32,
+
+    /// # Open duration
+    ///
+    /// How long the circuit breaker stays open before letting a probe request through.
+    pub open_duration: NonZeroFriendlyDuration,
+}
+
+impl Default for CircuitBreakerOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            failure_threshold: NonZeroU32::new(5).expect("is non zero"),
+            open_duration: NonZeroFriendlyDuration::from_secs_unchecked(10),
         }
     }
 }
@@ -372,6 +460,18 @@ pub struct StorageOptions {
     /// partitions.
     rocksdb_memory_ratio: f32,
 
+    /// # Journal compression threshold
+    ///
+    /// Journal entries larger than this size are compressed with zstd before being written to
+    /// the partition store. If unset, journal entries are not compressed.
+    ///
+    /// Enable this only once all the nodes of the cluster run a version that can read
+    /// compressed journal entries.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<NonZeroByteCount>")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<NonZeroByteCount>"))]
+    journal_compression_threshold: Option<NonZeroUsize>,
+
     /// Whether to perform commits in background IO thread pools eagerly or not
     #[cfg_attr(feature = "schemars", schemars(skip))]
     #[serde(skip_serializing_if = "std::ops::Not::not", default)]
@@ -407,6 +507,10 @@ impl StorageOptions {
             .get()
     }
 
+    pub fn journal_compression_threshold(&self) -> Option<usize> {
+        self.journal_compression_threshold.map(NonZeroUsize::get)
+    }
+
     pub fn data_dir(&self, db_name: &str) -> PathBuf {
         super::data_dir(db_name)
     }
@@ -429,6 +533,7 @@ impl Default for StorageOptions {
             // set by apply_common in runtime
             rocksdb_memory_budget: None,
             rocksdb_memory_ratio: 0.49,
+            journal_compression_threshold: None,
             always_commit_in_background: false,
         }
     }
@@ -507,6 +612,109 @@ impl SnapshotsOptions {
     }
 }
 
+/// # Change data capture options
+///
+/// Partition leaders deliver the changes captured by their partition processor to the configured
+/// sink with at-least-once semantics. Changes are retained in the partition store until their
+/// delivery has been acknowledged, so delivery resumes from the last acknowledged LSN after a
+/// leadership change or restart.
+///
+/// Whether changes are captured is replicated to all replicas of a partition: a new partition
+/// leader turns change capture on or off depending on whether it has a sink configured. Configure
+/// the same sink on all nodes running partition processors, otherwise change capture is toggled
+/// whenever leadership moves, and captured changes that haven't been delivered are dropped
+/// when it is turned off.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, derive_builder::Builder)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "schemars",
+    schemars(rename = "ChangeCaptureOptions", default)
+)]
+#[serde(rename_all = "kebab-case")]
+#[builder(default)]
+pub struct ChangeCaptureOptions {
+    /// # Sink
+    ///
+    /// Where to deliver the captured changes to.
+    ///
+    /// Default: `None` - change data capture is disabled
+    pub sink: Option<ChangeCaptureSink>,
+
+    /// # Batch size
+    ///
+    /// The maximum number of changes delivered to the sink at once.
+    batch_size: NonZeroUsize,
+
+    /// # Poll interval
+    ///
+    /// How often partition leaders check for new changes to deliver.
+    poll_interval: NonZeroFriendlyDuration,
+
+    /// # Retry policy
+    ///
+    /// Retry policy for failed deliveries to the sink. Once exhausted, the delivery is attempted
+    /// again after the next poll interval.
+    pub retry_policy: RetryPolicy,
+}
+
+impl ChangeCaptureOptions {
+    pub fn is_enabled(&self) -> bool {
+        self.sink.is_some()
+    }
+
+    /// The service the changes are delivered to, if the sink is a service.
+    pub fn sink_service(&self) -> Option<&str> {
+        match &self.sink {
+            Some(ChangeCaptureSink::Service { service, .. }) => Some(service),
+            Some(ChangeCaptureSink::Kafka { .. }) | None => None,
+        }
+    }
+
+    pub fn batch_size(&self) -> usize {
+        self.batch_size.into()
+    }
+
+    pub fn poll_interval(&self) -> Duration {
+        self.poll_interval.into()
+    }
+}
+
+impl Default for ChangeCaptureOptions {
+    fn default() -> Self {
+        Self {
+            sink: None,
+            batch_size: NonZeroUsize::new(100).expect("Non zero number"),
+            poll_interval: NonZeroFriendlyDuration::from_secs_unchecked(1),
+            retry_policy: RetryPolicy::exponential(
+                Duration::from_millis(100),
+                2.,
+                Some(10),
+                Some(Duration::from_secs(10)),
+            ),
+        }
+    }
+}
+
+/// # Change data capture sink
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum ChangeCaptureSink {
+    /// Publishes every change as a JSON record to a Kafka topic. The record key identifies the
+    /// changed entity, so changes to the same state entry or invocation are kept in order.
+    #[serde(rename_all = "kebab-case")]
+    Kafka {
+        /// Name of the Kafka cluster, as configured in `ingress.kafka-clusters`.
+        cluster: String,
+        topic: String,
+    },
+    /// Invokes a Restate handler with a JSON array of changes per batch. The invocations and state
+    /// of this service are not captured.
+    #[serde(rename_all = "kebab-case")]
+    Service { service: String, handler: String },
+}
+
 /// # Throttling options
 ///
 /// Throttling options per invoker.
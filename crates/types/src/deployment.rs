@@ -9,20 +9,31 @@
 // by the Apache License, Version 2.0.
 
 use crate::identifiers::{DeploymentId, LambdaARN};
+use crate::schema::deployment::HttpConnectionPoolSettings;
 use crate::service_protocol::ServiceProtocolVersion;
 use http::{HeaderName, HeaderValue, Uri};
 use std::collections::HashMap;
 use std::fmt;
+use std::path::PathBuf;
 
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq)]
 pub struct HttpDeploymentAddress {
     pub uri: Uri,
+    pub connection_pool: HttpConnectionPoolSettings,
 }
 
 impl HttpDeploymentAddress {
     pub fn new(uri: Uri) -> Self {
-        Self { uri }
+        Self {
+            uri,
+            connection_pool: HttpConnectionPoolSettings::default(),
+        }
+    }
+
+    pub fn with_connection_pool(mut self, connection_pool: HttpConnectionPoolSettings) -> Self {
+        self.connection_pool = connection_pool;
+        self
     }
 }
 
@@ -54,11 +65,40 @@ impl fmt::Display for LambdaDeploymentAddress {
     }
 }
 
+/// WebAssembly module executed in-process by the workers.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct WasmDeploymentAddress {
+    /// Path of the module, which must be available at the same path on every worker node.
+    pub module_path: PathBuf,
+    /// Fuel available to each request, overrides the configured default.
+    pub fuel_limit: Option<u64>,
+    /// Maximum linear memory in bytes for each request, overrides the configured default.
+    pub memory_limit: Option<u64>,
+}
+
+impl WasmDeploymentAddress {
+    pub fn new(module_path: PathBuf, fuel_limit: Option<u64>, memory_limit: Option<u64>) -> Self {
+        Self {
+            module_path,
+            fuel_limit,
+            memory_limit,
+        }
+    }
+}
+
+impl fmt::Display for WasmDeploymentAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "wasm://{}", self.module_path.display())
+    }
+}
+
 /// This is the representation of a deployment address
 #[derive(Debug, Clone, PartialEq, derive_more::From)]
 pub enum DeploymentAddress {
     Http(HttpDeploymentAddress),
     Lambda(LambdaDeploymentAddress),
+    Wasm(WasmDeploymentAddress),
 }
 
 impl fmt::Display for DeploymentAddress {
@@ -66,6 +106,7 @@ impl fmt::Display for DeploymentAddress {
         match self {
             DeploymentAddress::Http(d) => fmt::Display::fmt(d, f),
             DeploymentAddress::Lambda(d) => fmt::Display::fmt(d, f),
+            DeploymentAddress::Wasm(d) => fmt::Display::fmt(d, f),
         }
     }
 }
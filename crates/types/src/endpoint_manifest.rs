@@ -65,6 +65,9 @@ impl Handler {
     pub fn idempotency_retention_duration(&self) -> Option<Duration> {
         self.idempotency_retention.map(Duration::from_millis)
     }
+    pub fn deadline_duration(&self) -> Option<Duration> {
+        self.deadline.map(Duration::from_millis)
+    }
     pub fn workflow_completion_retention_duration(&self) -> Option<Duration> {
         self.workflow_completion_retention
             .map(Duration::from_millis)
@@ -77,3 +80,13 @@ impl Handler {
         self.retry_policy_max_interval.map(Duration::from_millis)
     }
 }
+
+impl RetryPolicyRule {
+    pub fn retry_policy_initial_interval(&self) -> Option<Duration> {
+        self.retry_policy_initial_interval
+            .map(Duration::from_millis)
+    }
+    pub fn retry_policy_max_interval(&self) -> Option<Duration> {
+        self.retry_policy_max_interval.map(Duration::from_millis)
+    }
+}
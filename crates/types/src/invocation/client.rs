@@ -173,6 +173,36 @@ pub enum ResumeInvocationResponse {
     Completed,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MigrateInvocationResponse {
+    Ok {
+        deployment_id: DeploymentId,
+    },
+    /// The invocation is already pinned to the chosen deployment
+    AlreadyPinned,
+    NotFound,
+    /// The invocation isn't started yet (it's enqueued or scheduled)
+    NotStarted,
+    /// The invocation is running, only suspended or paused invocations can be migrated
+    Running,
+    /// There is no pinned deployment yet
+    NotPinned,
+    /// No deployment found for the given service, or the given deployment id doesn't exist.
+    DeploymentNotFound,
+    /// The chosen deployment id is incompatible
+    IncompatibleDeploymentId {
+        pinned_protocol_version: i32,
+        deployment_id: DeploymentId,
+        supported_protocol_versions: RangeInclusive<i32>,
+    },
+    /// The chosen deployment doesn't contain the handler of the invocation
+    MissingHandler {
+        deployment_id: DeploymentId,
+    },
+    /// Invocation is completed
+    Completed,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PauseInvocationResponse {
     AlreadyPaused,
@@ -272,6 +302,14 @@ pub trait InvocationClient {
         resume_invocation_deployment_id: PatchDeploymentId,
     ) -> impl Future<Output = Result<ResumeInvocationResponse, InvocationClientError>> + Send;
 
+    /// Re-pin the given suspended or paused invocation to another deployment, without resuming it.
+    fn migrate_invocation(
+        &self,
+        request_id: PartitionProcessorRpcRequestId,
+        invocation_id: InvocationId,
+        deployment_id: PatchDeploymentId,
+    ) -> impl Future<Output = Result<MigrateInvocationResponse, InvocationClientError>> + Send;
+
     /// Pause the given invocation.
     fn pause_invocation(
         &self,
@@ -11,6 +11,7 @@
 //! This module contains all the core types representing a service invocation.
 
 pub mod client;
+pub mod offloaded_payload;
 
 use crate::errors::InvocationError;
 use crate::identifiers::{
@@ -317,6 +318,10 @@ pub struct InvocationRequestHeader {
     /// Time when the request should be executed. If none, it's executed immediately.
     pub execution_time: Option<MillisSinceEpoch>,
 
+    /// End-to-end deadline of the invocation. Once exceeded, the invocation is cancelled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<MillisSinceEpoch>,
+
     /// Retention duration of the completed status.
     /// If zero, the completed status is not retained.
     #[serde(default)]
@@ -338,6 +343,7 @@ impl InvocationRequestHeader {
             span_context: ServiceInvocationSpanContext::empty(),
             idempotency_key: None,
             execution_time: None,
+            deadline: None,
             completion_retention_duration: Duration::ZERO,
             journal_retention_duration: Duration::ZERO,
         }
@@ -417,6 +423,10 @@ pub struct ServiceInvocation {
     /// Time when the request should be executed
     pub execution_time: Option<MillisSinceEpoch>,
 
+    /// End-to-end deadline of the invocation, including retries and suspensions.
+    /// Once exceeded, the invocation is cancelled.
+    pub deadline: Option<MillisSinceEpoch>,
+
     /// Retention duration of the completed status. If zero, the completed status is not retained, and invocation won't be deduplicated.
     pub completion_retention_duration: Duration,
     /// Retention duration of the journal. If zero, the journal is not retained. This should be smaller than `completion_retention_duration`.
@@ -456,6 +466,7 @@ impl ServiceInvocation {
             span_context: request.header.span_context,
             headers: request.header.headers,
             execution_time: request.header.execution_time,
+            deadline: request.header.deadline,
             completion_retention_duration: request.header.completion_retention_duration,
             journal_retention_duration: cmp::min(
                 request.header.journal_retention_duration,
@@ -482,6 +493,7 @@ impl ServiceInvocation {
             span_context: ServiceInvocationSpanContext::empty(),
             headers: vec![],
             execution_time: None,
+            deadline: None,
             completion_retention_duration: Duration::ZERO,
             journal_retention_duration: Duration::ZERO,
             idempotency_key: None,
@@ -985,6 +997,21 @@ impl WithInvocationId for ResumeInvocationRequest {
     }
 }
 
+/// Message to re-pin a suspended or paused invocation to another deployment, without resuming it.
+#[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct MigrateInvocationRequest {
+    pub invocation_id: InvocationId,
+    pub deployment_id: DeploymentId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_sink: Option<InvocationMutationResponseSink>,
+}
+
+impl WithInvocationId for MigrateInvocationRequest {
+    fn invocation_id(&self) -> InvocationId {
+        self.invocation_id
+    }
+}
+
 /// Message to restart an invocation.
 #[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct RestartAsNewInvocationRequest {
@@ -1356,6 +1383,8 @@ mod serde_hacks {
         pub span_context: ServiceInvocationSpanContext,
         pub headers: Vec<Header>,
         pub execution_time: Option<MillisSinceEpoch>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub deadline: Option<MillisSinceEpoch>,
         pub completion_retention_duration: Option<Duration>,
         #[serde(default, skip_serializing_if = "Duration::is_zero")]
         pub journal_retention_duration: Duration,
@@ -1390,6 +1419,7 @@ mod serde_hacks {
                 span_context,
                 headers,
                 execution_time,
+                deadline,
                 completion_retention_duration,
                 journal_retention_duration,
                 idempotency_key,
@@ -1406,6 +1436,7 @@ mod serde_hacks {
                 span_context,
                 headers,
                 execution_time,
+                deadline,
                 completion_retention_duration: completion_retention_duration.unwrap_or_default(),
                 journal_retention_duration,
                 idempotency_key,
@@ -1435,6 +1466,7 @@ mod serde_hacks {
                 span_context,
                 headers,
                 execution_time,
+                deadline,
                 completion_retention_duration,
                 journal_retention_duration,
                 idempotency_key,
@@ -1456,6 +1488,7 @@ mod serde_hacks {
                 span_context,
                 headers,
                 execution_time,
+                deadline,
                 completion_retention_duration: Some(completion_retention_duration),
                 journal_retention_duration,
                 idempotency_key,
@@ -1688,6 +1721,7 @@ mod mocks {
                 span_context: Default::default(),
                 headers: vec![],
                 execution_time: None,
+                deadline: None,
                 completion_retention_duration: Duration::ZERO,
                 journal_retention_duration: Duration::ZERO,
                 idempotency_key: None,
@@ -1715,6 +1749,7 @@ mod mocks {
                 span_context: Default::default(),
                 idempotency_key: None,
                 execution_time: None,
+                deadline: None,
                 completion_retention_duration: Default::default(),
                 journal_retention_duration: Default::default(),
             }
@@ -0,0 +1,174 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! References to payloads offloaded to an object store.
+//!
+//! An offloaded payload is replaced in-place by its reference, so it can flow unchanged through
+//! the log, the invocation status and the journal. The reference is recognized by a magic prefix
+//! that cannot be produced by the JSON or protobuf encodings used by the SDKs. Payloads of clients
+//! and services that start with the magic prefix anyway are rejected where they enter the system
+//! (see [`OffloadedPayload::check_not_reference`]), so every payload carrying the prefix is a
+//! reference created by Restate.
+//!
+//! Every object belongs to an invocation, and its key is relative to the objects of that
+//! invocation. This way the journal entries copied by restart-as-new resolve to the copies of
+//! the objects made for the new invocation.
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+use crate::identifiers::PartitionProcessorRpcRequestId;
+use crate::journal_v2::CompletionId;
+
+const MAGIC: &[u8] = b"\0\xffrestate-offloaded-payload\0";
+const VERSION: u8 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("payloads must not start with the prefix reserved for references to offloaded payloads")]
+pub struct ReservedPayloadPrefixError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OffloadedPayload {
+    /// Key of the object, relative to the objects of the invocation it belongs to.
+    pub key: String,
+    /// Length of the original payload.
+    pub length: u64,
+}
+
+impl OffloadedPayload {
+    /// Key prefix of the responses sent to the ingress. These are needed only until the ingress
+    /// has read them, hence they are not retained together with the journal of the invocation.
+    pub const RESPONSES_PREFIX: &'static str = "responses";
+
+    /// Reference to the input of the invocation.
+    pub fn input(length: u64) -> Self {
+        Self {
+            key: "input".to_owned(),
+            length,
+        }
+    }
+
+    /// Reference to the result of a run. The suffix tells apart the results proposed by
+    /// different attempts, as only one of them will be stored in the journal.
+    pub fn run_result(
+        completion_id: CompletionId,
+        suffix: impl std::fmt::Display,
+        length: u64,
+    ) -> Self {
+        Self {
+            key: format!("run/{completion_id}-{suffix}"),
+            length,
+        }
+    }
+
+    /// Reference to the response sent to the ingress for the given request.
+    pub fn response(request_id: &PartitionProcessorRpcRequestId, length: u64) -> Self {
+        Self {
+            key: format!("{}/{request_id}", Self::RESPONSES_PREFIX),
+            length,
+        }
+    }
+
+    /// Returns true if the referenced object is retained together with the journal of the
+    /// invocation.
+    pub fn is_retained_with_journal(key: &str) -> bool {
+        !key.starts_with(Self::RESPONSES_PREFIX)
+    }
+
+    /// Returns true if the given payload is a reference to an offloaded payload.
+    pub fn is_offloaded(payload: &[u8]) -> bool {
+        payload.starts_with(MAGIC)
+    }
+
+    /// Checks that a payload provided by a client or a service can't be mistaken for a
+    /// reference.
+    pub fn check_not_reference(payload: &[u8]) -> Result<(), ReservedPayloadPrefixError> {
+        if Self::is_offloaded(payload) {
+            Err(ReservedPayloadPrefixError)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn encode(&self) -> Bytes {
+        let mut buf = BytesMut::with_capacity(MAGIC.len() + 1 + 8 + self.key.len());
+        buf.put_slice(MAGIC);
+        buf.put_u8(VERSION);
+        buf.put_u64(self.length);
+        buf.put_slice(self.key.as_bytes());
+        buf.freeze()
+    }
+
+    /// Decodes the reference, returns `None` if the given payload is not a valid reference.
+    pub fn decode(payload: &[u8]) -> Option<Self> {
+        let mut buf = payload.strip_prefix(MAGIC)?;
+        if buf.remaining() < 1 + 8 || buf.get_u8() != VERSION {
+            return None;
+        }
+        let length = buf.get_u64();
+        let key = std::str::from_utf8(buf).ok()?.to_owned();
+
+        Some(Self { key, length })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        for reference in [
+            OffloadedPayload::input(50 * 1024 * 1024),
+            OffloadedPayload::run_result(3, "01", 1024),
+            OffloadedPayload::response(&PartitionProcessorRpcRequestId::new(), 0),
+        ] {
+            let encoded = reference.encode();
+            assert!(OffloadedPayload::is_offloaded(&encoded));
+            assert_eq!(OffloadedPayload::decode(&encoded), Some(reference));
+        }
+    }
+
+    #[test]
+    fn only_responses_are_not_retained_with_journal() {
+        assert!(OffloadedPayload::is_retained_with_journal(
+            &OffloadedPayload::input(1).key
+        ));
+        assert!(OffloadedPayload::is_retained_with_journal(
+            &OffloadedPayload::run_result(1, "01", 1).key
+        ));
+        assert!(!OffloadedPayload::is_retained_with_journal(
+            &OffloadedPayload::response(&PartitionProcessorRpcRequestId::new(), 1).key
+        ));
+    }
+
+    #[test]
+    fn regular_payloads_are_not_references() {
+        for payload in [&b""[..], b"{}", b"\"restate-offloaded-payload\"", b"\0\xff"] {
+            assert!(!OffloadedPayload::is_offloaded(payload));
+            assert_eq!(OffloadedPayload::decode(payload), None);
+            assert_eq!(OffloadedPayload::check_not_reference(payload), Ok(()));
+        }
+    }
+
+    #[test]
+    fn payloads_with_reserved_prefix_are_rejected() {
+        // even if it's not a valid reference
+        let mut payload = MAGIC.to_vec();
+        payload.extend_from_slice(b"{}");
+        assert_eq!(
+            OffloadedPayload::check_not_reference(&payload),
+            Err(ReservedPayloadPrefixError)
+        );
+        assert_eq!(
+            OffloadedPayload::check_not_reference(&OffloadedPayload::input(1).encode()),
+            Err(ReservedPayloadPrefixError)
+        );
+    }
+}
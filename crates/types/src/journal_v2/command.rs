@@ -273,6 +273,9 @@ pub struct CallRequest {
     // matches the default behaviour of <= 1.3.x.
     #[serde(default)]
     pub journal_retention_duration: Duration,
+    /// End-to-end deadline of the callee invocation, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<MillisSinceEpoch>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -424,6 +427,7 @@ mod test_util {
                 idempotency_key: None,
                 completion_retention_duration: Default::default(),
                 journal_retention_duration: Default::default(),
+                deadline: None,
             }
         }
     }
@@ -19,6 +19,7 @@ use crate::journal_v2::encoding::DecodingError;
 use crate::journal_v2::{
     CommandType, Decoder, Entry, EntryMetadata, EntryType, NotificationId, NotificationType,
 };
+use crate::time::MillisSinceEpoch;
 
 #[derive(Debug, thiserror::Error)]
 #[error(
@@ -127,6 +128,8 @@ pub struct CallOrSendMetadata {
     // matches the default behaviour of <= 1.3.x.
     #[serde(default)]
     pub journal_retention_duration: Duration,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<MillisSinceEpoch>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
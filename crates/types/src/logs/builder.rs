@@ -35,6 +35,10 @@ pub enum BuilderError {
     ParamsSerde(#[from] serde_json::Error),
     #[error("Segment conflicts with existing (base_lsn={0})")]
     SegmentConflict(Lsn),
+    #[error("segment {0} was not found in the chain")]
+    UnknownSegment(SegmentIndex),
+    #[error("segment {0} is not sealed")]
+    SegmentNotSealed(SegmentIndex),
 }
 
 impl LogsBuilder {
@@ -311,6 +315,52 @@ impl ChainBuilder<'_> {
             }
         }
     }
+
+    /// Replaces the loglet of a sealed segment with an object-store loglet described by `params`.
+    /// The segment keeps its base_lsn and index, readers will transparently switch to the new
+    /// loglet once they observe the updated chain.
+    ///
+    /// The tail segment cannot be offloaded since it might still accept writes. Offloading a
+    /// segment that is already offloaded replaces its params.
+    pub fn offload_segment(
+        &mut self,
+        segment_index: SegmentIndex,
+        params: LogletParams,
+    ) -> Result<(), BuilderError> {
+        let tail_base_lsn = *self
+            .inner
+            .chain
+            .last_key_value()
+            .expect("chain have at least one segment")
+            .0;
+
+        let (base_lsn, config) = self
+            .inner
+            .chain
+            .iter_mut()
+            .find(|(_, config)| {
+                config.index() == segment_index && !config.kind.is_seal_marker()
+            })
+            .ok_or(BuilderError::UnknownSegment(segment_index))?;
+
+        // A segment is sealed if it's followed by another segment or a seal marker.
+        if *base_lsn == tail_base_lsn {
+            return Err(BuilderError::SegmentNotSealed(segment_index));
+        }
+
+        if ProviderKind::Replicated == config.kind {
+            let old_params = ReplicatedLogletParams::deserialize_from(config.params.as_bytes())?;
+            self.lookup_index.rm_replicated_loglet_reference(
+                self.log_id,
+                segment_index,
+                old_params.loglet_id,
+            );
+        }
+
+        *config = LogletConfig::new_offloaded(segment_index, params);
+        *self.modified = true;
+        Ok(())
+    }
 }
 
 impl Deref for ChainBuilder<'_> {
@@ -793,6 +843,7 @@ mod tests {
             sequencer: GenerationalNodeId::new(1, 1),
             replication: ReplicationProperty::new(NonZeroU8::new(2).unwrap()),
             nodeset: NodeSet::new(),
+            erasure_coding: None,
         };
 
         let loglet2 = ReplicatedLogletParams {
@@ -800,6 +851,7 @@ mod tests {
             sequencer: GenerationalNodeId::new(1, 1),
             replication: ReplicationProperty::new(NonZeroU8::new(2).unwrap()),
             nodeset: NodeSet::new(),
+            erasure_coding: None,
         };
 
         let loglet1_params = LogletParams::from(loglet1.serialize()?);
@@ -928,4 +980,96 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_offload_segment() -> googletest::Result<()> {
+        use crate::GenerationalNodeId;
+        use crate::logs::LogletId;
+        use crate::logs::metadata::InternalKind;
+        use crate::replicated_loglet::ReplicatedLogletParams;
+        use crate::replication::{NodeSet, ReplicationProperty};
+
+        let mut builder = LogsBuilder::default();
+
+        let loglet1 = ReplicatedLogletParams {
+            loglet_id: LogletId::from(1),
+            sequencer: GenerationalNodeId::new(1, 1),
+            replication: ReplicationProperty::new(NonZeroU8::new(2).unwrap()),
+            nodeset: NodeSet::new(),
+            erasure_coding: None,
+        };
+        let loglet2 = ReplicatedLogletParams {
+            loglet_id: LogletId::from(2),
+            ..loglet1.clone()
+            erasure_coding: None,
+        };
+
+        // log-1 -> [replicated-loglet-1]
+        let mut chain = builder.add_log(
+            LogId::new(1),
+            Chain::new(
+                ProviderKind::Replicated,
+                LogletParams::from(loglet1.serialize()?),
+            ),
+        )?;
+
+        // the tail segment cannot be offloaded
+        assert_that!(
+            chain.offload_segment(SegmentIndex(0), LogletParams::from("archived")),
+            err(pat!(BuilderError::SegmentNotSealed(eq(SegmentIndex(0)))))
+        );
+        assert_that!(
+            chain.offload_segment(SegmentIndex(5), LogletParams::from("archived")),
+            err(pat!(BuilderError::UnknownSegment(eq(SegmentIndex(5)))))
+        );
+
+        // log-1 -> [replicated-loglet-1, replicated-loglet-2]
+        chain.append_segment(
+            Lsn::from(10),
+            ProviderKind::Replicated,
+            LogletParams::from(loglet2.serialize()?),
+        )?;
+
+        // log-1 -> [object-store-loglet, replicated-loglet-2]
+        chain.offload_segment(SegmentIndex(0), LogletParams::from("archived"))?;
+
+        let head = chain.head();
+        assert_that!(head.config.kind, eq(InternalKind::ObjectStore));
+        assert_that!(head.index(), eq(SegmentIndex(0)));
+        assert_that!(head.base_lsn, eq(Lsn::OLDEST));
+        assert_that!(head.tail_lsn, some(eq(Lsn::from(10))));
+        assert_that!(head.config.params, eq(LogletParams::from("archived")));
+        let _ = chain;
+
+        // the offloaded loglet is no longer referenced
+        assert_that!(
+            builder
+                .inner
+                .lookup_index
+                .get_replicated_loglet(&LogletId::from(1)),
+            none()
+        );
+        assert_that!(
+            builder
+                .inner
+                .lookup_index
+                .get_replicated_loglet(&LogletId::from(2)),
+            some(anything())
+        );
+
+        // the params of an offloaded segment can be replaced
+        builder
+            .chain(LogId::new(1))
+            .unwrap()
+            .offload_segment(SegmentIndex(0), LogletParams::from("archived-v2"))?;
+        let logs = builder.build();
+        let chain = logs.chain(&LogId::new(1)).unwrap();
+        assert_that!(chain.num_segments(), eq(2));
+        assert_that!(
+            chain.head().config.params,
+            eq(LogletParams::from("archived-v2"))
+        );
+
+        Ok(())
+    }
 }
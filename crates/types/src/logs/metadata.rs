@@ -29,10 +29,13 @@ use crate::config::Configuration;
 use crate::logs::{LogId, Lsn, SequenceNumber};
 use crate::metadata::GlobalMetadata;
 use crate::net::metadata::{MetadataContainer, MetadataKind};
-use crate::replicated_loglet::ReplicatedLogletParams;
-use crate::replication::ReplicationProperty;
+use crate::replicated_loglet::{ErasureCoding, ReplicatedLogletParams};
+use crate::replication::{NodeSet, ReplicationProperty};
 use crate::time::MillisSinceEpoch;
-use crate::{GenerationalNodeId, Version, Versioned, flexbuffers_storage_encode_decode};
+use crate::{
+    GenerationalNodeId, SemanticRestateVersion, Version, Versioned,
+    flexbuffers_storage_encode_decode,
+};
 
 // Starts with 0 being the oldest loglet in the chain.
 #[derive(
@@ -139,6 +142,7 @@ impl Default for ProviderConfiguration {
         Self::Replicated(ReplicatedLogletConfig {
             replication_property: ReplicationProperty::new_unchecked(1),
             target_nodeset_size: NodeSetSize::new(1).expect("is valid nodeset size"),
+            erasure_coding: None,
         })
     }
 }
@@ -158,6 +162,20 @@ impl ProviderConfiguration {
             configuration.common.default_replication.clone(),
             configuration.bifrost.replicated_loglet.default_nodeset_size,
         ))
+        .with_erasure_coding(
+            configuration
+                .bifrost
+                .replicated_loglet
+                .default_erasure_coding,
+        )
+    }
+
+    /// Sets the erasure coding of the replicated provider. Has no effect on other providers.
+    pub fn with_erasure_coding(mut self, erasure_coding: Option<ErasureCoding>) -> Self {
+        if let ProviderConfiguration::Replicated(config) = &mut self {
+            config.erasure_coding = erasure_coding;
+        }
+        self
     }
 
     pub fn replication(&self) -> Option<&ReplicationProperty> {
@@ -191,6 +209,7 @@ impl From<(ProviderKind, ReplicationProperty, NodeSetSize)> for ProviderConfigur
             ProviderKind::Replicated => ProviderConfiguration::Replicated(ReplicatedLogletConfig {
                 replication_property: log_replication,
                 target_nodeset_size,
+                erasure_coding: None,
             }),
         }
     }
@@ -211,6 +230,7 @@ impl From<ProviderConfiguration> for crate::protobuf::cluster::BifrostProvider {
                     replication_property: config.replication_property.to_string(),
                 });
                 result.target_nodeset_size = config.target_nodeset_size.as_u32();
+                result.erasure_coding = config.erasure_coding.map(|ec| ec.to_string());
             }
         };
 
@@ -239,6 +259,11 @@ impl TryFrom<crate::protobuf::cluster::BifrostProvider> for ProviderConfiguratio
                         // the error message helps the user learn about the logical maximum rather
                         // than the type max limit.
                         .context("target_nodeset_size is too big, please keep it under 128")?,
+                    erasure_coding: value
+                        .erasure_coding
+                        .map(|ec| ec.parse())
+                        .transpose()
+                        .context("invalid erasure_coding")?,
                 }))
             }
         }
@@ -253,6 +278,46 @@ pub struct ReplicatedLogletConfig {
     /// The default target for new nodesets. 0 (default) auto-chooses a nodeset-size that
     /// balances read and write availability. It's a reasonable default for most cases.
     pub target_nodeset_size: NodeSetSize,
+    /// If set, new loglets store erasure-coded fragments of records instead of full copies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub erasure_coding: Option<ErasureCoding>,
+}
+
+impl ReplicatedLogletConfig {
+    /// The replication property of new loglets. For erasure-coded loglets, this is the number
+    /// of fragments that need to be stored for a record to be committed.
+    pub fn loglet_replication(&self) -> ReplicationProperty {
+        match &self.erasure_coding {
+            Some(erasure_coding) => erasure_coding.replication_property(&self.replication_property),
+            None => self.replication_property.clone(),
+        }
+    }
+
+    /// The target size of new nodesets. Erasure-coded loglets need exactly one node per fragment.
+    pub fn nodeset_target_size(&self) -> NodeSetSize {
+        match &self.erasure_coding {
+            Some(erasure_coding) => u16::try_from(erasure_coding.total_fragments())
+                .ok()
+                .and_then(NodeSetSize::new)
+                .unwrap_or(NodeSetSize::MAX),
+            None => self.target_nodeset_size,
+        }
+    }
+
+    /// Fits a selected nodeset to the erasure coding of new loglets by keeping one node per
+    /// fragment. Returns `None` if the nodeset has fewer nodes than fragments.
+    pub fn fit_nodeset(&self, mut nodeset: NodeSet) -> Option<NodeSet> {
+        let Some(erasure_coding) = &self.erasure_coding else {
+            return Some(nodeset);
+        };
+        if nodeset.len() < erasure_coding.total_fragments() {
+            return None;
+        }
+        while nodeset.len() > erasure_coding.total_fragments() {
+            nodeset.pop();
+        }
+        Some(nodeset)
+    }
 }
 
 /// New type that enforces that the nodeset size is never larger than 128.
@@ -396,6 +461,7 @@ impl TryFrom<LogsSerde> for Logs {
                                     replication_property: ReplicationProperty::new(
                                         NonZeroU8::new(2).expect("2 is not 0"),
                                     ),
+                                    erasure_coding: None,
                                 },
                             ),
                         })
@@ -591,12 +657,39 @@ pub enum InternalKind {
 
     /// A loglet that's always sealed and has no records. Used as a seal marker for a sealed chain.
     Sealed,
+    /// A read-only loglet whose records have been offloaded to an object store. Sealed segments
+    /// of replicated loglets are converted to this kind once their records were uploaded.
+    ObjectStore,
 }
 
 impl InternalKind {
     pub fn is_seal_marker(&self) -> bool {
         matches!(self, Self::Sealed)
     }
+
+    /// Minimum version all the nodes of the cluster must run before segments of this kind can be
+    /// added to a log chain. Older versions fail decoding the logs metadata otherwise.
+    pub fn min_restate_version(&self) -> Option<SemanticRestateVersion> {
+        match self {
+            Self::ObjectStore => {
+                Some(SemanticRestateVersion::parse("1.6.0-dev").expect("valid version"))
+            }
+            Self::Local | Self::InMemory | Self::Replicated | Self::Sealed => None,
+        }
+    }
+
+    /// Whether segments of this kind can be added to a log chain of a cluster whose nodes run at
+    /// least the given version. See [`NodesConfiguration::min_binary_version`].
+    ///
+    /// [`NodesConfiguration::min_binary_version`]: crate::nodes_config::NodesConfiguration::min_binary_version
+    pub fn is_supported_by(&self, min_binary_version: Option<&SemanticRestateVersion>) -> bool {
+        match self.min_restate_version() {
+            None => true,
+            Some(required) => {
+                min_binary_version.is_some_and(|version| version.is_equal_or_newer_than(&required))
+            }
+        }
+    }
 }
 
 impl From<ProviderKind> for InternalKind {
@@ -616,7 +709,7 @@ impl TryFrom<InternalKind> for ProviderKind {
             InternalKind::Local => Ok(Self::Local),
             InternalKind::InMemory => Ok(Self::InMemory),
             InternalKind::Replicated => Ok(Self::Replicated),
-            InternalKind::Sealed => Err(anyhow::anyhow!(
+            InternalKind::Sealed | InternalKind::ObjectStore => Err(anyhow::anyhow!(
                 "a special loglet kind that cannot be converted into user-facing kind"
             )),
         }
@@ -705,6 +798,14 @@ impl LogletConfig {
         })
     }
 
+    pub(crate) fn new_offloaded(index: SegmentIndex, params: LogletParams) -> Self {
+        Self {
+            kind: InternalKind::ObjectStore,
+            params,
+            index,
+        }
+    }
+
     pub fn index(&self) -> SegmentIndex {
         self.index
     }
@@ -1027,6 +1128,21 @@ pub fn bootstrap_logs_metadata(
 mod tests {
     use super::*;
 
+    #[test]
+    fn object_store_kind_is_version_gated() {
+        let released: SemanticRestateVersion = "1.6.0".parse().unwrap();
+        let dev: SemanticRestateVersion = "1.6.0-dev".parse().unwrap();
+        let old: SemanticRestateVersion = "1.5.3".parse().unwrap();
+
+        assert!(InternalKind::ObjectStore.is_supported_by(Some(&released)));
+        assert!(InternalKind::ObjectStore.is_supported_by(Some(&dev)));
+        assert!(!InternalKind::ObjectStore.is_supported_by(Some(&old)));
+        // some node didn't report its version
+        assert!(!InternalKind::ObjectStore.is_supported_by(None));
+
+        assert!(InternalKind::Replicated.is_supported_by(None));
+    }
+
     #[test]
     fn test_chain_new() {
         let chain = Chain::new(ProviderKind::Local, LogletParams::from("test".to_string()));
@@ -1041,4 +1157,30 @@ mod tests {
         assert_eq!(ProviderKind::Local, config.kind);
         assert_eq!("test".to_string(), config.params.0.to_string());
     }
+
+    #[test]
+    fn erasure_coded_loglet_config() {
+        let config = ReplicatedLogletConfig {
+            replication_property: ReplicationProperty::new_unchecked(2),
+            target_nodeset_size: NodeSetSize::default(),
+            erasure_coding: Some("4+2".parse().unwrap()),
+        };
+        assert_eq!(
+            ReplicationProperty::new_unchecked(5),
+            config.loglet_replication()
+        );
+        assert_eq!(6, config.nodeset_target_size().as_u16());
+        assert!(config.fit_nodeset(NodeSet::from([1, 2, 3, 4, 5])).is_none());
+        assert_eq!(
+            NodeSet::from([1, 2, 3, 4, 5, 6]),
+            config
+                .fit_nodeset(NodeSet::from([1, 2, 3, 4, 5, 6, 7]))
+                .unwrap()
+        );
+
+        let provider = ProviderConfiguration::Replicated(config);
+        let proto = crate::protobuf::cluster::BifrostProvider::from(provider.clone());
+        assert_eq!(Some("4+2"), proto.erasure_coding.as_deref());
+        assert_eq!(provider, ProviderConfiguration::try_from(proto).unwrap());
+    }
 }
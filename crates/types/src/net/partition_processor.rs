@@ -16,9 +16,9 @@ use crate::identifiers::{
     PartitionProcessorRpcRequestId, WithPartitionKey,
 };
 use crate::invocation::client::{
-    CancelInvocationResponse, InvocationOutput, KillInvocationResponse, PatchDeploymentId,
-    PauseInvocationResponse, PurgeInvocationResponse, RestartAsNewInvocationResponse,
-    ResumeInvocationResponse, SubmittedInvocationNotification,
+    CancelInvocationResponse, InvocationOutput, KillInvocationResponse, MigrateInvocationResponse,
+    PatchDeploymentId, PauseInvocationResponse, PurgeInvocationResponse,
+    RestartAsNewInvocationResponse, ResumeInvocationResponse, SubmittedInvocationNotification,
 };
 use crate::invocation::{InvocationQuery, InvocationRequest, InvocationResponse};
 use crate::journal_v2::Signal;
@@ -97,6 +97,10 @@ pub enum PartitionProcessorRpcRequestInner {
     PauseInvocation {
         invocation_id: InvocationId,
     },
+    MigrateInvocation {
+        invocation_id: InvocationId,
+        deployment_id: PatchDeploymentId,
+    },
 }
 
 impl WithPartitionKey for PartitionProcessorRpcRequestInner {
@@ -127,6 +131,9 @@ impl WithPartitionKey for PartitionProcessorRpcRequestInner {
             PartitionProcessorRpcRequestInner::PauseInvocation { invocation_id } => {
                 invocation_id.partition_key()
             }
+            PartitionProcessorRpcRequestInner::MigrateInvocation { invocation_id, .. } => {
+                invocation_id.partition_key()
+            }
         }
     }
 }
@@ -486,6 +493,88 @@ impl From<PauseInvocationRpcResponse> for PartitionProcessorRpcResponse {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MigrateInvocationRpcResponse {
+    Ok {
+        deployment_id: DeploymentId,
+    },
+    AlreadyPinned,
+    NotFound,
+    NotStarted,
+    Running,
+    NotPinned,
+    DeploymentNotFound,
+    IncompatibleDeploymentId {
+        pinned_protocol_version: i32,
+        deployment_id: DeploymentId,
+        supported_protocol_versions: RangeInclusive<i32>,
+    },
+    MissingHandler {
+        deployment_id: DeploymentId,
+    },
+    Completed,
+}
+
+impl From<MigrateInvocationRpcResponse> for MigrateInvocationResponse {
+    fn from(value: MigrateInvocationRpcResponse) -> Self {
+        match value {
+            MigrateInvocationRpcResponse::Ok { deployment_id } => Self::Ok { deployment_id },
+            MigrateInvocationRpcResponse::AlreadyPinned => Self::AlreadyPinned,
+            MigrateInvocationRpcResponse::NotFound => Self::NotFound,
+            MigrateInvocationRpcResponse::NotStarted => Self::NotStarted,
+            MigrateInvocationRpcResponse::Running => Self::Running,
+            MigrateInvocationRpcResponse::NotPinned => Self::NotPinned,
+            MigrateInvocationRpcResponse::DeploymentNotFound => Self::DeploymentNotFound,
+            MigrateInvocationRpcResponse::IncompatibleDeploymentId {
+                pinned_protocol_version,
+                deployment_id,
+                supported_protocol_versions,
+            } => Self::IncompatibleDeploymentId {
+                pinned_protocol_version,
+                deployment_id,
+                supported_protocol_versions,
+            },
+            MigrateInvocationRpcResponse::MissingHandler { deployment_id } => {
+                Self::MissingHandler { deployment_id }
+            }
+            MigrateInvocationRpcResponse::Completed => Self::Completed,
+        }
+    }
+}
+
+impl From<MigrateInvocationResponse> for MigrateInvocationRpcResponse {
+    fn from(value: MigrateInvocationResponse) -> Self {
+        match value {
+            MigrateInvocationResponse::Ok { deployment_id } => Self::Ok { deployment_id },
+            MigrateInvocationResponse::AlreadyPinned => Self::AlreadyPinned,
+            MigrateInvocationResponse::NotFound => Self::NotFound,
+            MigrateInvocationResponse::NotStarted => Self::NotStarted,
+            MigrateInvocationResponse::Running => Self::Running,
+            MigrateInvocationResponse::NotPinned => Self::NotPinned,
+            MigrateInvocationResponse::DeploymentNotFound => Self::DeploymentNotFound,
+            MigrateInvocationResponse::IncompatibleDeploymentId {
+                pinned_protocol_version,
+                deployment_id,
+                supported_protocol_versions,
+            } => Self::IncompatibleDeploymentId {
+                pinned_protocol_version,
+                deployment_id,
+                supported_protocol_versions,
+            },
+            MigrateInvocationResponse::MissingHandler { deployment_id } => {
+                Self::MissingHandler { deployment_id }
+            }
+            MigrateInvocationResponse::Completed => Self::Completed,
+        }
+    }
+}
+
+impl From<MigrateInvocationRpcResponse> for PartitionProcessorRpcResponse {
+    fn from(value: MigrateInvocationRpcResponse) -> Self {
+        Self::MigrateInvocation(value)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PartitionProcessorRpcResponse {
     Appended,
@@ -501,4 +590,5 @@ pub enum PartitionProcessorRpcResponse {
     RestartAsNewInvocation(RestartAsNewInvocationRpcResponse),
     ResumeInvocation(ResumeInvocationRpcResponse),
     PauseInvocation(PauseInvocationRpcResponse),
+    MigrateInvocation(MigrateInvocationRpcResponse),
 }
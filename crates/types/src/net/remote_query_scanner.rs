@@ -15,7 +15,7 @@ use restate_encoding::RestateEncoding;
 
 use super::ServiceTag;
 use crate::GenerationalNodeId;
-use crate::identifiers::{PartitionId, PartitionKey};
+use crate::identifiers::{InvocationId, PartitionId, PartitionKey, ServiceId, WithPartitionKey};
 use crate::net::{bilrost_wire_codec, define_rpc, define_service};
 
 pub struct RemoteDataFusionService;
@@ -53,12 +53,81 @@ pub struct RemoteQueryScannerOpen {
     #[bilrost(tag(6))]
     #[serde(default = "default_batch_size")]
     pub batch_size: u64,
+    /// Scanners ignoring this filter fall back to scanning the whole `range`.
+    #[bilrost(tag(7))]
+    #[serde(default)]
+    pub key_filter: ScanKeyFilter,
 }
 
 fn default_batch_size() -> u64 {
     64
 }
 
+/// Keys extracted from the query predicates, restricting a partition scan.
+///
+/// Tables keyed by invocation or service id use them to replace the scan of the partition key
+/// range with point lookups or prefix scans. The filter is only an optimization, the predicates
+/// are still evaluated on the scanned rows. An empty filter doesn't restrict the scan.
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, bilrost::Message,
+)]
+pub struct ScanKeyFilter {
+    #[bilrost(1)]
+    #[serde(default)]
+    pub invocation_ids: Vec<InvocationId>,
+    #[bilrost(2)]
+    #[serde(default)]
+    pub services: Vec<ScanKeyFilterService>,
+}
+
+/// A virtual object or workflow instance selected by a [`ScanKeyFilter`].
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, bilrost::Message,
+)]
+pub struct ScanKeyFilterService {
+    #[bilrost(1)]
+    pub service_name: String,
+    #[bilrost(2)]
+    pub key: String,
+}
+
+impl ScanKeyFilter {
+    pub fn is_empty(&self) -> bool {
+        self.invocation_ids.is_empty() && self.services.is_empty()
+    }
+
+    /// Retains only the keys falling into the given partition key range.
+    pub fn for_range(&self, range: &RangeInclusive<PartitionKey>) -> Self {
+        Self {
+            invocation_ids: self
+                .invocation_ids
+                .iter()
+                .filter(|invocation_id| range.contains(&invocation_id.partition_key()))
+                .copied()
+                .collect(),
+            services: self
+                .services
+                .iter()
+                .filter(|service| range.contains(&service.service_id().partition_key()))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    pub fn service_ids(&self) -> Vec<ServiceId> {
+        self.services
+            .iter()
+            .map(ScanKeyFilterService::service_id)
+            .collect()
+    }
+}
+
+impl ScanKeyFilterService {
+    pub fn service_id(&self) -> ServiceId {
+        ServiceId::new(self.service_name.as_str(), self.key.as_str())
+    }
+}
+
 #[derive(
     Debug,
     Clone,
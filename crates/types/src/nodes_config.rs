@@ -24,7 +24,7 @@ use crate::net::metadata::{MetadataContainer, MetadataKind};
 use crate::{
     GenerationalNodeId, NodeId, PlainNodeId, base62_util, flexbuffers_storage_encode_decode,
 };
-use crate::{Version, Versioned};
+use crate::{SemanticRestateVersion, Version, Versioned};
 use ahash::HashMap;
 
 #[derive(
@@ -225,6 +225,11 @@ pub struct NodeConfig {
     #[serde(default)]
     #[builder(default)]
     pub worker_config: WorkerConfig,
+    /// Version of the restate binary the node last joined the cluster with. Unset for nodes that
+    /// joined with a version that didn't record it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[builder(default)]
+    pub binary_version: Option<SemanticRestateVersion>,
 }
 
 impl NodeConfig {
@@ -248,6 +253,7 @@ impl NodeConfig {
             location,
             metadata_server_config,
             worker_config,
+            binary_version: None,
         }
     }
 
@@ -489,6 +495,20 @@ impl NodesConfiguration {
         })
     }
 
+    /// Returns the lowest binary version among all the nodes of the cluster, or `None` if the
+    /// version of some node is unknown. Persisted formats that older versions can't decode must
+    /// only be written once all the nodes run a version supporting them.
+    pub fn min_binary_version(&self) -> Option<SemanticRestateVersion> {
+        let mut min_version: Option<&SemanticRestateVersion> = None;
+        for (_, node) in self.iter() {
+            let version = node.binary_version.as_ref()?;
+            if min_version.is_none_or(|min| version.cmp_precedence(min).is_lt()) {
+                min_version = Some(version);
+            }
+        }
+        min_version.cloned()
+    }
+
     /// Returns the maximum known plain node id.
     pub fn max_plain_node_id(&self) -> Option<PlainNodeId> {
         self.nodes.keys().max().cloned()
@@ -727,6 +747,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn min_binary_version() {
+        let mut config = NodesConfiguration::new_for_testing();
+        assert_eq!(None, config.min_binary_version());
+
+        let node = |id: u32, binary_version: Option<&str>| {
+            NodeConfig::builder()
+                .name(format!("node{id}"))
+                .current_generation(GenerationalNodeId::new(id, 1))
+                .address("unix:/tmp/my_socket".parse().unwrap())
+                .roles(EnumSet::only(Role::Worker))
+                .binary_version(binary_version.map(|v| v.parse().unwrap()))
+                .build()
+        };
+
+        config.upsert_node(node(1, Some("1.6.0")));
+        config.upsert_node(node(2, Some("1.6.0-dev")));
+        assert_eq!(
+            Some("1.6.0-dev".parse().unwrap()),
+            config.min_binary_version()
+        );
+
+        // a node that didn't record its version
+        config.upsert_node(node(3, None));
+        assert_eq!(None, config.min_binary_version());
+
+        // removed nodes don't count
+        config.remove_node_unchecked(NodeId::new_plain(3));
+        assert_eq!(
+            Some("1.6.0-dev".parse().unwrap()),
+            config.min_binary_version()
+        );
+    }
+
     #[test]
     fn test_upsert_node() {
         let mut config = NodesConfiguration::new_for_testing();
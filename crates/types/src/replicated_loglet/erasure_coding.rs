@@ -0,0 +1,490 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Erasure coding of records for replicated loglets.
+//!
+//! Instead of storing a full copy of every record on each node of the spread, an erasure-coded
+//! loglet splits the encoded body of a record into `data` fragments and computes `parity`
+//! additional fragments using a systematic Reed-Solomon code over GF(2^8). The i-th node in the
+//! loglet's nodeset stores the i-th fragment, and any `data` fragments are sufficient to
+//! reconstruct the record.
+//!
+//! Fragments are regular records that keep the keys and the creation time of the original
+//! record, so that log-servers can filter them without knowing about erasure coding.
+
+use std::fmt::{Display, Formatter};
+use std::num::NonZeroU8;
+use std::str::FromStr;
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+use crate::logs::Record;
+use crate::replication::ReplicationProperty;
+use crate::storage::PolyBytes;
+
+const FRAGMENT_FORMAT_VERSION: u8 = 1;
+// version + fragment index + original body length
+const FRAGMENT_HEADER_SIZE: usize = 1 + 1 + 4;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ErasureCodingError {
+    #[error("need {needed} distinct fragments to reconstruct the record, got {got}")]
+    NotEnoughFragments { needed: usize, got: usize },
+    #[error("malformed fragment: {0}")]
+    MalformedFragment(&'static str),
+    #[error("invalid erasure coding '{0}', expected '<data>+<parity>' with at most 255 fragments")]
+    InvalidSpec(String),
+}
+
+/// The erasure coding scheme of a replicated loglet. Displayed and parsed as `<data>+<parity>`,
+/// e.g. `4+2`.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    Hash,
+    serde_with::SerializeDisplay,
+    serde_with::DeserializeFromStr,
+)]
+pub struct ErasureCoding {
+    /// Number of fragments the record body is split into. Any `data` fragments are sufficient
+    /// to reconstruct the record.
+    data: NonZeroU8,
+    /// Number of additional parity fragments
+    parity: u8,
+}
+
+impl ErasureCoding {
+    pub fn new(data: NonZeroU8, parity: u8) -> Result<Self, ErasureCodingError> {
+        if usize::from(data.get()) + usize::from(parity) > usize::from(u8::MAX) {
+            return Err(ErasureCodingError::InvalidSpec(format!(
+                "{}+{}",
+                data, parity
+            )));
+        }
+        Ok(Self { data, parity })
+    }
+
+    pub fn data_fragments(&self) -> usize {
+        self.data.get().into()
+    }
+
+    pub fn parity_fragments(&self) -> usize {
+        self.parity.into()
+    }
+
+    /// The number of nodes an erasure-coded loglet's nodeset consists of
+    pub fn total_fragments(&self) -> usize {
+        self.data_fragments() + self.parity_fragments()
+    }
+
+    /// The replication property of an erasure-coded loglet, given the configured replication.
+    ///
+    /// A record is committed once `data + replication - 1` nodes have stored their fragment,
+    /// which tolerates the same number of node failures as full-copy replication. The tolerated
+    /// failures are capped by the number of parity fragments. Erasure-coded loglets only support
+    /// node-level replication.
+    pub fn replication_property(&self, replication: &ReplicationProperty) -> ReplicationProperty {
+        let tolerated_failures = replication.num_copies().saturating_sub(1).min(self.parity);
+        ReplicationProperty::new(
+            self.data
+                .checked_add(tolerated_failures)
+                .expect("total fragments fit into u8"),
+        )
+    }
+
+    /// Splits the record into [`Self::total_fragments`] fragment records.
+    pub fn encode_record(&self, record: &Record, scratch: &mut BytesMut) -> Vec<Record> {
+        let body = record
+            .body()
+            .encode_to_bytes(scratch)
+            .expect("record serde is infallible");
+        let original_len = u32::try_from(body.len()).expect("record body fits into u32");
+
+        let shards = encode_shards(&body, self.data_fragments(), self.parity_fragments());
+        shards
+            .iter()
+            .enumerate()
+            .map(|(index, shard)| {
+                scratch.reserve(FRAGMENT_HEADER_SIZE + shard.len());
+                scratch.put_u8(FRAGMENT_FORMAT_VERSION);
+                scratch.put_u8(u8::try_from(index).expect("fragment index fits into u8"));
+                scratch.put_u32(original_len);
+                scratch.put_slice(shard);
+                Record::from_parts(
+                    record.created_at(),
+                    record.keys().clone(),
+                    PolyBytes::Bytes(scratch.split().freeze()),
+                )
+            })
+            .collect()
+    }
+
+    /// Reconstructs the original record from at least [`Self::data_fragments`] distinct
+    /// fragments.
+    pub fn decode_record<'a>(
+        &self,
+        fragments: impl IntoIterator<Item = &'a Record>,
+    ) -> Result<Record, ErasureCodingError> {
+        let k = self.data_fragments();
+        let mut template = None;
+        let mut original_len = 0;
+        let mut shard_len = None;
+        let mut shards: Vec<(usize, Bytes)> = Vec::with_capacity(k);
+
+        for record in fragments {
+            let PolyBytes::Bytes(body) = record.body() else {
+                return Err(ErasureCodingError::MalformedFragment(
+                    "fragment is not encoded",
+                ));
+            };
+            let (index, len, shard) = decode_fragment(body.clone())?;
+            if index >= self.total_fragments() {
+                return Err(ErasureCodingError::MalformedFragment(
+                    "fragment index is out of range",
+                ));
+            }
+            if *shard_len.get_or_insert(shard.len()) != shard.len() {
+                return Err(ErasureCodingError::MalformedFragment(
+                    "fragments have different sizes",
+                ));
+            }
+            if shards.iter().any(|(existing, _)| *existing == index) {
+                continue;
+            }
+            original_len = len;
+            template.get_or_insert(record);
+            shards.push((index, shard));
+            if shards.len() == k {
+                break;
+            }
+        }
+
+        if shards.len() < k {
+            return Err(ErasureCodingError::NotEnoughFragments {
+                needed: k,
+                got: shards.len(),
+            });
+        }
+
+        let mut body = reconstruct_data(&shards, k);
+        if original_len > body.len() {
+            return Err(ErasureCodingError::MalformedFragment(
+                "fragments are shorter than the original record",
+            ));
+        }
+        body.truncate(original_len);
+
+        let template = template.expect("at least one fragment");
+        Ok(Record::from_parts(
+            template.created_at(),
+            template.keys().clone(),
+            PolyBytes::Bytes(Bytes::from(body)),
+        ))
+    }
+}
+
+impl Display for ErasureCoding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}+{}", self.data, self.parity)
+    }
+}
+
+impl FromStr for ErasureCoding {
+    type Err = ErasureCodingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ErasureCodingError::InvalidSpec(s.to_owned());
+        let (data, parity) = s.split_once('+').ok_or_else(invalid)?;
+        let data = data.trim().parse().map_err(|_| invalid())?;
+        let parity = parity.trim().parse().map_err(|_| invalid())?;
+        Self::new(data, parity).map_err(|_| invalid())
+    }
+}
+
+fn decode_fragment(mut body: Bytes) -> Result<(usize, usize, Bytes), ErasureCodingError> {
+    if body.len() < FRAGMENT_HEADER_SIZE {
+        return Err(ErasureCodingError::MalformedFragment(
+            "fragment is too short",
+        ));
+    }
+    if body.get_u8() != FRAGMENT_FORMAT_VERSION {
+        return Err(ErasureCodingError::MalformedFragment(
+            "unsupported fragment format version",
+        ));
+    }
+    let index = usize::from(body.get_u8());
+    let original_len = usize::try_from(body.get_u32()).expect("u32 fits into usize");
+    Ok((index, original_len, body))
+}
+
+/// Splits `data` into `k` equally sized data shards (zero-padded) followed by `m` parity shards.
+fn encode_shards(data: &[u8], k: usize, m: usize) -> Vec<Vec<u8>> {
+    let shard_len = data.len().div_ceil(k);
+    let mut shards: Vec<Vec<u8>> = (0..k)
+        .map(|i| {
+            let start = (i * shard_len).min(data.len());
+            let end = ((i + 1) * shard_len).min(data.len());
+            let mut shard = Vec::with_capacity(shard_len);
+            shard.extend_from_slice(&data[start..end]);
+            shard.resize(shard_len, 0);
+            shard
+        })
+        .collect();
+
+    for parity_row in 0..m {
+        let mut parity = vec![0u8; shard_len];
+        for (col, shard) in shards[..k].iter().enumerate() {
+            mul_add(&mut parity, shard, cauchy(k, parity_row, col));
+        }
+        shards.push(parity);
+    }
+    shards
+}
+
+/// Reconstructs the concatenated data shards from exactly `k` distinct shards.
+fn reconstruct_data(shards: &[(usize, Bytes)], k: usize) -> Vec<u8> {
+    debug_assert_eq!(k, shards.len());
+    let shard_len = shards[0].1.len();
+
+    // fast path, all data shards are available
+    let mut data_shards: Vec<Option<&Bytes>> = vec![None; k];
+    for (index, shard) in shards {
+        if *index < k {
+            data_shards[*index] = Some(shard);
+        }
+    }
+    if data_shards.iter().all(Option::is_some) {
+        let mut data = Vec::with_capacity(k * shard_len);
+        for shard in data_shards.into_iter().flatten() {
+            data.extend_from_slice(shard);
+        }
+        return data;
+    }
+
+    // The rows of the generator matrix of the available shards. Data shards are rows of the
+    // identity matrix, parity shards are rows of the Cauchy matrix.
+    let mut matrix: Vec<Vec<u8>> = shards
+        .iter()
+        .map(|(index, _)| {
+            (0..k)
+                .map(|col| {
+                    if *index < k {
+                        u8::from(*index == col)
+                    } else {
+                        cauchy(k, *index - k, col)
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    let inverse = invert(&mut matrix);
+
+    let mut data = vec![0u8; k * shard_len];
+    for (row, chunk) in inverse.iter().zip(data.chunks_mut(shard_len.max(1))) {
+        for (coefficient, (_, shard)) in row.iter().zip(shards) {
+            mul_add(chunk, shard, *coefficient);
+        }
+    }
+    data
+}
+
+/// The coefficient of the Cauchy matrix at (`row`, `col`) is `1 / (x_row + y_col)` where
+/// `x_row = k + row` and `y_col = col`. Since both sets are disjoint, every square sub-matrix is
+/// invertible, which makes the code maximum distance separable.
+fn cauchy(k: usize, row: usize, col: usize) -> u8 {
+    let x = u8::try_from(k + row).expect("total fragments fit into u8");
+    let y = u8::try_from(col).expect("data fragments fit into u8");
+    gf_inv(x ^ y)
+}
+
+/// Inverts a square matrix over GF(2^8) with Gauss-Jordan elimination. The matrix must be
+/// invertible, which is guaranteed for the sub-matrices of the generator matrix.
+fn invert(matrix: &mut [Vec<u8>]) -> Vec<Vec<u8>> {
+    let n = matrix.len();
+    let mut inverse: Vec<Vec<u8>> = (0..n)
+        .map(|row| (0..n).map(|col| u8::from(row == col)).collect())
+        .collect();
+
+    for col in 0..n {
+        let pivot = (col..n)
+            .find(|row| matrix[*row][col] != 0)
+            .expect("generator sub-matrix is invertible");
+        matrix.swap(col, pivot);
+        inverse.swap(col, pivot);
+
+        let scale = gf_inv(matrix[col][col]);
+        for value in matrix[col].iter_mut() {
+            *value = gf_mul(*value, scale);
+        }
+        for value in inverse[col].iter_mut() {
+            *value = gf_mul(*value, scale);
+        }
+
+        // eliminate the pivot column from all other rows
+        let pivot_row = matrix[col].clone();
+        let pivot_inverse_row = inverse[col].clone();
+        for (row, (matrix_row, inverse_row)) in
+            matrix.iter_mut().zip(inverse.iter_mut()).enumerate()
+        {
+            let factor = matrix_row[col];
+            if row == col || factor == 0 {
+                continue;
+            }
+            mul_add(matrix_row, &pivot_row, factor);
+            mul_add(inverse_row, &pivot_inverse_row, factor);
+        }
+    }
+    inverse
+}
+
+/// `dst += coefficient * src`
+fn mul_add(dst: &mut [u8], src: &[u8], coefficient: u8) {
+    if coefficient == 0 {
+        return;
+    }
+    for (d, s) in dst.iter_mut().zip(src) {
+        *d ^= gf_mul(coefficient, *s);
+    }
+}
+
+// Arithmetic in GF(2^8) with the primitive polynomial x^8 + x^4 + x^3 + x^2 + 1 (0x11d)
+const GF_TABLES: ([u8; 512], [u8; 256]) = {
+    let mut exp = [0u8; 512];
+    let mut log = [0u8; 256];
+    let mut x: u16 = 1;
+    let mut i = 0;
+    while i < 255 {
+        exp[i] = x as u8;
+        log[x as usize] = i as u8;
+        x <<= 1;
+        if x & 0x100 != 0 {
+            x ^= 0x11d;
+        }
+        i += 1;
+    }
+    while i < 512 {
+        exp[i] = exp[i - 255];
+        i += 1;
+    }
+    (exp, log)
+};
+
+fn gf_mul(a: u8, b: u8) -> u8 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    let (exp, log) = &GF_TABLES;
+    exp[usize::from(log[usize::from(a)]) + usize::from(log[usize::from(b)])]
+}
+
+fn gf_inv(a: u8) -> u8 {
+    assert_ne!(a, 0, "zero has no inverse");
+    let (exp, log) = &GF_TABLES;
+    exp[255 - usize::from(log[usize::from(a)])]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::logs::Keys;
+    use crate::time::NanosSinceEpoch;
+
+    fn record(body: &[u8]) -> Record {
+        Record::from_parts(
+            NanosSinceEpoch::now(),
+            Keys::Single(42),
+            PolyBytes::Bytes(Bytes::copy_from_slice(body)),
+        )
+    }
+
+    fn body(record: &Record) -> Bytes {
+        let PolyBytes::Bytes(body) = record.body() else {
+            panic!("expected encoded body");
+        };
+        body.clone()
+    }
+
+    #[test]
+    fn parse_and_display() {
+        let coding: ErasureCoding = "4+2".parse().unwrap();
+        assert_eq!(4, coding.data_fragments());
+        assert_eq!(2, coding.parity_fragments());
+        assert_eq!("4+2", coding.to_string());
+
+        assert!("4".parse::<ErasureCoding>().is_err());
+        assert!("0+2".parse::<ErasureCoding>().is_err());
+        assert!("200+100".parse::<ErasureCoding>().is_err());
+    }
+
+    #[test]
+    fn replication_property() {
+        let coding: ErasureCoding = "4+2".parse().unwrap();
+        assert_eq!(
+            ReplicationProperty::new_unchecked(5),
+            coding.replication_property(&ReplicationProperty::new_unchecked(2))
+        );
+        // tolerated failures are capped by the parity fragments
+        assert_eq!(
+            ReplicationProperty::new_unchecked(6),
+            coding.replication_property(&ReplicationProperty::new_unchecked(5))
+        );
+    }
+
+    #[test]
+    fn reconstruct_from_any_data_fragments() {
+        let coding: ErasureCoding = "4+2".parse().unwrap();
+        let original = record(b"the quick brown fox jumps over the lazy dog");
+        let fragments = coding.encode_record(&original, &mut BytesMut::new());
+        assert_eq!(6, fragments.len());
+        for fragment in &fragments {
+            assert_eq!(original.keys(), fragment.keys());
+            assert_eq!(original.created_at(), fragment.created_at());
+        }
+
+        // every combination of 4 out of 6 fragments
+        for missing_a in 0..6 {
+            for missing_b in (missing_a + 1)..6 {
+                let available = fragments
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| *i != missing_a && *i != missing_b)
+                    .map(|(_, f)| f);
+                let decoded = coding.decode_record(available).unwrap();
+                assert_eq!(body(&original), body(&decoded));
+                assert_eq!(original.keys(), decoded.keys());
+            }
+        }
+
+        let result = coding.decode_record(&fragments[..3]);
+        assert!(matches!(
+            result,
+            Err(ErasureCodingError::NotEnoughFragments { needed: 4, got: 3 })
+        ));
+        // duplicates don't count
+        let result =
+            coding.decode_record([&fragments[0], &fragments[0], &fragments[1], &fragments[2]]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn empty_and_short_bodies() {
+        let coding: ErasureCoding = "3+2".parse().unwrap();
+        for len in 0..8 {
+            let original = record(&vec![7u8; len]);
+            let fragments = coding.encode_record(&original, &mut BytesMut::new());
+            let decoded = coding.decode_record(fragments.iter().rev()).unwrap();
+            assert_eq!(body(&original), body(&decoded));
+        }
+    }
+}
@@ -8,10 +8,12 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+mod erasure_coding;
 mod log_nodeset;
 mod params;
 mod spread;
 
+pub use erasure_coding::*;
 pub use log_nodeset::*;
 pub use params::*;
 pub use spread::*;
@@ -8,9 +8,10 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use crate::GenerationalNodeId;
+use super::ErasureCoding;
 use crate::logs::LogletId;
 use crate::replication::{NodeSet, ReplicationProperty};
+use crate::{GenerationalNodeId, PlainNodeId};
 
 /// Configuration parameters of a replicated loglet segment
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
@@ -23,6 +24,10 @@ pub struct ReplicatedLogletParams {
     /// Replication properties of this loglet
     pub replication: ReplicationProperty,
     pub nodeset: NodeSet,
+    /// If set, nodes of the nodeset store erasure-coded fragments of the records instead of
+    /// full copies. The i-th node of the nodeset stores the i-th fragment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub erasure_coding: Option<ErasureCoding>,
 }
 
 impl ReplicatedLogletParams {
@@ -33,4 +38,11 @@ impl ReplicatedLogletParams {
     pub fn serialize(&self) -> Result<String, serde_json::Error> {
         serde_json::to_string(self)
     }
+
+    /// The index of the erasure-coded fragment that the given node stores, if the loglet is
+    /// erasure-coded and the node is part of its nodeset.
+    pub fn fragment_index(&self, node_id: PlainNodeId) -> Option<usize> {
+        self.erasure_coding?;
+        self.nodeset.iter().position(|n| *n == node_id)
+    }
 }
@@ -11,19 +11,25 @@
 use std::collections::HashMap;
 use std::fmt;
 use std::fmt::{Display, Formatter};
+use std::num::NonZeroUsize;
 use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::time::Duration;
 
 use crate::config::Configuration;
 use crate::deployment::{
     DeploymentAddress, Headers, HttpDeploymentAddress, LambdaDeploymentAddress,
+    WasmDeploymentAddress,
 };
 use crate::identifiers::{DeploymentId, LambdaARN, ServiceRevision};
 use crate::schema::info::Info;
+use crate::schema::routing::ServiceRouting;
 use crate::schema::service::ServiceMetadata;
 use crate::time::MillisSinceEpoch;
 use bytestring::ByteString;
 use http::Uri;
 use http::header::{HeaderName, HeaderValue};
+use restate_time_util::FriendlyDuration;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
@@ -43,6 +49,8 @@ pub struct Deployment {
     pub ty: DeploymentType,
     pub additional_headers: HashMap<HeaderName, HeaderValue>,
     pub supported_protocol_versions: RangeInclusive<i32>,
+    /// Service protocol message compression advertised during discovery
+    pub message_compression: Option<MessageCompression>,
     /// Declared SDK during discovery
     pub sdk_version: Option<String>,
     pub created_at: MillisSinceEpoch,
@@ -80,7 +88,9 @@ impl Deployment {
                     address: this_address,
                     ..
                 },
-                DeploymentAddress::Http(HttpDeploymentAddress { uri: other_address }),
+                DeploymentAddress::Http(HttpDeploymentAddress {
+                    uri: other_address, ..
+                }),
             ) => Self::semantic_eq_http(
                 this_address,
                 other_address,
@@ -91,6 +101,16 @@ impl Deployment {
                 DeploymentType::Lambda { arn: this_arn, .. },
                 DeploymentAddress::Lambda(LambdaDeploymentAddress { arn: other_arn, .. }),
             ) => Self::semantic_eq_lambda(this_arn, other_arn),
+            (
+                DeploymentType::Wasm {
+                    module_path: this_module_path,
+                    ..
+                },
+                DeploymentAddress::Wasm(WasmDeploymentAddress {
+                    module_path: other_module_path,
+                    ..
+                }),
+            ) => this_module_path == other_module_path,
             _ => false,
         }
     }
@@ -132,6 +152,73 @@ impl EndpointLambdaCompression {
     }
 }
 
+/// # HTTP connection pool settings
+///
+/// Connection pool settings of an HTTP deployment, overriding the ones configured in the
+/// `service-client` options of the Restate server. Only HTTP/2 connections are pooled.
+#[serde_as]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct HttpConnectionPoolSettings {
+    /// # Max connections
+    ///
+    /// Maximum number of connections opened to the deployment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_connections: Option<NonZeroUsize>,
+
+    /// # Max streams per connection
+    ///
+    /// Maximum number of concurrent streams opened on a connection before opening a new one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_streams_per_connection: Option<NonZeroUsize>,
+
+    /// # Idle timeout
+    ///
+    /// How long an idle connection is kept open.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "serde_with::As::<Option<FriendlyDuration>>"
+    )]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    pub idle_timeout: Option<Duration>,
+
+    /// # Keep-alive interval
+    ///
+    /// Interval between the HTTP/2 PING frames keeping the connections alive.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "serde_with::As::<Option<FriendlyDuration>>"
+    )]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    pub keep_alive_interval: Option<Duration>,
+
+    /// # Keep-alive timeout
+    ///
+    /// How long to wait for the acknowledgement of a keep-alive PING before closing the connection.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "serde_with::As::<Option<FriendlyDuration>>"
+    )]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    pub keep_alive_timeout: Option<Duration>,
+}
+
+impl HttpConnectionPoolSettings {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Compression of the service protocol messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum MessageCompression {
+    Zstd,
+}
+
 // TODO this type is serde because it represents how data is stored in the schema registry
 //  re-evaluate whether we should use another ad-hoc data structure for storage representation after schema v2 migration.
 #[serde_as]
@@ -144,6 +231,11 @@ pub enum DeploymentType {
         protocol_type: ProtocolType,
         #[serde(with = "serde_with::As::<restate_serde_util::VersionSerde>")]
         http_version: http::Version,
+        #[serde(
+            default,
+            skip_serializing_if = "HttpConnectionPoolSettings::is_default"
+        )]
+        connection_pool: HttpConnectionPoolSettings,
     },
     Lambda {
         arn: LambdaARN,
@@ -151,6 +243,13 @@ pub enum DeploymentType {
         #[serde(default, skip_serializing_if = "Option::is_none")]
         compression: Option<EndpointLambdaCompression>,
     },
+    Wasm {
+        module_path: PathBuf,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fuel_limit: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        memory_limit: Option<u64>,
+    },
 }
 
 impl DeploymentType {
@@ -163,6 +262,9 @@ impl DeploymentType {
                 match self {
                     Wrapper(DeploymentType::Http { address, .. }) => address.fmt(f),
                     Wrapper(DeploymentType::Lambda { arn, .. }) => arn.fmt(f),
+                    Wrapper(DeploymentType::Wasm { module_path, .. }) => {
+                        write!(f, "wasm://{}", module_path.display())
+                    }
                 }
             }
         }
@@ -171,15 +273,24 @@ impl DeploymentType {
 
     pub fn as_address(&self) -> DeploymentAddress {
         match self {
-            DeploymentType::Http { address, .. } => {
-                HttpDeploymentAddress::new(address.clone()).into()
-            }
+            DeploymentType::Http {
+                address,
+                connection_pool,
+                ..
+            } => HttpDeploymentAddress::new(address.clone())
+                .with_connection_pool(*connection_pool)
+                .into(),
             DeploymentType::Lambda {
                 arn,
                 assume_role_arn,
                 ..
             } => LambdaDeploymentAddress::new(arn.clone(), assume_role_arn.clone().map(Into::into))
                 .into(),
+            DeploymentType::Wasm {
+                module_path,
+                fuel_limit,
+                memory_limit,
+            } => WasmDeploymentAddress::new(module_path.clone(), *fuel_limit, *memory_limit).into(),
         }
     }
 
@@ -193,7 +304,9 @@ impl DeploymentType {
     pub fn protocol_type(&self) -> ProtocolType {
         match self {
             DeploymentType::Http { protocol_type, .. } => *protocol_type,
-            DeploymentType::Lambda { .. } => ProtocolType::RequestResponse,
+            DeploymentType::Lambda { .. } | DeploymentType::Wasm { .. } => {
+                ProtocolType::RequestResponse
+            }
         }
     }
 }
@@ -204,6 +317,11 @@ pub trait DeploymentResolver {
         service_name: impl AsRef<str>,
     ) -> Option<Deployment>;
 
+    /// Returns the rules routing the new invocations of the given service, if any.
+    ///
+    /// The routes always point to deployments containing the service.
+    fn resolve_service_routing(&self, service_name: impl AsRef<str>) -> Option<ServiceRouting>;
+
     fn find_deployment(
         &self,
         deployment_address: &DeploymentAddress,
@@ -235,6 +353,8 @@ mod serde_hacks {
             )]
             // this field did not used to be stored, so we must consider it optional when deserialising
             http_version: Option<http::Version>,
+            #[serde(default)]
+            connection_pool: HttpConnectionPoolSettings,
         },
         Lambda {
             arn: LambdaARN,
@@ -242,6 +362,13 @@ mod serde_hacks {
             #[serde(default, skip_serializing_if = "Option::is_none")]
             compression: Option<EndpointLambdaCompression>,
         },
+        Wasm {
+            module_path: PathBuf,
+            #[serde(default)]
+            fuel_limit: Option<u64>,
+            #[serde(default)]
+            memory_limit: Option<u64>,
+        },
     }
 
     impl From<DeploymentType> for super::DeploymentType {
@@ -251,6 +378,7 @@ mod serde_hacks {
                     address,
                     protocol_type,
                     http_version,
+                    connection_pool,
                 } => Self::Http {
                     address,
                     protocol_type,
@@ -258,6 +386,7 @@ mod serde_hacks {
                         Some(v) => v,
                         None => Self::backfill_http_version(protocol_type),
                     },
+                    connection_pool,
                 },
                 DeploymentType::Lambda {
                     arn,
@@ -268,6 +397,15 @@ mod serde_hacks {
                     assume_role_arn,
                     compression,
                 },
+                DeploymentType::Wasm {
+                    module_path,
+                    fuel_limit,
+                    memory_limit,
+                } => Self::Wasm {
+                    module_path,
+                    fuel_limit,
+                    memory_limit,
+                },
             }
         }
     }
@@ -314,6 +452,7 @@ mod serde_tests {
                 address: Uri::from_static("google.com"),
                 protocol_type: ProtocolType::BidiStream,
                 http_version: http::Version::HTTP_2,
+                connection_pool: Default::default(),
             },
             dt
         );
@@ -333,10 +472,32 @@ mod serde_tests {
                 address: Uri::from_static("google.com"),
                 protocol_type: ProtocolType::RequestResponse,
                 http_version: http::Version::HTTP_11,
+                connection_pool: Default::default(),
             },
             dt
         );
     }
+
+    #[test]
+    fn wasm_roundtrip() {
+        for deployment_type in [
+            DeploymentType::Wasm {
+                module_path: "/opt/restate/greeter.wasm".into(),
+                fuel_limit: None,
+                memory_limit: None,
+            },
+            DeploymentType::Wasm {
+                module_path: "/opt/restate/greeter.wasm".into(),
+                fuel_limit: Some(1_000),
+                memory_limit: Some(1024 * 1024),
+            },
+        ] {
+            let mut buf = bytes::BytesMut::default();
+            StorageCodec::encode(&deployment_type, &mut buf).unwrap();
+            let dt: DeploymentType = StorageCodec::decode(&mut buf).unwrap();
+            assert_eq!(deployment_type, dt);
+        }
+    }
 }
 
 #[cfg(feature = "test-util")]
@@ -357,8 +518,10 @@ pub mod test_util {
                     address: "http://localhost:9080".parse().unwrap(),
                     protocol_type: ProtocolType::BidiStream,
                     http_version: http::Version::HTTP_2,
+                    connection_pool: Default::default(),
                 },
                 supported_protocol_versions: 1..=MAX_SERVICE_PROTOCOL_VERSION_VALUE,
+                message_compression: None,
                 sdk_version: None,
                 created_at: MillisSinceEpoch::now(),
                 metadata: Default::default(),
@@ -377,8 +540,10 @@ pub mod test_util {
                     address: uri.parse().unwrap(),
                     protocol_type: ProtocolType::BidiStream,
                     http_version: http::Version::HTTP_2,
+                    connection_pool: Default::default(),
                 },
                 supported_protocol_versions: 1..=MAX_SERVICE_PROTOCOL_VERSION_VALUE,
+                message_compression: None,
                 sdk_version: None,
                 created_at: MillisSinceEpoch::now(),
                 metadata: Default::default(),
@@ -391,7 +556,9 @@ pub mod test_util {
     #[derive(Default, Clone, Debug)]
     pub struct MockDeploymentMetadataRegistry {
         pub deployments: HashMap<DeploymentId, Deployment>,
+        pub services: HashMap<DeploymentId, Vec<ServiceMetadata>>,
         pub latest_deployment: HashMap<String, DeploymentId>,
+        pub service_routing: HashMap<String, ServiceRouting>,
     }
 
     impl MockDeploymentMetadataRegistry {
@@ -399,9 +566,22 @@ pub mod test_util {
             self.deployments.insert(deployment.id, deployment);
         }
 
+        pub fn mock_deployment_with_services(
+            &mut self,
+            deployment: Deployment,
+            services: Vec<ServiceMetadata>,
+        ) {
+            self.services.insert(deployment.id, services);
+            self.mock_deployment(deployment);
+        }
+
         pub fn mock_latest_service(&mut self, service: &str, deployment_id: DeploymentId) {
             self.latest_deployment.insert(service.into(), deployment_id);
         }
+
+        pub fn mock_service_routing(&mut self, service: &str, service_routing: ServiceRouting) {
+            self.service_routing.insert(service.into(), service_routing);
+        }
     }
 
     impl DeploymentResolver for MockDeploymentMetadataRegistry {
@@ -414,6 +594,10 @@ pub mod test_util {
                 .and_then(|deployment_id| self.get_deployment(deployment_id))
         }
 
+        fn resolve_service_routing(&self, service_name: impl AsRef<str>) -> Option<ServiceRouting> {
+            self.service_routing.get(service_name.as_ref()).cloned()
+        }
+
         fn find_deployment(
             &self,
             deployment_address: &DeploymentAddress,
@@ -438,7 +622,14 @@ pub mod test_util {
             self.deployments
                 .get(deployment_id)
                 .cloned()
-                .map(|deployment| (deployment, vec![]))
+                .map(|deployment| {
+                    let services = self
+                        .services
+                        .get(deployment_id)
+                        .cloned()
+                        .unwrap_or_default();
+                    (deployment, services)
+                })
         }
 
         fn get_deployments(&self) -> Vec<(Deployment, Vec<(String, ServiceRevision)>)> {
@@ -454,6 +645,10 @@ pub mod test_util {
             None
         }
 
+        fn resolve_service_routing(&self, _: impl AsRef<str>) -> Option<ServiceRouting> {
+            None
+        }
+
         fn find_deployment(
             &self,
             _: &DeploymentAddress,
@@ -14,6 +14,8 @@ use crate::invocation::{
 
 use crate::identifiers::DeploymentId;
 use crate::retries::RetryIter;
+use crate::schema::service::ErrorClass;
+use crate::time::MillisSinceEpoch;
 use bytes::Bytes;
 use bytestring::ByteString;
 use itertools::Itertools;
@@ -60,6 +62,18 @@ pub trait InvocationTargetResolver {
         service_name: impl AsRef<str>,
         handler_name: impl AsRef<str>,
     ) -> (RetryIter<'static>, OnMaxAttempts);
+
+    /// Resolve the invocation retry rules, in the order they should be evaluated.
+    ///
+    /// The retry policy of each rule is applied on top of the retry policy returned by [`Self::resolve_invocation_retry_policy`].
+    ///
+    /// If deployment id is not provided, the last service/handler configuration will be applied instead.
+    fn resolve_invocation_retry_rules(
+        &self,
+        deployment_id: Option<&DeploymentId>,
+        service_name: impl AsRef<str>,
+        handler_name: impl AsRef<str>,
+    ) -> Vec<ResolvedRetryRule>;
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
@@ -72,6 +86,37 @@ pub enum OnMaxAttempts {
     Kill,
 }
 
+/// Retry rule of a handler, see [`RetryRule`](super::service::RetryRule).
+#[derive(Debug)]
+pub struct ResolvedRetryRule {
+    pub error_class: ErrorClass,
+    pub codes: Vec<u16>,
+    pub action: ResolvedRetryRuleAction,
+}
+
+impl ResolvedRetryRule {
+    /// Returns true if this rule applies to an error of the given class and code.
+    pub fn matches(&self, error_class: ErrorClass, code: Option<u16>) -> bool {
+        self.error_class == error_class
+            && match error_class {
+                ErrorClass::HttpStatus | ErrorClass::ErrorCode => {
+                    code.is_some_and(|code| self.codes.contains(&code))
+                }
+                ErrorClass::Timeout | ErrorClass::ConnectionRefused => true,
+            }
+    }
+}
+
+#[derive(Debug)]
+pub enum ResolvedRetryRuleAction {
+    /// Retry using the given retry policy, applying the given behavior once retries are exhausted.
+    Retry(RetryIter<'static>, OnMaxAttempts),
+    /// Fail the invocation with a terminal error.
+    Fail,
+    /// Pause the invocation.
+    Pause,
+}
+
 #[derive(Debug, Clone, Default)]
 pub enum DeploymentStatus {
     #[default]
@@ -93,6 +138,9 @@ pub struct InvocationTargetMetadata {
     pub input_rules: InputRules,
     pub output_rules: OutputRules,
 
+    /// Default end-to-end deadline for invocations to this target, measured from the invocation creation.
+    pub deadline: Option<Duration>,
+
     pub deployment_status: DeploymentStatus,
 }
 
@@ -117,6 +165,16 @@ impl InvocationTargetMetadata {
             _ => InvocationRetention::none(),
         }
     }
+
+    /// Computes the absolute deadline from the configured [`InvocationTargetMetadata::deadline`].
+    /// The deadline is measured starting from the `execution_time`, or from now if the invocation is not delayed.
+    pub fn compute_deadline(
+        &self,
+        execution_time: Option<MillisSinceEpoch>,
+    ) -> Option<MillisSinceEpoch> {
+        self.deadline
+            .map(|deadline| execution_time.unwrap_or_else(MillisSinceEpoch::now) + deadline)
+    }
 }
 
 #[derive(Debug, Eq, PartialEq, Default)]
@@ -576,6 +634,15 @@ pub mod test_util {
                 retry_policy.on_max_attempts,
             )
         }
+
+        fn resolve_invocation_retry_rules(
+            &self,
+            _: Option<&DeploymentId>,
+            _: impl AsRef<str>,
+            _: impl AsRef<str>,
+        ) -> Vec<ResolvedRetryRule> {
+            vec![]
+        }
     }
 
     impl InvocationTargetMetadata {
@@ -587,6 +654,7 @@ pub mod test_util {
                 target_ty: invocation_target_type,
                 input_rules: Default::default(),
                 output_rules: Default::default(),
+                deadline: None,
                 deployment_status: DeploymentStatus::Enabled,
             }
         }
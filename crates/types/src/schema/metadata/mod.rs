@@ -20,6 +20,7 @@ use restate_time_util::FriendlyDuration;
 use crate::config::{Configuration, InvocationRetryPolicyOptions};
 use crate::deployment::{
     DeploymentAddress, Headers, HttpDeploymentAddress, LambdaDeploymentAddress,
+    WasmDeploymentAddress,
 };
 use crate::identifiers::{DeploymentId, SubscriptionId};
 use crate::invocation::{InvocationTargetType, ServiceType, WorkflowHandlerType};
@@ -28,16 +29,21 @@ use crate::metadata::GlobalMetadata;
 use crate::net::address::{AdvertisedAddress, HttpIngressPort};
 use crate::net::metadata::{MetadataContainer, MetadataKind};
 use crate::retries::{RetryIter, RetryPolicy};
-use crate::schema::deployment::{DeploymentResolver, DeploymentType, ProtocolType};
+use crate::schema::deployment::{
+    DeploymentResolver, DeploymentType, MessageCompression, ProtocolType,
+};
 use crate::schema::info::Info;
 use crate::schema::invocation_target::{
     DEFAULT_IDEMPOTENCY_RETENTION, DEFAULT_WORKFLOW_COMPLETION_RETENTION, DeploymentStatus,
     InputRules, InvocationAttemptOptions, InvocationTargetMetadata, InvocationTargetResolver,
-    OnMaxAttempts, OutputRules,
+    OnMaxAttempts, OutputRules, ResolvedRetryRule, ResolvedRetryRuleAction,
 };
 use crate::schema::metadata::openapi::ServiceOpenAPI;
+use crate::schema::migration::MigrationPolicy;
+use crate::schema::routing::ServiceRouting;
 use crate::schema::service::{
-    HandlerRetryPolicyMetadata, ServiceMetadataResolver, ServiceRetryPolicyMetadata,
+    HandlerRetryPolicyMetadata, RetryRule, RetryRuleAction, ServiceMetadataResolver,
+    ServiceRetryPolicyMetadata,
 };
 use crate::schema::subscriptions::{ListSubscriptionFilter, Subscription, SubscriptionResolver};
 use crate::schema::{deployment, service};
@@ -58,6 +64,10 @@ pub struct Schema {
     deployments: HashMap<DeploymentId, Deployment>,
     active_service_revisions: HashMap<String, ActiveServiceRevision>,
     subscriptions: HashMap<SubscriptionId, Subscription>,
+    /// Routing rules of new invocations, per service name.
+    service_routing: HashMap<String, ServiceRouting>,
+    /// Migration policies of suspended and paused invocations, per service name.
+    migration_policies: HashMap<String, MigrationPolicy>,
 }
 
 impl Default for Schema {
@@ -67,6 +77,8 @@ impl Default for Schema {
             active_service_revisions: HashMap::default(),
             deployments: HashMap::default(),
             subscriptions: HashMap::default(),
+            service_routing: HashMap::default(),
+            migration_policies: HashMap::default(),
         }
     }
 }
@@ -160,6 +172,9 @@ struct Deployment {
     ty: DeploymentType,
     delivery_options: DeliveryOptions,
     supported_protocol_versions: RangeInclusive<i32>,
+    /// Service protocol message compression advertised during discovery
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    message_compression: Option<MessageCompression>,
     /// Declared SDK during discovery
     sdk_version: Option<String>,
     created_at: MillisSinceEpoch,
@@ -186,6 +201,7 @@ impl Deployment {
             id: self.id,
             ty: self.ty.clone(),
             supported_protocol_versions: self.supported_protocol_versions.clone(),
+            message_compression: self.message_compression,
             sdk_version: self.sdk_version.clone(),
             created_at: self.created_at,
             metadata: self.metadata.clone(),
@@ -205,7 +221,9 @@ impl Deployment {
                     address: this_address,
                     ..
                 },
-                DeploymentAddress::Http(HttpDeploymentAddress { uri: other_address }),
+                DeploymentAddress::Http(HttpDeploymentAddress {
+                    uri: other_address, ..
+                }),
             ) => deployment::Deployment::semantic_eq_http(
                 this_address,
                 other_address,
@@ -216,6 +234,16 @@ impl Deployment {
                 DeploymentType::Lambda { arn: this_arn, .. },
                 DeploymentAddress::Lambda(LambdaDeploymentAddress { arn: other_arn, .. }),
             ) => deployment::Deployment::semantic_eq_lambda(this_arn, other_arn),
+            (
+                DeploymentType::Wasm {
+                    module_path: this_module_path,
+                    ..
+                },
+                DeploymentAddress::Wasm(WasmDeploymentAddress {
+                    module_path: other_module_path,
+                    ..
+                }),
+            ) => this_module_path == other_module_path,
             _ => false,
         }
     }
@@ -483,6 +511,8 @@ struct Handler {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     abort_timeout: Option<Duration>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    deadline: Option<Duration>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     documentation: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none", default)]
     enable_lazy_state: Option<bool>,
@@ -506,6 +536,8 @@ struct Handler {
     retry_policy_max_interval: Option<Duration>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     retry_policy_on_max_attempts: Option<OnMaxAttempts>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    retry_rules: Vec<RetryRule>,
 }
 
 impl MapAsVecItem for Handler {
@@ -564,6 +596,7 @@ impl Handler {
                 self.inactivity_timeout
             },
             abort_timeout: self.abort_timeout,
+            deadline: self.deadline,
             enable_lazy_state: self.enable_lazy_state,
             retry_policy: HandlerRetryPolicyMetadata {
                 initial_interval: self.retry_policy_initial_interval,
@@ -572,6 +605,7 @@ impl Handler {
                 max_interval: self.retry_policy_max_interval,
                 on_max_attempts: self.retry_policy_on_max_attempts,
             },
+            retry_rules: self.retry_rules.clone(),
             info,
         }
     }
@@ -591,6 +625,10 @@ impl DeploymentResolver for Schema {
             .map(|dp| dp.to_deployment())
     }
 
+    fn resolve_service_routing(&self, service_name: impl AsRef<str>) -> Option<ServiceRouting> {
+        self.service_routing.get(service_name.as_ref()).cloned()
+    }
+
     fn find_deployment(
         &self,
         deployment_address: &DeploymentAddress,
@@ -723,6 +761,7 @@ impl InvocationTargetResolver for Schema {
             target_ty: handler.target_ty,
             input_rules: handler.input_rules.clone(),
             output_rules: handler.output_rules.clone(),
+            deadline: handler.deadline,
             deployment_status,
         })
     }
@@ -764,40 +803,133 @@ impl InvocationTargetResolver for Schema {
         handler_name: impl AsRef<str>,
     ) -> (RetryIter<'static>, OnMaxAttempts) {
         let configuration = Configuration::pinned();
+        let (retry_policy, _) = self.resolve_computed_retry_policy(
+            &configuration,
+            deployment_id,
+            service_name.as_ref(),
+            handler_name.as_ref(),
+        );
+
+        (
+            retry_policy.as_retry_policy().into_iter(),
+            retry_policy.on_max_attempts,
+        )
+    }
+
+    fn resolve_invocation_retry_rules(
+        &self,
+        deployment_id: Option<&DeploymentId>,
+        service_name: impl AsRef<str>,
+        handler_name: impl AsRef<str>,
+    ) -> Vec<ResolvedRetryRule> {
+        let configuration = Configuration::pinned();
+        let (retry_policy, Some(handler)) = self.resolve_computed_retry_policy(
+            &configuration,
+            deployment_id,
+            service_name.as_ref(),
+            handler_name.as_ref(),
+        ) else {
+            return vec![];
+        };
+
+        handler
+            .retry_rules
+            .iter()
+            .map(|rule| ResolvedRetryRule {
+                error_class: rule.error_class,
+                codes: rule.codes.clone(),
+                action: match rule.action {
+                    RetryRuleAction::Retry => {
+                        let mut rule_retry_policy = retry_policy.clone();
+                        rule_retry_policy.merge_with_rule_overrides(&rule.retry_policy);
+                        rule_retry_policy.max_attempts =
+                            configuration.clamp_max_attempts(rule_retry_policy.max_attempts);
+                        ResolvedRetryRuleAction::Retry(
+                            rule_retry_policy.as_retry_policy().into_iter(),
+                            rule_retry_policy.on_max_attempts,
+                        )
+                    }
+                    RetryRuleAction::Fail => ResolvedRetryRuleAction::Fail,
+                    RetryRuleAction::Pause => ResolvedRetryRuleAction::Pause,
+                },
+            })
+            .collect()
+    }
+}
+
+impl Schema {
+    /// Returns whether the given deployment doesn't receive new invocations anymore, because none
+    /// of its services has its latest revision in it and no routing rule points to it. Returns
+    /// `None` if the deployment doesn't exist.
+    pub(in crate::schema) fn is_deployment_draining(
+        &self,
+        deployment_id: &DeploymentId,
+    ) -> Option<bool> {
+        let deployment = self.deployments.get(deployment_id)?;
+        Some(deployment.services.keys().all(|service_name| {
+            self.active_service_revisions
+                .get(service_name)
+                .is_none_or(|active| active.deployment_id != *deployment_id)
+                && self
+                    .service_routing
+                    .get(service_name)
+                    .is_none_or(|routing| routing.deployment_ids().all(|id| id != deployment_id))
+        }))
+    }
+
+    pub(in crate::schema) fn draining_deployments(&self) -> impl Iterator<Item = DeploymentId> {
+        self.deployments
+            .keys()
+            .filter(|deployment_id| self.is_deployment_draining(deployment_id) == Some(true))
+            .copied()
+    }
+
+    pub(in crate::schema) fn resolve_migration_policy(
+        &self,
+        service_name: &str,
+    ) -> Option<&MigrationPolicy> {
+        self.migration_policies.get(service_name)
+    }
+
+    pub(in crate::schema) fn migration_policies(
+        &self,
+    ) -> impl Iterator<Item = (&String, &MigrationPolicy)> {
+        self.migration_policies.iter()
+    }
+
+    /// Computes the retry policy of the given handler, returning the handler too if it was found.
+    fn resolve_computed_retry_policy(
+        &self,
+        configuration: &Configuration,
+        deployment_id: Option<&DeploymentId>,
+        service_name: &str,
+        handler_name: &str,
+    ) -> (ComputedRetryPolicy, Option<&Handler>) {
         let mut retry_policy = configuration.resolve_default_retry_policy();
 
         let Some(service_revision) = (if let Some(deployment_id) = deployment_id {
             self.deployments
                 .get(deployment_id)
-                .and_then(|dp| dp.services.get(service_name.as_ref()))
+                .and_then(|dp| dp.services.get(service_name))
         } else {
             self.active_service_revisions
-                .get(service_name.as_ref())
+                .get(service_name)
                 .map(|a| &a.service_revision)
         }) else {
-            return (
-                retry_policy.as_retry_policy().into_iter(),
-                retry_policy.on_max_attempts,
-            );
+            return (retry_policy, None);
         };
 
         retry_policy.merge_with_service_revision_overrides(service_revision);
 
-        let Some(handler) = service_revision.handlers.get(handler_name.as_ref()) else {
-            return (
-                retry_policy.as_retry_policy().into_iter(),
-                retry_policy.on_max_attempts,
-            );
+        let Some(handler) = service_revision.handlers.get(handler_name) else {
+            return (retry_policy, None);
         };
 
         retry_policy.merge_with_handler_overrides(handler);
 
         retry_policy.max_attempts = configuration.clamp_max_attempts(retry_policy.max_attempts);
 
-        (
-            retry_policy.as_retry_policy().into_iter(),
-            retry_policy.on_max_attempts,
-        )
+        (retry_policy, Some(handler))
     }
 }
 
@@ -940,6 +1072,24 @@ impl ComputedRetryPolicy {
         }
     }
 
+    fn merge_with_rule_overrides(&mut self, overrides: &HandlerRetryPolicyMetadata) {
+        if let Some(initial_interval) = overrides.initial_interval {
+            self.initial_interval = initial_interval;
+        }
+        if let Some(max_attempts) = overrides.max_attempts {
+            self.max_attempts = Some(max_attempts);
+        }
+        if let Some(max_interval) = overrides.max_interval {
+            self.max_interval = Some(max_interval);
+        }
+        if let Some(exponentiation_factor) = overrides.exponentiation_factor {
+            self.exponentiation_factor = exponentiation_factor;
+        }
+        if let Some(on_max_attempts) = overrides.on_max_attempts {
+            self.on_max_attempts = on_max_attempts;
+        }
+    }
+
     pub(super) fn as_retry_policy(&self) -> RetryPolicy {
         if self.max_attempts == Some(NonZeroUsize::MIN) {
             // No retries!
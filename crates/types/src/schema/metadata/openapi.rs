@@ -58,6 +58,7 @@ impl ServiceOpenAPI {
             attach_get_output_parameters
                 .push(parameters_ref(IDEMPOTENCY_KEY_PATH_PARAMETER_REF_NAME).into());
         }
+        call_parameters.push(parameters_ref(DEADLINE_HEADER_PARAMETER_REF_NAME).into());
 
         let mut rpc_paths = Paths::builder();
         let mut send_paths = Paths::builder();
@@ -508,6 +509,10 @@ fn restate_components() -> Components {
             IDEMPOTENCY_KEY_PATH_PARAMETER_REF_NAME,
             idempotency_key_path_parameter(),
         )
+        .parameter(
+            DEADLINE_HEADER_PARAMETER_REF_NAME,
+            deadline_header_parameter(),
+        )
         .response(GENERIC_ERROR_RESPONSE_REF_NAME, generic_error_response())
         .response(
             INVOCATION_NOT_FOUND_ERROR_RESPONSE_REF_NAME,
@@ -577,6 +582,19 @@ fn idempotency_key_header_parameter() -> Parameter {
         .build()
 }
 
+const DEADLINE_HEADER_PARAMETER_REF_NAME: &str = "deadlineHeader";
+
+fn deadline_header_parameter() -> Parameter {
+    Parameter::builder()
+        .name("x-restate-deadline")
+        .parameter_in(ParameterIn::Header)
+        .schema(Some(string_json_schema()))
+        .example(Some(Value::String("1h".to_string())))
+        .required(Required::False)
+        .description(Some("End-to-end deadline of the invocation, including retries and suspensions. Once exceeded, the invocation is cancelled. Overrides the deadline configured for the handler."))
+        .build()
+}
+
 const IDEMPOTENCY_KEY_PATH_PARAMETER_REF_NAME: &str = "idempotencyKeyPath";
 
 fn idempotency_key_path_parameter() -> Parameter {
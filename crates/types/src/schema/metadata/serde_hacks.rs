@@ -384,6 +384,14 @@ pub struct Schema {
     // flexbuffers only supports string-keyed maps :-( --> so we store it as vector of kv pairs
     #[serde_as(as = "serde_with::Seq<(_, _)>")]
     subscriptions: HashMap<SubscriptionId, Subscription>,
+
+    // Routing rules of new invocations, per service name
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    service_routing: HashMap<String, ServiceRouting>,
+
+    // Migration policies of suspended and paused invocations, per service name
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    migration_policies: HashMap<String, MigrationPolicy>,
 }
 
 impl From<super::Schema> for Schema {
@@ -392,6 +400,8 @@ impl From<super::Schema> for Schema {
             version,
             deployments,
             subscriptions,
+            service_routing,
+            migration_policies,
             ..
         }: super::Schema,
     ) -> Self {
@@ -401,6 +411,8 @@ impl From<super::Schema> for Schema {
             deployments_v2: Some(deployments.into_values().collect()),
             version,
             subscriptions,
+            service_routing,
+            migration_policies,
         }
     }
 }
@@ -413,6 +425,8 @@ impl From<Schema> for super::Schema {
             deployments_v2,
             version,
             subscriptions,
+            service_routing,
+            migration_policies,
         }: Schema,
     ) -> Self {
         if let Some(deployments_v2) = deployments_v2 {
@@ -424,6 +438,8 @@ impl From<Schema> for super::Schema {
                     .map(|deployment| (deployment.id, deployment))
                     .collect(),
                 subscriptions,
+                service_routing,
+                migration_policies,
             }
         } else if let (Some(services), Some(deployments)) = (services, deployments) {
             let conversions::V2Schemas { deployments } = conversions::V1Schemas {
@@ -440,6 +456,8 @@ impl From<Schema> for super::Schema {
                     .map(|deployment| (deployment.id, deployment))
                     .collect(),
                 subscriptions,
+                service_routing,
+                migration_policies,
             }
         } else {
             panic!(
@@ -510,6 +528,8 @@ mod conversions {
                             retry_policy_max_attempts: None,
                             retry_policy_max_interval: None,
                             retry_policy_on_max_attempts: None,
+                            retry_rules: vec![],
+                            deadline: None,
                         };
                         v2_handlers.insert(handler_name, handler);
                     }
@@ -543,6 +563,7 @@ mod conversions {
                     ty: deployment.metadata.ty,
                     delivery_options: deployment.metadata.delivery_options,
                     supported_protocol_versions: deployment.metadata.supported_protocol_versions,
+                    message_compression: None,
                     sdk_version: deployment.metadata.sdk_version,
                     created_at: deployment.metadata.created_at,
                     metadata: Default::default(),
@@ -816,9 +837,11 @@ mod conversions {
                             address: "http://localhost:9080/".parse().unwrap(),
                             protocol_type: ProtocolType::BidiStream,
                             http_version: http::Version::HTTP_2,
+                            connection_pool: Default::default(),
                         },
                         delivery_options: Default::default(),
                         supported_protocol_versions: 5..=5,
+                        message_compression: None,
                         sdk_version: None,
                         created_at: MillisSinceEpoch::now(),
                         metadata: Default::default(),
@@ -865,6 +888,8 @@ mod conversions {
                                             retry_policy_max_attempts: None,
                                             retry_policy_max_interval: None,
                                             retry_policy_on_max_attempts: None,
+                                            retry_rules: vec![],
+                                            deadline: None,
                                         },
                                     )]),
                                 }),
@@ -914,6 +939,8 @@ mod conversions {
                                                 retry_policy_max_attempts: None,
                                                 retry_policy_max_interval: None,
                                                 retry_policy_on_max_attempts: None,
+                                                retry_rules: vec![],
+                                                deadline: None,
                                             },
                                         ),
                                         (
@@ -939,6 +966,8 @@ mod conversions {
                                                 retry_policy_max_attempts: None,
                                                 retry_policy_max_interval: None,
                                                 retry_policy_on_max_attempts: None,
+                                                retry_rules: vec![],
+                                                deadline: None,
                                             },
                                         ),
                                     ]),
@@ -952,9 +981,11 @@ mod conversions {
                             address: "http://localhost:9081/".parse().unwrap(),
                             protocol_type: ProtocolType::RequestResponse,
                             http_version: http::Version::HTTP_2,
+                            connection_pool: Default::default(),
                         },
                         delivery_options: Default::default(),
                         supported_protocol_versions: 5..=5,
+                        message_compression: None,
                         sdk_version: None,
                         created_at: MillisSinceEpoch::now(),
                         metadata: Default::default(),
@@ -1000,6 +1031,8 @@ mod conversions {
                                         retry_policy_max_attempts: None,
                                         retry_policy_max_interval: None,
                                         retry_policy_on_max_attempts: None,
+                                        retry_rules: vec![],
+                                        deadline: None,
                                     },
                                 )]),
                             }),
@@ -1093,9 +1126,11 @@ mod conversions {
                                     address: "http://localhost:9080/".parse().unwrap(),
                                     protocol_type: ProtocolType::BidiStream,
                                     http_version: http::Version::HTTP_2,
+                                    connection_pool: Default::default(),
                                 },
                                 delivery_options: Default::default(),
                                 supported_protocol_versions: 5..=5,
+                                message_compression: None,
                                 sdk_version: None,
                                 created_at: MillisSinceEpoch::now(),
                             },
@@ -1211,9 +1246,11 @@ mod conversions {
                                     address: "http://localhost:9081/".parse().unwrap(),
                                     protocol_type: ProtocolType::RequestResponse,
                                     http_version: http::Version::HTTP_2,
+                                    connection_pool: Default::default(),
                                 },
                                 delivery_options: Default::default(),
                                 supported_protocol_versions: 5..=5,
+                                message_compression: None,
                                 sdk_version: None,
                                 created_at: MillisSinceEpoch::now(),
                             },
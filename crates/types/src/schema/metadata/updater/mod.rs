@@ -23,7 +23,10 @@ use crate::schema::invocation_target::{
     BadInputContentType, DEFAULT_IDEMPOTENCY_RETENTION, DEFAULT_WORKFLOW_COMPLETION_RETENTION,
     InputRules, InputValidationRule, OnMaxAttempts, OutputContentTypeRule, OutputRules,
 };
+use crate::schema::migration::MigrationPolicy;
 use crate::schema::registry::{DeploymentConnectionParameters, DiscoveryResponse};
+use crate::schema::routing::ServiceRouting;
+use crate::schema::service::{ErrorClass, HandlerRetryPolicyMetadata, RetryRule, RetryRuleAction};
 use crate::schema::subscriptions::{EventInvocationTargetTemplate, Sink, Source, Subscription};
 use crate::time::MillisSinceEpoch;
 use crate::{deployment, endpoint_manifest, identifiers};
@@ -131,6 +134,17 @@ pub(in crate::schema) enum ServiceError {
     #[error("modifying retention time for service type {0} is unsupported")]
     #[code(unknown)]
     CannotModifyRetentionTime(ServiceType),
+    #[error("the retry rule {index} of {service}/{handler} is invalid: {reason}")]
+    #[code(unknown)]
+    BadRetryRule {
+        service: String,
+        handler: String,
+        index: usize,
+        reason: &'static str,
+    },
+    #[error("the routing rules of {service} are invalid: {reason}")]
+    #[code(unknown)]
+    BadRouting { service: String, reason: String },
 }
 
 #[derive(Debug, thiserror::Error, codederror::CodedError)]
@@ -171,6 +185,11 @@ pub(in crate::schema) enum DeploymentError {
     )]
     #[code(restate_errors::META0016)]
     DifferentSupportedProtocolVersions(RangeInclusive<i32>, RangeInclusive<i32>),
+    #[error(
+        "the deployment {0} still receives new invocations, as it contains the latest revision of a service or a routing rule points to it"
+    )]
+    #[code(unknown)]
+    NotDraining(DeploymentId),
 }
 
 /// Behavior when a handler is removed during service update
@@ -240,6 +259,8 @@ pub struct ModifyServiceRequest {
     pub workflow_completion_retention: Option<Duration>,
     pub inactivity_timeout: Option<Duration>,
     pub abort_timeout: Option<Duration>,
+    /// Retry rules replacing the existing ones, per handler name
+    pub handler_retry_rules: HashMap<String, Vec<RetryRule>>,
 }
 
 /// Responsible for updating the provided [`Schema`] with new
@@ -288,6 +309,26 @@ impl SchemaUpdater {
     fn mark_updated(&mut self) {
         self.schema.active_service_revisions =
             ActiveServiceRevision::create_index(self.schema.deployments.values());
+
+        // Routes can only point to deployments containing the service
+        let deployments = &self.schema.deployments;
+        self.schema
+            .service_routing
+            .retain(|service_name, service_routing| {
+                service_routing.retain_deployments(|deployment_id| {
+                    deployments
+                        .get(deployment_id)
+                        .is_some_and(|dp| dp.services.contains_key(service_name))
+                });
+                !service_routing.is_empty()
+            });
+
+        // Migration policies are removed together with the service
+        let active_service_revisions = &self.schema.active_service_revisions;
+        self.schema
+            .migration_policies
+            .retain(|service_name, _| active_service_revisions.contains_key(service_name));
+
         self.modified = true;
     }
 
@@ -415,6 +456,7 @@ impl SchemaUpdater {
                 ),
                 delivery_options: DeliveryOptions::new(additional_headers),
                 supported_protocol_versions: discovery_response.supported_protocol_versions,
+                message_compression: discovery_response.message_compression,
                 sdk_version: discovery_response.sdk_version,
                 created_at: MillisSinceEpoch::now(),
                 metadata,
@@ -442,6 +484,7 @@ impl SchemaUpdater {
                 address: a.uri,
                 protocol_type,
                 http_version,
+                connection_pool: a.connection_pool,
             },
             (
                 DeploymentAddress::Lambda(a),
@@ -451,6 +494,13 @@ impl SchemaUpdater {
                 assume_role_arn: a.assume_role_arn.map(Into::into),
                 compression,
             },
+            (DeploymentAddress::Wasm(a), DeploymentConnectionParameters::Wasm) => {
+                DeploymentType::Wasm {
+                    module_path: a.module_path,
+                    fuel_limit: a.fuel_limit,
+                    memory_limit: a.memory_limit,
+                }
+            }
             _ => unreachable!(
                 "deployment address and discovered deployment parameters are not of the same type"
             ),
@@ -676,12 +726,13 @@ impl SchemaUpdater {
             self.schema.deployments.insert(
                 deployment_id,
                 Deployment {
-                    // We update only these 3 fields
+                    // We update only these 4 fields
                     ty: Self::create_deployment_ty(
                         deployment_address,
                         discovery_response.deployment_type_parameters,
                     ),
                     delivery_options: DeliveryOptions::new(additional_headers),
+                    message_compression: discovery_response.message_compression,
                     sdk_version: discovery_response.sdk_version,
 
                     // We keep these the same
@@ -787,6 +838,7 @@ impl SchemaUpdater {
                     ),
                     delivery_options: DeliveryOptions::new(additional_headers),
                     supported_protocol_versions: discovery_response.supported_protocol_versions,
+                    message_compression: discovery_response.message_compression,
                     sdk_version: discovery_response.sdk_version,
                     services: computed_services,
 
@@ -828,6 +880,23 @@ impl SchemaUpdater {
         false
     }
 
+    /// Removes the deployment only if it's draining, see [`Schema::is_deployment_draining`].
+    pub(in crate::schema) fn remove_draining_deployment(
+        &mut self,
+        deployment_id: DeploymentId,
+    ) -> Result<(), SchemaError> {
+        match self.schema.is_deployment_draining(&deployment_id) {
+            None => Err(SchemaError::NotFound(format!(
+                "deployment with id '{deployment_id}'"
+            ))),
+            Some(false) => Err(DeploymentError::NotDraining(deployment_id).into()),
+            Some(true) => {
+                self.remove_deployment(deployment_id);
+                Ok(())
+            }
+        }
+    }
+
     pub(in crate::schema) fn add_subscription(
         &mut self,
         id: Option<SubscriptionId>,
@@ -952,6 +1021,113 @@ impl SchemaUpdater {
         false
     }
 
+    /// Replaces the routing rules of the given service. Empty rules remove the routing, such that
+    /// new invocations are routed to the latest service revision.
+    pub(in crate::schema) fn set_service_routing(
+        &mut self,
+        service_name: &str,
+        service_routing: ServiceRouting,
+    ) -> Result<(), SchemaError> {
+        if !self
+            .schema
+            .active_service_revisions
+            .contains_key(service_name)
+        {
+            return Err(SchemaError::NotFound(format!(
+                "service with name '{service_name}'"
+            )));
+        }
+
+        let bad_routing = |reason: String| {
+            SchemaError::Service(ServiceError::BadRouting {
+                service: service_name.to_owned(),
+                reason,
+            })
+        };
+
+        for deployment_id in service_routing.deployment_ids() {
+            match self.schema.deployments.get(deployment_id) {
+                None => return Err(bad_routing(format!("unknown deployment {deployment_id}"))),
+                Some(dp) if !dp.services.contains_key(service_name) => {
+                    return Err(bad_routing(format!(
+                        "deployment {deployment_id} doesn't contain the service"
+                    )));
+                }
+                _ => {}
+            }
+        }
+        for route in &service_routing.header_routes {
+            if http::HeaderName::from_bytes(route.name.as_bytes()).is_err() {
+                return Err(bad_routing(format!(
+                    "'{}' is not a valid header name",
+                    route.name
+                )));
+            }
+        }
+        if !service_routing.weighted_routes.is_empty() {
+            if service_routing
+                .weighted_routes
+                .iter()
+                .all(|route| route.weight == 0)
+            {
+                return Err(bad_routing(
+                    "at least one weighted route must have a positive weight".to_owned(),
+                ));
+            }
+            for (i, route) in service_routing.weighted_routes.iter().enumerate() {
+                if service_routing.weighted_routes[..i]
+                    .iter()
+                    .any(|other| other.deployment_id == route.deployment_id)
+                {
+                    return Err(bad_routing(format!(
+                        "deployment {} has more than one weighted route",
+                        route.deployment_id
+                    )));
+                }
+            }
+        }
+
+        if service_routing.is_empty() {
+            self.schema.service_routing.remove(service_name);
+        } else {
+            self.schema
+                .service_routing
+                .insert(service_name.to_owned(), service_routing);
+        }
+        self.mark_updated();
+
+        Ok(())
+    }
+
+    /// Replaces the migration policy of the given service. An empty policy removes it, such that
+    /// invocations stay pinned to the deployment they started on.
+    pub(in crate::schema) fn set_migration_policy(
+        &mut self,
+        service_name: &str,
+        migration_policy: MigrationPolicy,
+    ) -> Result<(), SchemaError> {
+        if !self
+            .schema
+            .active_service_revisions
+            .contains_key(service_name)
+        {
+            return Err(SchemaError::NotFound(format!(
+                "service with name '{service_name}'"
+            )));
+        }
+
+        if migration_policy.is_empty() {
+            self.schema.migration_policies.remove(service_name);
+        } else {
+            self.schema
+                .migration_policies
+                .insert(service_name.to_owned(), migration_policy);
+        }
+        self.mark_updated();
+
+        Ok(())
+    }
+
     pub(in crate::schema) fn modify_service(
         &mut self,
         name: &str,
@@ -986,6 +1162,16 @@ impl SchemaUpdater {
             if let Some(new_abort_timeout) = modify_service_request.abort_timeout {
                 svc.abort_timeout = Some(new_abort_timeout);
             }
+            for (handler_name, new_retry_rules) in modify_service_request.handler_retry_rules {
+                validate_retry_rules(&svc.name, &handler_name, &new_retry_rules)?;
+                let Some(handler) = svc.handlers.get_mut(&handler_name) else {
+                    return Err(SchemaError::NotFound(format!(
+                        "handler {}/{handler_name}",
+                        svc.name
+                    )));
+                };
+                handler.retry_rules = new_retry_rules;
+            }
             Ok(())
         })?;
 
@@ -1080,6 +1266,7 @@ impl Handler {
         let workflow_completion_retention = handler.workflow_completion_retention_duration();
         let inactivity_timeout = handler.inactivity_timeout_duration();
         let abort_timeout = handler.abort_timeout_duration();
+        let deadline = handler.deadline_duration();
         let retry_policy_initial_interval = handler.retry_policy_initial_interval();
         let retry_policy_max_interval = handler.retry_policy_max_interval();
         let retry_policy_exponentiation_factor =
@@ -1099,6 +1286,13 @@ impl Handler {
             });
         }
 
+        let retry_rules = handler
+            .retry_policy_rules
+            .iter()
+            .map(retry_rule_from_schema)
+            .collect::<Vec<_>>();
+        validate_retry_rules(service_name, &handler.name, &retry_rules)?;
+
         Ok(Self {
             name: handler.name.to_string(),
             target_ty: ty,
@@ -1127,9 +1321,11 @@ impl Handler {
             workflow_completion_retention,
             inactivity_timeout,
             abort_timeout,
+            deadline,
             enable_lazy_state: handler.enable_lazy_state,
             public: handler.ingress_private.map(bool::not),
             retry_policy_on_max_attempts,
+            retry_rules,
         })
     }
 
@@ -1230,6 +1426,69 @@ fn validate_service_name(name: &str) -> Result<(), ServiceError> {
     }
 }
 
+fn retry_rule_from_schema(rule: &endpoint_manifest::RetryPolicyRule) -> RetryRule {
+    RetryRule {
+        error_class: match rule.error_class {
+            endpoint_manifest::RetryPolicyRuleErrorClass::HttpStatus => ErrorClass::HttpStatus,
+            endpoint_manifest::RetryPolicyRuleErrorClass::ErrorCode => ErrorClass::ErrorCode,
+            endpoint_manifest::RetryPolicyRuleErrorClass::Timeout => ErrorClass::Timeout,
+            endpoint_manifest::RetryPolicyRuleErrorClass::ConnectionRefused => {
+                ErrorClass::ConnectionRefused
+            }
+        },
+        codes: rule.codes.clone(),
+        action: match rule.action {
+            endpoint_manifest::RetryPolicyRuleAction::Retry => RetryRuleAction::Retry,
+            endpoint_manifest::RetryPolicyRuleAction::Fail => RetryRuleAction::Fail,
+            endpoint_manifest::RetryPolicyRuleAction::Pause => RetryRuleAction::Pause,
+        },
+        retry_policy: HandlerRetryPolicyMetadata {
+            initial_interval: rule.retry_policy_initial_interval(),
+            exponentiation_factor: rule.retry_policy_exponentiation_factor.map(|f| f as f32),
+            max_attempts: rule
+                .retry_policy_max_attempts
+                .map(|n| NonZeroUsize::new(n as usize).unwrap_or(NonZeroUsize::MIN)),
+            max_interval: rule.retry_policy_max_interval(),
+            on_max_attempts: rule.retry_policy_on_max_attempts.map(|f| match f {
+                endpoint_manifest::RetryPolicyOnMaxAttempts::Pause => OnMaxAttempts::Pause,
+                endpoint_manifest::RetryPolicyOnMaxAttempts::Kill => OnMaxAttempts::Kill,
+            }),
+        },
+    }
+}
+
+fn validate_retry_rules(
+    service_name: &str,
+    handler_name: &str,
+    retry_rules: &[RetryRule],
+) -> Result<(), ServiceError> {
+    for (index, rule) in retry_rules.iter().enumerate() {
+        let reason = match rule.error_class {
+            ErrorClass::HttpStatus | ErrorClass::ErrorCode if rule.codes.is_empty() => {
+                Some("the HttpStatus and ErrorCode error classes require at least one code")
+            }
+            ErrorClass::Timeout | ErrorClass::ConnectionRefused if !rule.codes.is_empty() => {
+                Some("codes can be set only for the HttpStatus and ErrorCode error classes")
+            }
+            _ if rule.action != RetryRuleAction::Retry
+                && rule.retry_policy != HandlerRetryPolicyMetadata::default() =>
+            {
+                Some("the retry policy can be set only for the Retry action")
+            }
+            _ => None,
+        };
+        if let Some(reason) = reason {
+            return Err(ServiceError::BadRetryRule {
+                service: service_name.to_owned(),
+                handler: handler_name.to_owned(),
+                index,
+                reason,
+            });
+        }
+    }
+    Ok(())
+}
+
 #[derive(Debug, thiserror::Error)]
 #[error("invalid option '{name}'. Reason: {reason}")]
 pub struct ValidationError {
@@ -48,6 +48,8 @@ fn greeter_service_greet_handler() -> endpoint_manifest::Handler {
         enable_lazy_state: None,
         ingress_private: None,
         retry_policy_on_max_attempts: None,
+        retry_policy_rules: vec![],
+        deadline: None,
     }
 }
 
@@ -71,6 +73,8 @@ fn greeter_workflow_greet_handler() -> endpoint_manifest::Handler {
         enable_lazy_state: None,
         ingress_private: None,
         retry_policy_on_max_attempts: None,
+        retry_policy_rules: vec![],
+        deadline: None,
     }
 }
 
@@ -125,6 +129,8 @@ fn greeter_virtual_object() -> endpoint_manifest::Service {
             enable_lazy_state: None,
             ingress_private: None,
             retry_policy_on_max_attempts: None,
+            retry_policy_rules: vec![],
+            deadline: None,
         }],
         idempotency_retention: None,
         inactivity_timeout: None,
@@ -186,6 +192,8 @@ fn another_greeter_service() -> endpoint_manifest::Service {
             enable_lazy_state: None,
             ingress_private: None,
             retry_policy_on_max_attempts: None,
+            retry_policy_rules: vec![],
+            deadline: None,
         }],
         idempotency_retention: None,
         inactivity_timeout: None,
@@ -208,6 +216,7 @@ fn add_deployment_request(services: Vec<endpoint_manifest::Service>) -> AddDeplo
             },
             supported_protocol_versions: (MIN_INFLIGHT_SERVICE_PROTOCOL_VERSION as i32)
                 ..=(MAX_INFLIGHT_SERVICE_PROTOCOL_VERSION as i32),
+            message_compression: None,
             sdk_version: None,
             services,
         },
@@ -231,6 +240,7 @@ fn update_deployment_request(
             },
             supported_protocol_versions: (MIN_INFLIGHT_SERVICE_PROTOCOL_VERSION as i32)
                 ..=(MAX_INFLIGHT_SERVICE_PROTOCOL_VERSION as i32),
+            message_compression: None,
             sdk_version: None,
             services,
         },
@@ -894,6 +904,8 @@ mod remove_handler {
                     enable_lazy_state: None,
                     ingress_private: None,
                     retry_policy_on_max_attempts: None,
+                    retry_policy_rules: vec![],
+                    deadline: None,
                 },
                 endpoint_manifest::Handler {
                     abort_timeout: None,
@@ -914,6 +926,8 @@ mod remove_handler {
                     enable_lazy_state: None,
                     ingress_private: None,
                     retry_policy_on_max_attempts: None,
+                    retry_policy_rules: vec![],
+                    deadline: None,
                 },
             ],
             idempotency_retention: None,
@@ -955,6 +969,8 @@ mod remove_handler {
                 enable_lazy_state: None,
                 ingress_private: None,
                 retry_policy_on_max_attempts: None,
+                retry_policy_rules: vec![],
+                deadline: None,
             }],
             idempotency_retention: None,
             inactivity_timeout: None,
@@ -1186,6 +1202,8 @@ fn update_latest_deployment_add_handler() {
             enable_lazy_state: None,
             ingress_private: None,
             retry_policy_on_max_attempts: None,
+            retry_policy_rules: vec![],
+            deadline: None,
         });
 
     updater
@@ -1260,6 +1278,8 @@ fn update_draining_deployment_add_handler() {
             enable_lazy_state: None,
             ingress_private: None,
             retry_policy_on_max_attempts: None,
+            retry_policy_rules: vec![],
+            deadline: None,
         });
 
     updater
@@ -1447,9 +1467,12 @@ mod endpoint_manifest_options_propagation {
 
     use crate::config::Configuration;
     use crate::invocation::InvocationRetention;
-    use crate::schema::invocation_target::{InvocationAttemptOptions, InvocationTargetMetadata};
+    use crate::schema::invocation_target::{
+        InvocationAttemptOptions, InvocationTargetMetadata, ResolvedRetryRuleAction,
+    };
     use crate::schema::service::{HandlerMetadata, ServiceMetadata};
     use googletest::prelude::*;
+    use restate_test_util::let_assert;
     use restate_time_util::FriendlyDuration;
     use std::time::Duration;
     use test_log::test;
@@ -1536,6 +1559,107 @@ mod endpoint_manifest_options_propagation {
         );
     }
 
+    #[test]
+    fn handler_retry_rules() {
+        let schema = SchemaUpdater::update(Schema::default(), |updater| {
+            updater
+                .add_deployment(add_deployment_request(vec![endpoint_manifest::Service {
+                    handlers: vec![endpoint_manifest::Handler {
+                        retry_policy_rules: vec![
+                            endpoint_manifest::RetryPolicyRule {
+                                error_class:
+                                    endpoint_manifest::RetryPolicyRuleErrorClass::HttpStatus,
+                                codes: vec![400, 422],
+                                action: endpoint_manifest::RetryPolicyRuleAction::Fail,
+                                retry_policy_initial_interval: None,
+                                retry_policy_max_interval: None,
+                                retry_policy_max_attempts: None,
+                                retry_policy_exponentiation_factor: None,
+                                retry_policy_on_max_attempts: None,
+                            },
+                            endpoint_manifest::RetryPolicyRule {
+                                error_class:
+                                    endpoint_manifest::RetryPolicyRuleErrorClass::ConnectionRefused,
+                                codes: vec![],
+                                action: endpoint_manifest::RetryPolicyRuleAction::Retry,
+                                retry_policy_initial_interval: Some(5000),
+                                retry_policy_max_interval: None,
+                                retry_policy_max_attempts: Some(3),
+                                retry_policy_exponentiation_factor: None,
+                                retry_policy_on_max_attempts: Some(
+                                    endpoint_manifest::RetryPolicyOnMaxAttempts::Kill,
+                                ),
+                            },
+                        ],
+                        ..greeter_service_greet_handler()
+                    }],
+                    ..greeter_service()
+                }]))
+                .map(|_| ())
+        })
+        .unwrap();
+
+        assert_that!(
+            schema
+                .assert_handler(GREETER_SERVICE_NAME, GREET_HANDLER_NAME)
+                .retry_rules,
+            elements_are![
+                pat!(RetryRule {
+                    error_class: eq(ErrorClass::HttpStatus),
+                    codes: elements_are![eq(400), eq(422)],
+                    action: eq(RetryRuleAction::Fail),
+                }),
+                pat!(RetryRule {
+                    error_class: eq(ErrorClass::ConnectionRefused),
+                    action: eq(RetryRuleAction::Retry),
+                    retry_policy: pat!(HandlerRetryPolicyMetadata {
+                        initial_interval: some(eq(Duration::from_secs(5))),
+                        max_attempts: some(eq(NonZeroUsize::new(3).unwrap())),
+                        on_max_attempts: some(eq(OnMaxAttempts::Kill)),
+                    }),
+                })
+            ]
+        );
+
+        let mut retry_rules =
+            schema.resolve_invocation_retry_rules(None, GREETER_SERVICE_NAME, GREET_HANDLER_NAME);
+        assert_that!(retry_rules, len(eq(2)));
+        assert!(retry_rules[0].matches(ErrorClass::HttpStatus, Some(422)));
+        assert!(!retry_rules[0].matches(ErrorClass::HttpStatus, Some(500)));
+        let_assert!(
+            ResolvedRetryRuleAction::Retry(retry_iter, OnMaxAttempts::Kill) =
+                &mut retry_rules[1].action
+        );
+        assert_eq!(retry_iter.next(), Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn handler_retry_rule_without_codes() {
+        let mut updater = SchemaUpdater::new(Schema::default());
+
+        assert_that!(
+            updater.add_deployment(add_deployment_request(vec![endpoint_manifest::Service {
+                handlers: vec![endpoint_manifest::Handler {
+                    retry_policy_rules: vec![endpoint_manifest::RetryPolicyRule {
+                        error_class: endpoint_manifest::RetryPolicyRuleErrorClass::ErrorCode,
+                        codes: vec![],
+                        action: endpoint_manifest::RetryPolicyRuleAction::Pause,
+                        retry_policy_initial_interval: None,
+                        retry_policy_max_interval: None,
+                        retry_policy_max_attempts: None,
+                        retry_policy_exponentiation_factor: None,
+                        retry_policy_on_max_attempts: None,
+                    }],
+                    ..greeter_service_greet_handler()
+                }],
+                ..greeter_service()
+            }])),
+            err(pat!(SchemaError::Service(pat!(
+                ServiceError::BadRetryRule { index: eq(0) }
+            ))))
+        );
+    }
+
     #[test]
     fn workflow_retention() {
         let ((_, deployment_id), schema) =
@@ -2263,6 +2387,7 @@ mod endpoint_manifest_options_propagation {
                         },
                         supported_protocol_versions: (MIN_INFLIGHT_SERVICE_PROTOCOL_VERSION as i32)
                             ..=(MAX_INFLIGHT_SERVICE_PROTOCOL_VERSION as i32),
+                        message_compression: None,
                         sdk_version: None,
                         services: vec![endpoint_manifest::Service {
                             inactivity_timeout: Some(60 * 1000), // 60 seconds
@@ -2360,6 +2485,79 @@ mod modify_service {
         );
     }
 
+    #[test]
+    fn handler_retry_rules() {
+        let mut schema = SchemaUpdater::update(Schema::default(), |updater| {
+            updater
+                .add_deployment(add_deployment_request(vec![greeter_service()]))
+                .map(|_| ())
+        })
+        .unwrap();
+
+        let retry_rule = RetryRule {
+            error_class: ErrorClass::HttpStatus,
+            codes: vec![409],
+            action: RetryRuleAction::Pause,
+            retry_policy: Default::default(),
+        };
+        schema = SchemaUpdater::update(schema, |updater| {
+            updater.modify_service(
+                GREETER_SERVICE_NAME,
+                ModifyServiceRequest {
+                    handler_retry_rules: HashMap::from([(
+                        GREET_HANDLER_NAME.to_owned(),
+                        vec![retry_rule.clone()],
+                    )]),
+                    ..ModifyServiceRequest::default()
+                },
+            )
+        })
+        .unwrap();
+
+        assert_that!(
+            schema
+                .assert_handler(GREETER_SERVICE_NAME, GREET_HANDLER_NAME)
+                .retry_rules,
+            elements_are![eq(retry_rule)]
+        );
+
+        // Unknown handlers and invalid rules are rejected
+        assert_that!(
+            SchemaUpdater::update(schema.clone(), |updater| {
+                updater.modify_service(
+                    GREETER_SERVICE_NAME,
+                    ModifyServiceRequest {
+                        handler_retry_rules: HashMap::from([("unknown".to_owned(), vec![])]),
+                        ..ModifyServiceRequest::default()
+                    },
+                )
+            }),
+            err(pat!(SchemaError::NotFound(anything())))
+        );
+        assert_that!(
+            SchemaUpdater::update(schema, |updater| {
+                updater.modify_service(
+                    GREETER_SERVICE_NAME,
+                    ModifyServiceRequest {
+                        handler_retry_rules: HashMap::from([(
+                            GREET_HANDLER_NAME.to_owned(),
+                            vec![RetryRule {
+                                error_class: ErrorClass::Timeout,
+                                codes: vec![408],
+                                action: RetryRuleAction::Fail,
+                                retry_policy: Default::default(),
+                            }],
+                        )]),
+                        ..ModifyServiceRequest::default()
+                    },
+                )
+            }),
+            err(pat!(SchemaError::Service(pat!(
+                ServiceError::BadRetryRule { index: eq(0) }
+            ))))
+        );
+    }
+
     #[test]
     fn modify_all_service_config_options_then_register_second_version() {
         const DEFAULT_JOURNAL_RETENTION: Duration = Duration::from_secs(60 * 60 * 24);
@@ -2406,6 +2604,7 @@ mod modify_service {
                     workflow_completion_retention: None,
                     inactivity_timeout: Some(new_inactivity_timeout),
                     abort_timeout: Some(new_abort_timeout),
+                    handler_retry_rules: Default::default(),
                 },
             )
         })
@@ -2498,6 +2697,7 @@ mod modify_service {
                     workflow_completion_retention: Some(new_workflow_completion_retention),
                     inactivity_timeout: Some(new_inactivity_timeout),
                     abort_timeout: Some(new_abort_timeout),
+                    handler_retry_rules: Default::default(),
                 },
             )
         })
@@ -2562,3 +2762,301 @@ mod modify_service {
         );
     }
 }
+
+mod service_routing {
+    use super::*;
+
+    use crate::schema::routing::{HeaderRoute, WeightedRoute};
+
+    fn register_two_greeter_deployments() -> (Schema, DeploymentId, DeploymentId) {
+        let ((_, deployment_id_1), schema) =
+            SchemaUpdater::update_and_return(Schema::default(), |updater| {
+                updater.add_deployment(AddDeploymentRequest {
+                    deployment_address: DeploymentAddress::mock_uri("http://localhost:9080"),
+                    ..add_deployment_request(vec![greeter_service(), another_greeter_service()])
+                })
+            })
+            .unwrap();
+        let ((_, deployment_id_2), schema) = SchemaUpdater::update_and_return(schema, |updater| {
+            updater.add_deployment(AddDeploymentRequest {
+                deployment_address: DeploymentAddress::mock_uri("http://localhost:9081"),
+                ..add_deployment_request(vec![greeter_service()])
+            })
+        })
+        .unwrap();
+
+        (schema, deployment_id_1, deployment_id_2)
+    }
+
+    fn canary(stable: DeploymentId, canary: DeploymentId) -> ServiceRouting {
+        ServiceRouting {
+            header_routes: vec![HeaderRoute {
+                name: "x-canary".to_owned(),
+                value: "true".to_owned(),
+                deployment_id: canary,
+            }],
+            weighted_routes: vec![
+                WeightedRoute {
+                    deployment_id: stable,
+                    weight: 95,
+                },
+                WeightedRoute {
+                    deployment_id: canary,
+                    weight: 5,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn set_and_clear_routing() {
+        let (schema, deployment_id_1, deployment_id_2) = register_two_greeter_deployments();
+
+        let version_before_update = schema.version();
+        let schema = SchemaUpdater::update(schema, |updater| {
+            updater.set_service_routing(
+                GREETER_SERVICE_NAME,
+                canary(deployment_id_1, deployment_id_2),
+            )
+        })
+        .unwrap();
+
+        assert!(version_before_update < schema.version());
+        assert_eq!(
+            schema.resolve_service_routing(GREETER_SERVICE_NAME),
+            Some(canary(deployment_id_1, deployment_id_2))
+        );
+        // The latest deployment is unaffected
+        schema.assert_service_deployment(GREETER_SERVICE_NAME, deployment_id_2);
+
+        let schema = SchemaUpdater::update(schema, |updater| {
+            updater.set_service_routing(GREETER_SERVICE_NAME, ServiceRouting::default())
+        })
+        .unwrap();
+        assert!(
+            schema
+                .resolve_service_routing(GREETER_SERVICE_NAME)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn reject_invalid_routing() {
+        let (schema, deployment_id_1, deployment_id_2) = register_two_greeter_deployments();
+
+        // The second deployment doesn't contain the service
+        assert!(let Err(SchemaError::Service(ServiceError::BadRouting { .. })) = SchemaUpdater::update(schema.clone(), |updater| {
+            updater.set_service_routing(
+                ANOTHER_GREETER_SERVICE_NAME,
+                canary(deployment_id_1, deployment_id_2),
+            )
+        }));
+
+        // Unknown deployment
+        assert!(let Err(SchemaError::Service(ServiceError::BadRouting { .. })) = SchemaUpdater::update(schema.clone(), |updater| {
+            updater.set_service_routing(
+                GREETER_SERVICE_NAME,
+                canary(deployment_id_1, DeploymentId::new()),
+            )
+        }));
+
+        // All weights are zero
+        let mut routing = canary(deployment_id_1, deployment_id_2);
+        routing
+            .weighted_routes
+            .iter_mut()
+            .for_each(|r| r.weight = 0);
+        assert!(let Err(SchemaError::Service(ServiceError::BadRouting { .. })) = SchemaUpdater::update(schema.clone(), |updater| {
+            updater.set_service_routing(GREETER_SERVICE_NAME, routing)
+        }));
+
+        // Unknown service
+        assert!(let Err(SchemaError::NotFound(_)) = SchemaUpdater::update(schema, |updater| {
+            updater.set_service_routing("greeter.Unknown", ServiceRouting::default())
+        }));
+    }
+
+    #[test]
+    fn removing_deployment_removes_its_routes() {
+        let (schema, deployment_id_1, deployment_id_2) = register_two_greeter_deployments();
+
+        let schema = SchemaUpdater::update(schema, |updater| {
+            updater.set_service_routing(
+                GREETER_SERVICE_NAME,
+                canary(deployment_id_1, deployment_id_2),
+            )
+        })
+        .unwrap();
+
+        // Roll back the canary
+        let schema = SchemaUpdater::update(schema, |updater| {
+            assert!(updater.remove_deployment(deployment_id_2));
+            Ok::<(), Infallible>(())
+        })
+        .unwrap();
+        assert_eq!(
+            schema.resolve_service_routing(GREETER_SERVICE_NAME),
+            Some(ServiceRouting {
+                header_routes: vec![],
+                weighted_routes: vec![WeightedRoute {
+                    deployment_id: deployment_id_1,
+                    weight: 95,
+                }],
+            })
+        );
+
+        let schema = SchemaUpdater::update(schema, |updater| {
+            assert!(updater.remove_deployment(deployment_id_1));
+            Ok::<(), Infallible>(())
+        })
+        .unwrap();
+        assert!(
+            schema
+                .resolve_service_routing(GREETER_SERVICE_NAME)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn routing_survives_serde_roundtrip() {
+        let (schema, deployment_id_1, deployment_id_2) = register_two_greeter_deployments();
+        let schema = SchemaUpdater::update(schema, |updater| {
+            updater.set_service_routing(
+                GREETER_SERVICE_NAME,
+                canary(deployment_id_1, deployment_id_2),
+            )
+        })
+        .unwrap();
+
+        let serialized = flexbuffers::to_vec(&schema).unwrap();
+        let deserialized: Schema = flexbuffers::from_slice(&serialized).unwrap();
+
+        assert_eq!(
+            deserialized.resolve_service_routing(GREETER_SERVICE_NAME),
+            Some(canary(deployment_id_1, deployment_id_2))
+        );
+    }
+}
+
+mod draining {
+    use super::*;
+
+    use crate::schema::routing::WeightedRoute;
+
+    #[test]
+    fn superseded_deployment_is_draining() {
+        let ((_, deployment_id_1), schema) =
+            SchemaUpdater::update_and_return(Schema::default(), |updater| {
+                updater.add_deployment(AddDeploymentRequest {
+                    deployment_address: DeploymentAddress::mock_uri("http://localhost:9080"),
+                    ..add_deployment_request(vec![greeter_service()])
+                })
+            })
+            .unwrap();
+        assert_eq!(schema.is_deployment_draining(&deployment_id_1), Some(false));
+
+        let ((_, deployment_id_2), schema) = SchemaUpdater::update_and_return(schema, |updater| {
+            updater.add_deployment(AddDeploymentRequest {
+                deployment_address: DeploymentAddress::mock_uri("http://localhost:9081"),
+                ..add_deployment_request(vec![greeter_service()])
+            })
+        })
+        .unwrap();
+        assert_eq!(schema.is_deployment_draining(&deployment_id_1), Some(true));
+        assert_eq!(schema.is_deployment_draining(&deployment_id_2), Some(false));
+        assert_eq!(
+            schema.draining_deployments().collect::<Vec<_>>(),
+            vec![deployment_id_1]
+        );
+        assert_eq!(schema.is_deployment_draining(&DeploymentId::new()), None);
+
+        // A routing rule pointing to the old deployment stops the draining
+        let schema = SchemaUpdater::update(schema, |updater| {
+            updater.set_service_routing(
+                GREETER_SERVICE_NAME,
+                ServiceRouting {
+                    header_routes: vec![],
+                    weighted_routes: vec![WeightedRoute {
+                        deployment_id: deployment_id_1,
+                        weight: 1,
+                    }],
+                },
+            )
+        })
+        .unwrap();
+        assert_eq!(schema.is_deployment_draining(&deployment_id_1), Some(false));
+        assert!(let Err(SchemaError::Deployment(DeploymentError::NotDraining(_))) = SchemaUpdater::update(schema.clone(), |updater| {
+            updater.remove_draining_deployment(deployment_id_1)
+        }));
+
+        let schema = SchemaUpdater::update(schema, |updater| {
+            updater.set_service_routing(GREETER_SERVICE_NAME, ServiceRouting::default())
+        })
+        .unwrap();
+        let schema = SchemaUpdater::update(schema, |updater| {
+            updater.remove_draining_deployment(deployment_id_1)
+        })
+        .unwrap();
+        assert!(schema.get_deployment(&deployment_id_1).is_none());
+        schema.assert_service_deployment(GREETER_SERVICE_NAME, deployment_id_2);
+    }
+}
+
+mod migration_policy {
+    use super::*;
+
+    use crate::schema::migration::MigrationPolicy;
+
+    const MIGRATE_TO_LATEST: MigrationPolicy = MigrationPolicy {
+        migrate_to_latest: true,
+    };
+
+    #[test]
+    fn set_and_clear_migration_policy() {
+        let ((_, deployment_id), schema) =
+            SchemaUpdater::update_and_return(Schema::default(), |updater| {
+                updater.add_deployment(add_deployment_request(vec![greeter_service()]))
+            })
+            .unwrap();
+
+        let version_before_update = schema.version();
+        let schema = SchemaUpdater::update(schema, |updater| {
+            updater.set_migration_policy(GREETER_SERVICE_NAME, MIGRATE_TO_LATEST)
+        })
+        .unwrap();
+        assert!(version_before_update < schema.version());
+        assert_eq!(
+            schema.resolve_migration_policy(GREETER_SERVICE_NAME),
+            Some(&MIGRATE_TO_LATEST)
+        );
+
+        let schema = SchemaUpdater::update(schema, |updater| {
+            updater.set_migration_policy(GREETER_SERVICE_NAME, MigrationPolicy::default())
+        })
+        .unwrap();
+        assert!(
+            schema
+                .resolve_migration_policy(GREETER_SERVICE_NAME)
+                .is_none()
+        );
+
+        // The policy is removed together with the service
+        let schema = SchemaUpdater::update(schema, |updater| {
+            updater.set_migration_policy(GREETER_SERVICE_NAME, MIGRATE_TO_LATEST)
+        })
+        .unwrap();
+        let schema = SchemaUpdater::update(schema, |updater| {
+            assert!(updater.remove_deployment(deployment_id));
+            Ok::<(), Infallible>(())
+        })
+        .unwrap();
+        assert_eq!(schema.migration_policies().count(), 0);
+    }
+
+    #[test]
+    fn reject_unknown_service() {
+        assert!(let Err(SchemaError::NotFound(_)) = SchemaUpdater::update(Schema::default(), |updater| {
+            updater.set_migration_policy(GREETER_SERVICE_NAME, MIGRATE_TO_LATEST)
+        }));
+    }
+}
@@ -0,0 +1,37 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use serde::{Deserialize, Serialize};
+
+/// # Migration policy
+///
+/// Policy moving the invocations of a service to newer deployments.
+///
+/// Invocations are pinned to the deployment they started on. With this policy, suspended and
+/// paused invocations are periodically re-pinned to the deployment of the latest service revision,
+/// provided it supports the service protocol version of their journal and contains their handler.
+/// The service routing rules take precedence: invocations pinned to a deployment the rules route
+/// to are not migrated. Migrated invocations are not resumed, they continue on the new deployment once woken up.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct MigrationPolicy {
+    /// # Migrate to latest
+    ///
+    /// If true, suspended and paused invocations are re-pinned to the deployment of the latest
+    /// service revision.
+    #[serde(default)]
+    pub migrate_to_latest: bool,
+}
+
+impl MigrationPolicy {
+    pub fn is_empty(&self) -> bool {
+        !self.migrate_to_latest
+    }
+}
@@ -27,7 +27,9 @@ pub mod deployment;
 pub mod info;
 pub mod invocation_target;
 mod metadata;
+pub mod migration;
 pub mod registry;
+pub mod routing;
 pub mod service;
 pub mod subscriptions;
 
@@ -16,7 +16,7 @@ use http::{HeaderName, HeaderValue};
 
 use crate::deployment::DeploymentAddress;
 use crate::endpoint_manifest;
-use crate::schema::deployment::{EndpointLambdaCompression, ProtocolType};
+use crate::schema::deployment::{EndpointLambdaCompression, MessageCompression, ProtocolType};
 
 #[derive(Debug)]
 pub struct DiscoveryRequest {
@@ -35,6 +35,7 @@ pub enum DeploymentConnectionParameters {
     Lambda {
         compression: Option<EndpointLambdaCompression>,
     },
+    Wasm,
 }
 
 #[derive(Debug, Clone)]
@@ -43,6 +44,7 @@ pub struct DiscoveryResponse {
     // type is i32 because the generated ServiceProtocolVersion enum uses this as its representation
     // and we need to represent unknown later versions
     pub supported_protocol_versions: RangeInclusive<i32>,
+    pub message_compression: Option<MessageCompression>,
     pub sdk_version: Option<String>,
     pub services: Vec<endpoint_manifest::Service>,
 }
@@ -74,6 +76,7 @@ mod mocks {
                 },
                 supported_protocol_versions: MIN_DISCOVERABLE_SERVICE_PROTOCOL_VERSION.as_repr()
                     ..=MAX_DISCOVERABLE_SERVICE_PROTOCOL_VERSION.as_repr(),
+                message_compression: None,
                 sdk_version: None,
                 services,
             }
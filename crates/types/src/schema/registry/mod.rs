@@ -25,12 +25,17 @@ use tracing::subscriber::NoSubscriber;
 use crate::deployment;
 use crate::deployment::{
     DeploymentAddress, Headers, HttpDeploymentAddress, LambdaDeploymentAddress,
+    WasmDeploymentAddress,
 };
 use crate::identifiers::{DeploymentId, LambdaARN, ServiceRevision, SubscriptionId};
 use crate::net::address::{AdvertisedAddress, HttpIngressPort};
-use crate::schema::deployment::{Deployment, DeploymentResolver, DeploymentType};
+use crate::schema::deployment::{
+    Deployment, DeploymentResolver, DeploymentType, HttpConnectionPoolSettings,
+};
 use crate::schema::metadata::updater;
-use crate::schema::metadata::updater::{SchemaError, SchemaUpdater, ServiceError};
+use crate::schema::metadata::updater::{DeploymentError, SchemaError, SchemaUpdater, ServiceError};
+use crate::schema::migration::MigrationPolicy;
+use crate::schema::routing::ServiceRouting;
 use crate::schema::service::{HandlerMetadata, ServiceMetadata, ServiceMetadataResolver};
 use crate::schema::subscriptions::{ListSubscriptionFilter, Subscription, SubscriptionResolver};
 
@@ -62,6 +67,7 @@ impl SchemaRegistryError {
                     StatusCode::CONFLICT
                 }
                 SchemaError::Service(_) => StatusCode::BAD_REQUEST,
+                SchemaError::Deployment(DeploymentError::NotDraining(_)) => StatusCode::CONFLICT,
                 _ => StatusCode::BAD_REQUEST,
             },
             SchemaRegistryErrorInner::UpdateDeployment { .. } => StatusCode::BAD_REQUEST,
@@ -145,6 +151,7 @@ pub enum UpdateDeploymentAddress {
     Http {
         uri: Option<Uri>,
         use_http_11: Option<bool>,
+        connection_pool: Option<HttpConnectionPoolSettings>,
     },
 }
 
@@ -289,16 +296,27 @@ impl<Metadata: MetadataService, Discovery: DiscoveryClient, Telemetry>
         };
 
         // Merge with update changes requested
+        let existing_connection_pool = match &existing_deployment.ty {
+            DeploymentType::Http {
+                connection_pool, ..
+            } => *connection_pool,
+            _ => HttpConnectionPoolSettings::default(),
+        };
         let (deployment_address, use_http_11) =
             match (update_deployment_address, existing_deployment.ty) {
                 (
                     Some(UpdateDeploymentAddress::Http {
                         uri: Some(uri),
                         use_http_11,
+                        connection_pool,
                     }),
                     _,
                 ) => (
-                    DeploymentAddress::Http(HttpDeploymentAddress::new(uri)),
+                    DeploymentAddress::Http(
+                        HttpDeploymentAddress::new(uri).with_connection_pool(
+                            connection_pool.unwrap_or(existing_connection_pool),
+                        ),
+                    ),
                     use_http_11.unwrap_or(false),
                 ),
                 (
@@ -315,6 +333,7 @@ impl<Metadata: MetadataService, Discovery: DiscoveryClient, Telemetry>
                     Some(UpdateDeploymentAddress::Http {
                         uri: None,
                         use_http_11,
+                        connection_pool,
                     }),
                     DeploymentType::Http {
                         address,
@@ -322,7 +341,11 @@ impl<Metadata: MetadataService, Discovery: DiscoveryClient, Telemetry>
                         ..
                     },
                 ) => (
-                    DeploymentAddress::Http(HttpDeploymentAddress::new(address)),
+                    DeploymentAddress::Http(
+                        HttpDeploymentAddress::new(address).with_connection_pool(
+                            connection_pool.unwrap_or(existing_connection_pool),
+                        ),
+                    ),
                     use_http_11.unwrap_or(http_version == http::Version::HTTP_11),
                 ),
                 (
@@ -356,15 +379,32 @@ impl<Metadata: MetadataService, Discovery: DiscoveryClient, Telemetry>
                     }
                     .into());
                 }
+                (Some(UpdateDeploymentAddress::Http { .. }), DeploymentType::Wasm { .. }) => {
+                    return Err(SchemaRegistryErrorInner::UpdateDeployment {
+                        actual_deployment_type: "wasm",
+                        expected_deployment_type: "http",
+                    }
+                    .into());
+                }
+                (Some(UpdateDeploymentAddress::Lambda { .. }), DeploymentType::Wasm { .. }) => {
+                    return Err(SchemaRegistryErrorInner::UpdateDeployment {
+                        actual_deployment_type: "wasm",
+                        expected_deployment_type: "lambda",
+                    }
+                    .into());
+                }
                 (
                     None,
                     DeploymentType::Http {
                         address,
                         http_version,
+                        connection_pool,
                         ..
                     },
                 ) => (
-                    DeploymentAddress::Http(HttpDeploymentAddress::new(address)),
+                    DeploymentAddress::Http(
+                        HttpDeploymentAddress::new(address).with_connection_pool(connection_pool),
+                    ),
                     http_version == http::Version::HTTP_11,
                 ),
                 (
@@ -381,6 +421,21 @@ impl<Metadata: MetadataService, Discovery: DiscoveryClient, Telemetry>
                     )),
                     false,
                 ),
+                (
+                    None,
+                    DeploymentType::Wasm {
+                        module_path,
+                        fuel_limit,
+                        memory_limit,
+                    },
+                ) => (
+                    DeploymentAddress::Wasm(WasmDeploymentAddress::new(
+                        module_path,
+                        fuel_limit,
+                        memory_limit,
+                    )),
+                    false,
+                ),
             };
         let additional_headers =
             additional_headers.unwrap_or(existing_deployment.additional_headers);
@@ -472,6 +527,25 @@ impl<Metadata: MetadataService, Discovery, Telemetry>
         Ok(())
     }
 
+    /// Deletes the deployment, failing if it still receives new invocations.
+    pub async fn delete_draining_deployment(
+        &self,
+        deployment_id: DeploymentId,
+    ) -> Result<(), SchemaRegistryError> {
+        self.metadata_service
+            .update(|schema| {
+                Ok((
+                    (),
+                    SchemaUpdater::update(schema, |updater| {
+                        updater.remove_draining_deployment(deployment_id)
+                    })?,
+                ))
+            })
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn modify_service(
         &self,
         service_name: String,
@@ -500,6 +574,51 @@ impl<Metadata: MetadataService, Discovery, Telemetry>
         Ok(response)
     }
 
+    pub async fn set_service_routing(
+        &self,
+        service_name: String,
+        service_routing: ServiceRouting,
+    ) -> Result<ServiceRouting, SchemaRegistryError> {
+        let (_, schema) = self
+            .metadata_service
+            .update(|schema| {
+                Ok((
+                    (),
+                    SchemaUpdater::update(schema, |updater| {
+                        updater.set_service_routing(&service_name, service_routing.clone())
+                    })?,
+                ))
+            })
+            .await?;
+
+        Ok(schema
+            .resolve_service_routing(&service_name)
+            .unwrap_or_default())
+    }
+
+    pub async fn set_migration_policy(
+        &self,
+        service_name: String,
+        migration_policy: MigrationPolicy,
+    ) -> Result<MigrationPolicy, SchemaRegistryError> {
+        let (_, schema) = self
+            .metadata_service
+            .update(|schema| {
+                Ok((
+                    (),
+                    SchemaUpdater::update(schema, |updater| {
+                        updater.set_migration_policy(&service_name, migration_policy.clone())
+                    })?,
+                ))
+            })
+            .await?;
+
+        Ok(schema
+            .resolve_migration_policy(&service_name)
+            .cloned()
+            .unwrap_or_default())
+    }
+
     pub async fn delete_subscription(
         &self,
         subscription_id: SubscriptionId,
@@ -534,6 +653,38 @@ impl<Metadata: MetadataService, Discovery, Telemetry>
             .resolve_latest_service(&service_name)
     }
 
+    pub fn get_service_routing(&self, service_name: impl AsRef<str>) -> Option<ServiceRouting> {
+        let schema = self.metadata_service.get();
+        schema.resolve_latest_service(&service_name)?;
+        Some(
+            schema
+                .resolve_service_routing(&service_name)
+                .unwrap_or_default(),
+        )
+    }
+
+    pub fn get_migration_policy(&self, service_name: impl AsRef<str>) -> Option<MigrationPolicy> {
+        let schema = self.metadata_service.get();
+        schema.resolve_latest_service(&service_name)?;
+        Some(
+            schema
+                .resolve_migration_policy(service_name.as_ref())
+                .cloned()
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Returns the services whose suspended and paused invocations should be migrated to the
+    /// latest deployment.
+    pub fn list_services_migrating_to_latest(&self) -> Vec<String> {
+        self.metadata_service
+            .get()
+            .migration_policies()
+            .filter(|(_, policy)| policy.migrate_to_latest)
+            .map(|(service_name, _)| service_name.clone())
+            .collect()
+    }
+
     pub fn get_service_openapi(
         &self,
         service_name: impl AsRef<str>,
@@ -553,6 +704,18 @@ impl<Metadata: MetadataService, Discovery, Telemetry>
             .get_deployment_and_services(&deployment_id)
     }
 
+    /// Returns whether the deployment doesn't receive new invocations anymore, because none of its
+    /// services has its latest revision in it and no routing rule points to it.
+    pub fn is_deployment_draining(&self, deployment_id: DeploymentId) -> Option<bool> {
+        self.metadata_service
+            .get()
+            .is_deployment_draining(&deployment_id)
+    }
+
+    pub fn list_draining_deployments(&self) -> Vec<DeploymentId> {
+        self.metadata_service.get().draining_deployments().collect()
+    }
+
     pub fn list_deployments(&self) -> Vec<(Deployment, Vec<(String, ServiceRevision)>)> {
         self.metadata_service.get().get_deployments()
     }
@@ -38,6 +38,8 @@ fn greeter_service_greet_handler() -> endpoint_manifest::Handler {
         enable_lazy_state: None,
         ingress_private: None,
         retry_policy_on_max_attempts: None,
+        retry_policy_rules: vec![],
+        deadline: None,
     }
 }
 
@@ -0,0 +1,271 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use serde::{Deserialize, Serialize};
+
+use crate::identifiers::DeploymentId;
+
+/// # Service routing
+///
+/// Rules distributing the new invocations of a service among the deployments containing it.
+///
+/// The rules are evaluated when a new invocation is pinned to a deployment: header routes are
+/// evaluated first, in order, and if none matches the invocation is assigned according to the
+/// weighted routes. Without any rule, or if no rule applies, new invocations are routed to the
+/// deployment of the latest service revision.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ServiceRouting {
+    /// # Header routes
+    ///
+    /// Routes matching the headers of the invocation.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub header_routes: Vec<HeaderRoute>,
+
+    /// # Weighted routes
+    ///
+    /// Routes splitting the invocations among deployments, proportionally to their weight.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub weighted_routes: Vec<WeightedRoute>,
+}
+
+/// # Header route
+///
+/// Routes the invocations carrying the given header to the given deployment.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct HeaderRoute {
+    /// # Header name
+    ///
+    /// Name of the header, matched case-insensitively.
+    pub name: String,
+
+    /// # Header value
+    ///
+    /// Value the header must be equal to.
+    pub value: String,
+
+    /// # Deployment ID
+    pub deployment_id: DeploymentId,
+}
+
+/// # Weighted route
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct WeightedRoute {
+    /// # Deployment ID
+    pub deployment_id: DeploymentId,
+
+    /// # Weight
+    ///
+    /// Share of the invocations routed to the deployment, relative to the sum of all the weights.
+    pub weight: u32,
+}
+
+/// Which rule routed an invocation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteKind {
+    Header,
+    Weighted,
+}
+
+impl RouteKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RouteKind::Header => "header",
+            RouteKind::Weighted => "weighted",
+        }
+    }
+}
+
+impl ServiceRouting {
+    pub fn is_empty(&self) -> bool {
+        self.header_routes.is_empty() && self.weighted_routes.is_empty()
+    }
+
+    pub fn has_header_routes(&self) -> bool {
+        !self.header_routes.is_empty()
+    }
+
+    /// Returns an iterator over all the deployments these rules route to.
+    pub fn deployment_ids(&self) -> impl Iterator<Item = &DeploymentId> {
+        self.header_routes
+            .iter()
+            .map(|r| &r.deployment_id)
+            .chain(self.weighted_routes.iter().map(|r| &r.deployment_id))
+    }
+
+    /// Removes all the routes to the deployments not satisfying the given predicate.
+    pub fn retain_deployments(&mut self, mut f: impl FnMut(&DeploymentId) -> bool) {
+        self.header_routes.retain(|r| f(&r.deployment_id));
+        self.weighted_routes.retain(|r| f(&r.deployment_id));
+    }
+
+    /// Picks the deployment for a new invocation with the given headers.
+    ///
+    /// `seed` must be stable across retries of the same invocation, such that the choice is
+    /// deterministic, and uniformly distributed across invocations.
+    ///
+    /// `resolve` returns the deployment of a route if it can serve the invocation, e.g. because
+    /// it contains the invoked handler. The routes to other deployments are skipped, such that the
+    /// invocation is not pinned to a deployment that would fail it on every retry.
+    pub fn pick<'a, D>(
+        &self,
+        headers: impl IntoIterator<Item = (&'a str, &'a str)> + Clone,
+        seed: u64,
+        mut resolve: impl FnMut(&DeploymentId) -> Option<D>,
+    ) -> Option<(D, RouteKind)> {
+        for route in &self.header_routes {
+            if headers
+                .clone()
+                .into_iter()
+                .any(|(name, value)| name.eq_ignore_ascii_case(&route.name) && value == route.value)
+                && let Some(deployment) = resolve(&route.deployment_id)
+            {
+                return Some((deployment, RouteKind::Header));
+            }
+        }
+
+        let weighted_deployments: Vec<_> = self
+            .weighted_routes
+            .iter()
+            .filter(|r| r.weight > 0)
+            .filter_map(|r| Some((resolve(&r.deployment_id)?, u64::from(r.weight))))
+            .collect();
+        let total_weight: u64 = weighted_deployments.iter().map(|(_, weight)| weight).sum();
+        if total_weight == 0 {
+            return None;
+        }
+        let mut point = seed % total_weight;
+        for (deployment, weight) in weighted_deployments {
+            if point < weight {
+                return Some((deployment, RouteKind::Weighted));
+            }
+            point -= weight;
+        }
+        unreachable!("the point is always lower than the total weight")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weighted(weights: &[(DeploymentId, u32)]) -> ServiceRouting {
+        ServiceRouting {
+            header_routes: vec![],
+            weighted_routes: weights
+                .iter()
+                .map(|(deployment_id, weight)| WeightedRoute {
+                    deployment_id: *deployment_id,
+                    weight: *weight,
+                })
+                .collect(),
+        }
+    }
+
+    fn any_deployment(deployment_id: &DeploymentId) -> Option<DeploymentId> {
+        Some(*deployment_id)
+    }
+
+    #[test]
+    fn weighted_split() {
+        let (a, b) = (DeploymentId::new(), DeploymentId::new());
+        let routing = weighted(&[(a, 95), (b, 5)]);
+
+        let mut routed_to_b = 0;
+        for seed in 0..1000 {
+            let (deployment_id, kind) = routing.pick([], seed, any_deployment).unwrap();
+            assert_eq!(kind, RouteKind::Weighted);
+            if deployment_id == b {
+                routed_to_b += 1;
+            }
+        }
+        assert_eq!(routed_to_b, 50);
+
+        // the same seed always picks the same deployment
+        assert_eq!(
+            routing.pick([], 42, any_deployment),
+            routing.pick([], 42, any_deployment)
+        );
+    }
+
+    #[test]
+    fn zero_weights_route_nowhere() {
+        let routing = weighted(&[(DeploymentId::new(), 0)]);
+        assert_eq!(routing.pick([], 7, any_deployment), None);
+        assert_eq!(ServiceRouting::default().pick([], 7, any_deployment), None);
+    }
+
+    #[test]
+    fn header_routes_take_precedence() {
+        let (a, b) = (DeploymentId::new(), DeploymentId::new());
+        let mut routing = weighted(&[(a, 1)]);
+        routing.header_routes.push(HeaderRoute {
+            name: "x-canary".to_owned(),
+            value: "true".to_owned(),
+            deployment_id: b,
+        });
+
+        assert_eq!(
+            routing.pick([("X-Canary", "true")], 0, any_deployment),
+            Some((b, RouteKind::Header))
+        );
+        assert_eq!(
+            routing.pick([("x-canary", "false")], 0, any_deployment),
+            Some((a, RouteKind::Weighted))
+        );
+
+        routing.retain_deployments(|id| *id != b);
+        assert_eq!(
+            routing.pick([("x-canary", "true")], 0, any_deployment),
+            Some((a, RouteKind::Weighted))
+        );
+    }
+
+    #[test]
+    fn routes_to_unusable_deployments_are_skipped() {
+        let (a, b, c) = (
+            DeploymentId::new(),
+            DeploymentId::new(),
+            DeploymentId::new(),
+        );
+        let mut routing = weighted(&[(a, 50), (b, 50)]);
+        routing.header_routes.push(HeaderRoute {
+            name: "x-canary".to_owned(),
+            value: "true".to_owned(),
+            deployment_id: c,
+        });
+        let without = |unusable: DeploymentId| {
+            move |deployment_id: &DeploymentId| {
+                (*deployment_id != unusable).then_some(*deployment_id)
+            }
+        };
+
+        // the header route is skipped, falling back to the weighted routes
+        assert_eq!(
+            routing
+                .pick([("x-canary", "true")], 0, without(c))
+                .map(|(_, kind)| kind),
+            Some(RouteKind::Weighted)
+        );
+
+        // all the invocations go to the remaining weighted route
+        for seed in 0..100 {
+            assert_eq!(
+                routing.pick([], seed, without(b)),
+                Some((a, RouteKind::Weighted))
+            );
+        }
+
+        // no usable route
+        assert_eq!(routing.pick([], 0, |_| None::<DeploymentId>), None);
+    }
+}
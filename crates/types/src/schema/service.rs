@@ -402,6 +402,24 @@ pub struct HandlerMetadata {
     #[cfg_attr(feature = "schemars", schemars(with = "Option<String>" /* TODO(slinkydeveloper) https://github.com/restatedev/restate/issues/3766 */))]
     pub abort_timeout: Option<Duration>,
 
+    /// # Deadline
+    ///
+    /// Default end-to-end deadline for invocations to this handler, measured from the moment
+    /// the invocation is created. Unlike the 'inactivity timeout' and the 'abort timeout', which
+    /// guard single attempts, the deadline bounds the whole invocation lifetime, including retries
+    /// and suspensions. Once exceeded, the invocation is cancelled.
+    ///
+    /// Can be overridden per request using the `x-restate-deadline` ingress header.
+    ///
+    /// Can be configured using the [`jiff::fmt::friendly`](https://docs.rs/jiff/latest/jiff/fmt/friendly/index.html) format or ISO8601, for example `5 hours`.
+    #[serde(
+        with = "serde_with::As::<Option<FriendlyDuration>>",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>" /* TODO(slinkydeveloper) https://github.com/restatedev/restate/issues/3766 */))]
+    pub deadline: Option<Duration>,
+
     /// # Enable lazy state
     ///
     /// If true, lazy state will be enabled for all invocations to this service.
@@ -446,6 +464,12 @@ pub struct HandlerMetadata {
     #[serde(default)]
     pub retry_policy: HandlerRetryPolicyMetadata,
 
+    /// # Retry rules
+    ///
+    /// Rules overriding the retry policy for specific classes of errors. The first matching rule applies.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub retry_rules: Vec<RetryRule>,
+
     /// # Info
     ///
     /// List of configuration/deprecation information related to this handler.
@@ -510,6 +534,62 @@ pub struct HandlerRetryPolicyMetadata {
     pub on_max_attempts: Option<OnMaxAttempts>,
 }
 
+/// # Retry rule
+///
+/// Action to take when an invocation attempt fails with an error of the given class.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct RetryRule {
+    /// # Error class
+    ///
+    /// Class of the errors this rule applies to.
+    pub error_class: ErrorClass,
+
+    /// # Codes
+    ///
+    /// HTTP status codes, for the `HttpStatus` error class, or error codes, for the `ErrorCode` error class, matched by this rule.
+    /// Must be empty for the other error classes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub codes: Vec<u16>,
+
+    /// # Action
+    ///
+    /// Action to take when the rule matches.
+    pub action: RetryRuleAction,
+
+    /// # Retry policy
+    ///
+    /// Retry policy overrides applied on top of the handler retry policy when the action is `Retry`.
+    #[serde(default)]
+    pub retry_policy: HandlerRetryPolicyMetadata,
+}
+
+/// # Error class
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum ErrorClass {
+    /// The deployment replied with an unexpected HTTP status code.
+    HttpStatus,
+    /// The service failed the invocation attempt with an error code.
+    ErrorCode,
+    /// The invocation attempt timed out.
+    Timeout,
+    /// The deployment could not be reached.
+    ConnectionRefused,
+}
+
+/// # Retry rule action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum RetryRuleAction {
+    /// Retry the invocation, using the retry policy of the rule.
+    Retry,
+    /// Fail the invocation immediately with a terminal error.
+    Fail,
+    /// Pause the invocation immediately.
+    Pause,
+}
+
 #[cfg(feature = "test-util")]
 #[allow(dead_code)]
 pub mod test_util {
@@ -579,6 +659,8 @@ pub mod test_util {
                                 input_json_schema: None,
                                 output_json_schema: None,
                                 retry_policy: Default::default(),
+                                retry_rules: vec![],
+                                deadline: None,
                                 info: vec![],
                             },
                         )
@@ -628,6 +710,8 @@ pub mod test_util {
                                 input_json_schema: None,
                                 output_json_schema: None,
                                 retry_policy: Default::default(),
+                                retry_rules: vec![],
+                                deadline: None,
                                 info: vec![],
                             },
                         )
@@ -27,6 +27,8 @@ use crate::journal_v2::raw::{RawEntry, RawEntryError, TryFromEntry};
 use crate::journal_v2::{Decoder, EntryMetadata, EntryType};
 use crate::time::MillisSinceEpoch;
 
+const ZSTD_COMPRESSION_LEVEL: i32 = 3;
+
 #[derive(Debug, thiserror::Error)]
 pub enum StorageEncodeError {
     #[error("encoding failed: {0}")]
@@ -41,6 +43,8 @@ pub enum StorageDecodeError {
     DecodeValue(GenericError),
     #[error("unsupported codec kind: {0}")]
     UnsupportedCodecKind(StorageCodecKind),
+    #[error("decompression failed: {0}")]
+    Decompress(#[from] std::io::Error),
 }
 
 #[derive(
@@ -70,6 +74,12 @@ pub enum StorageCodecKind {
     /// it is up to your implementation to decide how (or if) to use them, and how the final
     /// byte representation is constructed.
     Custom = 7,
+    /// zstd compressed value (length-prefixed, length is u32). The compressed frame contains the
+    /// codec byte and the encoded value of the wrapped value.
+    ///
+    /// This codec is only written by the [`StorageCodec`] when compression is enabled, the
+    /// [`StorageDecode`] implementations never see it.
+    Zstd = 8,
 }
 
 impl From<StorageCodecKind> for u8 {
@@ -101,10 +111,46 @@ impl StorageCodec {
         value: &T,
         buf: &mut BytesMut,
     ) -> Result<(), StorageEncodeError> {
+        Self::encode_with_compression(value, buf, value.compression_threshold())
+    }
+
+    /// Encodes the value like [`Self::encode`], but compresses it with zstd if the encoded value
+    /// is larger than `compression_threshold` bytes. The compressed representation is only kept
+    /// if it's smaller than the uncompressed one.
+    pub fn encode_with_compression<T: StorageEncode + ?Sized>(
+        value: &T,
+        buf: &mut BytesMut,
+        compression_threshold: Option<usize>,
+    ) -> Result<(), StorageEncodeError> {
+        let start = buf.len();
         // write codec
         buf.put_u8(value.default_codec().into());
         // encode value
-        value.encode(buf)
+        value.encode(buf)?;
+
+        let Some(compression_threshold) = compression_threshold else {
+            return Ok(());
+        };
+        let encoded_len = buf.len() - start;
+        if encoded_len <= compression_threshold {
+            return Ok(());
+        }
+
+        let compressed = zstd::bulk::compress(&buf[start..], ZSTD_COMPRESSION_LEVEL)
+            .map_err(|err| StorageEncodeError::EncodeValue(err.into()))?;
+        let compressed_len = u32::try_from(compressed.len()).map_err(|_| {
+            StorageEncodeError::EncodeValue(
+                anyhow::anyhow!("only support compressing values of size <= 4GB").into(),
+            )
+        })?;
+        if mem::size_of::<u8>() + mem::size_of::<u32>() + compressed.len() < encoded_len {
+            buf.truncate(start);
+            buf.put_u8(StorageCodecKind::Zstd.into());
+            buf.put_u32_le(compressed_len);
+            buf.put_slice(&compressed);
+        }
+
+        Ok(())
     }
 
     pub fn encode_and_split<T: StorageEncode + ?Sized>(
@@ -127,9 +173,48 @@ impl StorageCodec {
         // read version
         let codec = StorageCodecKind::try_from(buf.get_u8())?;
 
+        if codec == StorageCodecKind::Zstd {
+            let mut decompressed = Self::decompress(buf)?;
+            if !decompressed.has_remaining() {
+                return Err(StorageDecodeError::ReadingCodec(
+                    "empty compressed value".to_owned(),
+                ));
+            }
+            let codec = StorageCodecKind::try_from(decompressed.get_u8())?;
+            if codec == StorageCodecKind::Zstd {
+                return Err(StorageDecodeError::UnsupportedCodecKind(codec));
+            }
+            return T::decode(&mut decompressed, codec);
+        }
+
         // decode value
         T::decode(buf, codec)
     }
+
+    fn decompress<B: Buf>(buf: &mut B) -> Result<Bytes, StorageDecodeError> {
+        if buf.remaining() < mem::size_of::<u32>() {
+            return Err(StorageDecodeError::DecodeValue(
+                anyhow::anyhow!(
+                    "insufficient data: expecting {} bytes for length",
+                    mem::size_of::<u32>()
+                )
+                .into(),
+            ));
+        }
+        let length = usize::try_from(buf.get_u32_le()).expect("u32 to fit into usize");
+        if buf.remaining() < length {
+            return Err(StorageDecodeError::DecodeValue(
+                anyhow::anyhow!(
+                    "insufficient data: expecting {} bytes for compressed value",
+                    length
+                )
+                .into(),
+            ));
+        }
+
+        let compressed = buf.copy_to_bytes(length);
+        Ok(zstd::stream::decode_all(compressed.reader())?.into())
+    }
 }
 
 /// Trait to encode a value using the specified [`Self::default_codec`]. The trait is used by the
@@ -143,6 +228,12 @@ pub trait StorageEncode: DowncastSync {
 
     /// Codec which is used when encode new values.
     fn default_codec(&self) -> StorageCodecKind;
+
+    /// Encoded values larger than this threshold are compressed by the [`StorageCodec`]. Values
+    /// are not compressed by default.
+    fn compression_threshold(&self) -> Option<usize> {
+        None
+    }
 }
 impl_downcast!(sync StorageEncode);
 
@@ -162,14 +253,24 @@ pub trait StorageDecode {
 
 /// Implements the [`StorageEncode`] and [`StorageDecode`] by encoding/decoding the implementing
 /// type using [`flexbuffers`] and [`serde`].
+///
+/// Optionally, a `compression_threshold` expression can be provided to compress large encoded
+/// values, see [`StorageEncode::compression_threshold`].
 #[macro_export]
 macro_rules! flexbuffers_storage_encode_decode {
     ($name:tt) => {
+        $crate::flexbuffers_storage_encode_decode!($name, compression_threshold = None);
+    };
+    ($name:tt, compression_threshold = $compression_threshold:expr) => {
         impl $crate::storage::StorageEncode for $name {
             fn default_codec(&self) -> $crate::storage::StorageCodecKind {
                 $crate::storage::StorageCodecKind::FlexbuffersSerde
             }
 
+            fn compression_threshold(&self) -> Option<usize> {
+                $compression_threshold
+            }
+
             fn encode(
                 &self,
                 buf: &mut ::bytes::BytesMut,
@@ -529,4 +630,29 @@ mod tests {
         let a: Arc<dyn StorageEncode> = Arc::new("hello".to_string());
         assert!(a.is::<String>());
     }
+
+    #[test]
+    fn compressed_roundtrip() {
+        let value = "hello ".repeat(1024);
+
+        let mut buf = BytesMut::new();
+        StorageCodec::encode_with_compression(&value, &mut buf, Some(128)).unwrap();
+        assert_eq!(buf[0], u8::from(StorageCodecKind::Zstd));
+        assert!(buf.len() < value.len());
+
+        let decoded: String = StorageCodec::decode(&mut buf.freeze()).unwrap();
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    fn values_below_threshold_are_not_compressed() {
+        let value = "hello".to_owned();
+
+        let mut buf = BytesMut::new();
+        StorageCodec::encode_with_compression(&value, &mut buf, Some(128)).unwrap();
+        assert_eq!(buf[0], u8::from(StorageCodecKind::LengthPrefixedRawBytes));
+
+        let decoded: String = StorageCodec::decode(&mut buf.freeze()).unwrap();
+        assert_eq!(decoded, value);
+    }
 }
@@ -9,6 +9,7 @@
 // by the Apache License, Version 2.0.
 
 use std::ops::RangeInclusive;
+use std::time::Duration;
 
 use restate_types::identifiers::{LeaderEpoch, PartitionId, PartitionKey};
 use restate_types::logs::{Keys, Lsn};
@@ -73,3 +74,56 @@ pub struct UpsertSchema {
     pub partition_key_range: Keys,
     pub schema: Schema,
 }
+
+/// Acknowledges the delivery of captured state changes to the change data capture sink. Replicas
+/// drop all captured changes caused by records up to, and including, `up_to_lsn`. Only applies to
+/// the partition with the same `partition_id`.
+///
+/// Since v1.6.0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TruncateChanges {
+    pub partition_id: PartitionId,
+    pub up_to_lsn: Lsn,
+}
+
+/// Turns the capture of state changes on or off. Replicas only capture changes while enabled, so
+/// that all of them retain the same changes regardless of their local configuration. Disabling
+/// change capture drops all changes that haven't been truncated yet. Only applies to the
+/// partition with the same `partition_id`.
+///
+/// Since v1.6.0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConfigureChangeCapture {
+    pub partition_id: PartitionId,
+    pub enabled: bool,
+    /// Service whose invocations and state are not captured, because changes are delivered to
+    /// it.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub excluded_service: Option<String>,
+}
+
+/// Turns the invocation history archive on or off. Replicas only record the summaries of
+/// finished invocations while a retention is set, so that all of them archive the same
+/// invocations regardless of their local configuration. Disabling the archive drops all recorded
+/// summaries. Only applies to the partition with the same `partition_id`.
+///
+/// Since v1.6.0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConfigureInvocationHistory {
+    pub partition_id: PartitionId,
+    pub retention: Option<Duration>,
+}
+
+/// Drops the summaries of the invocations completed before `completed_before` from the
+/// invocation history archive. Only applies to the partition with the same `partition_id`.
+///
+/// Since v1.6.0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TrimInvocationHistory {
+    pub partition_id: PartitionId,
+    pub completed_before: MillisSinceEpoch,
+}
@@ -9,17 +9,22 @@
 // by the Apache License, Version 2.0.
 
 use restate_storage_api::deduplication_table::DedupInformation;
-use restate_types::identifiers::{LeaderEpoch, PartitionId, PartitionKey, WithPartitionKey};
+use restate_types::identifiers::{
+    InvocationId, LeaderEpoch, PartitionId, PartitionKey, WithInvocationId, WithPartitionKey,
+};
 use restate_types::invocation::{
     AttachInvocationRequest, GetInvocationOutputResponse, InvocationResponse,
-    InvocationTermination, NotifySignalRequest, PurgeInvocationRequest,
+    InvocationTermination, MigrateInvocationRequest, NotifySignalRequest, PurgeInvocationRequest,
     RestartAsNewInvocationRequest, ResumeInvocationRequest, ServiceInvocation,
 };
 use restate_types::logs::{self, HasRecordKeys, Keys, MatchKeyQuery};
 use restate_types::message::MessageIndex;
 use restate_types::state_mut::ExternalStateMutation;
 
-use crate::control::{AnnounceLeader, UpsertSchema, VersionBarrier};
+use crate::control::{
+    AnnounceLeader, ConfigureChangeCapture, ConfigureInvocationHistory, TrimInvocationHistory,
+    TruncateChanges, UpsertSchema, VersionBarrier,
+};
 use crate::timer::TimerKeyValue;
 
 use self::control::PartitionDurability;
@@ -36,7 +41,12 @@ pub struct Envelope {
 }
 
 #[cfg(feature = "serde")]
-restate_types::flexbuffers_storage_encode_decode!(Envelope);
+restate_types::flexbuffers_storage_encode_decode!(
+    Envelope,
+    compression_threshold = restate_types::config::Configuration::pinned()
+        .bifrost
+        .record_compression_threshold()
+);
 
 impl Envelope {
     pub fn new(header: Header, command: Command) -> Self {
@@ -189,12 +199,60 @@ pub enum Command {
     /// Upsert schema for consistent schema across replicas
     /// *Since v1.6.0
     UpsertSchema(UpsertSchema),
+    /// Truncate the captured state changes that have been delivered to the change data capture
+    /// sink.
+    /// *Since v1.6.0*
+    TruncateChanges(TruncateChanges),
+    /// Turn the capture of state changes on or off
+    /// *Since v1.6.0*
+    ConfigureChangeCapture(ConfigureChangeCapture),
+    /// Turn the invocation history archive on or off
+    /// *Since v1.6.0*
+    ConfigureInvocationHistory(ConfigureInvocationHistory),
+    /// Drop the oldest summaries of the invocation history archive
+    /// *Since v1.6.0*
+    TrimInvocationHistory(TrimInvocationHistory),
+    /// Re-pin a suspended or paused invocation to another deployment
+    /// *Since v1.6.0*
+    MigrateInvocation(MigrateInvocationRequest),
 }
 
 impl Command {
     pub fn name(&self) -> &'static str {
         CommandDiscriminants::from(self).into()
     }
+
+    /// The invocation this command relates to, if any. Control commands and commands that
+    /// target the partition as a whole return `None`.
+    pub fn invocation_id(&self) -> Option<InvocationId> {
+        match self {
+            Command::UpdatePartitionDurability(_)
+            | Command::VersionBarrier(_)
+            | Command::AnnounceLeader(_)
+            | Command::PatchState(_)
+            | Command::TruncateOutbox(_)
+            | Command::UpsertSchema(_)
+            | Command::TruncateChanges(_)
+            | Command::ConfigureChangeCapture(_)
+            | Command::ConfigureInvocationHistory(_)
+            | Command::TrimInvocationHistory(_) => None,
+            Command::TerminateInvocation(terminate) => Some(terminate.invocation_id),
+            Command::PurgeInvocation(purge) => Some(purge.invocation_id),
+            Command::PurgeJournal(purge) => Some(purge.invocation_id),
+            Command::Invoke(invoke) => Some(invoke.invocation_id),
+            Command::ProxyThrough(invoke) => Some(invoke.invocation_id),
+            Command::AttachInvocation(attach) => Some(attach.invocation_query.to_invocation_id()),
+            Command::ResumeInvocation(req) => Some(req.invocation_id),
+            Command::RestartAsNewInvocation(req) => Some(req.invocation_id),
+            Command::MigrateInvocation(req) => Some(req.invocation_id),
+            Command::InvokerEffect(effect) => Some(effect.invocation_id),
+            Command::Timer(timer) => Some(timer.invocation_id()),
+            Command::ScheduleTimer(timer) => Some(timer.invocation_id()),
+            Command::InvocationResponse(response) => Some(response.invocation_id()),
+            Command::NotifyGetInvocationOutputResponse(res) => Some(res.invocation_id()),
+            Command::NotifySignal(sig) => Some(sig.invocation_id),
+        }
+    }
 }
 
 impl WithPartitionKey for Envelope {
@@ -240,6 +298,11 @@ impl HasRecordKeys for Envelope {
             Command::NotifySignal(sig) => Keys::Single(sig.partition_key()),
             Command::NotifyGetInvocationOutputResponse(res) => Keys::Single(res.partition_key()),
             Command::UpsertSchema(schema) => schema.partition_key_range.clone(),
+            Command::TruncateChanges(_) => Keys::Single(self.partition_key()),
+            Command::ConfigureChangeCapture(_) => Keys::Single(self.partition_key()),
+            Command::ConfigureInvocationHistory(_) => Keys::Single(self.partition_key()),
+            Command::TrimInvocationHistory(_) => Keys::Single(self.partition_key()),
+            Command::MigrateInvocation(req) => Keys::Single(req.partition_key()),
         }
     }
 }
@@ -249,3 +312,59 @@ impl MatchKeyQuery for Envelope {
         self.record_keys().matches_key_query(query)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use restate_types::GenerationalNodeId;
+    use restate_types::invocation::TerminationFlavor;
+
+    #[test]
+    fn invocation_id_of_invocation_commands() {
+        let invocation_id = InvocationId::mock_random();
+
+        let commands = [
+            Command::TerminateInvocation(InvocationTermination {
+                invocation_id,
+                flavor: TerminationFlavor::Cancel,
+                response_sink: None,
+            }),
+            Command::PurgeInvocation(PurgeInvocationRequest {
+                invocation_id,
+                response_sink: None,
+            }),
+            Command::PurgeJournal(PurgeInvocationRequest {
+                invocation_id,
+                response_sink: None,
+            }),
+            Command::ResumeInvocation(ResumeInvocationRequest {
+                invocation_id,
+                update_pinned_deployment_id: None,
+                response_sink: None,
+            }),
+        ];
+        for command in commands {
+            assert_eq!(
+                command.invocation_id(),
+                Some(invocation_id),
+                "{}",
+                command.name()
+            );
+        }
+    }
+
+    #[test]
+    fn control_commands_have_no_invocation_id() {
+        assert_eq!(Command::TruncateOutbox(42).invocation_id(), None);
+        assert_eq!(
+            Command::AnnounceLeader(Box::new(AnnounceLeader {
+                node_id: GenerationalNodeId::new(1, 1),
+                leader_epoch: LeaderEpoch::INITIAL,
+                partition_key_range: 0..=PartitionKey::MAX,
+            }))
+            .invocation_id(),
+            None
+        );
+    }
+}
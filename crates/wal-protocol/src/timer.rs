@@ -68,6 +68,11 @@ impl TimerKeyValue {
         Self { timer_key, value }
     }
 
+    pub fn cancel_invocation(deadline: MillisSinceEpoch, invocation_id: InvocationId) -> Self {
+        let (timer_key, value) = Timer::cancel_invocation(deadline.as_u64(), invocation_id);
+        Self { timer_key, value }
+    }
+
     pub fn into_inner(self) -> (TimerKey, Timer) {
         (self.timer_key, self.value)
     }
@@ -141,6 +146,9 @@ impl fmt::Display for TimerKeyDisplay<'_> {
             TimerKeyKind::CleanInvocationStatus { invocation_uuid } => {
                 write!(f, "Clean invocation status '{invocation_uuid}'")
             }
+            TimerKeyKind::CancelInvocation { invocation_uuid } => {
+                write!(f, "Cancel invocation '{invocation_uuid}' after deadline")
+            }
         }
     }
 }
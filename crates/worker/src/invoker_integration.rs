@@ -19,6 +19,7 @@ use bytestring::ByteString;
 
 use restate_types::errors::{InvocationError, codes};
 use restate_types::identifiers::{AwakeableIdentifier, ExternalSignalIdentifier, InvocationId};
+use restate_types::invocation::offloaded_payload::OffloadedPayload;
 use restate_types::invocation::{
     InvocationTarget, InvocationTargetType, ServiceInvocationSpanContext, ServiceType, SpanRelation,
 };
@@ -28,7 +29,8 @@ use restate_types::journal::enriched::{
 use restate_types::journal::raw::{PlainEntryHeader, PlainRawEntry, RawEntry, RawEntryCodec};
 use restate_types::journal::{
     AttachInvocationEntry, AttachInvocationTarget, CancelInvocationEntry, CancelInvocationTarget,
-    CompleteAwakeableEntry, Entry, GetInvocationOutputEntry, InvokeEntry, OneWayCallEntry,
+    CompleteAwakeableEntry, Entry, EntryResult, GetInvocationOutputEntry, InvokeEntry,
+    OneWayCallEntry, OutputEntry,
 };
 use restate_types::journal::{EntryType, InvokeRequest};
 use restate_types::journal_v2::SignalId;
@@ -66,6 +68,10 @@ where
         let entry = Codec::deserialize(entry_type, serialized_entry.clone())
             .map_err(|e| InvocationError::internal(e.to_string()))?;
         let request = request_extractor(entry);
+        // The parameter becomes the input of the callee, it must not be mistaken for a reference
+        // to an offloaded payload
+        OffloadedPayload::check_not_reference(&request.parameter)
+            .map_err(|e| InvocationError::from(anyhow!(e)))?;
 
         let meta = self
             .schemas
@@ -160,7 +166,20 @@ where
 
         let enriched_header = match header {
             PlainEntryHeader::Input {} => EnrichedEntryHeader::Input {},
-            PlainEntryHeader::Output {} => EnrichedEntryHeader::Output {},
+            PlainEntryHeader::Output {} => {
+                // The output is handed to callers and the ingress as is, it must not be mistaken
+                // for a reference to an offloaded payload
+                let entry = Codec::deserialize(header.as_entry_type(), serialized_entry.clone())
+                    .map_err(|e| InvocationError::internal(e.to_string()))?;
+                if let Entry::Output(OutputEntry {
+                    result: EntryResult::Success(value),
+                }) = &entry
+                {
+                    OffloadedPayload::check_not_reference(value)
+                        .map_err(|e| InvocationError::from(anyhow!(e)))?;
+                }
+                EnrichedEntryHeader::Output {}
+            }
             PlainEntryHeader::GetState { is_completed } => {
                 can_read_state(
                     &header.as_entry_type(),
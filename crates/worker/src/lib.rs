@@ -36,6 +36,7 @@ use restate_core::{Metadata, TaskKind};
 use restate_core::{MetadataWriter, TaskCenter};
 use restate_ingress_kafka::Service as IngressKafkaService;
 use restate_invoker_impl::InvokerHandle as InvokerChannelServiceHandle;
+use restate_object_store_util::PayloadStore;
 use restate_partition_store::snapshots::SnapshotRepository;
 use restate_partition_store::{PartitionStore, PartitionStoreManager};
 use restate_storage_query_datafusion::context::{QueryContext, SelectPartitionsFromMetadata};
@@ -108,6 +109,7 @@ impl Worker {
         bifrost: Bifrost,
         router_builder: &mut MessageRouterBuilder,
         metadata_writer: MetadataWriter,
+        payload_store: Option<PayloadStore>,
     ) -> Result<Self, BuildError> {
         metric_definitions::describe_metrics();
         health_status.update(WorkerStatus::StartingUp);
@@ -148,6 +150,7 @@ impl Worker {
             )
             .await
             .map_err(BuildError::SnapshotRepository)?,
+            payload_store,
         );
 
         let remote_scanner_manager = RemoteScannerManager::new(
@@ -13,6 +13,9 @@
 use metrics::{Unit, describe_counter, describe_gauge, describe_histogram};
 
 pub const PARTITION_LABEL: &str = "partition";
+pub const SERVICE_LABEL: &str = "service";
+pub const HANDLER_LABEL: &str = "handler";
+pub const STATUS_LABEL: &str = "status";
 
 pub const PARTITION_BLOCKED_FLARE: &str = "restate.partition.blocked_flare";
 
@@ -29,6 +32,7 @@ pub const PARTITION_TIME_SINCE_LAST_STATUS_UPDATE: &str =
     "restate.partition.time_since_last_status_update";
 pub const PARTITION_APPLIED_LSN_LAG: &str = "restate.partition.applied_lsn_lag";
 pub const PARTITION_IS_EFFECTIVE_LEADER: &str = "restate.partition.is_effective_leader";
+pub const PARTITION_INVOCATIONS: &str = "restate.partition.invocations";
 
 pub const PARTITION_RECORD_COMMITTED_TO_READ_LATENCY_SECONDS: &str =
     "restate.partition.record_committed_to_read_latency.seconds";
@@ -86,6 +90,12 @@ pub(crate) fn describe_metrics() {
         "Set to 1 if the partition is an effective leader"
     );
 
+    describe_gauge!(
+        PARTITION_INVOCATIONS,
+        Unit::Count,
+        "Number of invocations of the partition by service, handler and status, reported by the partition leader"
+    );
+
     describe_gauge!(
         PARTITION_TIME_SINCE_LAST_STATUS_UPDATE,
         Unit::Seconds,
@@ -0,0 +1,646 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Context;
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use bytes::Bytes;
+use bytestring::ByteString;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::util::Timeout;
+use tokio::sync::mpsc;
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, instrument, trace, warn};
+
+use restate_bifrost::Bifrost;
+use restate_core::{Metadata, cancellation_watcher};
+use restate_storage_api::cdc_table::{
+    ChangePosition, InvocationStatusSnapshot, ReadCdcTable, StateChange,
+};
+use restate_storage_api::invocation_status_table::InvocationStatusDiscriminants;
+use restate_types::config::{ChangeCaptureOptions, ChangeCaptureSink, IngressOptions};
+use restate_types::errors::InvocationErrorCode;
+use restate_types::identifiers::{InvocationId, LeaderEpoch, PartitionId, WithPartitionKey};
+use restate_types::invocation::{InvocationTarget, ResponseResult, ServiceInvocation};
+use restate_types::logs::{Lsn, SequenceNumber};
+use restate_types::schema::invocation_target::InvocationTargetResolver;
+use restate_types::time::MillisSinceEpoch;
+use restate_wal_protocol::{Command, Destination, Envelope, Header, Source};
+
+/// Delivers the changes captured by the partition processor to the configured sink and reports
+/// the LSN up to which all changes have been delivered, so that they can be truncated.
+///
+/// Delivery is at-least-once: the shipper of a new leader starts from the oldest change that has
+/// not been truncated yet.
+pub(super) struct ChangeShipper<Storage> {
+    partition_id: PartitionId,
+    storage: Storage,
+    sink: Sink,
+    options: ChangeCaptureOptions,
+    delivered_tx: mpsc::Sender<Lsn>,
+}
+
+impl<Storage> ChangeShipper<Storage>
+where
+    Storage: ReadCdcTable + Send + Sync + 'static,
+{
+    pub(super) fn new(
+        partition_id: PartitionId,
+        leader_epoch: LeaderEpoch,
+        storage: Storage,
+        bifrost: Bifrost,
+        options: ChangeCaptureOptions,
+        ingress_options: &IngressOptions,
+        delivered_tx: mpsc::Sender<Lsn>,
+    ) -> anyhow::Result<Self> {
+        let sink = match options
+            .sink
+            .as_ref()
+            .context("no change capture sink configured")?
+        {
+            ChangeCaptureSink::Kafka { cluster, topic } => {
+                let cluster_options = ingress_options
+                    .get_kafka_cluster(cluster)
+                    .with_context(|| format!("Change capture sink references the Kafka cluster '{cluster}' which is not configured. Configured Kafka clusters: {:?}", ingress_options.available_kafka_clusters()))?;
+
+                let mut client_config = rdkafka::ClientConfig::new();
+                client_config.set("metadata.broker.list", cluster_options.brokers.join(","));
+                for (k, v) in cluster_options.additional_options.clone() {
+                    client_config.set(k, v);
+                }
+
+                Sink::Kafka {
+                    producer: client_config
+                        .create()
+                        .context("failed creating the Kafka producer")?,
+                    topic: topic.clone(),
+                }
+            }
+            ChangeCaptureSink::Service { service, handler } => Sink::Service {
+                bifrost,
+                source: Source::Processor {
+                    partition_id: None,
+                    partition_key: None,
+                    leader_epoch,
+                },
+                invocation_target: InvocationTarget::service(service.clone(), handler.clone()),
+            },
+        };
+
+        Ok(Self {
+            partition_id,
+            storage,
+            sink,
+            options,
+            delivered_tx,
+        })
+    }
+
+    #[instrument(skip_all)]
+    pub(super) async fn run(mut self) -> anyhow::Result<()> {
+        debug!(sink = %self.sink, "Running change shipper");
+
+        let mut interval = tokio::time::interval(self.options.poll_interval());
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        let mut next_position = ChangePosition::MIN;
+        let mut acknowledged_lsn = Lsn::INVALID;
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if let Err(err) = self.ship_changes(&mut next_position, &mut acknowledged_lsn).await {
+                        warn!(%err, "Failed to deliver captured changes, will retry in {:?}", self.options.poll_interval());
+                    }
+                },
+                _ = cancellation_watcher() => {
+                    break;
+                }
+            }
+        }
+
+        debug!("Stopping change shipper");
+
+        Ok(())
+    }
+
+    /// Delivers all changes that are currently available, starting at `next_position`.
+    async fn ship_changes(
+        &mut self,
+        next_position: &mut ChangePosition,
+        acknowledged_lsn: &mut Lsn,
+    ) -> anyhow::Result<()> {
+        let batch_size = self.options.batch_size();
+        loop {
+            let changes = self
+                .storage
+                .get_next_changes(*next_position, batch_size)
+                .await
+                .context("failed reading captured changes")?;
+            // All changes caused by a record are committed at once. So if we read less than a
+            // full batch, we have seen all changes of the last LSN.
+            let is_complete = changes.len() < batch_size;
+
+            if let Some((last_position, _)) = changes.last() {
+                let last_position = *last_position;
+                let partition_id = self.partition_id;
+                let events: Vec<_> = changes
+                    .into_iter()
+                    .map(|(position, change)| ChangeEvent::new(partition_id, position, change))
+                    .collect();
+
+                self.options
+                    .retry_policy
+                    .clone()
+                    .retry(|| self.sink.deliver(&events))
+                    .await?;
+
+                trace!(
+                    "Delivered {} changes up to {:?} to the sink",
+                    events.len(),
+                    last_position
+                );
+                *next_position = ChangePosition::new(last_position.lsn, last_position.index + 1);
+            }
+
+            let delivered_lsn = if is_complete {
+                next_position.lsn
+            } else {
+                next_position.lsn.prev()
+            };
+            if delivered_lsn > *acknowledged_lsn {
+                self.delivered_tx
+                    .send(delivered_lsn)
+                    .await
+                    .context("partition leader stopped")?;
+                *acknowledged_lsn = delivered_lsn;
+            }
+
+            if is_complete {
+                return Ok(());
+            }
+        }
+    }
+}
+
+enum Sink {
+    Kafka {
+        producer: FutureProducer,
+        topic: String,
+    },
+    Service {
+        bifrost: Bifrost,
+        source: Source,
+        invocation_target: InvocationTarget,
+    },
+    #[cfg(test)]
+    Collect(mpsc::UnboundedSender<Vec<ChangeEvent>>),
+}
+
+impl std::fmt::Display for Sink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Sink::Kafka { topic, .. } => write!(f, "kafka topic '{topic}'"),
+            Sink::Service {
+                invocation_target, ..
+            } => write!(f, "handler '{invocation_target}'"),
+            #[cfg(test)]
+            Sink::Collect(_) => write!(f, "collector"),
+        }
+    }
+}
+
+impl Sink {
+    async fn deliver(&self, events: &[ChangeEvent]) -> anyhow::Result<()> {
+        match self {
+            Sink::Kafka { producer, topic } => {
+                let deliveries = events
+                    .iter()
+                    .map(|event| {
+                        let key = event.key();
+                        let payload = serde_json::to_vec(event)?;
+                        Ok(async move {
+                            producer
+                                .send(
+                                    FutureRecord::to(topic).key(&key).payload(&payload),
+                                    Timeout::After(Duration::from_secs(30)),
+                                )
+                                .await
+                                .map_err(|(err, _)| err)
+                        })
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+
+                futures::future::try_join_all(deliveries)
+                    .await
+                    .context("failed producing to Kafka")?;
+            }
+            Sink::Service {
+                bifrost,
+                source,
+                invocation_target,
+            } => {
+                let retention = Metadata::with_current(|m| m.schema())
+                    .resolve_latest_invocation_target(
+                        invocation_target.service_name(),
+                        invocation_target.handler_name(),
+                    )
+                    .with_context(|| {
+                        format!(
+                            "change capture sink handler '{invocation_target}' is not registered"
+                        )
+                    })?
+                    .compute_retention(true);
+
+                let mut service_invocation = sink_invocation(invocation_target, events)?;
+                service_invocation.with_retention(retention);
+
+                restate_bifrost::append_to_bifrost(
+                    bifrost,
+                    Arc::new(Envelope {
+                        header: Header {
+                            source: source.clone(),
+                            dest: Destination::Processor {
+                                partition_key: service_invocation.invocation_id.partition_key(),
+                                dedup: None,
+                            },
+                        },
+                        command: Command::Invoke(Box::new(service_invocation)),
+                    }),
+                )
+                .await
+                .context("failed appending the change capture invocation to bifrost")?;
+            }
+            #[cfg(test)]
+            Sink::Collect(tx) => {
+                tx.send(events.to_vec()).context("collector closed")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Builds the invocation delivering a batch of changes to the sink handler. The partition
+/// processor doesn't capture the changes of the sink service, so that this invocation doesn't
+/// cause new changes to deliver.
+pub(super) fn sink_invocation(
+    invocation_target: &InvocationTarget,
+    events: &[ChangeEvent],
+) -> anyhow::Result<ServiceInvocation> {
+    let idempotency_key = batch_idempotency_key(events);
+    let invocation_id = InvocationId::generate(invocation_target, Some(&idempotency_key));
+
+    let mut service_invocation = ServiceInvocation::initialize(
+        invocation_id,
+        invocation_target.clone(),
+        restate_types::invocation::Source::Internal,
+    );
+    service_invocation.argument = Bytes::from(serde_json::to_vec(events)?);
+    service_invocation.headers = vec![restate_types::invocation::Header::new(
+        "content-type",
+        "application/json",
+    )];
+    service_invocation.idempotency_key = Some(ByteString::from(idempotency_key));
+    Ok(service_invocation)
+}
+
+/// Identifies a batch by the range of changes it contains. A new leader starts delivering from
+/// the oldest untruncated change, so its batches can overlap with the ones delivered by the
+/// previous leader. Keying on the whole range makes sure that a batch containing additional
+/// changes is not deduplicated against a batch delivered before.
+fn batch_idempotency_key(events: &[ChangeEvent]) -> String {
+    let first = events.first().expect("at least one event");
+    let last = events.last().expect("at least one event");
+    format!(
+        "change-capture-{}-{}.{}-{}.{}",
+        first.partition_id, first.lsn, first.index, last.lsn, last.index
+    )
+}
+
+/// The JSON representation of a captured change as delivered to the sink.
+#[derive(Debug, Clone, serde::Serialize)]
+pub(super) struct ChangeEvent {
+    partition_id: PartitionId,
+    lsn: Lsn,
+    index: u32,
+    #[serde(flatten)]
+    change: ChangeEventKind,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ChangeEventKind {
+    UserState {
+        service_name: ByteString,
+        service_key: ByteString,
+        key: String,
+        /// Base64 encoded value before the change
+        old_value: Option<String>,
+        /// Base64 encoded value after the change
+        new_value: Option<String>,
+    },
+    InvocationStatus {
+        invocation_id: String,
+        /// Status before the change, `None` if the invocation did not exist
+        old_status: Option<InvocationStatusEvent>,
+        /// Status after the change, `None` if the invocation has been removed
+        new_status: Option<InvocationStatusEvent>,
+    },
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct InvocationStatusEvent {
+    status: InvocationStatusDiscriminants,
+    target: String,
+    idempotency_key: Option<String>,
+    deployment_id: Option<String>,
+    journal_length: Option<u32>,
+    created_at: MillisSinceEpoch,
+    modified_at: MillisSinceEpoch,
+    /// Base64 encoded output of a successfully completed invocation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output: Option<String>,
+    /// Failure of a completed invocation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    failure: Option<InvocationFailureEvent>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct InvocationFailureEvent {
+    code: InvocationErrorCode,
+    message: String,
+}
+
+impl From<InvocationStatusSnapshot> for InvocationStatusEvent {
+    fn from(snapshot: InvocationStatusSnapshot) -> Self {
+        let (output, failure) = match snapshot.response_result {
+            Some(ResponseResult::Success(output)) => (Some(BASE64_STANDARD.encode(output)), None),
+            Some(ResponseResult::Failure(err)) => (
+                None,
+                Some(InvocationFailureEvent {
+                    code: err.code(),
+                    message: err.message().to_owned(),
+                }),
+            ),
+            None => (None, None),
+        };
+
+        Self {
+            status: snapshot.status,
+            target: snapshot.invocation_target.to_string(),
+            idempotency_key: snapshot.idempotency_key.map(|key| key.to_string()),
+            deployment_id: snapshot.deployment_id.map(|id| id.to_string()),
+            journal_length: snapshot.journal_length,
+            created_at: snapshot.created_at,
+            modified_at: snapshot.modified_at,
+            output,
+            failure,
+        }
+    }
+}
+
+impl ChangeEvent {
+    pub(super) fn new(
+        partition_id: PartitionId,
+        position: ChangePosition,
+        change: StateChange,
+    ) -> Self {
+        let change = match change {
+            StateChange::UserState {
+                service_id,
+                key,
+                old_value,
+                new_value,
+            } => ChangeEventKind::UserState {
+                service_name: service_id.service_name,
+                service_key: service_id.key,
+                key: String::from_utf8_lossy(&key).into_owned(),
+                old_value: old_value.map(|value| BASE64_STANDARD.encode(value)),
+                new_value: new_value.map(|value| BASE64_STANDARD.encode(value)),
+            },
+            StateChange::InvocationStatus {
+                invocation_id,
+                old_status,
+                new_status,
+            } => ChangeEventKind::InvocationStatus {
+                invocation_id: invocation_id.to_string(),
+                old_status: old_status.map(Into::into),
+                new_status: new_status.map(Into::into),
+            },
+        };
+
+        Self {
+            partition_id,
+            lsn: position.lsn,
+            index: position.index,
+            change,
+        }
+    }
+
+    /// Identifies the changed entity. Used as Kafka record key to keep the changes of the same
+    /// entity in order.
+    fn key(&self) -> String {
+        match &self.change {
+            ChangeEventKind::UserState {
+                service_name,
+                service_key,
+                key,
+                ..
+            } => format!("{service_name}/{service_key}/{key}"),
+            ChangeEventKind::InvocationStatus { invocation_id, .. } => invocation_id.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::num::NonZeroUsize;
+
+    use googletest::prelude::*;
+    use test_log::test;
+
+    use restate_types::config::ChangeCaptureOptionsBuilder;
+    use restate_types::identifiers::ServiceId;
+
+    struct MockCdcTable(Vec<(ChangePosition, StateChange)>);
+
+    impl ReadCdcTable for MockCdcTable {
+        async fn get_next_changes(
+            &mut self,
+            from: ChangePosition,
+            limit: usize,
+        ) -> restate_storage_api::Result<Vec<(ChangePosition, StateChange)>> {
+            Ok(self
+                .0
+                .iter()
+                .filter(|(position, _)| *position >= from)
+                .take(limit)
+                .cloned()
+                .collect())
+        }
+    }
+
+    fn state_change(value: &'static [u8]) -> StateChange {
+        StateChange::UserState {
+            service_id: ServiceId::new("counter", "my-key"),
+            key: Bytes::from_static(b"count"),
+            old_value: None,
+            new_value: Some(Bytes::from_static(value)),
+        }
+    }
+
+    fn change_shipper(
+        changes: Vec<(ChangePosition, StateChange)>,
+        batch_size: usize,
+    ) -> (
+        ChangeShipper<MockCdcTable>,
+        mpsc::UnboundedReceiver<Vec<ChangeEvent>>,
+        mpsc::Receiver<Lsn>,
+    ) {
+        let (events_tx, events_rx) = mpsc::unbounded_channel();
+        let (delivered_tx, delivered_rx) = mpsc::channel(16);
+        let options = ChangeCaptureOptionsBuilder::default()
+            .batch_size(NonZeroUsize::new(batch_size).unwrap())
+            .build()
+            .unwrap();
+
+        (
+            ChangeShipper {
+                partition_id: PartitionId::MIN,
+                storage: MockCdcTable(changes),
+                sink: Sink::Collect(events_tx),
+                options,
+                delivered_tx,
+            },
+            events_rx,
+            delivered_rx,
+        )
+    }
+
+    fn positions(events: &[ChangeEvent]) -> Vec<(Lsn, u32)> {
+        events
+            .iter()
+            .map(|event| (event.lsn, event.index))
+            .collect()
+    }
+
+    #[test(restate_core::test)]
+    async fn ships_changes_in_batches() -> anyhow::Result<()> {
+        let changes = vec![
+            (ChangePosition::new(Lsn::new(1), 0), state_change(b"1")),
+            (ChangePosition::new(Lsn::new(1), 1), state_change(b"2")),
+            (ChangePosition::new(Lsn::new(2), 0), state_change(b"3")),
+            (ChangePosition::new(Lsn::new(3), 0), state_change(b"4")),
+            (ChangePosition::new(Lsn::new(3), 1), state_change(b"5")),
+        ];
+        let (mut shipper, mut events_rx, mut delivered_rx) = change_shipper(changes, 2);
+
+        let mut next_position = ChangePosition::MIN;
+        let mut acknowledged_lsn = Lsn::INVALID;
+        shipper
+            .ship_changes(&mut next_position, &mut acknowledged_lsn)
+            .await?;
+
+        assert_that!(
+            positions(&events_rx.recv().await.unwrap()),
+            elements_are![eq((Lsn::new(1), 0)), eq((Lsn::new(1), 1))]
+        );
+        assert_that!(
+            positions(&events_rx.recv().await.unwrap()),
+            elements_are![eq((Lsn::new(2), 0)), eq((Lsn::new(3), 0))]
+        );
+        assert_that!(
+            positions(&events_rx.recv().await.unwrap()),
+            elements_are![eq((Lsn::new(3), 1))]
+        );
+        assert!(events_rx.try_recv().is_err());
+
+        // Only LSNs whose changes have all been delivered are acknowledged
+        let mut delivered = Vec::new();
+        while let Ok(lsn) = delivered_rx.try_recv() {
+            delivered.push(lsn);
+        }
+        assert_that!(
+            delivered,
+            elements_are![eq(Lsn::new(1)), eq(Lsn::new(2)), eq(Lsn::new(3))]
+        );
+        assert_that!(acknowledged_lsn, eq(Lsn::new(3)));
+
+        // Nothing is delivered nor acknowledged again without new changes
+        shipper
+            .ship_changes(&mut next_position, &mut acknowledged_lsn)
+            .await?;
+        assert!(events_rx.try_recv().is_err());
+        assert!(delivered_rx.try_recv().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn overlapping_batches_have_different_idempotency_keys() {
+        let event = |lsn: u64, index: u32| {
+            ChangeEvent::new(
+                PartitionId::MIN,
+                ChangePosition::new(Lsn::new(lsn), index),
+                state_change(b"1"),
+            )
+        };
+
+        let batch = [event(1, 0), event(1, 1)];
+        let overlapping_batch = [event(1, 0), event(1, 1), event(2, 0)];
+
+        assert_eq!(
+            batch_idempotency_key(&batch),
+            batch_idempotency_key(&[event(1, 0), event(1, 1)])
+        );
+        assert_ne!(
+            batch_idempotency_key(&batch),
+            batch_idempotency_key(&overlapping_batch)
+        );
+    }
+
+    #[test]
+    fn invocation_status_event_contains_completion() {
+        let invocation_id = InvocationId::mock_random();
+        let event = ChangeEvent::new(
+            PartitionId::MIN,
+            ChangePosition::new(Lsn::new(1), 0),
+            StateChange::InvocationStatus {
+                invocation_id,
+                old_status: None,
+                new_status: Some(InvocationStatusSnapshot {
+                    status: InvocationStatusDiscriminants::Completed,
+                    invocation_target: InvocationTarget::service("greeter", "greet"),
+                    idempotency_key: None,
+                    deployment_id: None,
+                    journal_length: Some(3),
+                    created_at: MillisSinceEpoch::new(1),
+                    modified_at: MillisSinceEpoch::new(2),
+                    response_result: Some(ResponseResult::Success(Bytes::from_static(b"hi"))),
+                }),
+            },
+        );
+
+        assert_eq!(event.key(), invocation_id.to_string());
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "invocation_status");
+        assert_eq!(json["old_status"], serde_json::Value::Null);
+        assert_eq!(json["new_status"]["status"], "completed");
+        assert_eq!(json["new_status"]["target"], "greeter/greet");
+        assert_eq!(json["new_status"]["journal_length"], 3);
+        assert_eq!(json["new_status"]["output"], BASE64_STANDARD.encode(b"hi"));
+    }
+}
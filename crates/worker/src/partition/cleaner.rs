@@ -19,24 +19,33 @@ use tracing::{debug, instrument, warn};
 
 use restate_bifrost::Bifrost;
 use restate_core::cancellation_watcher;
-use restate_storage_api::invocation_status_table::{InvocationStatus, ScanInvocationStatusTable};
+use restate_object_store_util::PayloadStore;
+use restate_storage_api::invocation_status_table::{
+    InvocationStatus, ReadInvocationStatusTable, ScanInvocationStatusTable,
+};
 use restate_types::identifiers::WithPartitionKey;
 use restate_types::identifiers::{LeaderEpoch, PartitionKey};
 use restate_types::invocation::PurgeInvocationRequest;
+use restate_types::invocation::offloaded_payload::OffloadedPayload;
 use restate_types::retries::with_jitter;
 use restate_wal_protocol::{Command, Destination, Envelope, Header, Source};
 
+/// Offloaded payloads younger than this are never swept. They might belong to an invocation
+/// which is not yet appended to the log, or be a response not yet read by the ingress.
+const OFFLOADED_PAYLOADS_GRACE_PERIOD: Duration = Duration::from_secs(60 * 60);
+
 pub(super) struct Cleaner<Storage> {
     leader_epoch: LeaderEpoch,
     partition_key_range: RangeInclusive<PartitionKey>,
     storage: Storage,
     bifrost: Bifrost,
     cleanup_interval: Duration,
+    payload_store: Option<PayloadStore>,
 }
 
 impl<Storage> Cleaner<Storage>
 where
-    Storage: ScanInvocationStatusTable + Send + Sync + 'static,
+    Storage: ScanInvocationStatusTable + ReadInvocationStatusTable + Send + Sync + 'static,
 {
     pub(super) fn new(
         leader_epoch: LeaderEpoch,
@@ -44,6 +53,7 @@ where
         bifrost: Bifrost,
         partition_key_range: RangeInclusive<PartitionKey>,
         cleanup_interval: Duration,
+        payload_store: Option<PayloadStore>,
     ) -> Self {
         Self {
             leader_epoch,
@@ -51,6 +61,7 @@ where
             storage,
             bifrost,
             cleanup_interval,
+            payload_store,
         }
     }
 
@@ -59,9 +70,10 @@ where
         let Self {
             leader_epoch,
             partition_key_range,
-            storage,
+            mut storage,
             bifrost,
             cleanup_interval,
+            payload_store,
         } = self;
 
         debug!(?cleanup_interval, "Running cleaner");
@@ -87,6 +99,12 @@ where
                     if let Err(e) = Self::do_cleanup(&storage, &bifrost, partition_key_range.clone(), &bifrost_envelope_source).await {
                         warn!("Error when trying to cleanup completed invocations: {e:?}");
                     }
+                    if let Some(payload_store) = &payload_store
+                        && let Some(swept_before) = SystemTime::now().checked_sub(OFFLOADED_PAYLOADS_GRACE_PERIOD)
+                        && let Err(e) = Self::sweep_offloaded_payloads(&mut storage, payload_store, partition_key_range.clone(), swept_before).await
+                    {
+                        warn!("Error when trying to sweep offloaded payloads: {e:?}");
+                    }
                 },
                 _ = cancellation_watcher() => {
                     break;
@@ -190,14 +208,67 @@ where
 
         Ok(())
     }
+
+    /// Deletes the offloaded payloads of this partition which are not needed anymore. These are
+    /// usually deleted by the leader right away, but that delete is lost if the leader fails.
+    pub(super) async fn sweep_offloaded_payloads(
+        storage: &mut Storage,
+        payload_store: &PayloadStore,
+        partition_key_range: RangeInclusive<PartitionKey>,
+        swept_before: SystemTime,
+    ) -> anyhow::Result<()> {
+        debug!("Executing offloaded payloads sweep");
+
+        let payloads = payload_store.list_payloads();
+        tokio::pin!(payloads);
+
+        while let Some(payload) = payloads
+            .next()
+            .await
+            .transpose()
+            .context("Cannot list the offloaded payloads")?
+        {
+            if !partition_key_range.contains(&payload.invocation_id.partition_key())
+                || payload.last_modified > swept_before
+            {
+                continue;
+            }
+
+            // Responses are needed only until the ingress reads them, the other payloads as
+            // long as the journal or the input of the invocation are retained.
+            let is_needed = OffloadedPayload::is_retained_with_journal(&payload.key)
+                && match storage
+                    .get_invocation_status(&payload.invocation_id)
+                    .await
+                    .context("Cannot read the invocation status")?
+                {
+                    InvocationStatus::Free => false,
+                    InvocationStatus::Completed(completed_invocation) => {
+                        completed_invocation.journal_metadata.length != 0
+                    }
+                    _ => true,
+                };
+
+            if !is_needed {
+                payload_store
+                    .delete_payload(&payload)
+                    .await
+                    .context("Cannot delete the offloaded payload")?;
+            }
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use futures::{Stream, stream};
+    use bytes::Bytes;
+    use futures::{Stream, TryStreamExt, stream};
     use googletest::prelude::*;
+    use object_store::memory::InMemory;
     use restate_core::{Metadata, TaskCenter, TaskKind, TestCoreEnvBuilder};
     use restate_storage_api::StorageError;
     use restate_storage_api::invocation_status_table::{
@@ -213,6 +284,20 @@ mod tests {
     #[allow(dead_code)]
     struct MockInvocationStatusReader(Vec<(InvocationId, InvocationStatus)>);
 
+    impl ReadInvocationStatusTable for MockInvocationStatusReader {
+        fn get_invocation_status(
+            &mut self,
+            invocation_id: &InvocationId,
+        ) -> impl Future<Output = restate_storage_api::Result<InvocationStatus>> + Send {
+            std::future::ready(Ok(self
+                .0
+                .iter()
+                .find(|(id, _)| id == invocation_id)
+                .map(|(_, status)| status.clone())
+                .unwrap_or_default()))
+        }
+    }
+
     impl ScanInvocationStatusTable for MockInvocationStatusReader {
         fn scan_invocation_statuses(
             &self,
@@ -244,6 +329,26 @@ mod tests {
             Ok(std::future::pending())
         }
 
+        fn for_each_invocation_status_lazy_of<
+            E: Into<anyhow::Error>,
+            F: for<'a> FnMut(
+                    (InvocationId, InvocationStatusV2Lazy<'a>),
+                ) -> std::ops::ControlFlow<std::result::Result<(), E>>
+                + Send
+                + Sync
+                + 'static,
+        >(
+            &self,
+            _: Vec<InvocationId>,
+            _: F,
+        ) -> restate_storage_api::Result<impl Future<Output = restate_storage_api::Result<()>> + Send>
+        {
+            unimplemented!();
+
+            #[allow(unreachable_code)]
+            Ok(std::future::pending())
+        }
+
         fn scan_invoked_invocations(
             &self,
         ) -> restate_storage_api::Result<
@@ -324,6 +429,7 @@ mod tests {
                 bifrost.clone(),
                 RangeInclusive::new(PartitionKey::MIN, PartitionKey::MAX),
                 Duration::from_secs(1),
+                None,
             )
             .run(),
         )
@@ -362,4 +468,95 @@ mod tests {
             )
         );
     }
+
+    #[test(restate_core::test)]
+    pub async fn sweep_deletes_payloads_not_needed_anymore() {
+        let payload_store = PayloadStore::new(Arc::new(InMemory::new()), 0);
+        let payload = Bytes::from_static(b"payload");
+
+        let running_invocation =
+            InvocationId::from_parts(PartitionKey::MIN, InvocationUuid::mock_random());
+        let retained_journal =
+            InvocationId::from_parts(PartitionKey::MIN, InvocationUuid::mock_random());
+        let dropped_journal =
+            InvocationId::from_parts(PartitionKey::MIN, InvocationUuid::mock_random());
+        let purged_invocation =
+            InvocationId::from_parts(PartitionKey::MIN, InvocationUuid::mock_random());
+        let other_partition_invocation =
+            InvocationId::from_parts(PartitionKey::MAX, InvocationUuid::mock_random());
+
+        for invocation_id in [
+            running_invocation,
+            retained_journal,
+            dropped_journal,
+            purged_invocation,
+            other_partition_invocation,
+        ] {
+            payload_store
+                .offload_input(&invocation_id, payload.clone())
+                .await
+                .unwrap();
+        }
+        payload_store
+            .offload_response(&running_invocation, &Default::default(), payload.clone())
+            .await
+            .unwrap();
+
+        let mut storage = MockInvocationStatusReader(vec![
+            (
+                running_invocation,
+                InvocationStatus::Invoked(InFlightInvocationMetadata::mock()),
+            ),
+            (
+                retained_journal,
+                InvocationStatus::Completed(CompletedInvocation {
+                    journal_metadata: JournalMetadata {
+                        length: 2,
+                        commands: 2,
+                        span_context: Default::default(),
+                    },
+                    ..CompletedInvocation::mock_neo()
+                }),
+            ),
+            (
+                dropped_journal,
+                InvocationStatus::Completed(CompletedInvocation::mock_neo()),
+            ),
+        ]);
+
+        // Payloads uploaded after the given time are left untouched
+        Cleaner::sweep_offloaded_payloads(
+            &mut storage,
+            &payload_store,
+            RangeInclusive::new(PartitionKey::MIN, PartitionKey::MAX - 1),
+            SystemTime::UNIX_EPOCH,
+        )
+        .await
+        .unwrap();
+        let payloads: Vec<_> = payload_store.list_payloads().try_collect().await.unwrap();
+        assert_that!(payloads, len(eq(6)));
+
+        Cleaner::sweep_offloaded_payloads(
+            &mut storage,
+            &payload_store,
+            RangeInclusive::new(PartitionKey::MIN, PartitionKey::MAX - 1),
+            SystemTime::now() + Duration::from_secs(1),
+        )
+        .await
+        .unwrap();
+        let remaining: Vec<_> = payload_store
+            .list_payloads()
+            .map_ok(|payload| (payload.invocation_id, payload.key))
+            .try_collect()
+            .await
+            .unwrap();
+        assert_that!(
+            remaining,
+            unordered_elements_are![
+                eq((running_invocation, "input".to_owned())),
+                eq((retained_journal, "input".to_owned())),
+                eq((other_partition_invocation, "input".to_owned())),
+            ]
+        );
+    }
 }
@@ -0,0 +1,101 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::{HashMap, HashSet};
+
+use bytestring::ByteString;
+use metrics::{SharedString, gauge};
+
+use restate_partition_store::PartitionStore;
+use restate_storage_api::StorageError;
+use restate_storage_api::invocation_counts_table::ReadInvocationCountsTable;
+use restate_storage_api::invocation_status_table::InvocationStatusDiscriminants;
+
+use crate::metric_definitions::{
+    HANDLER_LABEL, PARTITION_INVOCATIONS, PARTITION_LABEL, SERVICE_LABEL, STATUS_LABEL,
+};
+
+type Labels = (ByteString, ByteString, &'static str);
+
+/// Reports the invocation counts of the partition as gauges.
+///
+/// Only the leader reports the counts, so that the gauges of all nodes can be summed up without
+/// counting the replicas of a partition several times.
+pub(super) struct InvocationCountsReporter {
+    partition_id_str: SharedString,
+    /// Labels of the reported gauges, which need to be reset once they aren't reported anymore.
+    reported: HashSet<Labels>,
+}
+
+impl InvocationCountsReporter {
+    pub(super) fn new(partition_id_str: SharedString) -> Self {
+        Self {
+            partition_id_str,
+            reported: HashSet::new(),
+        }
+    }
+
+    pub(super) async fn report(
+        &mut self,
+        partition_store: &mut PartitionStore,
+        is_leader: bool,
+    ) -> Result<(), StorageError> {
+        let mut counts: HashMap<Labels, u64> = HashMap::new();
+        if is_leader {
+            // the pinned deployment is left out to keep the cardinality of the gauges low
+            for (key, count) in partition_store.get_invocation_counts().await? {
+                *counts
+                    .entry((key.service_name, key.handler_name, status_label(key.status)))
+                    .or_default() += count;
+            }
+        }
+
+        for labels in self.reported.iter() {
+            if !counts.contains_key(labels) {
+                self.gauge(labels).set(0.0);
+            }
+        }
+        for (labels, count) in &counts {
+            self.gauge(labels).set(*count as f64);
+        }
+        self.reported = counts.into_keys().collect();
+
+        Ok(())
+    }
+
+    fn gauge(&self, (service, handler, status): &Labels) -> metrics::Gauge {
+        gauge!(PARTITION_INVOCATIONS,
+            PARTITION_LABEL => self.partition_id_str.clone(),
+            SERVICE_LABEL => service.to_string(),
+            HANDLER_LABEL => handler.to_string(),
+            STATUS_LABEL => *status,
+        )
+    }
+}
+
+impl Drop for InvocationCountsReporter {
+    fn drop(&mut self) {
+        for labels in self.reported.iter() {
+            self.gauge(labels).set(0.0);
+        }
+    }
+}
+
+fn status_label(status: InvocationStatusDiscriminants) -> &'static str {
+    match status {
+        InvocationStatusDiscriminants::Scheduled => "scheduled",
+        InvocationStatusDiscriminants::Inboxed => "inboxed",
+        InvocationStatusDiscriminants::Invoked => "invoked",
+        InvocationStatusDiscriminants::Suspended => "suspended",
+        InvocationStatusDiscriminants::Paused => "paused",
+        InvocationStatusDiscriminants::Killed => "killed",
+        InvocationStatusDiscriminants::Completed => "completed",
+    }
+}
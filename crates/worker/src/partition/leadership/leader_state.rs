@@ -21,20 +21,24 @@ use futures::future::OptionFuture;
 use futures::stream::FuturesUnordered;
 use futures::{FutureExt, StreamExt, stream};
 use metrics::counter;
-use restate_types::logs::Keys;
-use restate_wal_protocol::control::UpsertSchema;
-use tokio_stream::wrappers::{ReceiverStream, WatchStream};
-use tracing::{debug, trace};
+use restate_types::logs::{Keys, Lsn};
+use restate_wal_protocol::control::{TrimInvocationHistory, TruncateChanges, UpsertSchema};
+use tokio::time::MissedTickBehavior;
+use tokio_stream::wrappers::{IntervalStream, ReceiverStream, WatchStream};
+use tracing::{debug, trace, warn};
 
 use restate_bifrost::CommitToken;
 use restate_core::network::{Oneshot, Reciprocal};
-use restate_core::{Metadata, MetadataKind, TaskCenter, TaskHandle, TaskId};
+use restate_core::{Metadata, MetadataKind, TaskCenter, TaskHandle, TaskId, TaskKind};
+use restate_object_store_util::PayloadStore;
 use restate_partition_store::PartitionStore;
 use restate_types::identifiers::{
     InvocationId, LeaderEpoch, PartitionId, PartitionKey, PartitionProcessorRpcRequestId,
     WithPartitionKey,
 };
-use restate_types::invocation::client::{InvocationOutput, SubmittedInvocationNotification};
+use restate_types::invocation::client::{
+    InvocationOutput, InvocationOutputResponse, SubmittedInvocationNotification,
+};
 use restate_types::net::partition_processor::{
     PartitionProcessorRpcError, PartitionProcessorRpcResponse,
 };
@@ -81,7 +85,13 @@ pub struct LeaderState {
     pub pending_cleanup_timers_to_schedule: VecDeque<(InvocationId, Duration)>,
     cleaner_task_id: TaskId,
     trimmer_task_id: TaskId,
+    change_shipper_task_id: Option<TaskId>,
+    changes_delivered_stream: ReceiverStream<Lsn>,
     durability_tracker: DurabilityTracker,
+    payload_store: Option<PayloadStore>,
+    /// Ticks whenever the leader should propose to trim the invocation history
+    invocation_history_trim_stream: IntervalStream,
+    invocation_history_retention: Option<Duration>,
 }
 
 impl LeaderState {
@@ -93,13 +103,22 @@ impl LeaderState {
         shuffle_task_handle: TaskHandle<anyhow::Result<()>>,
         cleaner_task_id: TaskId,
         trimmer_task_id: TaskId,
+        change_shipper_task_id: Option<TaskId>,
         shuffle_hint_tx: HintSender,
         timer_service: TimerService,
         self_proposer: SelfProposer,
         invoker_rx: InvokerStream,
         shuffle_rx: tokio::sync::mpsc::Receiver<shuffle::OutboxTruncation>,
+        changes_delivered_rx: tokio::sync::mpsc::Receiver<Lsn>,
         durability_tracker: DurabilityTracker,
+        payload_store: Option<PayloadStore>,
+        invocation_history_retention: Option<Duration>,
+        invocation_history_trim_interval: Duration,
     ) -> Self {
+        let mut invocation_history_trim_interval =
+            tokio::time::interval(invocation_history_trim_interval);
+        invocation_history_trim_interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
         LeaderState {
             partition_id,
             leader_epoch,
@@ -107,6 +126,7 @@ impl LeaderState {
             shuffle_task_handle: Some(shuffle_task_handle),
             cleaner_task_id,
             trimmer_task_id,
+            change_shipper_task_id,
             shuffle_hint_tx,
             schema_stream: Metadata::with_current(|m| {
                 WatchStream::new(m.watch(MetadataKind::Schema))
@@ -117,8 +137,12 @@ impl LeaderState {
             awaiting_rpc_self_propose: Default::default(),
             invoker_stream: invoker_rx,
             shuffle_stream: ReceiverStream::new(shuffle_rx),
+            changes_delivered_stream: ReceiverStream::new(changes_delivered_rx),
             pending_cleanup_timers_to_schedule: Default::default(),
             durability_tracker,
+            payload_store,
+            invocation_history_trim_stream: IntervalStream::new(invocation_history_trim_interval),
+            invocation_history_retention,
         }
     }
 
@@ -155,6 +179,15 @@ impl LeaderState {
         let shuffle_stream = (&mut self.shuffle_stream).map(ActionEffect::Shuffle);
         let dur_tracker_stream =
             (&mut self.durability_tracker).map(ActionEffect::PartitionMaintenance);
+        let changes_delivered_stream =
+            (&mut self.changes_delivered_stream).map(ActionEffect::ChangesDelivered);
+        let invocation_history_retention = self.invocation_history_retention;
+        let invocation_history_trim_stream =
+            (&mut self.invocation_history_trim_stream).filter_map(move |_| {
+                future::ready(invocation_history_retention.map(|retention| {
+                    ActionEffect::TrimInvocationHistory(MillisSinceEpoch::now() - retention)
+                }))
+            });
 
         let action_effects_stream = stream::unfold(
             &mut self.pending_cleanup_timers_to_schedule,
@@ -179,6 +212,8 @@ impl LeaderState {
             action_effects_stream,
             awaiting_rpc_self_propose_stream,
             dur_tracker_stream,
+            changes_delivered_stream,
+            invocation_history_trim_stream,
             schema_stream
         );
         let mut all_streams = all_streams.ready_chunks(BATCH_READY_UP_TO);
@@ -229,6 +264,10 @@ impl LeaderState {
 
         // We don't really care about waiting for the trimmer to finish cancelling
         TaskCenter::cancel_task(self.trimmer_task_id);
+        // Undelivered changes will be delivered by the next leader
+        if let Some(change_shipper_task_id) = self.change_shipper_task_id {
+            TaskCenter::cancel_task(change_shipper_task_id);
+        }
 
         // It's ok to not check the abort_result because either it succeeded or the invoker
         // is not running. If the invoker is not running, and we are not shutting down, then
@@ -274,6 +313,28 @@ impl LeaderState {
                         )
                         .await?;
                 }
+                ActionEffect::TrimInvocationHistory(completed_before) => {
+                    self.self_proposer
+                        .propose(
+                            *self.partition_key_range.start(),
+                            Command::TrimInvocationHistory(TrimInvocationHistory {
+                                partition_id: self.partition_id,
+                                completed_before,
+                            }),
+                        )
+                        .await?;
+                }
+                ActionEffect::ChangesDelivered(up_to_lsn) => {
+                    self.self_proposer
+                        .propose(
+                            *self.partition_key_range.start(),
+                            Command::TruncateChanges(TruncateChanges {
+                                partition_id: self.partition_id,
+                                up_to_lsn,
+                            }),
+                        )
+                        .await?;
+                }
                 ActionEffect::Invoker(invoker_effect) => {
                     self.self_proposer
                         .propose(
@@ -479,14 +540,49 @@ impl LeaderState {
                 ..
             } => {
                 if let Some(response_tx) = self.awaiting_rpc_actions.remove(&request_id) {
-                    response_tx.send(Ok(PartitionProcessorRpcResponse::Output(
-                        InvocationOutput {
-                            request_id,
-                            invocation_id,
-                            completion_expiry_time,
-                            response,
-                        },
-                    )));
+                    let mut output = InvocationOutput {
+                        request_id,
+                        invocation_id,
+                        completion_expiry_time,
+                        response,
+                    };
+                    // Large responses are passed to the ingress through the object store
+                    match (&self.payload_store, invocation_id, &output.response) {
+                        (
+                            Some(payload_store),
+                            Some(invocation_id),
+                            InvocationOutputResponse::Success(_, payload),
+                        ) if payload_store.should_offload(payload.len()) => {
+                            let payload_store = payload_store.clone();
+                            TaskCenter::spawn(
+                                TaskKind::Disposable,
+                                "offload-ingress-response",
+                                async move {
+                                    if let InvocationOutputResponse::Success(_, payload) =
+                                        &mut output.response
+                                    {
+                                        match payload_store
+                                            .offload_response(
+                                                &invocation_id,
+                                                &request_id,
+                                                payload.clone(),
+                                            )
+                                            .await
+                                        {
+                                            Ok(reference) => *payload = reference,
+                                            Err(err) => {
+                                                warn!(%invocation_id, %err, "Failed to offload the ingress response, sending it inline")
+                                            }
+                                        }
+                                    }
+                                    response_tx
+                                        .send(Ok(PartitionProcessorRpcResponse::Output(output)));
+                                    Ok(())
+                                },
+                            )?;
+                        }
+                        _ => response_tx.send(Ok(PartitionProcessorRpcResponse::Output(output))),
+                    }
                 } else {
                     debug!(%request_id, "Ignoring sending ingress response because there is no awaiting rpc");
                 }
@@ -514,6 +610,26 @@ impl LeaderState {
                 self.pending_cleanup_timers_to_schedule
                     .push_back((invocation_id, retention));
             }
+            Action::DeleteOffloadedPayloads { invocation_id } => {
+                // Nothing to delete if payload offloading is not configured
+                if let Some(payload_store) = self.payload_store.clone() {
+                    // Best-effort, if the delete fails or the leader fails in the meantime, the
+                    // cleaner deletes the payloads of the invocation later on
+                    TaskCenter::spawn(
+                        TaskKind::Disposable,
+                        "delete-offloaded-payloads",
+                        async move {
+                            if let Err(err) = payload_store
+                                .delete_invocation_payloads(&invocation_id)
+                                .await
+                            {
+                                warn!(%invocation_id, %err, "Failed to delete offloaded payloads");
+                            }
+                            Ok(())
+                        },
+                    )?;
+                }
+            }
             Action::ForwardNotification {
                 invocation_id,
                 invocation_epoch,
@@ -588,6 +704,16 @@ impl LeaderState {
                     )));
                 }
             }
+            Action::ForwardMigrateInvocationResponse {
+                request_id,
+                response,
+            } => {
+                if let Some(response_tx) = self.awaiting_rpc_actions.remove(&request_id) {
+                    response_tx.send(Ok(PartitionProcessorRpcResponse::MigrateInvocation(
+                        response.into(),
+                    )));
+                }
+            }
         }
 
         Ok(())
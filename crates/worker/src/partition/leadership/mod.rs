@@ -30,6 +30,7 @@ use restate_core::network::{Oneshot, Reciprocal};
 use restate_core::{ShutdownError, TaskCenter, TaskKind, my_node_id};
 use restate_errors::NotRunningError;
 use restate_invoker_api::InvokeInputJournal;
+use restate_object_store_util::PayloadStore;
 use restate_partition_store::PartitionStore;
 use restate_storage_api::deduplication_table::EpochSequenceNumber;
 use restate_storage_api::fsm_table::ReadFsmTable;
@@ -45,6 +46,7 @@ use restate_types::config::Configuration;
 use restate_types::errors::GenericError;
 use restate_types::identifiers::{InvocationId, PartitionKey, PartitionProcessorRpcRequestId};
 use restate_types::identifiers::{LeaderEpoch, PartitionLeaderEpoch};
+use restate_types::logs::Lsn;
 use restate_types::message::MessageIndex;
 use restate_types::net::partition_processor::{
     PartitionProcessorRpcError, PartitionProcessorRpcResponse,
@@ -54,10 +56,14 @@ use restate_types::partitions::state::PartitionReplicaSetStates;
 use restate_types::retries::with_jitter;
 use restate_types::schema::Schema;
 use restate_types::storage::StorageEncodeError;
+use restate_types::time::MillisSinceEpoch;
 use restate_wal_protocol::Command;
-use restate_wal_protocol::control::{AnnounceLeader, PartitionDurability};
+use restate_wal_protocol::control::{
+    AnnounceLeader, ConfigureChangeCapture, ConfigureInvocationHistory, PartitionDurability,
+};
 use restate_wal_protocol::timer::TimerKeyValue;
 
+use crate::partition::change_shipper::ChangeShipper;
 use crate::partition::cleaner::Cleaner;
 use crate::partition::invoker_storage_reader::InvokerStorageReader;
 use crate::partition::leadership::leader_state::LeaderState;
@@ -125,6 +131,8 @@ pub(crate) enum ActionEffect {
     Timer(TimerKeyValue),
     ScheduleCleanupTimer(InvocationId, Duration),
     PartitionMaintenance(PartitionDurability),
+    ChangesDelivered(Lsn),
+    TrimInvocationHistory(MillisSinceEpoch),
     UpsertSchema(Schema),
     AwaitingRpcSelfProposeDone,
 }
@@ -157,6 +165,7 @@ pub(crate) struct LeadershipState<I> {
     bifrost: Bifrost,
     #[allow(unused)]
     trim_queue: TrimQueue,
+    payload_store: Option<PayloadStore>,
 }
 
 impl<I> LeadershipState<I>
@@ -170,6 +179,7 @@ where
         bifrost: Bifrost,
         last_seen_leader_epoch: Option<LeaderEpoch>,
         trim_queue: TrimQueue,
+        payload_store: Option<PayloadStore>,
     ) -> Self {
         Self {
             state: State::Follower,
@@ -178,6 +188,7 @@ where
             bifrost,
             last_seen_leader_epoch,
             trim_queue,
+            payload_store,
         }
     }
 
@@ -385,11 +396,38 @@ where
                 self.bifrost.clone(),
                 self.partition.key_range.clone(),
                 config.worker.cleanup_interval(),
+                self.payload_store.clone(),
             );
 
             let cleaner_task_id =
                 TaskCenter::spawn_child(TaskKind::Cleaner, "cleaner", cleaner.run())?;
 
+            let (changes_delivered_tx, changes_delivered_rx) =
+                mpsc::channel(config.worker.internal_queue_length());
+            let change_shipper_task_id = if config.worker.change_capture.is_enabled() {
+                match ChangeShipper::new(
+                    self.partition.partition_id,
+                    *leader_epoch,
+                    partition_store.clone(),
+                    self.bifrost.clone(),
+                    config.worker.change_capture.clone(),
+                    &config.ingress,
+                    changes_delivered_tx,
+                ) {
+                    Ok(change_shipper) => Some(TaskCenter::spawn_child(
+                        TaskKind::ChangeShipper,
+                        "change-shipper",
+                        change_shipper.run(),
+                    )?),
+                    Err(err) => {
+                        warn!(%err, "Cannot deliver captured changes, change capture sink is misconfigured");
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+
             let trimmer_task_id = LogTrimmer::spawn(
                 self.bifrost.clone(),
                 self.partition.log_id(),
@@ -399,6 +437,48 @@ where
             let mut self_proposer = self_proposer.take().expect("must be present");
             self_proposer.mark_as_leader().await;
 
+            // Whether changes are captured is part of the replicated state. The leader aligns
+            // it with its configuration, all replicas follow once the command is applied.
+            // The sink service is excluded from capture, otherwise each delivery would cause
+            // new changes to deliver.
+            let capture_changes = config.worker.change_capture.is_enabled();
+            let excluded_service = config.worker.change_capture.sink_service();
+            if capture_changes != partition_store.get_change_capture_enabled().await?
+                || excluded_service
+                    != partition_store
+                        .get_change_capture_excluded_service()
+                        .await?
+                        .as_deref()
+            {
+                self_proposer
+                    .propose(
+                        *self.partition.key_range.start(),
+                        Command::ConfigureChangeCapture(ConfigureChangeCapture {
+                            partition_id: self.partition.partition_id,
+                            enabled: capture_changes,
+                            excluded_service: excluded_service.map(str::to_owned),
+                        }),
+                    )
+                    .await?;
+            }
+
+            // Same for the invocation history archive. The trim point is chosen by the leader
+            // too, so that replicas don't depend on their own clocks.
+            let invocation_history_retention = config.worker.invocation_history_retention();
+            if invocation_history_retention
+                != partition_store.get_invocation_history_retention().await?
+            {
+                self_proposer
+                    .propose(
+                        *self.partition.key_range.start(),
+                        Command::ConfigureInvocationHistory(ConfigureInvocationHistory {
+                            partition_id: self.partition.partition_id,
+                            retention: invocation_history_retention,
+                        }),
+                    )
+                    .await?;
+            }
+
             let last_reported_durable_lsn = partition_store
                 .get_partition_durability()
                 .await?
@@ -419,12 +499,17 @@ where
                 shuffle_task_handle,
                 cleaner_task_id,
                 trimmer_task_id,
+                change_shipper_task_id,
                 shuffle_hint_tx,
                 timer_service,
                 self_proposer,
                 invoker_rx,
                 shuffle_rx,
+                changes_delivered_rx,
                 durability_tracker,
+                self.payload_store.clone(),
+                invocation_history_retention,
+                with_jitter(config.worker.cleanup_interval(), 0.5),
             )));
 
             Ok(())
@@ -700,6 +785,7 @@ mod tests {
             bifrost.clone(),
             None,
             TrimQueue::default(),
+            None,
         );
 
         assert!(matches!(state.state, State::Follower));
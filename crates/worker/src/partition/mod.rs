@@ -8,7 +8,9 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+mod change_shipper;
 mod cleaner;
+mod invocation_counts;
 pub mod invoker_storage_reader;
 mod leadership;
 mod rpc;
@@ -22,6 +24,7 @@ use std::time::Duration;
 
 use anyhow::Context;
 use assert2::let_assert;
+use bytestring::ByteString;
 use enumset::EnumSet;
 use futures::{FutureExt, Stream, StreamExt};
 use metrics::{SharedString, gauge, histogram};
@@ -33,12 +36,15 @@ use restate_bifrost::loglet::FindTailOptions;
 use restate_bifrost::{Bifrost, LogEntry, MaybeRecord};
 use restate_core::network::{Oneshot, Reciprocal, ServiceMessage, Verdict};
 use restate_core::{Metadata, ShutdownError, cancellation_watcher, my_node_id};
+use restate_object_store_util::PayloadStore;
 use restate_partition_store::{PartitionStore, PartitionStoreTransaction};
+use restate_storage_api::cdc_table::WriteCdcTable;
 use restate_storage_api::deduplication_table::{
     DedupInformation, DedupSequenceNumber, ProducerId, ReadDeduplicationTable,
     WriteDeduplicationTable,
 };
 use restate_storage_api::fsm_table::{PartitionDurability, ReadFsmTable, WriteFsmTable};
+use restate_storage_api::invocation_history_table::WriteInvocationHistoryTable;
 use restate_storage_api::outbox_table::ReadOutboxTable;
 use restate_storage_api::{StorageError, Transaction};
 use restate_time_util::DurationExt;
@@ -64,10 +70,14 @@ use self::leadership::trim_queue::TrimQueue;
 use crate::metric_definitions::{
     PARTITION_BLOCKED_FLARE, PARTITION_LABEL, PARTITION_RECORD_COMMITTED_TO_READ_LATENCY_SECONDS,
 };
+use crate::partition::invocation_counts::InvocationCountsReporter;
 use crate::partition::invoker_storage_reader::InvokerStorageReader;
 use crate::partition::leadership::LeadershipState;
 use crate::partition::state_machine::{ActionCollector, StateMachine};
 
+/// Maximum number of invocation history summaries removed at once.
+const INVOCATION_HISTORY_TRIM_CHUNK_SIZE: usize = 1000;
+
 /// Target leader state of the partition processor.
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub enum TargetLeaderState {
@@ -83,6 +93,7 @@ pub(super) struct PartitionProcessorBuilder<InvokerInputSender> {
     target_leader_state_rx: watch::Receiver<TargetLeaderState>,
     network_svc_rx: mpsc::Receiver<ServiceMessage<PartitionLeaderService>>,
     status_watch_tx: watch::Sender<PartitionProcessorStatus>,
+    payload_store: Option<PayloadStore>,
 }
 
 impl<InvokerInputSender> PartitionProcessorBuilder<InvokerInputSender>
@@ -96,6 +107,7 @@ where
         network_svc_rx: mpsc::Receiver<ServiceMessage<PartitionLeaderService>>,
         status_watch_tx: watch::Sender<PartitionProcessorStatus>,
         invoker_tx: InvokerInputSender,
+        payload_store: Option<PayloadStore>,
     ) -> Self {
         Self {
             status,
@@ -103,6 +115,7 @@ where
             target_leader_state_rx,
             network_svc_rx,
             status_watch_tx,
+            payload_store,
         }
     }
 
@@ -118,7 +131,7 @@ where
             network_svc_rx: rpc_rx,
             status_watch_tx,
             status,
-            ..
+            payload_store,
         } = self;
 
         let partition_id_str = SharedString::from(partition_store.partition_id().to_string());
@@ -128,6 +141,14 @@ where
         if let Some(ref partition_durability) = partition_store.get_partition_durability().await? {
             trim_queue.push(partition_durability);
         }
+        let change_capture_enabled = partition_store.get_change_capture_enabled().await?;
+        let change_capture_excluded_service = partition_store
+            .get_change_capture_excluded_service()
+            .await?;
+        let invocation_history_retention =
+            partition_store.get_invocation_history_retention().await?;
+        let invocation_history_trim_point =
+            partition_store.get_invocation_history_trim_point().await?;
 
         let last_seen_leader_epoch = partition_store
             .get_dedup_sequence_number(&ProducerId::self_producer())
@@ -157,6 +178,7 @@ where
             bifrost.clone(),
             last_seen_leader_epoch,
             trim_queue.clone(),
+            payload_store.clone(),
         );
 
         Ok(PartitionProcessor {
@@ -171,6 +193,12 @@ where
             status,
             replica_set_states,
             trim_queue,
+            change_capture_enabled,
+            change_capture_excluded_service,
+            invocation_history_retention,
+            invocation_history_trim_point,
+            invocation_history_trimmed: false,
+            payload_store,
         })
     }
 
@@ -220,6 +248,21 @@ pub struct PartitionProcessor<InvokerSender> {
 
     partition_store: PartitionStore,
     trim_queue: TrimQueue,
+    /// Replicated through [`Command::ConfigureChangeCapture`], so that all replicas capture the
+    /// same changes.
+    change_capture_enabled: bool,
+    /// Replicated through [`Command::ConfigureChangeCapture`] as well.
+    change_capture_excluded_service: Option<ByteString>,
+    /// Replicated through [`Command::ConfigureInvocationHistory`], so that all replicas archive
+    /// the same invocations.
+    invocation_history_retention: Option<Duration>,
+    /// Replicated through [`Command::TrimInvocationHistory`]. The summaries below the trim point
+    /// are hidden from scans, and removed in bounded chunks outside of the apply loop.
+    invocation_history_trim_point: MillisSinceEpoch,
+    /// Whether all summaries below the trim point have been removed.
+    invocation_history_trimmed: bool,
+    /// To copy the offloaded payloads of restarted invocations.
+    payload_store: Option<PayloadStore>,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -424,6 +467,16 @@ where
             tokio::time::interval(with_jitter(Duration::from_millis(500), 0.5));
         status_update_timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
 
+        let mut invocation_history_trim_timer =
+            tokio::time::interval(with_jitter(Duration::from_secs(1), 0.5));
+        invocation_history_trim_timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        let mut invocation_counts_timer =
+            tokio::time::interval(with_jitter(Duration::from_secs(10), 0.5));
+        invocation_counts_timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        let mut invocation_counts_reporter =
+            InvocationCountsReporter::new(self.partition_id_str.clone());
+
         let mut action_collector = ActionCollector::default();
         let mut command_buffer =
             Vec::with_capacity(live_config.live_load().worker.max_command_batch_size());
@@ -490,6 +543,20 @@ where
                         old.updated_at = MillisSinceEpoch::now();
                     });
                 }
+                _ = invocation_history_trim_timer.tick(), if !self.invocation_history_trimmed => {
+                    // The trim point is replicated, removing the summaries below it doesn't
+                    // change what the replica exposes. Only a bounded chunk is removed at every
+                    // tick, to not hold up the processing of records.
+                    let mut transaction = partition_store.transaction();
+                    self.invocation_history_trimmed = transaction.trim_invocation_history(
+                        self.invocation_history_trim_point,
+                        INVOCATION_HISTORY_TRIM_CHUNK_SIZE,
+                    )?;
+                    transaction.commit().await?;
+                }
+                _ = invocation_counts_timer.tick() => {
+                    invocation_counts_reporter.report(&mut partition_store, self.leadership_state.is_leader()).await?;
+                }
                 operation = Self::read_entries(&mut record_stream, config.worker.max_command_batch_size(), &mut command_buffer) => {
                     // check that reading has succeeded
                     operation?;
@@ -518,6 +585,20 @@ where
                             envelope: record.decode_arc()?,
                         };
 
+                        if self.change_capture_enabled {
+                            transaction.capture_changes_at(
+                                lsn,
+                                self.change_capture_excluded_service.clone(),
+                            );
+                        } else {
+                            transaction.stop_capturing_changes();
+                        }
+                        if self.invocation_history_retention.is_some() {
+                            transaction.record_invocation_history();
+                        } else {
+                            transaction.stop_recording_invocation_history();
+                        }
+
                         let maybe_announce_leader = self.apply_record(
                             record,
                             &mut transaction,
@@ -609,7 +690,8 @@ where
         schemas: &Schema,
     ) {
         let _ = rpc::RpcHandler::handle(
-            rpc::RpcContext::new(&mut self.leadership_state, schemas, partition_store),
+            rpc::RpcContext::new(&mut self.leadership_state, schemas, partition_store)
+                .with_payload_store(self.payload_store.as_ref()),
             body,
             rpc::Replier::new(response_tx),
         )
@@ -734,6 +816,108 @@ where
                 if self.trim_queue.push(&partition_durability) {
                     transaction.put_partition_durability(&partition_durability)?;
                 }
+            } else if let Command::TruncateChanges(truncate_changes) = envelope.command {
+                if truncate_changes.partition_id != self.partition_store.partition_id() {
+                    self.status.num_skipped_records += 1;
+                    trace!(
+                        "Ignore truncate-changes message which is not targeted to me. Message is for {} but I'm {}",
+                        truncate_changes.partition_id,
+                        self.partition_store.partition_id()
+                    );
+                    return Ok(None);
+                }
+
+                transaction.truncate_changes(truncate_changes.up_to_lsn)?;
+            } else if let Command::ConfigureChangeCapture(configure_change_capture) =
+                envelope.command
+            {
+                if configure_change_capture.partition_id != self.partition_store.partition_id() {
+                    self.status.num_skipped_records += 1;
+                    trace!(
+                        "Ignore configure-change-capture message which is not targeted to me. Message is for {} but I'm {}",
+                        configure_change_capture.partition_id,
+                        self.partition_store.partition_id()
+                    );
+                    return Ok(None);
+                }
+
+                if configure_change_capture.enabled != self.change_capture_enabled {
+                    debug!(
+                        enabled = configure_change_capture.enabled,
+                        "Reconfiguring change capture"
+                    );
+                    transaction.put_change_capture_enabled(configure_change_capture.enabled)?;
+                    if !configure_change_capture.enabled {
+                        // Nobody is going to deliver them anymore
+                        transaction.truncate_changes(Lsn::MAX)?;
+                    }
+                    self.change_capture_enabled = configure_change_capture.enabled;
+                }
+                if configure_change_capture.excluded_service.as_deref()
+                    != self.change_capture_excluded_service.as_deref()
+                {
+                    debug!(
+                        excluded_service = ?configure_change_capture.excluded_service,
+                        "Reconfiguring the service excluded from change capture"
+                    );
+                    transaction.put_change_capture_excluded_service(
+                        configure_change_capture.excluded_service.as_deref(),
+                    )?;
+                    self.change_capture_excluded_service = configure_change_capture
+                        .excluded_service
+                        .map(ByteString::from);
+                }
+            } else if let Command::ConfigureInvocationHistory(configure_invocation_history) =
+                envelope.command
+            {
+                if configure_invocation_history.partition_id != self.partition_store.partition_id()
+                {
+                    self.status.num_skipped_records += 1;
+                    trace!(
+                        "Ignore configure-invocation-history message which is not targeted to me. Message is for {} but I'm {}",
+                        configure_invocation_history.partition_id,
+                        self.partition_store.partition_id()
+                    );
+                    return Ok(None);
+                }
+
+                let retention = configure_invocation_history.retention;
+                if retention != self.invocation_history_retention {
+                    debug!(?retention, "Reconfiguring invocation history");
+                    transaction.put_invocation_history_retention(retention)?;
+                    if retention.is_none() || self.invocation_history_retention.is_none() {
+                        // Drop the whole history when disabling it. When enabling it, drop what
+                        // might be left from when it was disabled.
+                        let trim_point = if retention.is_none() {
+                            MillisSinceEpoch::MAX
+                        } else {
+                            record_created_at.into()
+                        };
+                        transaction.put_invocation_history_trim_point(trim_point)?;
+                        self.invocation_history_trim_point = trim_point;
+                        self.invocation_history_trimmed = false;
+                    }
+                    self.invocation_history_retention = retention;
+                }
+            } else if let Command::TrimInvocationHistory(trim_invocation_history) = envelope.command
+            {
+                if trim_invocation_history.partition_id != self.partition_store.partition_id() {
+                    self.status.num_skipped_records += 1;
+                    trace!(
+                        "Ignore trim-invocation-history message which is not targeted to me. Message is for {} but I'm {}",
+                        trim_invocation_history.partition_id,
+                        self.partition_store.partition_id()
+                    );
+                    return Ok(None);
+                }
+
+                if trim_invocation_history.completed_before > self.invocation_history_trim_point {
+                    transaction.put_invocation_history_trim_point(
+                        trim_invocation_history.completed_before,
+                    )?;
+                    self.invocation_history_trim_point = trim_invocation_history.completed_before;
+                    self.invocation_history_trimmed = false;
+                }
             } else {
                 self.state_machine
                     .apply(
@@ -0,0 +1,477 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::*;
+use restate_storage_api::invocation_status_table::{
+    InFlightInvocationMetadata, InvocationStatus, ReadInvocationStatusTable,
+};
+use restate_types::identifiers::{InvocationId, WithPartitionKey};
+use restate_types::invocation::client::PatchDeploymentId;
+use restate_types::invocation::{
+    IngressInvocationResponseSink, InvocationMutationResponseSink, MigrateInvocationRequest,
+};
+use restate_types::net::partition_processor::MigrateInvocationRpcResponse;
+use restate_types::schema::deployment::DeploymentResolver;
+
+pub(super) struct Request {
+    pub(super) request_id: PartitionProcessorRpcRequestId,
+    pub(super) invocation_id: InvocationId,
+    pub(super) deployment_id: PatchDeploymentId,
+}
+
+impl<'a, TActuator: Actuator, TSchemas, TStorage> RpcHandler<Request>
+    for RpcContext<'a, TActuator, TSchemas, TStorage>
+where
+    TActuator: Actuator,
+    TSchemas: DeploymentResolver,
+    TStorage: ReadInvocationStatusTable,
+{
+    type Output = MigrateInvocationRpcResponse;
+    type Error = ();
+
+    async fn handle(
+        self,
+        Request {
+            request_id,
+            invocation_id,
+            deployment_id,
+        }: Request,
+        replier: Replier<Self::Output>,
+    ) -> Result<(), Self::Error> {
+        match self.storage.get_invocation_status(&invocation_id).await {
+            Ok(InvocationStatus::Suspended {
+                metadata:
+                    InFlightInvocationMetadata {
+                        invocation_target,
+                        pinned_deployment,
+                        ..
+                    },
+                ..
+            })
+            | Ok(InvocationStatus::Paused(InFlightInvocationMetadata {
+                invocation_target,
+                pinned_deployment,
+                ..
+            })) => {
+                let Some(pinned_deployment) = pinned_deployment else {
+                    replier.send(MigrateInvocationRpcResponse::NotPinned);
+                    return Ok(());
+                };
+
+                let deployment_id = match deployment_id {
+                    PatchDeploymentId::KeepPinned => pinned_deployment.deployment_id,
+                    PatchDeploymentId::PinToLatest => {
+                        let Some(deployment) = self.schemas.resolve_latest_deployment_for_service(
+                            invocation_target.service_name(),
+                        ) else {
+                            replier.send(MigrateInvocationRpcResponse::DeploymentNotFound);
+                            return Ok(());
+                        };
+                        deployment.id
+                    }
+                    PatchDeploymentId::PinTo { id } => id,
+                };
+                if deployment_id == pinned_deployment.deployment_id {
+                    replier.send(MigrateInvocationRpcResponse::AlreadyPinned);
+                    return Ok(());
+                }
+
+                let Some((deployment, services)) =
+                    self.schemas.get_deployment_and_services(&deployment_id)
+                else {
+                    replier.send(MigrateInvocationRpcResponse::DeploymentNotFound);
+                    return Ok(());
+                };
+
+                // The journal must be replayable by the new deployment
+                if !deployment
+                    .supported_protocol_versions
+                    .contains(&(pinned_deployment.service_protocol_version as i32))
+                {
+                    replier.send(MigrateInvocationRpcResponse::IncompatibleDeploymentId {
+                        pinned_protocol_version: pinned_deployment.service_protocol_version as i32,
+                        deployment_id: deployment.id,
+                        supported_protocol_versions: deployment.supported_protocol_versions,
+                    });
+                    return Ok(());
+                }
+                if !services.iter().any(|service| {
+                    service.name.as_str() == &**invocation_target.service_name()
+                        && service
+                            .handlers
+                            .contains_key(&**invocation_target.handler_name())
+                }) {
+                    replier.send(MigrateInvocationRpcResponse::MissingHandler {
+                        deployment_id: deployment.id,
+                    });
+                    return Ok(());
+                }
+
+                self.proposer
+                    .handle_rpc_proposal_command(
+                        invocation_id.partition_key(),
+                        Command::MigrateInvocation(MigrateInvocationRequest {
+                            invocation_id,
+                            deployment_id: deployment.id,
+                            response_sink: Some(InvocationMutationResponseSink::Ingress(
+                                IngressInvocationResponseSink { request_id },
+                            )),
+                        }),
+                        request_id,
+                        replier,
+                    )
+                    .await;
+            }
+            Ok(InvocationStatus::Invoked(_)) => {
+                replier.send(MigrateInvocationRpcResponse::Running);
+            }
+            Ok(InvocationStatus::Scheduled(_)) | Ok(InvocationStatus::Inboxed(_)) => {
+                replier.send(MigrateInvocationRpcResponse::NotStarted);
+            }
+            Ok(InvocationStatus::Completed(_)) => {
+                replier.send(MigrateInvocationRpcResponse::Completed);
+            }
+            Ok(InvocationStatus::Free) => {
+                replier.send(MigrateInvocationRpcResponse::NotFound);
+            }
+            Err(storage_error) => {
+                replier.send_result(Err(PartitionProcessorRpcError::Internal(
+                    storage_error.to_string(),
+                )));
+            }
+        };
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::partition::rpc::MockActuator;
+    use futures::FutureExt;
+    use googletest::prelude::*;
+    use restate_types::deployment::PinnedDeployment;
+    use restate_types::identifiers::DeploymentId;
+    use restate_types::invocation::InvocationTarget;
+    use restate_types::schema::deployment::Deployment;
+    use restate_types::schema::deployment::test_util::MockDeploymentMetadataRegistry;
+    use restate_types::schema::service::ServiceMetadata;
+    use restate_types::service_protocol::ServiceProtocolVersion;
+    use rstest::rstest;
+    use std::future::ready;
+
+    struct MockStorage {
+        expected_invocation_id: InvocationId,
+        status: InvocationStatus,
+    }
+
+    impl ReadInvocationStatusTable for MockStorage {
+        fn get_invocation_status(
+            &mut self,
+            inv_id: &InvocationId,
+        ) -> impl Future<Output = restate_storage_api::Result<InvocationStatus>> + Send {
+            assert_eq!(*inv_id, self.expected_invocation_id);
+            ready(Ok(self.status.clone()))
+        }
+    }
+
+    fn suspended_or_paused(
+        suspended: bool,
+        metadata: InFlightInvocationMetadata,
+    ) -> InvocationStatus {
+        if suspended {
+            InvocationStatus::Suspended {
+                metadata,
+                waiting_for_notifications: Default::default(),
+            }
+        } else {
+            InvocationStatus::Paused(metadata)
+        }
+    }
+
+    async fn handle(
+        proposer: &mut MockActuator,
+        schemas: &MockDeploymentMetadataRegistry,
+        status: InvocationStatus,
+        invocation_id: InvocationId,
+        deployment_id: PatchDeploymentId,
+    ) -> PartitionProcessorRpcResponse {
+        let mut storage = MockStorage {
+            expected_invocation_id: invocation_id,
+            status,
+        };
+
+        let (tx, rx) = Reciprocal::mock();
+        RpcHandler::handle(
+            RpcContext::new(proposer, schemas, &mut storage),
+            Request {
+                request_id: Default::default(),
+                invocation_id,
+                deployment_id,
+            },
+            Replier::new(tx),
+        )
+        .await
+        .unwrap();
+        rx.recv().await.unwrap()
+    }
+
+    #[rstest]
+    #[restate_core::test]
+    async fn migrate_to_compatible_deployment_proposes_command(
+        #[values(true, false)] pin_to_latest: bool,
+        #[values(true, false)] suspended: bool,
+    ) {
+        let invocation_id = InvocationId::mock_random();
+        let invocation_target = InvocationTarget::mock_service();
+
+        let mut dep = Deployment::mock();
+        dep.id = DeploymentId::new();
+        dep.supported_protocol_versions = 1..=5;
+        let expected_deployment_id = dep.id;
+
+        let mut schemas = MockDeploymentMetadataRegistry::default();
+        schemas.mock_deployment_with_services(
+            dep,
+            vec![ServiceMetadata::mock_service(
+                invocation_target.service_name(),
+                [invocation_target.handler_name()],
+            )],
+        );
+        schemas.mock_latest_service(invocation_target.service_name(), expected_deployment_id);
+
+        let mut proposer = MockActuator::new();
+        proposer
+            .expect_handle_rpc_proposal_command::<MigrateInvocationRpcResponse>()
+            .return_once_st(move |_, cmd, request_id, replier| {
+                assert_that!(
+                    cmd,
+                    pat!(Command::MigrateInvocation(pat!(MigrateInvocationRequest {
+                        invocation_id: eq(invocation_id),
+                        deployment_id: eq(expected_deployment_id),
+                        response_sink: some(eq(InvocationMutationResponseSink::Ingress(
+                            IngressInvocationResponseSink { request_id }
+                        )))
+                    })))
+                );
+                replier.send(MigrateInvocationRpcResponse::Ok {
+                    deployment_id: expected_deployment_id,
+                });
+                ready(()).boxed()
+            });
+
+        let metadata = InFlightInvocationMetadata {
+            invocation_target,
+            pinned_deployment: Some(PinnedDeployment::new(
+                DeploymentId::new(),
+                ServiceProtocolVersion::V5,
+            )),
+            ..InFlightInvocationMetadata::mock()
+        };
+
+        assert_eq!(
+            handle(
+                &mut proposer,
+                &schemas,
+                suspended_or_paused(suspended, metadata),
+                invocation_id,
+                if pin_to_latest {
+                    PatchDeploymentId::PinToLatest
+                } else {
+                    PatchDeploymentId::PinTo {
+                        id: expected_deployment_id,
+                    }
+                },
+            )
+            .await,
+            PartitionProcessorRpcResponse::MigrateInvocation(MigrateInvocationRpcResponse::Ok {
+                deployment_id: expected_deployment_id
+            })
+        );
+    }
+
+    #[restate_core::test]
+    async fn reject_incompatible_protocol_version() {
+        let invocation_id = InvocationId::mock_random();
+        let invocation_target = InvocationTarget::mock_service();
+
+        // Candidate deployment supports only up to V4 -> incompatible with V5
+        let mut dep = Deployment::mock();
+        dep.id = DeploymentId::new();
+        dep.supported_protocol_versions = 1..=4;
+        let deployment_id = dep.id;
+
+        let mut schemas = MockDeploymentMetadataRegistry::default();
+        schemas.mock_deployment_with_services(
+            dep,
+            vec![ServiceMetadata::mock_service(
+                invocation_target.service_name(),
+                [invocation_target.handler_name()],
+            )],
+        );
+
+        let mut proposer = MockActuator::new();
+        proposer
+            .expect_handle_rpc_proposal_command::<MigrateInvocationRpcResponse>()
+            .never();
+
+        let metadata = InFlightInvocationMetadata {
+            invocation_target,
+            pinned_deployment: Some(PinnedDeployment::new(
+                DeploymentId::new(),
+                ServiceProtocolVersion::V5,
+            )),
+            ..InFlightInvocationMetadata::mock()
+        };
+
+        assert_eq!(
+            handle(
+                &mut proposer,
+                &schemas,
+                suspended_or_paused(true, metadata),
+                invocation_id,
+                PatchDeploymentId::PinTo { id: deployment_id },
+            )
+            .await,
+            PartitionProcessorRpcResponse::MigrateInvocation(
+                MigrateInvocationRpcResponse::IncompatibleDeploymentId {
+                    pinned_protocol_version: i32::from(ServiceProtocolVersion::V5),
+                    deployment_id,
+                    supported_protocol_versions: 1..=4,
+                }
+            )
+        );
+    }
+
+    #[restate_core::test]
+    async fn reject_deployment_without_handler() {
+        let invocation_id = InvocationId::mock_random();
+        let invocation_target = InvocationTarget::mock_service();
+
+        let mut dep = Deployment::mock();
+        dep.id = DeploymentId::new();
+        let deployment_id = dep.id;
+
+        // The new deployment removed the handler of the invocation
+        let mut schemas = MockDeploymentMetadataRegistry::default();
+        schemas.mock_deployment_with_services(
+            dep,
+            vec![ServiceMetadata::mock_service(
+                invocation_target.service_name(),
+                ["another_handler"],
+            )],
+        );
+        schemas.mock_latest_service(invocation_target.service_name(), deployment_id);
+
+        let mut proposer = MockActuator::new();
+        proposer
+            .expect_handle_rpc_proposal_command::<MigrateInvocationRpcResponse>()
+            .never();
+
+        let metadata = InFlightInvocationMetadata {
+            invocation_target,
+            pinned_deployment: Some(PinnedDeployment::new(
+                DeploymentId::new(),
+                ServiceProtocolVersion::V5,
+            )),
+            ..InFlightInvocationMetadata::mock()
+        };
+
+        assert_eq!(
+            handle(
+                &mut proposer,
+                &schemas,
+                suspended_or_paused(false, metadata),
+                invocation_id,
+                PatchDeploymentId::PinToLatest,
+            )
+            .await,
+            PartitionProcessorRpcResponse::MigrateInvocation(
+                MigrateInvocationRpcResponse::MissingHandler { deployment_id }
+            )
+        );
+    }
+
+    #[restate_core::test]
+    async fn reply_already_pinned() {
+        let invocation_id = InvocationId::mock_random();
+        let invocation_target = InvocationTarget::mock_service();
+        let pinned_deployment_id = DeploymentId::new();
+
+        let mut schemas = MockDeploymentMetadataRegistry::default();
+        let mut dep = Deployment::mock();
+        dep.id = pinned_deployment_id;
+        schemas.mock_deployment(dep);
+        schemas.mock_latest_service(invocation_target.service_name(), pinned_deployment_id);
+
+        let mut proposer = MockActuator::new();
+        proposer
+            .expect_handle_rpc_proposal_command::<MigrateInvocationRpcResponse>()
+            .never();
+
+        let metadata = InFlightInvocationMetadata {
+            invocation_target,
+            pinned_deployment: Some(PinnedDeployment::new(
+                pinned_deployment_id,
+                ServiceProtocolVersion::V5,
+            )),
+            ..InFlightInvocationMetadata::mock()
+        };
+
+        assert_eq!(
+            handle(
+                &mut proposer,
+                &schemas,
+                suspended_or_paused(true, metadata),
+                invocation_id,
+                PatchDeploymentId::PinToLatest,
+            )
+            .await,
+            PartitionProcessorRpcResponse::MigrateInvocation(
+                MigrateInvocationRpcResponse::AlreadyPinned
+            )
+        );
+    }
+
+    #[rstest]
+    #[case::running(
+        InvocationStatus::Invoked(InFlightInvocationMetadata::mock()),
+        MigrateInvocationRpcResponse::Running
+    )]
+    #[case::not_pinned(
+        InvocationStatus::Paused(InFlightInvocationMetadata::mock()),
+        MigrateInvocationRpcResponse::NotPinned
+    )]
+    #[case::not_found(InvocationStatus::Free, MigrateInvocationRpcResponse::NotFound)]
+    #[restate_core::test]
+    async fn reply_without_proposing(
+        #[case] status: InvocationStatus,
+        #[case] expected: MigrateInvocationRpcResponse,
+    ) {
+        let mut proposer = MockActuator::new();
+        proposer
+            .expect_handle_rpc_proposal_command::<MigrateInvocationRpcResponse>()
+            .never();
+
+        assert_eq!(
+            handle(
+                &mut proposer,
+                &MockDeploymentMetadataRegistry::default(),
+                status,
+                InvocationId::mock_random(),
+                PatchDeploymentId::PinToLatest,
+            )
+            .await,
+            PartitionProcessorRpcResponse::MigrateInvocation(expected)
+        );
+    }
+}
@@ -14,6 +14,7 @@ mod append_signal;
 mod cancel_invocation;
 mod get_invocation_output;
 mod kill_invocation;
+mod migrate_invocation;
 mod pause_invocation;
 mod purge_invocation;
 mod purge_journal;
@@ -24,6 +25,7 @@ use crate::partition;
 use crate::partition::leadership::LeadershipState;
 use restate_core::network::{Oneshot, Reciprocal};
 use restate_invoker_api::InvokerHandle;
+use restate_object_store_util::PayloadStore;
 use restate_storage_api::idempotency_table::ReadOnlyIdempotencyTable;
 use restate_storage_api::invocation_status_table::ReadInvocationStatusTable;
 use restate_storage_api::journal_table as journal_table_v1;
@@ -150,6 +152,7 @@ pub(super) struct RpcContext<'a, Actuator, Schemas, Storage> {
     proposer: &'a mut Actuator,
     schemas: &'a Schemas,
     storage: &'a mut Storage,
+    payload_store: Option<&'a PayloadStore>,
 }
 
 impl<'a, Actuator, Schemas, Storage> RpcContext<'a, Actuator, Schemas, Storage> {
@@ -162,8 +165,14 @@ impl<'a, Actuator, Schemas, Storage> RpcContext<'a, Actuator, Schemas, Storage>
             proposer,
             schemas,
             storage,
+            payload_store: None,
         }
     }
+
+    pub(super) fn with_payload_store(mut self, payload_store: Option<&'a PayloadStore>) -> Self {
+        self.payload_store = payload_store;
+        self
+    }
 }
 
 pub(super) struct Replier<O>(
@@ -349,6 +358,20 @@ where
                 self.handle(pause_invocation::Request { invocation_id }, replier.map())
                     .await
             }
+            PartitionProcessorRpcRequestInner::MigrateInvocation {
+                invocation_id,
+                deployment_id,
+            } => {
+                self.handle(
+                    migrate_invocation::Request {
+                        request_id,
+                        invocation_id,
+                        deployment_id,
+                    },
+                    replier.map(),
+                )
+                .await
+            }
         }
     }
 }
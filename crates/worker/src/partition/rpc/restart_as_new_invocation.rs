@@ -217,6 +217,14 @@ where
                 invocation::Source::RestartAsNew(invocation_id),
             );
 
+            if let Err(err) = self
+                .copy_offloaded_payloads(&invocation_id, &new_invocation_id)
+                .await
+            {
+                replier.send_result(Err(err));
+                return Ok(());
+            }
+
             // Propose the usual Invoke command
             let cmd = Command::Invoke(Box::new(service_invocation));
 
@@ -345,6 +353,14 @@ where
             };
         }
 
+        if let Err(err) = self
+            .copy_offloaded_payloads(&invocation_id, &new_invocation_id)
+            .await
+        {
+            replier.send_result(Err(err));
+            return Ok(());
+        }
+
         // Pass the ball to the state machine, the PP will reply to the RPC request.
         let cmd = Command::RestartAsNewInvocation(RestartAsNewInvocationRequest {
             invocation_id,
@@ -363,6 +379,25 @@ where
     }
 }
 
+impl<TActuator, TSchemas, TStorage> RpcContext<'_, TActuator, TSchemas, TStorage> {
+    /// Copies the offloaded payloads of the invocation, as the journal entries referencing them
+    /// are copied to the new invocation. The copies of a restart which doesn't go through are
+    /// deleted by the cleaner.
+    async fn copy_offloaded_payloads(
+        &self,
+        invocation_id: &InvocationId,
+        new_invocation_id: &InvocationId,
+    ) -> Result<(), PartitionProcessorRpcError> {
+        let Some(payload_store) = self.payload_store else {
+            return Ok(());
+        };
+        payload_store
+            .copy_invocation_payloads(invocation_id, new_invocation_id)
+            .await
+            .map_err(|err| PartitionProcessorRpcError::Internal(err.to_string()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -373,6 +408,8 @@ mod tests {
     use futures::{FutureExt, Stream, stream};
     use googletest::prelude::*;
     use journal_v2::{InputCommand, SleepCommand};
+    use object_store::memory::InMemory;
+    use restate_object_store_util::PayloadStore;
     use restate_storage_api::invocation_status_table::{
         CompletedInvocation, InFlightInvocationMetadata, InboxedInvocation, JournalMetadata,
         PreFlightInvocationMetadata, ScheduledInvocation,
@@ -392,6 +429,7 @@ mod tests {
     use rstest::rstest;
     use std::collections::{HashMap, HashSet};
     use std::future::ready;
+    use std::sync::Arc;
     use test_log::test;
 
     struct MockStorage {
@@ -651,6 +689,78 @@ mod tests {
         .unwrap();
     }
 
+    #[test(restate_core::test)]
+    async fn offloaded_payloads_are_copied_to_the_new_invocation() {
+        let old_invocation_id = InvocationId::mock_random();
+        let invocation_target = InvocationTarget::mock_virtual_object();
+        let payload = Bytes::from_static(b"a large payload");
+
+        let payload_store = PayloadStore::new(Arc::new(InMemory::new()), 4);
+        let reference = payload_store
+            .offload_input(&old_invocation_id, payload.clone())
+            .await
+            .unwrap();
+
+        let mut proposer = MockActuator::new();
+        let (new_invocation_tx, new_invocation_rx) = std::sync::mpsc::channel();
+        let reference_clone = reference.clone();
+        proposer
+            .expect_self_propose_and_respond_asynchronously::<RestartAsNewInvocationRpcResponse>()
+            .return_once_st(move |_, cmd, _, _| {
+                let_assert!(Command::Invoke(service_invocation) = cmd);
+                // The reference is resolved against the new invocation
+                assert_eq!(service_invocation.argument, reference_clone);
+                new_invocation_tx
+                    .send(service_invocation.invocation_id)
+                    .unwrap();
+                ready(()).boxed()
+            });
+
+        let mut storage = MockStorage::new_with_input_v1(
+            old_invocation_id,
+            InvocationStatus::Completed(CompletedInvocation {
+                journal_metadata: JournalMetadata {
+                    length: 1,
+                    ..JournalMetadata::empty()
+                },
+                invocation_target,
+                pinned_deployment: None,
+                ..CompletedInvocation::mock_neo()
+            }),
+            reference.clone(),
+            vec![],
+        );
+
+        let (tx, _rx) = Reciprocal::mock();
+        RpcHandler::handle(
+            RpcContext::new(&mut proposer, &(), &mut storage)
+                .with_payload_store(Some(&payload_store)),
+            Request {
+                request_id: Default::default(),
+                invocation_id: old_invocation_id,
+                copy_prefix_up_to_index_included: 0,
+                patch_deployment_id: Default::default(),
+            },
+            Replier::new(tx),
+        )
+        .await
+        .unwrap();
+        let new_invocation_id = new_invocation_rx.recv().unwrap();
+
+        // Purging the original invocation doesn't affect the new one
+        payload_store
+            .delete_invocation_payloads(&old_invocation_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            payload_store
+                .rehydrate(&new_invocation_id, reference)
+                .await
+                .unwrap(),
+            payload
+        );
+    }
+
     #[test(restate_core::test)]
     async fn old_workaround_nonzero_prefix_is_unsupported() {
         let invocation_id = InvocationId::mock_random();
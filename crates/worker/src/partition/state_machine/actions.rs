@@ -14,7 +14,8 @@ use restate_storage_api::timer_table::TimerKey;
 use restate_types::identifiers::{InvocationId, PartitionProcessorRpcRequestId};
 use restate_types::invocation::client::{
     CancelInvocationResponse, InvocationOutputResponse, KillInvocationResponse,
-    PurgeInvocationResponse, RestartAsNewInvocationResponse, ResumeInvocationResponse,
+    MigrateInvocationResponse, PurgeInvocationResponse, RestartAsNewInvocationResponse,
+    ResumeInvocationResponse,
 };
 use restate_types::invocation::{InvocationEpoch, InvocationTarget};
 use restate_types::journal::Completion;
@@ -80,6 +81,10 @@ pub enum Action {
         invocation_id: InvocationId,
         retention: Duration,
     },
+    /// Delete the payloads of the given invocation which were offloaded to the object store.
+    DeleteOffloadedPayloads {
+        invocation_id: InvocationId,
+    },
     ForwardKillResponse {
         request_id: PartitionProcessorRpcRequestId,
         response: KillInvocationResponse,
@@ -104,6 +109,10 @@ pub enum Action {
         request_id: PartitionProcessorRpcRequestId,
         response: RestartAsNewInvocationResponse,
     },
+    ForwardMigrateInvocationResponse {
+        request_id: PartitionProcessorRpcRequestId,
+        response: MigrateInvocationResponse,
+    },
 }
 
 impl Action {
@@ -101,6 +101,7 @@ where
             idempotency_key,
             completion_retention_duration,
             journal_retention_duration,
+            deadline,
         } = self.request;
 
         // Prepare the service invocation to propose
@@ -116,6 +117,7 @@ where
             }),
             span_context: span_context.clone(),
             execution_time: self.execution_time,
+            deadline,
             completion_retention_duration,
             journal_retention_duration,
             idempotency_key,
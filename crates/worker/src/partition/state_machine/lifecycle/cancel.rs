@@ -12,6 +12,7 @@ use crate::partition::state_machine::entries::OnJournalEntryCommand;
 use crate::partition::state_machine::{CommandHandler, Error, StateMachineApplyContext};
 use restate_storage_api::fsm_table::WriteFsmTable;
 use restate_storage_api::inbox_table::WriteInboxTable;
+use restate_storage_api::invocation_history_table::WriteInvocationHistoryTable;
 use restate_storage_api::invocation_status_table::{
     InvocationStatus, ReadInvocationStatusTable, WriteInvocationStatusTable,
 };
@@ -53,7 +54,8 @@ where
         + WriteJournalEventsTable
         + WriteTimerTable
         + ReadPromiseTable
-        + WritePromiseTable,
+        + WritePromiseTable
+        + WriteInvocationHistoryTable,
 {
     async fn apply(self, ctx: &'ctx mut StateMachineApplyContext<'s, S>) -> Result<(), Error> {
         match self.invocation_status {
@@ -12,12 +12,14 @@ use crate::partition::state_machine::{CommandHandler, Error, StateMachineApplyCo
 use restate_storage_api::invocation_status_table::{InvocationStatus, WriteInvocationStatusTable};
 use restate_storage_api::journal_events::{EventView, WriteJournalEventsTable};
 use restate_types::identifiers::InvocationId;
+use restate_types::journal_events::EventType;
 use restate_types::journal_events::raw::RawEvent;
 
 pub struct OnInvokerEventCommand {
     pub invocation_id: InvocationId,
     pub invocation_status: InvocationStatus,
     pub event: RawEvent,
+    pub duplicate: bool,
 }
 
 impl<'ctx, 's: 'ctx, S: WriteJournalEventsTable + WriteInvocationStatusTable>
@@ -28,14 +30,25 @@ impl<'ctx, 's: 'ctx, S: WriteJournalEventsTable + WriteInvocationStatusTable>
             invocation_id,
             mut invocation_status,
             event,
+            duplicate,
         } = self;
-        ApplyEventCommand {
-            invocation_id,
-            invocation_status: &mut invocation_status,
-            event,
+
+        if event.ty() == EventType::TransientError
+            && let Some(metadata) = invocation_status.get_invocation_metadata_mut()
+        {
+            metadata.transient_failures += 1;
+        }
+
+        // The same event was already stored for the previous attempt
+        if !duplicate {
+            ApplyEventCommand {
+                invocation_id,
+                invocation_status: &mut invocation_status,
+                event,
+            }
+            .apply(ctx)
+            .await?;
         }
-        .apply(ctx)
-        .await?;
 
         // Store invocation status
         ctx.storage
@@ -85,6 +98,9 @@ mod tests {
     use crate::partition::types::InvokerEffectKind;
     use googletest::prelude::*;
     use restate_invoker_api::Effect;
+    use restate_storage_api::invocation_status_table::{
+        InFlightInvocationMetadata, InvocationStatus, ReadInvocationStatusTable,
+    };
     use restate_types::journal_events::raw::RawEvent;
     use restate_types::journal_events::{Event, TransientErrorEvent};
     use restate_wal_protocol::Command;
@@ -112,6 +128,7 @@ mod tests {
                 kind: InvokerEffectKind::JournalEvent {
                     event: RawEvent::from(Event::TransientError(transient_error_event.clone()))
                         .clone(),
+                    duplicate: false,
                 },
             })))
             .await;
@@ -123,4 +140,53 @@ mod tests {
 
         test_env.shutdown().await;
     }
+
+    #[restate_core::test]
+    async fn count_duplicate_transient_errors_without_storing_them() {
+        let mut test_env = TestEnv::create().await;
+        let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+        fixtures::mock_pinned_deployment_v5(&mut test_env, invocation_id).await;
+
+        let transient_error_event = TransientErrorEvent {
+            error_code: 501u16.into(),
+            error_message: "my bad".to_string(),
+            error_stacktrace: None,
+            restate_doc_error_code: None,
+            related_command_index: None,
+            related_command_name: None,
+            related_command_type: None,
+        };
+
+        for duplicate in [false, true, true] {
+            let _ = test_env
+                .apply(Command::InvokerEffect(Box::new(Effect {
+                    invocation_id,
+                    invocation_epoch: 0,
+                    kind: InvokerEffectKind::JournalEvent {
+                        event: RawEvent::from(Event::TransientError(transient_error_event.clone())),
+                        duplicate,
+                    },
+                })))
+                .await;
+        }
+
+        assert_that!(
+            test_env.read_journal_events(invocation_id).await,
+            elements_are![eq(Event::TransientError(transient_error_event))]
+        );
+        assert_that!(
+            test_env
+                .storage()
+                .get_invocation_status(&invocation_id)
+                .await
+                .unwrap(),
+            pat!(InvocationStatus::Invoked(pat!(
+                InFlightInvocationMetadata {
+                    transient_failures: eq(3)
+                }
+            )))
+        );
+
+        test_env.shutdown().await;
+    }
 }
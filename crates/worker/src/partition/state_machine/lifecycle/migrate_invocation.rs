@@ -0,0 +1,276 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::debug_if_leader;
+use crate::partition::state_machine::{Action, CommandHandler, Error, StateMachineApplyContext};
+use restate_storage_api::invocation_status_table::{
+    InvocationStatus, ReadInvocationStatusTable, WriteInvocationStatusTable,
+};
+use restate_types::identifiers::{DeploymentId, InvocationId};
+use restate_types::invocation::InvocationMutationResponseSink;
+use restate_types::invocation::client::MigrateInvocationResponse;
+
+pub struct OnMigrateInvocationCommand {
+    pub invocation_id: InvocationId,
+    pub deployment_id: DeploymentId,
+    pub response_sink: Option<InvocationMutationResponseSink>,
+}
+
+impl<'ctx, 's: 'ctx, S> StateMachineApplyContext<'s, S> {
+    fn reply_to_migrate_invocation(
+        &'ctx mut self,
+        response_sink: Option<InvocationMutationResponseSink>,
+        response: MigrateInvocationResponse,
+    ) {
+        if let Some(InvocationMutationResponseSink::Ingress(sink)) = response_sink {
+            debug_if_leader!(
+                self.is_leader,
+                "Send migrate response to request id '{:?}': {:?}",
+                sink.request_id,
+                response
+            );
+            self.action_collector
+                .push(Action::ForwardMigrateInvocationResponse {
+                    request_id: sink.request_id,
+                    response,
+                })
+        }
+    }
+}
+
+impl<'ctx, 's: 'ctx, S> CommandHandler<&'ctx mut StateMachineApplyContext<'s, S>>
+    for OnMigrateInvocationCommand
+where
+    S: ReadInvocationStatusTable + WriteInvocationStatusTable,
+{
+    async fn apply(self, ctx: &'ctx mut StateMachineApplyContext<'s, S>) -> Result<(), Error> {
+        let OnMigrateInvocationCommand {
+            invocation_id,
+            deployment_id,
+            response_sink,
+        } = self;
+
+        let response = match ctx.get_invocation_status(&invocation_id).await? {
+            mut is @ InvocationStatus::Suspended { .. } | mut is @ InvocationStatus::Paused(_) => {
+                // The compatibility of the deployment was already checked by the RPC handler.
+                // The invocation is not resumed, it will run on the new deployment once woken up.
+                match &mut is.get_invocation_metadata_mut().unwrap().pinned_deployment {
+                    None => MigrateInvocationResponse::NotPinned,
+                    Some(pinned_deployment) if pinned_deployment.deployment_id == deployment_id => {
+                        MigrateInvocationResponse::AlreadyPinned
+                    }
+                    Some(pinned_deployment) => {
+                        debug_if_leader!(
+                            ctx.is_leader,
+                            restate.deployment.id = %deployment_id,
+                            "Migrate invocation from deployment {}",
+                            pinned_deployment.deployment_id
+                        );
+                        pinned_deployment.deployment_id = deployment_id;
+                        ctx.storage.put_invocation_status(&invocation_id, &is)?;
+                        MigrateInvocationResponse::Ok { deployment_id }
+                    }
+                }
+            }
+            // The invocation might have been woken up after the RPC handler checked its status
+            InvocationStatus::Invoked(_) => MigrateInvocationResponse::Running,
+            InvocationStatus::Scheduled(_) | InvocationStatus::Inboxed(_) => {
+                MigrateInvocationResponse::NotStarted
+            }
+            InvocationStatus::Completed(_) => MigrateInvocationResponse::Completed,
+            InvocationStatus::Free => MigrateInvocationResponse::NotFound,
+        };
+        ctx.reply_to_migrate_invocation(response_sink, response);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::partition::state_machine::tests::fixtures::{
+        invoker_entry_effect, invoker_suspended,
+    };
+    use crate::partition::state_machine::tests::{TestEnv, fixtures, matchers};
+    use googletest::prelude::{all, assert_that, contains, eq, not, pat};
+    use restate_storage_api::invocation_status_table::{
+        InvocationStatusDiscriminants, ReadInvocationStatusTable,
+    };
+    use restate_types::identifiers::PartitionProcessorRpcRequestId;
+    use restate_types::invocation::{IngressInvocationResponseSink, MigrateInvocationRequest};
+    use restate_types::journal_v2::{NotificationId, SleepCommand};
+    use restate_wal_protocol::Command;
+    use std::time::{Duration, SystemTime};
+
+    fn migrate_command(
+        invocation_id: InvocationId,
+        deployment_id: DeploymentId,
+        request_id: PartitionProcessorRpcRequestId,
+    ) -> Command {
+        Command::MigrateInvocation(MigrateInvocationRequest {
+            invocation_id,
+            deployment_id,
+            response_sink: Some(InvocationMutationResponseSink::Ingress(
+                IngressInvocationResponseSink { request_id },
+            )),
+        })
+    }
+
+    #[restate_core::test]
+    async fn migrate_suspended_invocation() {
+        let mut test_env = TestEnv::create().await;
+        let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+        fixtures::mock_pinned_deployment_v5(&mut test_env, invocation_id).await;
+
+        let completion_id = 1;
+        let _ = test_env
+            .apply_multiple([
+                invoker_entry_effect(
+                    invocation_id,
+                    SleepCommand {
+                        wake_up_time: (SystemTime::now() + Duration::from_secs(60)).into(),
+                        name: Default::default(),
+                        completion_id,
+                    },
+                ),
+                invoker_suspended(
+                    invocation_id,
+                    [NotificationId::for_completion(completion_id)],
+                ),
+            ])
+            .await;
+
+        let request_id = PartitionProcessorRpcRequestId::new();
+        let new_deployment_id = DeploymentId::new();
+        let actions = test_env
+            .apply(migrate_command(
+                invocation_id,
+                new_deployment_id,
+                request_id,
+            ))
+            .await;
+        assert_that!(
+            actions,
+            all!(
+                contains(pat!(Action::ForwardMigrateInvocationResponse {
+                    request_id: eq(request_id),
+                    response: eq(MigrateInvocationResponse::Ok {
+                        deployment_id: new_deployment_id
+                    })
+                })),
+                // The invocation stays suspended
+                not(contains(matchers::actions::invoke_for_id(invocation_id)))
+            )
+        );
+        assert_that!(
+            test_env
+                .storage
+                .get_invocation_status(&invocation_id)
+                .await
+                .unwrap(),
+            all!(
+                matchers::storage::is_variant(InvocationStatusDiscriminants::Suspended),
+                matchers::storage::pinned_deployment_id_eq(new_deployment_id)
+            )
+        );
+
+        // Migrating again to the same deployment is a no-op
+        let request_id = PartitionProcessorRpcRequestId::new();
+        let actions = test_env
+            .apply(migrate_command(
+                invocation_id,
+                new_deployment_id,
+                request_id,
+            ))
+            .await;
+        assert_that!(
+            actions,
+            contains(pat!(Action::ForwardMigrateInvocationResponse {
+                request_id: eq(request_id),
+                response: eq(MigrateInvocationResponse::AlreadyPinned)
+            }))
+        );
+
+        test_env.shutdown().await;
+    }
+
+    #[restate_core::test]
+    async fn migrate_paused_invocation_without_pinned_deployment() {
+        let mut test_env = TestEnv::create().await;
+        let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+        test_env
+            .modify_invocation_status(invocation_id, |invocation_status| {
+                *invocation_status = InvocationStatus::Paused(
+                    invocation_status
+                        .get_invocation_metadata_mut()
+                        .unwrap()
+                        .clone(),
+                )
+            })
+            .await;
+
+        let request_id = PartitionProcessorRpcRequestId::new();
+        let actions = test_env
+            .apply(migrate_command(
+                invocation_id,
+                DeploymentId::new(),
+                request_id,
+            ))
+            .await;
+        assert_that!(
+            actions,
+            contains(pat!(Action::ForwardMigrateInvocationResponse {
+                request_id: eq(request_id),
+                response: eq(MigrateInvocationResponse::NotPinned)
+            }))
+        );
+        let invocation_status = test_env
+            .storage
+            .get_invocation_status(&invocation_id)
+            .await
+            .unwrap();
+        assert!(
+            invocation_status
+                .get_invocation_metadata()
+                .unwrap()
+                .pinned_deployment
+                .is_none()
+        );
+
+        test_env.shutdown().await;
+    }
+
+    #[restate_core::test]
+    async fn reject_running_invocation() {
+        let mut test_env = TestEnv::create().await;
+        let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+        fixtures::mock_pinned_deployment_v5(&mut test_env, invocation_id).await;
+
+        let request_id = PartitionProcessorRpcRequestId::new();
+        let actions = test_env
+            .apply(migrate_command(
+                invocation_id,
+                DeploymentId::new(),
+                request_id,
+            ))
+            .await;
+        assert_that!(
+            actions,
+            contains(pat!(Action::ForwardMigrateInvocationResponse {
+                request_id: eq(request_id),
+                response: eq(MigrateInvocationResponse::Running)
+            }))
+        );
+
+        test_env.shutdown().await;
+    }
+}
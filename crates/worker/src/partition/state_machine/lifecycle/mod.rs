@@ -11,6 +11,7 @@
 mod cancel;
 mod event;
 mod manual_resume;
+mod migrate_invocation;
 mod migrate_journal_table;
 mod notify_get_invocation_output_response;
 mod notify_invocation_response;
@@ -28,6 +29,7 @@ mod version_barrier;
 pub(super) use cancel::OnCancelCommand;
 pub(super) use event::OnInvokerEventCommand;
 pub(super) use manual_resume::OnManualResumeCommand;
+pub(super) use migrate_invocation::OnMigrateInvocationCommand;
 pub(super) use migrate_journal_table::VerifyOrMigrateJournalTableToV2Command;
 pub(super) use notify_get_invocation_output_response::OnNotifyGetInvocationOutputResponse;
 pub(super) use notify_invocation_response::OnNotifyInvocationResponse;
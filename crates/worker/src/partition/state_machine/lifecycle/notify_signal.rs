@@ -12,6 +12,7 @@ use crate::partition::state_machine::entries::OnJournalEntryCommand;
 use crate::partition::state_machine::{CommandHandler, Error, StateMachineApplyContext};
 use restate_storage_api::fsm_table::WriteFsmTable;
 use restate_storage_api::inbox_table::WriteInboxTable;
+use restate_storage_api::invocation_history_table::WriteInvocationHistoryTable;
 use restate_storage_api::invocation_status_table::{
     InvocationStatus, ReadInvocationStatusTable, WriteInvocationStatusTable,
 };
@@ -50,7 +51,8 @@ where
         + WriteTimerTable
         + ReadPromiseTable
         + WritePromiseTable
-        + WriteVirtualObjectStatusTable,
+        + WriteVirtualObjectStatusTable
+        + WriteInvocationHistoryTable,
 {
     async fn apply(self, ctx: &'ctx mut StateMachineApplyContext<'s, S>) -> Result<(), Error> {
         let OnNotifySignalCommand {
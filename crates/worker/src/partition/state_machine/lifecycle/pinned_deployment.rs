@@ -14,6 +14,7 @@ use crate::debug_if_leader;
 use crate::partition::state_machine::{CommandHandler, Error, StateMachineApplyContext};
 use restate_storage_api::fsm_table::WriteFsmTable;
 use restate_storage_api::inbox_table::WriteInboxTable;
+use restate_storage_api::invocation_history_table::WriteInvocationHistoryTable;
 use restate_storage_api::invocation_status_table::{
     InvocationStatus, ReadInvocationStatusTable, WriteInvocationStatusTable,
 };
@@ -53,7 +54,8 @@ where
         + WriteJournalEventsTable
         + WriteTimerTable
         + ReadPromiseTable
-        + WritePromiseTable,
+        + WritePromiseTable
+        + WriteInvocationHistoryTable,
 {
     async fn apply(self, ctx: &'ctx mut StateMachineApplyContext<'s, S>) -> Result<(), Error> {
         let mut in_flight_invocation_metadata = self
@@ -8,6 +8,8 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::time::Duration;
+
 use crate::debug_if_leader;
 use crate::partition::state_machine::{Action, CommandHandler, Error, StateMachineApplyContext};
 use ahash::HashSet;
@@ -236,6 +238,15 @@ where
                 invocation_id.to_string())
         );
 
+        // The new invocation gets the same time budget the original invocation had, starting now
+        let deadline = completed_invocation.deadline.map(|deadline| {
+            let started_at = completed_invocation
+                .execution_time
+                .unwrap_or_else(|| completed_invocation.timestamps.creation_time());
+            ctx.record_created_at
+                + Duration::from_millis(deadline.as_u64().saturating_sub(started_at.as_u64()))
+        });
+
         // Let's prep the PreFlightInvocationMetadata
         let pre_flight_invocation_metadata = PreFlightInvocationMetadata {
             timestamps: StatusTimestamps::init(ctx.record_created_at),
@@ -256,6 +267,7 @@ where
             completion_retention_duration: completed_invocation.completion_retention_duration,
             journal_retention_duration: completed_invocation.journal_retention_duration,
             random_seed: completed_invocation.random_seed,
+            deadline,
 
             // We don't set those
             idempotency_key: None,
@@ -38,10 +38,13 @@ use restate_storage_api::Result as StorageResult;
 use restate_storage_api::fsm_table::WriteFsmTable;
 use restate_storage_api::idempotency_table::{IdempotencyTable, ReadOnlyIdempotencyTable};
 use restate_storage_api::inbox_table::{InboxEntry, WriteInboxTable};
+use restate_storage_api::invocation_history_table::{
+    InvocationOutcome, InvocationSummary, WriteInvocationHistoryTable,
+};
 use restate_storage_api::invocation_status_table::{
     CompletedInvocation, InFlightInvocationMetadata, InboxedInvocation, JournalRetentionPolicy,
     PreFlightInvocationArgument, PreFlightInvocationJournal, PreFlightInvocationMetadata,
-    ReadInvocationStatusTable, WriteInvocationStatusTable,
+    ReadInvocationStatusTable, StatusTimestamps, WriteInvocationStatusTable,
 };
 use restate_storage_api::invocation_status_table::{InvocationStatus, ScheduledInvocation};
 use restate_storage_api::journal_events::WriteJournalEventsTable;
@@ -60,7 +63,7 @@ use restate_storage_api::timer_table::TimerKey;
 use restate_storage_api::timer_table::{Timer, WriteTimerTable};
 use restate_tracing_instrumentation as instrumentation;
 use restate_types::errors::{
-    ALREADY_COMPLETED_INVOCATION_ERROR, CANCELED_INVOCATION_ERROR, GenericError,
+    ALREADY_COMPLETED_INVOCATION_ERROR, CANCELED_INVOCATION_ERROR, GenericError, InvocationError,
     InvocationErrorCode, KILLED_INVOCATION_ERROR, NOT_FOUND_INVOCATION_ERROR,
     NOT_READY_INVOCATION_ERROR, WORKFLOW_ALREADY_INVOKED_INVOCATION_ERROR,
 };
@@ -376,6 +379,14 @@ impl<S> StateMachineApplyContext<'_, S> {
                     "Register cleanup invocation status timer"
                 )
             }
+            Timer::CancelInvocation(_) => {
+                debug_if_leader!(
+                    self.is_leader,
+                    restate.timer.wake_up_time = %timer_value.wake_up_time(),
+                    restate.timer.key = %TimerKeyDisplay(timer_value.key()),
+                    "Register invocation deadline timer"
+                )
+            }
         };
 
         self.storage
@@ -443,15 +454,20 @@ impl<S> StateMachineApplyContext<'_, S> {
             + WriteStateTable
             + journal_table_v2::WriteJournalTable
             + journal_table_v2::ReadJournalTable
-            + WriteJournalEventsTable,
+            + WriteJournalEventsTable
+            + WriteInvocationHistoryTable,
     {
         match command {
-            Command::UpdatePartitionDurability(_) => {
+            Command::UpdatePartitionDurability(_)
+            | Command::TruncateChanges(_)
+            | Command::ConfigureChangeCapture(_)
+            | Command::ConfigureInvocationHistory(_)
+            | Command::TrimInvocationHistory(_) => {
                 // no-op :-)
                 //
-                // This is a partition-level command that doesn't impact the state machine.
-                // Handling of this command should have happened without entering the state machine
-                // on_apply() method.
+                // These are partition-level commands that don't impact the state machine.
+                // Handling of these commands should have happened without entering the state
+                // machine on_apply() method.
                 Ok(())
             }
             Command::VersionBarrier(barrier) => {
@@ -574,6 +590,16 @@ impl<S> StateMachineApplyContext<'_, S> {
                 .await?;
                 Ok(())
             }
+            Command::MigrateInvocation(migrate_invocation_request) => {
+                lifecycle::OnMigrateInvocationCommand {
+                    invocation_id: migrate_invocation_request.invocation_id,
+                    deployment_id: migrate_invocation_request.deployment_id,
+                    response_sink: migrate_invocation_request.response_sink,
+                }
+                .apply(self)
+                .await?;
+                Ok(())
+            }
             Command::RestartAsNewInvocation(restart_as_new_invocation_request) => {
                 lifecycle::OnRestartAsNewInvocationCommand {
                     invocation_id: restart_as_new_invocation_request.invocation_id,
@@ -712,6 +738,15 @@ impl<S> StateMachineApplyContext<'_, S> {
     {
         // A pre-flight invocation has been already deduplicated
 
+        // Register the deadline timer, if any. The timer covers the whole lifecycle of the invocation,
+        // including the time spent scheduled or inboxed.
+        if let Some(deadline) = pre_flight_invocation_metadata.deadline {
+            self.register_timer(
+                TimerKeyValue::cancel_invocation(deadline, invocation_id),
+                pre_flight_invocation_metadata.span_context().clone(),
+            )?;
+        }
+
         // 1. Check if we need to schedule it
         let execution_time = pre_flight_invocation_metadata.execution_time;
         let Some(pre_flight_invocation_metadata) = self.handle_service_invocation_execution_time(
@@ -1207,7 +1242,8 @@ impl<S> StateMachineApplyContext<'_, S> {
             + WriteTimerTable
             + ReadPromiseTable
             + WritePromiseTable
-            + WriteJournalEventsTable,
+            + WriteJournalEventsTable
+            + WriteInvocationHistoryTable,
     {
         match termination_flavor {
             TerminationFlavor::Kill => self.on_kill_invocation(invocation_id, response_sink).await,
@@ -1238,7 +1274,8 @@ impl<S> StateMachineApplyContext<'_, S> {
             + WriteFsmTable
             + journal_table_v2::WriteJournalTable
             + journal_table_v2::ReadJournalTable
-            + WriteJournalEventsTable,
+            + WriteJournalEventsTable
+            + WriteInvocationHistoryTable,
     {
         let status = self.get_invocation_status(&invocation_id).await?;
 
@@ -1310,7 +1347,8 @@ impl<S> StateMachineApplyContext<'_, S> {
             + WriteJournalEventsTable
             + ReadPromiseTable
             + WritePromiseTable
-            + WriteTimerTable,
+            + WriteTimerTable
+            + WriteInvocationHistoryTable,
     {
         let mut status = self.get_invocation_status(&invocation_id).await?;
 
@@ -1459,7 +1497,9 @@ impl<S> StateMachineApplyContext<'_, S> {
             + WriteFsmTable
             + WriteJournalTable
             + journal_table_v2::WriteJournalTable
-            + WriteJournalEventsTable,
+            + WriteJournalEventsTable
+            + WriteTimerTable
+            + WriteInvocationHistoryTable,
     {
         let error = match termination_flavor {
             TerminationFlavor::Kill => KILLED_INVOCATION_ERROR,
@@ -1473,6 +1513,8 @@ impl<S> StateMachineApplyContext<'_, S> {
                     response_sinks,
                     invocation_target,
                     input,
+                    deadline,
+                    timestamps,
                     ..
                 },
         } = inboxed_invocation;
@@ -1495,6 +1537,8 @@ impl<S> StateMachineApplyContext<'_, S> {
             inbox_sequence_number,
         )
         .await?;
+        self.do_delete_deadline_timer(invocation_id, deadline)
+            .await?;
         self.do_free_invocation(invocation_id)?;
 
         // If there's a journal, delete journal
@@ -1513,6 +1557,19 @@ impl<S> StateMachineApplyContext<'_, S> {
                 should_remove_journal_table_v2,
             )
             .await?;
+        } else {
+            // The input was stored alongside the invocation status
+            self.do_delete_offloaded_payloads(invocation_id);
+        }
+
+        if self.storage.is_recording_invocation_history() {
+            self.archive_pre_flight_invocation(
+                invocation_id,
+                &invocation_target,
+                &timestamps,
+                &input,
+                &error,
+            )?;
         }
 
         self.notify_invocation_result(
@@ -1539,7 +1596,8 @@ impl<S> StateMachineApplyContext<'_, S> {
             + WriteFsmTable
             + WriteJournalTable
             + journal_table_v2::WriteJournalTable
-            + WriteJournalEventsTable,
+            + WriteJournalEventsTable
+            + WriteInvocationHistoryTable,
     {
         let error = match termination_flavor {
             TerminationFlavor::Kill => KILLED_INVOCATION_ERROR,
@@ -1553,6 +1611,8 @@ impl<S> StateMachineApplyContext<'_, S> {
                     input,
                     invocation_target,
                     execution_time,
+                    deadline,
+                    timestamps,
                     ..
                 },
         } = scheduled_invocation;
@@ -1577,6 +1637,8 @@ impl<S> StateMachineApplyContext<'_, S> {
         } else {
             warn!("Scheduled invocations must always have an execution time.");
         }
+        self.do_delete_deadline_timer(invocation_id, deadline)
+            .await?;
 
         // Free invocation
         self.do_free_invocation(invocation_id)?;
@@ -1597,6 +1659,19 @@ impl<S> StateMachineApplyContext<'_, S> {
                 should_remove_journal_table_v2,
             )
             .await?;
+        } else {
+            // The input was stored alongside the invocation status
+            self.do_delete_offloaded_payloads(invocation_id);
+        }
+
+        if self.storage.is_recording_invocation_history() {
+            self.archive_pre_flight_invocation(
+                invocation_id,
+                &invocation_target,
+                &timestamps,
+                &input,
+                &error,
+            )?;
         }
 
         self.notify_invocation_result(
@@ -1629,7 +1704,9 @@ impl<S> StateMachineApplyContext<'_, S> {
             + WriteFsmTable
             + journal_table_v2::WriteJournalTable
             + journal_table_v2::ReadJournalTable
-            + WriteJournalEventsTable,
+            + WriteJournalEventsTable
+            + WriteInvocationHistoryTable
+            + WriteTimerTable,
     {
         self.kill_child_invocations(&invocation_id, metadata.journal_metadata.length, &metadata)
             .await?;
@@ -1663,7 +1740,9 @@ impl<S> StateMachineApplyContext<'_, S> {
             + WriteFsmTable
             + journal_table_v2::WriteJournalTable
             + journal_table_v2::ReadJournalTable
-            + WriteJournalEventsTable,
+            + WriteJournalEventsTable
+            + WriteInvocationHistoryTable
+            + WriteTimerTable,
     {
         self.kill_child_invocations(&invocation_id, metadata.journal_metadata.length, &metadata)
             .await?;
@@ -1898,9 +1977,11 @@ impl<S> StateMachineApplyContext<'_, S> {
             + WriteStateTable
             + journal_table_v2::WriteJournalTable
             + journal_table_v2::ReadJournalTable
-            + WriteJournalEventsTable,
+            + WriteJournalEventsTable
+            + WriteInvocationHistoryTable,
     {
         let (key, value) = timer_value.into_inner();
+        let fire_time = key.timestamp;
         self.do_delete_timer(key).await?;
 
         match value {
@@ -1947,6 +2028,23 @@ impl<S> StateMachineApplyContext<'_, S> {
                 Ok(())
             }
             Timer::NeoInvoke(invocation_id) => self.on_neo_invoke_timer(invocation_id).await,
+            Timer::CancelInvocation(invocation_id) => {
+                let status = self.get_invocation_status(&invocation_id).await?;
+                // Completed invocations don't retain the deadline, and the check on the deadline value
+                // protects from cancelling an invocation that reused the same id after being purged.
+                if status.deadline().map(|d| d.as_u64()) != Some(fire_time) {
+                    trace!(
+                        "Ignoring deadline timer for invocation '{invocation_id}', because it is completed or its deadline changed"
+                    );
+                    return Ok(());
+                }
+                debug_if_leader!(
+                    self.is_leader,
+                    restate.invocation.id = %invocation_id,
+                    "Invocation deadline exceeded, cancelling the invocation"
+                );
+                self.on_cancel_invocation(invocation_id, None).await
+            }
         }
     }
 
@@ -2024,7 +2122,8 @@ impl<S> StateMachineApplyContext<'_, S> {
             + WriteVirtualObjectStatusTable
             + journal_table_v2::WriteJournalTable
             + journal_table_v2::ReadJournalTable
-            + WriteJournalEventsTable,
+            + WriteJournalEventsTable
+            + WriteInvocationHistoryTable,
     {
         let status = self
             .get_invocation_status(&invoker_effect.invocation_id)
@@ -2055,7 +2154,8 @@ impl<S> StateMachineApplyContext<'_, S> {
             + WriteVirtualObjectStatusTable
             + journal_table_v2::WriteJournalTable
             + journal_table_v2::ReadJournalTable
-            + WriteJournalEventsTable,
+            + WriteJournalEventsTable
+            + WriteInvocationHistoryTable,
     {
         let is_status_invoked = matches!(invocation_status, InvocationStatus::Invoked(_));
 
@@ -2128,11 +2228,12 @@ impl<S> StateMachineApplyContext<'_, S> {
                     });
                 }
             }
-            InvokerEffectKind::JournalEvent { event } => {
+            InvokerEffectKind::JournalEvent { event, duplicate } => {
                 lifecycle::OnInvokerEventCommand {
                     invocation_id: effect.invocation_id,
                     invocation_status,
                     event,
+                    duplicate,
                 }
                 .apply(self)
                 .await?;
@@ -2242,13 +2343,18 @@ impl<S> StateMachineApplyContext<'_, S> {
             + WriteStateTable
             + journal_table_v2::WriteJournalTable
             + journal_table_v2::ReadJournalTable
-            + WriteJournalEventsTable,
+            + WriteJournalEventsTable
+            + WriteInvocationHistoryTable
+            + WriteTimerTable,
     {
         let invocation_target = invocation_metadata.invocation_target.clone();
         let journal_length = invocation_metadata.journal_metadata.length;
         let completion_retention = invocation_metadata.completion_retention_duration;
         let journal_retention = invocation_metadata.journal_retention_duration;
 
+        self.do_delete_deadline_timer(invocation_id, invocation_metadata.deadline)
+            .await?;
+
         let should_remove_journal_table_v2 = invocation_metadata
             .pinned_deployment
             .as_ref()
@@ -2256,9 +2362,12 @@ impl<S> StateMachineApplyContext<'_, S> {
                 pinned_deployment.service_protocol_version >= ServiceProtocolVersion::V4
             });
 
-        // If there are any response sinks, or we need to store back the completed status,
-        //  we need to find the latest output entry
-        if !invocation_metadata.response_sinks.is_empty() || !completion_retention.is_zero() {
+        // If there are any response sinks, or we need to store back the completed status or to
+        //  archive the invocation, we need to find the latest output entry
+        if !invocation_metadata.response_sinks.is_empty()
+            || !completion_retention.is_zero()
+            || self.storage.is_recording_invocation_history()
+        {
             let response_result = if let Some(response_result) = response_result_override {
                 response_result
             } else if let Some(response_result) = self
@@ -2302,6 +2411,10 @@ impl<S> StateMachineApplyContext<'_, S> {
                 },
             );
 
+            if self.storage.is_recording_invocation_history() {
+                self.archive_invocation(invocation_id, &invocation_metadata, &response_result)?;
+            }
+
             // Store the completed status, if needed
             if !completion_retention.is_zero() {
                 let completed_invocation = CompletedInvocation::from_in_flight_invocation_metadata(
@@ -2347,6 +2460,85 @@ impl<S> StateMachineApplyContext<'_, S> {
         Ok(())
     }
 
+    /// Records the summary of the finished invocation in the invocation history archive.
+    fn archive_invocation(
+        &mut self,
+        invocation_id: InvocationId,
+        invocation_metadata: &InFlightInvocationMetadata,
+        response_result: &ResponseResult,
+    ) -> Result<(), Error>
+    where
+        S: WriteInvocationHistoryTable,
+    {
+        let outcome = match response_result {
+            ResponseResult::Success(_) => InvocationOutcome::Success,
+            ResponseResult::Failure(err) => InvocationOutcome::Failure {
+                code: err.code(),
+                message: err.message().to_owned(),
+            },
+        };
+
+        self.storage.put_invocation_summary(&InvocationSummary {
+            invocation_id,
+            invocation_target: invocation_metadata.invocation_target.clone(),
+            outcome,
+            created_at: invocation_metadata.timestamps.creation_time(),
+            running_at: invocation_metadata.timestamps.running_transition_time(),
+            completed_at: self.record_created_at,
+            transient_failures: invocation_metadata.transient_failures,
+            deployment_id: invocation_metadata
+                .pinned_deployment
+                .as_ref()
+                .map(|pinned_deployment| pinned_deployment.deployment_id),
+            journal_length: invocation_metadata.journal_metadata.length,
+        })?;
+
+        Ok(())
+    }
+
+    /// Records the summary of an invocation terminated before it started running.
+    fn archive_pre_flight_invocation(
+        &mut self,
+        invocation_id: InvocationId,
+        invocation_target: &InvocationTarget,
+        timestamps: &StatusTimestamps,
+        input: &PreFlightInvocationArgument,
+        error: &InvocationError,
+    ) -> Result<(), Error>
+    where
+        S: WriteInvocationHistoryTable,
+    {
+        let (deployment_id, journal_length) = match input {
+            PreFlightInvocationArgument::Input(_) => (None, 0),
+            PreFlightInvocationArgument::Journal(PreFlightInvocationJournal {
+                journal_metadata,
+                pinned_deployment,
+            }) => (
+                pinned_deployment
+                    .as_ref()
+                    .map(|pinned_deployment| pinned_deployment.deployment_id),
+                journal_metadata.length,
+            ),
+        };
+
+        self.storage.put_invocation_summary(&InvocationSummary {
+            invocation_id,
+            invocation_target: invocation_target.clone(),
+            outcome: InvocationOutcome::Failure {
+                code: error.code(),
+                message: error.message().to_owned(),
+            },
+            created_at: timestamps.creation_time(),
+            running_at: timestamps.running_transition_time(),
+            completed_at: self.record_created_at,
+            transient_failures: 0,
+            deployment_id,
+            journal_length,
+        })?;
+
+        Ok(())
+    }
+
     #[allow(clippy::too_many_arguments)]
     async fn send_response_to_sinks(
         &mut self,
@@ -2916,6 +3108,7 @@ impl<S> StateMachineApplyContext<'_, S> {
                         span_context: span_context.clone(),
                         headers: request.headers,
                         execution_time: None,
+                        deadline: None,
                         completion_retention_duration: (*completion_retention_time)
                             .unwrap_or_default(),
                         journal_retention_duration: Default::default(),
@@ -2983,6 +3176,7 @@ impl<S> StateMachineApplyContext<'_, S> {
                     span_context: span_context.clone(),
                     headers: request.headers,
                     execution_time: delay,
+                    deadline: None,
                     completion_retention_duration: (*completion_retention_time).unwrap_or_default(),
                     journal_retention_duration: Default::default(),
                     idempotency_key: request.idempotency_key,
@@ -4147,6 +4341,25 @@ impl<S> StateMachineApplyContext<'_, S> {
         Ok(())
     }
 
+    /// Deletes the deadline timer of an invocation which ended, if it has a deadline.
+    async fn do_delete_deadline_timer(
+        &mut self,
+        invocation_id: InvocationId,
+        deadline: Option<MillisSinceEpoch>,
+    ) -> Result<(), Error>
+    where
+        S: WriteTimerTable,
+    {
+        if let Some(deadline) = deadline {
+            self.do_delete_timer(TimerKey::cancel_invocation(
+                deadline.as_u64(),
+                invocation_id.invocation_uuid(),
+            ))
+            .await?;
+        }
+        Ok(())
+    }
+
     async fn append_journal_entry(
         &mut self,
         invocation_id: InvocationId,
@@ -4222,9 +4435,17 @@ impl<S> StateMachineApplyContext<'_, S> {
         }
         WriteJournalEventsTable::delete_journal_events(self.storage, invocation_id)
             .map_err(Error::Storage)?;
+
+        // The input entry might reference an offloaded payload
+        self.do_delete_offloaded_payloads(invocation_id);
         Ok(())
     }
 
+    fn do_delete_offloaded_payloads(&mut self, invocation_id: InvocationId) {
+        self.action_collector
+            .push(Action::DeleteOffloadedPayloads { invocation_id });
+    }
+
     async fn do_truncate_outbox(&mut self, range: RangeInclusive<MessageIndex>) -> Result<(), Error>
     where
         S: WriteOutboxTable,
@@ -0,0 +1,99 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::*;
+
+use restate_storage_api::cdc_table::{ChangePosition, ReadCdcTable, WriteCdcTable};
+use restate_types::logs::Lsn;
+
+use crate::partition::change_shipper::{ChangeEvent, sink_invocation};
+
+const SINK_SERVICE: &str = "ChangeSink";
+
+/// Applies the command like the partition processor does when change capture is enabled with
+/// the service sink.
+async fn apply_capturing_changes(test_env: &mut TestEnv, lsn: Lsn, command: Command) {
+    let mut transaction = test_env.storage.transaction();
+    transaction.capture_changes_at(lsn, Some(ByteString::from_static(SINK_SERVICE)));
+    test_env
+        .state_machine
+        .apply(
+            command,
+            MillisSinceEpoch::now(),
+            lsn,
+            &mut transaction,
+            &mut ActionCollector::default(),
+            true,
+        )
+        .await
+        .unwrap();
+    transaction.commit().await.unwrap();
+}
+
+#[restate_core::test]
+async fn service_sink_does_not_capture_its_own_invocations() {
+    let mut test_env = TestEnv::create().await;
+    let sink_target = InvocationTarget::service(SINK_SERVICE, "onChanges");
+
+    let invocation_target = InvocationTarget::mock_service();
+    let mut lsn = Lsn::OLDEST;
+    apply_capturing_changes(
+        &mut test_env,
+        lsn,
+        Command::Invoke(Box::new(ServiceInvocation {
+            invocation_id: InvocationId::mock_generate(&invocation_target),
+            invocation_target,
+            ..ServiceInvocation::mock()
+        })),
+    )
+    .await;
+
+    // Ship the captured changes like the change shipper does, each batch invoking the sink
+    let mut next_position = ChangePosition::MIN;
+    let mut shipped_changes = 0;
+    for _ in 0..5 {
+        let changes = test_env
+            .storage
+            .get_next_changes(next_position, 100)
+            .await
+            .unwrap();
+        let Some((last_position, _)) = changes.last() else {
+            break;
+        };
+        next_position = ChangePosition::new(last_position.lsn, last_position.index + 1);
+        shipped_changes += changes.len();
+
+        let events: Vec<_> = changes
+            .into_iter()
+            .map(|(position, change)| ChangeEvent::new(PartitionId::MIN, position, change))
+            .collect();
+        lsn = lsn.next();
+        apply_capturing_changes(
+            &mut test_env,
+            lsn,
+            Command::Invoke(Box::new(sink_invocation(&sink_target, &events).unwrap())),
+        )
+        .await;
+    }
+
+    // Only the status change of the invocation of the user service is shipped, the invocations
+    // of the sink don't cause further changes
+    assert_eq!(shipped_changes, 1);
+    assert!(
+        test_env
+            .storage
+            .get_next_changes(next_position, 100)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    test_env.shutdown().await;
+}
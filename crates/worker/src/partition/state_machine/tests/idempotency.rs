@@ -242,6 +242,7 @@ async fn complete_already_completed_invocation() {
             created_using_restate_version: RestateVersion::current(),
             source: Source::Ingress(PartitionProcessorRpcRequestId::new()),
             execution_time: None,
+            deadline: None,
             idempotency_key: Some(idempotency_key.clone()),
             timestamps: StatusTimestamps::mock(),
             response_result: ResponseResult::Success(response_bytes.clone()),
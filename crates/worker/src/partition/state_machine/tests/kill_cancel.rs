@@ -12,7 +12,7 @@ use super::{fixtures, matchers, *};
 
 use assert2::assert;
 use assert2::let_assert;
-use googletest::any;
+use googletest::{any, elements_are};
 use prost::Message;
 use restate_storage_api::journal_table::WriteJournalTable;
 use restate_storage_api::timer_table::{
@@ -20,11 +20,16 @@ use restate_storage_api::timer_table::{
 };
 use restate_types::deployment::PinnedDeployment;
 use restate_types::identifiers::EntryIndex;
-use restate_types::invocation::{IngressInvocationResponseSink, TerminationFlavor};
+use restate_types::invocation::{
+    IngressInvocationResponseSink, RestartAsNewInvocationRequest, TerminationFlavor,
+};
 use restate_types::journal::enriched::EnrichedEntryHeader;
-use restate_types::journal_v2::NotificationId;
+use restate_types::journal_events::TransientErrorEvent;
+use restate_types::journal_events::raw::RawEvent;
+use restate_types::journal_v2::{NotificationId, OutputCommand, OutputResult};
 use restate_types::service_protocol;
 use rstest::rstest;
+use std::time::Duration;
 use test_log::test;
 
 #[restate_core::test]
@@ -66,6 +71,7 @@ async fn kill_inboxed_invocation() -> anyhow::Result<()> {
     // assert that inboxed invocation is in invocation_status
     assert!(let InvocationStatus::Inboxed(_) = current_invocation_status);
 
+    test_env.record_invocation_history();
     let request_id = PartitionProcessorRpcRequestId::new();
     let actions = test_env
         .apply(Command::TerminateInvocation(InvocationTermination {
@@ -98,7 +104,8 @@ async fn kill_inboxed_invocation() -> anyhow::Result<()> {
                     0,
                     eq(ResponseResult::Failure(KILLED_INVOCATION_ERROR))
                 )
-            )
+            ),
+            contains(matchers::actions::delete_offloaded_payloads(inboxed_id))
         )
     );
 
@@ -116,6 +123,244 @@ async fn kill_inboxed_invocation() -> anyhow::Result<()> {
         ))
     );
 
+    // the killed invocation is archived, although it never ran
+    assert_that!(
+        test_env.read_invocation_history().await,
+        elements_are![pat!(InvocationSummary {
+            invocation_id: eq(inboxed_id),
+            outcome: eq(InvocationOutcome::Failure {
+                code: KILLED_INVOCATION_ERROR.code(),
+                message: KILLED_INVOCATION_ERROR.message().to_owned(),
+            }),
+            running_at: none(),
+            transient_failures: eq(0),
+            deployment_id: none(),
+        })]
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[restate_core::test]
+async fn cancel_inboxed_invocation_when_deadline_is_exceeded() -> anyhow::Result<()> {
+    let mut test_env = TestEnv::create().await;
+
+    let invocation_target = InvocationTarget::mock_virtual_object();
+    let invocation_id = InvocationId::mock_generate(&invocation_target);
+
+    let inboxed_target = invocation_target.clone();
+    let inboxed_id = InvocationId::mock_generate(&inboxed_target);
+    let deadline = MillisSinceEpoch::new(1_000);
+
+    let caller_id = InvocationId::mock_random();
+
+    let _ = test_env
+        .apply(Command::Invoke(Box::new(ServiceInvocation {
+            invocation_id,
+            invocation_target: invocation_target.clone(),
+            ..ServiceInvocation::mock()
+        })))
+        .await;
+
+    let actions = test_env
+        .apply(Command::Invoke(Box::new(ServiceInvocation {
+            invocation_id: inboxed_id,
+            invocation_target: inboxed_target,
+            response_sink: Some(ServiceInvocationResponseSink::PartitionProcessor(
+                JournalCompletionTarget::from_parts(caller_id, 0, 0),
+            )),
+            deadline: Some(deadline),
+            ..ServiceInvocation::mock()
+        })))
+        .await;
+    assert_that!(
+        actions,
+        contains(pat!(Action::RegisterTimer {
+            timer_value: eq(TimerKeyValue::cancel_invocation(deadline, inboxed_id))
+        }))
+    );
+
+    let current_invocation_status = test_env
+        .storage()
+        .get_invocation_status(&inboxed_id)
+        .await?;
+    assert!(let InvocationStatus::Inboxed(_) = current_invocation_status);
+    assert_eq!(current_invocation_status.deadline(), Some(deadline));
+
+    // A timer not matching the invocation deadline is ignored
+    let actions = test_env
+        .apply(Command::Timer(TimerKeyValue::cancel_invocation(
+            MillisSinceEpoch::new(500),
+            inboxed_id,
+        )))
+        .await;
+    assert_that!(
+        actions,
+        not(contains(
+            matchers::actions::invocation_response_to_partition_processor(caller_id, 0, anything())
+        ))
+    );
+    assert!(
+        let InvocationStatus::Inboxed(_) = test_env.storage().get_invocation_status(&inboxed_id).await?
+    );
+
+    let actions = test_env
+        .apply(Command::Timer(TimerKeyValue::cancel_invocation(
+            deadline, inboxed_id,
+        )))
+        .await;
+    assert_that!(
+        actions,
+        contains(
+            matchers::actions::invocation_response_to_partition_processor(
+                caller_id,
+                0,
+                eq(ResponseResult::Failure(CANCELED_INVOCATION_ERROR))
+            )
+        )
+    );
+    assert!(
+        let InvocationStatus::Free = test_env.storage().get_invocation_status(&inboxed_id).await?
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[restate_core::test]
+async fn delete_deadline_timer_when_invocation_ends() -> anyhow::Result<()> {
+    let mut test_env = TestEnv::create().await;
+
+    let invocation_target = InvocationTarget::mock_service();
+    let invocation_id = InvocationId::mock_generate(&invocation_target);
+    let deadline = MillisSinceEpoch::new(1_000);
+
+    let actions = test_env
+        .apply(Command::Invoke(Box::new(ServiceInvocation {
+            invocation_id,
+            invocation_target,
+            deadline: Some(deadline),
+            ..ServiceInvocation::mock()
+        })))
+        .await;
+    assert_that!(
+        actions,
+        contains(pat!(Action::RegisterTimer {
+            timer_value: eq(TimerKeyValue::cancel_invocation(deadline, invocation_id))
+        }))
+    );
+
+    let actions = test_env
+        .apply_multiple([
+            fixtures::pinned_deployment(invocation_id, ServiceProtocolVersion::V6),
+            fixtures::invoker_entry_effect(
+                invocation_id,
+                OutputCommand {
+                    result: OutputResult::Success(Default::default()),
+                    name: Default::default(),
+                },
+            ),
+            fixtures::invoker_end_effect(invocation_id),
+        ])
+        .await;
+    assert_that!(
+        actions,
+        contains(pat!(Action::DeleteTimer {
+            timer_key: eq(TimerKey::cancel_invocation(
+                deadline.as_u64(),
+                invocation_id.invocation_uuid()
+            ))
+        }))
+    );
+    assert_that!(
+        test_env
+            .storage
+            .next_timers_greater_than(None, usize::MAX)
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await?,
+        empty()
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[restate_core::test]
+async fn restart_as_new_carries_over_deadline() -> anyhow::Result<()> {
+    let mut test_env = TestEnv::create().await;
+
+    let invocation_target = InvocationTarget::mock_virtual_object();
+    let invocation_id = InvocationId::mock_generate(&invocation_target);
+    let time_budget = Duration::from_secs(60);
+    let deadline = MillisSinceEpoch::now() + time_budget;
+
+    let _ = test_env
+        .apply_multiple([
+            Command::Invoke(Box::new(ServiceInvocation {
+                invocation_id,
+                invocation_target: invocation_target.clone(),
+                deadline: Some(deadline),
+                completion_retention_duration: Duration::from_secs(120),
+                journal_retention_duration: Duration::from_secs(120),
+                ..ServiceInvocation::mock()
+            })),
+            fixtures::pinned_deployment(invocation_id, ServiceProtocolVersion::V6),
+            fixtures::invoker_entry_effect(
+                invocation_id,
+                OutputCommand {
+                    result: OutputResult::Success(Default::default()),
+                    name: Default::default(),
+                },
+            ),
+            fixtures::invoker_end_effect(invocation_id),
+        ])
+        .await;
+    let_assert!(
+        InvocationStatus::Completed(completed_invocation) = test_env
+            .storage()
+            .get_invocation_status(&invocation_id)
+            .await?
+    );
+    assert_eq!(completed_invocation.deadline, Some(deadline));
+
+    let new_invocation_id = InvocationId::mock_generate(&invocation_target);
+    let restarted_at = MillisSinceEpoch::now();
+    let actions = test_env
+        .apply(Command::RestartAsNewInvocation(
+            RestartAsNewInvocationRequest {
+                invocation_id,
+                new_invocation_id,
+                copy_prefix_up_to_index_included: 0,
+                patch_deployment_id: None,
+                response_sink: None,
+            },
+        ))
+        .await;
+
+    // The new invocation gets the same time budget, starting from the restart
+    let_assert!(
+        Some(timer_value) = actions.iter().find_map(|action| match action {
+            Action::RegisterTimer { timer_value }
+                if *timer_value.value() == Timer::CancelInvocation(new_invocation_id) =>
+            {
+                Some(timer_value)
+            }
+            _ => None,
+        })
+    );
+    assert!(timer_value.wake_up_time() >= restarted_at + time_budget - Duration::from_secs(1));
+    assert!(timer_value.wake_up_time() <= MillisSinceEpoch::now() + time_budget);
+    assert_eq!(
+        test_env
+            .storage()
+            .get_invocation_status(&new_invocation_id)
+            .await?
+            .deadline(),
+        Some(timer_value.wake_up_time())
+    );
+
     test_env.shutdown().await;
     Ok(())
 }
@@ -148,6 +393,7 @@ async fn terminate_scheduled_invocation(
         .await?;
     assert!(let InvocationStatus::Scheduled(_) = current_invocation_status);
 
+    test_env.record_invocation_history();
     let actions = test_env
         .apply(Command::TerminateInvocation(InvocationTermination {
             invocation_id,
@@ -157,16 +403,19 @@ async fn terminate_scheduled_invocation(
         .await;
     assert_that!(
         actions,
-        contains(pat!(Action::IngressResponse {
-            request_id: eq(rpc_id),
-            invocation_id: some(eq(invocation_id)),
-            response: eq(InvocationOutputResponse::Failure(
-                match termination_flavor {
-                    TerminationFlavor::Kill => KILLED_INVOCATION_ERROR,
-                    TerminationFlavor::Cancel => CANCELED_INVOCATION_ERROR,
-                }
-            ))
-        }))
+        all!(
+            contains(pat!(Action::IngressResponse {
+                request_id: eq(rpc_id),
+                invocation_id: some(eq(invocation_id)),
+                response: eq(InvocationOutputResponse::Failure(
+                    match termination_flavor {
+                        TerminationFlavor::Kill => KILLED_INVOCATION_ERROR,
+                        TerminationFlavor::Cancel => CANCELED_INVOCATION_ERROR,
+                    }
+                ))
+            })),
+            contains(matchers::actions::delete_offloaded_payloads(invocation_id))
+        )
     );
 
     // assert that invocation status was removed
@@ -176,6 +425,81 @@ async fn terminate_scheduled_invocation(
         .await?;
     assert!(let InvocationStatus::Free = current_invocation_status);
 
+    // assert that the terminated invocation was archived
+    let error = match termination_flavor {
+        TerminationFlavor::Kill => KILLED_INVOCATION_ERROR,
+        TerminationFlavor::Cancel => CANCELED_INVOCATION_ERROR,
+    };
+    assert_that!(
+        test_env.read_invocation_history().await,
+        elements_are![pat!(InvocationSummary {
+            invocation_id: eq(invocation_id),
+            outcome: eq(InvocationOutcome::Failure {
+                code: error.code(),
+                message: error.message().to_owned(),
+            }),
+            running_at: none(),
+            transient_failures: eq(0),
+        })]
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[restate_core::test]
+async fn archive_transient_failures_of_killed_invocation() -> anyhow::Result<()> {
+    let mut test_env = TestEnv::create().await;
+    test_env.record_invocation_history();
+
+    let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+    fixtures::mock_pinned_deployment_v5(&mut test_env, invocation_id).await;
+
+    let transient_error_event = RawEvent::from(Event::TransientError(TransientErrorEvent {
+        error_code: 500u16.into(),
+        error_message: "boom".to_string(),
+        error_stacktrace: None,
+        restate_doc_error_code: None,
+        related_command_index: None,
+        related_command_name: None,
+        related_command_type: None,
+    }));
+    // The invoker marks the repeated error of the second retry as duplicate
+    for duplicate in [false, true] {
+        let _ = test_env
+            .apply(Command::InvokerEffect(Box::new(Effect {
+                invocation_id,
+                invocation_epoch: 0,
+                kind: InvokerEffectKind::JournalEvent {
+                    event: transient_error_event.clone(),
+                    duplicate,
+                },
+            })))
+            .await;
+    }
+
+    let _ = test_env
+        .apply(Command::TerminateInvocation(InvocationTermination {
+            invocation_id,
+            flavor: TerminationFlavor::Kill,
+            response_sink: None,
+        }))
+        .await;
+
+    assert_that!(
+        test_env.read_invocation_history().await,
+        elements_are![pat!(InvocationSummary {
+            invocation_id: eq(invocation_id),
+            outcome: eq(InvocationOutcome::Failure {
+                code: KILLED_INVOCATION_ERROR.code(),
+                message: KILLED_INVOCATION_ERROR.message().to_owned(),
+            }),
+            running_at: some(anything()),
+            transient_failures: eq(2),
+            deployment_id: some(anything()),
+        })]
+    );
+
     test_env.shutdown().await;
     Ok(())
 }
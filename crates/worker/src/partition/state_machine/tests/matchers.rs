@@ -188,6 +188,14 @@ pub mod actions {
         })
     }
 
+    pub fn delete_offloaded_payloads(
+        invocation_id: InvocationId,
+    ) -> impl Matcher<ActualT = Action> {
+        pat!(Action::DeleteOffloadedPayloads {
+            invocation_id: eq(invocation_id)
+        })
+    }
+
     pub fn forward_purge_invocation_response(
         request_id: PartitionProcessorRpcRequestId,
         purge_invocation_response: PurgeInvocationResponse,
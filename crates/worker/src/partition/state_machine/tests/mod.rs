@@ -10,6 +10,7 @@
 
 use super::*;
 
+mod change_capture;
 mod delayed_send;
 pub mod fixtures;
 mod idempotency;
@@ -36,6 +37,7 @@ use restate_rocksdb::RocksDbManager;
 use restate_service_protocol::codec::ProtobufRawEntryCodec;
 use restate_storage_api::Transaction;
 use restate_storage_api::inbox_table::ReadInboxTable;
+use restate_storage_api::invocation_history_table::ScanInvocationHistoryTable;
 use restate_storage_api::invocation_status_table::{
     InFlightInvocationMetadata, InvocationStatus, ReadInvocationStatusTable,
     WriteInvocationStatusTable,
@@ -69,6 +71,8 @@ use restate_types::logs::SequenceNumber;
 use restate_types::partitions::Partition;
 use restate_types::state_mut::ExternalStateMutation;
 use std::collections::{HashMap, HashSet};
+use std::ops::ControlFlow;
+use std::sync::{Arc, Mutex};
 use test_log::test;
 use tracing_subscriber::fmt::format::FmtSpan;
 
@@ -77,6 +81,7 @@ pub struct TestEnv {
     // TODO for the time being we use rocksdb storage because we have no mocks for storage interfaces.
     //  Perhaps we could make these tests faster by having those.
     pub storage: PartitionStore,
+    record_invocation_history: bool,
 }
 
 impl TestEnv {
@@ -148,11 +153,21 @@ impl TestEnv {
         Self {
             state_machine,
             storage: rocksdb_storage,
+            record_invocation_history: false,
         }
     }
 
+    /// Records the finished invocations of the subsequently applied commands in the invocation
+    /// history archive.
+    pub fn record_invocation_history(&mut self) {
+        self.record_invocation_history = true;
+    }
+
     pub async fn apply(&mut self, command: Command) -> Vec<Action> {
         let mut transaction = self.storage.transaction();
+        if self.record_invocation_history {
+            transaction.record_invocation_history();
+        }
         let mut action_collector = ActionCollector::default();
         self.state_machine
             .apply(
@@ -173,6 +188,9 @@ impl TestEnv {
 
     pub async fn apply_fallible(&mut self, command: Command) -> Result<Vec<Action>, Error> {
         let mut transaction = self.storage.transaction();
+        if self.record_invocation_history {
+            transaction.record_invocation_history();
+        }
         let mut action_collector = ActionCollector::default();
         self.state_machine
             .apply(
@@ -265,6 +283,20 @@ impl TestEnv {
             .collect()
     }
 
+    pub async fn read_invocation_history(&mut self) -> Vec<InvocationSummary> {
+        let summaries = Arc::new(Mutex::new(Vec::new()));
+        let collected = Arc::clone(&summaries);
+        self.storage
+            .for_each_invocation_summary(PartitionKey::MIN..=PartitionKey::MAX, move |summary| {
+                collected.lock().unwrap().push(summary);
+                ControlFlow::Continue(())
+            })
+            .expect("storage to be working")
+            .await
+            .expect("to be decodable");
+        Arc::try_unwrap(summaries).unwrap().into_inner().unwrap()
+    }
+
     pub async fn modify_invocation_status(
         &mut self,
         invocation_id: InvocationId,
@@ -43,10 +43,11 @@ use restate_core::{
     my_node_id,
 };
 use restate_core::{RuntimeTaskHandle, TaskCenter};
-use restate_invoker_api::StatusHandle;
-use restate_invoker_impl::{ChannelStatusReader, TokenBucket};
+use restate_invoker_api::{CircuitBreakerReport, StatusHandle};
+use restate_invoker_impl::{ChannelStatusReader, DeploymentCircuitBreakers, TokenBucket};
 use restate_metadata_server::{MetadataStoreClient, ReadModifyWriteError};
 use restate_metadata_store::{ReadWriteError, RetryError, retry_on_retryable_error};
+use restate_object_store_util::PayloadStore;
 use restate_partition_store::PartitionStoreManager;
 use restate_partition_store::snapshots::{
     PartitionSnapshotMetadata, SnapshotPartitionTask, SnapshotRepository,
@@ -76,6 +77,7 @@ use restate_types::partitions::Partition;
 use restate_types::partitions::state::PartitionReplicaSetStates;
 use restate_types::protobuf::common::WorkerStatus;
 use restate_types::retries::with_jitter;
+use restate_types::schema::deployment::DeploymentResolver;
 use restate_types::{GenerationalNodeId, SharedString};
 
 use crate::metric_definitions::NUM_PARTITIONS;
@@ -132,6 +134,7 @@ pub struct PartitionProcessorManager {
     latest_snapshots: HashMap<PartitionId, SnapshotCreated>,
     snapshot_export_tasks: FuturesUnordered<TaskHandle<SnapshotResultInternal>>,
     snapshot_repository: Option<SnapshotRepository>,
+    payload_store: Option<PayloadStore>,
     fast_forward_on_startup: HashMap<PartitionId, Lsn>,
 
     partition_table: Live<PartitionTable>,
@@ -140,6 +143,9 @@ pub struct PartitionProcessorManager {
     // throttling
     invocation_token_bucket: Option<TokenBucket>,
     action_token_bucket: Option<TokenBucket>,
+
+    // shared by the invokers of all the partitions
+    circuit_breakers: DeploymentCircuitBreakers,
 }
 
 type SnapshotResult = Result<SnapshotCreated, SnapshotError>;
@@ -203,6 +209,7 @@ type ChannelStatusReaderList = Vec<(RangeInclusive<PartitionKey>, ChannelStatusR
 #[derive(Debug, Clone, Default)]
 pub struct MultiplexedInvokerStatusReader {
     readers: Arc<parking_lot::RwLock<ChannelStatusReaderList>>,
+    circuit_breakers: DeploymentCircuitBreakers,
 }
 
 impl MultiplexedInvokerStatusReader {
@@ -243,6 +250,10 @@ impl StatusHandle for MultiplexedInvokerStatusReader {
 
         result.into_iter().flatten()
     }
+
+    fn read_circuit_breakers(&self) -> Vec<CircuitBreakerReport> {
+        self.circuit_breakers.reports()
+    }
 }
 
 impl PartitionProcessorManager {
@@ -256,6 +267,7 @@ impl PartitionProcessorManager {
         router_builder: &mut MessageRouterBuilder,
         bifrost: Bifrost,
         snapshot_repository: Option<SnapshotRepository>,
+        payload_store: Option<PayloadStore>,
     ) -> Self {
         let ppm_svc_rx = router_builder.register_service(24, BackPressureMode::PushBack);
         let pp_rpc_rx = router_builder.register_service(24, BackPressureMode::PushBack);
@@ -289,6 +301,8 @@ impl PartitionProcessorManager {
                 bucket
             });
 
+        let circuit_breakers = DeploymentCircuitBreakers::default();
+
         let (tx, rx) = mpsc::channel(updateable_config.pinned().worker.internal_queue_length());
         Self {
             health_status,
@@ -305,17 +319,22 @@ impl PartitionProcessorManager {
             replica_set_states,
             archived_lsns: HashMap::default(),
             target_tail_lsns: HashMap::default(),
-            invokers_status_reader: MultiplexedInvokerStatusReader::default(),
+            invokers_status_reader: MultiplexedInvokerStatusReader {
+                readers: Default::default(),
+                circuit_breakers: circuit_breakers.clone(),
+            },
             asynchronous_operations: JoinSet::default(),
             pending_snapshots: HashMap::default(),
             latest_snapshots: HashMap::default(),
             snapshot_export_tasks: FuturesUnordered::default(),
             snapshot_repository,
+            payload_store,
             fast_forward_on_startup: HashMap::default(),
             partition_table: Metadata::with_current(|m| m.updateable_partition_table()),
             wait_for_partition_table_update: false,
             invocation_token_bucket,
             action_token_bucket,
+            circuit_breakers,
         }
     }
 
@@ -333,6 +352,7 @@ impl PartitionProcessorManager {
         let metadata = Metadata::current();
 
         let mut partition_table_version_watcher = metadata.watch(MetadataKind::PartitionTable);
+        let mut schema_version_watcher = metadata.watch(MetadataKind::Schema);
         gauge!(NUM_PARTITIONS).set(self.partition_table.live_load().len() as f64);
 
         let mut snapshot_check_interval = tokio::time::interval_at(
@@ -380,6 +400,12 @@ impl PartitionProcessorManager {
                         self.on_replica_set_state_changes(&replica_set_states);
                     }
                 }
+                _ = schema_version_watcher.changed() => {
+                    let schema = metadata.schema();
+                    self.circuit_breakers.retain_deployments(|deployment_id| {
+                        schema.get_deployment(deployment_id).is_some()
+                    });
+                }
                 Some(event) = self.asynchronous_operations.join_next() => {
                     self.on_asynchronous_event(event.context("asynchronous operations must not panic")?);
                 }
@@ -1318,6 +1344,8 @@ impl PartitionProcessorManager {
             self.fast_forward_on_startup.remove(&partition_id),
             self.invocation_token_bucket.clone(),
             self.action_token_bucket.clone(),
+            self.circuit_breakers.clone(),
+            self.payload_store.clone(),
         );
 
         self.asynchronous_operations
@@ -1532,6 +1560,7 @@ mod tests {
             &mut env_builder.router_builder,
             bifrost,
             None,
+            None,
         );
 
         // only needed for setting up the metadata
@@ -18,7 +18,8 @@ use tracing::{instrument, warn};
 use restate_bifrost::Bifrost;
 use restate_core::{Metadata, RuntimeTaskHandle, TaskCenter, TaskKind, cancellation_token};
 use restate_invoker_impl::Service as InvokerService;
-use restate_invoker_impl::TokenBucket;
+use restate_invoker_impl::{DeploymentCircuitBreakers, TokenBucket};
+use restate_object_store_util::PayloadStore;
 use restate_partition_store::{PartitionStore, PartitionStoreManager};
 use restate_service_protocol::codec::ProtobufRawEntryCodec;
 use restate_types::SharedString;
@@ -47,6 +48,8 @@ pub struct SpawnPartitionProcessorTask {
     fast_forward_lsn: Option<Lsn>,
     invocation_token_bucket: Option<TokenBucket>,
     action_token_bucket: Option<TokenBucket>,
+    circuit_breakers: DeploymentCircuitBreakers,
+    payload_store: Option<PayloadStore>,
 }
 
 impl SpawnPartitionProcessorTask {
@@ -61,6 +64,8 @@ impl SpawnPartitionProcessorTask {
         fast_forward_lsn: Option<Lsn>,
         invocation_token_bucket: Option<TokenBucket>,
         action_token_bucket: Option<TokenBucket>,
+        circuit_breakers: DeploymentCircuitBreakers,
+        payload_store: Option<PayloadStore>,
     ) -> Self {
         Self {
             task_name,
@@ -72,6 +77,8 @@ impl SpawnPartitionProcessorTask {
             fast_forward_lsn,
             invocation_token_bucket,
             action_token_bucket,
+            circuit_breakers,
+            payload_store,
         }
     }
 
@@ -100,6 +107,8 @@ impl SpawnPartitionProcessorTask {
             fast_forward_lsn,
             invocation_token_bucket,
             action_token_bucket,
+            circuit_breakers,
+            payload_store,
         } = self;
 
         let config = configuration.pinned();
@@ -116,6 +125,8 @@ impl SpawnPartitionProcessorTask {
             schema,
             invocation_token_bucket,
             action_token_bucket,
+            circuit_breakers,
+            payload_store.clone(),
         )?;
 
         let status_reader = invoker.status_reader();
@@ -125,8 +136,14 @@ impl SpawnPartitionProcessorTask {
         let status = PartitionProcessorStatus::new();
         let (watch_tx, watch_rx) = watch::channel(status.clone());
 
-        let pp_builder =
-            PartitionProcessorBuilder::new(status, control_rx, net_rx, watch_tx, invoker.handle());
+        let pp_builder = PartitionProcessorBuilder::new(
+            status,
+            control_rx,
+            net_rx,
+            watch_tx,
+            invoker.handle(),
+            payload_store,
+        );
 
         let invoker_name = Arc::from(format!("invoker-{}", partition.partition_id));
         let invoker_config = configuration.clone().map(|c| &c.worker.invoker);
@@ -71,6 +71,7 @@ async fn replicated_loglet() -> googletest::Result<()> {
     let replicated_loglet_config = ReplicatedLogletConfig {
         target_nodeset_size: NodeSetSize::default(),
         replication_property: ReplicationProperty::new(NonZeroU8::new(2).expect("to be non-zero")),
+        erasure_coding: None,
     };
 
     info!("Provisioning the cluster");
@@ -125,6 +126,7 @@ async fn cluster_chaos_test() -> googletest::Result<()> {
     let replicated_loglet_config = ReplicatedLogletConfig {
         target_nodeset_size: NodeSetSize::default(),
         replication_property: ReplicationProperty::new(NonZeroU8::new(2).expect("to be non-zero")),
+        erasure_coding: None,
     };
 
     cluster.nodes[0]
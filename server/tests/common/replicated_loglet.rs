@@ -152,6 +152,7 @@ where
         replication,
         // all nodes are log-servers
         nodeset: (1..=log_server_count).collect(),
+        erasure_coding: None,
     };
     let loglet_params = loglet_params.serialize()?;
 
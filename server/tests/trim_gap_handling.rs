@@ -83,6 +83,7 @@ async fn fast_forward_over_trim_gap() -> googletest::Result<()> {
     let replicated_loglet_config = ReplicatedLogletConfig {
         target_nodeset_size: NodeSetSize::default(),
         replication_property: ReplicationProperty::new_unchecked(1),
+        erasure_coding: None,
     };
 
     info!("Provisioning the cluster");
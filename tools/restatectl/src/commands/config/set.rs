@@ -20,6 +20,7 @@ use restate_core::protobuf::cluster_ctrl_svc::{
 };
 use restate_types::logs::metadata::ProviderKind;
 use restate_types::nodes_config::Role;
+use restate_types::replicated_loglet::ErasureCoding;
 use restate_types::replication::ReplicationProperty;
 
 use super::cluster_config_string;
@@ -52,6 +53,11 @@ pub struct ConfigSetOpts {
     #[clap(long)]
     log_default_nodeset_size: Option<u16>,
 
+    /// Erasure coding of new replicated loglets, e.g. "4+2" to split records into 4 data and 2
+    /// parity fragments. Use "none" to store full copies of records.
+    #[clap(long)]
+    log_erasure_coding: Option<String>,
+
     /// Number of partitions.
     ///
     /// It is only possible to change the number of partitions if the current cluster value is 0.
@@ -121,6 +127,14 @@ async fn config_set(connection: &ConnectionInfo, set_opts: &ConfigSetOpts) -> an
         bifrost_provider.target_nodeset_size = u32::from(nodeset_size);
     }
 
+    if let Some(erasure_coding) = &set_opts.log_erasure_coding {
+        bifrost_provider.erasure_coding = if erasure_coding.eq_ignore_ascii_case("none") {
+            None
+        } else {
+            Some(erasure_coding.parse::<ErasureCoding>()?.to_string())
+        };
+    }
+
     if let Some(num_partitions) = set_opts.num_partitions {
         if current.num_partitions != 0 {
             anyhow::bail!(
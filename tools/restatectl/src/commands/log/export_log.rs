@@ -0,0 +1,110 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+use anyhow::Context;
+use clap::ValueEnum;
+use cling::prelude::*;
+use futures::StreamExt;
+
+use restate_cli_util::c_eprintln;
+
+use super::read_log::{RecordFilterOpts, read_log};
+use crate::connection::ConnectionInfo;
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default)]
+pub enum ExportFormat {
+    /// A single json array
+    Json,
+    /// One json object per line
+    #[default]
+    Jsonl,
+}
+
+#[derive(Run, Parser, Collect, Clone, Debug)]
+#[clap()]
+#[cling(run = "export_log")]
+pub struct ExportLogOpts {
+    /// The log id to export
+    log_id: u32,
+
+    /// First LSN to export, defaults to the oldest record in the log
+    #[arg(long)]
+    from_lsn: Option<u64>,
+
+    /// Last LSN to export (inclusive), defaults to the current tail of the log
+    #[arg(long)]
+    to_lsn: Option<u64>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t)]
+    format: ExportFormat,
+
+    /// Write the records to this file instead of stdout
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    #[clap(flatten)]
+    filter: RecordFilterOpts,
+}
+
+async fn export_log(connection: &ConnectionInfo, opts: &ExportLogOpts) -> anyhow::Result<()> {
+    let mut writer: BufWriter<Box<dyn Write + Send>> = BufWriter::new(match &opts.output {
+        Some(path) => Box::new(
+            File::create(path).with_context(|| format!("failed to create {}", path.display()))?,
+        ),
+        None => Box::new(std::io::stdout()),
+    });
+
+    let mut records = std::pin::pin!(
+        read_log(
+            connection,
+            opts.log_id,
+            opts.from_lsn,
+            opts.to_lsn,
+            false,
+            &opts.filter,
+        )
+        .await?
+    );
+
+    let mut count: usize = 0;
+    if let ExportFormat::Json = opts.format {
+        writer.write_all(b"[")?;
+    }
+    while let Some(record) = records.next().await {
+        let record = record?;
+        match opts.format {
+            ExportFormat::Json => {
+                if count > 0 {
+                    writer.write_all(b",")?;
+                }
+                writeln!(writer)?;
+                serde_json::to_writer(&mut writer, &record)?;
+            }
+            ExportFormat::Jsonl => {
+                serde_json::to_writer(&mut writer, &record)?;
+                writeln!(writer)?;
+            }
+        }
+        count += 1;
+    }
+    if let ExportFormat::Json = opts.format {
+        writeln!(writer, "\n]")?;
+    }
+    writer.flush()?;
+
+    c_eprintln!("Exported {} records of log {}", count, opts.log_id);
+
+    Ok(())
+}
@@ -52,6 +52,7 @@ async fn generate_log_metadata(opts: &GenerateLogMetadataOpts) -> anyhow::Result
             sequencer: opts.sequencer,
             replication: opts.replication.clone(),
             nodeset: NodeSet::from_iter(opts.nodeset.clone()),
+            erasure_coding: None,
         };
         let params = LogletParams::from(loglet_params.serialize()?);
 
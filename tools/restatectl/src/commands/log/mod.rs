@@ -12,11 +12,14 @@ mod describe_log;
 // -- disabled until migrated to become loglet-provider agnostic
 // #[cfg(feature = "dump-local-log")]
 // mod dump_log;
+mod export_log;
 mod find_tail;
 mod gen_metadata;
 pub mod list_logs;
+mod read_log;
 mod reconfigure;
 mod seal;
+mod tail_log;
 mod trim_log;
 
 use cling::prelude::*;
@@ -47,6 +50,10 @@ pub enum Logs {
     Seal(seal::SealOpts),
     /// Find and show tail state of a log
     FindTail(find_tail::FindTailOpts),
+    /// Print the most recent records of a log as json, optionally following new records
+    Tail(tail_log::TailLogOpts),
+    /// Export a range of records of a log as json
+    Export(export_log::ExportLogOpts),
 }
 
 pub fn render_loglet_params<F>(params: &Option<ReplicatedLogletParams>, render_fn: F) -> Cell
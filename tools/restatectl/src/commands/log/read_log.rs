@@ -0,0 +1,103 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use anyhow::Context;
+use cling::prelude::*;
+use futures::{Stream, StreamExt};
+use serde::Serialize;
+
+use restate_core::protobuf::cluster_ctrl_svc::{
+    ReadLogRequest, ReadLogResponse, new_cluster_ctrl_client, read_log_response,
+};
+use restate_types::nodes_config::Role;
+
+use crate::connection::ConnectionInfo;
+
+/// Filters that are applied by the server before records are returned
+#[derive(Args, Clone, Debug)]
+pub struct RecordFilterOpts {
+    /// Only include records of the given command types, e.g. "Invoke", "InvokerEffect",
+    /// "Timer" or "PatchState"
+    #[arg(long = "type", value_name = "COMMAND_TYPE", value_delimiter = ',')]
+    command_types: Vec<String>,
+
+    /// Only include records that relate to the given invocation id
+    #[arg(long)]
+    invocation_id: Option<String>,
+}
+
+/// A log record as printed by `log tail` and `log export`
+#[derive(Debug, Serialize)]
+pub struct DecodedLogRecord {
+    log_id: u32,
+    lsn: u64,
+    #[serde(flatten)]
+    record: DecodedRecord,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum DecodedRecord {
+    Envelope(serde_json::Value),
+    TrimGap { to_lsn: u64 },
+    DecodeError(String),
+}
+
+impl DecodedLogRecord {
+    fn from_response(log_id: u32, response: ReadLogResponse) -> anyhow::Result<Self> {
+        let record = match response.record.context("response is missing the record")? {
+            read_log_response::Record::Envelope(json) => DecodedRecord::Envelope(
+                serde_json::from_str(&json).context("server returned invalid json")?,
+            ),
+            read_log_response::Record::TrimGapToLsn(to_lsn) => DecodedRecord::TrimGap { to_lsn },
+            read_log_response::Record::DecodeError(err) => DecodedRecord::DecodeError(err),
+        };
+
+        Ok(Self {
+            log_id,
+            lsn: response.lsn,
+            record,
+        })
+    }
+}
+
+/// Streams the decoded records of `log_id` in the given range from an admin node.
+pub async fn read_log(
+    connection: &ConnectionInfo,
+    log_id: u32,
+    from_lsn: Option<u64>,
+    to_lsn: Option<u64>,
+    follow: bool,
+    filter: &RecordFilterOpts,
+) -> anyhow::Result<impl Stream<Item = anyhow::Result<DecodedLogRecord>>> {
+    let request = ReadLogRequest {
+        log_id,
+        from_lsn,
+        to_lsn,
+        follow,
+        command_types: filter.command_types.clone(),
+        invocation_id: filter.invocation_id.clone(),
+    };
+
+    let stream = connection
+        .try_each(Some(Role::Admin), |channel| async {
+            new_cluster_ctrl_client(channel)
+                .read_log(request.clone())
+                .await
+        })
+        .await
+        .with_context(|| format!("failed to read log {log_id}"))?
+        .into_inner();
+
+    Ok(stream.map(move |response| {
+        let response = response.context("failed to read log records")?;
+        DecodedLogRecord::from_response(log_id, response)
+    }))
+}
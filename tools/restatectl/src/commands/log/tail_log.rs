@@ -0,0 +1,78 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use anyhow::Context;
+use cling::prelude::*;
+use futures::StreamExt;
+
+use restate_cli_util::c_println;
+use restate_core::protobuf::cluster_ctrl_svc::{FindTailRequest, new_cluster_ctrl_client};
+use restate_types::nodes_config::Role;
+
+use super::read_log::{RecordFilterOpts, read_log};
+use crate::connection::ConnectionInfo;
+
+#[derive(Run, Parser, Collect, Clone, Debug)]
+#[clap()]
+#[cling(run = "tail_log")]
+pub struct TailLogOpts {
+    /// The log id to tail
+    log_id: u32,
+
+    /// Keep printing records as they are appended to the log
+    #[arg(short, long)]
+    follow: bool,
+
+    /// Start this many records before the current tail. Filters are applied
+    /// after selecting the records, so fewer records might be printed.
+    #[arg(short = 'n', long, default_value_t = 10)]
+    records: u64,
+
+    #[clap(flatten)]
+    filter: RecordFilterOpts,
+}
+
+async fn tail_log(connection: &ConnectionInfo, opts: &TailLogOpts) -> anyhow::Result<()> {
+    let find_tail_request = FindTailRequest {
+        log_id: opts.log_id,
+    };
+    let tail_lsn = connection
+        .try_each(Some(Role::Admin), |channel| async {
+            new_cluster_ctrl_client(channel)
+                .find_tail(find_tail_request)
+                .await
+        })
+        .await
+        .with_context(|| format!("failed to find the tail of log {}", opts.log_id))?
+        .into_inner()
+        .tail_lsn;
+
+    // the oldest valid lsn is 1
+    let from_lsn = tail_lsn.saturating_sub(opts.records).max(1);
+    let to_lsn = (!opts.follow).then(|| tail_lsn.saturating_sub(1));
+
+    let mut records = std::pin::pin!(
+        read_log(
+            connection,
+            opts.log_id,
+            Some(from_lsn),
+            to_lsn,
+            opts.follow,
+            &opts.filter,
+        )
+        .await?
+    );
+
+    while let Some(record) = records.next().await {
+        c_println!("{}", serde_json::to_string(&record?)?);
+    }
+
+    Ok(())
+}
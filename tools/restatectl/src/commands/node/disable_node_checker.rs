@@ -152,7 +152,7 @@ impl<'a, 'b> DisableNodeChecker<'a, 'b> {
                             )));
                         }
                     }
-                    InternalKind::Sealed => {}
+                    InternalKind::Sealed | InternalKind::ObjectStore => {}
                 }
             }
         }
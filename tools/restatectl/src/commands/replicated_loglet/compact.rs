@@ -0,0 +1,73 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use cling::prelude::*;
+
+use restate_cli_util::c_println;
+use restate_log_server_grpc::{CompactLogletRequest, new_log_server_client};
+use restate_types::PlainNodeId;
+use restate_types::logs::LogletId;
+use restate_types::nodes_config::Role;
+use restate_types::replicated_loglet::EffectiveNodeSet;
+
+use crate::connection::ConnectionInfo;
+use crate::util::grpc_channel;
+
+#[derive(Run, Parser, Collect, Clone, Debug)]
+#[cling(run = "compact_loglet")]
+pub struct CompactOpts {
+    /// The replicated loglet id
+    loglet_id: LogletId,
+    /// Only compact the loglet on these log-servers (comma-separated). Defaults to all
+    /// log-servers in the loglet's nodeset.
+    #[arg(long, visible_alias = "node", value_delimiter = ',')]
+    nodes: Vec<PlainNodeId>,
+}
+
+async fn compact_loglet(connection: &ConnectionInfo, opts: &CompactOpts) -> anyhow::Result<()> {
+    let logs = connection.get_logs().await?;
+    let Some(loglet) = logs.get_replicated_loglet(&opts.loglet_id) else {
+        return Err(anyhow::anyhow!("loglet {} not found", opts.loglet_id));
+    };
+    let nodes_config = connection.get_nodes_configuration().await?;
+
+    let mut nodeset = loglet.params.nodeset.clone();
+    nodeset.sort();
+    for node_id in EffectiveNodeSet::new(nodeset, &nodes_config) {
+        if !opts.nodes.is_empty() && !opts.nodes.contains(&node_id) {
+            continue;
+        }
+        let node = nodes_config.find_node_by_id(node_id)?;
+        if !node.has_role(Role::LogServer) {
+            c_println!("{node_id}: skipped, not running the log-server role");
+            continue;
+        }
+
+        let mut client = new_log_server_client(grpc_channel(node.address.clone()));
+        match client
+            .compact_loglet(CompactLogletRequest {
+                loglet_id: opts.loglet_id.into(),
+            })
+            .await
+        {
+            Ok(response) => {
+                let trim_point = response.into_inner().trim_point;
+                if trim_point == 0 {
+                    c_println!("{node_id}: nothing to compact, the loglet is not trimmed");
+                } else {
+                    c_println!("{node_id}: compacted records up to offset {trim_point}");
+                }
+            }
+            Err(err) => c_println!("{node_id}: failed to compact: {}", err.message()),
+        }
+    }
+
+    Ok(())
+}
@@ -8,10 +8,12 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+mod compact;
 mod digest;
 mod digest_util;
 mod info;
 mod list_servers;
+mod storage;
 
 use cling::prelude::*;
 
@@ -26,6 +28,10 @@ pub enum ReplicatedLoglet {
     Info(info::InfoOpts),
     /// View log-server(s) state
     ListServers(list_servers::ListServersOpts),
+    /// View the storage used by loglets on log-server(s)
+    Storage(storage::StorageOpts),
+    /// Compact the storage of the trimmed records of a loglet to reclaim disk space
+    Compact(compact::CompactOpts),
 }
 
 fn render_storage_state(state: StorageState) -> Cell {
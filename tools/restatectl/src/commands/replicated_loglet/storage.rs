@@ -0,0 +1,84 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use bytesize::ByteSize;
+use cling::prelude::*;
+use itertools::Itertools;
+use tracing::warn;
+
+use restate_cli_util::_comfy_table::{Cell, Color, Table};
+use restate_cli_util::c_println;
+use restate_cli_util::ui::console::StyledTable;
+use restate_log_server_grpc::{ListLogletStorageRequest, new_log_server_client};
+use restate_types::PlainNodeId;
+use restate_types::logs::LogletId;
+use restate_types::nodes_config::Role;
+
+use crate::connection::ConnectionInfo;
+use crate::util::grpc_channel;
+
+#[derive(Run, Parser, Collect, Clone, Debug)]
+#[cling(run = "list_storage")]
+pub struct StorageOpts {
+    /// Only show the storage usage of this loglet
+    #[arg(long)]
+    loglet_id: Option<LogletId>,
+    /// Only show the storage usage on these log-servers (comma-separated)
+    #[arg(long, visible_alias = "node", value_delimiter = ',')]
+    nodes: Vec<PlainNodeId>,
+}
+
+async fn list_storage(connection: &ConnectionInfo, opts: &StorageOpts) -> anyhow::Result<()> {
+    let nodes_config = connection.get_nodes_configuration().await?;
+
+    let mut storage_table = Table::new_styled();
+    storage_table.set_styled_header(vec!["NODE", "LOGLET", "RECORDS", "SIZE"]);
+
+    for (node_id, config) in nodes_config.iter().sorted_by(|a, b| Ord::cmp(&a.0, &b.0)) {
+        if !config.has_role(Role::LogServer)
+            || (!opts.nodes.is_empty() && !opts.nodes.contains(&node_id))
+        {
+            continue;
+        }
+
+        let mut client = new_log_server_client(grpc_channel(config.address.clone()));
+        let loglets = match client
+            .list_loglet_storage(ListLogletStorageRequest {
+                loglet_id: opts.loglet_id.map(Into::into),
+            })
+            .await
+        {
+            Ok(response) => response.into_inner().loglets,
+            Err(err) => {
+                warn!("Failed to get the loglet storage of node {node_id}: {err}");
+                storage_table.add_row(vec![
+                    Cell::new(node_id.to_string()),
+                    Cell::new("?").fg(Color::Red),
+                    Cell::new("?").fg(Color::Red),
+                    Cell::new("?").fg(Color::Red),
+                ]);
+                continue;
+            }
+        };
+
+        for loglet in loglets.iter().sorted_by_key(|loglet| loglet.loglet_id) {
+            storage_table.add_row(vec![
+                Cell::new(node_id.to_string()),
+                Cell::new(LogletId::from(loglet.loglet_id).to_string()),
+                Cell::new(loglet.records),
+                Cell::new(ByteSize::b(loglet.bytes).display().iec().to_string()),
+            ]);
+        }
+    }
+
+    c_println!("{}", storage_table);
+
+    Ok(())
+}
@@ -56,10 +56,20 @@ pub fn write_default_provider<W: fmt::Write>(
             write_leaf(
                 w,
                 depth,
-                true,
+                false,
                 "Nodeset size",
                 config.target_nodeset_size.to_string(),
             )?;
+            write_leaf(
+                w,
+                depth,
+                true,
+                "Erasure coding",
+                config
+                    .erasure_coding
+                    .map(|ec| ec.to_string())
+                    .unwrap_or_else(|| "none".to_owned()),
+            )?;
         }
     }
     Ok(())
@@ -29,7 +29,7 @@ use restate_types::identifiers::{InvocationId, PartitionProcessorRpcRequestId, S
 use restate_types::invocation::client::{
     AttachInvocationResponse, CancelInvocationResponse, GetInvocationOutputResponse,
     InvocationClient, InvocationClientError, InvocationOutput, KillInvocationResponse,
-    PatchDeploymentId, PauseInvocationResponse, PurgeInvocationResponse,
+    MigrateInvocationResponse, PatchDeploymentId, PauseInvocationResponse, PurgeInvocationResponse,
     RestartAsNewInvocationResponse, ResumeInvocationResponse, SubmittedInvocationNotification,
 };
 use restate_types::invocation::{
@@ -200,6 +200,15 @@ impl InvocationClient for Mock {
         pending()
     }
 
+    fn migrate_invocation(
+        &self,
+        _: PartitionProcessorRpcRequestId,
+        _: InvocationId,
+        _: PatchDeploymentId,
+    ) -> impl Future<Output = Result<MigrateInvocationResponse, InvocationClientError>> + Send {
+        pending()
+    }
+
     fn pause_invocation(
         &self,
         _: PartitionProcessorRpcRequestId,